@@ -28,23 +28,179 @@ struct RepresentativeCityRow {
     currency_code: String,
 }
 
+#[derive(Debug)]
+struct WorldCityRow {
+    city_name: String,
+    city_code: String,
+    country_code: String,
+    timezone: String,
+    currency_code: String,
+    lat: f64,
+    lon: f64,
+}
+
 fn main() {
     println!("cargo:rerun-if-changed=data/countries.csv");
     println!("cargo:rerun-if-changed=data/representative_cities.json");
+    println!("cargo:rerun-if-changed=data/fallback_rates.csv");
+    println!("cargo:rerun-if-changed=data/world_cities.csv");
+    println!("cargo:rerun-if-changed=data/nz_coastline.geojson");
+    println!("cargo:rerun-if-changed=data/nz_highways.geojson");
 
     let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("manifest dir should exist");
     let countries_path = Path::new(&manifest_dir).join("data/countries.csv");
     let representative_cities_path =
         Path::new(&manifest_dir).join("data/representative_cities.json");
+    let fallback_rates_path = Path::new(&manifest_dir).join("data/fallback_rates.csv");
+    let world_cities_path = Path::new(&manifest_dir).join("data/world_cities.csv");
 
     let countries = parse_countries_csv(&countries_path);
     let representative_cities = parse_representative_cities_json(&representative_cities_path);
     validate_representative_cities(&countries, &representative_cities);
+    let fallback_rates = parse_fallback_rates_csv(&fallback_rates_path);
+    validate_fallback_rates(&countries, &fallback_rates);
+    let world_cities = parse_world_cities_csv(&world_cities_path);
+    validate_world_cities(&countries, &world_cities);
 
-    let generated = render_reference_data(&countries, &representative_cities);
+    let mut generated = render_reference_data(&countries, &representative_cities);
+    generated.push_str(&render_fallback_rates(&fallback_rates));
+    generated.push_str(&render_world_cities(&world_cities));
     let out_dir = env::var("OUT_DIR").expect("OUT_DIR should exist");
     let output_path = Path::new(&out_dir).join("reference_data.rs");
     fs::write(output_path, generated).expect("generated reference data should be written");
+
+    let coastline_path = Path::new(&manifest_dir).join("data/nz_coastline.geojson");
+    let coastline = parse_coastline_geojson(&coastline_path);
+    fs::write(
+        Path::new(&out_dir).join("coastline_data.rs"),
+        render_coastline(&coastline),
+    )
+    .expect("generated coastline data should be written");
+
+    let highways_path = Path::new(&manifest_dir).join("data/nz_highways.geojson");
+    let highways = parse_highways_geojson(&highways_path);
+    fs::write(
+        Path::new(&out_dir).join("highways_data.rs"),
+        render_highways(&highways),
+    )
+    .expect("generated highway data should be written");
+}
+
+/// a GeoJSON [lon, lat] position, checked to lie on the NZ map
+fn nz_position(point: &serde_json::Value, what: &str) -> (f64, f64) {
+    let lon = point[0]
+        .as_f64()
+        .unwrap_or_else(|| panic!("{} longitude should parse", what));
+    let lat = point[1]
+        .as_f64()
+        .unwrap_or_else(|| panic!("{} latitude should parse", what));
+    assert!(
+        (166.0..=179.0).contains(&lon) && (-47.5..=-34.0).contains(&lat),
+        "{} point {}, {} is outside the NZ map",
+        what,
+        lat,
+        lon
+    );
+    (lon, lat)
+}
+
+/// the outer ring of each polygon in a GeoJSON feature collection, as (lon, lat)
+fn parse_coastline_geojson(path: &Path) -> Vec<Vec<(f64, f64)>> {
+    let contents = fs::read_to_string(path).expect("nz_coastline.geojson should be readable");
+    let collection: serde_json::Value =
+        serde_json::from_str(&contents).expect("nz_coastline.geojson should parse");
+    let features = collection["features"]
+        .as_array()
+        .expect("nz_coastline.geojson should hold a feature collection");
+
+    features
+        .iter()
+        .map(|feature| {
+            let geometry = &feature["geometry"];
+            assert_eq!(
+                geometry["type"], "Polygon",
+                "nz_coastline.geojson features should be polygons"
+            );
+            let ring: Vec<(f64, f64)> = geometry["coordinates"][0]
+                .as_array()
+                .expect("coastline polygon should have an outer ring")
+                .iter()
+                .map(|point| nz_position(point, "coastline"))
+                .collect();
+            assert!(
+                ring.len() > 3 && ring.first() == ring.last(),
+                "coastline rings should be closed"
+            );
+            ring
+        })
+        .collect()
+}
+
+fn render_coastline(rings: &[Vec<(f64, f64)>]) -> String {
+    let mut output = String::new();
+    output.push_str("// generated by build.rs\n");
+    output.push_str("pub const NZ_COASTLINE: &[&[(f64, f64)]] = &[\n");
+    for ring in rings {
+        output.push_str("    &[\n");
+        for (lon, lat) in ring {
+            output.push_str(&format!("        ({:?}, {:?}),\n", lon, lat));
+        }
+        output.push_str("    ],\n");
+    }
+    output.push_str("];\n");
+    output
+}
+
+/// each LineString feature's `ref` (e.g. "SH1") and its (lon, lat) points
+fn parse_highways_geojson(path: &Path) -> Vec<(String, Vec<(f64, f64)>)> {
+    let contents = fs::read_to_string(path).expect("nz_highways.geojson should be readable");
+    let collection: serde_json::Value =
+        serde_json::from_str(&contents).expect("nz_highways.geojson should parse");
+    let features = collection["features"]
+        .as_array()
+        .expect("nz_highways.geojson should hold a feature collection");
+
+    features
+        .iter()
+        .map(|feature| {
+            let geometry = &feature["geometry"];
+            assert_eq!(
+                geometry["type"], "LineString",
+                "nz_highways.geojson features should be line strings"
+            );
+            let reference = feature["properties"]["ref"]
+                .as_str()
+                .expect("highway features should have a ref")
+                .to_string();
+            let line: Vec<(f64, f64)> = geometry["coordinates"]
+                .as_array()
+                .expect("highway line should have coordinates")
+                .iter()
+                .map(|point| nz_position(point, "highway"))
+                .collect();
+            assert!(
+                line.len() >= 2,
+                "highway {} should have two points",
+                reference
+            );
+            (reference, line)
+        })
+        .collect()
+}
+
+fn render_highways(highways: &[(String, Vec<(f64, f64)>)]) -> String {
+    let mut output = String::new();
+    output.push_str("// generated by build.rs\n");
+    output.push_str("pub const NZ_HIGHWAYS: &[(&str, &[(f64, f64)])] = &[\n");
+    for (reference, line) in highways {
+        output.push_str(&format!("    ({:?}, &[\n", reference));
+        for (lon, lat) in line {
+            output.push_str(&format!("        ({:?}, {:?}),\n", lon, lat));
+        }
+        output.push_str("    ]),\n");
+    }
+    output.push_str("];\n");
+    output
 }
 
 fn parse_countries_csv(path: &Path) -> Vec<CountryRow> {
@@ -118,6 +274,192 @@ fn validate_representative_cities(
     }
 }
 
+fn parse_world_cities_csv(path: &Path) -> Vec<WorldCityRow> {
+    let contents = fs::read_to_string(path).expect("world_cities.csv should be readable");
+    let mut rows = Vec::new();
+
+    for (line_number, line) in contents.lines().enumerate() {
+        if line_number == 0 || line.trim().is_empty() {
+            continue;
+        }
+
+        let columns: Vec<&str> = line.split(',').collect();
+        assert!(
+            columns.len() == 7,
+            "world_cities.csv line {} should have 7 columns, got {}",
+            line_number + 1,
+            columns.len()
+        );
+
+        let lat: f64 = columns[5]
+            .trim()
+            .parse()
+            .expect("world city latitude should parse");
+        let lon: f64 = columns[6]
+            .trim()
+            .parse()
+            .expect("world city longitude should parse");
+        assert!(
+            (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon),
+            "world_cities.csv line {} has an out-of-range position",
+            line_number + 1
+        );
+
+        rows.push(WorldCityRow {
+            city_name: columns[0].trim().to_string(),
+            city_code: columns[1].trim().to_string(),
+            country_code: columns[2].trim().to_string(),
+            timezone: columns[3].trim().to_string(),
+            currency_code: columns[4].trim().to_string(),
+            lat,
+            lon,
+        });
+    }
+
+    rows
+}
+
+fn validate_world_cities(countries: &[CountryRow], world_cities: &[WorldCityRow]) {
+    let country_codes: HashSet<&str> = countries
+        .iter()
+        .map(|row| row.country_code.as_str())
+        .collect();
+    let currency_codes: HashSet<&str> = countries
+        .iter()
+        .map(|row| row.currency_code.as_str())
+        .collect();
+    let mut seen_names = HashSet::new();
+    let mut seen_codes = HashSet::new();
+
+    for city in world_cities {
+        assert!(
+            country_codes.contains(city.country_code.as_str()),
+            "world city {} references unknown country code {}",
+            city.city_name,
+            city.country_code
+        );
+        assert!(
+            currency_codes.contains(city.currency_code.as_str()),
+            "world city {} references unknown currency code {}",
+            city.city_name,
+            city.currency_code
+        );
+        assert!(
+            city.timezone.contains('/'),
+            "world city {} should use an IANA timezone",
+            city.city_name
+        );
+        assert!(
+            seen_names.insert(city.city_name.to_lowercase()),
+            "duplicate world city {}",
+            city.city_name
+        );
+        assert!(
+            seen_codes.insert(city.city_code.as_str()),
+            "duplicate world city code {}",
+            city.city_code
+        );
+    }
+}
+
+fn render_world_cities(world_cities: &[WorldCityRow]) -> String {
+    let mut output = String::new();
+    output.push_str("\npub const WORLD_CITY_REFERENCES: &[WorldCityReference] = &[\n");
+    for row in world_cities {
+        output.push_str("    WorldCityReference {\n");
+        output.push_str(&format!("        name: {:?},\n", row.city_name));
+        output.push_str(&format!("        code: {:?},\n", row.city_code));
+        output.push_str(&format!("        country_code: {:?},\n", row.country_code));
+        output.push_str(&format!("        timezone: {:?},\n", row.timezone));
+        output.push_str(&format!(
+            "        currency_code: {:?},\n",
+            row.currency_code
+        ));
+        output.push_str(&format!("        lat: {:?},\n", row.lat));
+        output.push_str(&format!("        lon: {:?},\n", row.lon));
+        output.push_str("    },\n");
+    }
+    output.push_str("];\n");
+    output
+}
+
+#[derive(Debug)]
+struct FallbackRates {
+    as_of: String,
+    rows: Vec<(String, f64)>,
+}
+
+fn parse_fallback_rates_csv(path: &Path) -> FallbackRates {
+    let contents = fs::read_to_string(path).expect("fallback_rates.csv should be readable");
+    let mut lines = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let as_of = lines
+        .next()
+        .and_then(|line| line.strip_prefix("as_of,"))
+        .expect("fallback_rates.csv should start with an as_of line")
+        .trim()
+        .to_string();
+    assert_eq!(
+        lines.next(),
+        Some("currency_code,per_usd"),
+        "fallback_rates.csv should have a currency_code,per_usd header"
+    );
+
+    let rows = lines
+        .map(|line| {
+            let (code, per_usd) = line
+                .split_once(',')
+                .unwrap_or_else(|| panic!("fallback rate line should have 2 columns: {}", line));
+            let per_usd: f64 = per_usd
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("fallback rate for {} should parse", code));
+            assert!(
+                per_usd > 0.0,
+                "fallback rate for {} should be positive",
+                code
+            );
+            (code.trim().to_string(), per_usd)
+        })
+        .collect();
+
+    FallbackRates { as_of, rows }
+}
+
+fn validate_fallback_rates(countries: &[CountryRow], fallback_rates: &FallbackRates) {
+    let currency_codes: HashSet<&str> = countries
+        .iter()
+        .map(|row| row.currency_code.as_str())
+        .collect();
+    let mut seen = HashSet::new();
+
+    for (code, _) in &fallback_rates.rows {
+        assert!(
+            currency_codes.contains(code.as_str()),
+            "fallback rate references unknown currency code {}",
+            code
+        );
+        assert!(seen.insert(code), "duplicate fallback rate for {}", code);
+    }
+}
+
+fn render_fallback_rates(fallback_rates: &FallbackRates) -> String {
+    let mut output = String::new();
+    output.push_str(&format!(
+        "\npub const FALLBACK_RATES_AS_OF: &str = {:?};\n\n",
+        fallback_rates.as_of
+    ));
+    output.push_str("pub const FALLBACK_RATES_PER_USD: &[(&str, f64)] = &[\n");
+    for (code, per_usd) in &fallback_rates.rows {
+        output.push_str(&format!("    ({:?}, {:?}),\n", code, per_usd));
+    }
+    output.push_str("];\n");
+    output
+}
+
 fn render_reference_data(
     countries: &[CountryRow],
     representative_cities: &[RepresentativeCityRow],
@@ -0,0 +1,141 @@
+//! one-shot alarms at an overseas local time
+//! "/alarm 09:00 LDN" rings when London next reaches 9am; pending alarms are kept in config
+
+use chrono::{DateTime, Duration, NaiveDateTime, NaiveTime, Utc};
+
+use crate::config::{AlarmConfig, Config};
+use crate::events::{EVENT_TIME_FORMAT, city_local_instant, parse_event_time};
+use crate::timezone::parse_city_timezone;
+
+/// an alarm more overdue than this rang while nzi was closed
+pub const MISSED_AFTER: Duration = Duration::minutes(5);
+
+/// a stored alarm resolved against its city's clock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledAlarm {
+    pub alarm: AlarmConfig,
+    pub city_name: String,
+    pub local: NaiveDateTime,
+    /// negative once the alarm time has passed
+    pub remaining: Duration,
+}
+
+impl ScheduledAlarm {
+    pub fn is_due(&self) -> bool {
+        self.remaining <= Duration::zero()
+    }
+
+    pub fn is_missed(&self) -> bool {
+        self.remaining < -MISSED_AFTER
+    }
+}
+
+/// every alarm whose city is still configured, soonest first
+pub fn scheduled_alarms(config: &Config, now: DateTime<Utc>) -> Vec<ScheduledAlarm> {
+    let mut alarms: Vec<ScheduledAlarm> = config
+        .alarms
+        .iter()
+        .filter_map(|alarm| {
+            let city = config
+                .all_cities()
+                .into_iter()
+                .find(|city| city.code.eq_ignore_ascii_case(&alarm.city_code))?;
+            let local = parse_event_time(&alarm.at)?;
+            let instant = city_local_instant(local, city)?;
+            Some(ScheduledAlarm {
+                alarm: alarm.clone(),
+                city_name: city.name.clone(),
+                local,
+                remaining: instant - now,
+            })
+        })
+        .collect();
+    alarms.sort_by_key(|alarm| alarm.remaining);
+    alarms
+}
+
+/// parse "<HH:MM> <city code>" into the next time that city's clock shows HH:MM
+pub fn parse_alarm_command(
+    input: &str,
+    config: &Config,
+    now: DateTime<Utc>,
+) -> std::result::Result<AlarmConfig, String> {
+    const USAGE: &str = "usage: /alarm <HH:MM> <city code>";
+
+    let mut parts = input.split_whitespace();
+    let (Some(time), Some(code), None) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(USAGE.to_string());
+    };
+
+    let city = config
+        .all_cities()
+        .into_iter()
+        .find(|city| city.code.eq_ignore_ascii_case(code))
+        .ok_or_else(|| format!("unknown city code: {}", code))?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| format!("invalid time: {} (expected HH:MM)", time))?;
+    let timezone = parse_city_timezone(&city.timezone)
+        .ok_or_else(|| format!("invalid timezone for {}", city.name))?;
+    let city_now = timezone.convert_datetime(&now.fixed_offset()).naive_local();
+
+    let mut at = city_now.date().and_time(time);
+    if at <= city_now {
+        at += Duration::days(1);
+    }
+
+    Ok(AlarmConfig {
+        city_code: city.code.clone(),
+        at: at.format(EVENT_TIME_FORMAT).to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn alarm_rings_at_next_local_occurrence() {
+        let mut config = Config::default();
+        // 2025-06-06 12:00 UTC is 13:00 in london (BST)
+        let now = Utc.with_ymd_and_hms(2025, 6, 6, 12, 0, 0).unwrap();
+
+        let later_today = parse_alarm_command("17:30 ldn", &config, now).unwrap();
+        assert_eq!(later_today.city_code, "LDN");
+        assert_eq!(later_today.at, "2025-06-06 17:30");
+        let tomorrow = parse_alarm_command("09:00 LDN", &config, now).unwrap();
+        assert_eq!(tomorrow.at, "2025-06-07 09:00");
+
+        assert!(parse_alarm_command("09:00", &config, now).is_err());
+        assert!(parse_alarm_command("9am LDN", &config, now).is_err());
+        assert!(parse_alarm_command("09:00 XXX", &config, now).is_err());
+
+        config.alarms = vec![tomorrow, later_today];
+        let scheduled = scheduled_alarms(&config, now);
+        assert_eq!(scheduled[0].local.format("%H:%M").to_string(), "17:30");
+        assert_eq!(scheduled[0].remaining, Duration::minutes(270));
+        assert!(!scheduled[0].is_due());
+
+        let overdue = now + Duration::minutes(272);
+        let scheduled = scheduled_alarms(&config, overdue);
+        assert!(scheduled[0].is_due() && !scheduled[0].is_missed());
+        let much_later = now + Duration::hours(6);
+        assert!(scheduled_alarms(&config, much_later)[0].is_missed());
+    }
+
+    #[test]
+    fn alarms_in_a_dst_gap_ring_when_the_clocks_jump() {
+        // london skips 01:00-02:00 on 2026-03-29; the jump is at 01:00 UTC
+        let config = Config {
+            alarms: vec![AlarmConfig {
+                city_code: "LDN".to_string(),
+                at: "2026-03-29 01:30".to_string(),
+            }],
+            ..Config::default()
+        };
+        let now = Utc.with_ymd_and_hms(2026, 3, 29, 0, 0, 0).unwrap();
+        let scheduled = scheduled_alarms(&config, now);
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!(scheduled[0].remaining, Duration::hours(1));
+    }
+}
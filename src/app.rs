@@ -1,20 +1,97 @@
 //! application state and logic for nzi-cli
 
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
 use anyhow::{Result, anyhow};
-use chrono::Timelike;
-
-use crate::config::{City, Config, MapConfig, TimeConfig};
-use crate::exchange::{CurrencyConverter, ExchangeService};
-use crate::map::NZ_CITIES;
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, FixedOffset, Local, LocalResult, NaiveDate,
+    NaiveTime, Timelike, Utc,
+};
+use ratatui::layout::Rect;
+
+use crate::alarms::{ScheduledAlarm, parse_alarm_command, scheduled_alarms};
+use crate::aurora::AuroraService;
+use crate::bundle;
+use crate::cache::{self, CacheLimits, RATE_SNAPSHOTS, SnapshotStore, WEATHER_SNAPSHOTS};
+use crate::calendar::{CalendarCell, first_of_month, month_weeks, shift_month};
+use crate::climate::{self, ClimateRecorder, DailyRecord, MonthSummary, monthly_summaries};
+use crate::clock::{describe_skew, measure_skew};
+use crate::config::{
+    AlarmConfig, AnimationLayer, City, Config, EventConfig, FxThreshold, LAYOUT_HEIGHT_RANGE,
+    LAYOUT_PERCENT_RANGE, MapBookmark, MapConfig, MapMarkerStyle, StartupFocus, TimeConfig,
+    TodoItem, WorldProjection,
+};
+use crate::config_check::{self, ConfigProblem};
+use crate::events::{
+    UpcomingEvent, event_has_passed, event_instant, format_countdown, next_event,
+    parse_event_command,
+};
+use crate::exchange::{CurrencyConverter, ExchangeService, threshold_breach};
+use crate::export;
+use crate::flight::{
+    MIN_FLIGHT_KM, flight_duration, format_distance_km, format_flight_duration, great_circle_km,
+};
+use crate::health::{DataSource, SourceHealth};
+use crate::heat::HeatService;
+use crate::holidays::{Holiday, HolidayService, NzRegion, holiday_day_label, upcoming_nz_holiday};
+use crate::http;
+use crate::icons::{self, Glyph};
+use crate::iss::{IssService, Pass};
+use crate::keymap::{KeyAction, Keymap};
+use crate::ledger::{self, LedgerEntry};
+use crate::map::{
+    MapRegion, MapViewport, NZ_CITIES, NZ_LAT_MAX, NZ_LAT_MIN, NZ_LON_MAX, NZ_LON_MIN, NZ_REGIONS,
+    WORLD_LAT_MAX, WORLD_LAT_MIN, WORLD_REGIONS, canvas_point, nearest_point,
+};
+use crate::notify::notify;
+use crate::orbit::Orbit;
+use crate::panels::CustomPanels;
+use crate::places::{find_world_city, search_new_cities, world_city_entry};
+use crate::planner::{BestSlot, CallWindow, PlannerRow, best_slot, call_windows, plan_rows};
+use crate::quakes::{Quake, QuakeService, recent_quakes};
 use crate::reference::{
-    country_by_code, focal_country_code_for_currency, lookup_country, lookup_currency,
-    representative_city_by_city_code, search_countries, search_currencies,
-    search_representative_cities,
+    FALLBACK_RATES_AS_OF, country_alpha2, country_by_code, focal_country_code_for_currency,
+    lookup_country, lookup_currency, representative_city_by_city_code, search_countries,
+    search_currencies, search_representative_cities,
 };
-use crate::timezone::{CityTime, TimeConverter, TimezoneService};
-use crate::weather::{CurrentWeather, WeatherService};
+use crate::settings::SettingRow;
+use crate::stats::SessionStats;
+use crate::sun::city_coordinates;
+use crate::theme;
+use crate::timezone::{
+    CityTime, TimeConverter, TimezoneService, clock_format, format_instant_in, parse_city_timezone,
+    parse_epoch,
+};
+use crate::tour::{TourStep, TourTarget, tour_steps};
+use crate::ui::{self, ClickAreas};
+use crate::weather::{CITY_CODE_COORDS, CurrentWeather, WeatherAlert, WeatherService};
+
+/// how long the splash stays up after every data source has been checked
+const SPLASH_HOLD: Duration = Duration::from_millis(1500);
+
+/// event poll interval while the terminal has focus
+const FOCUSED_POLL: Duration = Duration::from_millis(100);
+
+/// tick and poll interval while the terminal is unfocused (energy saver)
+const UNFOCUSED_INTERVAL: Duration = Duration::from_secs(1);
+
+/// size a map export is drawn at before the map has been on screen (and when headless)
+pub const EXPORT_MAP_SIZE: (u16, u16) = (72, 36);
+
+/// fraction of the visible map an arrow key pans
+const MAP_PAN_STEP: f64 = 0.25;
+
+/// kept responses the currency panel's rate sparkline looks back over
+const RATE_TREND_LEN: usize = 30;
+
+/// percentage points Ctrl+Left and Ctrl+Right move the map split
+const SPLIT_PERCENT_STEP: u16 = 5;
+
+/// animation frames a zoom or weather view change takes to settle into the new layout
+pub const TRANSITION_FRAMES: usize = 6;
 
 /// which panel is currently focused
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -25,6 +102,17 @@ pub enum Focus {
     Currency,
 }
 
+impl From<StartupFocus> for Focus {
+    fn from(focus: StartupFocus) -> Self {
+        match focus {
+            StartupFocus::Map => Focus::Map,
+            StartupFocus::Weather => Focus::Weather,
+            StartupFocus::Time => Focus::TimeConvert,
+            StartupFocus::Currency => Focus::Currency,
+        }
+    }
+}
+
 impl Focus {
     pub fn next(self) -> Self {
         match self {
@@ -81,6 +169,30 @@ impl Focus {
     }
 }
 
+/// the result of a fetch run off the UI loop, handed back through the app's channel
+enum Fetched {
+    Holidays {
+        alpha2: String,
+        year: i32,
+        result: Result<Vec<Holiday>>,
+    },
+    Quakes(Result<Vec<Quake>>),
+    Aurora(Result<f64>),
+    Climate {
+        today: NaiveDate,
+        result: Result<Vec<DailyRecord>>,
+    },
+    Heat(Result<Vec<f64>>),
+    Iss(Result<(f64, f64)>),
+    IssOrbit(Result<Orbit>),
+    ClockSkew(Result<i64>),
+    FxRate {
+        from: String,
+        to: String,
+        result: Result<String>,
+    },
+}
+
 /// main application state
 pub struct App {
     pub config: Config,
@@ -89,20 +201,54 @@ pub struct App {
     pub running: bool,
     pub focus: Focus,
     pub map_context: Focus,
+    /// the focused map panel shows NZ rather than the world ('v')
+    pub map_shows_nz: bool,
+    /// the NZ map draws the Cook Strait ferry route ('f' on the map)
+    pub map_shows_ferry: bool,
+    /// both maps mark airports ('a' on the map)
+    pub map_shows_airports: bool,
+    /// a legend box in the map panel's corner ('i' on the map)
+    pub map_shows_legend: bool,
+    /// the NZ coastline is shaded by current temperatures ('H' on the map)
+    pub map_shows_heat: bool,
+    /// SH1 and the main state highways on the NZ map ('r' on the map)
+    pub map_shows_highways: bool,
+    /// places picked by the measuring tool ('m' on the map), as (code, (lat, lon))
+    pub map_measure: Option<Vec<(String, (f64, f64))>>,
+    /// the (lat, lon) under the keyboard crosshair ('x' on the map)
+    pub map_crosshair: Option<(f64, f64)>,
+    /// the `[map]` bookmark last shown by ', within those for the shown map
+    map_bookmark: Option<usize>,
+    pub nz_map_view: MapViewport,
+    pub world_map_view: MapViewport,
+    /// where panels, title hints and rows were last drawn, for mouse clicks
+    pub click_areas: ClickAreas,
 
     // services
     pub exchange_service: ExchangeService,
     pub timezone_service: TimezoneService,
+    time_refresh_key: Option<(i64, String)>, // displayed time bucket and from city of the last refresh
     pub weather_service: WeatherService,
+    pub holiday_service: HolidayService,
+    pub quake_service: QuakeService,
+    pub iss_service: IssService,
+    pub aurora_service: AuroraService,
+    pub heat_service: HeatService,
+    /// background fetches send their results here for the UI loop to pick up
+    fetched_tx: mpsc::Sender<Fetched>,
+    fetched_rx: mpsc::Receiver<Fetched>,
 
     // widget states
     pub currency_converter: CurrencyConverter,
+    /// the converter pair's rate in recent kept responses, oldest first
+    pub rate_history: Vec<f64>,
     pub time_converter: TimeConverter,
 
     // cached city times
     pub current_city_time: Option<CityTime>,
     pub home_city_time: Option<CityTime>,
-    pub world_city_times: Vec<CityTime>, // tracked world cities
+    pub world_city_times: Vec<CityTime>,  // tracked world cities
+    pub from_city_time: Option<CityTime>, // time converter's from city
 
     // cached weather - now supports multiple cities
     pub current_weather: Option<CurrentWeather>,
@@ -110,11 +256,16 @@ pub struct App {
     pub weather_error: Option<String>, // last weather fetch error
     pub weather_refresh_pending: bool, // flag to request weather refresh
     pub weather_expanded: bool,    // toggle between compact and expanded grid view
+    pub zoomed: bool,              // focused panel fills the content area
+    pub transition: Option<PanelTransition>, // layout change still animating
 
     // animation state
     pub animation_frame: usize,
     pub last_tick: Instant,
     pub tick_rate: Duration,
+    pub terminal_focused: bool, // false drops to the energy-saver rate
+    pub last_input: Instant,    // last key or click, for `display.ambient_after_mins`
+    pub ambient: bool,          // full-screen clocks, map and sparkles until a key
 
     // status message
     pub status_message: Option<(String, Instant)>,
@@ -124,18 +275,75 @@ pub struct App {
 
     // data source status
     pub is_online: bool,
+    pub data_sources: Vec<SourceHealth>,
+
+    // startup splash, dismissed once every source is checked (or on any key)
+    pub show_splash: bool,
+    pub splash_until: Option<Instant>,
 
     // help overlay
     pub show_help: bool,
 
+    // informational overlay opened by a slash command
+    pub overlay: Option<Overlay>,
+
+    // fields of config.toml that fell back to defaults at the last load
+    pub config_problems: Vec<ConfigProblem>,
+
+    // main-screen keys after any [keys] remaps
+    keymap: Keymap,
+
+    // session metrics for /stats
+    pub session_stats: SessionStats,
+
+    // conversion ledger rows shown by /history, newest first
+    pub history: Vec<LedgerEntry>,
+    pub history_scroll: usize,
+
+    // time converter expanded to list the input time in every city ('b')
+    pub broadcast_expanded: bool,
+
+    // how far scrub mode has moved the converter's input time, in minutes
+    pub scrub_offset_minutes: i32,
+
+    // month shown by the /cal popup (always the first of the month)
+    pub calendar_month: NaiveDate,
+
+    // index into the /tour steps while the tour is running
+    pub tour_step: Option<usize>,
+
+    // monthly climate summaries for the weather city, shown by /climate
+    pub climate: Vec<MonthSummary>,
+    /// how this month compares with earlier years, for the /climate overlay
+    pub climate_headline: Option<String>,
+    climate_recorder: ClimateRecorder,
+
     // request to open config in editor
     pub edit_config_requested: bool,
 
     // command input buffer (for /help, /edit, etc.)
     pub command_buffer: String,
 
+    // first key of a two-key chord, awaiting the second
+    pub pending_chord: Option<char>,
+
+    // vim-style numeric prefix for the next repeatable key
+    pub pending_count: Option<u32>,
+
     // interactive search picker
     pub picker: Option<PickerState>,
+
+    // meeting planner overlay
+    pub planner: Option<PlannerState>,
+    pub custom_panels: CustomPanels,
+    reminded_events: HashSet<String>,
+    // seconds the system clock is ahead of network time, once measured
+    pub clock_skew: Option<i64>,
+    // unix timestamp shown in the converter's from and to cities (/ts)
+    pub timestamp: Option<DateTime<Utc>>,
+    // weather alerts already cued for the current weather city
+    active_weather_alerts: HashSet<WeatherAlert>,
+    active_fx_alerts: HashSet<String>,
 }
 
 /// input mode for the application
@@ -144,6 +352,87 @@ pub enum InputMode {
     Normal,
     EditingCurrency,
     EditingTime,
+    /// left/right move the time converter in half-hour steps
+    ScrubbingTime,
+}
+
+/// read-only overlays opened from slash commands, closed with Esc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlay {
+    Stats,
+    About,
+    History,
+    Climate,
+    Calendar,
+    /// what was wrong with config.toml at load, and the defaults used instead
+    ConfigProblems,
+}
+
+/// actions reachable through two-key chords
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordAction {
+    Focus(Focus),
+    CyclePair,
+    CycleFromCity,
+    CycleWeatherCity,
+}
+
+/// chord bindings as (prefix, key, action, hint)
+pub const CHORDS: &[(char, char, ChordAction, &str)] = &[
+    ('g', 'm', ChordAction::Focus(Focus::Map), "map"),
+    ('g', 'w', ChordAction::Focus(Focus::Weather), "weather"),
+    ('g', 't', ChordAction::Focus(Focus::TimeConvert), "time"),
+    ('g', 'c', ChordAction::Focus(Focus::Currency), "currency"),
+    ('c', 'p', ChordAction::CyclePair, "cycle pair"),
+    ('c', 'f', ChordAction::CycleFromCity, "cycle from city"),
+    (
+        'c',
+        'w',
+        ChordAction::CycleWeatherCity,
+        "cycle weather city",
+    ),
+];
+
+/// a place picked on the map, as (code, name, (lat, lon))
+type MapPlace = (String, String, (f64, f64));
+
+/// largest accepted count prefix, so a stray run of digits cannot spin for long
+const MAX_COUNT: u32 = 99;
+
+fn is_chord_prefix(key: char) -> bool {
+    CHORDS.iter().any(|(prefix, ..)| *prefix == key)
+}
+
+fn resolve_chord(prefix: char, key: char) -> Option<ChordAction> {
+    CHORDS
+        .iter()
+        .find(|(chord_prefix, chord_key, ..)| *chord_prefix == prefix && *chord_key == key)
+        .map(|(_, _, action, _)| *action)
+}
+
+/// (key, hint) pairs available after a chord prefix, for the which-key popup
+pub fn chord_hints(prefix: char) -> Vec<(char, &'static str)> {
+    CHORDS
+        .iter()
+        .filter(|(chord_prefix, ..)| *chord_prefix == prefix)
+        .map(|(_, key, _, hint)| (*key, *hint))
+        .collect()
+}
+
+/// where the panels were drawn before a zoom or weather view change, so the new layout
+/// can grow out of the old one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanelTransition {
+    pub from: Vec<(Rect, Focus)>,
+    pub started: usize,
+}
+
+/// meeting planner overlay state; the candidate time is in the anchor city
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannerState {
+    pub date: NaiveDate,
+    pub time: NaiveTime,
+    pub best: Option<BestSlot>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -156,13 +445,17 @@ pub struct PickerState {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConfigTab {
     Places,
+    Settings,
     Actions,
 }
 
 impl ConfigTab {
+    pub const ALL: [ConfigTab; 3] = [Self::Places, Self::Settings, Self::Actions];
+
     fn next(self) -> Self {
         match self {
-            Self::Places => Self::Actions,
+            Self::Places => Self::Settings,
+            Self::Settings => Self::Actions,
             Self::Actions => Self::Places,
         }
     }
@@ -170,13 +463,15 @@ impl ConfigTab {
     fn prev(self) -> Self {
         match self {
             Self::Places => Self::Actions,
-            Self::Actions => Self::Places,
+            Self::Settings => Self::Places,
+            Self::Actions => Self::Settings,
         }
     }
 
     pub fn label(self) -> &'static str {
         match self {
             Self::Places => "Places",
+            Self::Settings => "Settings",
             Self::Actions => "Actions",
         }
     }
@@ -195,6 +490,9 @@ enum PickerKind {
     AnchorCity,
     TargetCity,
     PlaceCurrency,
+    AddCity,
+    /// the currency for a city found only as an IANA zone, before it is added
+    NewCityCurrency(City),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -222,6 +520,7 @@ enum PickerChoice {
         name: String,
         country: String,
     },
+    NewCity(City),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -236,12 +535,98 @@ enum CommandAction {
     ResetDraft,
     RestoreDraft,
     Refresh,
-    SetFocalCountry { code: String, name: String },
-    AddPlaceCurrency { code: String, name: String },
-    SetMapEnabled { enabled: bool },
+    ShowStats,
+    ShowAbout,
+    ShowConfigProblems,
+    OpenSettings,
+    OpenPlanner,
+    SetFocalCountry {
+        code: String,
+        name: String,
+    },
+    AddPlaceCurrency {
+        code: String,
+        name: String,
+    },
+    SetMapEnabled {
+        enabled: bool,
+    },
+    SetMapMarker {
+        style: MapMarkerStyle,
+    },
     OpenCountryPicker,
     OpenPlaceCurrencyPicker,
     OpenMapPicker,
+    OpenCityPicker {
+        query: String,
+    },
+    SetClockFormat {
+        use_24_hour: Option<bool>,
+    }, // None toggles
+    AddEvent {
+        args: String,
+    },
+    ClearEvents,
+    SaveMapBookmark {
+        name: String,
+    },
+    ListMapBookmarks,
+    ClearMapBookmarks,
+    AddAlarm {
+        args: String,
+    },
+    ListAlarms,
+    ClearAlarms,
+    AddTodo {
+        text: String,
+    },
+    ToggleTodo {
+        number: usize,
+    }, // 1-based, as shown in the panel
+    RemoveTodo {
+        number: usize,
+    },
+    ClearDoneTodos,
+    ToggleChecklist,
+    ToggleHighContrast,
+    SetNote {
+        note: Option<String>,
+    }, // None clears
+    ShowNote,
+    ShowTimestamp {
+        input: String,
+    }, // empty uses the current time
+    ClearTimestamp,
+    ShowHistory,
+    ShowClimate,
+    ShowCalendar,
+    StartTour,
+    ExportHistory {
+        path: Option<String>,
+    }, // None uses the dated default
+    ExportMap {
+        path: Option<String>,
+    }, // None uses the timestamped default
+    ExportConfig {
+        path: Option<String>,
+    }, // None uses the dated default
+    ImportConfig {
+        path: String,
+        allow_commands: bool, // --yes keeps the bundle's shell commands
+    },
+    AddTrackedCity {
+        city: City,
+    },
+    AddWorldCity {
+        query: String,
+    },
+    ShowCache,
+    ClearCache,
+    ShowAnimations,
+    SetAnimation {
+        layer: AnimationLayer,
+        on: Option<bool>,
+    }, // None toggles
 }
 
 fn parse_command(input: &str) -> std::result::Result<CommandAction, String> {
@@ -259,9 +644,56 @@ fn parse_command(input: &str) -> std::result::Result<CommandAction, String> {
         "/reset" => return Ok(CommandAction::ResetDraft),
         "/restore" => return Ok(CommandAction::RestoreDraft),
         "/refresh" => return Ok(CommandAction::Refresh),
+        "/stats" => return Ok(CommandAction::ShowStats),
+        "/history" => return Ok(CommandAction::ShowHistory),
+        "/climate" => return Ok(CommandAction::ShowClimate),
+        "/cal" | "/calendar" => return Ok(CommandAction::ShowCalendar),
+        "/tour" => return Ok(CommandAction::StartTour),
+        "/anim" => return Ok(CommandAction::ShowAnimations),
+        "/cache" => return Ok(CommandAction::ShowCache),
+        "/cache clear" => return Ok(CommandAction::ClearCache),
+        "/history export" => return Ok(CommandAction::ExportHistory { path: None }),
+        "/export map" => return Ok(CommandAction::ExportMap { path: None }),
+        "/export config" => return Ok(CommandAction::ExportConfig { path: None }),
+        "/about" => return Ok(CommandAction::ShowAbout),
+        "/problems" => return Ok(CommandAction::ShowConfigProblems),
+        "/settings" => return Ok(CommandAction::OpenSettings),
+        "/plan" | "/meet" => return Ok(CommandAction::OpenPlanner),
         "/country" | "/focus" => return Ok(CommandAction::OpenCountryPicker),
         "/currency" => return Ok(CommandAction::OpenPlaceCurrencyPicker),
         "/map" => return Ok(CommandAction::OpenMapPicker),
+        "/clock" => return Ok(CommandAction::SetClockFormat { use_24_hour: None }),
+        "/clock 12" | "/12h" => {
+            return Ok(CommandAction::SetClockFormat {
+                use_24_hour: Some(false),
+            });
+        }
+        "/clock 24" | "/24h" => {
+            return Ok(CommandAction::SetClockFormat {
+                use_24_hour: Some(true),
+            });
+        }
+        "/event clear" | "/events clear" => return Ok(CommandAction::ClearEvents),
+        "/bookmark" | "/bookmarks" => return Ok(CommandAction::ListMapBookmarks),
+        "/bookmark clear" | "/bookmarks clear" => return Ok(CommandAction::ClearMapBookmarks),
+        "/alarm" | "/alarms" => return Ok(CommandAction::ListAlarms),
+        "/alarm clear" | "/alarms clear" => return Ok(CommandAction::ClearAlarms),
+        "/ts" => {
+            return Ok(CommandAction::ShowTimestamp {
+                input: String::new(),
+            });
+        }
+        "/ts clear" => return Ok(CommandAction::ClearTimestamp),
+        "/note" => return Ok(CommandAction::ShowNote),
+        "/todo" => return Ok(CommandAction::ToggleChecklist),
+        "/contrast" => return Ok(CommandAction::ToggleHighContrast),
+        "/todo clear" => return Ok(CommandAction::ClearDoneTodos),
+        "/note clear" => return Ok(CommandAction::SetNote { note: None }),
+        "/city" | "/city add" | "/addcity" => {
+            return Ok(CommandAction::OpenCityPicker {
+                query: String::new(),
+            });
+        }
         _ => {}
     }
 
@@ -281,9 +713,120 @@ fn parse_command(input: &str) -> std::result::Result<CommandAction, String> {
         return resolve_map_command(rest);
     }
 
+    if let Some(rest) = lowered.strip_prefix("/anim ") {
+        return resolve_anim_command(rest);
+    }
+
+    if let Some(rest) = lowered.strip_prefix("/city add ") {
+        // keep the user's casing for the search query
+        let query = trimmed[trimmed.len() - rest.len()..].trim().to_string();
+        return Ok(CommandAction::OpenCityPicker { query });
+    }
+
+    if let Some(rest) = lowered.strip_prefix("/addcity ") {
+        let query = trimmed[trimmed.len() - rest.len()..].trim().to_string();
+        return Ok(CommandAction::AddWorldCity { query });
+    }
+
+    if let Some(rest) = trimmed.strip_prefix("/alarm ") {
+        return Ok(CommandAction::AddAlarm {
+            args: rest.trim().to_string(),
+        });
+    }
+
+    if let Some(rest) = trimmed.strip_prefix("/bookmark ") {
+        return Ok(CommandAction::SaveMapBookmark {
+            name: rest.trim().to_string(),
+        });
+    }
+
+    if let Some(rest) = trimmed.strip_prefix("/event ") {
+        return Ok(CommandAction::AddEvent {
+            args: rest.trim().to_string(),
+        });
+    }
+
+    if lowered.starts_with("/todo ") {
+        return resolve_todo_command(&trimmed["/todo ".len()..]);
+    }
+
+    if let Some(rest) = trimmed.strip_prefix("/note ") {
+        return Ok(CommandAction::SetNote {
+            note: Some(rest.trim().to_string()),
+        });
+    }
+
+    if lowered.starts_with("/history export ") {
+        let path = trimmed["/history export ".len()..].trim().to_string();
+        return Ok(CommandAction::ExportHistory { path: Some(path) });
+    }
+
+    if lowered.starts_with("/export map ") {
+        let path = trimmed["/export map ".len()..].trim().to_string();
+        return Ok(CommandAction::ExportMap { path: Some(path) });
+    }
+
+    if lowered.starts_with("/export config ") {
+        let path = trimmed["/export config ".len()..].trim().to_string();
+        return Ok(CommandAction::ExportConfig { path: Some(path) });
+    }
+
+    if lowered == "/export" || lowered.starts_with("/export ") {
+        return Err("usage: /export map|config [path]".to_string());
+    }
+
+    if lowered.starts_with("/import config ") {
+        let rest = trimmed["/import config ".len()..].trim();
+        let (path, allow_commands) = match rest.strip_suffix("--yes") {
+            Some(path) => (path.trim(), true),
+            None => (rest, false),
+        };
+        if !path.is_empty() {
+            return Ok(CommandAction::ImportConfig {
+                path: path.to_string(),
+                allow_commands,
+            });
+        }
+    }
+
+    if lowered == "/import" || lowered.starts_with("/import ") {
+        return Err("usage: /import config <path> [--yes]".to_string());
+    }
+
+    if let Some(rest) = trimmed.strip_prefix("/ts ") {
+        return Ok(CommandAction::ShowTimestamp {
+            input: rest.trim().to_string(),
+        });
+    }
+
+    if lowered.starts_with("/city ") {
+        return Err("usage: /city add [search]".to_string());
+    }
+
     Err(format!("unknown command: {}", trimmed))
 }
 
+fn resolve_todo_command(args: &str) -> std::result::Result<CommandAction, String> {
+    const USAGE: &str = "usage: /todo add <text> | done <n> | rm <n> | clear";
+
+    let (verb, rest) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+    let rest = rest.trim();
+    let number = || {
+        rest.parse::<usize>()
+            .ok()
+            .filter(|number| *number > 0)
+            .ok_or_else(|| format!("invalid item number: {}", rest))
+    };
+    match verb.to_lowercase().as_str() {
+        "add" if !rest.is_empty() => Ok(CommandAction::AddTodo {
+            text: rest.to_string(),
+        }),
+        "done" | "toggle" => Ok(CommandAction::ToggleTodo { number: number()? }),
+        "rm" | "remove" => Ok(CommandAction::RemoveTodo { number: number()? }),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
 fn resolve_country_command(query: &str) -> std::result::Result<CommandAction, String> {
     let query = query.trim();
     if query.is_empty() {
@@ -326,11 +869,32 @@ fn resolve_map_command(query: &str) -> std::result::Result<CommandAction, String
     match query.trim().to_lowercase().as_str() {
         "on" | "show" => Ok(CommandAction::SetMapEnabled { enabled: true }),
         "off" | "hide" => Ok(CommandAction::SetMapEnabled { enabled: false }),
-        "" => Err("usage: /map <on|off>".to_string()),
-        other => Err(format!("unknown map option: {}", other)),
+        "" => Err("usage: /map <on|off|braille|half-block|dot>".to_string()),
+        other => MapMarkerStyle::from_name(other)
+            .map(|style| CommandAction::SetMapMarker { style })
+            .ok_or_else(|| format!("unknown map option: {}", other)),
     }
 }
 
+fn resolve_anim_command(args: &str) -> std::result::Result<CommandAction, String> {
+    let usage = || {
+        let names: Vec<&str> = AnimationLayer::ALL
+            .iter()
+            .map(|layer| layer.name())
+            .collect();
+        format!("usage: /anim <{}> [on|off]", names.join("|"))
+    };
+    let (name, state) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+    let layer = AnimationLayer::from_name(name).ok_or_else(usage)?;
+    let on = match state.trim() {
+        "" => None,
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => return Err(usage()),
+    };
+    Ok(CommandAction::SetAnimation { layer, on })
+}
+
 fn apply_command_action_to_config(
     config: &mut Config,
     action: &CommandAction,
@@ -340,7 +904,7 @@ fn apply_command_action_to_config(
             let city = config
                 .representative_city_for_country_code(code)
                 .ok_or_else(|| format!("no representative city configured for {}", name))?;
-            ensure_city_in_config_catalogue(config, &city);
+            ensure_city_in_config_catalogue(config, &city)?;
             let time = config.time.get_or_insert_with(TimeConfig::default);
             time.anchor_city_code = Some(city.code.clone());
             time.city_codes.clear();
@@ -357,7 +921,7 @@ fn apply_command_action_to_config(
             let city = config
                 .representative_city_for_currency_code(code)
                 .ok_or_else(|| format!("no representative city configured for {}", name))?;
-            ensure_city_in_config_catalogue(config, &city);
+            ensure_city_in_config_catalogue(config, &city)?;
             let anchor_code = config.effective_anchor_city_code();
             let time = config.time.get_or_insert_with(TimeConfig::default);
             time.anchor_city_code
@@ -376,6 +940,25 @@ fn apply_command_action_to_config(
                 code, country_name, city.name
             )))
         }
+        CommandAction::AddTrackedCity { city } => {
+            ensure_city_in_config_catalogue(config, city)?;
+            // an explicit target list would otherwise hide the new city
+            let anchor_code = config.effective_anchor_city_code();
+            if let Some(time) = config.time.as_mut()
+                && !time.target_city_codes.is_empty()
+                && !city.code.eq_ignore_ascii_case(&anchor_code)
+                && !time
+                    .target_city_codes
+                    .iter()
+                    .any(|value| value.eq_ignore_ascii_case(&city.code))
+            {
+                time.target_city_codes.push(city.code.clone());
+            }
+            Ok(Some(format!(
+                "{} ({}) added to tracked cities",
+                city.name, city.code
+            )))
+        }
         CommandAction::SetMapEnabled { enabled } => {
             let map = config.map.get_or_insert_with(MapConfig::default);
             map.enabled = *enabled;
@@ -384,6 +967,11 @@ fn apply_command_action_to_config(
                 if *enabled { "enabled" } else { "disabled" }
             )))
         }
+        CommandAction::SetMapMarker { style } => {
+            let map = config.map.get_or_insert_with(MapConfig::default);
+            map.marker_style = Some(*style);
+            Ok(Some(format!("Map drawn with {} markers", style.name())))
+        }
         CommandAction::EnterConfigDraft
         | CommandAction::ShowHelp
         | CommandAction::EditConfig
@@ -394,24 +982,110 @@ fn apply_command_action_to_config(
         | CommandAction::ResetDraft
         | CommandAction::RestoreDraft
         | CommandAction::Refresh
+        | CommandAction::ShowStats
+        | CommandAction::ShowAbout
+        | CommandAction::ShowConfigProblems
+        | CommandAction::OpenSettings
+        | CommandAction::OpenPlanner
         | CommandAction::OpenCountryPicker
         | CommandAction::OpenPlaceCurrencyPicker
-        | CommandAction::OpenMapPicker => Ok(None),
+        | CommandAction::OpenMapPicker
+        | CommandAction::OpenCityPicker { .. }
+        | CommandAction::AddWorldCity { .. }
+        | CommandAction::SetClockFormat { .. }
+        | CommandAction::AddEvent { .. }
+        | CommandAction::ClearEvents
+        | CommandAction::SaveMapBookmark { .. }
+        | CommandAction::ListMapBookmarks
+        | CommandAction::ClearMapBookmarks
+        | CommandAction::AddAlarm { .. }
+        | CommandAction::ListAlarms
+        | CommandAction::ClearAlarms
+        | CommandAction::AddTodo { .. }
+        | CommandAction::ToggleTodo { .. }
+        | CommandAction::RemoveTodo { .. }
+        | CommandAction::ClearDoneTodos
+        | CommandAction::ToggleChecklist
+        | CommandAction::ToggleHighContrast
+        | CommandAction::SetNote { .. }
+        | CommandAction::ShowNote
+        | CommandAction::ShowTimestamp { .. }
+        | CommandAction::ClearTimestamp
+        | CommandAction::ShowHistory
+        | CommandAction::ShowClimate
+        | CommandAction::ShowCalendar
+        | CommandAction::StartTour
+        | CommandAction::ShowAnimations
+        | CommandAction::SetAnimation { .. }
+        | CommandAction::ShowCache
+        | CommandAction::ClearCache
+        | CommandAction::ExportHistory { .. }
+        | CommandAction::ExportMap { .. }
+        | CommandAction::ExportConfig { .. }
+        | CommandAction::ImportConfig { .. } => Ok(None),
     }
 }
 
-fn ensure_city_in_config_catalogue(config: &mut Config, city: &City) {
-    if config.current_city.code.eq_ignore_ascii_case(&city.code)
-        || config.home_city.code.eq_ignore_ascii_case(&city.code)
-        || config
-            .tracked_cities
-            .iter()
-            .any(|tracked| tracked.code.eq_ignore_ascii_case(&city.code))
+/// whether a panel is in the layout: the map under `[map] enabled`, the rest under `[panel]`
+fn panel_shown(config: &Config, focus: Focus) -> bool {
+    match focus {
+        Focus::Map => config.effective_map_settings().enabled,
+        Focus::Weather => config.shows_weather_panel(),
+        Focus::TimeConvert => config.shows_time_panel(),
+        Focus::Currency => config.shows_currency_panel(),
+    }
+}
+
+/// `focus` when it's shown, else the next shown panel in Tab order
+fn first_shown(config: &Config, focus: Focus) -> Focus {
+    let mut candidate = focus;
+    for _ in 0..4 {
+        if panel_shown(config, candidate) {
+            return candidate;
+        }
+        candidate = candidate.next();
+    }
+    focus
+}
+
+/// index into NZ_CITIES of `[startup] weather_city_code`, else of `fallback`, else the first
+fn startup_weather_index(config: &Config, fallback: &str) -> usize {
+    let code = config
+        .startup
+        .as_ref()
+        .and_then(|startup| startup.weather_city_code.as_deref())
+        .unwrap_or(fallback);
+    NZ_CITIES
+        .iter()
+        .position(|city| city.code == code)
+        .unwrap_or(0)
+}
+
+/// ISO alpha-2 country code for a city, as the holiday lookups expect
+fn city_alpha2(city: &City) -> Option<String> {
+    country_alpha2(lookup_country(&city.country)?.code)
+}
+
+fn ensure_city_in_config_catalogue(
+    config: &mut Config,
+    city: &City,
+) -> std::result::Result<(), String> {
+    if let Some(existing) = config.code_conflict(city) {
+        return Err(format!(
+            "can't add {}: code {} already belongs to {} ({})",
+            city.name, city.code, existing.name, existing.country
+        ));
+    }
+    if config
+        .all_cities()
+        .iter()
+        .any(|configured| configured.code.eq_ignore_ascii_case(&city.code))
     {
-        return;
+        return Ok(());
     }
 
     config.tracked_cities.push(city.clone());
+    Ok(())
 }
 
 fn reset_places_to_package_defaults(config: &mut Config) {
@@ -438,87 +1112,277 @@ impl App {
 
         let (from_city_code, to_city_code) = config.effective_default_time_pair();
         let time_converter = TimeConverter::new(&from_city_code, &to_city_code);
+        let custom_panels = CustomPanels::new(&config.effective_custom_panels());
 
-        // start on Wellington for weather by default
-        let wellington_index = NZ_CITIES.iter().position(|c| c.code == "WLG").unwrap_or(0);
+        // start on [startup] focus and weather city, else the map and Wellington
+        let startup = config.effective_startup();
+        let weather_city_index = startup_weather_index(&config, "WLG");
+        let focus = first_shown(&config, Focus::from(startup.focus));
+        let map_context = if focus == Focus::Map {
+            Focus::Weather
+        } else {
+            focus
+        };
+        let map_shows_heat = config.map.as_ref().is_some_and(|map| map.heat);
+        let map_shows_highways = config.map.as_ref().is_some_and(|map| map.highways);
+        let keymap = Keymap::from_config(&config.keys).unwrap_or_default();
+        theme::set_custom_colours(config.custom_colours());
+        theme::set_high_contrast(config.display.high_contrast);
+        icons::set_icon_set(config.display.icons);
+        let (fetched_tx, fetched_rx) = mpsc::channel();
+        let client = http::client();
 
         Self {
             config,
             config_draft: None,
             config_editor: None,
             running: true,
-            focus: Focus::Map,
-            map_context: Focus::Weather,
-            exchange_service: ExchangeService::new(),
+            focus,
+            map_context,
+            map_shows_nz: false,
+            map_shows_ferry: false,
+            map_shows_airports: false,
+            map_shows_legend: false,
+            map_shows_heat,
+            map_shows_highways,
+            map_measure: None,
+            map_bookmark: None,
+            map_crosshair: None,
+            nz_map_view: MapViewport::default(),
+            world_map_view: MapViewport::default(),
+            click_areas: ClickAreas::default(),
+            exchange_service: ExchangeService::new(client.clone()),
             timezone_service: TimezoneService::new(),
-            weather_service: WeatherService::new(),
+            time_refresh_key: None,
+            weather_service: WeatherService::new(client.clone()),
+            holiday_service: HolidayService::new(client.clone()),
+            quake_service: QuakeService::new(client.clone()),
+            iss_service: IssService::new(client.clone()),
+            aurora_service: AuroraService::new(client.clone()),
+            heat_service: HeatService::new(client.clone()),
+            fetched_tx,
+            fetched_rx,
             currency_converter,
+            rate_history: Vec::new(),
             time_converter,
             current_city_time: None,
             home_city_time: None,
             world_city_times: Vec::new(),
+            from_city_time: None,
             current_weather: None,
-            weather_city_index: wellington_index,
+            weather_city_index,
             weather_error: None,
             weather_refresh_pending: true, // fetch on startup
-            weather_expanded: true,        // start expanded grid
+            weather_expanded: startup.weather_expanded,
+            zoomed: false,
+            transition: None,
             animation_frame: 0,
             last_tick: Instant::now(),
             tick_rate,
+            terminal_focused: true,
+            last_input: Instant::now(),
+            ambient: false,
             status_message: None,
             input_mode: InputMode::Normal,
             is_online: false, // assume offline until proven otherwise
+            data_sources: DataSource::ALL.into_iter().map(SourceHealth::new).collect(),
+            show_splash: true,
+            splash_until: None,
             show_help: false,
+            overlay: None,
+            config_problems: Vec::new(),
+            keymap,
+            session_stats: SessionStats::new(),
+            history: Vec::new(),
+            history_scroll: 0,
+            climate: Vec::new(),
+            climate_headline: None,
+            climate_recorder: ClimateRecorder::new(client),
+            broadcast_expanded: false,
+            scrub_offset_minutes: 0,
+            calendar_month: first_of_month(Utc::now().date_naive()),
+            tour_step: None,
             edit_config_requested: false,
             command_buffer: String::new(),
+            pending_chord: None,
+            pending_count: None,
             picker: None,
+            planner: None,
+            custom_panels,
+            reminded_events: HashSet::new(),
+            clock_skew: None,
+            timestamp: None,
+            active_weather_alerts: HashSet::new(),
+            active_fx_alerts: HashSet::new(),
         }
     }
 
-    /// load application with default or saved config
+    /// load application with default or saved config; a first run starts the tour
     pub fn load() -> Result<Self> {
-        let config = Config::load()?;
-        Ok(Self::new(config))
+        let moved = Config::migrate_legacy_files();
+        let first_run = !Config::config_path().exists();
+        let (config, problems) = Config::load_checked()?;
+        let mut app = Self::new(config);
+        app.show_config_problems(problems);
+        let limits = CacheLimits::from(&app.config.effective_cache());
+        let cache_dir = Config::cache_dir();
+        cache::prune(&cache_dir, &limits, std::time::SystemTime::now());
+        app.weather_service.keep_snapshots_in(SnapshotStore::new(
+            cache_dir.clone(),
+            WEATHER_SNAPSHOTS,
+            limits,
+        ));
+        app.exchange_service.keep_snapshots_in(SnapshotStore::new(
+            cache_dir,
+            RATE_SNAPSHOTS,
+            limits,
+        ));
+        // the splash shows what an earlier session left on disk before anything is fetched
+        for source in DataSource::ALL {
+            let warm = app.source_cache_warm(source);
+            if let Some(health) = app.source_health_mut(source) {
+                health.cache_warm = warm;
+            }
+        }
+        if first_run {
+            app.start_tour();
+        }
+        match moved {
+            Ok(moved) if !moved.is_empty() => app.set_status(format!(
+                "Moved {} into the XDG config and data directories",
+                moved.join(", ")
+            )),
+            Ok(_) => {}
+            Err(e) => app.set_status(format!("Couldn't move old files: {:#}", e)),
+        }
+        Ok(app)
     }
 
     /// update the application state (called on each tick)
     pub fn tick(&mut self) {
         // update animation frame
         self.animation_frame = self.animation_frame.wrapping_add(1);
+        if self.transition_progress().is_none() {
+            self.transition = None;
+        }
 
-        // update times
-        self.update_times();
+        // update times once the displayed minute (or second) rolls over
+        self.refresh_times_if_due();
 
         // update time converter result
         self.update_time_conversion();
 
+        // reminders for countdown events
+        self.check_event_reminder();
+        self.prune_past_events();
+        self.check_alarms();
+
+        // collect and reschedule custom panel commands
+        self.custom_panels.poll();
+
+        // leave the splash once its hold time has passed
+        if self.show_splash
+            && let Some(until) = self.splash_until
+            && Instant::now() >= until
+        {
+            self.show_splash = false;
+        }
+
         // clear old status messages
         if let Some((_, timestamp)) = &self.status_message
             && timestamp.elapsed() > Duration::from_secs(5)
         {
             self.status_message = None;
         }
+
+        // drift into the ambient view once nothing has been pressed for a while
+        let idle_mins = self.config.display.ambient_after_mins;
+        if idle_mins > 0
+            && !self.ambient
+            && !self.show_splash
+            && self.last_input.elapsed() >= Duration::from_secs(idle_mins * 60)
+        {
+            self.ambient = true;
+        }
+    }
+
+    /// animate from the panels as last drawn into the next layout, when animations are on
+    fn start_transition(&mut self) {
+        if self.config.display.show_animations && !self.click_areas.panels.is_empty() {
+            self.transition = Some(PanelTransition {
+                from: self.click_areas.panels.clone(),
+                started: self.animation_frame,
+            });
+        }
+    }
+
+    /// the panels before the running layout change and how far it has got, eased so it
+    /// slows into place; None once it has finished
+    pub fn transition_progress(&self) -> Option<(&[(Rect, Focus)], f64)> {
+        let transition = self.transition.as_ref()?;
+        let elapsed = self.animation_frame.wrapping_sub(transition.started);
+        if elapsed >= TRANSITION_FRAMES {
+            return None;
+        }
+        let linear = elapsed as f64 / TRANSITION_FRAMES as f64;
+        Some((&transition.from, 1.0 - (1.0 - linear).powi(3)))
+    }
+
+    /// note a key or click; returns true when it only woke the ambient view
+    fn wake(&mut self) -> bool {
+        self.last_input = Instant::now();
+        std::mem::take(&mut self.ambient)
+    }
+
+    /// key identifying what the city times on screen depend on
+    fn time_refresh_key(&self) -> (i64, String) {
+        let now = chrono::Utc::now().timestamp();
+        let bucket = if self.config.display.show_seconds {
+            now
+        } else {
+            now.div_euclid(60)
+        };
+        (bucket, self.time_converter.from_city_code.clone())
+    }
+
+    /// recompute city times only when the displayed time or from city has changed
+    pub fn refresh_times_if_due(&mut self) {
+        if self.time_refresh_key.as_ref() != Some(&self.time_refresh_key()) {
+            self.update_times();
+        }
     }
 
     /// update all city times
     fn update_times(&mut self) {
+        self.time_refresh_key = Some(self.time_refresh_key());
+        let timezones = &mut self.timezone_service;
+
         // update current city time
-        self.current_city_time = CityTime::from_city(&self.config.current_city);
+        self.current_city_time = timezones.city_time(&self.config.current_city);
 
         // update home city time
-        self.home_city_time = CityTime::from_city(&self.config.home_city);
+        self.home_city_time = timezones.city_time(&self.config.home_city);
 
         // update target city times
         self.world_city_times = self
             .config
             .effective_target_cities()
             .into_iter()
-            .filter_map(CityTime::from_city)
+            .filter_map(|city| timezones.city_time(city))
             .collect();
 
+        // update the converter's from city time (may differ from the anchor)
+        let from_code = &self.time_converter.from_city_code;
+        self.from_city_time = self
+            .config
+            .all_cities()
+            .into_iter()
+            .find(|city| city.code.eq_ignore_ascii_case(from_code))
+            .and_then(|city| timezones.city_time(city));
+
         // update timezone service with all cities
         let cities: Vec<&City> = self.config.all_cities();
-        self.timezone_service.update(&cities);
+        timezones.update(&cities);
     }
 
     /// update time conversion result
@@ -535,12 +1399,33 @@ impl App {
         }
     }
 
+    /// cached rates older than the `[exchange]` refresh interval are fetched again
+    fn exchange_max_age(&self) -> Duration {
+        Duration::from_secs(self.config.effective_exchange().refresh_secs)
+    }
+
     /// fetch exchange rate asynchronously
     pub async fn refresh_exchange_rate(&mut self) {
         let from = self.currency_converter.from_currency.clone();
         let to = self.currency_converter.to_currency.clone();
 
-        match self.exchange_service.get_rate(&from, &to).await {
+        let result = self
+            .exchange_service
+            .get_rate(&from, &to, self.exchange_max_age())
+            .await;
+        let history = self.exchange_service.rate_history(&from, &to);
+        self.rate_history = history[history.len().saturating_sub(RATE_TREND_LEN)..].to_vec();
+        self.record_source_health(DataSource::Exchange, self.exchange_service.last_fetch_ok());
+
+        match result {
+            Ok(rate) if self.exchange_service.served_bundled_rate() => {
+                self.currency_converter.update_rate(rate);
+                self.is_online = false;
+                self.set_status(format!(
+                    "Offline: 1 {} ≈ {:.4} {} ({} average)",
+                    from, rate, to, FALLBACK_RATES_AS_OF
+                ));
+            }
             Ok(rate) => {
                 self.currency_converter.update_rate(rate);
                 self.is_online = true;
@@ -552,6 +1437,94 @@ impl App {
                 self.set_status(e.to_string());
             }
         }
+
+        self.check_fx_alerts();
+    }
+
+    /// cue alerts for rates past their configured levels; pairs without a fresh cached rate
+    /// are fetched off the UI loop and judged by `judge_fx_rate` when they arrive
+    fn check_fx_alerts(&mut self) {
+        let mut pairs: Vec<(String, String)> = self
+            .config
+            .effective_alerts()
+            .fx_thresholds
+            .iter()
+            .filter_map(FxThreshold::currencies)
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        // alerts for thresholds taken out of the config are forgotten
+        self.active_fx_alerts.retain(|label| {
+            pairs
+                .iter()
+                .any(|(from, to)| label.starts_with(&format!("{}/{} ", from, to)))
+        });
+
+        let max_age = self.exchange_max_age();
+        for (from, to) in pairs {
+            if let Some(rate) = self.exchange_service.fresh_rate(&from, &to, max_age) {
+                self.judge_fx_rate(&from, &to, rate);
+                continue;
+            }
+            let request = self.exchange_service.start_rate_fetch(&from);
+            self.spawn_fetch(async move {
+                Fetched::FxRate {
+                    from,
+                    to,
+                    result: request.await,
+                }
+            });
+        }
+    }
+
+    /// compare a live `from`/`to` rate with that pair's levels; each alert fires once until
+    /// the rate is back inside. only live rates are judged, so a failed fetch or the bundled
+    /// averages keep what was known and the alert doesn't fire again later
+    fn judge_fx_rate(&mut self, from: &str, to: &str, rate: f64) {
+        let settings = self.config.effective_alerts();
+        let pair = (from.to_string(), to.to_string());
+        let active: HashSet<String> = settings
+            .fx_thresholds
+            .iter()
+            .filter(|threshold| threshold.currencies().as_ref() == Some(&pair))
+            .filter_map(|threshold| threshold_breach(threshold, rate))
+            .collect();
+
+        let mut fired: Vec<String> = active.difference(&self.active_fx_alerts).cloned().collect();
+        let prefix = format!("{}/{} ", from, to);
+        self.active_fx_alerts
+            .retain(|label| !label.starts_with(&prefix));
+        self.active_fx_alerts.extend(active);
+        if fired.is_empty() {
+            return;
+        }
+        fired.sort_unstable();
+        let body = fired.join(", ");
+        notify(settings.fx.as_deref(), "nzi exchange rate", &body);
+        self.set_status(format!("{} {}", icons::glyph(Glyph::Alert), body));
+    }
+
+    /// compare the system clock with network time once per session, off the UI loop;
+    /// offline is not an error
+    pub fn check_clock_skew(&mut self) {
+        if self.config.display.clock_skew_warn_secs == 0 {
+            return;
+        }
+        self.spawn_fetch(async { Fetched::ClockSkew(measure_skew().await) });
+    }
+
+    /// a warning when the measured skew exceeds the configured threshold
+    pub fn clock_skew_warning(&self) -> Option<String> {
+        let threshold = self.config.display.clock_skew_warn_secs;
+        let skew = self.clock_skew?;
+        (threshold > 0 && skew.unsigned_abs() > threshold)
+            .then(|| format!("⚠ System clock is {}", describe_skew(skew)))
+    }
+
+    /// cached readings older than the `[weather]` refresh interval are fetched again
+    fn weather_max_age(&self) -> Duration {
+        Duration::from_secs(self.config.effective_weather().refresh_secs)
     }
 
     /// fetch weather for currently selected NZ city
@@ -561,12 +1534,19 @@ impl App {
         let city_name = city.name.to_string();
 
         // fetch weather for selected city
-        match self.weather_service.get_weather(&city_name).await {
+        let result = self
+            .weather_service
+            .get_weather(&city_name, self.weather_max_age())
+            .await;
+        self.record_source_health(DataSource::Weather, self.weather_service.last_fetch_ok());
+
+        match result {
             Ok(weather) => {
                 self.current_weather = Some(weather);
                 self.weather_error = None;
                 self.is_online = true;
                 self.set_status(format!("Weather updated for {}", city_name));
+                self.check_weather_alerts();
             }
             Err(e) => {
                 let error_message = format!("{:#}", e);
@@ -588,6 +1568,40 @@ impl App {
         }
     }
 
+    /// whether a source has responses kept in the cache directory, which outlives the
+    /// session, unlike the services' in-memory caches
+    fn source_cache_warm(&self, source: DataSource) -> bool {
+        match source {
+            DataSource::Weather => self
+                .weather_service
+                .has_kept_weather(self.get_weather_city_name()),
+            DataSource::Exchange => self.exchange_service.has_kept_rate(
+                &self.currency_converter.from_currency,
+                &self.currency_converter.to_currency,
+            ),
+        }
+    }
+
+    fn source_health_mut(&mut self, source: DataSource) -> Option<&mut SourceHealth> {
+        self.data_sources
+            .iter_mut()
+            .find(|health| health.source == source)
+    }
+
+    fn record_source_health(&mut self, source: DataSource, reachable: Option<bool>) {
+        let warm = self.source_cache_warm(source);
+        if let Some(health) = self.source_health_mut(source) {
+            health.record(reachable, warm);
+        }
+
+        if self.show_splash
+            && self.splash_until.is_none()
+            && self.data_sources.iter().all(SourceHealth::is_checked)
+        {
+            self.splash_until = Some(Instant::now() + SPLASH_HOLD);
+        }
+    }
+
     /// check if weather refresh is needed
     pub fn needs_weather_refresh(&self) -> bool {
         self.weather_refresh_pending
@@ -598,13 +1612,45 @@ impl App {
         NZ_CITIES[self.weather_city_index].name
     }
 
-    /// get current weather city code
-    pub fn get_weather_city_code(&self) -> &str {
-        NZ_CITIES[self.weather_city_index].code
-    }
-
-    pub fn city_by_code(&self, code: &str) -> Option<&City> {
-        self.config
+    /// the value of a `display.footer` placeholder, with a dash while it's unknown
+    pub fn footer_value(&self, name: &str) -> String {
+        let clock = |time: Option<&CityTime>| {
+            let format = clock_format(self.config.display.use_24_hour, false);
+            time.map_or("--:--".to_string(), |time| {
+                time.datetime.format(format).to_string()
+            })
+        };
+        match name {
+            "nz_time" => clock(self.current_city_time.as_ref()),
+            "home_time" => clock(self.home_city_time.as_ref()),
+            "rate" => {
+                let converter = &self.currency_converter;
+                let rate = converter
+                    .rate
+                    .map_or("-".to_string(), |rate| format!("{:.4}", rate));
+                format!(
+                    "{}→{} {}",
+                    converter.from_currency, converter.to_currency, rate
+                )
+            }
+            "weather_city" => self.get_weather_city_name().to_string(),
+            "temp" => self
+                .current_weather
+                .as_ref()
+                .map_or("-°C".to_string(), |weather| {
+                    format!("{}°C", weather.temp_c)
+                }),
+            _ => format!("{{{}}}", name),
+        }
+    }
+
+    /// get current weather city code
+    pub fn get_weather_city_code(&self) -> &str {
+        NZ_CITIES[self.weather_city_index].code
+    }
+
+    pub fn city_by_code(&self, code: &str) -> Option<&City> {
+        self.config
             .all_cities()
             .into_iter()
             .find(|city| city.code.eq_ignore_ascii_case(code))
@@ -648,2052 +1694,6181 @@ impl App {
             return;
         };
 
-        self.time_converter.from_city_code = anchor.code.clone();
+        // keep a user-selected from city unless it collides with the new target
+        let from_city = self
+            .city_by_code(&self.time_converter.from_city_code)
+            .filter(|city| !city.code.eq_ignore_ascii_case(&target_city.code))
+            .cloned()
+            .unwrap_or(anchor);
+
+        self.time_converter.from_city_code = from_city.code.clone();
         self.time_converter.to_city_code = target_city.code.clone();
         self.currency_converter
-            .set_pair(&anchor.currency, &target_city.currency);
+            .set_pair(&from_city.currency, &target_city.currency);
         self.update_time_conversion();
     }
 
-    fn cycle_current_target_city(&mut self) {
-        let target_codes: Vec<String> = self
-            .target_cities()
-            .iter()
-            .map(|city| city.code.clone())
-            .collect();
-        if target_codes.is_empty() {
-            return;
+    /// switch the clock style live and persist it (a staged draft follows along)
+    fn set_clock_format(&mut self, use_24_hour: bool) {
+        self.config.display.use_24_hour = use_24_hour;
+        if let Some(draft) = self.config_draft.as_mut() {
+            draft.display.use_24_hour = use_24_hour;
         }
 
-        let current_code = self.current_target_city_code();
-        let current_index = current_code
-            .as_deref()
-            .and_then(|code| {
-                target_codes
-                    .iter()
-                    .position(|entry| entry.eq_ignore_ascii_case(code))
-            })
-            .unwrap_or(0);
-        let next_index = (current_index + 1) % target_codes.len();
-        self.set_current_target_city(&target_codes[next_index]);
+        let label = if use_24_hour { "24-hour" } else { "12-hour" };
+        match self.config.save() {
+            Ok(()) => self.set_status(format!("Clock set to {}", label)),
+            Err(err) => self.set_status(format!("Clock set to {} (not saved: {})", label, err)),
+        }
     }
 
-    fn sync_currency_to_time_selection(&mut self) {
-        let anchor = self
-            .anchor_city()
-            .cloned()
-            .unwrap_or_else(|| self.config.current_city.clone());
-        let Some(target_city) = self
-            .city_by_code(&self.time_converter.to_city_code)
-            .cloned()
-        else {
-            return;
-        };
+    /// change the event list and persist it (a staged draft follows along)
+    fn update_events(&mut self, change: impl Fn(&mut Vec<EventConfig>)) {
+        change(&mut self.config.events);
+        if let Some(draft) = self.config_draft.as_mut() {
+            change(&mut draft.events);
+        }
 
-        self.currency_converter
-            .set_pair(&anchor.currency, &target_city.currency);
+        let status = match self.config.events.len() {
+            0 => "No events".to_string(),
+            1 => "1 event scheduled".to_string(),
+            count => format!("{} events scheduled", count),
+        };
+        match self.config.save() {
+            Ok(()) => self.set_status(status),
+            Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
+        }
     }
 
-    pub fn map_enabled(&self) -> bool {
-        self.config.effective_map_settings().enabled
+    /// change the pending alarms in config and draft, and persist them
+    fn update_alarms(&mut self, change: impl Fn(&mut Vec<AlarmConfig>)) -> Result<()> {
+        change(&mut self.config.alarms);
+        if let Some(draft) = self.config_draft.as_mut() {
+            change(&mut draft.alarms);
+        }
+        self.config.save()
     }
 
-    fn next_visible_focus(&self, focus: Focus) -> Focus {
-        if self.map_enabled() {
-            return focus.next();
+    /// change the checklist and persist it; an error leaves both config and draft untouched
+    fn update_todos(
+        &mut self,
+        change: impl Fn(&mut Vec<TodoItem>) -> std::result::Result<(), String>,
+    ) {
+        if let Err(message) = change(&mut self.config.todos) {
+            self.set_status(message);
+            return;
+        }
+        if let Some(draft) = self.config_draft.as_mut() {
+            let _ = change(&mut draft.todos);
         }
 
-        match focus {
-            Focus::Weather => Focus::TimeConvert,
-            Focus::TimeConvert => Focus::Currency,
-            Focus::Currency | Focus::Map => Focus::Weather,
+        let open = self.config.todos.iter().filter(|todo| !todo.done).count();
+        let status = format!("Checklist: {} of {} open", open, self.config.todos.len());
+        match self.config.save() {
+            Ok(()) => self.set_status(status),
+            Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
         }
     }
 
-    fn prev_visible_focus(&self, focus: Focus) -> Focus {
-        if self.map_enabled() {
-            return focus.prev();
+    /// whether the checklist panel has something to show
+    pub fn checklist_visible(&self) -> bool {
+        self.config.display.show_checklist && !self.config.todos.is_empty()
+    }
+
+    /// set or clear the note on the converter's destination city and persist it
+    fn set_destination_note(&mut self, note: Option<String>) {
+        let code = self.time_converter.to_city_code.clone();
+        let apply = |config: &mut Config| {
+            config
+                .notes
+                .retain(|key, _| !key.eq_ignore_ascii_case(&code));
+            if let Some(note) = &note {
+                config.notes.insert(code.clone(), note.clone());
+            }
+        };
+        apply(&mut self.config);
+        if let Some(draft) = self.config_draft.as_mut() {
+            apply(draft);
         }
 
-        match focus {
-            Focus::Weather | Focus::Map => Focus::Currency,
-            Focus::TimeConvert => Focus::Weather,
-            Focus::Currency => Focus::TimeConvert,
+        let status = match &note {
+            Some(_) => format!("Note saved for {}", code),
+            None => format!("Note cleared for {}", code),
+        };
+        match self.config.save() {
+            Ok(()) => self.set_status(status),
+            Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
         }
     }
 
-    fn up_visible_focus(&self, focus: Focus) -> Focus {
-        if self.map_enabled() {
-            return focus.up();
-        }
+    /// the soonest upcoming countdown event
+    pub fn next_event(&self) -> Option<UpcomingEvent> {
+        next_event(&self.config, Utc::now())
+    }
 
-        match focus {
-            Focus::TimeConvert | Focus::Currency | Focus::Map => Focus::Weather,
-            Focus::Weather => Focus::Weather,
-        }
+    /// alarms still to ring, soonest first
+    pub fn pending_alarms(&self) -> Vec<ScheduledAlarm> {
+        scheduled_alarms(&self.config, Utc::now())
     }
 
-    fn down_visible_focus(&self, focus: Focus) -> Focus {
-        if self.map_enabled() {
-            return focus.down();
+    /// the best upcoming windows for a call between the converter's from and to cities
+    pub fn call_windows(&self) -> Vec<CallWindow> {
+        let (Some(from), Some(to)) = (
+            self.city_by_code(&self.time_converter.from_city_code),
+            self.city_by_code(&self.time_converter.to_city_code),
+        ) else {
+            return Vec::new();
+        };
+        if from.timezone == to.timezone {
+            return Vec::new();
         }
+        let (mine, theirs) = self.config.effective_call_hours();
+        call_windows(from, to, Utc::now(), &mine, &theirs, 3)
+    }
 
-        match focus {
-            Focus::Weather | Focus::Map => Focus::TimeConvert,
-            Focus::TimeConvert | Focus::Currency => focus,
+    /// flight from the converter's from city to its to city, departing at the entered time;
+    /// returns the distance in km, the duration and the local arrival time
+    pub fn flight_estimate(&self) -> Option<(f64, ChronoDuration, DateTime<FixedOffset>)> {
+        if self.time_converter.invalid_input {
+            return None;
+        }
+        let from = self.city_by_code(&self.time_converter.from_city_code)?;
+        let to = self.city_by_code(&self.time_converter.to_city_code)?;
+        let distance = great_circle_km(city_coordinates(from)?, city_coordinates(to)?);
+        if distance < MIN_FLIGHT_KM {
+            return None;
         }
+
+        let from_timezone = parse_city_timezone(&from.timezone)?;
+        let departure_time = NaiveTime::from_hms_opt(
+            self.time_converter.input_hour,
+            self.time_converter.input_minute,
+            0,
+        )?;
+        let departure_local = from_timezone
+            .convert_datetime(&Utc::now().fixed_offset())
+            .date_naive()
+            .and_time(departure_time);
+        let departure = match from_timezone.resolve_local_datetime(&departure_local) {
+            LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => datetime,
+            LocalResult::None => return None,
+        };
+
+        let duration = flight_duration(distance);
+        let arrival = parse_city_timezone(&to.timezone)?.convert_datetime(&(departure + duration));
+        Some((distance, duration, arrival))
+    }
+
+    /// the converter's input time in the anchor and every target city except the from city,
+    /// as (code, name, hour, minute, day offset)
+    pub fn broadcast_rows(&self) -> Vec<(String, String, u32, u32, i32)> {
+        let converter = &self.time_converter;
+        let mut seen = HashSet::new();
+        std::iter::once(&self.config.current_city)
+            .chain(self.config.effective_target_cities())
+            .filter(|city| !city.code.eq_ignore_ascii_case(&converter.from_city_code))
+            .filter(|city| seen.insert(city.code.to_uppercase()))
+            .filter_map(|city| {
+                let (hour, minute, day_offset) = self.timezone_service.convert_time(
+                    &converter.from_city_code,
+                    &city.code,
+                    converter.input_hour,
+                    converter.input_minute,
+                )?;
+                Some((
+                    city.code.clone(),
+                    city.name.clone(),
+                    hour,
+                    minute,
+                    day_offset,
+                ))
+            })
+            .collect()
     }
 
-    fn left_visible_focus(&self, focus: Focus) -> Focus {
-        if self.map_enabled() {
-            return focus.left();
+    /// the converted time's date in the destination city, with the country's alpha-2 code
+    fn destination_date(&self) -> Option<(String, NaiveDate)> {
+        if self.time_converter.invalid_input {
+            return None;
         }
-
-        match focus {
-            Focus::Currency => Focus::TimeConvert,
-            Focus::Weather | Focus::TimeConvert | Focus::Map => focus,
+        let from = self.city_by_code(&self.time_converter.from_city_code)?;
+        let to = self.city_by_code(&self.time_converter.to_city_code)?;
+        let from_today = parse_city_timezone(&from.timezone)?
+            .convert_datetime(&Utc::now().fixed_offset())
+            .date_naive();
+        let alpha2 = country_alpha2(lookup_country(&to.country)?.code)?;
+        Some((
+            alpha2,
+            from_today + ChronoDuration::days(self.time_converter.day_offset.into()),
+        ))
+    }
+
+    /// a public holiday in the destination country on the converted date
+    pub fn destination_holiday(&self) -> Option<(NaiveDate, Holiday)> {
+        let (alpha2, date) = self.destination_date()?;
+        let holiday = self.holiday_service.holiday_on(&alpha2, date)?;
+        Some((date, holiday))
+    }
+
+    /// country/year holiday tables currently on screen: the converter's destination,
+    /// plus both calendar cities while the /cal popup is open
+    fn holiday_lookups(&self) -> Vec<(String, i32)> {
+        let mut lookups: Vec<(String, i32)> = self
+            .destination_date()
+            .map(|(alpha2, date)| (alpha2, date.year()))
+            .into_iter()
+            .collect();
+        if self.overlay == Some(Overlay::Calendar) {
+            for city in [&self.config.current_city, &self.config.home_city] {
+                if let Some(alpha2) = city_alpha2(city) {
+                    lookups.push((alpha2, self.calendar_month.year()));
+                }
+            }
         }
+        lookups
     }
 
-    fn right_visible_focus(&self, focus: Focus) -> Focus {
-        if self.map_enabled() {
-            return focus.right();
-        }
+    /// whether any on-screen country's holidays for the year are still unknown
+    pub fn needs_holiday_refresh(&self) -> bool {
+        self.holiday_lookups()
+            .iter()
+            .any(|(alpha2, year)| self.holiday_service.needs_fetch(alpha2, *year))
+    }
 
-        match focus {
-            Focus::TimeConvert => Focus::Currency,
-            Focus::Map => Focus::Weather,
-            Focus::Weather | Focus::Currency => focus,
+    /// look up on-screen countries' holidays; failures only post a status
+    pub fn refresh_holidays(&mut self) {
+        for (alpha2, year) in self.holiday_lookups() {
+            if !self.holiday_service.needs_fetch(&alpha2, year) {
+                continue;
+            }
+            let request = self.holiday_service.start_fetch(&alpha2, year);
+            self.spawn_fetch(async move {
+                Fetched::Holidays {
+                    alpha2,
+                    year,
+                    result: request.await,
+                }
+            });
         }
     }
 
-    fn set_focus(&mut self, focus: Focus) {
-        let focus = if self.map_enabled() || focus != Focus::Map {
-            focus
-        } else {
-            Focus::Weather
-        };
+    /// quakes are only fetched while the map, and its quake layer, are on
+    pub fn needs_quake_refresh(&self) -> bool {
+        let map = self.config.effective_map_settings();
+        map.enabled && map.quakes && self.quake_service.needs_fetch()
+    }
 
-        self.focus = focus;
-        if focus != Focus::Map {
-            self.map_context = focus;
-        }
+    pub fn refresh_quakes(&mut self) {
+        let request = self.quake_service.start_fetch();
+        self.spawn_fetch(async move { Fetched::Quakes(request.await) });
     }
 
-    /// set a status message
-    pub fn set_status(&mut self, message: String) {
-        self.status_message = Some((message, Instant::now()));
+    /// the Kp index is only fetched while the map, and its aurora layer, are on
+    pub fn needs_aurora_refresh(&self) -> bool {
+        let map = self.config.effective_map_settings();
+        map.enabled && map.aurora && self.aurora_service.needs_fetch()
     }
 
-    /// handle keyboard input
-    pub fn handle_key(&mut self, key: crossterm::event::KeyCode) {
-        use crossterm::event::KeyCode;
+    pub fn refresh_aurora(&mut self) {
+        let request = self.aurora_service.start_fetch();
+        self.spawn_fetch(async move { Fetched::Aurora(request.await) });
+    }
 
-        if self.picker.is_some() {
-            self.handle_picker_input(key);
-            return;
+    /// the latest Kp index, when the aurora layer is on
+    pub fn aurora_kp(&self) -> Option<f64> {
+        if !self.config.effective_map_settings().aurora {
+            return None;
         }
+        self.aurora_service.kp()
+    }
 
-        if self.config_editor.is_some() {
-            self.handle_config_editor_input(key);
-            return;
+    /// yesterday's figures go into the climate record once a day
+    pub fn needs_climate_refresh(&self) -> bool {
+        self.climate_recorder
+            .needs_fetch(chrono::Local::now().date_naive())
+    }
+
+    /// record the day just gone for the weather city and every configured city with coordinates
+    pub fn refresh_climate(&mut self) {
+        let mut places: Vec<(String, (f64, f64))> = Vec::new();
+        let weather_city = &NZ_CITIES[self.weather_city_index];
+        let cities = self
+            .config
+            .all_cities()
+            .into_iter()
+            .map(|city| (city.name.as_str(), city_coordinates(city)));
+        for (name, coordinates) in std::iter::once((
+            weather_city.name,
+            Some((weather_city.lat, weather_city.lon)),
+        ))
+        .chain(cities)
+        {
+            if let Some(coordinates) = coordinates
+                && !places.iter().any(|(known, _)| known == name)
+            {
+                places.push((name.to_string(), coordinates));
+            }
         }
 
-        // if help is showing, Esc closes it
-        if self.show_help {
-            if matches!(key, KeyCode::Esc) {
-                self.show_help = false;
+        let today = chrono::Local::now().date_naive();
+        let request = self.climate_recorder.start_fetch(&places);
+        self.spawn_fetch(async move {
+            Fetched::Climate {
+                today,
+                result: request.await,
             }
-            return;
+        });
+    }
+
+    /// the temperature grid is only fetched while the heat layer is shown
+    pub fn needs_heat_refresh(&self) -> bool {
+        self.config.effective_map_settings().enabled
+            && self.map_shows_heat
+            && self.heat_service.needs_fetch()
+    }
+
+    pub fn refresh_heat(&mut self) {
+        let request = self.heat_service.start_fetch();
+        self.spawn_fetch(async move { Fetched::Heat(request.await) });
+    }
+
+    /// current temperatures across NZ as (lat, lon, °C), when the heat layer is on
+    pub fn heat_points(&self) -> &[(f64, f64, f64)] {
+        if !self.map_shows_heat {
+            return &[];
         }
+        self.heat_service.points()
+    }
 
-        // if typing a command, handle that first
-        if !self.command_buffer.is_empty() {
-            self.handle_command_input(key);
-            return;
+    /// the ISS is only tracked while the map, and its ISS layer, are on
+    pub fn needs_iss_refresh(&self) -> bool {
+        let map = self.config.effective_map_settings();
+        map.enabled
+            && map.iss
+            && (self.iss_service.needs_fetch() || self.iss_service.needs_orbit_fetch())
+    }
+
+    pub fn refresh_iss(&mut self) {
+        if self.iss_service.needs_fetch() {
+            let request = self.iss_service.start_fetch();
+            self.spawn_fetch(async move { Fetched::Iss(request.await) });
         }
+        if self.iss_service.needs_orbit_fetch() {
+            let request = self.iss_service.start_orbit_fetch();
+            self.spawn_fetch(async move { Fetched::IssOrbit(request.await) });
+        }
+    }
 
-        match self.input_mode {
-            InputMode::Normal => self.handle_normal_input(key),
-            InputMode::EditingCurrency => self.handle_currency_input(key),
-            InputMode::EditingTime => self.handle_time_input(key),
+    /// keep the next visible ISS pass over the weather city current; cheap until it goes by
+    pub fn plan_iss_pass(&mut self) {
+        if !self.config.effective_map_settings().iss {
+            return;
         }
+        let city = &NZ_CITIES[self.weather_city_index];
+        self.iss_service.plan_pass((city.lat, city.lon), Utc::now());
     }
 
-    fn handle_config_editor_input(&mut self, key: crossterm::event::KeyCode) {
-        use crossterm::event::KeyCode;
+    /// run a request off the UI loop; `receive_fetched` picks up what it sends back
+    fn spawn_fetch(&self, request: impl Future<Output = Fetched> + Send + 'static) {
+        let sender = self.fetched_tx.clone();
+        tokio::spawn(async move {
+            // the receiver only goes away with the app
+            let _ = sender.send(request.await);
+        });
+    }
 
-        match key {
-            KeyCode::Esc => {
-                self.close_config_editor();
-            }
-            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
-                self.cycle_config_tab(true);
-            }
-            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
-                self.cycle_config_tab(false);
-            }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if let Some(editor) = &mut self.config_editor {
-                    editor.selected = editor.selected.saturating_sub(1);
+    /// apply the background fetches that have finished since the last pass
+    pub fn receive_fetched(&mut self) {
+        while let Ok(fetched) = self.fetched_rx.try_recv() {
+            match fetched {
+                Fetched::Holidays {
+                    alpha2,
+                    year,
+                    result,
+                } => {
+                    if let Err(err) = self.holiday_service.finish_fetch(&alpha2, year, result) {
+                        self.set_status(format!("Holiday lookup failed for {}: {:#}", alpha2, err));
+                    }
                 }
-            }
-            KeyCode::Down | KeyCode::Char('j') => {
-                let row_count = self.config_editor_row_count();
-                if row_count == 0 {
-                    return;
+                Fetched::Quakes(result) => {
+                    if let Err(err) = self.quake_service.finish_fetch(result) {
+                        self.set_status(format!("Quake lookup failed: {:#}", err));
+                    }
                 }
-                if let Some(editor) = &mut self.config_editor {
-                    editor.selected = (editor.selected + 1).min(row_count.saturating_sub(1));
+                Fetched::Aurora(result) => {
+                    if let Err(err) = self.aurora_service.finish_fetch(result) {
+                        self.set_status(format!("Aurora lookup failed: {:#}", err));
+                    }
                 }
-            }
-            KeyCode::Enter | KeyCode::Char(' ') => {
-                if let Err(err) = self.activate_config_editor_row() {
-                    self.set_status(err.to_string());
+                Fetched::Climate { today, result } => {
+                    if let Err(err) = self.climate_recorder.finish_fetch(today, result) {
+                        self.set_status(format!("Climate record not updated: {:#}", err));
+                    }
                 }
-            }
-            KeyCode::Char('a') => {
-                if let Err(err) = self.add_config_editor_item() {
-                    self.set_status(err.to_string());
+                Fetched::Heat(result) => {
+                    if let Err(err) = self.heat_service.finish_fetch(result) {
+                        self.set_status(format!("Temperature grid lookup failed: {:#}", err));
+                    }
                 }
-            }
-            KeyCode::Char('K') => {
-                if let Err(err) = self.move_config_editor_item(-1) {
-                    self.set_status(err.to_string());
+                Fetched::Iss(result) => {
+                    // a failure is left quiet: the trail stays up and the next try backs off
+                    let _ = self.iss_service.finish_fetch(result);
                 }
-            }
-            KeyCode::Char('J') => {
-                if let Err(err) = self.move_config_editor_item(1) {
-                    self.set_status(err.to_string());
+                Fetched::IssOrbit(result) => {
+                    // without elements there is just no pass time; the trail is unaffected
+                    let _ = self.iss_service.finish_orbit_fetch(result);
                 }
-            }
-            KeyCode::Backspace | KeyCode::Delete | KeyCode::Char('x') => {
-                if let Err(err) = self.remove_config_editor_item() {
-                    self.set_status(err.to_string());
+                Fetched::FxRate { from, to, result } => {
+                    // a failed fetch is left quiet; the pair is tried again next refresh
+                    if let Ok(rate) = self.exchange_service.finish_rate_fetch(&from, &to, result) {
+                        self.judge_fx_rate(&from, &to, rate);
+                    }
+                }
+                Fetched::ClockSkew(result) => {
+                    if let Ok(skew) = result {
+                        self.clock_skew = Some(skew);
+                        if let Some(warning) = self.clock_skew_warning() {
+                            self.set_status(warning);
+                        }
+                    }
                 }
             }
-            _ => {}
         }
     }
 
-    fn handle_normal_input(&mut self, key: crossterm::event::KeyCode) {
-        use crossterm::event::KeyCode;
-
-        match key {
-            KeyCode::Char('q') => self.running = false,
+    /// the ISS's recent track, oldest first, when the layer is on
+    pub fn iss_trail(&self) -> &[(f64, f64)] {
+        if !self.config.effective_map_settings().iss {
+            return &[];
+        }
+        self.iss_service.trail()
+    }
 
-            // arrow keys move between panels
-            KeyCode::Up => self.set_focus(self.up_visible_focus(self.focus)),
-            KeyCode::Down => self.set_focus(self.down_visible_focus(self.focus)),
-            KeyCode::Left => self.set_focus(self.left_visible_focus(self.focus)),
-            KeyCode::Right => self.set_focus(self.right_visible_focus(self.focus)),
-            KeyCode::Tab => self.set_focus(self.next_visible_focus(self.focus)),
-            KeyCode::BackTab => self.set_focus(self.prev_visible_focus(self.focus)),
+    /// the next visible ISS pass over the weather city, when the layer is on
+    pub fn iss_next_pass(&self) -> Option<&Pass> {
+        if !self.config.effective_map_settings().iss {
+            return None;
+        }
+        self.iss_service.next_pass()
+    }
 
-            KeyCode::Enter => self.enter_edit_mode(),
-            KeyCode::Char('e') => self.enter_edit_mode(),
-
-            // hjkl for panel navigation (vim-style, same as arrows)
-            KeyCode::Char('h') => self.set_focus(self.left_visible_focus(self.focus)),
-            KeyCode::Char('l') => self.set_focus(self.right_visible_focus(self.focus)),
-            KeyCode::Char('j') => self.set_focus(self.down_visible_focus(self.focus)),
-            KeyCode::Char('k') => self.set_focus(self.up_visible_focus(self.focus)),
-
-            // swap/toggle shortcut
-            KeyCode::Char('s') => self.handle_swap(),
-
-            // now shortcut for time converter
-            KeyCode::Char('n') if self.focus == Focus::TimeConvert => {
-                self.time_converter.set_to_now();
-                self.update_time_conversion();
-            }
+    /// "ISS over Wellington Tue 19:42, 54° up" for the world map title
+    pub fn iss_pass_readout(&self) -> Option<String> {
+        let pass = self.iss_next_pass()?;
+        let clock = clock_format(self.config.display.use_24_hour, false);
+        let when = if pass.start <= Utc::now() {
+            "now".to_string()
+        } else {
+            pass.start
+                .with_timezone(&chrono_tz::Pacific::Auckland)
+                .format(&format!("%a {}", clock))
+                .to_string()
+        };
+        Some(format!(
+            "ISS over {} {}, {:.0}° up",
+            self.get_weather_city_name(),
+            when,
+            pass.peak_elevation
+        ))
+    }
 
-            // 'r' - refresh weather or reset time converter
-            KeyCode::Char('r') => match self.focus {
-                Focus::Weather => {
-                    self.weather_refresh_pending = true;
-                    self.set_status("Refreshing weather...".to_string());
-                }
-                Focus::TimeConvert => {
-                    self.time_converter.reset();
-                    self.update_time_conversion();
-                }
-                _ => {}
-            },
+    /// this week's felt quakes, largest first
+    pub fn recent_quakes(&self) -> Vec<&Quake> {
+        if !self.config.effective_map_settings().quakes {
+            return Vec::new();
+        }
+        recent_quakes(self.quake_service.quakes(), Utc::now())
+    }
 
-            // numeric input for currency when focused
-            KeyCode::Char(c) if c.is_ascii_digit() && self.focus == Focus::Currency => {
-                self.input_mode = InputMode::EditingCurrency;
-                self.currency_converter.clear_input();
-                self.currency_converter.handle_input(c);
-            }
+    /// today's date in the anchor city
+    fn anchor_today(&self) -> NaiveDate {
+        self.current_city_time
+            .as_ref()
+            .map(|ct| ct.datetime.date_naive())
+            .unwrap_or_else(|| Utc::now().date_naive())
+    }
 
-            // numeric input for time converter when focused
-            KeyCode::Char(c) if c.is_ascii_digit() && self.focus == Focus::TimeConvert => {
-                self.time_converter.handle_digit(c);
-                self.update_time_conversion();
-            }
+    /// the home city's date when the anchor city is on `date` at its current time of day
+    fn home_date_for(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let anchor = parse_city_timezone(&self.config.current_city.timezone)?;
+        let time_of_day = self.current_city_time.as_ref()?.datetime.time();
+        let instant = match anchor.resolve_local_datetime(&date.and_time(time_of_day)) {
+            LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => datetime,
+            LocalResult::None => return None,
+        };
+        Some(
+            parse_city_timezone(&self.config.home_city.timezone)?
+                .convert_datetime(&instant)
+                .date_naive(),
+        )
+    }
 
-            // backspace for time converter when typing
-            KeyCode::Backspace
-                if self.focus == Focus::TimeConvert && self.time_converter.is_typing() =>
-            {
-                self.time_converter.handle_backspace();
-                self.update_time_conversion();
-            }
+    /// anchor-city dates of configured events
+    fn event_dates(&self) -> Vec<(NaiveDate, String)> {
+        let anchor = parse_city_timezone(&self.config.current_city.timezone);
+        self.config
+            .events
+            .iter()
+            .filter_map(|event| {
+                let city = self.city_by_code(&event.city_code)?;
+                let instant = event_instant(event, city)?;
+                let date = anchor
+                    .as_ref()?
+                    .convert_datetime(&instant.fixed_offset())
+                    .date_naive();
+                Some((date, event.title.clone()))
+            })
+            .collect()
+    }
 
-            // escape clears time input buffer
-            KeyCode::Esc if self.focus == Focus::TimeConvert && self.time_converter.is_typing() => {
-                self.time_converter.clear_input_buffer();
-            }
+    /// the /cal month as weeks of dual-date cells
+    pub fn calendar_weeks(&self) -> Vec<Vec<CalendarCell>> {
+        let today = self.anchor_today();
+        let anchor_alpha2 = city_alpha2(&self.config.current_city);
+        let home_alpha2 = city_alpha2(&self.config.home_city);
+        let event_dates = self.event_dates();
+        month_weeks(self.calendar_month, self.config.display.week_starts)
+            .into_iter()
+            .map(|week| {
+                week.into_iter()
+                    .map(|anchor| {
+                        let home = self.home_date_for(anchor);
+                        let holiday = anchor_alpha2.as_ref().is_some_and(|alpha2| {
+                            self.holiday_service.holiday_on(alpha2, anchor).is_some()
+                        }) || home.zip(home_alpha2.as_ref()).is_some_and(
+                            |(home, alpha2)| {
+                                self.holiday_service.holiday_on(alpha2, home).is_some()
+                            },
+                        );
+                        CalendarCell {
+                            anchor,
+                            home,
+                            in_month: anchor.month() == self.calendar_month.month(),
+                            today: anchor == today,
+                            event: event_dates.iter().any(|(date, _)| *date == anchor),
+                            holiday,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
 
-            // 'c' cycles currency pair when on currency panel
-            KeyCode::Char('c') if self.focus == Focus::Currency => {
-                self.cycle_current_target_city();
-            }
+    /// events and holidays in the /cal month, by anchor date
+    pub fn calendar_notes(&self) -> Vec<(NaiveDate, String)> {
+        let in_month = |date: &NaiveDate| {
+            date.year() == self.calendar_month.year() && date.month() == self.calendar_month.month()
+        };
+        let mut notes: Vec<(NaiveDate, String)> = self
+            .event_dates()
+            .into_iter()
+            .filter(|(date, _)| in_month(date))
+            .map(|(date, title)| {
+                (
+                    date,
+                    format!("{} {}", icons::glyph(Glyph::Countdown), title),
+                )
+            })
+            .collect();
 
-            // space - context-dependent action
-            KeyCode::Char(' ') => {
-                match self.focus {
-                    Focus::Weather => {
-                        // cycle NZ cities
-                        self.weather_city_index = (self.weather_city_index + 1) % NZ_CITIES.len();
-                        self.current_weather = None;
-                        self.weather_error = None;
-                        self.weather_refresh_pending = true;
-                    }
-                    Focus::TimeConvert => {
-                        self.cycle_current_target_city();
-                    }
-                    Focus::Currency => {
-                        self.cycle_current_target_city();
-                    }
-                    _ => {}
+        let anchor_alpha2 = city_alpha2(&self.config.current_city);
+        let home_alpha2 = city_alpha2(&self.config.home_city);
+        let days = self
+            .calendar_month
+            .iter_days()
+            .take_while(|date| in_month(date));
+        for anchor in days {
+            let anchor_holiday = anchor_alpha2
+                .as_ref()
+                .and_then(|alpha2| self.holiday_service.holiday_on(alpha2, anchor));
+            let home_holiday = self
+                .home_date_for(anchor)
+                .zip(home_alpha2.as_ref())
+                .and_then(|(home, alpha2)| self.holiday_service.holiday_on(alpha2, home));
+            for (holiday, city) in [
+                (anchor_holiday, &self.config.current_city),
+                (home_holiday, &self.config.home_city),
+            ] {
+                let Some(holiday) = holiday else {
+                    continue;
+                };
+                let note = format!(
+                    "{} {} ({})",
+                    icons::glyph(Glyph::Holiday),
+                    holiday.name,
+                    city.code
+                );
+                if !notes.iter().any(|(_, existing)| *existing == note) {
+                    notes.push((anchor, note));
                 }
             }
+        }
+        notes.sort();
+        notes
+    }
 
-            // '?' toggles help overlay
-            KeyCode::Char('?') => {
-                self.show_help = !self.show_help;
-            }
+    /// the next NZ public holiday within a week, by NZ date, with its day label
+    pub fn upcoming_nz_holiday(&self) -> Option<(String, Holiday)> {
+        let today = Utc::now()
+            .with_timezone(&chrono_tz::Pacific::Auckland)
+            .date_naive();
+        let region = NzRegion::for_city(&self.config.current_city.name);
+        upcoming_nz_holiday(today, region)
+            .map(|holiday| (holiday_day_label(holiday.date, today), holiday))
+    }
 
-            // 'R' (shift+r) reloads config from disk
-            KeyCode::Char('R') => {
-                if let Err(e) = self.reload_config() {
-                    self.set_status(format!("Failed to reload config: {}", e));
-                }
-            }
+    /// cue alerts that became active since the last fetch; each fires once until it clears
+    fn check_weather_alerts(&mut self) {
+        let Some(weather) = &self.current_weather else {
+            return;
+        };
+        let settings = self.config.effective_alerts();
+        let city_name = self.get_weather_city_name().to_string();
+        let active: HashSet<WeatherAlert> = weather
+            .active_alerts(settings.severe_wind_kmph)
+            .into_iter()
+            .collect();
 
-            // 'E' (shift+e) opens config in editor
-            KeyCode::Char('E') => {
-                self.edit_config_requested = true;
+        let mut fired = Vec::new();
+        for alert in active.difference(&self.active_weather_alerts) {
+            let spec = match alert {
+                WeatherAlert::RainSoon => &settings.rain,
+                WeatherAlert::SevereWind => &settings.wind,
+            };
+            let body = format!("{} in {}", alert.label(), city_name);
+            if notify(spec.as_deref(), "nzi weather", &body) {
+                fired.push(alert.label());
             }
+        }
+        self.active_weather_alerts = active;
 
-            // '/' starts command input
-            KeyCode::Char('/') => {
-                self.command_buffer.push('/');
-            }
+        if !fired.is_empty() {
+            fired.sort_unstable();
+            self.set_status(format!(
+                "{} {} in {}",
+                icons::glyph(Glyph::Alert),
+                fired.join(", "),
+                city_name
+            ));
+        }
+    }
 
-            _ => {}
+    /// post a one-off reminder when the next event is close
+    fn check_event_reminder(&mut self) {
+        if let Some(event) = self.next_event()
+            && event.is_due_for_reminder()
+            && self.reminded_events.insert(event.key())
+        {
+            let message = format!(
+                "{} in {} ({} time)",
+                event.title,
+                format_countdown(event.remaining),
+                event.city_code
+            );
+            notify(
+                self.config.effective_alerts().reminder.as_deref(),
+                "nzi reminder",
+                &message,
+            );
+            self.set_status(format!("{} {}", icons::glyph(Glyph::Alarm), message));
         }
     }
 
-    fn handle_command_input(&mut self, key: crossterm::event::KeyCode) {
-        use crossterm::event::KeyCode;
+    /// drop events once their time has passed so the list and calendar stay current
+    fn prune_past_events(&mut self) {
+        let now = Utc::now();
+        let passed: Vec<EventConfig> = self
+            .config
+            .events
+            .iter()
+            .filter(|event| event_has_passed(event, &self.config, now))
+            .cloned()
+            .collect();
+        if passed.is_empty() {
+            return;
+        }
 
-        match key {
-            KeyCode::Esc => {
-                self.command_buffer.clear();
-            }
-            KeyCode::Enter => {
-                self.execute_command();
-                self.command_buffer.clear();
-            }
-            KeyCode::Backspace => {
-                self.command_buffer.pop();
-            }
-            KeyCode::Char(c) => {
-                self.command_buffer.push(c);
-            }
-            _ => {}
+        self.config.events.retain(|event| !passed.contains(event));
+        if let Some(draft) = self.config_draft.as_mut() {
+            draft.events.retain(|event| !passed.contains(event));
+        }
+        if let Err(err) = self.config.save() {
+            self.set_status(format!("Cleared past events (not saved: {})", err));
         }
     }
 
-    fn handle_picker_input(&mut self, key: crossterm::event::KeyCode) {
-        use crossterm::event::KeyCode;
+    /// ring alarms whose time has come and drop them; ones that passed while closed are only reported
+    fn check_alarms(&mut self) {
+        let due: Vec<ScheduledAlarm> = self
+            .pending_alarms()
+            .into_iter()
+            .filter(ScheduledAlarm::is_due)
+            .collect();
+        if due.is_empty() {
+            return;
+        }
 
-        match key {
-            KeyCode::Esc => {
-                self.picker = None;
-            }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if let Some(picker) = &mut self.picker {
-                    picker.selected = picker.selected.saturating_sub(1);
-                }
-            }
-            KeyCode::Down | KeyCode::Char('j') => {
-                let option_count = self.picker_options().len();
-                if option_count == 0 {
-                    return;
-                }
-                if let Some(picker) = &mut self.picker {
-                    picker.selected = (picker.selected + 1).min(option_count.saturating_sub(1));
-                }
-            }
-            KeyCode::Enter => {
-                if let Err(err) = self.submit_picker_selection() {
-                    self.set_status(err.to_string());
-                }
-            }
-            KeyCode::Backspace => {
-                if let Some(picker) = &mut self.picker {
-                    picker.query.pop();
-                    picker.selected = 0;
-                }
-            }
-            KeyCode::Char(c) if !c.is_control() => {
-                if let Some(picker) = &mut self.picker {
-                    picker.query.push(c);
-                    picker.selected = 0;
-                }
+        let clock = clock_format(self.config.display.use_24_hour, false);
+        let mut rung = Vec::new();
+        let mut missed = Vec::new();
+        for alarm in &due {
+            let label = format!("{} in {}", alarm.local.format(clock), alarm.city_name);
+            if alarm.is_missed() {
+                missed.push(label);
+            } else {
+                let backend = self.config.effective_alerts().alarm;
+                notify(
+                    Some(backend.as_deref().unwrap_or("bell")),
+                    "nzi alarm",
+                    &label,
+                );
+                rung.push(label);
             }
-            _ => {}
         }
-    }
 
-    fn submit_picker_selection(&mut self) -> Result<()> {
-        let Some(choice) = self.current_picker_choice() else {
-            return Ok(());
+        let status = if rung.is_empty() {
+            format!("Missed alarm: {}", missed.join(", "))
+        } else {
+            format!("{} {}", icons::glyph(Glyph::Alarm), rung.join(", "))
         };
+        let fired: Vec<AlarmConfig> = due.into_iter().map(|alarm| alarm.alarm).collect();
+        match self.update_alarms(|alarms| alarms.retain(|alarm| !fired.contains(alarm))) {
+            Ok(()) => self.set_status(status),
+            Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
+        }
+    }
 
-        let Some(picker) = self.picker.clone() else {
-            return Ok(());
-        };
+    /// anchor plus target cities, excluding the current to city
+    fn convert_from_candidates(&self) -> Vec<String> {
+        let mut codes = vec![self.config.effective_anchor_city_code()];
+        codes.extend(self.config.effective_target_city_codes());
 
-        match (picker.kind, choice) {
-            (PickerKind::Country, PickerChoice::Country { code, name }) => {
-                self.picker = None;
-                self.apply_config_command(CommandAction::SetFocalCountry { code, name })
-            }
-            (PickerKind::PlaceCurrency, PickerChoice::Currency { code, name }) => {
-                self.picker = None;
-                self.apply_config_command(CommandAction::AddPlaceCurrency { code, name })
-            }
-            (PickerKind::MapMode, PickerChoice::MapEnabled { enabled, .. }) => {
-                self.picker = None;
-                if self.config_editor.is_some() {
-                    self.apply_config_command(CommandAction::SetMapEnabled { enabled })
-                } else {
-                    self.apply_immediate_config_command(CommandAction::SetMapEnabled { enabled })
-                }
-            }
-            (PickerKind::AnchorCity, PickerChoice::City { code, .. }) => {
-                self.picker = None;
-                self.set_anchor_city_in_draft(&code)?;
-                self.open_picker(PickerKind::TargetCity);
-                self.set_status(
-                    "Draft updated: anchor city set. Pick one or more target cities".to_string(),
-                );
-                Ok(())
-            }
-            (PickerKind::TargetCity, PickerChoice::City { code, .. }) => {
-                self.picker = None;
-                self.add_target_city_to_draft(&code)
+        let mut candidates: Vec<String> = Vec::new();
+        for code in codes {
+            if code.eq_ignore_ascii_case(&self.time_converter.to_city_code)
+                || candidates
+                    .iter()
+                    .any(|entry| entry.eq_ignore_ascii_case(&code))
+                || self.city_by_code(&code).is_none()
+            {
+                continue;
             }
-            _ => Ok(()),
+            candidates.push(code);
         }
+        candidates
     }
 
-    fn open_picker(&mut self, kind: PickerKind) {
-        self.show_help = false;
-        self.command_buffer.clear();
-        self.picker = Some(PickerState {
-            query: String::new(),
-            selected: 0,
-            kind,
-        });
+    fn cycle_from_city(&mut self) {
+        let candidates = self.convert_from_candidates();
+        if candidates.is_empty() {
+            return;
+        }
+
+        let next_index = candidates
+            .iter()
+            .position(|code| code.eq_ignore_ascii_case(&self.time_converter.from_city_code))
+            .map(|index| (index + 1) % candidates.len())
+            .unwrap_or(0);
+        self.time_converter.from_city_code = candidates[next_index].clone();
+        self.session_stats.record_city_cycle();
+        self.sync_currency_to_time_selection();
+        self.update_times();
+        self.update_time_conversion();
+        self.set_status(format!(
+            "Converting from {}",
+            self.get_time_convert_from_name()
+        ));
     }
 
-    fn open_config_editor(&mut self) {
-        self.begin_config_draft();
-        if self.config_editor.is_none() {
-            self.config_editor = Some(ConfigEditorState {
-                tab: ConfigTab::Places,
-                selected: 0,
-            });
-        }
-        self.show_help = false;
-        self.command_buffer.clear();
+    fn cycle_current_target_city(&mut self) {
+        self.step_current_target_city(true);
     }
 
-    fn close_config_editor(&mut self) {
-        self.config_editor = None;
-        self.set_status(
-            "Config editor closed. Draft kept open; use /apply or /discard".to_string(),
-        );
-    }
+    /// move the destination to the next or previous target city
+    fn step_current_target_city(&mut self, forward: bool) {
+        let target_codes: Vec<String> = self
+            .target_cities()
+            .iter()
+            .map(|city| city.code.clone())
+            .collect();
+        if target_codes.is_empty() {
+            return;
+        }
 
-    fn execute_command(&mut self) {
-        let raw_command = self.command_buffer.trim();
+        let current_code = self.current_target_city_code();
+        let current_index = current_code
+            .as_deref()
+            .and_then(|code| {
+                target_codes
+                    .iter()
+                    .position(|entry| entry.eq_ignore_ascii_case(code))
+            })
+            .unwrap_or(0);
+        let count = target_codes.len();
+        let next_index = if forward {
+            (current_index + 1) % count
+        } else {
+            (current_index + count - 1) % count
+        };
+        self.set_current_target_city(&target_codes[next_index]);
+        self.session_stats.record_city_cycle();
+    }
 
-        let action = match parse_command(raw_command) {
-            Ok(action) => action,
-            Err(message) => {
-                self.set_status(message);
-                return;
-            }
+    fn sync_currency_to_time_selection(&mut self) {
+        let from_city = self
+            .city_by_code(&self.time_converter.from_city_code)
+            .or_else(|| self.anchor_city())
+            .cloned()
+            .unwrap_or_else(|| self.config.current_city.clone());
+        let Some(target_city) = self
+            .city_by_code(&self.time_converter.to_city_code)
+            .cloned()
+        else {
+            return;
         };
 
-        match action {
-            CommandAction::EnterConfigDraft => {
-                self.open_config_editor();
-            }
-            CommandAction::ShowHelp => {
-                self.show_help = true;
-            }
-            CommandAction::EditConfig => {
-                self.edit_config_requested = true;
-            }
-            CommandAction::Quit => {
-                self.running = false;
-            }
-            CommandAction::Reload => {
-                if let Err(e) = self.reload_config_state() {
-                    self.set_status(format!("Failed to reload config: {}", e));
-                }
-            }
-            CommandAction::ApplyDraft => {
-                if let Err(e) = self.apply_config_draft() {
-                    self.set_status(format!("Failed to apply draft: {}", e));
-                }
-            }
-            CommandAction::DiscardDraft => {
-                self.discard_config_draft();
-            }
-            CommandAction::ResetDraft => {
-                let editing_config = self.config_editor.is_some();
-                self.reset_config_draft();
-                if !editing_config && let Err(e) = self.apply_config_draft() {
-                    self.set_status(format!("Failed to apply reset draft: {}", e));
-                }
-            }
-            CommandAction::RestoreDraft => {
-                if let Err(e) = self.restore_config_draft() {
-                    self.set_status(format!("Failed to restore draft: {}", e));
-                }
-            }
-            CommandAction::Refresh => {
-                self.weather_refresh_pending = true;
-                self.set_status("Refreshing...".to_string());
-            }
-            CommandAction::OpenCountryPicker => {
-                self.open_picker(PickerKind::Country);
-            }
-            CommandAction::OpenPlaceCurrencyPicker => {
-                self.open_picker(PickerKind::PlaceCurrency);
-            }
-            CommandAction::OpenMapPicker => {
-                self.open_picker(PickerKind::MapMode);
-            }
-            other => {
-                let result = if matches!(other, CommandAction::SetMapEnabled { .. })
-                    && self.config_editor.is_none()
-                {
-                    self.apply_immediate_config_command(other)
-                } else {
-                    self.apply_config_command(other)
-                };
-                if let Err(err) = result {
-                    self.set_status(err.to_string());
-                }
-            }
-        }
+        self.currency_converter
+            .set_pair(&from_city.currency, &target_city.currency);
     }
 
-    /// check if currency rate refresh is needed
-    pub fn needs_currency_refresh(&self) -> bool {
-        self.currency_converter.needs_rate_refresh()
+    pub fn map_enabled(&self) -> bool {
+        self.config.effective_map_settings().enabled
     }
 
-    /// handle swap action for focused panel
-    fn handle_swap(&mut self) {
-        match self.focus {
-            Focus::Currency => {
-                // swap_currencies already handles rate inversion
-                self.currency_converter.swap_currencies();
-            }
-            Focus::TimeConvert => {
-                self.time_converter.swap_cities();
-                self.sync_currency_to_time_selection();
-                self.update_time_conversion();
-            }
-            Focus::Weather => {
-                self.weather_expanded = !self.weather_expanded;
-            }
-            _ => {}
-        }
+    pub fn panel_shown(&self, focus: Focus) -> bool {
+        panel_shown(&self.config, focus)
     }
 
-    fn handle_currency_input(&mut self, key: crossterm::event::KeyCode) {
-        use crossterm::event::KeyCode;
+    /// the first of `candidates` that's shown, else `focus` unchanged
+    fn shown_or_stay(&self, candidates: &[Focus], focus: Focus) -> Focus {
+        candidates
+            .iter()
+            .copied()
+            .find(|&candidate| self.panel_shown(candidate))
+            .unwrap_or(focus)
+    }
 
-        match key {
-            KeyCode::Esc | KeyCode::Enter => {
-                self.input_mode = InputMode::Normal;
-            }
-            KeyCode::Char(c) if c.is_ascii_digit() || c == '.' => {
-                self.currency_converter.handle_input(c);
-            }
-            KeyCode::Backspace => {
-                self.currency_converter.handle_backspace();
-            }
-            _ => {}
-        }
+    fn next_visible_focus(&self, focus: Focus) -> Focus {
+        let order = [
+            focus.next(),
+            focus.next().next(),
+            focus.next().next().next(),
+        ];
+        self.shown_or_stay(&order, focus)
     }
 
-    fn handle_time_input(&mut self, key: crossterm::event::KeyCode) {
-        use crossterm::event::KeyCode;
+    fn prev_visible_focus(&self, focus: Focus) -> Focus {
+        let order = [
+            focus.prev(),
+            focus.prev().prev(),
+            focus.prev().prev().prev(),
+        ];
+        self.shown_or_stay(&order, focus)
+    }
 
-        match key {
-            KeyCode::Esc => {
-                self.input_mode = InputMode::Normal;
-                if let Some(ct) = &self.current_city_time {
-                    self.time_converter.input_hour = ct.datetime.hour();
-                    self.time_converter.input_minute = ct.datetime.minute();
-                } else {
-                    self.time_converter.set_to_now();
-                }
-                self.time_converter.clear_input_buffer();
-                self.update_time_conversion();
-            }
-            KeyCode::Enter => {
-                self.input_mode = InputMode::Normal;
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.time_converter.increment_hour();
-                self.update_time_conversion();
-            }
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.time_converter.decrement_hour();
-                self.update_time_conversion();
-            }
-            KeyCode::Char('l') | KeyCode::Right => {
-                self.time_converter.increment_minute();
-                self.update_time_conversion();
-            }
-            KeyCode::Char('h') | KeyCode::Left => {
-                self.time_converter.decrement_minute();
-                self.update_time_conversion();
-            }
-            _ => {}
+    fn up_visible_focus(&self, focus: Focus) -> Focus {
+        if let Some(above) = self.stacked_neighbour(focus, false) {
+            return above;
+        }
+        if self.map_enabled() {
+            return self.shown_or_stay(&[focus.up()], focus);
         }
-    }
 
-    fn enter_edit_mode(&mut self) {
-        match self.focus {
-            Focus::Currency => {
-                if self.input_mode == InputMode::EditingCurrency {
-                    self.input_mode = InputMode::Normal;
-                    self.currency_converter.editing = false;
-                } else {
-                    self.input_mode = InputMode::EditingCurrency;
-                    self.currency_converter.editing = true;
-                }
-            }
-            Focus::TimeConvert => {
-                if self.input_mode == InputMode::EditingTime {
-                    self.input_mode = InputMode::Normal;
-                } else {
-                    self.input_mode = InputMode::EditingTime;
-                }
+        match focus {
+            Focus::TimeConvert | Focus::Currency | Focus::Map => {
+                self.shown_or_stay(&[Focus::Weather], focus)
             }
-            _ => {}
+            Focus::Weather => Focus::Weather,
         }
     }
 
-    /// check if edit config was requested
-    pub fn needs_edit_config(&self) -> bool {
-        self.edit_config_requested
+    fn down_visible_focus(&self, focus: Focus) -> Focus {
+        if let Some(below) = self.stacked_neighbour(focus, true) {
+            return below;
+        }
+        if self.map_enabled() {
+            // with the time panel off, currency spans the bottom strip
+            let below = focus.down();
+            return self.shown_or_stay(&[below, below.right()], focus);
+        }
+
+        match focus {
+            Focus::Weather | Focus::Map => {
+                self.shown_or_stay(&[Focus::TimeConvert, Focus::Currency], focus)
+            }
+            Focus::TimeConvert | Focus::Currency => focus,
+        }
     }
 
-    /// clear edit config request
-    pub fn clear_edit_request(&mut self) {
-        self.edit_config_requested = false;
+    /// when the panels were last drawn in one column (a narrow terminal), the panel above
+    /// or below `focus`, or `focus` itself at either end
+    fn stacked_neighbour(&self, focus: Focus, below: bool) -> Option<Focus> {
+        let mut panels = self.click_areas.panels.clone();
+        let (first, _) = *panels.first()?;
+        if panels.len() < 2
+            || panels
+                .iter()
+                .any(|(area, _)| area.x != first.x || area.width != first.width)
+        {
+            return None;
+        }
+        panels.sort_by_key(|(area, _)| area.y);
+        let index = panels.iter().position(|(_, shown)| *shown == focus)?;
+        let next = if below {
+            panels.get(index + 1)
+        } else {
+            index.checked_sub(1).and_then(|above| panels.get(above))
+        };
+        Some(next.map_or(focus, |(_, next)| *next))
     }
 
-    /// reload config from disk and refresh dependent state
-    pub fn reload_config(&mut self) -> Result<()> {
-        self.config = Config::load()?;
-        self.sync_runtime_to_config();
+    fn left_visible_focus(&self, focus: Focus) -> Focus {
+        if self.map_enabled() {
+            let left = focus.left();
+            return self.shown_or_stay(&[left, left.left()], focus);
+        }
 
-        self.set_status("Config reloaded".to_string());
-        Ok(())
+        match focus {
+            Focus::Currency => self.shown_or_stay(&[Focus::TimeConvert], focus),
+            Focus::Weather | Focus::TimeConvert | Focus::Map => focus,
+        }
     }
 
-    fn reload_config_state(&mut self) -> Result<()> {
-        if self.config_draft.is_some() {
-            self.config_draft = Some(Config::load()?);
-            self.picker = None;
-            if let Some(editor) = self.config_editor.as_mut() {
-                editor.tab = ConfigTab::Places;
-                editor.selected = 0;
-            }
-            self.clamp_config_editor_selection();
-            self.set_status("Saved preferences reloaded from config.toml".to_string());
-            Ok(())
-        } else {
-            self.reload_config()
+    fn right_visible_focus(&self, focus: Focus) -> Focus {
+        if self.map_enabled() {
+            // with the weather panel off, the time and currency panels fill the right side
+            let right = focus.right();
+            return self.shown_or_stay(&[right, right.down(), right.down().right()], focus);
+        }
+
+        match focus {
+            Focus::TimeConvert => self.shown_or_stay(&[Focus::Currency], focus),
+            Focus::Map => self.shown_or_stay(
+                &[Focus::Weather, Focus::TimeConvert, Focus::Currency],
+                focus,
+            ),
+            Focus::Weather | Focus::Currency => focus,
         }
     }
 
-    /// get editor command from config
-    pub fn get_editor(&self) -> String {
-        self.config.display.get_editor()
+    pub fn start_tour(&mut self) {
+        self.show_help = false;
+        self.overlay = None;
+        self.tour_step = Some(0);
     }
 
-    /// check if it's time for a tick
-    pub fn should_tick(&self) -> bool {
-        self.last_tick.elapsed() >= self.tick_rate
+    /// the step being shown, with its position and the number of steps
+    pub fn current_tour_step(&self) -> Option<(usize, usize, &'static TourStep)> {
+        let steps = tour_steps(self.map_enabled());
+        let index = self.tour_step?;
+        Some((index, steps.len(), *steps.get(index)?))
     }
 
-    /// reset the tick timer
-    pub fn reset_tick(&mut self) {
-        self.last_tick = Instant::now();
-    }
-
-    /// get the from city name for time conversion
-    pub fn get_time_convert_from_name(&self) -> &str {
-        self.city_by_code(&self.time_converter.from_city_code)
-            .map(|city| city.name.as_str())
-            .unwrap_or(&self.time_converter.from_city_code)
-    }
-
-    /// get the to city name for time conversion
-    pub fn get_time_convert_to_name(&self) -> &str {
-        self.city_by_code(&self.time_converter.to_city_code)
-            .map(|city| city.name.as_str())
-            .unwrap_or(&self.time_converter.to_city_code)
-    }
-
-    pub fn active_map_focus(&self) -> Focus {
-        if self.focus == Focus::Map {
-            Focus::Map
+    /// move through the tour, focusing each highlighted panel so its keys can be tried
+    fn step_tour(&mut self, forward: bool) {
+        let Some(index) = self.tour_step else {
+            return;
+        };
+        let steps = tour_steps(self.map_enabled());
+        let next = if forward {
+            index + 1
         } else {
-            self.focus
+            index.saturating_sub(1)
+        };
+        let Some(step) = steps.get(next) else {
+            self.tour_step = None;
+            self.set_status("Tour finished - /tour to replay it".to_string());
+            return;
+        };
+        self.tour_step = Some(next);
+        if let TourTarget::Panel(focus) = step.target {
+            self.set_focus(focus);
         }
     }
 
-    pub fn has_config_draft(&self) -> bool {
-        self.config_draft.is_some()
+    fn set_focus(&mut self, focus: Focus) {
+        let focus = first_shown(&self.config, focus);
+
+        self.focus = focus;
+        if focus != Focus::Map {
+            self.map_context = focus;
+        }
     }
 
-    pub fn picker_title(&self) -> Option<String> {
-        let picker = self.picker.as_ref()?;
-        let title = match &picker.kind {
-            PickerKind::Country => "Set focal city via country".to_string(),
-            PickerKind::PlaceCurrency => "Add currency via country".to_string(),
-            PickerKind::MapMode => "Map visibility".to_string(),
-            PickerKind::AnchorCity => "Pick anchor city".to_string(),
-            PickerKind::TargetCity => "Add target city".to_string(),
+    /// show the next or previous `[tabs]` page on the right and remember it
+    fn cycle_tab(&mut self, forward: bool) {
+        let Some(tabs) = self.config.tabs.as_ref() else {
+            return;
         };
-        Some(title)
+        let count = tabs.pages.len();
+        if count < 2 {
+            return;
+        }
+        self.select_tab(if forward {
+            (tabs.selected + 1) % count
+        } else {
+            (tabs.selected + count - 1) % count
+        });
     }
 
-    pub fn picker_prompt(&self) -> Option<&'static str> {
-        let picker = self.picker.as_ref()?;
-        let prompt = match picker.kind {
-            PickerKind::Country => "Pick a country and resolve to its representative focal city",
-            PickerKind::PlaceCurrency => {
-                "Pick a currency and resolve it through country to a representative city"
-            }
-            PickerKind::MapMode => "Choose whether the map is shown",
-            PickerKind::AnchorCity => "Search by city code, name, or country",
-            PickerKind::TargetCity => "Search by city code, name, or country",
+    /// show one `[tabs]` page on the right and remember it
+    fn select_tab(&mut self, selected: usize) {
+        let Some(tabs) = self.config.tabs.as_mut() else {
+            return;
         };
-        Some(prompt)
+        if selected >= tabs.pages.len() || selected == tabs.selected {
+            return;
+        }
+        tabs.selected = selected;
+        if let Some(tabs) = self
+            .config_draft
+            .as_mut()
+            .and_then(|draft| draft.tabs.as_mut())
+        {
+            tabs.selected = selected;
+        }
+        self.set_focus(self.focus);
+        if let Err(err) = self.config.save() {
+            self.set_status(format!("Tab not saved: {}", err));
+        }
     }
 
-    pub fn picker_options(&self) -> Vec<PickerOption> {
-        self.picker_choices()
-            .into_iter()
-            .map(|choice| match choice {
-                PickerChoice::Country { code, name } => PickerOption {
-                    label: name,
-                    detail: code,
-                },
-                PickerChoice::Currency { code, name } => PickerOption {
-                    label: name,
-                    detail: code,
-                },
-                PickerChoice::MapEnabled { enabled: _, label } => PickerOption {
-                    detail: "map visibility".to_string(),
-                    label,
-                },
-                PickerChoice::City {
-                    code,
-                    name,
-                    country,
-                } => PickerOption {
-                    label: format!("{} ({})", name, code),
-                    detail: country,
-                },
-            })
-            .collect()
+    /// set a status message
+    pub fn set_status(&mut self, message: String) {
+        self.status_message = Some((message, Instant::now()));
     }
 
-    fn current_picker_choice(&self) -> Option<PickerChoice> {
-        let picker = self.picker.as_ref()?;
-        let choices = self.picker_choices();
-        let index = picker.selected.min(choices.len().saturating_sub(1));
-        choices.get(index).cloned()
-    }
+    /// clicks focus a panel, press a title hint, switch tab, tick a checklist row, pick
+    /// a picker option or pick the nearest city on the map; the wheel steps the hovered
+    /// panel's city or zooms the map
+    pub fn handle_mouse(&mut self, event: crossterm::event::MouseEvent) {
+        use crossterm::event::{MouseButton, MouseEventKind};
 
-    fn picker_choices(&self) -> Vec<PickerChoice> {
-        let Some(picker) = self.picker.as_ref() else {
-            return Vec::new();
+        let (column, row) = (event.column, event.row);
+        let forward = match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => None,
+            MouseEventKind::ScrollDown => Some(true),
+            MouseEventKind::ScrollUp => Some(false),
+            _ => return,
         };
+        if self.wake() {
+            return;
+        }
 
-        match &picker.kind {
-            PickerKind::Country => search_countries(&picker.query)
-                .into_iter()
-                .map(|country| PickerChoice::Country {
-                    code: country.code.to_string(),
-                    name: country.name.to_string(),
-                })
-                .collect(),
-            PickerKind::PlaceCurrency => search_currencies(&picker.query)
-                .into_iter()
-                .map(|currency| PickerChoice::Currency {
-                    code: currency.code.to_string(),
-                    name: currency.name.to_string(),
-                })
-                .collect(),
-            PickerKind::MapMode => {
-                let query = picker.query.trim().to_lowercase();
-                vec![
-                    PickerChoice::MapEnabled {
-                        enabled: true,
-                        label: "Show map".to_string(),
-                    },
-                    PickerChoice::MapEnabled {
-                        enabled: false,
-                        label: "Hide map".to_string(),
-                    },
-                ]
-                .into_iter()
-                .filter(|choice| match choice {
-                    PickerChoice::MapEnabled { label, .. } => {
-                        query.is_empty() || label.to_lowercase().contains(&query)
+        if self.picker.is_some() {
+            match forward {
+                Some(down) => self.handle_picker_input(if down {
+                    crossterm::event::KeyCode::Down
+                } else {
+                    crossterm::event::KeyCode::Up
+                }),
+                None => {
+                    let Some((_, index)) = ui::hit(&self.click_areas.picker, column, row) else {
+                        return;
+                    };
+                    if let Some(picker) = &mut self.picker {
+                        picker.selected = index;
                     }
-                    _ => false,
-                })
-                .collect()
+                    if let Err(err) = self.submit_picker_selection() {
+                        self.set_status(err.to_string());
+                    }
+                }
             }
-            PickerKind::AnchorCity => self.search_config_cities(&picker.query),
-            PickerKind::TargetCity => self.search_config_cities(&picker.query),
+            return;
+        }
+        if self.show_splash
+            || self.show_help
+            || self.overlay.is_some()
+            || self.planner.is_some()
+            || self.config_editor.is_some()
+            || self.tour_step.is_some()
+            || self.input_mode != InputMode::Normal
+        {
+            return;
         }
-    }
-
-    fn search_config_cities(&self, query: &str) -> Vec<PickerChoice> {
-        let trimmed = query.trim().to_lowercase();
-        let mut seen_codes = std::collections::HashSet::new();
-        let mut choices = Vec::new();
 
-        let mut config_cities: Vec<&City> = self.active_config().representative_cities();
-        config_cities.sort_by(|left, right| left.name.cmp(&right.name));
-        for city in config_cities {
-            let matches_query = trimmed.is_empty()
-                || city.code.to_lowercase().contains(&trimmed)
-                || city.name.to_lowercase().contains(&trimmed)
-                || city.country.to_lowercase().contains(&trimmed)
-                || city.currency.to_lowercase().contains(&trimmed);
-            if matches_query && seen_codes.insert(city.code.clone()) {
-                choices.push(PickerChoice::City {
-                    code: city.code.clone(),
-                    name: city.name.clone(),
-                    country: city.country.clone(),
-                });
+        if let Some(forward) = forward {
+            match self.click_areas.panel_at(column, row) {
+                Some(Focus::Weather) => self.step_weather_city(forward),
+                Some(Focus::TimeConvert | Focus::Currency) => {
+                    self.step_current_target_city(forward)
+                }
+                // scrolling down zooms out, like most maps
+                Some(Focus::Map) if forward => self.shown_map_view().zoom_out(),
+                Some(Focus::Map) => self.shown_map_view().zoom_in(),
+                None => {}
             }
+            return;
         }
 
-        for city in search_representative_cities(query) {
-            if seen_codes.insert(city.city_code.to_string()) {
-                choices.push(PickerChoice::City {
-                    code: city.city_code.to_string(),
-                    name: city.city_name.to_string(),
-                    country: city.country_name.to_string(),
-                });
+        if let Some((_, index)) = ui::hit(&self.click_areas.tabs, column, row) {
+            self.select_tab(index);
+        } else if let Some((_, (focus, key))) = ui::hit(&self.click_areas.hints, column, row) {
+            self.set_focus(focus);
+            // the hint names the default key; press whatever it's bound to now
+            let key = match KeyAction::ALL
+                .into_iter()
+                .find(|action| action.default_key() == crossterm::event::KeyCode::Char(key))
+            {
+                Some(action) => self.keymap.key(action),
+                None => Some(crossterm::event::KeyCode::Char(key)),
+            };
+            if let Some(key) = key {
+                self.handle_normal_input(key);
+            }
+        } else if let Some((_, index)) = ui::hit(&self.click_areas.checklist, column, row) {
+            self.update_todos(|todos| {
+                let todo = todos
+                    .get_mut(index)
+                    .ok_or_else(|| format!("no checklist item {}", index + 1))?;
+                todo.done = !todo.done;
+                Ok(())
+            });
+        } else if let Some(area) = self.click_areas.map.filter(|area| {
+            self.map_enabled() && area.contains(ratatui::layout::Position::new(column, row))
+        }) {
+            if !self.click_map(area, column, row) {
+                self.set_focus(Focus::Map);
             }
+        } else if let Some(focus) = self.click_areas.panel_at(column, row) {
+            self.set_focus(focus);
         }
-
-        choices
     }
 
-    fn active_config(&self) -> &Config {
-        self.config_draft.as_ref().unwrap_or(&self.config)
-    }
+    /// a left click on the map picks the nearest city: the weather city on the NZ map,
+    /// the time destination on the world map; false when no city is near
+    fn click_map(&mut self, area: Rect, column: u16, row: u16) -> bool {
+        let measuring = self.map_measure.is_some();
+        let Some((code, name, point)) = self.clicked_place(area, column, row, measuring) else {
+            return false;
+        };
 
-    fn active_config_mut(&mut self) -> &mut Config {
-        if let Some(draft) = self.config_draft.as_mut() {
-            draft
+        if let Some(picked) = &mut self.map_measure {
+            // a third click starts over from the new place
+            if picked.len() == 2 {
+                picked.clear();
+            }
+            picked.push((code, point));
+            if let Some(label) = self.map_measurement() {
+                self.set_status(label);
+            }
+        } else if self.active_map_focus() == Focus::Weather {
+            let Some(index) = NZ_CITIES.iter().position(|city| city.code == code) else {
+                return true;
+            };
+            if index != self.weather_city_index {
+                self.select_weather_city(index);
+            }
+            self.set_status(format!("Weather: {}", name));
         } else {
-            &mut self.config
+            self.set_current_target_city(&code);
+            self.set_status(format!("Destination: {}", name));
         }
+        true
+    }
+
+    /// the marked place nearest a click on the map panel: an NZ city on the NZ map, a target city (or the anchor, with `with_anchor`) on the world map
+    fn clicked_place(
+        &self,
+        area: Rect,
+        column: u16,
+        row: u16,
+        with_anchor: bool,
+    ) -> Option<MapPlace> {
+        let (places, x, y): (Vec<MapPlace>, _, _) = if self.active_map_focus() == Focus::Weather {
+            (
+                NZ_CITIES
+                    .iter()
+                    .map(|city| {
+                        (
+                            city.code.to_string(),
+                            city.name.to_string(),
+                            (city.lat, city.lon),
+                        )
+                    })
+                    .collect(),
+                [NZ_LON_MIN, NZ_LON_MAX],
+                [NZ_LAT_MIN, NZ_LAT_MAX],
+            )
+        } else {
+            let anchor = with_anchor.then_some(&self.config.current_city);
+            (
+                anchor
+                    .into_iter()
+                    .chain(self.target_cities())
+                    .filter_map(|city| {
+                        Some((
+                            city.code.clone(),
+                            city.name.clone(),
+                            city_coordinates(city)?,
+                        ))
+                    })
+                    .collect(),
+                self.config.effective_map_settings().projection.lon_bounds(),
+                [WORLD_LAT_MIN, WORLD_LAT_MAX],
+            )
+        };
+        let (x_bounds, y_bounds) = self.shown_map_view_ref().bounds(x, y);
+        let (lon, lat) = canvas_point(area, column, row, x_bounds, y_bounds)?;
+        let index = nearest_point(places.iter().map(|(_, _, point)| *point), lat, lon)?;
+        places.into_iter().nth(index)
     }
 
-    pub fn config_editor_state(&self) -> Option<&ConfigEditorState> {
-        self.config_editor.as_ref()
+    /// the x and y bounds of the whole map on show, before any zoom
+    fn full_map_bounds(&self) -> ([f64; 2], [f64; 2]) {
+        if self.active_map_focus() == Focus::Weather {
+            ([NZ_LON_MIN, NZ_LON_MAX], [NZ_LAT_MIN, NZ_LAT_MAX])
+        } else {
+            (
+                self.config.effective_map_settings().projection.lon_bounds(),
+                [WORLD_LAT_MIN, WORLD_LAT_MAX],
+            )
+        }
     }
 
-    pub fn config_editor_config(&self) -> Option<&Config> {
-        self.config_editor.as_ref()?;
-        Some(self.active_config())
+    /// turn the crosshair on at the middle of the visible map, or off
+    fn toggle_map_crosshair(&mut self) {
+        self.map_crosshair = match self.map_crosshair {
+            Some(_) => None,
+            None => {
+                let (x, y) = self.full_map_bounds();
+                let (x, y) = self.shown_map_view_ref().bounds(x, y);
+                Some(((y[0] + y[1]) / 2.0, (x[0] + x[1]) / 2.0))
+            }
+        };
     }
 
-    fn cycle_config_tab(&mut self, forward: bool) {
-        if let Some(editor) = &mut self.config_editor {
-            editor.tab = if forward {
-                editor.tab.next()
-            } else {
-                editor.tab.prev()
-            };
-            editor.selected = 0;
+    /// nudge the crosshair a fortieth of the visible map, staying in view
+    fn move_map_crosshair(&mut self, dx: f64, dy: f64) {
+        let (x, y) = self.full_map_bounds();
+        let (x, y) = self.shown_map_view_ref().bounds(x, y);
+        if let Some((lat, lon)) = &mut self.map_crosshair {
+            *lat = (*lat + dy * (y[1] - y[0]) / 40.0).clamp(y[0], y[1]);
+            *lon = (*lon + dx * (x[1] - x[0]) / 40.0).clamp(x[0], x[1]);
         }
     }
 
-    fn config_editor_row_count(&self) -> usize {
-        let Some(editor) = self.config_editor.as_ref() else {
-            return 0;
+    /// the crosshair's readout with the nearest known place, e.g. "-41.29, 174.78 · near WLG (2 km)";
+    /// None when it is off
+    pub fn map_crosshair_readout(&self) -> Option<String> {
+        let (lat, lon) = self.map_crosshair?;
+        let places: Vec<(String, (f64, f64))> = if self.active_map_focus() == Focus::Weather {
+            NZ_CITIES
+                .iter()
+                .map(|city| (city.code.to_string(), (city.lat, city.lon)))
+                .chain(
+                    self.config
+                        .effective_map_settings()
+                        .markers
+                        .iter()
+                        .map(|town| (town.code.clone(), (town.lat, town.lon))),
+                )
+                .collect()
+        } else {
+            CITY_CODE_COORDS
+                .iter()
+                .map(|city| (city.code.to_string(), (city.lat, city.lon)))
+                .collect()
         };
-
-        match editor.tab {
-            ConfigTab::Places => 2 + self.active_config().effective_target_city_codes().len(),
-            ConfigTab::Actions => 6,
+        // the pacific projection runs past 180°E; read out the usual longitude
+        let lon = WorldProjection::Greenwich.wrap(lon);
+        let mut readout = format!("{:.2}, {:.2}", lat, lon);
+        if let Some(index) = nearest_point(places.iter().map(|(_, point)| *point), lat, lon) {
+            let (code, point) = &places[index];
+            readout.push_str(&format!(
+                " · near {} ({:.0} km)",
+                code,
+                great_circle_km((lat, lon), *point)
+            ));
         }
+        Some(readout)
+    }
+
+    /// the measuring tool's readout, e.g. "WLG→LDN 18,790 km · ~22h 40m flight";
+    /// None when it is off
+    pub fn map_measurement(&self) -> Option<String> {
+        let picked = self.map_measure.as_ref()?;
+        Some(match picked.as_slice() {
+            [] => "measure: click two places".to_string(),
+            [(code, _)] => format!("{}→ click another place", code),
+            [(from, a), (to, b), ..] => {
+                let distance = great_circle_km(*a, *b);
+                let mut label = format!("{}→{} {}", from, to, format_distance_km(distance));
+                if distance >= MIN_FLIGHT_KM {
+                    label.push_str(&format!(
+                        " · ~{} flight",
+                        format_flight_duration(flight_duration(distance))
+                    ));
+                }
+                label
+            }
+        })
     }
 
-    fn clamp_config_editor_selection(&mut self) {
-        let row_count = self.config_editor_row_count();
-        if let Some(editor) = &mut self.config_editor {
-            editor.selected = if row_count == 0 {
-                0
-            } else {
-                editor.selected.min(row_count - 1)
-            };
+    /// a key press with its modifiers: Ctrl+arrows resize the splits on the main screen,
+    /// everything else goes to `handle_key`
+    pub fn handle_key_event(&mut self, event: crossterm::event::KeyEvent) {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        if self.wake() {
+            return;
         }
+
+        if event.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(
+                event.code,
+                KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down
+            )
+            && !self.show_splash
+            && !self.show_help
+            && self.overlay.is_none()
+            && self.picker.is_none()
+            && self.planner.is_none()
+            && self.config_editor.is_none()
+            && self.input_mode == InputMode::Normal
+        {
+            self.resize_split(event.code);
+            return;
+        }
+        self.handle_key(event.code);
     }
 
-    fn activate_config_editor_row(&mut self) -> Result<()> {
-        let Some(editor) = self.config_editor.as_ref() else {
-            return Ok(());
-        };
+    /// move the map/info split (left, right) or the weather/utilities split (up, down) of
+    /// the weather view in use, and save it to `[layout]`
+    fn resize_split(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
 
-        match editor.tab {
-            ConfigTab::Places => self.activate_places_editor_row(editor.selected),
-            ConfigTab::Actions => self.activate_actions_editor_row(editor.selected),
+        let expanded = self.weather_expanded;
+        let layout = self.config.layout.get_or_insert_default();
+        let (map, bottom) = if expanded {
+            (&mut layout.map_percent, &mut layout.bottom_height)
+        } else {
+            (
+                &mut layout.compact_map_percent,
+                &mut layout.compact_bottom_height,
+            )
+        };
+        match key {
+            KeyCode::Left => *map = map.saturating_sub(SPLIT_PERCENT_STEP),
+            KeyCode::Right => *map += SPLIT_PERCENT_STEP,
+            KeyCode::Up => *bottom += 1,
+            KeyCode::Down => *bottom = bottom.saturating_sub(1),
+            _ => return,
         }
-    }
+        *map = (*map).clamp(*LAYOUT_PERCENT_RANGE.start(), *LAYOUT_PERCENT_RANGE.end());
+        *bottom = (*bottom).clamp(*LAYOUT_HEIGHT_RANGE.start(), *LAYOUT_HEIGHT_RANGE.end());
+        let status = format!("Map {}% wide, time and currency {} rows", map, bottom);
 
-    fn activate_places_editor_row(&mut self, selected: usize) -> Result<()> {
-        let list_len = self.active_config().effective_target_city_codes().len();
-        match selected {
-            0 => {
-                self.open_picker(PickerKind::AnchorCity);
-                Ok(())
-            }
-            index if index == 1 + list_len => {
-                self.open_picker(PickerKind::TargetCity);
-                Ok(())
-            }
-            _ => Ok(()),
+        let layout = *layout;
+        if let Some(draft) = self.config_draft.as_mut() {
+            draft.layout = Some(layout);
+        }
+        match self.config.save() {
+            Ok(()) => self.set_status(status),
+            Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
         }
     }
 
-    fn activate_actions_editor_row(&mut self, selected: usize) -> Result<()> {
-        match selected {
-            0 => self.apply_config_draft(),
-            1 => {
-                self.discard_config_draft();
-                Ok(())
-            }
-            2 => {
-                self.reset_config_draft();
-                Ok(())
-            }
-            3 => self.reload_config_state(),
-            4 => self.restore_config_draft(),
-            5 => {
-                let enabled = !self.active_config().effective_map_settings().enabled;
-                self.apply_config_command(CommandAction::SetMapEnabled { enabled })
-            }
-            _ => Ok(()),
+    /// handle keyboard input
+    pub fn handle_key(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        // any key leaves the ambient view and does nothing else
+        if self.wake() {
+            return;
         }
-    }
 
-    fn add_config_editor_item(&mut self) -> Result<()> {
-        let Some(editor) = self.config_editor.as_ref() else {
-            return Ok(());
-        };
+        // any key skips the startup splash
+        if self.show_splash {
+            self.show_splash = false;
+            return;
+        }
 
-        match editor.tab {
-            ConfigTab::Places => {
-                self.open_picker(PickerKind::TargetCity);
-                Ok(())
-            }
-            ConfigTab::Actions => Ok(()),
+        if self.picker.is_some() {
+            self.handle_picker_input(key);
+            return;
         }
-    }
 
-    fn remove_config_editor_item(&mut self) -> Result<()> {
-        let Some(editor) = self.config_editor.as_ref() else {
-            return Ok(());
-        };
+        if self.planner.is_some() {
+            self.handle_planner_input(key);
+            return;
+        }
 
-        match editor.tab {
-            ConfigTab::Places => {
-                if editor.selected == 0 {
-                    return Ok(());
-                }
+        if self.config_editor.is_some() {
+            self.handle_config_editor_input(key);
+            return;
+        }
 
-                let codes = self.active_config().effective_target_city_codes();
-                let index = editor.selected - 1;
-                if let Some(code) = codes.get(index) {
-                    self.remove_target_city_from_draft(code)?;
+        // informational overlays close on Esc; the history table scrolls
+        if let Some(overlay) = self.overlay {
+            match key {
+                KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                KeyCode::Char('j') | KeyCode::Down if overlay == Overlay::History => {
+                    self.history_scroll =
+                        (self.history_scroll + 1).min(self.history.len().saturating_sub(1));
                 }
-                Ok(())
+                KeyCode::Char('k') | KeyCode::Up if overlay == Overlay::History => {
+                    self.history_scroll = self.history_scroll.saturating_sub(1);
+                }
+                KeyCode::Char('h') | KeyCode::Left if overlay == Overlay::Calendar => {
+                    self.calendar_month = shift_month(self.calendar_month, -1);
+                }
+                KeyCode::Char('l') | KeyCode::Right if overlay == Overlay::Calendar => {
+                    self.calendar_month = shift_month(self.calendar_month, 1);
+                }
+                _ => {}
             }
-            ConfigTab::Actions => Ok(()),
+            return;
         }
-    }
 
-    fn move_config_editor_item(&mut self, direction: isize) -> Result<()> {
-        let Some(editor) = self.config_editor.as_ref() else {
-            return Ok(());
-        };
+        // if help is showing, Esc closes it
+        if self.show_help {
+            if matches!(key, KeyCode::Esc) {
+                self.show_help = false;
+            }
+            return;
+        }
 
-        match editor.tab {
-            ConfigTab::Places => {
-                if editor.selected == 0 {
-                    return Ok(());
+        // the tour keeps its own keys and passes the rest through to be tried
+        if self.tour_step.is_some()
+            && self.command_buffer.is_empty()
+            && self.input_mode == InputMode::Normal
+        {
+            match key {
+                KeyCode::Char('>') => return self.step_tour(true),
+                KeyCode::Char('<') => return self.step_tour(false),
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.tour_step = None;
+                    return;
                 }
-                let index = editor.selected - 1;
-                self.reorder_target_city_in_draft(index, direction)
+                _ => {}
             }
-            ConfigTab::Actions => Ok(()),
         }
-    }
-
-    fn set_anchor_city_in_draft(&mut self, code: &str) -> Result<()> {
-        let city = self
-            .active_config()
-            .all_cities()
-            .into_iter()
-            .find(|city| city.code.eq_ignore_ascii_case(code))
-            .cloned()
-            .or_else(|| {
-                representative_city_by_city_code(code).map(|city| City {
-                    name: city.city_name.to_string(),
-                    code: city.city_code.to_string(),
-                    country: city.country_name.to_string(),
-                    timezone: city.timezone.to_string(),
-                    currency: city.currency_code.to_string(),
-                })
-            })
-            .ok_or_else(|| anyhow!("unknown city: {}", code))?;
-        let city_code = city.code.clone();
-
-        self.ensure_city_in_active_catalogue(&city);
 
-        let target = self.active_config_mut();
-        let time = target.time.get_or_insert_with(TimeConfig::default);
-        time.anchor_city_code = Some(city_code.clone());
-        time.city_codes.clear();
-        time.target_city_codes
-            .retain(|entry| !entry.eq_ignore_ascii_case(&city_code));
-        self.clamp_config_editor_selection();
-        self.set_status(format!(
-            "Draft updated: anchor city set to {}. Use /apply to save",
-            city_code
-        ));
-        Ok(())
-    }
+        // if typing a command, handle that first
+        if !self.command_buffer.is_empty() {
+            self.handle_command_input(key);
+            return;
+        }
 
-    fn add_target_city_to_draft(&mut self, code: &str) -> Result<()> {
-        let city = self
-            .active_config()
-            .all_cities()
-            .into_iter()
-            .find(|city| city.code.eq_ignore_ascii_case(code))
-            .cloned()
-            .or_else(|| {
-                representative_city_by_city_code(code).map(|city| City {
-                    name: city.city_name.to_string(),
-                    code: city.city_code.to_string(),
-                    country: city.country_name.to_string(),
-                    timezone: city.timezone.to_string(),
-                    currency: city.currency_code.to_string(),
-                })
-            })
-            .ok_or_else(|| anyhow!("unknown city: {}", code))?;
-        let city_code = city.code.clone();
+        if let KeyCode::Char(c) = key
+            && let Some(digit) = c.to_digit(10)
+            && self.accepts_count_digit(digit)
+        {
+            let count = self.pending_count.unwrap_or(0) * 10 + digit;
+            self.pending_count = Some(count.min(MAX_COUNT));
+            return;
+        }
 
-        let anchor_code = self.active_config().effective_anchor_city_code();
-        if city_code.eq_ignore_ascii_case(&anchor_code) {
-            self.set_status(format!("{} is already the anchor city", city_code));
-            return Ok(());
+        let count = self.pending_count.take().unwrap_or(1);
+        if count > 1 && self.repeats_with_count(key) {
+            // a counted chord repeats its action, not the second key
+            if let Some(prefix) = self.pending_chord.take() {
+                if let KeyCode::Char(c) = key
+                    && let Some(action) = resolve_chord(prefix, c)
+                {
+                    for _ in 0..count {
+                        self.run_chord_action(action);
+                    }
+                }
+                return;
+            }
+            for _ in 0..count {
+                self.dispatch_mode_key(key);
+            }
+            return;
         }
 
-        self.ensure_city_in_active_catalogue(&city);
+        self.dispatch_mode_key(key);
 
-        let target = self.active_config_mut();
-        let time = target.time.get_or_insert_with(TimeConfig::default);
-        time.city_codes.clear();
-        if time
-            .target_city_codes
-            .iter()
-            .any(|entry| entry.eq_ignore_ascii_case(&city_code))
-        {
-            self.set_status(format!("{} is already in the target list", city_code));
-            return Ok(());
+        // keep the count across a chord prefix so "3cp" works
+        if count > 1 && self.pending_chord.is_some() {
+            self.pending_count = Some(count);
         }
-        time.target_city_codes.push(city_code.clone());
-        self.clamp_config_editor_selection();
-        self.set_status(format!(
-            "Draft updated: added {} to target cities. Use /apply to save",
-            city_code
-        ));
-        Ok(())
     }
 
-    fn ensure_city_in_active_catalogue(&mut self, city: &City) {
-        let target = self.active_config_mut();
-        ensure_city_in_config_catalogue(target, city);
+    fn dispatch_mode_key(&mut self, key: crossterm::event::KeyCode) {
+        match self.input_mode {
+            InputMode::Normal => self.handle_normal_input(key),
+            InputMode::EditingCurrency => self.handle_currency_input(key),
+            InputMode::EditingTime => self.handle_time_input(key),
+            InputMode::ScrubbingTime => self.handle_scrub_input(key),
+        }
     }
 
-    fn remove_target_city_from_draft(&mut self, code: &str) -> Result<()> {
-        let target = self.active_config_mut();
-        let time = target.time.get_or_insert_with(TimeConfig::default);
-        time.city_codes.clear();
-        let original_len = time.target_city_codes.len();
-        time.target_city_codes
-            .retain(|entry| !entry.eq_ignore_ascii_case(code));
-        if time.target_city_codes.len() < original_len {
-            self.clamp_config_editor_selection();
-            self.set_status(format!(
-                "Draft updated: removed {} from target cities. Use /apply to save",
-                code
-            ));
+    /// digits build a count wherever they are not already direct entry;
+    /// a leading zero is never a count
+    fn accepts_count_digit(&self, digit: u32) -> bool {
+        if digit == 0 && self.pending_count.is_none() {
+            return false;
+        }
+        match self.input_mode {
+            InputMode::EditingTime | InputMode::ScrubbingTime => true,
+            InputMode::EditingCurrency => false,
+            InputMode::Normal => {
+                self.pending_chord.is_none()
+                    && !matches!(
+                        self.focus,
+                        Focus::Currency | Focus::TimeConvert | Focus::Map
+                    )
+            }
         }
-        Ok(())
     }
 
-    fn reorder_target_city_in_draft(&mut self, index: usize, direction: isize) -> Result<()> {
-        let moved_code = {
-            let target = self.active_config_mut();
-            let time = target.time.get_or_insert_with(TimeConfig::default);
-            time.city_codes.clear();
+    /// keys that step something and so make sense repeated
+    fn repeats_with_count(&self, key: crossterm::event::KeyCode) -> bool {
+        use crossterm::event::KeyCode;
 
-            if index >= time.target_city_codes.len() {
-                return Ok(());
-            }
+        match self.input_mode {
+            InputMode::EditingTime => matches!(
+                key,
+                KeyCode::Char('h' | 'j' | 'k' | 'l')
+                    | KeyCode::Up
+                    | KeyCode::Down
+                    | KeyCode::Left
+                    | KeyCode::Right
+            ),
+            InputMode::ScrubbingTime => matches!(
+                key,
+                KeyCode::Char('h' | 'l') | KeyCode::Left | KeyCode::Right
+            ),
+            InputMode::EditingCurrency => false,
+            InputMode::Normal => match self.pending_chord {
+                Some(prefix) => matches!(
+                    key,
+                    KeyCode::Char(c) if resolve_chord(prefix, c)
+                        .is_some_and(|action| !matches!(action, ChordAction::Focus(_)))
+                ),
+                None => {
+                    // a remapped cycling key counts like the default it stands for
+                    let key = self.keymap.translate(key);
+                    matches!(key, KeyCode::Char(' '))
+                        || (key == KeyCode::Char('f') && self.focus == Focus::TimeConvert)
+                        || (matches!(key, KeyCode::Char('+' | '=' | '-'))
+                            && self.focus == Focus::Map)
+                }
+            },
+        }
+    }
 
-            let Some(next_index) = index.checked_add_signed(direction) else {
-                return Ok(());
-            };
-            if next_index >= time.target_city_codes.len() {
-                return Ok(());
-            }
+    fn handle_planner_input(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
 
-            time.target_city_codes.swap(index, next_index);
-            time.target_city_codes[next_index].clone()
+        let Some(planner) = &mut self.planner else {
+            return;
         };
 
-        if let Some(editor) = self.config_editor.as_mut() {
-            editor.selected = (index.checked_add_signed(direction).unwrap_or(index)) + 1;
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.planner = None;
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.step_planner(ChronoDuration::minutes(30));
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.step_planner(ChronoDuration::minutes(-30));
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.step_planner(ChronoDuration::hours(1));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.step_planner(ChronoDuration::hours(-1));
+            }
+            KeyCode::Char('b') => {
+                if let Some(best) = planner.best {
+                    planner.time = best.anchor_time;
+                }
+            }
+            KeyCode::Char('n') => {
+                self.open_planner();
+            }
+            _ => {}
         }
-
-        self.set_status(format!(
-            "Draft updated: moved {} in target cities. Use /apply to save",
-            moved_code
-        ));
-        Ok(())
     }
 
-    fn apply_config_command(&mut self, action: CommandAction) -> Result<()> {
-        let editing_draft = self.config_draft.is_some();
+    /// move the candidate slot, carrying past midnight into the next or previous day;
+    /// the best slot is looked up again for a new day
+    fn step_planner(&mut self, step: ChronoDuration) {
+        let Some(planner) = &mut self.planner else {
+            return;
+        };
+        let moved = planner.date.and_time(planner.time) + step;
+        let new_day = moved.date() != planner.date;
+        planner.date = moved.date();
+        planner.time = moved.time();
+        if !new_day {
+            return;
+        }
+
+        let anchor = self
+            .anchor_city()
+            .cloned()
+            .unwrap_or_else(|| self.config.current_city.clone());
+        let settings = self.config.effective_planner_settings();
+        let best = best_slot(&anchor, &self.planner_cities(), moved.date(), &settings);
+        if let Some(planner) = &mut self.planner {
+            planner.best = best;
+        }
+    }
+
+    /// anchor city plus target cities, in display order
+    fn planner_cities(&self) -> Vec<&City> {
+        let mut cities: Vec<&City> = self.anchor_city().into_iter().collect();
+        for city in self.target_cities() {
+            if !cities
+                .iter()
+                .any(|entry| entry.code.eq_ignore_ascii_case(&city.code))
+            {
+                cities.push(city);
+            }
+        }
+        cities
+    }
+
+    fn open_planner(&mut self) {
+        let anchor = self
+            .anchor_city()
+            .cloned()
+            .unwrap_or_else(|| self.config.current_city.clone());
+        let Some(anchor_time) = CityTime::from_city(&anchor) else {
+            self.set_status(format!("Invalid timezone for {}", anchor.name));
+            return;
+        };
+
+        // start on the current half hour
+        let now = anchor_time.datetime;
+        let date = now.date_naive();
+        let time = NaiveTime::from_hms_opt(now.hour(), now.minute() / 30 * 30, 0)
+            .unwrap_or(NaiveTime::MIN);
+        let settings = self.config.effective_planner_settings();
+        let best = best_slot(&anchor, &self.planner_cities(), date, &settings);
+
+        self.planner = Some(PlannerState { date, time, best });
+    }
+
+    /// local time in every planner city for the candidate slot
+    pub fn planner_rows(&self) -> Vec<PlannerRow> {
+        let Some(planner) = &self.planner else {
+            return Vec::new();
+        };
+        let Some(anchor) = self.anchor_city() else {
+            return Vec::new();
+        };
+
+        plan_rows(
+            anchor,
+            &self.planner_cities(),
+            planner.date,
+            planner.time,
+            &self.config.effective_planner_settings(),
+        )
+    }
+
+    fn handle_config_editor_input(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        match key {
+            KeyCode::Esc => {
+                self.close_config_editor();
+            }
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                self.cycle_config_tab(true);
+            }
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.cycle_config_tab(false);
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                if let Some(editor) = &mut self.config_editor {
+                    editor.selected = editor.selected.saturating_sub(1);
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let row_count = self.config_editor_row_count();
+                if row_count == 0 {
+                    return;
+                }
+                if let Some(editor) = &mut self.config_editor {
+                    editor.selected = (editor.selected + 1).min(row_count.saturating_sub(1));
+                }
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Err(err) = self.activate_config_editor_row() {
+                    self.set_status(err.to_string());
+                }
+            }
+            KeyCode::Char(c @ ('+' | '=' | '-'))
+                if self
+                    .config_editor
+                    .as_ref()
+                    .is_some_and(|editor| editor.tab == ConfigTab::Settings) =>
+            {
+                if let Some(selected) = self.config_editor.as_ref().map(|editor| editor.selected) {
+                    self.step_setting(selected, c != '-');
+                }
+            }
+            KeyCode::Char('a') => {
+                if let Err(err) = self.add_config_editor_item() {
+                    self.set_status(err.to_string());
+                }
+            }
+            KeyCode::Char('K') => {
+                if let Err(err) = self.move_config_editor_item(-1) {
+                    self.set_status(err.to_string());
+                }
+            }
+            KeyCode::Char('J') => {
+                if let Err(err) = self.move_config_editor_item(1) {
+                    self.set_status(err.to_string());
+                }
+            }
+            KeyCode::Backspace | KeyCode::Delete | KeyCode::Char('x') => {
+                if let Err(err) = self.remove_config_editor_item() {
+                    self.set_status(err.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_normal_input(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        // second key of a chord; anything unbound cancels it
+        if let Some(prefix) = self.pending_chord.take() {
+            if let KeyCode::Char(c) = key
+                && let Some(action) = resolve_chord(prefix, c)
+            {
+                self.run_chord_action(action);
+            }
+            return;
+        }
+
+        // [keys] remaps land on the default key, which the global arms below handle; the
+        // map's own uses of default keys are matched on the key as pressed
+        let pressed = key;
+        let key = self.keymap.translate(key);
+
+        // on the currency panel 'c' keeps its older single-key meaning, cycling the pair
+        if key == KeyCode::Char('c') && self.focus == Focus::Currency {
+            self.cycle_current_target_city();
+            return;
+        }
+
+        if let KeyCode::Char(c) = key
+            && is_chord_prefix(c)
+        {
+            self.pending_chord = Some(c);
+            return;
+        }
+
+        match key {
+            KeyCode::Char('q') => self.running = false,
+
+            // zoom and pan the focused map; arrows only pan once zoomed in
+            KeyCode::Char('+' | '=') if self.focus == Focus::Map => {
+                self.shown_map_view().zoom_in();
+            }
+            KeyCode::Char('-') if self.focus == Focus::Map => {
+                self.shown_map_view().zoom_out();
+            }
+            KeyCode::Char('0') if self.focus == Focus::Map => {
+                *self.shown_map_view() = MapViewport::default();
+            }
+            KeyCode::Char(c @ '1'..='3') if self.focus == Focus::Map => {
+                self.show_map_region(c as usize - '1' as usize);
+            }
+            KeyCode::Char('v') if self.focus == Focus::Map => {
+                self.map_shows_nz = !self.map_shows_nz;
+                // keep the crosshair on, in the middle of the other map
+                if self.map_crosshair.take().is_some() {
+                    self.toggle_map_crosshair();
+                }
+            }
+            KeyCode::Char('x') if self.focus == Focus::Map => {
+                self.toggle_map_crosshair();
+            }
+            KeyCode::Char('\'') if self.focus == Focus::Map => {
+                self.cycle_map_bookmark();
+            }
+            KeyCode::Char('b') if self.focus == Focus::Map => {
+                let style = self.config.effective_map_settings().marker_style().next();
+                if let Err(err) =
+                    self.apply_immediate_config_command(CommandAction::SetMapMarker { style })
+                {
+                    self.set_status(err.to_string());
+                }
+            }
+            KeyCode::Enter if self.focus == Focus::Map && self.map_crosshair.is_some() => {
+                if let Some((lat, lon)) = self.map_crosshair {
+                    self.set_status(format!("lat = {:.4}, lon = {:.4}", lat, lon));
+                }
+            }
+            _ if self.focus == Focus::Map
+                && self.map_crosshair.is_some()
+                && matches!(
+                    pressed,
+                    KeyCode::Up
+                        | KeyCode::Down
+                        | KeyCode::Left
+                        | KeyCode::Right
+                        | KeyCode::Char('h' | 'j' | 'k' | 'l')
+                ) =>
+            {
+                let (dx, dy) = match pressed {
+                    KeyCode::Up | KeyCode::Char('k') => (0.0, 1.0),
+                    KeyCode::Down | KeyCode::Char('j') => (0.0, -1.0),
+                    KeyCode::Left | KeyCode::Char('h') => (-1.0, 0.0),
+                    _ => (1.0, 0.0),
+                };
+                self.move_map_crosshair(dx, dy);
+            }
+            KeyCode::Char('f') if self.focus == Focus::Map => {
+                self.map_shows_ferry = !self.map_shows_ferry;
+            }
+            KeyCode::Char('a') if self.focus == Focus::Map => {
+                self.map_shows_airports = !self.map_shows_airports;
+            }
+            KeyCode::Char('i') if self.focus == Focus::Map => {
+                self.map_shows_legend = !self.map_shows_legend;
+            }
+            KeyCode::Char('H') if self.focus == Focus::Map => {
+                self.map_shows_heat = !self.map_shows_heat;
+            }
+            _ if self.focus == Focus::Map && pressed == KeyCode::Char('r') => {
+                self.map_shows_highways = !self.map_shows_highways;
+            }
+            KeyCode::Char('m') if self.focus == Focus::Map => {
+                self.map_measure = match self.map_measure {
+                    Some(_) => None,
+                    None => Some(Vec::new()),
+                };
+                if let Some(label) = self.map_measurement() {
+                    self.set_status(label);
+                }
+            }
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right
+                if self.focus == Focus::Map && self.shown_map_view().is_zoomed() =>
+            {
+                let (dx, dy) = match key {
+                    KeyCode::Up => (0.0, MAP_PAN_STEP),
+                    KeyCode::Down => (0.0, -MAP_PAN_STEP),
+                    KeyCode::Left => (-MAP_PAN_STEP, 0.0),
+                    _ => (MAP_PAN_STEP, 0.0),
+                };
+                self.shown_map_view().pan(dx, dy);
+            }
+
+            // arrow keys move between panels
+            KeyCode::Up => self.set_focus(self.up_visible_focus(self.focus)),
+            KeyCode::Down => self.set_focus(self.down_visible_focus(self.focus)),
+            KeyCode::Left => self.set_focus(self.left_visible_focus(self.focus)),
+            KeyCode::Right => self.set_focus(self.right_visible_focus(self.focus)),
+            KeyCode::Tab => self.set_focus(self.next_visible_focus(self.focus)),
+            KeyCode::BackTab => self.set_focus(self.prev_visible_focus(self.focus)),
+
+            KeyCode::Enter => self.enter_edit_mode(),
+            KeyCode::Char('e') => self.enter_edit_mode(),
+
+            // hjkl for panel navigation (vim-style, same as arrows)
+            KeyCode::Char('h') => self.set_focus(self.left_visible_focus(self.focus)),
+            KeyCode::Char('l') => self.set_focus(self.right_visible_focus(self.focus)),
+            KeyCode::Char('j') => self.set_focus(self.down_visible_focus(self.focus)),
+            KeyCode::Char('k') => self.set_focus(self.up_visible_focus(self.focus)),
+
+            // swap/toggle shortcut
+            KeyCode::Char('s') => self.handle_swap(),
+
+            // now shortcut for time converter
+            KeyCode::Char('n') if self.focus == Focus::TimeConvert => {
+                self.time_converter.set_to_now();
+                self.update_time_conversion();
+            }
+
+            // 'r' - refresh weather or reset time converter
+            KeyCode::Char('r') => match self.focus {
+                Focus::Weather => {
+                    self.weather_refresh_pending = true;
+                    self.set_status("Refreshing weather...".to_string());
+                }
+                Focus::TimeConvert => {
+                    self.time_converter.reset();
+                    self.update_time_conversion();
+                }
+                _ => {}
+            },
+
+            // numeric input for currency when focused
+            KeyCode::Char(c) if c.is_ascii_digit() && self.focus == Focus::Currency => {
+                self.input_mode = InputMode::EditingCurrency;
+                self.currency_converter.clear_input();
+                self.currency_converter.handle_input(c);
+            }
+
+            // numeric input for time converter when focused
+            KeyCode::Char(c) if c.is_ascii_digit() && self.focus == Focus::TimeConvert => {
+                self.time_converter.handle_digit(c);
+                self.update_time_conversion();
+                if self.time_converter.input_buffer.len() == 4 {
+                    self.session_stats.record_conversion();
+                }
+            }
+
+            // backspace for time converter when typing
+            KeyCode::Backspace
+                if self.focus == Focus::TimeConvert && self.time_converter.is_typing() =>
+            {
+                self.time_converter.handle_backspace();
+                self.update_time_conversion();
+            }
+
+            // escape clears time input buffer
+            KeyCode::Esc if self.focus == Focus::TimeConvert && self.time_converter.is_typing() => {
+                self.time_converter.clear_input_buffer();
+            }
+
+            // 'f' cycles the converter's from city
+            KeyCode::Char('f') if self.focus == Focus::TimeConvert => {
+                self.cycle_from_city();
+            }
+
+            // 'b' broadcasts the input time to every city
+            KeyCode::Char('b') if self.focus == Focus::TimeConvert => {
+                self.broadcast_expanded = !self.broadcast_expanded;
+            }
+
+            // 'S' scrubs the input time like a timezone slider
+            KeyCode::Char('S') if self.focus == Focus::TimeConvert => {
+                self.input_mode = InputMode::ScrubbingTime;
+                self.scrub_offset_minutes = 0;
+            }
+
+            // space - context-dependent action
+            KeyCode::Char(' ') => match self.focus {
+                Focus::Weather => {
+                    self.cycle_weather_city();
+                }
+                Focus::TimeConvert => {
+                    self.cycle_current_target_city();
+                }
+                Focus::Currency => {
+                    self.cycle_current_target_city();
+                }
+                _ => {}
+            },
+
+            // 't' flips between 12- and 24-hour clocks
+            KeyCode::Char('t') => {
+                self.set_clock_format(!self.config.display.use_24_hour);
+            }
+
+            // '[' and ']' switch the right-hand tab
+            KeyCode::Char('[') => self.cycle_tab(false),
+            KeyCode::Char(']') => self.cycle_tab(true),
+
+            // 'z' zooms the focused panel to the whole content area and back
+            KeyCode::Char('z') => {
+                self.start_transition();
+                self.zoomed = !self.zoomed;
+            }
+            KeyCode::Esc if self.zoomed => {
+                self.start_transition();
+                self.zoomed = false;
+            }
+
+            // '?' toggles help overlay
+            KeyCode::Char('?') => {
+                self.show_help = !self.show_help;
+            }
+
+            // 'R' (shift+r) reloads config from disk
+            KeyCode::Char('R') => {
+                if let Err(e) = self.reload_config() {
+                    self.set_status(format!("Failed to reload config: {}", e));
+                }
+            }
+
+            // 'E' (shift+e) opens config in editor
+            KeyCode::Char('E') => {
+                self.edit_config_requested = true;
+            }
+
+            // '/' starts command input
+            KeyCode::Char('/') => {
+                self.command_buffer.push('/');
+            }
+
+            _ => {}
+        }
+    }
+
+    fn run_chord_action(&mut self, action: ChordAction) {
+        match action {
+            ChordAction::Focus(Focus::Map) if !self.map_enabled() => {
+                self.set_status("Map is hidden (/map on to show it)".to_string());
+            }
+            ChordAction::Focus(focus) => self.set_focus(focus),
+            ChordAction::CyclePair => self.cycle_current_target_city(),
+            ChordAction::CycleFromCity => self.cycle_from_city(),
+            ChordAction::CycleWeatherCity => self.cycle_weather_city(),
+        }
+    }
+
+    fn cycle_weather_city(&mut self) {
+        self.step_weather_city(true);
+    }
+
+    /// show the next or previous NZ city's weather
+    fn step_weather_city(&mut self, forward: bool) {
+        let count = NZ_CITIES.len();
+        self.select_weather_city(if forward {
+            (self.weather_city_index + 1) % count
+        } else {
+            (self.weather_city_index + count - 1) % count
+        });
+        self.session_stats.record_city_cycle();
+    }
+
+    /// show another NZ city's weather, fetching it on the next pass
+    fn select_weather_city(&mut self, index: usize) {
+        self.weather_city_index = index;
+        self.active_weather_alerts.clear();
+        self.current_weather = None;
+        self.weather_error = None;
+        self.weather_refresh_pending = true;
+    }
+
+    fn handle_command_input(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        match key {
+            KeyCode::Esc => {
+                self.command_buffer.clear();
+            }
+            KeyCode::Enter => {
+                self.execute_command();
+                self.command_buffer.clear();
+            }
+            KeyCode::Backspace => {
+                self.command_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.command_buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_picker_input(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        match key {
+            KeyCode::Esc => {
+                self.picker = None;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                if let Some(picker) = &mut self.picker {
+                    picker.selected = picker.selected.saturating_sub(1);
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let option_count = self.picker_options().len();
+                if option_count == 0 {
+                    return;
+                }
+                if let Some(picker) = &mut self.picker {
+                    picker.selected = (picker.selected + 1).min(option_count.saturating_sub(1));
+                }
+            }
+            KeyCode::Enter => {
+                if let Err(err) = self.submit_picker_selection() {
+                    self.set_status(err.to_string());
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(picker) = &mut self.picker {
+                    picker.query.pop();
+                    picker.selected = 0;
+                }
+            }
+            KeyCode::Char(c) if !c.is_control() => {
+                if let Some(picker) = &mut self.picker {
+                    picker.query.push(c);
+                    picker.selected = 0;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn submit_picker_selection(&mut self) -> Result<()> {
+        let Some(choice) = self.current_picker_choice() else {
+            return Ok(());
+        };
+
+        let Some(picker) = self.picker.clone() else {
+            return Ok(());
+        };
+
+        match (picker.kind, choice) {
+            (PickerKind::Country, PickerChoice::Country { code, name }) => {
+                self.picker = None;
+                self.apply_config_command(CommandAction::SetFocalCountry { code, name })
+            }
+            (PickerKind::PlaceCurrency, PickerChoice::Currency { code, name }) => {
+                self.picker = None;
+                self.apply_config_command(CommandAction::AddPlaceCurrency { code, name })
+            }
+            (PickerKind::MapMode, PickerChoice::MapEnabled { enabled, .. }) => {
+                self.picker = None;
+                if self.config_editor.is_some() {
+                    self.apply_config_command(CommandAction::SetMapEnabled { enabled })
+                } else {
+                    self.apply_immediate_config_command(CommandAction::SetMapEnabled { enabled })
+                }
+            }
+            (PickerKind::AnchorCity, PickerChoice::City { code, .. }) => {
+                self.picker = None;
+                self.set_anchor_city_in_draft(&code)?;
+                self.open_picker(PickerKind::TargetCity);
+                self.set_status(
+                    "Draft updated: anchor city set. Pick one or more target cities".to_string(),
+                );
+                Ok(())
+            }
+            (PickerKind::TargetCity, PickerChoice::City { code, .. }) => {
+                self.picker = None;
+                self.add_target_city_to_draft(&code)
+            }
+            (PickerKind::AddCity, PickerChoice::NewCity(city)) if city.currency.is_empty() => {
+                self.open_picker(PickerKind::NewCityCurrency(city));
+                Ok(())
+            }
+            (PickerKind::AddCity, PickerChoice::NewCity(city)) => {
+                self.picker = None;
+                self.apply_config_command(CommandAction::AddTrackedCity { city })
+            }
+            (PickerKind::NewCityCurrency(mut city), PickerChoice::Currency { code, .. }) => {
+                self.picker = None;
+                city.currency = code;
+                self.apply_config_command(CommandAction::AddTrackedCity { city })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn open_picker(&mut self, kind: PickerKind) {
+        self.show_help = false;
+        self.command_buffer.clear();
+        self.picker = Some(PickerState {
+            query: String::new(),
+            selected: 0,
+            kind,
+        });
+    }
+
+    fn open_config_editor(&mut self) {
+        self.begin_config_draft();
+        if self.config_editor.is_none() {
+            self.config_editor = Some(ConfigEditorState {
+                tab: ConfigTab::Places,
+                selected: 0,
+            });
+        }
+        self.show_help = false;
+        self.command_buffer.clear();
+    }
+
+    /// the config editor opened on its Settings tab
+    fn open_settings(&mut self) {
+        self.open_config_editor();
+        if let Some(editor) = &mut self.config_editor {
+            editor.tab = ConfigTab::Settings;
+            editor.selected = 0;
+        }
+    }
+
+    fn close_config_editor(&mut self) {
+        self.config_editor = None;
+        self.set_status(
+            "Config editor closed. Draft kept open; use /apply or /discard".to_string(),
+        );
+    }
+
+    fn execute_command(&mut self) {
+        let raw_command = self.command_buffer.trim();
+
+        let action = match parse_command(raw_command) {
+            Ok(action) => action,
+            Err(message) => {
+                self.set_status(message);
+                return;
+            }
+        };
+
+        match action {
+            CommandAction::EnterConfigDraft => {
+                self.open_config_editor();
+            }
+            CommandAction::OpenSettings => {
+                self.open_settings();
+            }
+            CommandAction::ShowHelp => {
+                self.show_help = true;
+            }
+            CommandAction::EditConfig => {
+                self.edit_config_requested = true;
+            }
+            CommandAction::Quit => {
+                self.running = false;
+            }
+            CommandAction::Reload => {
+                if let Err(e) = self.reload_config_state() {
+                    self.set_status(format!("Failed to reload config: {}", e));
+                }
+            }
+            CommandAction::ApplyDraft => {
+                if let Err(e) = self.apply_config_draft() {
+                    self.set_status(format!("Failed to apply draft: {}", e));
+                }
+            }
+            CommandAction::DiscardDraft => {
+                self.discard_config_draft();
+            }
+            CommandAction::ResetDraft => {
+                let editing_config = self.config_editor.is_some();
+                self.reset_config_draft();
+                if !editing_config && let Err(e) = self.apply_config_draft() {
+                    self.set_status(format!("Failed to apply reset draft: {}", e));
+                }
+            }
+            CommandAction::RestoreDraft => {
+                if let Err(e) = self.restore_config_draft() {
+                    self.set_status(format!("Failed to restore draft: {}", e));
+                }
+            }
+            CommandAction::Refresh => {
+                self.weather_refresh_pending = true;
+                self.set_status("Refreshing...".to_string());
+            }
+            CommandAction::ShowStats => {
+                self.overlay = Some(Overlay::Stats);
+            }
+            CommandAction::ShowAbout => {
+                self.overlay = Some(Overlay::About);
+            }
+            CommandAction::ShowConfigProblems => {
+                if self.config_problems.is_empty() {
+                    self.set_status("config.toml has no problems".to_string());
+                } else {
+                    self.overlay = Some(Overlay::ConfigProblems);
+                }
+            }
+            CommandAction::OpenPlanner => {
+                self.open_planner();
+            }
+            CommandAction::OpenCountryPicker => {
+                self.open_picker(PickerKind::Country);
+            }
+            CommandAction::OpenPlaceCurrencyPicker => {
+                self.open_picker(PickerKind::PlaceCurrency);
+            }
+            CommandAction::OpenMapPicker => {
+                self.open_picker(PickerKind::MapMode);
+            }
+            CommandAction::SetClockFormat { use_24_hour } => {
+                let use_24_hour = use_24_hour.unwrap_or(!self.config.display.use_24_hour);
+                self.set_clock_format(use_24_hour);
+            }
+            CommandAction::AddEvent { args } => {
+                match parse_event_command(&args, &self.config, Utc::now()) {
+                    Ok(event) => self.update_events(|events| events.push(event.clone())),
+                    Err(message) => self.set_status(message),
+                }
+            }
+            CommandAction::ClearEvents => {
+                self.update_events(Vec::clear);
+            }
+            CommandAction::SaveMapBookmark { name } => self.save_map_bookmark(name),
+            CommandAction::ListMapBookmarks => {
+                let names: Vec<String> = self
+                    .config
+                    .effective_map_settings()
+                    .bookmarks
+                    .into_iter()
+                    .map(|bookmark| bookmark.name)
+                    .collect();
+                self.set_status(if names.is_empty() {
+                    "No map bookmarks (save the shown view with /bookmark <name>)".to_string()
+                } else {
+                    format!("Map bookmarks: {}", names.join(", "))
+                });
+            }
+            CommandAction::ClearMapBookmarks => {
+                self.map_bookmark = None;
+                match self.update_map_bookmarks(Vec::clear) {
+                    Ok(()) => self.set_status("Map bookmarks cleared".to_string()),
+                    Err(err) => {
+                        self.set_status(format!("Map bookmarks cleared (not saved: {})", err))
+                    }
+                }
+            }
+            CommandAction::AddAlarm { args } => {
+                match parse_alarm_command(&args, &self.config, Utc::now()) {
+                    Ok(alarm) => {
+                        let saved = self.update_alarms(|alarms| {
+                            if !alarms.contains(&alarm) {
+                                alarms.push(alarm.clone());
+                            }
+                        });
+                        let clock = clock_format(self.config.display.use_24_hour, false);
+                        let status = self
+                            .pending_alarms()
+                            .into_iter()
+                            .find(|scheduled| scheduled.alarm == alarm)
+                            .map(|scheduled| {
+                                format!(
+                                    "Alarm set for {} in {} (in {})",
+                                    scheduled.local.format(clock),
+                                    scheduled.city_name,
+                                    format_countdown(scheduled.remaining)
+                                )
+                            })
+                            .unwrap_or_else(|| "Alarm set".to_string());
+                        match saved {
+                            Ok(()) => self.set_status(status),
+                            Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
+                        }
+                    }
+                    Err(message) => self.set_status(message),
+                }
+            }
+            CommandAction::ListAlarms => {
+                let clock = clock_format(self.config.display.use_24_hour, false);
+                let pending: Vec<String> = self
+                    .pending_alarms()
+                    .iter()
+                    .map(|alarm| {
+                        format!(
+                            "{} {} (in {})",
+                            alarm.local.format(clock),
+                            alarm.alarm.city_code,
+                            format_countdown(alarm.remaining)
+                        )
+                    })
+                    .collect();
+                if pending.is_empty() {
+                    self.set_status("No alarms set - /alarm <HH:MM> <city code>".to_string());
+                } else {
+                    self.set_status(format!("Alarms: {}", pending.join(", ")));
+                }
+            }
+            CommandAction::ClearAlarms => match self.update_alarms(Vec::clear) {
+                Ok(()) => self.set_status("Alarms cleared".to_string()),
+                Err(err) => self.set_status(format!("Alarms cleared (not saved: {})", err)),
+            },
+            CommandAction::AddTodo { text } => {
+                self.update_todos(|todos| {
+                    todos.push(TodoItem {
+                        text: text.clone(),
+                        done: false,
+                    });
+                    Ok(())
+                });
+            }
+            CommandAction::ToggleTodo { number } => {
+                self.update_todos(|todos| {
+                    let todo = todos
+                        .get_mut(number - 1)
+                        .ok_or_else(|| format!("no checklist item {}", number))?;
+                    todo.done = !todo.done;
+                    Ok(())
+                });
+            }
+            CommandAction::RemoveTodo { number } => {
+                self.update_todos(|todos| {
+                    if number > todos.len() {
+                        return Err(format!("no checklist item {}", number));
+                    }
+                    todos.remove(number - 1);
+                    Ok(())
+                });
+            }
+            CommandAction::ClearDoneTodos => {
+                self.update_todos(|todos| {
+                    todos.retain(|todo| !todo.done);
+                    Ok(())
+                });
+            }
+            CommandAction::ShowAnimations => {
+                let display = &self.config.display;
+                let layers: Vec<String> = AnimationLayer::ALL
+                    .iter()
+                    .map(|layer| {
+                        let state = if display.animations.get(*layer) {
+                            "on"
+                        } else {
+                            "off"
+                        };
+                        format!("{} {}", layer.name(), state)
+                    })
+                    .collect();
+                let mut status = format!("Animations: {}", layers.join(", "));
+                if !display.show_animations {
+                    status.push_str(" (all paused: show_animations = false)");
+                }
+                self.set_status(status);
+            }
+            CommandAction::SetAnimation { layer, on } => {
+                let on = on.unwrap_or(!self.config.display.animations.get(layer));
+                self.config.display.animations.set(layer, on);
+                if let Some(draft) = self.config_draft.as_mut() {
+                    draft.display.animations.set(layer, on);
+                }
+                let status = format!(
+                    "Animation {} {}",
+                    layer.name(),
+                    if on { "on" } else { "off" }
+                );
+                match self.config.save() {
+                    Ok(()) => self.set_status(status),
+                    Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
+                }
+            }
+            CommandAction::ToggleChecklist => {
+                let show = !self.config.display.show_checklist;
+                self.config.display.show_checklist = show;
+                if let Some(draft) = self.config_draft.as_mut() {
+                    draft.display.show_checklist = show;
+                }
+                let status = if show {
+                    "Checklist shown"
+                } else {
+                    "Checklist hidden"
+                };
+                match self.config.save() {
+                    Ok(()) => self.set_status(status.to_string()),
+                    Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
+                }
+            }
+            CommandAction::ToggleHighContrast => {
+                let on = !self.config.display.high_contrast;
+                self.config.display.high_contrast = on;
+                if let Some(draft) = self.config_draft.as_mut() {
+                    draft.display.high_contrast = on;
+                }
+                theme::set_high_contrast(on);
+                let status = if on {
+                    "High contrast on"
+                } else {
+                    "High contrast off"
+                };
+                match self.config.save() {
+                    Ok(()) => self.set_status(status.to_string()),
+                    Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
+                }
+            }
+            CommandAction::SetNote { note } => {
+                self.set_destination_note(note);
+            }
+            CommandAction::ShowNote => {
+                let code = self.time_converter.to_city_code.clone();
+                let status = match self.config.note_for(&code) {
+                    Some(note) => format!("{}: {}", code, note),
+                    None => format!("No note for {} (add one with /note <text>)", code),
+                };
+                self.set_status(status);
+            }
+            CommandAction::ShowTimestamp { input } => {
+                let instant = if input.is_empty() {
+                    Some(Utc::now())
+                } else {
+                    parse_epoch(&input)
+                };
+                match instant {
+                    Some(instant) => {
+                        self.timestamp = Some(instant);
+                        self.set_status(format!("Timestamp {}", instant.timestamp()));
+                    }
+                    None => self.set_status(format!(
+                        "invalid timestamp: {} (expected unix seconds or milliseconds)",
+                        input
+                    )),
+                }
+            }
+            CommandAction::ClearTimestamp => {
+                self.timestamp = None;
+                self.set_status("Timestamp cleared".to_string());
+            }
+            CommandAction::ShowCalendar => {
+                self.calendar_month = first_of_month(self.anchor_today());
+                self.overlay = Some(Overlay::Calendar);
+            }
+            CommandAction::StartTour => self.start_tour(),
+            CommandAction::ShowClimate => match climate::load() {
+                Ok(records) => {
+                    let city = self.get_weather_city_name().to_string();
+                    let today = chrono::Local::now().date_naive();
+                    self.climate_headline = climate::headline(&records, &city, today);
+                    self.climate = monthly_summaries(&records, &city);
+                    self.overlay = Some(Overlay::Climate);
+                }
+                Err(e) => self.set_status(format!("Failed to read climate records: {}", e)),
+            },
+            CommandAction::ShowHistory => match ledger::load() {
+                Ok(mut entries) => {
+                    entries.reverse();
+                    self.history = entries;
+                    self.history_scroll = 0;
+                    self.overlay = Some(Overlay::History);
+                }
+                Err(e) => self.set_status(format!("Failed to read history: {}", e)),
+            },
+            CommandAction::ShowCache => {
+                let dir = Config::cache_dir();
+                let usage = cache::usage(&dir);
+                self.set_status(format!(
+                    "Cache: {} files, {} of {} MB in {}",
+                    usage.files,
+                    cache::format_bytes(usage.bytes),
+                    self.config.effective_cache().max_mb,
+                    dir.display()
+                ));
+            }
+            CommandAction::ClearCache => match cache::clear(&Config::cache_dir()) {
+                Ok(removed) => self.set_status(format!(
+                    "Cleared {} cached files ({})",
+                    removed.files,
+                    cache::format_bytes(removed.bytes)
+                )),
+                Err(e) => self.set_status(format!("Cache clear failed: {}", e)),
+            },
+            CommandAction::ExportHistory { path } => {
+                let path = match path {
+                    Some(path) => ledger::expand_home(&path),
+                    None => ledger::default_export_path(Utc::now()),
+                };
+                match ledger::export(&path) {
+                    Ok(count) => self.set_status(format!(
+                        "Exported {} conversions to {}",
+                        count,
+                        path.display()
+                    )),
+                    Err(e) => self.set_status(format!("Export failed: {}", e)),
+                }
+            }
+            CommandAction::ExportMap { path } => {
+                let path = match path {
+                    Some(path) => ledger::expand_home(&path),
+                    None => export::default_map_path(Local::now()),
+                };
+                // the size it's drawn at on screen, so the snapshot matches what's shown
+                let (width, height) = self
+                    .click_areas
+                    .map
+                    .map_or(EXPORT_MAP_SIZE, |area| (area.width, area.height));
+                match ui::render_map(self, width, height).map(|buf| export::write_map(&path, &buf))
+                {
+                    Some(Ok(())) => {
+                        self.set_status(format!("Exported the map to {}", path.display()))
+                    }
+                    Some(Err(e)) => self.set_status(format!("Export failed: {}", e)),
+                    None => self.set_status("The map is off; /map on to show it first".to_string()),
+                }
+            }
+            CommandAction::ExportConfig { path } => {
+                let path = match path {
+                    Some(path) => ledger::expand_home(&path),
+                    None => bundle::default_bundle_path(Utc::now()),
+                };
+                match bundle::export(&path) {
+                    Ok(summary) => self.set_status(format!(
+                        "Exported the config ({} cities, {} rates) to {}",
+                        summary.cities,
+                        summary.rates,
+                        path.display()
+                    )),
+                    Err(e) => self.set_status(format!("Export failed: {}", e)),
+                }
+            }
+            CommandAction::ImportConfig {
+                path,
+                allow_commands,
+            } => {
+                if self.config_draft.is_some() {
+                    self.set_status(
+                        "Apply or discard the config draft before importing".to_string(),
+                    );
+                    return;
+                }
+                let path = ledger::expand_home(&path);
+                match bundle::import(&path, allow_commands).and_then(|summary| {
+                    self.reload_config()?;
+                    Ok(summary)
+                }) {
+                    // reloading opens the problems overlay if the bundle's config has any
+                    Ok(summary) => self.set_status(format!(
+                        "Imported {} cities and {} rates from {}; the old config is kept as .bak.1",
+                        summary.cities,
+                        summary.rates,
+                        path.display()
+                    )),
+                    Err(e) => self.set_status(format!("Import failed: {}", e)),
+                }
+            }
+            CommandAction::OpenCityPicker { query } => {
+                self.open_picker(PickerKind::AddCity);
+                if let Some(picker) = self.picker.as_mut() {
+                    picker.query = query;
+                }
+            }
+            CommandAction::AddWorldCity { query } => {
+                let Some(reference) = find_world_city(&query) else {
+                    self.set_status(format!(
+                        "No bundled city matches '{}'; /city add {} also searches time zones",
+                        query, query
+                    ));
+                    return;
+                };
+                let cities = self.active_config().all_cities();
+                if cities
+                    .iter()
+                    .any(|city| city.name.eq_ignore_ascii_case(reference.name))
+                {
+                    self.set_status(format!("{} is already tracked", reference.name));
+                    return;
+                }
+                let taken: Vec<String> = cities.iter().map(|city| city.code.clone()).collect();
+                let city = world_city_entry(reference, &taken);
+                if let Err(err) = self.apply_config_command(CommandAction::AddTrackedCity { city })
+                {
+                    self.set_status(err.to_string());
+                }
+            }
+            other => {
+                let result = if matches!(
+                    other,
+                    CommandAction::SetMapEnabled { .. } | CommandAction::SetMapMarker { .. }
+                ) && self.config_editor.is_none()
+                {
+                    self.apply_immediate_config_command(other)
+                } else {
+                    self.apply_config_command(other)
+                };
+                if let Err(err) = result {
+                    self.set_status(err.to_string());
+                }
+            }
+        }
+    }
+
+    /// check if currency rate refresh is needed
+    pub fn needs_currency_refresh(&self) -> bool {
+        self.currency_converter.needs_rate_refresh()
+    }
+
+    /// handle swap action for focused panel
+    fn handle_swap(&mut self) {
+        match self.focus {
+            Focus::Currency => {
+                // swap_currencies already handles rate inversion
+                self.currency_converter.swap_currencies();
+            }
+            Focus::TimeConvert => {
+                self.time_converter.swap_cities();
+                self.sync_currency_to_time_selection();
+                self.update_time_conversion();
+            }
+            Focus::Weather => {
+                self.start_transition();
+                self.weather_expanded = !self.weather_expanded;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_currency_input(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        match key {
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Enter => {
+                self.input_mode = InputMode::Normal;
+                self.session_stats.record_conversion();
+                self.record_conversion_in_ledger();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '.' => {
+                self.currency_converter.handle_input(c);
+            }
+            KeyCode::Backspace => {
+                self.currency_converter.handle_backspace();
+            }
+            _ => {}
+        }
+    }
+
+    /// append the completed currency conversion to the ledger
+    fn record_conversion_in_ledger(&mut self) {
+        let converter = &self.currency_converter;
+        let Some(rate) = converter.rate else {
+            return;
+        };
+        if converter.from_amount <= 0.0 {
+            return;
+        }
+        let entry = LedgerEntry {
+            timestamp: Utc::now(),
+            from_currency: converter.from_currency.clone(),
+            from_amount: converter.from_amount,
+            to_currency: converter.to_currency.clone(),
+            to_amount: converter.to_amount,
+            rate,
+        };
+        if let Err(e) = ledger::append(&entry) {
+            self.set_status(format!("Failed to record conversion: {}", e));
+        }
+    }
+
+    fn handle_time_input(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        match key {
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.reset_time_input_to_now();
+            }
+            KeyCode::Enter => {
+                self.input_mode = InputMode::Normal;
+                self.session_stats.record_conversion();
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.time_converter.increment_hour();
+                self.update_time_conversion();
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.time_converter.decrement_hour();
+                self.update_time_conversion();
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.time_converter.increment_minute();
+                self.update_time_conversion();
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.time_converter.decrement_minute();
+                self.update_time_conversion();
+            }
+            _ => {}
+        }
+    }
+
+    /// scrub mode: the converter, the per-city list and the world clock follow the slider
+    fn handle_scrub_input(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        match key {
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.scrub_offset_minutes = 0;
+                self.reset_time_input_to_now();
+            }
+            KeyCode::Enter => {
+                self.input_mode = InputMode::Normal;
+                self.scrub_offset_minutes = 0;
+                self.session_stats.record_conversion();
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.scrub_offset_minutes += self.time_converter.scrub(true);
+                self.update_time_conversion();
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.scrub_offset_minutes += self.time_converter.scrub(false);
+                self.update_time_conversion();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_scrubbing(&self) -> bool {
+        self.input_mode == InputMode::ScrubbingTime
+    }
+
+    fn reset_time_input_to_now(&mut self) {
+        if let Some(ct) = &self.current_city_time {
+            self.time_converter.input_hour = ct.datetime.hour();
+            self.time_converter.input_minute = ct.datetime.minute();
+        } else {
+            self.time_converter.set_to_now();
+        }
+        self.time_converter.clear_input_buffer();
+        self.update_time_conversion();
+    }
+
+    fn enter_edit_mode(&mut self) {
+        match self.focus {
+            Focus::Currency => {
+                if self.input_mode == InputMode::EditingCurrency {
+                    self.input_mode = InputMode::Normal;
+                    self.currency_converter.editing = false;
+                } else {
+                    self.input_mode = InputMode::EditingCurrency;
+                    self.currency_converter.editing = true;
+                }
+            }
+            Focus::TimeConvert => {
+                if self.input_mode == InputMode::EditingTime {
+                    self.input_mode = InputMode::Normal;
+                } else {
+                    self.input_mode = InputMode::EditingTime;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// check if edit config was requested
+    pub fn needs_edit_config(&self) -> bool {
+        self.edit_config_requested
+    }
+
+    /// clear edit config request
+    pub fn clear_edit_request(&mut self) {
+        self.edit_config_requested = false;
+    }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// reload config from disk and refresh dependent state
+    pub fn reload_config(&mut self) -> Result<()> {
+        let (config, problems) = Config::load_checked()?;
+        self.config = config;
+        self.sync_runtime_to_config();
+
+        self.set_status("Config reloaded".to_string());
+        self.show_config_problems(problems);
+        Ok(())
+    }
+
+    /// keep the problems from the last load and open the overlay listing them, if any
+    fn show_config_problems(&mut self, problems: Vec<ConfigProblem>) {
+        if !problems.is_empty() {
+            self.set_status(format!(
+                "config.toml has {} problem{}; defaults used for those fields (/problems)",
+                problems.len(),
+                if problems.len() == 1 { "" } else { "s" }
+            ));
+            self.overlay = Some(Overlay::ConfigProblems);
+        }
+        self.config_problems = problems;
+    }
+
+    fn reload_config_state(&mut self) -> Result<()> {
+        if self.config_draft.is_some() {
+            self.config_draft = Some(Config::load()?);
+            self.picker = None;
+            if let Some(editor) = self.config_editor.as_mut() {
+                editor.tab = ConfigTab::Places;
+                editor.selected = 0;
+            }
+            self.clamp_config_editor_selection();
+            self.set_status("Saved preferences reloaded from config.toml".to_string());
+            Ok(())
+        } else {
+            self.reload_config()
+        }
+    }
+
+    /// get editor command from config
+    pub fn get_editor(&self) -> String {
+        self.config.display.get_editor()
+    }
+
+    /// check if it's time for a tick
+    pub fn should_tick(&self) -> bool {
+        self.last_tick.elapsed() >= self.effective_tick_rate()
+    }
+
+    /// animation tick rate, slowed while the terminal is unfocused
+    fn effective_tick_rate(&self) -> Duration {
+        if self.terminal_focused {
+            self.tick_rate
+        } else {
+            self.tick_rate.max(UNFOCUSED_INTERVAL)
+        }
+    }
+
+    /// how long the event loop waits for input before redrawing
+    pub fn poll_interval(&self) -> Duration {
+        if self.terminal_focused {
+            FOCUSED_POLL
+        } else {
+            UNFOCUSED_INTERVAL
+        }
+    }
+
+    /// poll timeout for the event loop; with seconds shown it also wakes at the next
+    /// whole second so the clock ticks evenly even at the slow unfocused rate
+    pub fn next_redraw_in(&self) -> Duration {
+        let interval = self.poll_interval();
+        if !self.config.display.show_seconds {
+            return interval;
+        }
+        let into_second = u64::from(Utc::now().timestamp_subsec_millis() % 1000);
+        interval.min(Duration::from_millis(1000 - into_second))
+    }
+
+    /// track terminal focus; regaining it ticks straight away so times are current
+    pub fn set_terminal_focus(&mut self, focused: bool) {
+        let regained = focused && !self.terminal_focused;
+        self.terminal_focused = focused;
+        if regained {
+            self.tick();
+            self.reset_tick();
+        }
+    }
+
+    /// reset the tick timer
+    pub fn reset_tick(&mut self) {
+        self.last_tick = Instant::now();
+    }
+
+    /// the /ts timestamp as (city code, local time) for the from and to cities
+    pub fn timestamp_rows(&self) -> Vec<(String, String)> {
+        let Some(instant) = self.timestamp else {
+            return Vec::new();
+        };
+        [
+            &self.time_converter.from_city_code,
+            &self.time_converter.to_city_code,
+        ]
+        .into_iter()
+        .filter_map(|code| {
+            let city = self.city_by_code(code)?;
+            let local =
+                format_instant_in(instant, &city.timezone, self.config.display.use_24_hour)?;
+            Some((city.code.clone(), local))
+        })
+        .collect()
+    }
+
+    /// get the from city name for time conversion
+    pub fn get_time_convert_from_name(&self) -> &str {
+        self.city_by_code(&self.time_converter.from_city_code)
+            .map(|city| city.name.as_str())
+            .unwrap_or(&self.time_converter.from_city_code)
+    }
+
+    /// get the to city name for time conversion
+    pub fn get_time_convert_to_name(&self) -> &str {
+        self.city_by_code(&self.time_converter.to_city_code)
+            .map(|city| city.name.as_str())
+            .unwrap_or(&self.time_converter.to_city_code)
+    }
+
+    pub fn active_map_focus(&self) -> Focus {
+        match self.focus {
+            Focus::Map if self.map_shows_nz => Focus::Weather,
+            Focus::TimeConvert if self.converter_nz_trip().is_some() => Focus::Weather,
+            focus => focus,
+        }
+    }
+
+    /// the time converter's from and to cities as (code, (lat, lon)) when both are in NZ,
+    /// so the map shows the trip on the NZ map rather than the world
+    pub fn converter_nz_trip(&self) -> Option<[(String, (f64, f64)); 2]> {
+        let place = |code: &str| {
+            let city = self.city_by_code(code)?;
+            let (lat, lon) = city_coordinates(city)?;
+            ((NZ_LAT_MIN..=NZ_LAT_MAX).contains(&lat) && (NZ_LON_MIN..=NZ_LON_MAX).contains(&lon))
+                .then(|| (city.code.clone(), (lat, lon)))
+        };
+        Some([
+            place(&self.time_converter.from_city_code)?,
+            place(&self.time_converter.to_city_code)?,
+        ])
+    }
+
+    /// (lat, lon) of the NZ end and the home city for the world map's flight home:
+    /// the current city when it's in NZ, otherwise Wellington; None when home is in NZ too
+    pub fn homeward_flight(&self) -> Option<[(f64, f64); 2]> {
+        if !self.config.display.active_animations().flight {
+            return None;
+        }
+        let in_nz = |(lat, lon): (f64, f64)| {
+            (NZ_LAT_MIN..=NZ_LAT_MAX).contains(&lat) && (NZ_LON_MIN..=NZ_LON_MAX).contains(&lon)
+        };
+        let home = city_coordinates(&self.config.home_city).filter(|&home| !in_nz(home))?;
+        let nz = city_coordinates(&self.config.current_city)
+            .filter(|&current| in_nz(current))
+            .or_else(|| {
+                let wellington = NZ_CITIES.iter().find(|city| city.code == "WLG")?;
+                Some((wellington.lat, wellington.lon))
+            })?;
+        Some([nz, home])
+    }
+
+    fn shown_map_view_ref(&self) -> &MapViewport {
+        if self.active_map_focus() == Focus::Weather {
+            &self.nz_map_view
+        } else {
+            &self.world_map_view
+        }
+    }
+
+    /// the zoom and pan of whichever map the map panel is showing
+    fn shown_map_view(&mut self) -> &mut MapViewport {
+        if self.active_map_focus() == Focus::Weather {
+            &mut self.nz_map_view
+        } else {
+            &mut self.world_map_view
+        }
+    }
+
+    /// jump the shown map to one of its presets; past the last one, the whole map
+    fn show_map_region(&mut self, index: usize) {
+        let (regions, projection) = if self.active_map_focus() == Focus::Weather {
+            (NZ_REGIONS, WorldProjection::Greenwich)
+        } else {
+            (
+                WORLD_REGIONS,
+                self.config.effective_map_settings().projection,
+            )
+        };
+        let (x, y) = self.full_map_bounds();
+        *self.shown_map_view() = regions
+            .get(index)
+            .map_or_else(MapViewport::default, |region| {
+                let region = MapRegion {
+                    x_bounds: projection.wrap_span(region.x_bounds),
+                    ..*region
+                };
+                MapViewport::region(&region, x, y)
+            });
+    }
+
+    /// step the shown map through the `[map]` bookmarks saved for it
+    fn cycle_map_bookmark(&mut self) {
+        let world = self.active_map_focus() != Focus::Weather;
+        let map = self.config.effective_map_settings();
+        let bookmarks: Vec<&MapBookmark> = map
+            .bookmarks
+            .iter()
+            .filter(|bookmark| bookmark.world == world)
+            .collect();
+        if bookmarks.is_empty() {
+            self.set_status(
+                "No bookmarks for this map (save one with /bookmark <name>)".to_string(),
+            );
+            return;
+        }
+
+        let index = self
+            .map_bookmark
+            .map_or(0, |index| (index + 1) % bookmarks.len());
+        let bookmark = bookmarks[index];
+        let lon = if world {
+            map.projection.wrap(bookmark.lon)
+        } else {
+            bookmark.lon
+        };
+        let (x, y) = self.full_map_bounds();
+        *self.shown_map_view() = MapViewport::centred(bookmark.zoom, lon, bookmark.lat, x, y);
+        self.map_bookmark = Some(index);
+        self.set_status(format!(
+            "{} ({}/{})",
+            bookmark.name,
+            index + 1,
+            bookmarks.len()
+        ));
+    }
+
+    /// save the shown map's view under `name`, replacing a bookmark of that name
+    fn save_map_bookmark(&mut self, name: String) {
+        if !self.config.effective_map_settings().enabled {
+            self.set_status("Show the map (/map on) to bookmark a view".to_string());
+            return;
+        }
+        let (x, y) = self.full_map_bounds();
+        let (zoom, lon, lat) = self.shown_map_view_ref().centre(x, y);
+        let round = |value: f64| (value * 1e4).round() / 1e4;
+        let bookmark = MapBookmark {
+            name,
+            world: self.active_map_focus() != Focus::Weather,
+            lat: round(lat),
+            lon: round(WorldProjection::Greenwich.wrap(lon)),
+            zoom,
+        };
+        let status = format!("Bookmarked {}", bookmark.name);
+        match self.update_map_bookmarks(|bookmarks| {
+            bookmarks.retain(|saved| !saved.name.eq_ignore_ascii_case(&bookmark.name));
+            bookmarks.push(bookmark.clone());
+        }) {
+            Ok(()) => self.set_status(status),
+            Err(err) => self.set_status(format!("{} (not saved: {})", status, err)),
+        }
+    }
+
+    /// change the map bookmarks and persist them (a staged draft follows along)
+    fn update_map_bookmarks(&mut self, change: impl Fn(&mut Vec<MapBookmark>)) -> Result<()> {
+        change(&mut self.config.map.get_or_insert_default().bookmarks);
+        if let Some(draft) = self.config_draft.as_mut() {
+            change(&mut draft.map.get_or_insert_default().bookmarks);
+        }
+        self.config.save()
+    }
+
+    pub fn has_config_draft(&self) -> bool {
+        self.config_draft.is_some()
+    }
+
+    pub fn picker_title(&self) -> Option<String> {
+        let picker = self.picker.as_ref()?;
+        let title = match &picker.kind {
+            PickerKind::Country => "Set focal city via country".to_string(),
+            PickerKind::PlaceCurrency => "Add currency via country".to_string(),
+            PickerKind::MapMode => "Map visibility".to_string(),
+            PickerKind::AnchorCity => "Pick anchor city".to_string(),
+            PickerKind::TargetCity => "Add target city".to_string(),
+            PickerKind::AddCity => "Add tracked city".to_string(),
+            PickerKind::NewCityCurrency(city) => format!("Currency for {}", city.name),
+        };
+        Some(title)
+    }
+
+    pub fn picker_prompt(&self) -> Option<&'static str> {
+        let picker = self.picker.as_ref()?;
+        let prompt = match &picker.kind {
+            PickerKind::Country => "Pick a country and resolve to its representative focal city",
+            PickerKind::PlaceCurrency => {
+                "Pick a currency and resolve it through country to a representative city"
+            }
+            PickerKind::MapMode => "Choose whether the map is shown",
+            PickerKind::AnchorCity => "Search by city code, name, or country",
+            PickerKind::TargetCity => "Search by city code, name, or country",
+            PickerKind::AddCity => "Search the city database or IANA time zones",
+            PickerKind::NewCityCurrency(_) => {
+                "Only the time zone is known for this city; pick the currency it uses"
+            }
+        };
+        Some(prompt)
+    }
+
+    pub fn picker_options(&self) -> Vec<PickerOption> {
+        self.picker_choices()
+            .into_iter()
+            .map(|choice| match choice {
+                PickerChoice::Country { code, name } => PickerOption {
+                    label: name,
+                    detail: code,
+                },
+                PickerChoice::Currency { code, name } => PickerOption {
+                    label: name,
+                    detail: code,
+                },
+                PickerChoice::MapEnabled { enabled: _, label } => PickerOption {
+                    detail: "map visibility".to_string(),
+                    label,
+                },
+                PickerChoice::City {
+                    code,
+                    name,
+                    country,
+                } => PickerOption {
+                    label: format!("{} ({})", name, code),
+                    detail: country,
+                },
+                PickerChoice::NewCity(city) => PickerOption {
+                    label: format!("{} ({})", city.name, city.code),
+                    detail: if city.currency.is_empty() {
+                        format!("{} · currency unknown", city.timezone)
+                    } else {
+                        format!("{} · {}", city.timezone, city.currency)
+                    },
+                },
+            })
+            .collect()
+    }
+
+    fn current_picker_choice(&self) -> Option<PickerChoice> {
+        let picker = self.picker.as_ref()?;
+        let choices = self.picker_choices();
+        let index = picker.selected.min(choices.len().saturating_sub(1));
+        choices.get(index).cloned()
+    }
+
+    fn picker_choices(&self) -> Vec<PickerChoice> {
+        let Some(picker) = self.picker.as_ref() else {
+            return Vec::new();
+        };
+
+        match &picker.kind {
+            PickerKind::Country => search_countries(&picker.query)
+                .into_iter()
+                .map(|country| PickerChoice::Country {
+                    code: country.code.to_string(),
+                    name: country.name.to_string(),
+                })
+                .collect(),
+            PickerKind::PlaceCurrency | PickerKind::NewCityCurrency(_) => {
+                search_currencies(&picker.query)
+                    .into_iter()
+                    .map(|currency| PickerChoice::Currency {
+                        code: currency.code.to_string(),
+                        name: currency.name.to_string(),
+                    })
+                    .collect()
+            }
+            PickerKind::MapMode => {
+                let query = picker.query.trim().to_lowercase();
+                vec![
+                    PickerChoice::MapEnabled {
+                        enabled: true,
+                        label: "Show map".to_string(),
+                    },
+                    PickerChoice::MapEnabled {
+                        enabled: false,
+                        label: "Hide map".to_string(),
+                    },
+                ]
+                .into_iter()
+                .filter(|choice| match choice {
+                    PickerChoice::MapEnabled { label, .. } => {
+                        query.is_empty() || label.to_lowercase().contains(&query)
+                    }
+                    _ => false,
+                })
+                .collect()
+            }
+            PickerKind::AnchorCity => self.search_config_cities(&picker.query),
+            PickerKind::TargetCity => self.search_config_cities(&picker.query),
+            PickerKind::AddCity => {
+                let config = self.active_config();
+                search_new_cities(&picker.query, &config.all_cities())
+                    .into_iter()
+                    .map(PickerChoice::NewCity)
+                    .collect()
+            }
+        }
+    }
+
+    fn search_config_cities(&self, query: &str) -> Vec<PickerChoice> {
+        let trimmed = query.trim().to_lowercase();
+        let mut seen_codes = std::collections::HashSet::new();
+        let mut choices = Vec::new();
+
+        let mut config_cities: Vec<&City> = self.active_config().representative_cities();
+        config_cities.sort_by(|left, right| left.name.cmp(&right.name));
+        for city in config_cities {
+            let matches_query = trimmed.is_empty()
+                || city.code.to_lowercase().contains(&trimmed)
+                || city.name.to_lowercase().contains(&trimmed)
+                || city.country.to_lowercase().contains(&trimmed)
+                || city.currency.to_lowercase().contains(&trimmed);
+            if matches_query && seen_codes.insert(city.code.clone()) {
+                choices.push(PickerChoice::City {
+                    code: city.code.clone(),
+                    name: city.name.clone(),
+                    country: city.country.clone(),
+                });
+            }
+        }
+
+        for city in search_representative_cities(query) {
+            if seen_codes.insert(city.city_code.to_string()) {
+                choices.push(PickerChoice::City {
+                    code: city.city_code.to_string(),
+                    name: city.city_name.to_string(),
+                    country: city.country_name.to_string(),
+                });
+            }
+        }
+
+        choices
+    }
+
+    fn active_config(&self) -> &Config {
+        self.config_draft.as_ref().unwrap_or(&self.config)
+    }
+
+    fn active_config_mut(&mut self) -> &mut Config {
+        if let Some(draft) = self.config_draft.as_mut() {
+            draft
+        } else {
+            &mut self.config
+        }
+    }
+
+    pub fn config_editor_state(&self) -> Option<&ConfigEditorState> {
+        self.config_editor.as_ref()
+    }
+
+    pub fn config_editor_config(&self) -> Option<&Config> {
+        self.config_editor.as_ref()?;
+        Some(self.active_config())
+    }
+
+    fn cycle_config_tab(&mut self, forward: bool) {
+        if let Some(editor) = &mut self.config_editor {
+            editor.tab = if forward {
+                editor.tab.next()
+            } else {
+                editor.tab.prev()
+            };
+            editor.selected = 0;
+        }
+    }
+
+    fn config_editor_row_count(&self) -> usize {
+        let Some(editor) = self.config_editor.as_ref() else {
+            return 0;
+        };
+
+        match editor.tab {
+            ConfigTab::Places => 2 + self.active_config().effective_target_city_codes().len(),
+            ConfigTab::Settings => SettingRow::rows(self.active_config()).len(),
+            ConfigTab::Actions => 6,
+        }
+    }
+
+    fn clamp_config_editor_selection(&mut self) {
+        let row_count = self.config_editor_row_count();
+        if let Some(editor) = &mut self.config_editor {
+            editor.selected = if row_count == 0 {
+                0
+            } else {
+                editor.selected.min(row_count - 1)
+            };
+        }
+    }
+
+    fn activate_config_editor_row(&mut self) -> Result<()> {
+        let Some(editor) = self.config_editor.as_ref() else {
+            return Ok(());
+        };
+
+        match editor.tab {
+            ConfigTab::Places => self.activate_places_editor_row(editor.selected),
+            ConfigTab::Settings => {
+                self.step_setting(editor.selected, true);
+                Ok(())
+            }
+            ConfigTab::Actions => self.activate_actions_editor_row(editor.selected),
+        }
+    }
+
+    fn activate_places_editor_row(&mut self, selected: usize) -> Result<()> {
+        let list_len = self.active_config().effective_target_city_codes().len();
+        match selected {
+            0 => {
+                self.open_picker(PickerKind::AnchorCity);
+                Ok(())
+            }
+            index if index == 1 + list_len => {
+                self.open_picker(PickerKind::TargetCity);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn activate_actions_editor_row(&mut self, selected: usize) -> Result<()> {
+        match selected {
+            0 => self.apply_config_draft(),
+            1 => {
+                self.discard_config_draft();
+                Ok(())
+            }
+            2 => {
+                self.reset_config_draft();
+                Ok(())
+            }
+            3 => self.reload_config_state(),
+            4 => self.restore_config_draft(),
+            5 => {
+                let enabled = !self.active_config().effective_map_settings().enabled;
+                self.apply_config_command(CommandAction::SetMapEnabled { enabled })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn add_config_editor_item(&mut self) -> Result<()> {
+        let Some(editor) = self.config_editor.as_ref() else {
+            return Ok(());
+        };
+
+        match editor.tab {
+            ConfigTab::Places => {
+                self.open_picker(PickerKind::TargetCity);
+                Ok(())
+            }
+            ConfigTab::Settings | ConfigTab::Actions => Ok(()),
+        }
+    }
+
+    fn remove_config_editor_item(&mut self) -> Result<()> {
+        let Some(editor) = self.config_editor.as_ref() else {
+            return Ok(());
+        };
+
+        match editor.tab {
+            ConfigTab::Places => {
+                if editor.selected == 0 {
+                    return Ok(());
+                }
+
+                let codes = self.active_config().effective_target_city_codes();
+                let index = editor.selected - 1;
+                if let Some(code) = codes.get(index) {
+                    self.remove_target_city_from_draft(code)?;
+                }
+                Ok(())
+            }
+            ConfigTab::Settings | ConfigTab::Actions => Ok(()),
+        }
+    }
+
+    fn move_config_editor_item(&mut self, direction: isize) -> Result<()> {
+        let Some(editor) = self.config_editor.as_ref() else {
+            return Ok(());
+        };
+
+        match editor.tab {
+            ConfigTab::Places => {
+                if editor.selected == 0 {
+                    return Ok(());
+                }
+                let index = editor.selected - 1;
+                self.reorder_target_city_in_draft(index, direction)
+            }
+            ConfigTab::Settings | ConfigTab::Actions => Ok(()),
+        }
+    }
+
+    /// move the selected Settings row forward or back in the draft
+    fn step_setting(&mut self, selected: usize, forward: bool) {
+        let Some(&row) = SettingRow::rows(self.active_config()).get(selected) else {
+            return;
+        };
+        let config = self.active_config_mut();
+        row.step(config, forward);
+        let status = format!(
+            "Draft updated: {} {}. Use /apply to save",
+            row.label(config).to_lowercase(),
+            row.value(config)
+        );
+        self.set_status(status);
+    }
+
+    fn set_anchor_city_in_draft(&mut self, code: &str) -> Result<()> {
+        let city = self
+            .active_config()
+            .all_cities()
+            .into_iter()
+            .find(|city| city.code.eq_ignore_ascii_case(code))
+            .cloned()
+            .or_else(|| {
+                representative_city_by_city_code(code).map(|city| City {
+                    name: city.city_name.to_string(),
+                    code: city.city_code.to_string(),
+                    country: city.country_name.to_string(),
+                    timezone: city.timezone.to_string(),
+                    currency: city.currency_code.to_string(),
+                })
+            })
+            .ok_or_else(|| anyhow!("unknown city: {}", code))?;
+        let city_code = city.code.clone();
+
+        self.ensure_city_in_active_catalogue(&city)?;
+
+        let target = self.active_config_mut();
+        let time = target.time.get_or_insert_with(TimeConfig::default);
+        time.anchor_city_code = Some(city_code.clone());
+        time.city_codes.clear();
+        time.target_city_codes
+            .retain(|entry| !entry.eq_ignore_ascii_case(&city_code));
+        self.clamp_config_editor_selection();
+        self.set_status(format!(
+            "Draft updated: anchor city set to {}. Use /apply to save",
+            city_code
+        ));
+        Ok(())
+    }
+
+    fn add_target_city_to_draft(&mut self, code: &str) -> Result<()> {
+        let city = self
+            .active_config()
+            .all_cities()
+            .into_iter()
+            .find(|city| city.code.eq_ignore_ascii_case(code))
+            .cloned()
+            .or_else(|| {
+                representative_city_by_city_code(code).map(|city| City {
+                    name: city.city_name.to_string(),
+                    code: city.city_code.to_string(),
+                    country: city.country_name.to_string(),
+                    timezone: city.timezone.to_string(),
+                    currency: city.currency_code.to_string(),
+                })
+            })
+            .ok_or_else(|| anyhow!("unknown city: {}", code))?;
+        let city_code = city.code.clone();
+
+        let anchor_code = self.active_config().effective_anchor_city_code();
+        if city_code.eq_ignore_ascii_case(&anchor_code) {
+            self.set_status(format!("{} is already the anchor city", city_code));
+            return Ok(());
+        }
+
+        self.ensure_city_in_active_catalogue(&city)?;
+
+        let target = self.active_config_mut();
+        let time = target.time.get_or_insert_with(TimeConfig::default);
+        time.city_codes.clear();
+        if time
+            .target_city_codes
+            .iter()
+            .any(|entry| entry.eq_ignore_ascii_case(&city_code))
+        {
+            self.set_status(format!("{} is already in the target list", city_code));
+            return Ok(());
+        }
+        time.target_city_codes.push(city_code.clone());
+        self.clamp_config_editor_selection();
+        self.set_status(format!(
+            "Draft updated: added {} to target cities. Use /apply to save",
+            city_code
+        ));
+        Ok(())
+    }
+
+    fn ensure_city_in_active_catalogue(&mut self, city: &City) -> Result<()> {
+        let target = self.active_config_mut();
+        ensure_city_in_config_catalogue(target, city).map_err(|err| anyhow!(err))
+    }
+
+    fn remove_target_city_from_draft(&mut self, code: &str) -> Result<()> {
+        let target = self.active_config_mut();
+        let time = target.time.get_or_insert_with(TimeConfig::default);
+        time.city_codes.clear();
+        let original_len = time.target_city_codes.len();
+        time.target_city_codes
+            .retain(|entry| !entry.eq_ignore_ascii_case(code));
+        if time.target_city_codes.len() < original_len {
+            self.clamp_config_editor_selection();
+            self.set_status(format!(
+                "Draft updated: removed {} from target cities. Use /apply to save",
+                code
+            ));
+        }
+        Ok(())
+    }
+
+    fn reorder_target_city_in_draft(&mut self, index: usize, direction: isize) -> Result<()> {
+        let moved_code = {
+            let target = self.active_config_mut();
+            let time = target.time.get_or_insert_with(TimeConfig::default);
+            time.city_codes.clear();
+
+            if index >= time.target_city_codes.len() {
+                return Ok(());
+            }
+
+            let Some(next_index) = index.checked_add_signed(direction) else {
+                return Ok(());
+            };
+            if next_index >= time.target_city_codes.len() {
+                return Ok(());
+            }
+
+            time.target_city_codes.swap(index, next_index);
+            time.target_city_codes[next_index].clone()
+        };
+
+        if let Some(editor) = self.config_editor.as_mut() {
+            editor.selected = (index.checked_add_signed(direction).unwrap_or(index)) + 1;
+        }
+
+        self.set_status(format!(
+            "Draft updated: moved {} in target cities. Use /apply to save",
+            moved_code
+        ));
+        Ok(())
+    }
+
+    fn apply_config_command(&mut self, action: CommandAction) -> Result<()> {
+        let editing_draft = self.config_draft.is_some();
         let target = if let Some(draft) = self.config_draft.as_mut() {
             draft
         } else {
-            &mut self.config
+            &mut self.config
+        };
+
+        let status =
+            apply_command_action_to_config(target, &action).map_err(|message| anyhow!(message))?;
+
+        if editing_draft {
+            if let Some(status) = status {
+                self.set_status(format!("Draft updated: {}. Use /apply to save", status));
+            }
+        } else {
+            self.config.save()?;
+            self.sync_runtime_to_config();
+
+            if let Some(status) = status {
+                self.set_status(status);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_immediate_config_command(&mut self, action: CommandAction) -> Result<()> {
+        let status = apply_command_action_to_config(&mut self.config, &action)
+            .map_err(|message| anyhow!(message))?;
+
+        if let Some(draft) = self.config_draft.as_mut() {
+            apply_command_action_to_config(draft, &action).map_err(|message| anyhow!(message))?;
+        }
+
+        self.config.save()?;
+        self.sync_runtime_to_config();
+
+        if let Some(status) = status {
+            self.set_status(status);
+        }
+
+        Ok(())
+    }
+
+    fn begin_config_draft(&mut self) {
+        if self.config_draft.is_none() {
+            self.config_draft = Some(self.config.clone());
+        }
+        self.clamp_config_editor_selection();
+        self.set_status(
+            "Config draft opened. Use /apply, /discard, /reset, or /restore".to_string(),
+        );
+    }
+
+    fn apply_config_draft(&mut self) -> Result<()> {
+        let Some(mut draft) = self.config_draft.take() else {
+            self.set_status("No config draft to apply".to_string());
+            return Ok(());
+        };
+        // removing a city takes its alarms with it, reported in /problems
+        let stranded = config_check::drop_stranded_alarms(&mut draft);
+
+        self.config.save_snapshot()?;
+        self.config = draft;
+        self.config.save_backed_up()?;
+        self.sync_runtime_to_config();
+        self.config_editor = None;
+        if stranded.is_empty() {
+            self.set_status("Config draft applied".to_string());
+        } else {
+            self.set_status(format!(
+                "Config draft applied; dropped {} alarm{} for a removed city (/problems)",
+                stranded.len(),
+                if stranded.len() == 1 { "" } else { "s" }
+            ));
+            self.config_problems.extend(stranded);
+        }
+        Ok(())
+    }
+
+    fn discard_config_draft(&mut self) {
+        if self.config_draft.take().is_some() {
+            self.config_editor = None;
+            self.set_status("Config draft discarded".to_string());
+        } else {
+            self.set_status("No config draft to discard".to_string());
+        }
+    }
+
+    fn reset_config_draft(&mut self) {
+        let was_editing = self.config_draft.is_some();
+        let mut draft = self
+            .config_draft
+            .take()
+            .unwrap_or_else(|| self.config.clone());
+        reset_places_to_package_defaults(&mut draft);
+        self.config_draft = Some(draft);
+        self.picker = None;
+        if let Some(editor) = self.config_editor.as_mut() {
+            editor.tab = ConfigTab::Places;
+            editor.selected = 0;
+        }
+        self.clamp_config_editor_selection();
+        self.set_status(if was_editing {
+            "Places reset in draft. Use Apply to write config.toml".to_string()
+        } else {
+            "Package default places draft opened. Use Apply to write config.toml".to_string()
+        });
+    }
+
+    fn restore_config_draft(&mut self) -> Result<()> {
+        let restored = Config::load_latest_snapshot()?;
+        self.config_draft = Some(restored);
+        self.picker = None;
+        if let Some(editor) = self.config_editor.as_mut() {
+            editor.tab = ConfigTab::Places;
+            editor.selected = 0;
+        }
+        self.clamp_config_editor_selection();
+        self.set_status(
+            "Latest saved preferences loaded into draft. Use /apply to save".to_string(),
+        );
+        Ok(())
+    }
+
+    fn sync_runtime_to_config(&mut self) {
+        self.keymap = Keymap::from_config(&self.config.keys).unwrap_or_default();
+        theme::set_custom_colours(self.config.custom_colours());
+        theme::set_high_contrast(self.config.display.high_contrast);
+        icons::set_icon_set(self.config.display.icons);
+        let limits = CacheLimits::from(&self.config.effective_cache());
+        if let Some(store) = self.weather_service.snapshots_mut() {
+            store.limits = limits;
+        }
+        if let Some(store) = self.exchange_service.snapshots_mut() {
+            store.limits = limits;
+        }
+        let currency_pairs = self.config.effective_currency_pairs();
+        let (from_currency, to_currency) = self.config.effective_default_currency_pair();
+        self.currency_converter =
+            CurrencyConverter::new_with_pairs(&from_currency, &to_currency, currency_pairs);
+        let (from_city_code, to_city_code) = self.config.effective_default_time_pair();
+        self.time_converter = TimeConverter::new(&from_city_code, &to_city_code);
+
+        self.weather_city_index =
+            startup_weather_index(&self.config, &self.config.current_city.code);
+        self.current_weather = None;
+        self.weather_error = None;
+        self.weather_expanded = self.config.effective_startup().weather_expanded;
+        self.weather_refresh_pending = true;
+        self.active_weather_alerts.clear();
+        if !self.panel_shown(self.focus) {
+            self.set_focus(self.focus);
+        }
+
+        self.custom_panels = CustomPanels::new(&self.config.effective_custom_panels());
+
+        self.update_times();
+        self.update_time_conversion();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::with_temp_config_dir_for_test;
+
+    #[test]
+    fn splash_holds_until_every_source_is_checked() {
+        let mut app = App::new(Config::default());
+        assert!(app.show_splash);
+
+        app.record_source_health(DataSource::Exchange, Some(false));
+        assert!(app.splash_until.is_none());
+
+        app.record_source_health(DataSource::Weather, Some(true));
+        assert!(app.splash_until.is_some());
+
+        app.splash_until = Some(Instant::now());
+        app.tick();
+        assert!(!app.show_splash);
+    }
+
+    #[test]
+    fn splash_reads_cache_state_from_disk() {
+        with_temp_config_dir_for_test(|| {
+            let store = SnapshotStore::new(
+                Config::cache_dir(),
+                WEATHER_SNAPSHOTS,
+                CacheLimits::from(&Config::default().effective_cache()),
+            );
+            let app = App::load().expect("app should load");
+            store
+                .save(app.get_weather_city_name(), "{}", Utc::now())
+                .expect("snapshot should save");
+
+            let mut app = App::load().expect("app should load");
+            let weather = |app: &App| app.data_sources[0].clone();
+            assert_eq!(weather(&app).source, DataSource::Weather);
+            assert!(weather(&app).cache_warm);
+            assert!(!app.data_sources[1].cache_warm);
+
+            // a cache hit leaves reachability unknown rather than claiming the network
+            app.record_source_health(DataSource::Weather, None);
+            assert!(weather(&app).is_checked());
+            assert_eq!(weather(&app).reachable, None);
+        });
+    }
+
+    #[test]
+    fn losing_focus_slows_ticks_until_focus_returns() {
+        let mut app = App::new(Config::default());
+        assert_eq!(app.poll_interval(), FOCUSED_POLL);
+
+        app.set_terminal_focus(false);
+        assert_eq!(app.poll_interval(), UNFOCUSED_INTERVAL);
+        app.last_tick = Instant::now() - app.tick_rate;
+        assert!(!app.should_tick());
+
+        let frame = app.animation_frame;
+        app.set_terminal_focus(true);
+        assert_eq!(app.animation_frame, frame.wrapping_add(1));
+        assert_eq!(app.poll_interval(), FOCUSED_POLL);
+    }
+
+    #[test]
+    fn redraw_wakes_by_the_next_second_only_when_seconds_are_shown() {
+        let mut app = App::new(Config::default());
+        app.set_terminal_focus(false);
+        assert!(app.next_redraw_in() <= Duration::from_secs(1));
+        assert!(app.next_redraw_in() > Duration::ZERO);
+
+        app.config.display.show_seconds = false;
+        assert_eq!(app.next_redraw_in(), UNFOCUSED_INTERVAL);
+    }
+
+    #[test]
+    fn tick_reuses_city_times_until_the_minute_or_from_city_changes() {
+        let config = Config {
+            display: crate::config::DisplayConfig {
+                show_seconds: false,
+                ..Default::default()
+            },
+            ..Config::default()
+        };
+        let mut app = App::new(config);
+        app.tick();
+        assert!(app.current_city_time.is_some());
+
+        app.current_city_time = None;
+        app.time_refresh_key = Some(app.time_refresh_key());
+        app.tick();
+        assert!(app.current_city_time.is_none());
+
+        app.time_converter.from_city_code = "LDN".to_string();
+        app.tick();
+        assert!(app.current_city_time.is_some());
+        assert_eq!(
+            app.from_city_time
+                .as_ref()
+                .map(|time| time.city_code.as_str()),
+            Some("LDN")
+        );
+    }
+
+    #[test]
+    fn any_key_skips_splash_without_acting_on_it() {
+        let mut app = App::new(Config::default());
+        app.handle_key(crossterm::event::KeyCode::Char('q'));
+
+        assert!(!app.show_splash);
+        assert!(app.running);
+    }
+
+    #[test]
+    fn parses_country_alias_command() {
+        let action = parse_command("/country uk").expect("command should parse");
+
+        assert_eq!(
+            action,
+            CommandAction::SetFocalCountry {
+                code: "GBR".to_string(),
+                name: "United Kingdom".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn stats_command_opens_overlay_and_esc_closes_it() {
+        let mut app = App::new(Config::default());
+        app.show_splash = false;
+        app.command_buffer = "/stats".to_string();
+        app.execute_command();
+        assert_eq!(app.overlay, Some(Overlay::Stats));
+
+        app.handle_key(crossterm::event::KeyCode::Esc);
+        assert_eq!(app.overlay, None);
+    }
+
+    #[test]
+    fn confirmed_conversion_is_recorded_in_history() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.show_splash = false;
+            app.currency_converter.update_rate(0.6);
+            app.input_mode = InputMode::EditingCurrency;
+            app.handle_key(crossterm::event::KeyCode::Enter);
+
+            app.command_buffer = "/history".to_string();
+            app.execute_command();
+            assert_eq!(app.overlay, Some(Overlay::History));
+            assert_eq!(app.history.len(), 1);
+            assert_eq!(app.history[0].to_amount, 60.0);
+
+            assert_eq!(
+                parse_command("/history export ~/tax.csv"),
+                Ok(CommandAction::ExportHistory {
+                    path: Some("~/tax.csv".to_string())
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn config_problems_open_an_overlay_and_leave_the_file_alone() {
+        with_temp_config_dir_for_test(|| {
+            let mut config = Config::default();
+            config.tracked_cities[0].timezone = "Europe/Londn".to_string();
+            let content = toml::to_string_pretty(&config).expect("config serialises");
+            std::fs::write(Config::config_path(), &content).expect("config written");
+
+            let mut app = App::load().expect("a bad timezone still loads");
+            assert_eq!(app.overlay, Some(Overlay::ConfigProblems));
+            assert_eq!(app.config_problems.len(), 1);
+            let problem = &app.config_problems[0];
+            assert_eq!(problem.field, "tracked_cities[0].timezone");
+            assert_eq!(
+                problem.suggestion.as_deref(),
+                Some("did you mean Europe/London?")
+            );
+            assert!(
+                !app.config
+                    .tracked_cities
+                    .iter()
+                    .any(|city| city.code == "LDN")
+            );
+            assert_eq!(
+                std::fs::read_to_string(Config::config_path()).unwrap(),
+                content
+            );
+
+            app.show_splash = false;
+            app.handle_key(crossterm::event::KeyCode::Esc);
+            assert_eq!(app.overlay, None);
+            assert_eq!(
+                parse_command("/problems"),
+                Ok(CommandAction::ShowConfigProblems)
+            );
+            app.command_buffer = "/problems".to_string();
+            app.execute_command();
+            assert_eq!(app.overlay, Some(Overlay::ConfigProblems));
+        });
+    }
+
+    #[test]
+    fn export_map_writes_the_shown_map_as_text() {
+        with_temp_config_dir_for_test(|| {
+            assert_eq!(
+                parse_command("/export map ~/Aotearoa.ans"),
+                Ok(CommandAction::ExportMap {
+                    path: Some("~/Aotearoa.ans".to_string())
+                })
+            );
+            assert!(parse_command("/export").is_err());
+
+            let mut app = App::new(Config::default());
+            let target = Config::config_dir().join("map.txt");
+            app.command_buffer = format!("/export map {}", target.display());
+            app.execute_command();
+            let (message, _) = app.status_message.clone().expect("a status");
+            assert_eq!(message, "The map is off; /map on to show it first");
+            assert!(!target.exists());
+
+            app.config.map.get_or_insert_with(Default::default).enabled = true;
+            app.focus = Focus::Weather;
+            app.execute_command();
+            let (message, _) = app.status_message.clone().expect("a status");
+            assert_eq!(message, format!("Exported the map to {}", target.display()));
+            let text = std::fs::read_to_string(&target).expect("the export was written");
+            assert_eq!(text.lines().count(), usize::from(EXPORT_MAP_SIZE.1));
+            assert!(text.contains("Aotearoa New Zealand"));
+            assert!(text.contains("AKL"));
+            assert!(!text.contains('\x1b'));
+        });
+    }
+
+    #[test]
+    fn parses_about_command() {
+        assert_eq!(parse_command("/about"), Ok(CommandAction::ShowAbout));
+    }
+
+    #[test]
+    fn planner_lists_anchor_first_and_steps_in_half_hours() {
+        let mut app = App::new(Config::default());
+        app.open_planner();
+        let start = app.planner.as_ref().unwrap().time;
+
+        let rows = app.planner_rows();
+        assert_eq!(rows[0].city_code, app.config.effective_anchor_city_code());
+        assert_eq!(rows.len(), app.planner_cities().len());
+
+        app.handle_planner_input(crossterm::event::KeyCode::Right);
+        assert_eq!(
+            app.planner.as_ref().unwrap().time,
+            start + ChronoDuration::minutes(30)
+        );
+
+        app.handle_planner_input(crossterm::event::KeyCode::Char('b'));
+        let best = app.planner.as_ref().unwrap().best.unwrap();
+        assert_eq!(app.planner.as_ref().unwrap().time, best.anchor_time);
+
+        app.handle_planner_input(crossterm::event::KeyCode::Esc);
+        assert!(app.planner.is_none());
+    }
+
+    #[test]
+    fn planner_steps_carry_over_midnight() {
+        let mut app = App::new(Config::default());
+        app.open_planner();
+        let planner = app.planner.as_mut().unwrap();
+        let day = planner.date;
+        planner.time = NaiveTime::from_hms_opt(23, 30, 0).unwrap();
+
+        app.handle_planner_input(crossterm::event::KeyCode::Right);
+        let planner = app.planner.as_ref().unwrap();
+        assert_eq!(planner.time, NaiveTime::MIN);
+        assert_eq!(planner.date, day.succ_opt().unwrap());
+
+        app.handle_planner_input(crossterm::event::KeyCode::Down);
+        let planner = app.planner.as_ref().unwrap();
+        assert_eq!(planner.time, NaiveTime::from_hms_opt(23, 0, 0).unwrap());
+        assert_eq!(planner.date, day);
+    }
+
+    #[test]
+    fn chords_jump_focus_and_cycle_pairs() {
+        let mut app = App::new(Config::default());
+        app.focus = Focus::TimeConvert;
+
+        app.handle_normal_input(crossterm::event::KeyCode::Char('g'));
+        assert_eq!(app.pending_chord, Some('g'));
+        app.handle_normal_input(crossterm::event::KeyCode::Char('w'));
+        assert_eq!(app.focus, Focus::Weather);
+        assert_eq!(app.pending_chord, None);
+
+        app.handle_normal_input(crossterm::event::KeyCode::Char('c'));
+        app.handle_normal_input(crossterm::event::KeyCode::Char('p'));
+        assert_eq!(app.time_converter.to_city_code, "LDN");
+
+        // on the currency panel a lone 'c' still cycles the pair
+        app.focus = Focus::Currency;
+        app.handle_normal_input(crossterm::event::KeyCode::Char('c'));
+        assert_eq!(app.pending_chord, None);
+        assert_ne!(app.time_converter.to_city_code, "LDN");
+    }
+
+    #[test]
+    fn unbound_chord_key_cancels_without_acting() {
+        let mut app = App::new(Config::default());
+        app.focus = Focus::Weather;
+
+        app.handle_normal_input(crossterm::event::KeyCode::Char('g'));
+        app.handle_normal_input(crossterm::event::KeyCode::Char('q'));
+
+        assert!(app.running);
+        assert_eq!(app.pending_chord, None);
+        assert_eq!(app.focus, Focus::Weather);
+        assert_eq!(chord_hints('g').len(), 4);
+    }
+
+    #[test]
+    fn cycling_cities_is_counted_in_session_stats() {
+        let mut app = App::new(Config::default());
+        app.focus = Focus::Currency;
+
+        app.handle_normal_input(crossterm::event::KeyCode::Char(' '));
+        app.focus = Focus::Weather;
+        app.handle_normal_input(crossterm::event::KeyCode::Char(' '));
+
+        assert_eq!(app.session_stats.cities_cycled, 2);
+    }
+
+    #[test]
+    fn parses_currency_command_to_place_add() {
+        let action = parse_command("/currency yen").expect("command should parse");
+
+        assert_eq!(
+            action,
+            CommandAction::AddPlaceCurrency {
+                code: "JPY".to_string(),
+                name: "Japanese yen".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_bare_country_command_to_picker() {
+        let action = parse_command("/country").expect("command should parse");
+
+        assert_eq!(action, CommandAction::OpenCountryPicker);
+    }
+
+    #[test]
+    fn parses_config_command_to_draft_mode() {
+        let action = parse_command("/config").expect("command should parse");
+
+        assert_eq!(action, CommandAction::EnterConfigDraft);
+    }
+
+    #[test]
+    fn applies_currency_command_to_places_config() {
+        let mut config = Config::default();
+        let action = parse_command("/currency yen").expect("command should parse");
+
+        let status = apply_command_action_to_config(&mut config, &action)
+            .expect("config mutation should succeed");
+
+        assert_eq!(
+            status.as_deref(),
+            Some("JPY -> Japan -> Tokyo added to target cities")
+        );
+        assert_eq!(
+            config
+                .time
+                .as_ref()
+                .map(|time| time.target_city_codes.clone()),
+            Some(vec!["TYO".to_string()])
+        );
+    }
+
+    #[test]
+    fn currency_command_adds_missing_representative_city_to_catalogue() {
+        let mut config = Config::default();
+        config.tracked_cities.clear();
+        let action = parse_command("/currency yen").expect("command should parse");
+
+        apply_command_action_to_config(&mut config, &action)
+            .expect("config mutation should succeed");
+
+        assert!(
+            config
+                .tracked_cities
+                .iter()
+                .any(|city| city.code.eq_ignore_ascii_case("TYO"))
+        );
+        assert_eq!(
+            config
+                .time
+                .as_ref()
+                .map(|time| time.target_city_codes.clone()),
+            Some(vec!["TYO".to_string()])
+        );
+    }
+
+    #[test]
+    fn applies_map_enabled_command_to_config() {
+        let mut config = Config::default();
+        let action = parse_command("/map on").expect("command should parse");
+
+        apply_command_action_to_config(&mut config, &action)
+            .expect("config mutation should succeed");
+
+        assert_eq!(config.map.as_ref().map(|map| map.enabled), Some(true));
+    }
+
+    #[test]
+    fn map_command_applies_immediately_even_with_saved_draft() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.open_config_editor();
+            app.close_config_editor();
+            app.command_buffer = "/map on".to_string();
+
+            app.execute_command();
+
+            assert_eq!(app.config.map.as_ref().map(|map| map.enabled), Some(true));
+            assert_eq!(
+                app.config_draft
+                    .as_ref()
+                    .and_then(|draft| draft.map.as_ref())
+                    .map(|map| map.enabled),
+                Some(true)
+            );
+
+            let saved = Config::load().expect("config should reload");
+            assert_eq!(saved.map.as_ref().map(|map| map.enabled), Some(true));
+        });
+    }
+
+    #[test]
+    fn map_picker_can_disable_map() {
+        let mut app = App::new(Config::default());
+        app.open_picker(PickerKind::MapMode);
+        if let Some(picker) = app.picker.as_mut() {
+            picker.query = "hide".to_string();
+        }
+
+        let choice = app
+            .current_picker_choice()
+            .expect("picker should return a choice");
+        let mut config = Config::default();
+        match choice {
+            PickerChoice::MapEnabled { enabled, .. } => {
+                apply_command_action_to_config(
+                    &mut config,
+                    &CommandAction::SetMapEnabled { enabled },
+                )
+                .expect("config mutation should succeed");
+            }
+            other => panic!("unexpected picker choice: {other:?}"),
+        }
+
+        assert_eq!(config.map.as_ref().map(|map| map.enabled), Some(false));
+    }
+
+    #[test]
+    fn map_focus_uses_configured_map_when_panel_is_focused() {
+        let mut app = App::new(Config::default());
+        app.focus = Focus::Map;
+        app.map_context = Focus::Weather;
+
+        assert_eq!(app.active_map_focus(), Focus::Map);
+    }
+
+    #[test]
+    fn focused_map_zooms_and_pans_the_map_it_shows() {
+        let mut app = App::new(Config::default());
+        app.show_splash = false;
+        app.focus = Focus::Map;
+
+        // unzoomed, arrows still move between panels
+        app.handle_key(crossterm::event::KeyCode::Right);
+        assert_eq!(app.focus, Focus::Weather);
+        app.focus = Focus::Map;
+
+        app.handle_key(crossterm::event::KeyCode::Char('+'));
+        app.handle_key(crossterm::event::KeyCode::Char('+'));
+        assert_eq!(app.world_map_view.zoom, 2);
+        app.handle_key(crossterm::event::KeyCode::Left);
+        assert_eq!(app.focus, Focus::Map);
+        assert!(app.world_map_view.center_x < 0.5);
+
+        app.handle_key(crossterm::event::KeyCode::Char('v'));
+        assert_eq!(app.active_map_focus(), Focus::Weather);
+        app.handle_key(crossterm::event::KeyCode::Char('+'));
+        assert_eq!(app.nz_map_view.zoom, 1);
+        assert_eq!(app.world_map_view.zoom, 2);
+
+        app.handle_key(crossterm::event::KeyCode::Char('v'));
+        app.handle_key(crossterm::event::KeyCode::Char('0'));
+        assert_eq!(app.world_map_view, MapViewport::default());
+
+        app.handle_key(crossterm::event::KeyCode::Char('f'));
+        assert!(app.map_shows_ferry);
+        app.handle_key(crossterm::event::KeyCode::Char('f'));
+        assert!(!app.map_shows_ferry);
+        app.handle_key(crossterm::event::KeyCode::Char('a'));
+        assert!(app.map_shows_airports);
+        app.handle_key(crossterm::event::KeyCode::Char('i'));
+        assert!(app.map_shows_legend);
+        app.handle_key(crossterm::event::KeyCode::Char('H'));
+        assert!(app.map_shows_heat);
+        // nothing to shade until the grid arrives, and nothing fetched while the map is off
+        assert!(app.heat_points().is_empty());
+        assert!(!app.needs_heat_refresh());
+        app.handle_key(crossterm::event::KeyCode::Char('H'));
+        assert!(!app.map_shows_heat);
+        app.handle_key(crossterm::event::KeyCode::Char('r'));
+        assert!(app.map_shows_highways);
+
+        // digits pick presets on the focused map rather than starting a count
+        app.handle_key(crossterm::event::KeyCode::Char('2'));
+        assert_eq!(app.world_map_view.label().as_deref(), Some("Europe"));
+        app.handle_key(crossterm::event::KeyCode::Char('v'));
+        app.handle_key(crossterm::event::KeyCode::Char('1'));
+        assert_eq!(app.nz_map_view.label().as_deref(), Some("North Island"));
+        app.handle_key(crossterm::event::KeyCode::Char('3'));
+        assert_eq!(app.nz_map_view, MapViewport::default());
+    }
+
+    #[test]
+    fn bookmarks_save_the_shown_view_and_cycle_per_map() {
+        with_temp_config_dir_for_test(|| {
+            let mut config = Config::default();
+            config.map.get_or_insert_default().enabled = true;
+            let mut app = App::new(config);
+            app.show_splash = false;
+            app.focus = Focus::Map;
+
+            app.handle_key(crossterm::event::KeyCode::Char('2'));
+            app.command_buffer = "/bookmark Europe close".to_string();
+            app.execute_command();
+            app.command_buffer.clear();
+            app.handle_key(crossterm::event::KeyCode::Char('v'));
+            for _ in 0..3 {
+                app.handle_key(crossterm::event::KeyCode::Char('+'));
+            }
+            app.command_buffer = "/bookmark Harbour".to_string();
+            app.execute_command();
+            app.command_buffer.clear();
+            let harbour = app.nz_map_view;
+
+            let saved = Config::load().unwrap().map.unwrap().bookmarks;
+            let names: Vec<(&str, bool)> = saved
+                .iter()
+                .map(|bookmark| (bookmark.name.as_str(), bookmark.world))
+                .collect();
+            assert_eq!(names, [("Europe close", true), ("Harbour", false)]);
+
+            // ' only steps through the bookmarks for the map on show
+            app.handle_key(crossterm::event::KeyCode::Char('0'));
+            app.handle_key(crossterm::event::KeyCode::Char('\''));
+            assert_eq!(app.nz_map_view.zoom, harbour.zoom);
+            assert!((app.nz_map_view.center_x - harbour.center_x).abs() < 1e-3);
+            assert_eq!(app.status_message.clone().unwrap().0, "Harbour (1/1)");
+
+            app.handle_key(crossterm::event::KeyCode::Char('v'));
+            app.handle_key(crossterm::event::KeyCode::Char('\''));
+            assert_eq!(app.world_map_view.zoom, saved[0].zoom);
+            assert!(app.world_map_view.center_y > 0.5);
+        });
+    }
+
+    #[test]
+    fn pacific_projection_wraps_presets_and_the_crosshair() {
+        let mut app = App::new(Config {
+            map: Some(MapConfig {
+                enabled: true,
+                projection: WorldProjection::Pacific,
+                ..MapConfig::default()
+            }),
+            ..Config::default()
+        });
+        app.show_splash = false;
+        app.focus = Focus::Map;
+
+        app.handle_key(crossterm::event::KeyCode::Char('3'));
+        assert_eq!(app.world_map_view.label().as_deref(), Some("Americas"));
+        let (x, y) = app.full_map_bounds();
+        assert_eq!(x, [-30.0, 330.0]);
+        let (x, _) = app.world_map_view.bounds(x, y);
+        assert!(x[0] > 180.0 && x[1] <= 330.0);
+
+        app.handle_key(crossterm::event::KeyCode::Char('0'));
+        app.handle_key(crossterm::event::KeyCode::Char('x'));
+        assert_eq!(app.map_crosshair, Some((12.5, 150.0)));
+        for _ in 0..5 {
+            app.handle_key(crossterm::event::KeyCode::Right);
+        }
+        let readout = app.map_crosshair_readout().expect("the crosshair is on");
+        assert!(readout.starts_with("12.50, -165.00"), "{}", readout);
+    }
+
+    #[test]
+    fn converter_between_nz_cities_shows_the_nz_map() {
+        let mut config = Config::default();
+        config.tracked_cities.push(City {
+            name: "Christchurch".to_string(),
+            code: "CHC".to_string(),
+            country: "New Zealand".to_string(),
+            timezone: "Pacific/Auckland".to_string(),
+            currency: "NZD".to_string(),
+        });
+        let mut app = App::new(config);
+        app.focus = Focus::TimeConvert;
+
+        app.time_converter.from_city_code = "WLG".to_string();
+        app.time_converter.to_city_code = "CHC".to_string();
+        let trip = app.converter_nz_trip().expect("both ends are in NZ");
+        assert_eq!([trip[0].0.as_str(), trip[1].0.as_str()], ["WLG", "CHC"]);
+        assert_eq!(app.active_map_focus(), Focus::Weather);
+
+        app.time_converter.to_city_code = app.config.home_city.code.clone();
+        assert_eq!(app.converter_nz_trip(), None);
+        assert_eq!(app.active_map_focus(), Focus::TimeConvert);
+    }
+
+    #[test]
+    fn homeward_flight_runs_from_nz_to_an_overseas_home() {
+        let mut app = App::new(Config::default());
+        let [nz, home] = app.homeward_flight().expect("the default home is overseas");
+        assert_eq!(Some(nz), city_coordinates(&app.config.current_city));
+        assert_eq!(Some(home), city_coordinates(&app.config.home_city));
+
+        app.config.current_city = app.config.home_city.clone();
+        let [nz, _] = app.homeward_flight().expect("still flying home");
+        assert_eq!(nz, (-41.2865, 174.7762));
+
+        app.config.display.animations.flight = false;
+        assert_eq!(app.homeward_flight(), None);
+        app.config.display.animations.flight = true;
+
+        app.config.home_city = Config::default().current_city;
+        assert_eq!(app.homeward_flight(), None);
+    }
+
+    #[test]
+    fn clicking_the_map_picks_the_nearest_city() {
+        use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+        let click = |column, row| MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        let mut app = App::new(Config {
+            map: Some(MapConfig {
+                enabled: true,
+                ..MapConfig::default()
+            }),
+            ..Config::default()
+        });
+        app.show_splash = false;
+        // 26 x 27 canvas cells inside the border, half a degree each on the NZ map
+        app.click_areas.map = Some(Rect::new(10, 5, 28, 29));
+
+        app.focus = Focus::Weather;
+        app.handle_mouse(click(20, 29));
+        assert_eq!(app.get_weather_city_code(), "DUD");
+        assert!(app.weather_refresh_pending);
+
+        // on the world map each cell spans about 14 by 5 degrees; this one covers Tokyo
+        app.focus = Focus::TimeConvert;
+        app.handle_mouse(click(34, 15));
+        assert_eq!(app.time_converter.to_city_code, "TYO");
+
+        // clicks on the border or outside the map do nothing
+        app.handle_mouse(click(10, 5));
+        app.handle_mouse(click(60, 15));
+        assert_eq!(app.time_converter.to_city_code, "TYO");
+
+        // measuring picks places instead, the anchor included, and leaves the selection alone
+        app.focus = Focus::Map;
+        app.handle_key(crossterm::event::KeyCode::Char('m'));
+        app.handle_mouse(click(36, 27));
+        assert_eq!(
+            app.map_measurement().as_deref(),
+            Some("WLG→ click another place")
+        );
+        app.handle_mouse(click(34, 15));
+        assert_eq!(
+            app.map_measurement().as_deref(),
+            Some("WLG→TYO 9,280 km · ~11h 25m flight")
+        );
+        assert_eq!(app.time_converter.to_city_code, "TYO");
+        app.handle_key(crossterm::event::KeyCode::Char('m'));
+        assert_eq!(app.map_measurement(), None);
+    }
+
+    #[test]
+    fn crosshair_moves_with_the_keys_and_reads_out_coordinates() {
+        let mut app = App::new(Config {
+            map: Some(MapConfig {
+                enabled: true,
+                ..MapConfig::default()
+            }),
+            ..Config::default()
+        });
+        app.show_splash = false;
+        app.focus = Focus::Map;
+        app.handle_key(crossterm::event::KeyCode::Char('v'));
+        assert_eq!(app.map_crosshair_readout(), None);
+
+        // it starts in the middle of the NZ map and steps a fortieth of the view
+        app.handle_key(crossterm::event::KeyCode::Char('x'));
+        assert_eq!(app.map_crosshair, Some((-40.75, 172.5)));
+        for _ in 0..4 {
+            app.handle_key(crossterm::event::KeyCode::Right);
+        }
+        app.handle_key(crossterm::event::KeyCode::Char('k'));
+        assert_eq!(app.focus, Focus::Map);
+        let readout = app.map_crosshair_readout().unwrap();
+        assert!(
+            readout.starts_with("-40.41, 173.80 · near WLG ("),
+            "{readout}"
+        );
+
+        app.handle_key(crossterm::event::KeyCode::Enter);
+        let (status, _) = app
+            .status_message
+            .clone()
+            .expect("Enter should show the coordinates");
+        assert_eq!(status, "lat = -40.4125, lon = 173.8000");
+
+        // it stays inside the map however far it is pushed
+        for _ in 0..100 {
+            app.handle_key(crossterm::event::KeyCode::Down);
+        }
+        assert_eq!(app.map_crosshair.map(|(lat, _)| lat), Some(NZ_LAT_MIN));
+
+        // switching maps re-centres it; x again hides it
+        app.handle_key(crossterm::event::KeyCode::Char('v'));
+        assert!(app.map_crosshair_readout().is_some());
+        assert_ne!(app.map_crosshair.map(|(lat, _)| lat), Some(NZ_LAT_MIN));
+        app.handle_key(crossterm::event::KeyCode::Char('x'));
+        assert_eq!(app.map_crosshair, None);
+    }
+
+    #[test]
+    fn hidden_map_is_skipped_in_focus_navigation() {
+        let config = Config {
+            map: Some(MapConfig {
+                enabled: false,
+                ..MapConfig::default()
+            }),
+            ..Config::default()
+        };
+        let mut app = App::new(config);
+        app.focus = Focus::Currency;
+
+        app.handle_normal_input(crossterm::event::KeyCode::Tab);
+        assert_eq!(app.focus, Focus::Weather);
+
+        app.handle_normal_input(crossterm::event::KeyCode::BackTab);
+        assert_eq!(app.focus, Focus::Currency);
+    }
+
+    #[test]
+    fn actions_tab_apply_saves_and_closes_editor() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.open_config_editor();
+            if let Some(draft) = app.config_draft.as_mut() {
+                draft.time = Some(TimeConfig {
+                    anchor_city_code: Some("WLG".to_string()),
+                    target_city_codes: vec!["TYO".to_string()],
+                    city_codes: Vec::new(),
+                });
+            }
+            if let Some(editor) = app.config_editor.as_mut() {
+                editor.tab = ConfigTab::Actions;
+                editor.selected = 0;
+            }
+
+            app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+
+            assert!(app.config_editor.is_none());
+            assert!(app.config_draft.is_none());
+            assert_eq!(
+                app.config.effective_target_city_codes(),
+                vec!["TYO".to_string()]
+            );
+        });
+    }
+
+    #[test]
+    fn settings_tab_steps_display_options_into_the_draft() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            assert_eq!(parse_command("/settings"), Ok(CommandAction::OpenSettings));
+            app.command_buffer = "/settings".to_string();
+            app.execute_command();
+            let editor = app.config_editor.clone().expect("the editor opened");
+            assert_eq!(editor.tab, ConfigTab::Settings);
+
+            app.handle_config_editor_input(crossterm::event::KeyCode::Down);
+            app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+            let (message, _) = app.status_message.clone().expect("a status");
+            assert_eq!(message, "Draft updated: clock 12-hour. Use /apply to save");
+            assert!(app.config.display.use_24_hour);
+
+            for _ in 0..9 {
+                app.handle_config_editor_input(crossterm::event::KeyCode::Down);
+            }
+            app.handle_config_editor_input(crossterm::event::KeyCode::Char('-'));
+            app.handle_config_editor_input(crossterm::event::KeyCode::Char('-'));
+            let draft = app.config_draft.as_ref().expect("a draft");
+            assert!(!draft.display.use_24_hour);
+            assert_eq!(draft.effective_weather().refresh_secs, 60);
+
+            app.handle_config_editor_input(crossterm::event::KeyCode::Tab);
+            app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+            assert!(!app.config.display.use_24_hour);
+            assert_eq!(Config::load().unwrap().effective_weather().refresh_secs, 60);
+            assert_eq!(app.weather_max_age(), Duration::from_secs(60));
+        });
+    }
+
+    #[test]
+    fn hidden_panels_leave_the_focus_cycle() {
+        let config = Config {
+            panel: Some(crate::config::PanelConfig {
+                weather: false,
+                time: false,
+                ..crate::config::PanelConfig::default()
+            }),
+            map: Some(MapConfig {
+                enabled: true,
+                ..MapConfig::default()
+            }),
+            ..Config::default()
+        };
+        let mut app = App::new(config);
+        app.show_splash = false;
+        assert_eq!(app.focus, Focus::Map);
+
+        app.handle_key(crossterm::event::KeyCode::Tab);
+        assert_eq!(app.focus, Focus::Currency);
+        app.handle_key(crossterm::event::KeyCode::Tab);
+        assert_eq!(app.focus, Focus::Map);
+        app.handle_key(crossterm::event::KeyCode::Right);
+        assert_eq!(app.focus, Focus::Currency);
+        app.handle_key(crossterm::event::KeyCode::Up);
+        assert_eq!(app.focus, Focus::Currency);
+        app.handle_key(crossterm::event::KeyCode::Left);
+        assert_eq!(app.focus, Focus::Map);
+
+        let mut config = app.config.clone();
+        config.map.get_or_insert_default().enabled = false;
+        assert!(config.validate().is_ok());
+        config.panel.get_or_insert_default().currency = false;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn startup_section_sets_focus_weather_city_and_view() {
+        let config = Config {
+            startup: Some(crate::config::StartupConfig {
+                focus: StartupFocus::Currency,
+                weather_expanded: false,
+                weather_city_code: Some("AKL".to_string()),
+            }),
+            ..Config::default()
+        };
+        let app = App::new(config);
+        assert_eq!(app.focus, Focus::Currency);
+        assert_eq!(app.map_context, Focus::Currency);
+        assert!(!app.weather_expanded);
+        assert_eq!(NZ_CITIES[app.weather_city_index].code, "AKL");
+
+        let mut config = Config::default();
+        config.map.get_or_insert_default().enabled = false;
+        let app = App::new(config);
+        assert_eq!(app.focus, Focus::Weather);
+        assert!(app.weather_expanded);
+        assert_eq!(NZ_CITIES[app.weather_city_index].code, "WLG");
+    }
+
+    #[test]
+    fn keys_section_remaps_quit_and_frees_the_default() {
+        let mut config = Config::default();
+        config.keys.insert("quit".to_string(), "Q".to_string());
+        let mut app = App::new(config);
+        app.show_splash = false;
+        app.focus = Focus::Weather;
+
+        app.handle_key(crossterm::event::KeyCode::Char('q'));
+        assert!(app.running);
+        app.handle_key(crossterm::event::KeyCode::Char('Q'));
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn moved_defaults_keep_their_meaning_on_the_map() {
+        use crossterm::event::KeyCode;
+
+        let mut config = Config {
+            map: Some(MapConfig {
+                enabled: true,
+                ..MapConfig::default()
+            }),
+            ..Config::default()
         };
+        config.keys.insert("refresh".to_string(), "f5".to_string());
+        config
+            .keys
+            .insert("focus_left".to_string(), "d".to_string());
+        config
+            .keys
+            .insert("focus_right".to_string(), "D".to_string());
+        let mut app = App::new(config);
+        app.show_splash = false;
 
-        let status =
-            apply_command_action_to_config(target, &action).map_err(|message| anyhow!(message))?;
+        // r still toggles the highways on the map, and f5 doesn't
+        app.focus = Focus::Map;
+        let highways = app.map_shows_highways;
+        app.handle_key(KeyCode::Char('r'));
+        assert_eq!(app.map_shows_highways, !highways);
+        app.handle_key(KeyCode::F(5));
+        assert_eq!(app.map_shows_highways, !highways);
+
+        // h and l still steer the crosshair
+        app.handle_key(KeyCode::Char('x'));
+        let (_, lon) = app.map_crosshair.expect("the crosshair is on");
+        app.handle_key(KeyCode::Char('l'));
+        app.handle_key(KeyCode::Char('l'));
+        app.handle_key(KeyCode::Char('h'));
+        assert_eq!(app.focus, Focus::Map);
+        assert!(app.map_crosshair.is_some_and(|(_, moved)| moved > lon));
+
+        // elsewhere the old refresh key does nothing and the new one refreshes
+        app.focus = Focus::Weather;
+        app.weather_refresh_pending = false;
+        app.handle_key(KeyCode::Char('r'));
+        assert!(!app.weather_refresh_pending);
+        app.handle_key(KeyCode::F(5));
+        assert!(app.weather_refresh_pending);
+    }
 
-        if editing_draft {
-            if let Some(status) = status {
-                self.set_status(format!("Draft updated: {}. Use /apply to save", status));
+    #[test]
+    fn remapped_cycling_key_takes_a_count() {
+        use crossterm::event::KeyCode;
+
+        let mut config = Config::default();
+        config
+            .keys
+            .insert("cycle_city".to_string(), "w".to_string());
+        let mut app = App::new(config);
+        app.show_splash = false;
+        app.focus = Focus::Weather;
+        let start = app.weather_city_index;
+        app.handle_key(KeyCode::Char('3'));
+        app.handle_key(KeyCode::Char('w'));
+        assert_eq!(app.weather_city_index, (start + 3) % NZ_CITIES.len());
+        assert_eq!(app.pending_count, None);
+    }
+
+    #[test]
+    fn actions_tab_reset_replaces_draft() {
+        let mut app = App::new(Config::default());
+        app.open_config_editor();
+        if let Some(draft) = app.config_draft.as_mut() {
+            draft.current_city.code = "AKL".to_string();
+            draft.current_city.name = "Auckland".to_string();
+            draft.home_city.code = "CPH".to_string();
+            draft.home_city.name = "Copenhagen".to_string();
+            draft.tracked_cities.clear();
+            draft.time = Some(TimeConfig {
+                anchor_city_code: Some("WLG".to_string()),
+                target_city_codes: vec!["PAR".to_string()],
+                city_codes: Vec::new(),
+            });
+        }
+        if let Some(editor) = app.config_editor.as_mut() {
+            editor.tab = ConfigTab::Actions;
+            editor.selected = 2;
+        }
+
+        app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+
+        assert!(app.config_editor.is_some());
+        assert!(app.config_draft.is_some());
+        assert_eq!(
+            app.config_draft
+                .as_ref()
+                .map(|draft| draft.effective_anchor_city_code()),
+            Some(Config::default().effective_anchor_city_code())
+        );
+        assert_eq!(
+            app.config_draft
+                .as_ref()
+                .map(|draft| draft.effective_target_city_codes()),
+            Some(Config::default().effective_target_city_codes())
+        );
+    }
+
+    #[test]
+    fn actions_tab_reset_preserves_map_visibility() {
+        let config = Config {
+            map: Some(MapConfig {
+                enabled: true,
+                ..MapConfig::default()
+            }),
+            ..Config::default()
+        };
+        let mut app = App::new(config);
+        app.open_config_editor();
+        if let Some(draft) = app.config_draft.as_mut() {
+            draft.time = Some(TimeConfig {
+                anchor_city_code: Some("BOS".to_string()),
+                target_city_codes: vec!["TYO".to_string()],
+                city_codes: Vec::new(),
+            });
+        }
+        if let Some(editor) = app.config_editor.as_mut() {
+            editor.tab = ConfigTab::Actions;
+            editor.selected = 2;
+        }
+
+        app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+
+        assert_eq!(
+            app.config_draft
+                .as_ref()
+                .and_then(|draft| draft.map.as_ref())
+                .map(|map| map.enabled),
+            Some(true)
+        );
+        assert_eq!(
+            app.config_draft
+                .as_ref()
+                .map(|draft| draft.effective_anchor_city_code()),
+            Some(Config::default().effective_anchor_city_code())
+        );
+    }
+
+    #[test]
+    fn actions_tab_reload_restores_saved_preferences_from_disk() {
+        with_temp_config_dir_for_test(|| {
+            let mut saved = Config {
+                time: Some(TimeConfig {
+                    anchor_city_code: Some("WLG".to_string()),
+                    target_city_codes: vec!["CPH".to_string(), "TYO".to_string()],
+                    city_codes: Vec::new(),
+                }),
+                ..Config::default()
+            };
+            saved.tracked_cities.push(City {
+                name: "Copenhagen".to_string(),
+                code: "CPH".to_string(),
+                country: "Denmark".to_string(),
+                timezone: "Europe/Copenhagen".to_string(),
+                currency: "DKK".to_string(),
+            });
+            saved.save().expect("saved config should write");
+
+            let mut app = App::new(saved.clone());
+            app.open_config_editor();
+            if let Some(draft) = app.config_draft.as_mut() {
+                draft.time = Some(TimeConfig {
+                    anchor_city_code: Some("TYO".to_string()),
+                    target_city_codes: vec!["PAR".to_string()],
+                    city_codes: Vec::new(),
+                });
+            }
+            if let Some(editor) = app.config_editor.as_mut() {
+                editor.tab = ConfigTab::Actions;
+                editor.selected = 3;
             }
-        } else {
-            self.config.save()?;
-            self.sync_runtime_to_config();
 
-            if let Some(status) = status {
-                self.set_status(status);
+            app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+
+            assert_eq!(
+                app.config_editor_state().map(|editor| editor.tab),
+                Some(ConfigTab::Places)
+            );
+            assert_eq!(
+                app.config_draft
+                    .as_ref()
+                    .map(|draft| draft.effective_anchor_city_code()),
+                Some("WLG".to_string())
+            );
+            assert_eq!(
+                app.config_draft
+                    .as_ref()
+                    .map(|draft| draft.effective_target_city_codes()),
+                Some(vec!["CPH".to_string(), "TYO".to_string()])
+            );
+        });
+    }
+
+    #[test]
+    fn actions_tab_can_toggle_map() {
+        let mut app = App::new(Config::default());
+        app.open_config_editor();
+        if let Some(editor) = app.config_editor.as_mut() {
+            editor.tab = ConfigTab::Actions;
+            editor.selected = 5;
+        }
+
+        app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+
+        assert_eq!(
+            app.config_draft
+                .as_ref()
+                .and_then(|draft| draft.map.as_ref())
+                .map(|map| map.enabled),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn cycling_time_keeps_currency_aligned_to_same_target_city() {
+        let mut app = App::new(Config::default());
+        app.focus = Focus::TimeConvert;
+
+        app.handle_normal_input(crossterm::event::KeyCode::Char(' '));
+
+        assert_eq!(app.time_converter.to_city_code, "LDN");
+        assert_eq!(app.currency_converter.to_currency, "GBP");
+    }
+
+    #[test]
+    fn cycling_currency_keeps_time_aligned_to_same_target_city() {
+        let mut app = App::new(Config::default());
+        app.focus = Focus::Currency;
+
+        app.handle_normal_input(crossterm::event::KeyCode::Char(' '));
+
+        assert_eq!(app.time_converter.to_city_code, "LDN");
+        assert_eq!(app.currency_converter.to_currency, "GBP");
+    }
+
+    #[test]
+    fn cycling_from_city_skips_target_and_realigns_currency() {
+        let config = Config {
+            time: Some(TimeConfig {
+                anchor_city_code: Some("WLG".to_string()),
+                target_city_codes: ["BOS", "LDN", "TYO"].map(String::from).to_vec(),
+                city_codes: Vec::new(),
+            }),
+            ..Config::default()
+        };
+        let mut app = App::new(config);
+        app.focus = Focus::TimeConvert;
+        app.set_current_target_city("LDN");
+        assert_eq!(app.time_converter.from_city_code, "WLG");
+
+        // from steps past the anchor to the first target that isn't the destination
+        app.handle_normal_input(crossterm::event::KeyCode::Char('f'));
+        assert_eq!(app.time_converter.from_city_code, "BOS");
+        assert_eq!(app.time_converter.to_city_code, "LDN");
+        assert_eq!(app.currency_converter.from_currency, "USD");
+
+        // cycling the target keeps the chosen from city
+        app.handle_normal_input(crossterm::event::KeyCode::Char(' '));
+        assert_eq!(app.time_converter.to_city_code, "TYO");
+        assert_eq!(app.time_converter.from_city_code, "BOS");
+        assert_eq!(app.currency_converter.from_currency, "USD");
+    }
+
+    #[test]
+    fn broadcast_lists_input_time_in_every_other_city() {
+        let mut app = App::new(Config::default());
+        app.focus = Focus::TimeConvert;
+        app.update_times();
+        app.handle_normal_input(crossterm::event::KeyCode::Char('b'));
+        assert!(app.broadcast_expanded);
+
+        let rows = app.broadcast_rows();
+        let codes: Vec<&str> = rows.iter().map(|row| row.0.as_str()).collect();
+        assert!(!codes.contains(&"WLG"));
+        assert!(codes.contains(&"BOS") && codes.contains(&"LDN") && codes.contains(&"TYO"));
+        for (code, _, hour, minute, _) in &rows {
+            let expected = app.timezone_service.convert_time(
+                "WLG",
+                code,
+                app.time_converter.input_hour,
+                app.time_converter.input_minute,
+            );
+            assert_eq!(expected.map(|(h, m, _)| (h, m)), Some((*hour, *minute)));
+        }
+    }
+
+    #[test]
+    fn calendar_command_opens_current_month_and_pages() {
+        let mut app = App::new(Config::default());
+        app.show_splash = false;
+        app.update_times();
+        app.command_buffer = "/cal".to_string();
+        app.execute_command();
+        assert_eq!(app.overlay, Some(Overlay::Calendar));
+
+        let today = app.anchor_today();
+        assert_eq!(app.calendar_month, first_of_month(today));
+        let weeks = app.calendar_weeks();
+        assert!(weeks.iter().all(|week| week.len() == 7));
+        let today_cells: Vec<&CalendarCell> =
+            weeks.iter().flatten().filter(|cell| cell.today).collect();
+        assert_eq!(today_cells.len(), 1);
+        assert_eq!(today_cells[0].anchor, today);
+        assert!(today_cells[0].home.is_some());
+
+        app.handle_key(crossterm::event::KeyCode::Char('l'));
+        assert_eq!(app.calendar_month, shift_month(today, 1));
+        app.handle_key(crossterm::event::KeyCode::Left);
+        app.handle_key(crossterm::event::KeyCode::Left);
+        assert_eq!(app.calendar_month, shift_month(today, -1));
+    }
+
+    #[test]
+    fn scrub_mode_steps_half_hours_and_esc_returns_to_now() {
+        let mut app = App::new(Config::default());
+        app.show_splash = false;
+        app.focus = Focus::TimeConvert;
+        app.update_times();
+        app.time_converter.input_hour = 10;
+        app.time_converter.input_minute = 0;
+        app.update_time_conversion();
+
+        app.handle_key(crossterm::event::KeyCode::Char('S'));
+        assert!(app.is_scrubbing());
+        app.handle_key(crossterm::event::KeyCode::Char('3'));
+        app.handle_key(crossterm::event::KeyCode::Right);
+        assert_eq!(app.time_converter.format_input_time(true), "11:30");
+        app.handle_key(crossterm::event::KeyCode::Char('h'));
+        assert_eq!(app.time_converter.format_input_time(true), "11:00");
+        assert_eq!(app.scrub_offset_minutes, 60);
+
+        let expected = app.timezone_service.convert_time("WLG", "BOS", 11, 0);
+        let (hour, minute, day_offset) = expected.expect("BOS should convert");
+        assert_eq!(
+            (
+                app.time_converter.result_hour,
+                app.time_converter.result_minute,
+                app.time_converter.day_offset
+            ),
+            (hour, minute, day_offset)
+        );
+
+        app.handle_key(crossterm::event::KeyCode::Esc);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.scrub_offset_minutes, 0);
+        let now = app.current_city_time.as_ref().unwrap().datetime;
+        assert_eq!(app.time_converter.input_hour, now.hour());
+    }
+
+    #[test]
+    fn tour_steps_through_panels_and_passes_other_keys() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.show_splash = false;
+            app.command_buffer = "/tour".to_string();
+            app.execute_command();
+            app.command_buffer.clear();
+            assert_eq!(app.tour_step, Some(0));
+            let (_, total, step) = app.current_tour_step().unwrap();
+            assert_eq!(step.target, TourTarget::Screen);
+
+            app.handle_key(crossterm::event::KeyCode::Char('>'));
+            let (index, _, step) = app.current_tour_step().unwrap();
+            assert_eq!(index, 1);
+            assert_eq!(step.target, TourTarget::Panel(app.focus));
+
+            app.handle_key(crossterm::event::KeyCode::Char('<'));
+            assert_eq!(app.tour_step, Some(0));
+            app.handle_key(crossterm::event::KeyCode::Char('<'));
+            assert_eq!(app.tour_step, Some(0));
+
+            // keys the tour doesn't use still reach the app
+            app.handle_key(crossterm::event::KeyCode::Char('t'));
+            assert!(!app.config.display.use_24_hour);
+            assert_eq!(app.tour_step, Some(0));
+
+            for _ in 0..total {
+                app.handle_key(crossterm::event::KeyCode::Char('>'));
             }
+            assert_eq!(app.tour_step, None);
+
+            app.start_tour();
+            app.handle_key(crossterm::event::KeyCode::Esc);
+            assert_eq!(app.tour_step, None);
+            assert!(app.running);
+        });
+    }
+
+    #[test]
+    fn swapping_time_keeps_currency_aligned() {
+        let mut app = App::new(Config::default());
+        app.focus = Focus::TimeConvert;
+
+        app.handle_normal_input(crossterm::event::KeyCode::Char('s'));
+
+        assert_eq!(app.time_converter.to_city_code, "WLG");
+        assert_eq!(app.currency_converter.to_currency, "NZD");
+    }
+
+    #[test]
+    fn picker_can_apply_country_selection() {
+        let mut app = App::new(Config::default());
+        app.open_picker(PickerKind::Country);
+        if let Some(picker) = &mut app.picker {
+            picker.query = "uk".to_string();
         }
 
-        Ok(())
+        let choice = app
+            .current_picker_choice()
+            .expect("picker should return a choice");
+        let mut config = Config::default();
+        let action = match choice {
+            PickerChoice::Country { code, name } => CommandAction::SetFocalCountry { code, name },
+            other => panic!("unexpected picker choice: {other:?}"),
+        };
+        apply_command_action_to_config(&mut config, &action)
+            .expect("config mutation should succeed");
+
+        assert_eq!(
+            config
+                .time
+                .as_ref()
+                .and_then(|time| time.anchor_city_code.as_deref()),
+            Some("LDN")
+        );
     }
 
-    fn apply_immediate_config_command(&mut self, action: CommandAction) -> Result<()> {
-        let status = apply_command_action_to_config(&mut self.config, &action)
-            .map_err(|message| anyhow!(message))?;
-
-        if let Some(draft) = self.config_draft.as_mut() {
-            apply_command_action_to_config(draft, &action).map_err(|message| anyhow!(message))?;
+    #[test]
+    fn picker_can_apply_place_currency_selection() {
+        let mut app = App::new(Config::default());
+        app.open_picker(PickerKind::PlaceCurrency);
+        if let Some(picker) = &mut app.picker {
+            picker.query = "yen".to_string();
         }
 
-        self.config.save()?;
-        self.sync_runtime_to_config();
+        let choice = app
+            .current_picker_choice()
+            .expect("picker should return a choice");
 
-        if let Some(status) = status {
-            self.set_status(status);
-        }
+        let mut config = Config::default();
+        let action = match choice {
+            PickerChoice::Currency { code, name } => CommandAction::AddPlaceCurrency { code, name },
+            other => panic!("unexpected picker choice: {other:?}"),
+        };
+        apply_command_action_to_config(&mut config, &action)
+            .expect("config mutation should succeed");
 
-        Ok(())
+        let time = config.time.as_ref().expect("time config should exist");
+        assert_eq!(time.target_city_codes, vec!["TYO".to_string()]);
     }
 
-    fn begin_config_draft(&mut self) {
-        if self.config_draft.is_none() {
-            self.config_draft = Some(self.config.clone());
+    #[test]
+    fn config_draft_can_be_opened_reset_and_discarded() {
+        let mut app = App::new(Config::default());
+
+        app.begin_config_draft();
+        assert!(app.has_config_draft());
+
+        if let Some(draft) = app.config_draft.as_mut() {
+            draft.time = Some(TimeConfig {
+                anchor_city_code: Some("BOS".to_string()),
+                target_city_codes: vec!["TYO".to_string()],
+                city_codes: Vec::new(),
+            });
         }
-        self.clamp_config_editor_selection();
-        self.set_status(
-            "Config draft opened. Use /apply, /discard, /reset, or /restore".to_string(),
-        );
-    }
 
-    fn apply_config_draft(&mut self) -> Result<()> {
-        let Some(draft) = self.config_draft.take() else {
-            self.set_status("No config draft to apply".to_string());
-            return Ok(());
-        };
+        app.reset_config_draft();
+        assert_eq!(
+            app.config_draft
+                .as_ref()
+                .map(|draft| draft.effective_anchor_city_code()),
+            Some("WLG".to_string())
+        );
 
-        self.config.save_snapshot()?;
-        self.config = draft;
-        self.config.save()?;
-        self.sync_runtime_to_config();
-        self.config_editor = None;
-        self.set_status("Config draft applied".to_string());
-        Ok(())
+        app.discard_config_draft();
+        assert!(!app.has_config_draft());
     }
 
-    fn discard_config_draft(&mut self) {
-        if self.config_draft.take().is_some() {
-            self.config_editor = None;
-            self.set_status("Config draft discarded".to_string());
-        } else {
-            self.set_status("No config draft to discard".to_string());
-        }
-    }
+    #[test]
+    fn config_command_opens_editor_and_draft() {
+        let mut app = App::new(Config::default());
+        app.command_buffer = "/config".to_string();
 
-    fn reset_config_draft(&mut self) {
-        let was_editing = self.config_draft.is_some();
-        let mut draft = self
-            .config_draft
-            .take()
-            .unwrap_or_else(|| self.config.clone());
-        reset_places_to_package_defaults(&mut draft);
-        self.config_draft = Some(draft);
-        self.picker = None;
-        if let Some(editor) = self.config_editor.as_mut() {
-            editor.tab = ConfigTab::Places;
-            editor.selected = 0;
-        }
-        self.clamp_config_editor_selection();
-        self.set_status(if was_editing {
-            "Places reset in draft. Use Apply to write config.toml".to_string()
-        } else {
-            "Package default places draft opened. Use Apply to write config.toml".to_string()
-        });
-    }
+        app.execute_command();
 
-    fn restore_config_draft(&mut self) -> Result<()> {
-        let restored = Config::load_latest_snapshot()?;
-        self.config_draft = Some(restored);
-        self.picker = None;
-        if let Some(editor) = self.config_editor.as_mut() {
-            editor.tab = ConfigTab::Places;
-            editor.selected = 0;
-        }
-        self.clamp_config_editor_selection();
-        self.set_status(
-            "Latest saved preferences loaded into draft. Use /apply to save".to_string(),
+        assert!(app.has_config_draft());
+        assert_eq!(
+            app.config_editor_state().map(|editor| editor.tab),
+            Some(ConfigTab::Places)
         );
-        Ok(())
     }
 
-    fn sync_runtime_to_config(&mut self) {
-        let currency_pairs = self.config.effective_currency_pairs();
-        let (from_currency, to_currency) = self.config.effective_default_currency_pair();
-        self.currency_converter =
-            CurrencyConverter::new_with_pairs(&from_currency, &to_currency, currency_pairs);
-        let (from_city_code, to_city_code) = self.config.effective_default_time_pair();
-        self.time_converter = TimeConverter::new(&from_city_code, &to_city_code);
+    #[test]
+    fn reset_command_applies_package_defaults_immediately() {
+        with_temp_config_dir_for_test(|| {
+            let config = Config {
+                time: Some(TimeConfig {
+                    anchor_city_code: Some("TYO".to_string()),
+                    target_city_codes: vec!["PAR".to_string()],
+                    city_codes: Vec::new(),
+                }),
+                ..Config::default()
+            };
+            config.save().expect("config should save");
 
-        self.weather_city_index = NZ_CITIES
-            .iter()
-            .position(|c| c.code == self.config.current_city.code)
-            .unwrap_or(0);
-        self.current_weather = None;
-        self.weather_error = None;
-        self.weather_expanded = true;
-        self.weather_refresh_pending = true;
-        if !self.map_enabled() && self.focus == Focus::Map {
-            self.focus = Focus::Weather;
-            self.map_context = Focus::Weather;
-        }
+            let mut app = App::new(config);
+            app.command_buffer = "/reset".to_string();
+            app.execute_command();
 
-        self.update_times();
-        self.update_time_conversion();
+            let reloaded = Config::load().expect("config should reload");
+            assert_eq!(
+                reloaded.effective_anchor_city_code(),
+                Config::default().effective_anchor_city_code()
+            );
+            assert_eq!(
+                reloaded.effective_target_city_codes(),
+                Config::default().effective_target_city_codes()
+            );
+            assert!(app.config_draft.is_none());
+        });
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::with_temp_config_dir_for_test;
 
     #[test]
-    fn parses_country_alias_command() {
-        let action = parse_command("/country uk").expect("command should parse");
+    fn config_editor_can_add_and_remove_target_city() {
+        let mut app = App::new(Config::default());
+        app.open_config_editor();
+
+        if let Some(editor) = app.config_editor.as_mut() {
+            editor.tab = ConfigTab::Places;
+        }
 
+        app.add_target_city_to_draft("TYO")
+            .expect("should add target city");
         assert_eq!(
-            action,
-            CommandAction::SetFocalCountry {
-                code: "GBR".to_string(),
-                name: "United Kingdom".to_string(),
-            }
+            app.config_draft
+                .as_ref()
+                .and_then(|draft| draft.time.as_ref())
+                .map(|time| time.target_city_codes.clone()),
+            Some(vec!["TYO".to_string()])
         );
-    }
 
-    #[test]
-    fn parses_currency_command_to_place_add() {
-        let action = parse_command("/currency yen").expect("command should parse");
+        if let Some(editor) = app.config_editor.as_mut() {
+            editor.selected = 1;
+        }
+        app.remove_config_editor_item()
+            .expect("should remove selected target city");
 
         assert_eq!(
-            action,
-            CommandAction::AddPlaceCurrency {
-                code: "JPY".to_string(),
-                name: "Japanese yen".to_string(),
-            }
+            app.config_draft
+                .as_ref()
+                .and_then(|draft| draft.time.as_ref())
+                .map(|time| time.target_city_codes.clone()),
+            Some(Vec::new())
         );
     }
 
     #[test]
-    fn parses_bare_country_command_to_picker() {
-        let action = parse_command("/country").expect("command should parse");
+    fn config_editor_can_reorder_target_cities() {
+        let mut app = App::new(Config::default());
+        app.open_config_editor();
 
-        assert_eq!(action, CommandAction::OpenCountryPicker);
-    }
+        if let Some(draft) = app.config_draft.as_mut() {
+            draft.time = Some(TimeConfig {
+                anchor_city_code: Some("WLG".to_string()),
+                target_city_codes: vec!["BOS".to_string(), "TYO".to_string()],
+                city_codes: Vec::new(),
+            });
+        }
+        if let Some(editor) = app.config_editor.as_mut() {
+            editor.tab = ConfigTab::Places;
+            editor.selected = 1;
+        }
 
-    #[test]
-    fn parses_config_command_to_draft_mode() {
-        let action = parse_command("/config").expect("command should parse");
+        app.move_config_editor_item(1)
+            .expect("should reorder selected target city");
 
-        assert_eq!(action, CommandAction::EnterConfigDraft);
+        assert_eq!(
+            app.config_draft
+                .as_ref()
+                .and_then(|draft| draft.time.as_ref())
+                .map(|time| time.target_city_codes.clone()),
+            Some(vec!["TYO".to_string(), "BOS".to_string()])
+        );
+        assert_eq!(
+            app.config_editor_state().map(|editor| editor.selected),
+            Some(2)
+        );
     }
 
     #[test]
-    fn applies_currency_command_to_places_config() {
-        let mut config = Config::default();
-        let action = parse_command("/currency yen").expect("command should parse");
+    fn target_city_search_resolves_country_name_to_representative_city() {
+        let mut app = App::new(Config::default());
+        app.open_config_editor();
 
-        let status = apply_command_action_to_config(&mut config, &action)
-            .expect("config mutation should succeed");
+        let city_code = app
+            .search_config_cities("Japan")
+            .into_iter()
+            .find_map(|choice| match choice {
+                PickerChoice::City { code, .. } => Some(code),
+                _ => None,
+            })
+            .expect("country search should resolve to a city");
+        app.add_target_city_to_draft(&city_code)
+            .expect("target add should resolve");
 
         assert_eq!(
-            status.as_deref(),
-            Some("JPY -> Japan -> Tokyo added to target cities")
-        );
-        assert_eq!(
-            config
-                .time
+            app.config_draft
                 .as_ref()
+                .and_then(|draft| draft.time.as_ref())
                 .map(|time| time.target_city_codes.clone()),
             Some(vec!["TYO".to_string()])
         );
     }
 
     #[test]
-    fn currency_command_adds_missing_representative_city_to_catalogue() {
+    fn target_city_search_can_use_generated_representative_city() {
         let mut config = Config::default();
         config.tracked_cities.clear();
-        let action = parse_command("/currency yen").expect("command should parse");
+        let mut app = App::new(config);
+        app.open_config_editor();
 
-        apply_command_action_to_config(&mut config, &action)
-            .expect("config mutation should succeed");
+        let city_code = app
+            .search_config_cities("France")
+            .into_iter()
+            .find_map(|choice| match choice {
+                PickerChoice::City { code, .. } => Some(code),
+                _ => None,
+            })
+            .expect("country search should resolve to a city");
+        app.add_target_city_to_draft(&city_code)
+            .expect("target add should resolve");
 
         assert!(
-            config
-                .tracked_cities
-                .iter()
-                .any(|city| city.code.eq_ignore_ascii_case("TYO"))
+            app.config_draft
+                .as_ref()
+                .map(|draft| draft
+                    .tracked_cities
+                    .iter()
+                    .any(|city| city.code.eq_ignore_ascii_case("PAR")))
+                .unwrap_or(false)
         );
         assert_eq!(
-            config
-                .time
+            app.config_draft
                 .as_ref()
+                .and_then(|draft| draft.time.as_ref())
                 .map(|time| time.target_city_codes.clone()),
-            Some(vec!["TYO".to_string()])
+            Some(vec!["PAR".to_string()])
         );
     }
 
     #[test]
-    fn applies_map_enabled_command_to_config() {
-        let mut config = Config::default();
-        let action = parse_command("/map on").expect("command should parse");
+    fn places_currency_helper_resolves_to_representative_city() {
+        let mut app = App::new(Config::default());
+        app.open_config_editor();
 
-        apply_command_action_to_config(&mut config, &action)
-            .expect("config mutation should succeed");
+        app.apply_config_command(CommandAction::AddPlaceCurrency {
+            code: "JPY".to_string(),
+            name: "Japanese yen".to_string(),
+        })
+        .expect("currency helper should resolve");
 
-        assert_eq!(config.map.as_ref().map(|map| map.enabled), Some(true));
+        assert_eq!(
+            app.config_draft
+                .as_ref()
+                .and_then(|draft| draft.time.as_ref())
+                .map(|time| time.target_city_codes.clone()),
+            Some(vec!["TYO".to_string()])
+        );
     }
 
     #[test]
-    fn map_command_applies_immediately_even_with_saved_draft() {
-        with_temp_config_dir_for_test(|| {
-            let mut app = App::new(Config::default());
-            app.open_config_editor();
-            app.close_config_editor();
-            app.command_buffer = "/map on".to_string();
-
-            app.execute_command();
-
-            assert_eq!(app.config.map.as_ref().map(|map| map.enabled), Some(true));
-            assert_eq!(
-                app.config_draft
-                    .as_ref()
-                    .and_then(|draft| draft.map.as_ref())
-                    .map(|map| map.enabled),
-                Some(true)
-            );
-
-            let saved = Config::load().expect("config should reload");
-            assert_eq!(saved.map.as_ref().map(|map| map.enabled), Some(true));
+    fn city_picker_uses_representative_cities() {
+        let mut config = Config::default();
+        config.tracked_cities.push(City {
+            name: "Honolulu".to_string(),
+            code: "HNL".to_string(),
+            country: "USA".to_string(),
+            timezone: "Pacific/Honolulu".to_string(),
+            currency: "USD".to_string(),
         });
-    }
 
-    #[test]
-    fn map_picker_can_disable_map() {
-        let mut app = App::new(Config::default());
-        app.open_picker(PickerKind::MapMode);
-        if let Some(picker) = app.picker.as_mut() {
-            picker.query = "hide".to_string();
-        }
+        let mut app = App::new(config);
+        app.open_picker(PickerKind::TargetCity);
 
-        let choice = app
-            .current_picker_choice()
-            .expect("picker should return a choice");
-        let mut config = Config::default();
-        match choice {
-            PickerChoice::MapEnabled { enabled, .. } => {
-                apply_command_action_to_config(
-                    &mut config,
-                    &CommandAction::SetMapEnabled { enabled },
-                )
-                .expect("config mutation should succeed");
-            }
-            other => panic!("unexpected picker choice: {other:?}"),
-        }
+        let choices = app.picker_choices();
+        let city_codes: Vec<String> = choices
+            .into_iter()
+            .filter_map(|choice| match choice {
+                PickerChoice::City { code, .. } => Some(code),
+                _ => None,
+            })
+            .collect();
 
-        assert_eq!(config.map.as_ref().map(|map| map.enabled), Some(false));
+        assert!(city_codes.iter().any(|code| code == "BOS"));
+        assert!(city_codes.iter().any(|code| code == "THR"));
+        assert!(city_codes.iter().any(|code| code == "HNL"));
     }
 
     #[test]
-    fn map_focus_uses_configured_map_when_panel_is_focused() {
+    fn target_city_picker_can_add_generated_city_outside_current_catalogue() {
         let mut app = App::new(Config::default());
-        app.focus = Focus::Map;
-        app.map_context = Focus::Weather;
+        app.open_config_editor();
+        app.add_target_city_to_draft("THR")
+            .expect("generated representative city should add");
 
-        assert_eq!(app.active_map_focus(), Focus::Map);
+        assert!(
+            app.config_draft
+                .as_ref()
+                .map(|draft| draft
+                    .tracked_cities
+                    .iter()
+                    .any(|city| city.code.eq_ignore_ascii_case("THR")))
+                .unwrap_or(false)
+        );
+        assert_eq!(
+            app.config_draft
+                .as_ref()
+                .and_then(|draft| draft.time.as_ref())
+                .map(|time| time.target_city_codes.clone()),
+            Some(vec!["THR".to_string()])
+        );
     }
 
     #[test]
-    fn hidden_map_is_skipped_in_focus_navigation() {
-        let mut config = Config::default();
-        config.map = Some(MapConfig {
-            enabled: false,
-            ..MapConfig::default()
-        });
-        let mut app = App::new(config);
-        app.focus = Focus::Currency;
+    fn applying_generated_city_with_fixed_offset_timezone_saves_successfully() {
+        with_temp_config_dir_for_test(|| {
+            let config = Config::default();
+            config.save().expect("default config should save");
 
-        app.handle_normal_input(crossterm::event::KeyCode::Tab);
-        assert_eq!(app.focus, Focus::Weather);
+            let mut app = App::new(config);
+            app.open_config_editor();
+            app.add_target_city_to_draft("KOR")
+                .expect("generated representative city should add");
+            app.apply_config_draft()
+                .expect("draft with fixed offset timezone should save");
 
-        app.handle_normal_input(crossterm::event::KeyCode::BackTab);
-        assert_eq!(app.focus, Focus::Currency);
+            let saved = Config::load().expect("saved config should reload");
+            assert!(
+                saved
+                    .tracked_cities
+                    .iter()
+                    .any(|city| city.code.eq_ignore_ascii_case("KOR"))
+            );
+            assert_eq!(saved.effective_target_city_codes(), vec!["KOR".to_string()]);
+        });
     }
 
     #[test]
-    fn actions_tab_apply_saves_and_closes_editor() {
+    fn clock_toggle_key_flips_and_persists_the_clock_style() {
         with_temp_config_dir_for_test(|| {
             let mut app = App::new(Config::default());
-            app.open_config_editor();
-            if let Some(draft) = app.config_draft.as_mut() {
-                draft.time = Some(TimeConfig {
-                    anchor_city_code: Some("WLG".to_string()),
-                    target_city_codes: vec!["TYO".to_string()],
-                    city_codes: Vec::new(),
-                });
-            }
-            if let Some(editor) = app.config_editor.as_mut() {
-                editor.tab = ConfigTab::Actions;
-                editor.selected = 0;
-            }
+            app.handle_normal_input(crossterm::event::KeyCode::Char('t'));
+            assert!(!app.config.display.use_24_hour);
 
-            app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+            let saved = Config::load().expect("config should reload");
+            assert!(!saved.display.use_24_hour);
 
-            assert!(app.config_editor.is_none());
-            assert!(app.config_draft.is_none());
-            assert_eq!(
-                app.config.effective_target_city_codes(),
-                vec!["TYO".to_string()]
-            );
+            app.command_buffer = "/clock 24".to_string();
+            app.execute_command();
+            assert!(app.config.display.use_24_hour);
         });
     }
 
     #[test]
-    fn actions_tab_reset_replaces_draft() {
-        let mut app = App::new(Config::default());
-        app.open_config_editor();
-        if let Some(draft) = app.config_draft.as_mut() {
-            draft.current_city.code = "AKL".to_string();
-            draft.current_city.name = "Auckland".to_string();
-            draft.home_city.code = "CPH".to_string();
-            draft.home_city.name = "Copenhagen".to_string();
-            draft.tracked_cities.clear();
-            draft.time = Some(TimeConfig {
-                anchor_city_code: Some("WLG".to_string()),
-                target_city_codes: vec!["PAR".to_string()],
-                city_codes: Vec::new(),
-            });
-        }
-        if let Some(editor) = app.config_editor.as_mut() {
-            editor.tab = ConfigTab::Actions;
-            editor.selected = 2;
-        }
+    fn event_command_schedules_and_clear_removes_events() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.command_buffer = "/event tomorrow 19:00 BOS Call with Mum".to_string();
+            app.execute_command();
 
-        app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+            assert_eq!(app.config.events.len(), 1);
+            assert_eq!(app.config.events[0].title, "Call with Mum");
+            let upcoming = app.next_event().expect("event should be upcoming");
+            assert_eq!(upcoming.city_code, "BOS");
 
-        assert!(app.config_editor.is_some());
-        assert!(app.config_draft.is_some());
-        assert_eq!(
-            app.config_draft
-                .as_ref()
-                .map(|draft| draft.effective_anchor_city_code()),
-            Some(Config::default().effective_anchor_city_code())
-        );
-        assert_eq!(
-            app.config_draft
-                .as_ref()
-                .map(|draft| draft.effective_target_city_codes()),
-            Some(Config::default().effective_target_city_codes())
-        );
+            let saved = Config::load().expect("config should reload");
+            assert_eq!(saved.events, app.config.events);
+
+            app.command_buffer = "/event clear".to_string();
+            app.execute_command();
+            assert!(app.config.events.is_empty());
+        });
     }
 
     #[test]
-    fn actions_tab_reset_preserves_map_visibility() {
-        let mut config = Config::default();
-        config.map = Some(MapConfig {
-            enabled: true,
-            ..MapConfig::default()
-        });
+    fn event_reminder_fires_once() {
+        let soon = (Utc::now() + ChronoDuration::minutes(5))
+            .with_timezone(&chrono_tz::America::New_York)
+            .format(crate::events::EVENT_TIME_FORMAT)
+            .to_string();
+        let config = Config {
+            events: vec![EventConfig {
+                title: "Call".to_string(),
+                city_code: "BOS".to_string(),
+                at: soon,
+            }],
+            ..Config::default()
+        };
         let mut app = App::new(config);
-        app.open_config_editor();
-        if let Some(draft) = app.config_draft.as_mut() {
-            draft.time = Some(TimeConfig {
-                anchor_city_code: Some("BOS".to_string()),
-                target_city_codes: vec!["TYO".to_string()],
-                city_codes: Vec::new(),
-            });
-        }
-        if let Some(editor) = app.config_editor.as_mut() {
-            editor.tab = ConfigTab::Actions;
-            editor.selected = 2;
-        }
 
-        app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+        app.tick();
+        let status = app.status_message.clone().expect("reminder should post");
+        assert!(status.0.starts_with("⏰ Call in"));
 
-        assert_eq!(
-            app.config_draft
-                .as_ref()
-                .and_then(|draft| draft.map.as_ref())
-                .map(|map| map.enabled),
-            Some(true)
-        );
-        assert_eq!(
-            app.config_draft
-                .as_ref()
-                .map(|draft| draft.effective_anchor_city_code()),
-            Some(Config::default().effective_anchor_city_code())
-        );
+        app.status_message = None;
+        app.tick();
+        assert!(app.status_message.is_none());
     }
 
     #[test]
-    fn actions_tab_reload_restores_saved_preferences_from_disk() {
+    fn passed_events_are_dropped_on_tick() {
         with_temp_config_dir_for_test(|| {
-            let mut saved = Config::default();
-            saved.time = Some(TimeConfig {
-                anchor_city_code: Some("WLG".to_string()),
-                target_city_codes: vec!["CPH".to_string(), "TYO".to_string()],
-                city_codes: Vec::new(),
-            });
-            saved.tracked_cities.push(City {
-                name: "Copenhagen".to_string(),
-                code: "CPH".to_string(),
-                country: "Denmark".to_string(),
-                timezone: "Europe/Copenhagen".to_string(),
-                currency: "DKK".to_string(),
+            let boston_at = |offset: ChronoDuration| {
+                (Utc::now() + offset)
+                    .with_timezone(&chrono_tz::America::New_York)
+                    .format(crate::events::EVENT_TIME_FORMAT)
+                    .to_string()
+            };
+            let event = |title: &str, at: String| EventConfig {
+                title: title.to_string(),
+                city_code: "BOS".to_string(),
+                at,
+            };
+            let config = Config {
+                events: vec![
+                    event("Done", boston_at(ChronoDuration::hours(-2))),
+                    event("Ahead", boston_at(ChronoDuration::days(2))),
+                ],
+                ..Config::default()
+            };
+            let mut app = App::new(config);
+
+            app.tick();
+            let titles: Vec<&str> = app.config.events.iter().map(|e| e.title.as_str()).collect();
+            assert_eq!(titles, ["Ahead"]);
+            assert_eq!(Config::load().unwrap().events, app.config.events);
+        });
+    }
+
+    #[tokio::test]
+    async fn alarms_persist_until_they_ring() {
+        with_temp_config_dir_for_test(|| {
+            let mut config = Config::default();
+            config.alerts = Some(crate::config::AlertsConfig {
+                alarm: Some("true".to_string()),
+                ..Default::default()
             });
-            saved.save().expect("saved config should write");
+            let mut app = App::new(config);
+            app.command_buffer = "/alarm 09:00 LDN".to_string();
+            app.execute_command();
+            let status = app.status_message.clone().expect("alarm should confirm");
+            assert!(status.0.starts_with("Alarm set for 09:00 in London"));
+            assert_eq!(Config::load().unwrap().alarms.len(), 1);
+
+            let london_at = |offset: ChronoDuration| {
+                (Utc::now() + offset)
+                    .with_timezone(&chrono_tz::Europe::London)
+                    .format(crate::events::EVENT_TIME_FORMAT)
+                    .to_string()
+            };
+            app.config.alarms = vec![
+                AlarmConfig {
+                    city_code: "LDN".to_string(),
+                    at: london_at(ChronoDuration::minutes(-1)),
+                },
+                AlarmConfig {
+                    city_code: "LDN".to_string(),
+                    at: london_at(ChronoDuration::hours(-3)),
+                },
+            ];
+            app.tick();
+            let status = app.status_message.clone().expect("alarm should ring");
+            assert!(status.0.starts_with("⏰ "), "{}", status.0);
+            assert!(app.config.alarms.is_empty());
+            assert!(Config::load().unwrap().alarms.is_empty());
+
+            app.command_buffer = "/alarms".to_string();
+            app.execute_command();
+            let status = app.status_message.clone().unwrap();
+            assert!(status.0.starts_with("No alarms set"));
+        });
+    }
 
-            let mut app = App::new(saved.clone());
-            app.open_config_editor();
-            if let Some(draft) = app.config_draft.as_mut() {
-                draft.time = Some(TimeConfig {
-                    anchor_city_code: Some("TYO".to_string()),
-                    target_city_codes: vec!["PAR".to_string()],
-                    city_codes: Vec::new(),
-                });
-            }
-            if let Some(editor) = app.config_editor.as_mut() {
-                editor.tab = ConfigTab::Actions;
-                editor.selected = 3;
-            }
+    #[tokio::test]
+    async fn weather_alert_cues_once_until_it_clears() {
+        let config = Config {
+            alerts: Some(crate::config::AlertsConfig {
+                rain: Some("true".to_string()),
+                ..Default::default()
+            }),
+            ..Config::default()
+        };
+        let mut app = App::new(config);
+        let weather = CurrentWeather {
+            temp_c: 12,
+            feels_like_c: 10,
+            humidity: 80,
+            wind_kmph: 90,
+            wind_dir: "N".to_string(),
+            description: "Overcast".to_string(),
+            icon: crate::weather::WeatherIcon::Cloudy,
+            is_day: true,
+            last_updated: Instant::now(),
+            forecast: Vec::new(),
+            next_hours: vec![crate::weather::WeatherIcon::Rain],
+            hourly_temps: Vec::new(),
+            uv_index: None,
+        };
 
-            app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+        app.current_weather = Some(weather.clone());
+        app.check_weather_alerts();
+        let status = app.status_message.clone().expect("rain cue should post");
+        assert_eq!(status.0, "🔔 Rain expected soon in Wellington");
 
-            assert_eq!(
-                app.config_editor_state().map(|editor| editor.tab),
-                Some(ConfigTab::Places)
-            );
-            assert_eq!(
-                app.config_draft
-                    .as_ref()
-                    .map(|draft| draft.effective_anchor_city_code()),
-                Some("WLG".to_string())
-            );
-            assert_eq!(
-                app.config_draft
-                    .as_ref()
-                    .map(|draft| draft.effective_target_city_codes()),
-                Some(vec!["CPH".to_string(), "TYO".to_string()])
-            );
+        // wind is severe but has no cue configured
+        app.status_message = None;
+        app.check_weather_alerts();
+        assert!(app.status_message.is_none());
+
+        app.current_weather = Some(CurrentWeather {
+            next_hours: Vec::new(),
+            ..weather.clone()
         });
+        app.check_weather_alerts();
+        app.current_weather = Some(weather);
+        app.check_weather_alerts();
+        assert!(app.status_message.is_some());
     }
 
     #[test]
-    fn actions_tab_can_toggle_map() {
+    fn ts_command_renders_epoch_in_from_and_to_cities() {
         let mut app = App::new(Config::default());
-        app.open_config_editor();
-        if let Some(editor) = app.config_editor.as_mut() {
-            editor.tab = ConfigTab::Actions;
-            editor.selected = 5;
-        }
-
-        app.handle_config_editor_input(crossterm::event::KeyCode::Enter);
+        app.time_converter = TimeConverter::new("WLG", "BOS");
 
+        app.command_buffer = "/ts 1700000000".to_string();
+        app.execute_command();
         assert_eq!(
-            app.config_draft
-                .as_ref()
-                .and_then(|draft| draft.map.as_ref())
-                .map(|map| map.enabled),
-            Some(true)
+            app.timestamp_rows(),
+            vec![
+                (
+                    "WLG".to_string(),
+                    "Wed 15 Nov 11:13:20 (UTC+13:00)".to_string()
+                ),
+                (
+                    "BOS".to_string(),
+                    "Tue 14 Nov 17:13:20 (UTC-05:00)".to_string()
+                ),
+            ]
         );
-    }
-
-    #[test]
-    fn cycling_time_keeps_currency_aligned_to_same_target_city() {
-        let mut app = App::new(Config::default());
-        app.focus = Focus::TimeConvert;
 
-        app.handle_normal_input(crossterm::event::KeyCode::Char(' '));
+        app.command_buffer = "/ts soon".to_string();
+        app.execute_command();
+        assert!(app.timestamp.is_some());
 
-        assert_eq!(app.time_converter.to_city_code, "LDN");
-        assert_eq!(app.currency_converter.to_currency, "GBP");
+        app.command_buffer = "/ts clear".to_string();
+        app.execute_command();
+        assert!(app.timestamp_rows().is_empty());
     }
 
     #[test]
-    fn cycling_currency_keeps_time_aligned_to_same_target_city() {
-        let mut app = App::new(Config::default());
-        app.focus = Focus::Currency;
+    fn count_prefix_repeats_cycling_and_hour_steps() {
+        use crossterm::event::KeyCode;
 
-        app.handle_normal_input(crossterm::event::KeyCode::Char(' '));
+        let mut app = App::new(Config::default());
+        app.show_splash = false;
+        app.focus = Focus::Weather;
+        let start = app.weather_city_index;
+        app.handle_key(KeyCode::Char('3'));
+        assert_eq!(app.pending_count, Some(3));
+        app.handle_key(KeyCode::Char(' '));
+        assert_eq!(app.weather_city_index, (start + 3) % NZ_CITIES.len());
+        assert_eq!(app.pending_count, None);
+
+        // the count survives a chord prefix and repeats the chord action
+        let mut expected = App::new(Config::default());
+        expected.run_chord_action(ChordAction::CyclePair);
+        expected.run_chord_action(ChordAction::CyclePair);
+        let mut counted = App::new(Config::default());
+        counted.show_splash = false;
+        counted.focus = Focus::Weather;
+        for key in ['2', 'c', 'p'] {
+            counted.handle_key(KeyCode::Char(key));
+        }
+        assert_eq!(
+            counted.time_converter.to_city_code,
+            expected.time_converter.to_city_code
+        );
+        assert_eq!(counted.pending_count, None);
 
-        assert_eq!(app.time_converter.to_city_code, "LDN");
-        assert_eq!(app.currency_converter.to_currency, "GBP");
+        app.focus = Focus::TimeConvert;
+        app.input_mode = InputMode::EditingTime;
+        app.time_converter.input_hour = 9;
+        app.handle_key(KeyCode::Char('5'));
+        app.handle_key(KeyCode::Char('j'));
+        assert_eq!(app.time_converter.input_hour, 4);
+        app.handle_key(KeyCode::Char('1'));
+        app.handle_key(KeyCode::Char('2'));
+        app.handle_key(KeyCode::Char('k'));
+        assert_eq!(app.time_converter.input_hour, 16);
+
+        // digits stay direct entry where the panel uses them
+        app.input_mode = InputMode::Normal;
+        app.handle_key(KeyCode::Char('7'));
+        assert_eq!(app.pending_count, None);
+        assert!(app.time_converter.is_typing());
     }
 
     #[test]
-    fn swapping_time_keeps_currency_aligned() {
-        let mut app = App::new(Config::default());
-        app.focus = Focus::TimeConvert;
+    fn note_command_saves_note_for_destination_city() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.time_converter = TimeConverter::new("WLG", "BOS");
 
-        app.handle_normal_input(crossterm::event::KeyCode::Char('s'));
+            app.command_buffer = "/note Mum & Dad - don't call before 8am their time".to_string();
+            app.execute_command();
+            assert_eq!(
+                app.config.note_for("bos"),
+                Some("Mum & Dad - don't call before 8am their time")
+            );
+            let saved = Config::load().expect("config should reload");
+            assert_eq!(saved.notes, app.config.notes);
 
-        assert_eq!(app.time_converter.to_city_code, "WLG");
-        assert_eq!(app.currency_converter.to_currency, "NZD");
+            app.command_buffer = "/note".to_string();
+            app.execute_command();
+            assert!(
+                app.status_message
+                    .as_ref()
+                    .is_some_and(|(message, _)| message.starts_with("BOS: Mum"))
+            );
+
+            app.command_buffer = "/note clear".to_string();
+            app.execute_command();
+            assert!(app.config.notes.is_empty());
+        });
     }
 
     #[test]
-    fn picker_can_apply_country_selection() {
+    fn warns_when_converted_date_is_a_destination_holiday() {
         let mut app = App::new(Config::default());
-        app.open_picker(PickerKind::Country);
-        if let Some(picker) = &mut app.picker {
-            picker.query = "uk".to_string();
-        }
-
-        let choice = app
-            .current_picker_choice()
-            .expect("picker should return a choice");
-        let mut config = Config::default();
-        let action = match choice {
-            PickerChoice::Country { code, name } => CommandAction::SetFocalCountry { code, name },
-            other => panic!("unexpected picker choice: {other:?}"),
-        };
-        apply_command_action_to_config(&mut config, &action)
-            .expect("config mutation should succeed");
-
+        app.time_converter = TimeConverter::new("WLG", "BOS");
+        app.update_times();
+        app.update_time_conversion();
+
+        let (alpha2, date) = app.destination_date().expect("destination should resolve");
+        assert_eq!(alpha2, "US");
+        assert!(app.needs_holiday_refresh());
+        assert!(app.destination_holiday().is_none());
+
+        app.holiday_service.store(
+            "US",
+            date.year(),
+            vec![Holiday {
+                date,
+                name: "Independence Day".to_string(),
+            }],
+        );
+        assert!(!app.needs_holiday_refresh());
         assert_eq!(
-            config
-                .time
-                .as_ref()
-                .and_then(|time| time.anchor_city_code.as_deref()),
-            Some("LDN")
+            app.destination_holiday().map(|(_, holiday)| holiday.name),
+            Some("Independence Day".to_string())
         );
     }
 
     #[test]
-    fn picker_can_apply_place_currency_selection() {
-        let mut app = App::new(Config::default());
-        app.open_picker(PickerKind::PlaceCurrency);
-        if let Some(picker) = &mut app.picker {
-            picker.query = "yen".to_string();
-        }
+    fn todo_commands_edit_and_persist_the_checklist() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
 
-        let choice = app
-            .current_picker_choice()
-            .expect("picker should return a choice");
+            for command in [
+                "/todo add travel insurance",
+                "/todo add Book flights",
+                "/todo done 1",
+            ] {
+                app.command_buffer = command.to_string();
+                app.execute_command();
+            }
+            assert_eq!(app.config.todos.len(), 2);
+            assert!(app.config.todos[0].done);
+            assert_eq!(app.config.todos[1].text, "Book flights");
+            assert!(app.checklist_visible());
+            let saved = Config::load().expect("config should reload");
+            assert_eq!(saved.todos, app.config.todos);
 
-        let mut config = Config::default();
-        let action = match choice {
-            PickerChoice::Currency { code, name } => CommandAction::AddPlaceCurrency { code, name },
-            other => panic!("unexpected picker choice: {other:?}"),
-        };
-        apply_command_action_to_config(&mut config, &action)
-            .expect("config mutation should succeed");
+            app.command_buffer = "/todo done 5".to_string();
+            app.execute_command();
+            assert!(
+                app.status_message
+                    .as_ref()
+                    .is_some_and(|(message, _)| message == "no checklist item 5")
+            );
 
-        let time = config.time.as_ref().expect("time config should exist");
-        assert_eq!(time.target_city_codes, vec!["TYO".to_string()]);
+            app.command_buffer = "/todo clear".to_string();
+            app.execute_command();
+            assert_eq!(app.config.todos.len(), 1);
+
+            app.command_buffer = "/todo".to_string();
+            app.execute_command();
+            assert!(!app.checklist_visible());
+        });
+        assert!(parse_command("/todo rm zero").is_err());
     }
 
     #[test]
-    fn config_draft_can_be_opened_reset_and_discarded() {
-        let mut app = App::new(Config::default());
-
-        app.begin_config_draft();
-        assert!(app.has_config_draft());
+    fn ctrl_arrows_resize_the_splits_and_save_them() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-        if let Some(draft) = app.config_draft.as_mut() {
-            draft.time = Some(TimeConfig {
-                anchor_city_code: Some("BOS".to_string()),
-                target_city_codes: vec!["TYO".to_string()],
-                city_codes: Vec::new(),
-            });
-        }
+        let ctrl = |code| KeyEvent::new(code, KeyModifiers::CONTROL);
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.show_splash = false;
+            app.weather_expanded = false;
+            app.handle_key_event(ctrl(KeyCode::Right));
+            app.handle_key_event(ctrl(KeyCode::Up));
+            let layout = app.config.effective_layout();
+            assert_eq!(layout.compact_map_percent, 45);
+            assert_eq!(layout.compact_bottom_height, 12);
+            assert_eq!(layout.map_percent, 33);
+            assert_eq!(
+                Config::load().expect("config should reload").layout,
+                Some(layout)
+            );
 
-        app.reset_config_draft();
-        assert_eq!(
-            app.config_draft
-                .as_ref()
-                .map(|draft| draft.effective_anchor_city_code()),
-            Some("WLG".to_string())
-        );
+            // the expanded view has its own split, held inside the allowed range
+            app.weather_expanded = true;
+            for _ in 0..20 {
+                app.handle_key_event(ctrl(KeyCode::Left));
+            }
+            assert_eq!(app.config.effective_layout().map_percent, 10);
 
-        app.discard_config_draft();
-        assert!(!app.has_config_draft());
+            // without Ctrl the arrows still move focus
+            app.focus = Focus::Weather;
+            app.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+            assert_ne!(app.focus, Focus::Weather);
+            assert_eq!(app.config.effective_layout().bottom_height, 7);
+        });
     }
 
     #[test]
-    fn config_command_opens_editor_and_draft() {
-        let mut app = App::new(Config::default());
-        app.command_buffer = "/config".to_string();
+    fn contrast_command_brightens_dim_text_and_persists() {
+        use crate::theme::{ThemeName, catppuccin};
+        use ratatui::buffer::Buffer;
+        use ratatui::layout::Rect;
 
-        app.execute_command();
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.command_buffer = "/contrast".to_string();
+            app.execute_command();
+            assert!(app.config.display.high_contrast);
+            assert!(
+                Config::load()
+                    .expect("config should reload")
+                    .display
+                    .high_contrast
+            );
 
-        assert!(app.has_config_draft());
-        assert_eq!(
-            app.config_editor_state().map(|editor| editor.tab),
-            Some(ConfigTab::Places)
-        );
+            let mut buf = Buffer::empty(Rect::new(0, 0, 2, 1));
+            buf.content[0].fg = catppuccin::SUBTEXT0;
+            buf.content[1].bg = catppuccin::SURFACE1;
+            theme::recolour(&mut buf, ThemeName::Mocha);
+            assert_eq!(buf.content[0].fg, catppuccin::TEXT);
+            assert_eq!(buf.content[1].bg, catppuccin::SURFACE1);
+
+            app.command_buffer = "/contrast".to_string();
+            app.execute_command();
+            assert!(!app.config.display.high_contrast);
+            assert!(!theme::high_contrast());
+        });
+    }
+
+    #[test]
+    fn anim_command_flips_single_layers_and_persists() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+
+            app.command_buffer = "/anim waves".to_string();
+            app.execute_command();
+            app.command_buffer = "/anim Rainbow off".to_string();
+            app.execute_command();
+            let layers = app.config.display.active_animations();
+            assert!(!layers.waves && !layers.rainbow);
+            assert!(layers.birds && layers.sparkles);
+            let saved = Config::load().expect("config should reload");
+            assert_eq!(saved.display.animations, app.config.display.animations);
+
+            app.command_buffer = "/anim".to_string();
+            app.execute_command();
+            assert!(app.status_message.as_ref().is_some_and(|(message, _)| {
+                message == "Animations: waves off, birds on, sparkles on, rainbow off, flight on"
+            }));
+
+            // the master switch still stops everything
+            app.config.display.show_animations = false;
+            assert_eq!(
+                app.config.display.active_animations(),
+                crate::config::AnimationLayers::NONE
+            );
+        });
+        assert!(parse_command("/anim clouds").is_err());
+        assert!(parse_command("/anim birds maybe").is_err());
     }
 
     #[test]
-    fn reset_command_applies_package_defaults_immediately() {
+    fn map_marker_style_switches_by_command_or_key_and_persists() {
         with_temp_config_dir_for_test(|| {
-            let mut config = Config::default();
-            config.time = Some(TimeConfig {
-                anchor_city_code: Some("TYO".to_string()),
-                target_city_codes: vec!["PAR".to_string()],
-                city_codes: Vec::new(),
+            let mut app = App::new(Config {
+                map: Some(MapConfig {
+                    enabled: true,
+                    ..MapConfig::default()
+                }),
+                ..Config::default()
             });
-            config.save().expect("config should save");
+            app.show_splash = false;
 
-            let mut app = App::new(config);
-            app.command_buffer = "/reset".to_string();
+            app.command_buffer = "/map halfblock".to_string();
             app.execute_command();
-
-            let reloaded = Config::load().expect("config should reload");
-            assert_eq!(
-                reloaded.effective_anchor_city_code(),
-                Config::default().effective_anchor_city_code()
-            );
+            let style = |app: &App| app.config.effective_map_settings().marker_style();
+            assert_eq!(style(&app), MapMarkerStyle::HalfBlock);
+            let saved = Config::load().expect("config should reload");
             assert_eq!(
-                reloaded.effective_target_city_codes(),
-                Config::default().effective_target_city_codes()
+                saved.map.and_then(|map| map.marker_style),
+                Some(MapMarkerStyle::HalfBlock)
             );
-            assert!(app.config_draft.is_none());
+
+            app.command_buffer.clear();
+            app.focus = Focus::Map;
+            app.handle_key(crossterm::event::KeyCode::Char('b'));
+            assert_eq!(style(&app), MapMarkerStyle::Dot);
+            app.handle_key(crossterm::event::KeyCode::Char('b'));
+            assert_eq!(style(&app), MapMarkerStyle::Braille);
         });
+        assert!(parse_command("/map sextant").is_err());
     }
 
     #[test]
-    fn config_editor_can_add_and_remove_target_city() {
+    fn warns_only_when_clock_skew_exceeds_threshold() {
         let mut app = App::new(Config::default());
-        app.open_config_editor();
+        assert!(app.clock_skew_warning().is_none());
 
-        if let Some(editor) = app.config_editor.as_mut() {
-            editor.tab = ConfigTab::Places;
-        }
+        app.clock_skew = Some(-45);
+        assert!(app.clock_skew_warning().is_none());
 
-        app.add_target_city_to_draft("TYO")
-            .expect("should add target city");
+        app.clock_skew = Some(134);
         assert_eq!(
-            app.config_draft
-                .as_ref()
-                .and_then(|draft| draft.time.as_ref())
-                .map(|time| time.target_city_codes.clone()),
-            Some(vec!["TYO".to_string()])
+            app.clock_skew_warning().as_deref(),
+            Some("⚠ System clock is 2m 14s fast")
         );
 
-        if let Some(editor) = app.config_editor.as_mut() {
-            editor.selected = 1;
-        }
-        app.remove_config_editor_item()
-            .expect("should remove selected target city");
-
-        assert_eq!(
-            app.config_draft
-                .as_ref()
-                .and_then(|draft| draft.time.as_ref())
-                .map(|time| time.target_city_codes.clone()),
-            Some(Vec::new())
-        );
+        app.config.display.clock_skew_warn_secs = 0;
+        assert!(app.clock_skew_warning().is_none());
     }
 
     #[test]
-    fn config_editor_can_reorder_target_cities() {
+    fn fx_threshold_rates_are_judged_when_they_arrive() {
         let mut app = App::new(Config::default());
-        app.open_config_editor();
-
-        if let Some(draft) = app.config_draft.as_mut() {
-            draft.time = Some(TimeConfig {
-                anchor_city_code: Some("WLG".to_string()),
-                target_city_codes: vec!["BOS".to_string(), "TYO".to_string()],
-                city_codes: Vec::new(),
-            });
-        }
-        if let Some(editor) = app.config_editor.as_mut() {
-            editor.tab = ConfigTab::Places;
-            editor.selected = 1;
-        }
+        app.config.alerts = Some(crate::config::AlertsConfig {
+            fx_thresholds: vec![FxThreshold {
+                pair: "nzd/usd".to_string(),
+                above: None,
+                below: Some(0.58),
+            }],
+            ..Default::default()
+        });
+        let arrive = |app: &mut App, result: Result<String>| {
+            app.fetched_tx
+                .send(Fetched::FxRate {
+                    from: "NZD".to_string(),
+                    to: "USD".to_string(),
+                    result,
+                })
+                .unwrap();
+            app.receive_fetched();
+        };
 
-        app.move_config_editor_item(1)
-            .expect("should reorder selected target city");
+        arrive(&mut app, Err(anyhow::anyhow!("offline")));
+        assert!(app.active_fx_alerts.is_empty());
 
-        assert_eq!(
-            app.config_draft
-                .as_ref()
-                .and_then(|draft| draft.time.as_ref())
-                .map(|time| time.target_city_codes.clone()),
-            Some(vec!["TYO".to_string(), "BOS".to_string()])
+        arrive(
+            &mut app,
+            Ok(r#"{"base":"NZD","rates":{"USD":0.5712}}"#.to_string()),
         );
-        assert_eq!(
-            app.config_editor_state().map(|editor| editor.selected),
-            Some(2)
+        assert!(app.active_fx_alerts.contains("NZD/USD below 0.5800"));
+        assert!(
+            app.status_message
+                .as_ref()
+                .is_some_and(|(message, _)| message.ends_with("NZD/USD below 0.5800"))
         );
+
+        // the arrived rate is cached, so the next check judges it without a request and
+        // the alert doesn't fire twice
+        app.status_message = None;
+        app.check_fx_alerts();
+        assert!(app.status_message.is_none());
+        assert_eq!(app.active_fx_alerts.len(), 1);
+
+        app.config.alerts = None;
+        app.check_fx_alerts();
+        assert!(app.active_fx_alerts.is_empty());
     }
 
     #[test]
-    fn target_city_search_resolves_country_name_to_representative_city() {
+    fn clock_skew_arrives_through_the_fetch_channel() {
         let mut app = App::new(Config::default());
-        app.open_config_editor();
-
-        let city_code = app
-            .search_config_cities("Japan")
-            .into_iter()
-            .find_map(|choice| match choice {
-                PickerChoice::City { code, .. } => Some(code),
-                _ => None,
-            })
-            .expect("country search should resolve to a city");
-        app.add_target_city_to_draft(&city_code)
-            .expect("target add should resolve");
-
+        app.fetched_tx
+            .send(Fetched::ClockSkew(Err(anyhow::anyhow!("offline"))))
+            .unwrap();
+        app.receive_fetched();
+        assert!(app.clock_skew.is_none());
+
+        app.fetched_tx.send(Fetched::ClockSkew(Ok(134))).unwrap();
+        app.receive_fetched();
+        assert_eq!(app.clock_skew, Some(134));
         assert_eq!(
-            app.config_draft
+            app.status_message
                 .as_ref()
-                .and_then(|draft| draft.time.as_ref())
-                .map(|time| time.target_city_codes.clone()),
-            Some(vec!["TYO".to_string()])
+                .map(|(message, _)| message.as_str()),
+            Some("⚠ System clock is 2m 14s fast")
         );
     }
 
     #[test]
-    fn target_city_search_can_use_generated_representative_city() {
-        let mut config = Config::default();
-        config.tracked_cities.clear();
-        let mut app = App::new(config);
-        app.open_config_editor();
-
-        let city_code = app
-            .search_config_cities("France")
-            .into_iter()
-            .find_map(|choice| match choice {
-                PickerChoice::City { code, .. } => Some(code),
-                _ => None,
-            })
-            .expect("country search should resolve to a city");
-        app.add_target_city_to_draft(&city_code)
-            .expect("target add should resolve");
-
-        assert!(
-            app.config_draft
-                .as_ref()
-                .map(|draft| draft
-                    .tracked_cities
-                    .iter()
-                    .any(|city| city.code.eq_ignore_ascii_case("PAR")))
-                .unwrap_or(false)
+    fn parses_city_add_with_and_without_query() {
+        assert_eq!(
+            parse_command("/city add Buenos").expect("command should parse"),
+            CommandAction::OpenCityPicker {
+                query: "Buenos".to_string()
+            }
         );
         assert_eq!(
-            app.config_draft
-                .as_ref()
-                .and_then(|draft| draft.time.as_ref())
-                .map(|time| time.target_city_codes.clone()),
-            Some(vec!["PAR".to_string()])
+            parse_command("/city").expect("command should parse"),
+            CommandAction::OpenCityPicker {
+                query: String::new()
+            }
+        );
+        assert!(parse_command("/city remove TYO").is_err());
+        assert_eq!(
+            parse_command("/addcity Tokyo").expect("command should parse"),
+            CommandAction::AddWorldCity {
+                query: "Tokyo".to_string()
+            }
         );
     }
 
     #[test]
-    fn places_currency_helper_resolves_to_representative_city() {
-        let mut app = App::new(Config::default());
-        app.open_config_editor();
+    fn config_bundle_export_and_import_restore_tracked_cities() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.config.save().expect("config should save");
+            let path = Config::config_dir().join("bundle.toml");
+            app.command_buffer = format!("/export config {}", path.display());
+            app.execute_command();
+            assert!(path.exists());
 
-        app.apply_config_command(CommandAction::AddPlaceCurrency {
-            code: "JPY".to_string(),
-            name: "Japanese yen".to_string(),
-        })
-        .expect("currency helper should resolve");
+            app.command_buffer = "/addcity reykjavik".to_string();
+            app.execute_command();
+            assert!(app.config.city_by_code("REK").is_some());
 
-        assert_eq!(
-            app.config_draft
-                .as_ref()
-                .and_then(|draft| draft.time.as_ref())
-                .map(|time| time.target_city_codes.clone()),
-            Some(vec!["TYO".to_string()])
-        );
+            app.command_buffer = format!("/import config {}", path.display());
+            app.execute_command();
+            assert!(app.config.city_by_code("REK").is_none());
+            let backup = std::fs::read_to_string(Config::config_dir().join("config.toml.bak.1"))
+                .expect("the replaced config should be kept");
+            assert!(backup.contains("Reykjavik"));
+
+            // a bundle that runs commands needs --yes
+            let bundle_text = std::fs::read_to_string(&path).unwrap();
+            let with_panel = Config::config_dir().join("panel.toml");
+            std::fs::write(
+                &with_panel,
+                format!(
+                    "{}\n[[config.panel.custom]]\ntitle = \"Up\"\ncommand = \"uptime\"\n",
+                    bundle_text
+                ),
+            )
+            .unwrap();
+            app.command_buffer = format!("/import config {}", with_panel.display());
+            app.execute_command();
+            assert!(app.config.effective_custom_panels().is_empty());
+            assert!(
+                app.status_message.as_ref().is_some_and(
+                    |(status, _)| status.contains("uptime") && status.contains("--yes")
+                )
+            );
+            app.command_buffer = format!("/import config {} --yes", with_panel.display());
+            app.execute_command();
+            assert_eq!(app.config.effective_custom_panels().len(), 1);
+        });
+        assert!(parse_command("/import config").is_err());
     }
 
     #[test]
-    fn city_picker_uses_representative_cities() {
-        let mut config = Config::default();
-        config.tracked_cities.push(City {
-            name: "Honolulu".to_string(),
-            code: "HNL".to_string(),
-            country: "USA".to_string(),
-            timezone: "Pacific/Honolulu".to_string(),
-            currency: "USD".to_string(),
-        });
-
-        let mut app = App::new(config);
-        app.open_picker(PickerKind::TargetCity);
+    fn addcity_tracks_a_bundled_city_with_weather_coordinates() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.command_buffer = "/addcity reykjavik".to_string();
+            app.execute_command();
 
-        let choices = app.picker_choices();
-        let city_codes: Vec<String> = choices
-            .into_iter()
-            .filter_map(|choice| match choice {
-                PickerChoice::City { code, .. } => Some(code),
-                _ => None,
-            })
-            .collect();
+            let saved = Config::load().expect("saved config should reload");
+            let reykjavik = saved
+                .tracked_cities
+                .iter()
+                .find(|city| city.name == "Reykjavik")
+                .expect("reykjavik should be tracked");
+            assert_eq!(reykjavik.timezone, "Atlantic/Reykjavik");
+            assert_eq!(reykjavik.currency, "ISK");
+            assert!(city_coordinates(reykjavik).is_some());
 
-        assert!(city_codes.iter().any(|code| code == "BOS"));
-        assert!(city_codes.iter().any(|code| code == "THR"));
-        assert!(city_codes.iter().any(|code| code == "HNL"));
+            app.command_buffer = "/addcity reykjavik".to_string();
+            app.execute_command();
+            assert!(
+                app.status_message
+                    .as_ref()
+                    .is_some_and(|(message, _)| message == "Reykjavik is already tracked")
+            );
+        });
     }
 
     #[test]
-    fn target_city_picker_can_add_generated_city_outside_current_catalogue() {
-        let mut app = App::new(Config::default());
-        app.open_config_editor();
-        app.add_target_city_to_draft("THR")
-            .expect("generated representative city should add");
+    fn city_add_picker_persists_iana_city_into_tracked_cities() {
+        with_temp_config_dir_for_test(|| {
+            let mut app = App::new(Config::default());
+            app.command_buffer = "/city add chicago".to_string();
+            app.execute_command();
+            assert_eq!(app.picker_title().as_deref(), Some("Add tracked city"));
 
-        assert!(
-            app.config_draft
-                .as_ref()
-                .map(|draft| draft
-                    .tracked_cities
-                    .iter()
-                    .any(|city| city.code.eq_ignore_ascii_case("THR")))
-                .unwrap_or(false)
-        );
-        assert_eq!(
-            app.config_draft
-                .as_ref()
-                .and_then(|draft| draft.time.as_ref())
-                .map(|time| time.target_city_codes.clone()),
-            Some(vec!["THR".to_string()])
-        );
+            app.submit_picker_selection().expect("city should be added");
+
+            assert!(app.picker.is_none());
+            let saved = Config::load().expect("saved config should reload");
+            let chicago = saved
+                .tracked_cities
+                .iter()
+                .find(|city| city.name == "Chicago")
+                .expect("chicago should be tracked");
+            assert_eq!(chicago.timezone, "America/Chicago");
+            assert!(saved.effective_target_city_codes().contains(&chicago.code));
+        });
     }
 
     #[test]
-    fn applying_generated_city_with_fixed_offset_timezone_saves_successfully() {
+    fn city_add_picker_asks_for_the_currency_of_a_zone_only_city() {
         with_temp_config_dir_for_test(|| {
-            let config = Config::default();
-            config.save().expect("default config should save");
+            let mut app = App::new(Config::default());
+            app.command_buffer = "/city add thule".to_string();
+            app.execute_command();
+            assert_eq!(
+                app.picker_options()[0].detail,
+                "America/Thule · currency unknown"
+            );
 
-            let mut app = App::new(config);
-            app.open_config_editor();
-            app.add_target_city_to_draft("KOR")
-                .expect("generated representative city should add");
-            app.apply_config_draft()
-                .expect("draft with fixed offset timezone should save");
+            app.submit_picker_selection()
+                .expect("currency picker should open");
+            assert_eq!(app.picker_title().as_deref(), Some("Currency for Thule"));
+            app.picker.as_mut().expect("picker").query = "DKK".to_string();
+            app.submit_picker_selection().expect("city should be added");
 
+            assert!(app.picker.is_none());
             let saved = Config::load().expect("saved config should reload");
-            assert!(
-                saved
-                    .tracked_cities
-                    .iter()
-                    .any(|city| city.code.eq_ignore_ascii_case("KOR"))
+            let thule = saved
+                .tracked_cities
+                .iter()
+                .find(|city| city.name == "Thule")
+                .expect("thule should be tracked");
+            assert_eq!(
+                (thule.currency.as_str(), thule.country.as_str()),
+                ("DKK", "")
             );
-            assert_eq!(saved.effective_target_city_codes(), vec!["KOR".to_string()]);
         });
     }
 
+    #[test]
+    fn adding_a_city_with_a_taken_code_is_rejected() {
+        let mut config = Config::default();
+        let city = City {
+            name: "Sydney".to_string(),
+            code: "AUS".to_string(),
+            country: "Australia".to_string(),
+            timezone: "Australia/Sydney".to_string(),
+            currency: "AUD".to_string(),
+        };
+        let before = config.tracked_cities.len();
+
+        let err = apply_command_action_to_config(
+            &mut config,
+            &CommandAction::AddTrackedCity { city: city.clone() },
+        )
+        .unwrap_err();
+
+        assert!(err.contains("already belongs to Austin"), "{}", err);
+        assert_eq!(config.tracked_cities.len(), before);
+        assert_eq!(
+            apply_command_action_to_config(
+                &mut config,
+                &CommandAction::AddTrackedCity {
+                    city: City::austin()
+                },
+            )
+            .map(|_| config.tracked_cities.len()),
+            Ok(before)
+        );
+    }
+
     #[test]
     fn selecting_anchor_city_opens_target_picker() {
         let mut app = App::new(Config::default());
@@ -0,0 +1,141 @@
+//! aurora australis outlook from NOAA SWPC's planetary Kp index
+//! a high Kp pushes the auroral oval north far enough to be seen from southern NZ
+
+use std::future::Future;
+use std::time::Instant;
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+use crate::stats::ServiceMetrics;
+
+/// how often the Kp index is re-fetched; NOAA publishes a new value every three hours
+const REFRESH_EVERY: std::time::Duration = std::time::Duration::from_secs(1800);
+
+const SWPC_KP_URL: &str = "https://services.swpc.noaa.gov/products/noaa-planetary-k-index.json";
+
+/// the lowest Kp at which the aurora is worth looking for from Dunedin
+pub const AURORA_MIN_KP: f64 = 5.0;
+
+/// the most recent Kp in a SWPC response, which comes either as rows under a header row
+/// (`[["time_tag","Kp",...],["2025-06-01 00:00:00","2.33",...]]`) or as objects with a `Kp` field
+pub fn parse_latest_kp(body: &str) -> Result<f64> {
+    let rows: Vec<Value> = serde_json::from_str(body).context("failed to parse Kp index")?;
+    let kp = |row: &Value| -> Option<f64> {
+        let value = match row {
+            Value::Array(cells) => cells.get(1)?,
+            Value::Object(fields) => fields.get("Kp").or_else(|| fields.get("kp_index"))?,
+            _ => return None,
+        };
+        match value {
+            Value::Number(number) => number.as_f64(),
+            Value::String(text) => text.trim().parse().ok(),
+            _ => None,
+        }
+    };
+    match rows.iter().rev().find_map(kp) {
+        Some(kp) if (0.0..=9.0).contains(&kp) => Ok(kp),
+        Some(kp) => bail!("Kp index {} is out of range", kp),
+        None => bail!("Kp response had no readings"),
+    }
+}
+
+/// how far north the aurora might be seen tonight, with the latitude it reaches,
+/// or None when it's unlikely
+pub fn aurora_outlook(kp: f64) -> Option<(&'static str, f64)> {
+    if kp >= 7.0 {
+        Some(("across the South Island", -40.5))
+    } else if kp >= 6.0 {
+        Some(("from Christchurch southwards", -43.3))
+    } else if kp >= AURORA_MIN_KP {
+        Some(("from Dunedin southwards", -45.6))
+    } else {
+        None
+    }
+}
+
+/// the latest planetary Kp index, fetched every half hour while the map is on
+pub struct AuroraService {
+    client: reqwest::Client,
+    kp: Option<f64>,
+    last_attempt: Option<Instant>,
+    metrics: ServiceMetrics,
+}
+
+impl AuroraService {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            kp: None,
+            last_attempt: None,
+            metrics: ServiceMetrics::default(),
+        }
+    }
+
+    pub fn needs_fetch(&self) -> bool {
+        self.last_attempt
+            .is_none_or(|attempt| attempt.elapsed() >= REFRESH_EVERY)
+    }
+
+    /// the request for the Kp index, to run off the UI loop; hand its result to `finish_fetch`
+    pub fn start_fetch(&mut self) -> impl Future<Output = Result<f64>> + Send + 'static {
+        self.last_attempt = Some(Instant::now());
+        self.metrics.record_api_call();
+        let client = self.client.clone();
+        async move {
+            let body = client
+                .get(SWPC_KP_URL)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .context("failed to fetch Kp index")?
+                .text()
+                .await
+                .context("failed to read Kp response")?;
+            parse_latest_kp(&body)
+        }
+    }
+
+    /// replace the Kp reading; on failure the previous one is kept until the next attempt
+    pub fn finish_fetch(&mut self, fetched: Result<f64>) -> Result<()> {
+        self.kp = Some(fetched?);
+        Ok(())
+    }
+
+    pub fn kp(&self) -> Option<f64> {
+        self.kp
+    }
+
+    pub fn metrics(&self) -> ServiceMetrics {
+        self.metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_latest_kp_in_either_shape() {
+        let rows = r#"[["time_tag","Kp","a_running","station_count"],
+            ["2025-06-01 00:00:00","2.33","9","8"],
+            ["2025-06-01 03:00:00","5.67","80","8"]]"#;
+        assert_eq!(parse_latest_kp(rows).unwrap(), 5.67);
+
+        let objects = r#"[{"time_tag":"2025-06-01T00:00:00","Kp":3.0},
+            {"time_tag":"2025-06-01T03:00:00","Kp":6.33}]"#;
+        assert_eq!(parse_latest_kp(objects).unwrap(), 6.33);
+
+        assert!(parse_latest_kp(r#"[["time_tag","Kp"]]"#).is_err());
+        assert!(parse_latest_kp(r#"[["2025-06-01","12"]]"#).is_err());
+    }
+
+    #[test]
+    fn outlook_reaches_further_north_as_kp_rises() {
+        let label = |kp| aurora_outlook(kp).map(|(label, _)| label);
+        assert_eq!(label(4.67), None);
+        assert_eq!(label(5.0), Some("from Dunedin southwards"));
+        assert_eq!(label(6.33), Some("from Christchurch southwards"));
+        assert_eq!(label(8.0), Some("across the South Island"));
+    }
+}
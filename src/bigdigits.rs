@@ -0,0 +1,85 @@
+//! figlet-style block digits for large clocks: a 3x5 pixel font drawn two pixel rows to a
+//! line with half blocks, so a clock takes three rows
+
+/// rows a line of big digits takes
+pub const HEIGHT: usize = 3;
+
+/// pixel rows of a glyph, most significant bit on the left
+fn glyph(c: char) -> Option<&'static [u8; 5]> {
+    Some(match c {
+        '0' => &[0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => &[0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => &[0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => &[0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => &[0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => &[0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => &[0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => &[0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => &[0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => &[0b111, 0b101, 0b111, 0b001, 0b111],
+        ':' => &[0b0, 0b1, 0b0, 0b1, 0b0],
+        ' ' => &[0b0; 5],
+        _ => return None,
+    })
+}
+
+/// columns a glyph takes; the colon and space are one pixel wide
+fn glyph_width(c: char) -> usize {
+    if matches!(c, ':' | ' ') { 1 } else { 3 }
+}
+
+/// columns `text` takes in big digits, with a blank column between glyphs
+pub fn width(text: &str) -> usize {
+    let glyphs: Vec<char> = text.chars().filter(|&c| glyph(c).is_some()).collect();
+    glyphs.iter().map(|&c| glyph_width(c)).sum::<usize>() + glyphs.len().saturating_sub(1)
+}
+
+/// `text` as `HEIGHT` lines of block characters; digits, ':' and ' ' are drawn and
+/// anything else is left out
+pub fn render(text: &str) -> [String; HEIGHT] {
+    let mut lines: [String; HEIGHT] = Default::default();
+    for (index, (c, rows)) in text
+        .chars()
+        .filter_map(|c| Some((c, glyph(c)?)))
+        .enumerate()
+    {
+        for (line, out) in lines.iter_mut().enumerate() {
+            if index > 0 {
+                out.push(' ');
+            }
+            let top = rows[line * 2];
+            let bottom = rows.get(line * 2 + 1).copied().unwrap_or(0);
+            for column in (0..glyph_width(c)).rev() {
+                out.push(match ((top >> column) & 1, (bottom >> column) & 1) {
+                    (1, 1) => '█',
+                    (1, 0) => '▀',
+                    (0, 1) => '▄',
+                    _ => ' ',
+                });
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_digits_and_colons_three_rows_tall() {
+        assert_eq!(
+            render("10:42"),
+            [
+                "▄█  █▀█ ▄ █ █ ▀▀█",
+                " █  █ █ ▄ ▀▀█ █▀▀",
+                "▀▀▀ ▀▀▀     ▀ ▀▀▀",
+            ]
+        );
+        assert_eq!(width("10:42"), 17);
+
+        // letters such as AM/PM are left for the caller to draw
+        assert_eq!(render("7 PM"), render("7 "));
+        assert_eq!(width(""), 0);
+    }
+}
@@ -0,0 +1,295 @@
+//! portable config bundles for moving nzi between machines
+//! one TOML file holding config.toml as written (cities, alerts, notes, keys and all)
+//! and the latest kept exchange rate for each of its currency pairs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cache::{CacheLimits, RATE_SNAPSHOTS, SnapshotStore};
+use crate::config::{Config, write_atomically};
+use crate::config_check;
+use crate::exchange::{keep_rates, kept_rate_history};
+use crate::notify::Notifier;
+
+/// bumped when the bundle layout changes incompatibly
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    /// RFC 3339 time the bundle was made
+    pub exported: String,
+    pub config: toml::Table,
+    /// the latest kept `FROM_TO` exchange rates
+    #[serde(default)]
+    pub rates: BTreeMap<String, f64>,
+}
+
+/// what a bundle carried, for the status line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundleSummary {
+    pub cities: usize,
+    pub rates: usize,
+}
+
+impl Bundle {
+    /// every program the bundle's config would run: the editor, custom panel commands and
+    /// alerts sent to a command, each with where it's set
+    pub fn commands(&self) -> Vec<String> {
+        let mut commands = Vec::new();
+        let editor = self
+            .config
+            .get("display")
+            .and_then(|display| display.get("editor"))
+            .and_then(toml::Value::as_str);
+        if let Some(editor) = editor {
+            commands.push(format!("display.editor: {}", editor));
+        }
+        let panels = self
+            .config
+            .get("panel")
+            .and_then(|panel| panel.get("custom"))
+            .and_then(toml::Value::as_array);
+        for panel in panels.into_iter().flatten() {
+            if let Some(command) = panel.get("command").and_then(toml::Value::as_str) {
+                let title = panel.get("title").and_then(toml::Value::as_str);
+                commands.push(format!("panel {:?}: {}", title.unwrap_or(""), command));
+            }
+        }
+        let alerts = self.config.get("alerts").and_then(toml::Value::as_table);
+        for (name, spec) in alerts.into_iter().flatten() {
+            if let Some(Notifier::Command(command)) = spec.as_str().and_then(Notifier::parse) {
+                commands.push(format!("alerts.{}: {}", name, command));
+            }
+        }
+        commands
+    }
+
+    fn summary(&self) -> BundleSummary {
+        BundleSummary {
+            cities: self
+                .config
+                .get("tracked_cities")
+                .and_then(toml::Value::as_array)
+                .map_or(0, Vec::len),
+            rates: self.rates.len(),
+        }
+    }
+}
+
+/// default bundle target: a dated TOML file in the home directory
+pub fn default_bundle_path(now: DateTime<Utc>) -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(format!("nzi-config-{}.toml", now.format("%Y%m%d")))
+}
+
+/// a bundle of `config_text` and `rates`, as TOML
+pub fn render(
+    config_text: &str,
+    rates: BTreeMap<String, f64>,
+    now: DateTime<Utc>,
+) -> Result<String> {
+    let bundle = Bundle {
+        version: BUNDLE_VERSION,
+        exported: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        config: config_text
+            .parse()
+            .context("config.toml isn't valid TOML")?,
+        rates,
+    };
+    toml::to_string_pretty(&bundle).context("failed to serialise the bundle")
+}
+
+pub fn parse(text: &str) -> Result<Bundle> {
+    let bundle: Bundle = toml::from_str(text).context("not an nzi config bundle")?;
+    if bundle.version != BUNDLE_VERSION {
+        bail!(
+            "bundle version {} isn't supported (expected {})",
+            bundle.version,
+            BUNDLE_VERSION
+        );
+    }
+    Ok(bundle)
+}
+
+/// where the rate responses kept for `config` live
+fn rate_store(config: &Config) -> SnapshotStore {
+    SnapshotStore::new(
+        Config::cache_dir(),
+        RATE_SNAPSHOTS,
+        CacheLimits::from(&config.effective_cache()),
+    )
+}
+
+/// the latest kept rate for each of the config's currency pairs
+fn kept_rates(config: &Config) -> BTreeMap<String, f64> {
+    let store = rate_store(config);
+    config
+        .effective_currency_pairs()
+        .into_iter()
+        .filter_map(|(from, to)| {
+            let rate = *kept_rate_history(&store, &from, &to).last()?;
+            Some((format!("{}_{}", from, to), rate))
+        })
+        .collect()
+}
+
+/// the current config file and its pairs' latest kept rates, as bundle text
+pub fn export_text() -> Result<(String, BundleSummary)> {
+    let config_text =
+        fs::read_to_string(Config::config_path()).context("failed to read config file")?;
+    let (config, _) = config_check::parse(&config_text);
+    let text = render(&config_text, kept_rates(&config), Utc::now())?;
+    let summary = parse(&text)?.summary();
+    Ok((text, summary))
+}
+
+/// write the current config file and its pairs' latest kept rates to `path`
+pub fn export(path: &Path) -> Result<BundleSummary> {
+    let (text, summary) = export_text()?;
+    fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(summary)
+}
+
+pub fn read(path: &Path) -> Result<Bundle> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    parse(&text)
+}
+
+/// replace the config file with the bundle's, keeping the old one as the newest
+/// `config.toml.bak.N` backup, and keep the bundle's rates in the cache as of its export;
+/// a bundle that runs shell commands is refused unless `allow_commands`
+pub fn import(path: &Path, allow_commands: bool) -> Result<BundleSummary> {
+    let bundle = read(path)?;
+    let commands = bundle.commands();
+    if !commands.is_empty() && !allow_commands {
+        bail!(
+            "the bundle runs shell commands ({}); import it with --yes to keep them",
+            commands.join("; ")
+        );
+    }
+
+    Config::back_up().context("failed to keep the current config")?;
+    let config_path = Config::config_path();
+    let config_text =
+        toml::to_string_pretty(&bundle.config).context("failed to serialise the config")?;
+    if let Some(dir) = config_path.parent() {
+        fs::create_dir_all(dir).context("failed to create config directory")?;
+    }
+    write_atomically(&config_path, &config_text).context("failed to write config file")?;
+
+    if !bundle.rates.is_empty() {
+        let (config, _) = config_check::parse(&config_text);
+        let exported = DateTime::parse_from_rfc3339(&bundle.exported)
+            .map_or_else(|_| Utc::now(), |time| time.with_timezone(&Utc));
+        keep_rates(&rate_store(&config), &bundle.rates, exported)?;
+    }
+
+    Ok(bundle.summary())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::ExchangeService;
+
+    #[test]
+    fn round_trips_config_and_rates() {
+        let config_text =
+            "[[tracked_cities]]\nname = \"Tokyo\"\ncode = \"TYO\"\n\n[alerts]\nrain = \"bell\"\n";
+        let rates = BTreeMap::from([("NZD_JPY".to_string(), 88.5)]);
+        let text = render(config_text, rates, Utc::now()).unwrap();
+
+        let bundle = parse(&text).unwrap();
+        assert_eq!(
+            bundle.summary(),
+            BundleSummary {
+                cities: 1,
+                rates: 1
+            }
+        );
+        assert_eq!(bundle.config["alerts"]["rain"].as_str(), Some("bell"));
+        assert_eq!(bundle.rates["NZD_JPY"], 88.5);
+    }
+
+    #[test]
+    fn lists_the_shell_commands_a_bundle_runs() {
+        let config_text = concat!(
+            "[alerts]\nrain = \"bell\"\nwind = \"https://example.com/hook\"\nfx = \"say fx\"\n\n",
+            "[[panel.custom]]\ntitle = \"Uptime\"\ncommand = \"uptime\"\n",
+        );
+        let bundle = parse(&render(config_text, BTreeMap::new(), Utc::now()).unwrap()).unwrap();
+        assert_eq!(
+            bundle.commands(),
+            vec!["panel \"Uptime\": uptime", "alerts.fx: say fx"]
+        );
+    }
+
+    #[test]
+    fn an_editor_alone_needs_confirming() {
+        crate::config::with_temp_config_dir_for_test(|| {
+            let config_text = "[display]\neditor = \"/tmp/not-an-editor\"\n";
+            let path = Config::config_dir().join("editor.toml");
+            fs::write(
+                &path,
+                render(config_text, BTreeMap::new(), Utc::now()).unwrap(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                read(&path).unwrap().commands(),
+                vec!["display.editor: /tmp/not-an-editor"]
+            );
+            let refused = import(&path, false).unwrap_err().to_string();
+            assert!(refused.contains("/tmp/not-an-editor"), "{}", refused);
+            assert!(!Config::config_path().exists());
+            import(&path, true).expect("--yes should import it");
+            assert!(
+                fs::read_to_string(Config::config_path())
+                    .unwrap()
+                    .contains("/tmp/not-an-editor")
+            );
+        });
+    }
+
+    #[test]
+    fn imported_rates_serve_offline_conversions() {
+        crate::config::with_temp_config_dir_for_test(|| {
+            let path = Config::config_dir().join("moving.toml");
+            let rates = BTreeMap::from([("NZD_JPY".to_string(), 88.5)]);
+            fs::write(&path, render("", rates, Utc::now()).unwrap()).unwrap();
+            import(&path, false).expect("bundle should import");
+
+            // a client that can't reach anything stands in for the new machine being offline
+            let offline = reqwest::Client::builder()
+                .proxy(reqwest::Proxy::all("http://127.0.0.1:9").unwrap())
+                .build()
+                .unwrap();
+            let mut service = ExchangeService::new(offline);
+            service.keep_snapshots_in(rate_store(&Config::default()));
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let rate = runtime
+                .block_on(service.get_rate("NZD", "JPY", std::time::Duration::ZERO))
+                .expect("the imported rate should be used offline");
+            assert_eq!(rate, 88.5);
+            assert!(!service.served_bundled_rate());
+        });
+    }
+
+    #[test]
+    fn rejects_other_files_and_versions() {
+        assert!(parse("tracked_cities = []\n").is_err());
+        let text = render("", BTreeMap::new(), Utc::now())
+            .unwrap()
+            .replace("version = 1", "version = 9");
+        assert!(parse(&text).is_err());
+        assert!(render("not = [toml", BTreeMap::new(), Utc::now()).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! the cache directory: raw weather and exchange rate responses kept as timestamped
+//! snapshots, pruned oldest first past an age and size limit
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+
+use crate::config::CacheConfig;
+
+/// subdirectory for open-meteo responses
+pub const WEATHER_SNAPSHOTS: &str = "weather";
+/// subdirectory for exchange rate responses
+pub const RATE_SNAPSHOTS: &str = "rates";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheLimits {
+    pub max_bytes: u64,
+    /// None keeps snapshots whatever their age
+    pub max_age: Option<Duration>,
+}
+
+impl From<&CacheConfig> for CacheLimits {
+    fn from(config: &CacheConfig) -> Self {
+        Self {
+            max_bytes: config.max_mb * 1024 * 1024,
+            max_age: (config.max_age_days > 0)
+                .then(|| Duration::from_secs(config.max_age_days * 24 * 60 * 60)),
+        }
+    }
+}
+
+/// files and bytes in the cache, or removed from it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheUsage {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl CacheUsage {
+    fn add(&mut self, bytes: u64) {
+        self.files += 1;
+        self.bytes += bytes;
+    }
+}
+
+/// "1.3 MB", "12 KB" or "80 B"
+pub fn format_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{} KB", bytes / KB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// where one service keeps its responses, under the cache root
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    root: PathBuf,
+    kind: &'static str,
+    pub limits: CacheLimits,
+}
+
+impl SnapshotStore {
+    pub fn new(root: PathBuf, kind: &'static str, limits: CacheLimits) -> Self {
+        Self { root, kind, limits }
+    }
+
+    /// keep `body` as `<kind>/<name>-<utc time>.json`, then prune the cache
+    pub fn save(&self, name: &str, body: &str, now: DateTime<Utc>) -> Result<()> {
+        let dir = self.root.join(self.kind);
+        fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let path = dir.join(format!(
+            "{}-{}.json",
+            file_name(name),
+            now.format("%Y%m%dT%H%M%S")
+        ));
+        fs::write(&path, body).with_context(|| format!("failed to write {}", path.display()))?;
+        prune(&self.root, &self.limits, SystemTime::now());
+        Ok(())
+    }
+
+    /// the responses kept for `name` as (utc time, body), oldest first; unreadable files
+    /// are skipped
+    pub fn saved(&self, name: &str) -> Vec<(String, String)> {
+        let prefix = format!("{}-", file_name(name));
+        let mut saved: Vec<(String, String)> = fs::read_dir(self.root.join(self.kind))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let file = entry.file_name().into_string().ok()?;
+                let time = file.strip_prefix(&prefix)?.strip_suffix(".json")?;
+                // another name that starts the same, like "nzd-x" beside "nzd"
+                if time.contains('-') {
+                    return None;
+                }
+                Some((time.to_string(), fs::read_to_string(entry.path()).ok()?))
+            })
+            .collect();
+        saved.sort();
+        saved
+    }
+}
+
+/// `name` as it appears in snapshot file names
+fn file_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// every snapshot under `root` with its size and modified time; files directly in `root`
+/// are not snapshots
+fn snapshots(root: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    let mut files = Vec::new();
+    for kind in [WEATHER_SNAPSHOTS, RATE_SNAPSHOTS] {
+        let Ok(entries) = fs::read_dir(root.join(kind)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_file() {
+                let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.push((entry.path(), meta.len(), modified));
+            }
+        }
+    }
+    files
+}
+
+/// everything in the cache: snapshots and the files beside them
+pub fn usage(root: &Path) -> CacheUsage {
+    let mut usage = CacheUsage::default();
+    for (_, bytes, _) in snapshots(root) {
+        usage.add(bytes);
+    }
+    for entry in fs::read_dir(root).into_iter().flatten().flatten() {
+        if let Ok(meta) = entry.metadata()
+            && meta.is_file()
+        {
+            usage.add(meta.len());
+        }
+    }
+    usage
+}
+
+/// drop snapshots older than the age limit, then the oldest until the cache fits its size
+/// limit; failures to remove a file are skipped
+pub fn prune(root: &Path, limits: &CacheLimits, now: SystemTime) -> CacheUsage {
+    let mut removed = CacheUsage::default();
+    let mut files = snapshots(root);
+    files.sort_by_key(|(_, _, modified)| *modified);
+    let mut total = usage(root).bytes;
+    for (path, bytes, modified) in files {
+        let expired = limits
+            .max_age
+            .is_some_and(|max_age| now.duration_since(modified).is_ok_and(|age| age > max_age));
+        if !expired && total <= limits.max_bytes {
+            continue;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(bytes);
+            removed.add(bytes);
+        }
+    }
+    removed
+}
+
+/// empty the cache
+pub fn clear(root: &Path) -> Result<CacheUsage> {
+    let removed = usage(root);
+    if root.exists() {
+        fs::remove_dir_all(root).with_context(|| format!("failed to clear {}", root.display()))?;
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prunes_expired_then_oldest_snapshots_only() {
+        let root = std::env::temp_dir().join(format!("nzi-cache-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("notes.txt"), "not a snapshot\n").unwrap();
+
+        let store = SnapshotStore::new(
+            root.clone(),
+            WEATHER_SNAPSHOTS,
+            CacheLimits {
+                max_bytes: 10_000,
+                max_age: None,
+            },
+        );
+        let now = Utc::now();
+        for minute in 0..3 {
+            let at = now + chrono::Duration::minutes(minute);
+            store
+                .save("Palmerston North", &"x".repeat(1000), at)
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(usage(&root).files, 4);
+        let saved = store.saved("Palmerston North");
+        assert_eq!(saved.len(), 3);
+        assert_eq!(saved[0].0, now.format("%Y%m%dT%H%M%S").to_string());
+        assert!(store.saved("Palmerston").is_empty());
+        let first = root.join(WEATHER_SNAPSHOTS).join(format!(
+            "palmerston-north-{}.json",
+            now.format("%Y%m%dT%H%M%S")
+        ));
+        assert!(first.exists());
+
+        let tight = CacheLimits {
+            max_bytes: 1500,
+            max_age: None,
+        };
+        let removed = prune(&root, &tight, SystemTime::now());
+        assert_eq!(removed.files, 2);
+        assert!(!first.exists());
+        assert!(root.join("notes.txt").exists());
+
+        let expiring = CacheLimits {
+            max_bytes: 10_000,
+            max_age: Some(Duration::from_secs(60)),
+        };
+        let later = SystemTime::now() + Duration::from_secs(120);
+        assert_eq!(prune(&root, &expiring, later).files, 1);
+
+        assert_eq!(clear(&root).unwrap().files, 1);
+        assert!(!root.exists());
+    }
+}
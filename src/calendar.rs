@@ -0,0 +1,86 @@
+//! month calendar laid out in weeks, for the dual-timezone /cal popup
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+use crate::config::WeekStart;
+
+/// one day of the popup: the anchor city's date and what the home city's date is at the same moment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarCell {
+    pub anchor: NaiveDate,
+    pub home: Option<NaiveDate>,
+    pub in_month: bool,
+    pub today: bool,
+    pub event: bool,
+    pub holiday: bool,
+}
+
+pub fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// the first of the month `delta` months away
+pub fn shift_month(month: NaiveDate, delta: i32) -> NaiveDate {
+    let month = first_of_month(month);
+    let shifted = if delta >= 0 {
+        month.checked_add_months(Months::new(delta.unsigned_abs()))
+    } else {
+        month.checked_sub_months(Months::new(delta.unsigned_abs()))
+    };
+    shifted.unwrap_or(month)
+}
+
+/// whole weeks covering the month, padded with days from the neighbouring months
+pub fn month_weeks(month: NaiveDate, week_starts: WeekStart) -> Vec<[NaiveDate; 7]> {
+    let first = first_of_month(month);
+    let lead = match week_starts {
+        WeekStart::Monday => first.weekday().num_days_from_monday(),
+        WeekStart::Sunday => first.weekday().num_days_from_sunday(),
+    };
+    let mut start = first - Duration::days(lead.into());
+    let mut weeks = Vec::new();
+    while start.month() == first.month() || start < first {
+        let mut week = [start; 7];
+        for (offset, day) in week.iter_mut().enumerate() {
+            *day = start + Duration::days(offset as i64);
+        }
+        weeks.push(week);
+        start += Duration::days(7);
+    }
+    weeks
+}
+
+/// two-letter weekday headings in display order
+pub fn weekday_headings(week_starts: WeekStart) -> [&'static str; 7] {
+    match week_starts {
+        WeekStart::Monday => ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"],
+        WeekStart::Sunday => ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn lays_out_whole_weeks_from_the_configured_first_day() {
+        // march 2026 starts on a sunday
+        let monday_weeks = month_weeks(date(2026, 3, 14), WeekStart::Monday);
+        assert_eq!(monday_weeks.len(), 6);
+        assert_eq!(monday_weeks[0][0], date(2026, 2, 23));
+        assert_eq!(monday_weeks[0][6], date(2026, 3, 1));
+        assert_eq!(monday_weeks[5][6], date(2026, 4, 5));
+
+        let sunday_weeks = month_weeks(date(2026, 3, 14), WeekStart::Sunday);
+        assert_eq!(sunday_weeks.len(), 5);
+        assert_eq!(sunday_weeks[0][0], date(2026, 3, 1));
+        assert_eq!(sunday_weeks[4][6], date(2026, 4, 4));
+
+        assert_eq!(shift_month(date(2026, 1, 31), -1), date(2025, 12, 1));
+        assert_eq!(shift_month(date(2026, 12, 5), 1), date(2027, 1, 1));
+    }
+}
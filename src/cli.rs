@@ -0,0 +1,127 @@
+//! command-line flags: config overrides that last one session, the map export, and
+//! config bundle export and import
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::config::SessionOverrides;
+use crate::theme::ThemeName;
+
+/// New Zealand around the world, in the terminal
+#[derive(Debug, Parser)]
+#[command(name = "nzi", version, about)]
+pub struct Cli {
+    /// NZ city to use as the current city, by code (e.g. AKL)
+    #[arg(long, value_name = "CODE")]
+    pub city: Option<String>,
+
+    /// city to use as home, by code (e.g. TYO)
+    #[arg(long, value_name = "CODE")]
+    pub home_city: Option<String>,
+
+    /// start with every animation off
+    #[arg(long)]
+    pub no_animations: bool,
+
+    /// colour theme: mocha, latte, gruvbox, gruvbox-light, nord, dracula or tokyo-night
+    #[arg(long, value_name = "NAME", value_parser = parse_theme)]
+    pub theme: Option<ThemeName>,
+
+    /// read and save this config file instead of config.toml
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// print the NZ map and exit, or save it to PATH (.ans keeps the colours)
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub export_map: Option<Option<String>>,
+
+    /// print a portable bundle of the config and its latest rates and exit, or save it to PATH
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub export_config: Option<Option<String>>,
+
+    /// replace the config with a bundle made by --export-config and exit
+    #[arg(long, value_name = "PATH", conflicts_with = "export_config")]
+    pub import_config: Option<String>,
+
+    /// with --import-config, keep the commands the bundle runs (editor, custom panels,
+    /// command alerts) without asking
+    #[arg(long, requires = "import_config")]
+    pub yes: bool,
+}
+
+impl Cli {
+    /// the flags that stand in for config values, none of which get saved
+    pub fn overrides(&self) -> SessionOverrides {
+        SessionOverrides {
+            city: self.city.clone(),
+            home_city: self.home_city.clone(),
+            no_animations: self.no_animations,
+            theme: self.theme,
+        }
+    }
+}
+
+fn parse_theme(name: &str) -> Result<ThemeName, String> {
+    ThemeName::from_name(name).ok_or_else(|| {
+        let names: Vec<_> = ThemeName::ALL.iter().map(|theme| theme.name()).collect();
+        format!("unknown theme (use one of {})", names.join(", "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_become_session_overrides() {
+        let cli = Cli::try_parse_from([
+            "nzi",
+            "--city",
+            "AKL",
+            "--home-city",
+            "tyo",
+            "--no-animations",
+            "--theme",
+            "Latte",
+            "--config",
+            "/tmp/nzi.toml",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.overrides(),
+            SessionOverrides {
+                city: Some("AKL".to_string()),
+                home_city: Some("tyo".to_string()),
+                no_animations: true,
+                theme: Some(ThemeName::Latte),
+            }
+        );
+        assert_eq!(cli.config, Some(PathBuf::from("/tmp/nzi.toml")));
+        assert_eq!(cli.export_map, None);
+
+        let cli = Cli::try_parse_from(["nzi", "--export-map"]).unwrap();
+        assert_eq!(cli.export_map, Some(None));
+        let cli = Cli::try_parse_from(["nzi", "--export-map", "map.ans"]).unwrap();
+        assert_eq!(cli.export_map, Some(Some("map.ans".to_string())));
+
+        let cli = Cli::try_parse_from(["nzi", "--export-config"]).unwrap();
+        assert_eq!(cli.export_config, Some(None));
+        let cli = Cli::try_parse_from(["nzi", "--import-config", "nzi.toml"]).unwrap();
+        assert_eq!(cli.import_config.as_deref(), Some("nzi.toml"));
+        assert!(!cli.yes);
+        assert!(
+            Cli::try_parse_from(["nzi", "--import-config", "nzi.toml", "--yes"])
+                .unwrap()
+                .yes
+        );
+        assert!(Cli::try_parse_from(["nzi", "--yes"]).is_err());
+        assert!(
+            Cli::try_parse_from(["nzi", "--export-config", "--import-config", "nzi.toml"]).is_err()
+        );
+
+        let cli = Cli::try_parse_from(["nzi", "--theme", "tokyo-night"]).unwrap();
+        assert_eq!(cli.theme, Some(ThemeName::TokyoNight));
+        assert!(Cli::try_parse_from(["nzi", "--theme", "solarized"]).is_err());
+    }
+}
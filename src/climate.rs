@@ -0,0 +1,424 @@
+//! personal climate records
+//! keeps one row per city per day, from open-meteo's figures for the day just gone, and
+//! summarises them by month for /climate
+
+use anyhow::{Context, Result, bail};
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::config::Config;
+
+pub const CLIMATE_HEADER: &str = "date,city,high_c,low_c,rain_mm";
+
+/// one day's high, low and rainfall for a city
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyRecord {
+    pub date: NaiveDate,
+    pub city: String,
+    pub high_c: i32,
+    pub low_c: i32,
+    pub rain_mm: f64,
+}
+
+impl DailyRecord {
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{:.1}",
+            self.date, self.city, self.high_c, self.low_c, self.rain_mm
+        )
+    }
+
+    fn from_csv(line: &str) -> Option<Self> {
+        let mut fields = line.trim().split(',');
+        let record = Self {
+            date: NaiveDate::parse_from_str(fields.next()?, "%Y-%m-%d").ok()?,
+            city: fields.next()?.to_string(),
+            high_c: fields.next()?.parse().ok()?,
+            low_c: fields.next()?.parse().ok()?,
+            rain_mm: fields.next()?.parse().ok()?,
+        };
+        fields.next().is_none().then_some(record)
+    }
+}
+
+pub fn climate_path() -> PathBuf {
+    Config::data_dir().join("climate.csv")
+}
+
+/// every stored record, oldest first
+pub fn load() -> Result<Vec<DailyRecord>> {
+    let path = climate_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).context("failed to read climate records")?;
+    Ok(content.lines().filter_map(DailyRecord::from_csv).collect())
+}
+
+/// store days, replacing any earlier reading for the same city and date
+pub fn record(entries: Vec<DailyRecord>) -> Result<()> {
+    let mut by_day: BTreeMap<(NaiveDate, String), DailyRecord> = load()?
+        .into_iter()
+        .map(|record| ((record.date, record.city.clone()), record))
+        .collect();
+    for entry in entries {
+        by_day.insert((entry.date, entry.city.clone()), entry);
+    }
+
+    let mut content = String::from(CLIMATE_HEADER);
+    content.push('\n');
+    for record in by_day.values() {
+        content.push_str(&record.to_csv());
+        content.push('\n');
+    }
+    let path = climate_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("failed to create data directory")?;
+    }
+    fs::write(&path, content).context("failed to write climate records")
+}
+
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+/// how long to wait after a failed fetch before trying again
+const RETRY_EVERY: std::time::Duration = std::time::Duration::from_secs(1800);
+
+#[derive(Deserialize)]
+struct ObservedPoint {
+    daily: ObservedDaily,
+}
+
+#[derive(Deserialize)]
+struct ObservedDaily {
+    time: Vec<String>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    precipitation_sum: Vec<Option<f64>>,
+}
+
+/// yesterday's record for each named place from a `past_days=1` response, in request
+/// order; open-meteo sends an array for several locations and a bare object for one,
+/// and a place with a missing reading is left out
+pub fn parse_observed_days(body: &str, cities: &[&str]) -> Result<Vec<DailyRecord>> {
+    let points: Vec<ObservedPoint> = if body.trim_start().starts_with('[') {
+        serde_json::from_str(body).context("failed to parse climate readings")?
+    } else {
+        vec![serde_json::from_str(body).context("failed to parse climate readings")?]
+    };
+    if points.len() != cities.len() {
+        bail!(
+            "climate readings had {} places, expected {}",
+            points.len(),
+            cities.len()
+        );
+    }
+    Ok(points
+        .into_iter()
+        .zip(cities)
+        .filter_map(|(point, city)| {
+            let daily = point.daily;
+            Some(DailyRecord {
+                date: NaiveDate::parse_from_str(daily.time.first()?, "%Y-%m-%d").ok()?,
+                city: city.to_string(),
+                high_c: (*daily.temperature_2m_max.first()?)?.round() as i32,
+                low_c: (*daily.temperature_2m_min.first()?)?.round() as i32,
+                rain_mm: (*daily.precipitation_sum.first()?)?,
+            })
+        })
+        .collect())
+}
+
+/// fetches the finished day's figures once a day for every city being tracked
+pub struct ClimateRecorder {
+    client: reqwest::Client,
+    recorded_on: Option<NaiveDate>,
+    last_attempt: Option<Instant>,
+}
+
+impl ClimateRecorder {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            recorded_on: None,
+            last_attempt: None,
+        }
+    }
+
+    /// whether `today` still needs yesterday recorded; a failure waits half an hour
+    pub fn needs_fetch(&self, today: NaiveDate) -> bool {
+        self.recorded_on != Some(today)
+            && self
+                .last_attempt
+                .is_none_or(|attempt| attempt.elapsed() >= RETRY_EVERY)
+    }
+
+    /// the request for yesterday's figures at each (city, (lat, lon)), in one request, to run
+    /// off the UI loop; hand its result to `finish_fetch`
+    pub fn start_fetch(
+        &mut self,
+        places: &[(String, (f64, f64))],
+    ) -> impl Future<Output = Result<Vec<DailyRecord>>> + Send + 'static {
+        self.last_attempt = Some(Instant::now());
+
+        let join = |coordinate: fn(&(f64, f64)) -> f64| {
+            places
+                .iter()
+                .map(|(_, point)| coordinate(point).to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let url = format!(
+            "{}?latitude={}&longitude={}&daily=temperature_2m_max,temperature_2m_min,precipitation_sum&timezone=auto&past_days=1&forecast_days=0",
+            OPEN_METEO_URL,
+            join(|point| point.0),
+            join(|point| point.1)
+        );
+        let cities: Vec<String> = places.iter().map(|(city, _)| city.clone()).collect();
+        let client = self.client.clone();
+        async move {
+            if cities.is_empty() {
+                return Ok(Vec::new());
+            }
+            let body = client
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .context("failed to fetch climate readings")?
+                .text()
+                .await
+                .context("failed to read climate readings")?;
+            let cities: Vec<&str> = cities.iter().map(String::as_str).collect();
+            parse_observed_days(&body, &cities)
+        }
+    }
+
+    /// store the fetched figures as `today`'s record of the day before
+    pub fn finish_fetch(
+        &mut self,
+        today: NaiveDate,
+        fetched: Result<Vec<DailyRecord>>,
+    ) -> Result<()> {
+        let days = fetched?;
+        if !days.is_empty() {
+            record(days)?;
+        }
+        self.recorded_on = Some(today);
+        Ok(())
+    }
+}
+
+impl Default for ClimateRecorder {
+    fn default() -> Self {
+        Self::new(crate::http::client())
+    }
+}
+
+/// one calendar month for one city
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthSummary {
+    pub year: i32,
+    pub month: u32,
+    pub days: usize,
+    pub mean_high_c: f64,
+    pub mean_low_c: f64,
+    pub warmest_c: i32,
+    pub coldest_c: i32,
+    pub rain_mm: f64,
+}
+
+/// monthly summaries for a city, newest first
+pub fn monthly_summaries(records: &[DailyRecord], city: &str) -> Vec<MonthSummary> {
+    let mut months: BTreeMap<(i32, u32), Vec<&DailyRecord>> = BTreeMap::new();
+    for record in records.iter().filter(|record| record.city == city) {
+        months
+            .entry((record.date.year(), record.date.month()))
+            .or_default()
+            .push(record);
+    }
+    months
+        .into_iter()
+        .rev()
+        .map(|((year, month), days)| {
+            let count = days.len() as f64;
+            MonthSummary {
+                year,
+                month,
+                days: days.len(),
+                mean_high_c: days.iter().map(|day| f64::from(day.high_c)).sum::<f64>() / count,
+                mean_low_c: days.iter().map(|day| f64::from(day.low_c)).sum::<f64>() / count,
+                warmest_c: days.iter().map(|day| day.high_c).max().unwrap_or_default(),
+                coldest_c: days.iter().map(|day| day.low_c).min().unwrap_or_default(),
+                rain_mm: days.iter().map(|day| day.rain_mm).sum(),
+            }
+        })
+        .collect()
+}
+
+/// days a month needs on record before it is compared with earlier years
+pub const MIN_HEADLINE_DAYS: usize = 7;
+
+/// how this month so far compares with the same days of the month in earlier tracked
+/// years, by daily means so a gap in the records doesn't read as a dry spell
+pub fn headline(records: &[DailyRecord], city: &str, today: NaiveDate) -> Option<String> {
+    // (year) -> (days, summed high, summed rain) over the 1st..=today of this month
+    let mut windows: BTreeMap<i32, (usize, f64, f64)> = BTreeMap::new();
+    for record in records.iter().filter(|record| {
+        record.city == city
+            && record.date.month() == today.month()
+            && record.date.day() <= today.day()
+            && record.date.year() <= today.year()
+    }) {
+        let window = windows.entry(record.date.year()).or_default();
+        window.0 += 1;
+        window.1 += f64::from(record.high_c);
+        window.2 += record.rain_mm;
+    }
+
+    let daily_means = |(days, high, rain): (usize, f64, f64)| {
+        (days >= MIN_HEADLINE_DAYS).then(|| (high / days as f64, rain / days as f64))
+    };
+    let (current_high, current_rain) = daily_means(windows.remove(&today.year())?)?;
+    let month = month_name(today.month());
+    let earlier: Vec<(f64, f64)> = windows.into_values().filter_map(daily_means).collect();
+    if earlier.is_empty() {
+        return Some(format!("First {} on record - keep tracking", month));
+    }
+
+    if earlier.iter().all(|&(_, rain)| current_rain > rain) {
+        return Some(format!("Wettest {} since you started tracking", month));
+    }
+    if earlier.iter().all(|&(_, rain)| current_rain < rain) {
+        return Some(format!("Driest {} since you started tracking", month));
+    }
+    if earlier.iter().all(|&(high, _)| current_high > high) {
+        return Some(format!("Warmest {} since you started tracking", month));
+    }
+    if earlier.iter().all(|&(high, _)| current_high < high) {
+        return Some(format!("Coolest {} since you started tracking", month));
+    }
+
+    let usual_high = earlier.iter().map(|&(high, _)| high).sum::<f64>() / earlier.len() as f64;
+    let difference = current_high - usual_high;
+    let feel = if difference >= 0.0 {
+        "warmer"
+    } else {
+        "cooler"
+    };
+    Some(format!(
+        "{} running {:.1}° {} than your usual",
+        month,
+        difference.abs(),
+        feel
+    ))
+}
+
+pub fn month_name(month: u32) -> &'static str {
+    const MONTHS: [&str; 12] = [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ];
+    MONTHS
+        .get(month.saturating_sub(1) as usize)
+        .copied()
+        .unwrap_or("?")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::with_temp_config_dir_for_test;
+
+    fn day(date: &str, high_c: i32, low_c: i32, rain_mm: f64) -> DailyRecord {
+        DailyRecord {
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            city: "Wellington".to_string(),
+            high_c,
+            low_c,
+            rain_mm,
+        }
+    }
+
+    #[test]
+    fn records_replace_same_day_readings() {
+        with_temp_config_dir_for_test(|| {
+            record(vec![day("2026-03-01", 20, 12, 0.0)]).unwrap();
+            record(vec![
+                day("2026-03-01", 21, 13, 4.5),
+                day("2026-03-02", 19, 11, 1.0),
+            ])
+            .unwrap();
+
+            let records = load().unwrap();
+            assert_eq!(records.len(), 2);
+            assert_eq!(records[0], day("2026-03-01", 21, 13, 4.5));
+        });
+    }
+
+    #[test]
+    fn summarises_months_newest_first() {
+        let records = vec![
+            day("2025-03-01", 22, 14, 2.0),
+            day("2025-03-02", 20, 12, 1.0),
+            day("2026-02-28", 25, 16, 0.0),
+            day("2026-03-01", 19, 11, 30.0),
+            day("2026-03-02", 18, 10, 12.5),
+        ];
+        let summaries = monthly_summaries(&records, "Wellington");
+        assert_eq!(summaries.len(), 3);
+        assert_eq!((summaries[0].year, summaries[0].month), (2026, 3));
+        assert_eq!(summaries[0].rain_mm, 42.5);
+        assert_eq!(summaries[0].mean_high_c, 18.5);
+        assert_eq!(summaries[0].coldest_c, 10);
+    }
+
+    #[test]
+    fn headlines_compare_the_same_days_once_the_month_has_enough() {
+        // a full, damp March last year and the first week of a wetter one now
+        let mut records: Vec<DailyRecord> = (1..=31)
+            .map(|d| day(&format!("2025-03-{:02}", d), 22, 14, 2.0))
+            .collect();
+        records.extend((1..=7).map(|d| day(&format!("2026-03-{:02}", d), 20, 12, 3.0)));
+
+        let sixth = NaiveDate::from_ymd_opt(2026, 3, 6).unwrap();
+        assert_eq!(headline(&records, "Wellington", sixth), None);
+
+        // the week's 21 mm is less than last March's 62 mm, but more per day
+        let seventh = NaiveDate::from_ymd_opt(2026, 3, 7).unwrap();
+        assert_eq!(
+            headline(&records, "Wellington", seventh).as_deref(),
+            Some("Wettest March since you started tracking")
+        );
+
+        // with last March's first week missing, there is nothing to compare with
+        records.retain(|record| record.date.year() == 2026 || record.date.day() > 7);
+        assert_eq!(
+            headline(&records, "Wellington", seventh).as_deref(),
+            Some("First March on record - keep tracking")
+        );
+    }
+
+    #[test]
+    fn parses_yesterday_for_each_place() {
+        let body = r#"[{"daily":{"time":["2026-03-01"],"temperature_2m_max":[21.6],"temperature_2m_min":[12.4],"precipitation_sum":[3.2]}},
+            {"daily":{"time":["2026-03-01"],"temperature_2m_max":[null],"temperature_2m_min":[8.0],"precipitation_sum":[0.0]}}]"#;
+        let records = parse_observed_days(body, &["Wellington", "Queenstown"]).unwrap();
+        assert_eq!(records, vec![day("2026-03-01", 22, 12, 3.2)]);
+        assert!(parse_observed_days(body, &["Wellington"]).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! system clock sanity check
+//! compares local time against an HTTP Date header once per session
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// any reliable HTTPS host works; reuse the weather provider rather than add a new one
+const SKEW_CHECK_URL: &str = "https://api.open-meteo.com/";
+
+/// parse an HTTP-date such as "Sun, 06 Nov 1994 08:49:37 GMT"
+pub fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value.trim())
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// seconds the local clock is ahead of the server (negative when behind);
+/// the local reading is the midpoint of the request so latency cancels out
+pub fn skew_seconds(sent: DateTime<Utc>, received: DateTime<Utc>, server: DateTime<Utc>) -> i64 {
+    let midpoint = sent + (received - sent) / 2;
+    (midpoint - server).num_seconds()
+}
+
+/// "2m 14s fast" or "40s slow"
+pub fn describe_skew(seconds: i64) -> String {
+    let direction = if seconds >= 0 { "fast" } else { "slow" };
+    let total = seconds.unsigned_abs();
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    let amount = if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, secs)
+    } else {
+        format!("{}s", secs)
+    };
+    format!("{} {}", amount, direction)
+}
+
+/// measure the local clock's skew against the server's Date header
+pub async fn measure_skew() -> Result<i64> {
+    let sent = Utc::now();
+    let response = crate::http::client()
+        .head(SKEW_CHECK_URL)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .context("failed to reach time check server")?;
+    let received = Utc::now();
+
+    let server = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_http_date)
+        .context("time check response had no usable Date header")?;
+    Ok(skew_seconds(sent, received, server))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn measures_skew_from_http_date() {
+        let server = parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").expect("date should parse");
+        assert_eq!(
+            server,
+            Utc.with_ymd_and_hms(1994, 11, 6, 8, 49, 37).unwrap()
+        );
+
+        // local clock reads 2m 15s..2m 17s ahead across a 2s round trip
+        let sent = Utc.with_ymd_and_hms(1994, 11, 6, 8, 51, 52).unwrap();
+        let received = Utc.with_ymd_and_hms(1994, 11, 6, 8, 51, 54).unwrap();
+        let skew = skew_seconds(sent, received, server);
+        assert_eq!(skew, 136);
+        assert_eq!(describe_skew(skew), "2m 16s fast");
+        assert_eq!(describe_skew(-40), "40s slow");
+    }
+}
@@ -1,24 +1,39 @@
 //! configuration management for nzi-cli
-//! handles loading and saving user preferences from ~/.config/nzi-cli/config.toml
+//! handles loading and saving user preferences from $XDG_CONFIG_HOME/nzi-cli/config.toml
+//! (~/.config/nzi-cli by default), with recorded data and caches in the XDG data and cache dirs
 //! follows margo-style config: simple toml with manual parsing
 
 use anyhow::{Context, Result, bail};
-use chrono::Utc;
+use chrono::{Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use crate::config_check::{self, ConfigProblem};
+use crate::events::parse_event_time;
+use crate::footer;
+use crate::icons::IconSet;
+use crate::keymap::Keymap;
+use crate::map::{
+    MAX_MAP_ZOOM, NZ_CITIES, NZ_LAT_MAX, NZ_LAT_MIN, NZ_LON_MAX, NZ_LON_MIN, WORLD_LON_MIN,
+};
+use crate::notify::Notifier;
 use crate::reference::{
     canonical_currency_code_for_country, country_by_code, focal_country_code_for_currency,
     is_valid_country_code, is_valid_currency_code, lookup_country, normalise_country_code,
-    normalise_currency_code, representative_city_by_country_code,
+    normalise_currency_code, representative_city_by_city_code, representative_city_by_country_code,
     representative_city_by_currency_code,
 };
+use crate::theme::{
+    Background, ColourDepth, CustomColours, SLOT_NAMES, ThemeName, parse_colour, parse_hex,
+};
 use crate::timezone::parse_city_timezone;
 
 /// city configuration with timezone and currency info
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct City {
     pub name: String,
     pub code: String,
@@ -28,6 +43,12 @@ pub struct City {
 }
 
 impl City {
+    /// whether two entries describe the same place, so repeating its code is harmless
+    pub fn same_place(&self, other: &City) -> bool {
+        self.name.trim().eq_ignore_ascii_case(other.name.trim())
+            && self.timezone.trim() == other.timezone.trim()
+    }
+
     pub fn wellington() -> Self {
         Self {
             name: "Wellington".to_string(),
@@ -189,6 +210,148 @@ pub struct DisplayConfig {
     /// editor command for /edit (defaults to $EDITOR or nvim)
     #[serde(default)]
     pub editor: Option<String>,
+    /// one-line world clock strip under the header
+    #[serde(default)]
+    pub show_world_clock: bool,
+    /// full date, ISO week and day-of-year for both time panel cities
+    #[serde(default)]
+    pub show_date_details: bool,
+    /// time panel draws the two clocks stacked in block digits, when it has the rows
+    #[serde(default)]
+    pub big_clock: bool,
+    /// day/month order: "day-month", "month-day" or "iso"
+    #[serde(default)]
+    pub date_format: DateFormat,
+    /// "monday" or "sunday"
+    #[serde(default)]
+    pub week_starts: WeekStart,
+    /// checklist panel above the footer, when it has items
+    #[serde(default = "default_true")]
+    pub show_checklist: bool,
+    /// warn when the system clock is off by more than this many seconds (0 disables the check)
+    #[serde(default = "default_clock_skew_warn_secs")]
+    pub clock_skew_warn_secs: u64,
+    /// individual animation layers, all under `show_animations`
+    #[serde(default)]
+    pub animations: AnimationLayers,
+    /// "mocha" (dark), "latte" (light), "gruvbox", "gruvbox-light", "nord", "dracula" or
+    /// "tokyo-night"
+    #[serde(default)]
+    pub theme: ThemeName,
+    /// "light", "dark" or "auto": swap the theme for its light or dark counterpart when it
+    /// doesn't suit the terminal; unset keeps the theme as chosen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<Background>,
+    /// "truecolor", "256" or "16"; unset goes by COLORTERM and TERM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colours: Option<ColourDepth>,
+    /// draw dim text and borders at full strength and mark focus and state in text
+    #[serde(default)]
+    pub high_contrast: bool,
+    /// "emoji", "ascii" or "nerd" (for Nerd Font patched fonts)
+    #[serde(default)]
+    pub icons: IconSet,
+    /// shown at the right of the footer whatever is focused, e.g. "{nz_time} {temp}";
+    /// placeholders are {nz_time}, {home_time}, {rate}, {weather_city} and {temp}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footer: Option<String>,
+    /// minutes without a key or click before the full-screen ambient view (0 never)
+    #[serde(default)]
+    pub ambient_after_mins: u64,
+}
+
+/// one of the decorative animations, for `/anim`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationLayer {
+    Waves,
+    Birds,
+    Sparkles,
+    Rainbow,
+    Flight,
+}
+
+impl AnimationLayer {
+    pub const ALL: [AnimationLayer; 5] = [
+        Self::Waves,
+        Self::Birds,
+        Self::Sparkles,
+        Self::Rainbow,
+        Self::Flight,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Waves => "waves",
+            Self::Birds => "birds",
+            Self::Sparkles => "sparkles",
+            Self::Rainbow => "rainbow",
+            Self::Flight => "flight",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|layer| layer.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// which animations run: waves around the NZ coast, birds and kiwi over the land,
+/// header sparkles, the colour-cycling coastline, and a flight home on the world map
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct AnimationLayers {
+    pub waves: bool,
+    pub birds: bool,
+    pub sparkles: bool,
+    pub rainbow: bool,
+    pub flight: bool,
+}
+
+impl Default for AnimationLayers {
+    fn default() -> Self {
+        Self {
+            waves: true,
+            birds: true,
+            sparkles: true,
+            rainbow: true,
+            flight: true,
+        }
+    }
+}
+
+impl AnimationLayers {
+    pub const NONE: AnimationLayers = AnimationLayers {
+        waves: false,
+        birds: false,
+        sparkles: false,
+        rainbow: false,
+        flight: false,
+    };
+
+    pub fn get(&self, layer: AnimationLayer) -> bool {
+        match layer {
+            AnimationLayer::Waves => self.waves,
+            AnimationLayer::Birds => self.birds,
+            AnimationLayer::Sparkles => self.sparkles,
+            AnimationLayer::Rainbow => self.rainbow,
+            AnimationLayer::Flight => self.flight,
+        }
+    }
+
+    pub fn set(&mut self, layer: AnimationLayer, on: bool) {
+        match layer {
+            AnimationLayer::Waves => self.waves = on,
+            AnimationLayer::Birds => self.birds = on,
+            AnimationLayer::Sparkles => self.sparkles = on,
+            AnimationLayer::Rainbow => self.rainbow = on,
+            AnimationLayer::Flight => self.flight = on,
+        }
+    }
+}
+
+fn default_clock_skew_warn_secs() -> u64 {
+    60
 }
 
 impl Default for DisplayConfig {
@@ -199,11 +362,47 @@ impl Default for DisplayConfig {
             show_animations: true,
             animation_speed_ms: 100,
             editor: None,
+            show_world_clock: false,
+            show_date_details: false,
+            big_clock: false,
+            date_format: DateFormat::default(),
+            week_starts: WeekStart::default(),
+            show_checklist: true,
+            clock_skew_warn_secs: default_clock_skew_warn_secs(),
+            animations: AnimationLayers::default(),
+            theme: ThemeName::default(),
+            background: None,
+            colours: None,
+            high_contrast: false,
+            icons: IconSet::default(),
+            footer: None,
+            ambient_after_mins: 0,
         }
     }
 }
 
 impl DisplayConfig {
+    /// the theme to draw in, after `background` has had its say
+    pub fn effective_theme(&self) -> ThemeName {
+        self.theme.for_background(self.background)
+    }
+
+    /// how many colours to draw with
+    pub fn effective_colour_depth(&self) -> ColourDepth {
+        self.colours.unwrap_or_else(ColourDepth::detect)
+    }
+}
+
+impl DisplayConfig {
+    /// the animation layers that actually run; none when `show_animations` is off
+    pub fn active_animations(&self) -> AnimationLayers {
+        if self.show_animations {
+            self.animations
+        } else {
+            AnimationLayers::NONE
+        }
+    }
+
     /// get the editor command, checking config, $EDITOR, then falling back to nvim
     pub fn get_editor(&self) -> String {
         self.editor
@@ -217,7 +416,7 @@ fn default_true() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TimeConfig {
     #[serde(default)]
     pub anchor_city_code: Option<String>,
@@ -227,16 +426,6 @@ pub struct TimeConfig {
     pub city_codes: Vec<String>,
 }
 
-impl Default for TimeConfig {
-    fn default() -> Self {
-        Self {
-            anchor_city_code: None,
-            target_city_codes: Vec::new(),
-            city_codes: Vec::new(),
-        }
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CurrencyConfig {
     #[serde(default = "default_true")]
@@ -263,18 +452,168 @@ impl Default for CurrencyConfig {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// how the map canvases draw their points
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MapMarkerStyle {
+    /// braille dots, 2×4 to a cell
+    #[default]
+    Braille,
+    /// half blocks, 1×2 to a cell, for fonts without braille
+    #[serde(alias = "halfblock")]
+    HalfBlock,
+    /// one dot to a cell
+    Dot,
+}
+
+impl MapMarkerStyle {
+    pub const ALL: [MapMarkerStyle; 3] = [Self::Braille, Self::HalfBlock, Self::Dot];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Braille => "braille",
+            Self::HalfBlock => "half-block",
+            Self::Dot => "dot",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().replace(['-', '_'], "");
+        Self::ALL
+            .into_iter()
+            .find(|style| style.name().replace('-', "").eq_ignore_ascii_case(&name))
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|style| *style == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// braille unless the terminal obviously can't show it: the Linux console and
+    /// old vt terminals have no braille glyphs, and a non-UTF-8 locale mangles them
+    pub fn detect(term: Option<&str>, locale: Option<&str>) -> Self {
+        let console = term.is_some_and(|term| term == "linux" || term.starts_with("vt"));
+        let legacy_charset = locale
+            .and_then(|locale| locale.split_once('.'))
+            .is_some_and(|(_, charset)| {
+                let charset = charset.split('@').next().unwrap_or_default().to_lowercase();
+                charset != "utf-8" && charset != "utf8"
+            });
+        if console || legacy_charset {
+            Self::HalfBlock
+        } else {
+            Self::Braille
+        }
+    }
+}
+
+/// where the equirectangular world map is centred
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum WorldProjection {
+    /// 0° in the middle, with NZ squeezed against the right-hand edge
+    #[default]
+    Greenwich,
+    /// 150°E in the middle, so the Pacific is whole and the seam runs down the Atlantic
+    Pacific,
+}
+
+impl WorldProjection {
+    /// the (lon) bounds of the whole map, 360° east from its western edge
+    pub fn lon_bounds(self) -> [f64; 2] {
+        let west = match self {
+            Self::Greenwich => WORLD_LON_MIN,
+            Self::Pacific => -30.0,
+        };
+        [west, west + 360.0]
+    }
+
+    /// a longitude moved by whole turns onto this map
+    pub fn wrap(self, lon: f64) -> f64 {
+        let [west, east] = self.lon_bounds();
+        if (west..east).contains(&lon) {
+            lon
+        } else {
+            west + (lon - west).rem_euclid(360.0)
+        }
+    }
+
+    /// a west-to-east span moved onto this map, keeping its width
+    pub fn wrap_span(self, [west, east]: [f64; 2]) -> [f64; 2] {
+        let start = self.wrap(west);
+        [start, start + (east - west)]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum MapMode {
     Cities,
+    #[default]
     #[serde(alias = "route")]
     Countries,
     Both,
 }
 
-impl Default for MapMode {
-    fn default() -> Self {
-        Self::Countries
+/// day/month order for dates shown across the panels
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DateFormat {
+    /// "14 Mar"
+    #[default]
+    DayMonth,
+    /// "Mar 14"
+    MonthDay,
+    /// "03-14"
+    Iso,
+}
+
+impl DateFormat {
+    /// day and month only, for compact rows
+    pub fn short(self, date: NaiveDate) -> String {
+        let pattern = match self {
+            Self::DayMonth => "%-d %b",
+            Self::MonthDay => "%b %-d",
+            Self::Iso => "%m-%d",
+        };
+        date.format(pattern).to_string()
+    }
+
+    /// weekday, day and month
+    pub fn with_weekday(self, date: NaiveDate) -> String {
+        format!("{} {}", date.format("%a"), self.short(date))
+    }
+
+    /// weekday and full date including the year
+    pub fn full(self, date: NaiveDate) -> String {
+        let pattern = match self {
+            Self::DayMonth => "%a %-d %b %Y",
+            Self::MonthDay => "%a %b %-d %Y",
+            Self::Iso => "%a %Y-%m-%d",
+        };
+        date.format(pattern).to_string()
+    }
+}
+
+/// first day of the week for week numbers and calendars
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    /// ISO 8601 week for Monday starts; Sunday-start weeks count from the year's first Sunday
+    pub fn week_number(self, date: NaiveDate) -> u32 {
+        match self {
+            Self::Monday => date.iso_week().week(),
+            Self::Sunday => (date.ordinal0() + 7 - date.weekday().num_days_from_sunday()) / 7,
+        }
     }
 }
 
@@ -290,6 +629,39 @@ pub struct MapConfig {
     pub focus_country_codes: Vec<String>,
     #[serde(default)]
     pub focal_country_code: Option<String>,
+    /// extra towns drawn on the NZ map beside the built-in cities
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<MapMarkerConfig>,
+    /// plot recent GeoNet earthquakes on the NZ map
+    #[serde(default = "default_true")]
+    pub quakes: bool,
+    /// tint the south of the NZ map when the aurora australis may be visible
+    #[serde(default = "default_true")]
+    pub aurora: bool,
+    /// draw the ISS and its recent track on the world map, refreshed every 15 seconds
+    #[serde(default)]
+    pub iss: bool,
+    /// start with the NZ coastline shaded by current temperatures ('H' on the map flips it)
+    #[serde(default)]
+    pub heat: bool,
+    /// draw NZ from the bundled outline rather than ratatui's coarser world coastline
+    #[serde(default)]
+    pub detailed_coastline: bool,
+    /// start with SH1 and the main state highways traced on the NZ map ('r' on the map flips it)
+    #[serde(default)]
+    pub highways: bool,
+    /// braille, half-block or dot; unset picks braille unless the terminal can't show it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marker_style: Option<MapMarkerStyle>,
+    /// a fixed coastline colour (palette name or #rrggbb) instead of the cycling rainbow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coastline_colour: Option<String>,
+    /// where the world map is centred: greenwich, or pacific to bring NZ in from the edge
+    #[serde(default)]
+    pub projection: WorldProjection,
+    /// saved views, cycled with ' on the map panel
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bookmarks: Vec<MapBookmark>,
 }
 
 impl Default for MapConfig {
@@ -300,140 +672,913 @@ impl Default for MapConfig {
             focus_city_code: None,
             focus_country_codes: Vec::new(),
             focal_country_code: None,
+            markers: Vec::new(),
+            quakes: true,
+            aurora: true,
+            iss: false,
+            heat: false,
+            detailed_coastline: false,
+            highways: false,
+            marker_style: None,
+            coastline_colour: None,
+            projection: WorldProjection::Greenwich,
+            bookmarks: Vec::new(),
         }
     }
 }
 
-/// main configuration structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Config {
-    /// where the user currently lives in NZ
-    pub current_city: City,
-    /// the user's home city overseas
-    pub home_city: City,
-    /// additional cities to track
-    pub tracked_cities: Vec<City>,
-    /// display preferences
-    pub display: DisplayConfig,
-    /// optional time list overrides
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub time: Option<TimeConfig>,
-    /// optional currency behaviour overrides
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub currency: Option<CurrencyConfig>,
-    /// optional map focus overrides
+impl MapConfig {
+    /// the configured marker style, or the one that suits this terminal
+    pub fn marker_style(&self) -> MapMarkerStyle {
+        self.marker_style.unwrap_or_else(|| {
+            let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+                .into_iter()
+                .filter_map(|name| std::env::var(name).ok())
+                .find(|value| !value.is_empty());
+            MapMarkerStyle::detect(std::env::var("TERM").ok().as_deref(), locale.as_deref())
+        })
+    }
+}
+
+/// most extra towns the NZ map will label
+pub const MAX_MAP_MARKERS: usize = 12;
+
+/// an extra town on the NZ map, e.g. Napier
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MapMarkerConfig {
+    pub name: String,
+    pub code: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// a saved map view, e.g. "Wellington harbour" at ×32; `/bookmark <name>` saves the shown one
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MapBookmark {
+    pub name: String,
+    /// a world map view; otherwise the NZ map
+    #[serde(default)]
+    pub world: bool,
+    /// centre of the view, with greenwich longitudes on the world map
+    pub lat: f64,
+    pub lon: f64,
+    /// each level halves the visible span
+    #[serde(default)]
+    pub zoom: u32,
+}
+
+/// most `[[markers]]` pins drawn on the world map
+pub const MAX_WORLD_MARKERS: usize = 12;
+
+/// a pinned place on the world map, e.g. a village no built-in marker covers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorldMarkerConfig {
+    pub label: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// a palette name ("peach", "sapphire", ...) or "#rrggbb"; green when unset
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub map: Option<MapConfig>,
+    pub colour: Option<String>,
 }
 
-impl Default for Config {
+/// working-hours windows used by the meeting planner
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlannerConfig {
+    #[serde(default = "default_work_start_hour")]
+    pub work_start_hour: u32,
+    #[serde(default = "default_work_end_hour")]
+    pub work_end_hour: u32,
+    #[serde(default = "default_awake_start_hour")]
+    pub awake_start_hour: u32,
+    #[serde(default = "default_awake_end_hour")]
+    pub awake_end_hour: u32,
+}
+
+fn default_work_start_hour() -> u32 {
+    9
+}
+
+fn default_work_end_hour() -> u32 {
+    17
+}
+
+fn default_awake_start_hour() -> u32 {
+    7
+}
+
+fn default_awake_end_hour() -> u32 {
+    22
+}
+
+impl Default for PlannerConfig {
     fn default() -> Self {
         Self {
-            // wellington is home - NZ anchor city
-            current_city: City::wellington(),
-            // boston as primary world city (for "around the world")
-            home_city: City::boston(),
-            // track other world cities for world clock
-            tracked_cities: vec![
-                City::london(),
-                City::los_angeles(),
-                City::austin(),
-                City::paris(),
-                City::berlin(),
-                City::sydney(),
-                City::tokyo(),
-                City::singapore(),
-                City::kuala_lumpur(),
-                City::rio(),
-                City::addis_ababa(),
-                City::dhaka(),
-                City::beijing(),
-            ],
-            display: DisplayConfig::default(),
-            time: None,
-            currency: None,
-            map: None,
+            work_start_hour: default_work_start_hour(),
+            work_end_hour: default_work_end_hour(),
+            awake_start_hour: default_awake_start_hour(),
+            awake_end_hour: default_awake_end_hour(),
         }
     }
 }
 
-impl Config {
-    /// path to config directory (~/.config/nzi-cli) - margo style
-    pub fn config_dir() -> PathBuf {
-        if let Some(path) = std::env::var_os("NZI_CONFIG_DIR") {
-            return PathBuf::from(path);
+impl PlannerConfig {
+    fn validate(&self, label: &str) -> Result<()> {
+        if self.work_start_hour >= self.work_end_hour || self.work_end_hour > 24 {
+            bail!(
+                "invalid {} working hours: {}-{}",
+                label,
+                self.work_start_hour,
+                self.work_end_hour
+            );
         }
-
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".config")
-            .join("nzi-cli")
+        if self.awake_start_hour > self.work_start_hour
+            || self.awake_end_hour < self.work_end_hour
+            || self.awake_end_hour > 24
+        {
+            bail!(
+                "invalid {} awake hours: {}-{} must contain working hours",
+                label,
+                self.awake_start_hour,
+                self.awake_end_hour
+            );
+        }
+        Ok(())
     }
+}
 
-    /// get the config file path
-    pub fn config_path() -> PathBuf {
-        Self::config_dir().join("config.toml")
-    }
+/// hours for the "best time to call" suggestions; each side falls back to [planner]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CallConfig {
+    /// my hours, in the converter's from city
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mine: Option<PlannerConfig>,
+    /// the other side's hours, in the converter's destination city
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theirs: Option<PlannerConfig>,
+}
 
-    pub fn snapshot_dir() -> PathBuf {
-        Self::config_dir().join("snapshots")
-    }
+/// a panel that renders a shell command's output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomPanelConfig {
+    pub title: String,
+    pub command: String,
+    #[serde(default = "default_custom_panel_refresh_secs")]
+    pub refresh_secs: u64,
+}
 
-    pub fn latest_snapshot_path() -> PathBuf {
-        Self::snapshot_dir().join("latest.toml")
-    }
+fn default_custom_panel_refresh_secs() -> u64 {
+    60
+}
 
-    /// load configuration from file, or create default if it doesn't exist
-    pub fn load() -> Result<Self> {
-        let config_path = Self::config_path();
+/// user-defined panels, and which built-in ones are shown
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PanelConfig {
+    #[serde(default)]
+    pub custom: Vec<CustomPanelConfig>,
+    /// false removes the panel from the layout and the focus cycle; the map has
+    /// `[map] enabled` instead
+    #[serde(default = "default_true")]
+    pub weather: bool,
+    #[serde(default = "default_true")]
+    pub time: bool,
+    #[serde(default = "default_true")]
+    pub currency: bool,
+}
 
-        if config_path.exists() {
-            let content = fs::read_to_string(&config_path).context("failed to read config file")?;
-            let mut config: Config =
-                toml::from_str(&content).context("failed to parse config file")?;
-            let mut updated = false;
-            updated |= config.normalize_legacy_cities();
-            updated |= config.normalize();
-            updated |= config.ensure_tracked_city(City::rio());
-            updated |= config.ensure_tracked_city(City::addis_ababa());
-            updated |= config.ensure_tracked_city(City::kuala_lumpur());
-            updated |= config.ensure_tracked_city(City::berlin());
-            updated |= config.ensure_tracked_city(City::dhaka());
-            updated |= config.ensure_tracked_city(City::beijing());
-            config.validate()?;
-            if updated {
-                config.save()?;
-            }
-            Ok(config)
-        } else {
-            // create default config
-            let config = Config::default();
-            config.validate()?;
-            config.save()?;
-            Ok(config)
+impl Default for PanelConfig {
+    fn default() -> Self {
+        Self {
+            custom: Vec::new(),
+            weather: true,
+            time: true,
+            currency: true,
         }
     }
+}
 
-    /// save configuration to file
-    pub fn save(&self) -> Result<()> {
-        let config_path = Self::config_path();
+/// opt-in notifications per alert type; each names a backend:
+/// "bell", "desktop", a webhook URL ("webhook:<url>" or a bare http(s) URL), or a shell command
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlertsConfig {
+    /// rain is forecast to start within the next couple of hours
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rain: Option<String>,
+    /// wind reaches the severe threshold
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wind: Option<String>,
+    /// an /event countdown is about to start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminder: Option<String>,
+    /// an /alarm rings; unset means the terminal bell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alarm: Option<String>,
+    #[serde(default = "default_severe_wind_kmph")]
+    pub severe_wind_kmph: i32,
+    /// an exchange rate crosses one of `fx_thresholds`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fx: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fx_thresholds: Vec<FxThreshold>,
+}
 
-        // ensure the config directory exists
-        if let Some(parent) = config_path.parent() {
-            fs::create_dir_all(parent).context("failed to create config directory")?;
+/// an exchange rate level worth hearing about, e.g. NZD/USD below 0.58
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FxThreshold {
+    /// "FROM/TO", priced as TO per one FROM
+    pub pair: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub above: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub below: Option<f64>,
+}
+
+impl FxThreshold {
+    /// the pair's currency codes, uppercased; `None` unless it is "FROM/TO"
+    pub fn currencies(&self) -> Option<(String, String)> {
+        let (from, to) = self.pair.split_once('/')?;
+        let (from, to) = (from.trim().to_uppercase(), to.trim().to_uppercase());
+        (is_valid_currency_code(&from) && is_valid_currency_code(&to)).then_some((from, to))
+    }
+}
+
+fn default_severe_wind_kmph() -> i32 {
+    60
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            rain: None,
+            wind: None,
+            reminder: None,
+            alarm: None,
+            severe_wind_kmph: default_severe_wind_kmph(),
+            fx: None,
+            fx_thresholds: Vec::new(),
+        }
+    }
+}
+
+/// one checklist item (/todo)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TodoItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// a countdown event, stored as a local time in one of the configured cities
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EventConfig {
+    pub title: String,
+    pub city_code: String,
+    /// local time in the event city, "YYYY-MM-DD HH:MM"
+    pub at: String,
+}
+
+/// a one-shot alarm at a local time in one of the configured cities
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AlarmConfig {
+    pub city_code: String,
+    /// local time in the alarm city, "YYYY-MM-DD HH:MM"
+    pub at: String,
+}
+
+/// split proportions and strip heights for the main dashboard
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LayoutConfig {
+    /// map width, as a percentage, beside the expanded weather grid
+    #[serde(default = "default_map_percent")]
+    pub map_percent: u16,
+    /// map width, as a percentage, beside the compact weather panel
+    #[serde(default = "default_compact_map_percent")]
+    pub compact_map_percent: u16,
+    /// rows for the time and currency panels under the expanded weather grid
+    #[serde(default = "default_bottom_height")]
+    pub bottom_height: u16,
+    /// rows for the time and currency panels under the compact weather panel
+    #[serde(default = "default_compact_bottom_height")]
+    pub compact_bottom_height: u16,
+    /// below this many columns the panels stack top to bottom (0 never stacks them)
+    #[serde(default = "default_narrow_width")]
+    pub narrow_width: u16,
+    /// rows the expanded weather grid keeps before the strip below gives way
+    #[serde(default = "default_weather_min_height")]
+    pub weather_min_height: u16,
+    /// rows the compact weather panel keeps before the strip below gives way
+    #[serde(default = "default_compact_weather_min_height")]
+    pub compact_weather_min_height: u16,
+}
+
+/// allowed `[layout]` map widths, and panel heights
+pub const LAYOUT_PERCENT_RANGE: std::ops::RangeInclusive<u16> = 10..=90;
+pub const LAYOUT_HEIGHT_RANGE: std::ops::RangeInclusive<u16> = 3..=40;
+
+fn default_map_percent() -> u16 {
+    33
+}
+
+fn default_compact_map_percent() -> u16 {
+    40
+}
+
+fn default_bottom_height() -> u16 {
+    7
+}
+
+fn default_compact_bottom_height() -> u16 {
+    11
+}
+
+fn default_narrow_width() -> u16 {
+    80
+}
+
+fn default_weather_min_height() -> u16 {
+    14
+}
+
+fn default_compact_weather_min_height() -> u16 {
+    10
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            map_percent: default_map_percent(),
+            compact_map_percent: default_compact_map_percent(),
+            bottom_height: default_bottom_height(),
+            compact_bottom_height: default_compact_bottom_height(),
+            narrow_width: default_narrow_width(),
+            weather_min_height: default_weather_min_height(),
+            compact_weather_min_height: default_compact_weather_min_height(),
+        }
+    }
+}
+
+/// panel focused when nzi starts
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StartupFocus {
+    #[default]
+    Map,
+    Weather,
+    Time,
+    Currency,
+}
+
+/// how the dashboard looks when nzi starts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StartupConfig {
+    /// "map", "weather", "time" or "currency"; the map falls back to weather when disabled
+    #[serde(default)]
+    pub focus: StartupFocus,
+    /// expanded forecast grid rather than the compact view
+    #[serde(default = "default_true")]
+    pub weather_expanded: bool,
+    /// NZ city shown in the weather panel, e.g. "AKL"; unset means Wellington
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather_city_code: Option<String>,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self {
+            focus: StartupFocus::default(),
+            weather_expanded: true,
+            weather_city_code: None,
+        }
+    }
+}
+
+/// colours laid over `display.theme`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub colors: ThemeColours,
+}
+
+/// "#rrggbb" colours by palette slot ("base", "text", "mauve", ...), and seven for the
+/// rainbow animations
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThemeColours {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rainbow: Vec<String>,
+    #[serde(flatten)]
+    pub slots: BTreeMap<String, String>,
+}
+
+/// a panel a `[tabs]` page can show on the right-hand side
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TabPanel {
+    Weather,
+    Time,
+    Currency,
+    /// recent earthquakes, from the map's quake layer
+    Quakes,
+    Checklist,
+    /// every `[[panel.custom]]` command panel
+    Custom,
+}
+
+impl TabPanel {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Weather => "Weather",
+            Self::Time => "Time",
+            Self::Currency => "Currency",
+            Self::Quakes => "Quakes",
+            Self::Checklist => "Checklist",
+            Self::Custom => "Custom",
+        }
+    }
+}
+
+/// pages of panels for the right-hand side, one shown at a time under a tab strip
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TabsConfig {
+    /// each page's one to three panels, top to bottom
+    #[serde(default = "default_tab_pages")]
+    pub pages: Vec<Vec<TabPanel>>,
+    /// the page shown, counted from 0; `[` and `]` change it and it's saved
+    #[serde(default)]
+    pub selected: usize,
+}
+
+fn default_tab_pages() -> Vec<Vec<TabPanel>> {
+    vec![
+        vec![TabPanel::Weather, TabPanel::Time, TabPanel::Currency],
+        vec![TabPanel::Quakes, TabPanel::Checklist, TabPanel::Custom],
+    ]
+}
+
+impl Default for TabsConfig {
+    fn default() -> Self {
+        Self {
+            pages: default_tab_pages(),
+            selected: 0,
+        }
+    }
+}
+
+/// limits on the cache directory's weather and rate snapshots
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// oldest snapshots are removed once the cache grows past this
+    #[serde(default = "default_cache_max_mb")]
+    pub max_mb: u64,
+    /// snapshots older than this are removed (0 keeps them whatever their age)
+    #[serde(default = "default_cache_max_age_days")]
+    pub max_age_days: u64,
+}
+
+fn default_cache_max_mb() -> u64 {
+    50
+}
+
+fn default_cache_max_age_days() -> u64 {
+    30
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_mb: default_cache_max_mb(),
+            max_age_days: default_cache_max_age_days(),
+        }
+    }
+}
+
+/// weather fetching
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WeatherConfig {
+    /// seconds between forecast refreshes
+    #[serde(default = "default_refresh_secs")]
+    pub refresh_secs: u64,
+}
+
+/// exchange rate fetching
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExchangeConfig {
+    /// seconds between rate refreshes
+    #[serde(default = "default_refresh_secs")]
+    pub refresh_secs: u64,
+}
+
+fn default_refresh_secs() -> u64 {
+    300
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        Self {
+            refresh_secs: default_refresh_secs(),
+        }
+    }
+}
+
+impl Default for ExchangeConfig {
+    fn default() -> Self {
+        Self {
+            refresh_secs: default_refresh_secs(),
+        }
+    }
+}
+
+/// main configuration structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// where the user currently lives in NZ
+    pub current_city: City,
+    /// the user's home city overseas
+    pub home_city: City,
+    /// additional cities to track
+    pub tracked_cities: Vec<City>,
+    /// display preferences
+    pub display: DisplayConfig,
+    /// optional time list overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<TimeConfig>,
+    /// optional currency behaviour overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<CurrencyConfig>,
+    /// optional map focus overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map: Option<MapConfig>,
+    /// places pinned on the world map
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<WorldMarkerConfig>,
+    /// optional meeting planner working hours
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub planner: Option<PlannerConfig>,
+    /// optional per-side hours for best-time-to-call suggestions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call: Option<CallConfig>,
+    /// user-defined panels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panel: Option<PanelConfig>,
+    /// countdown events shown in the time panel
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<EventConfig>,
+    /// pending /alarm times, removed once they ring
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alarms: Vec<AlarmConfig>,
+    /// opt-in weather alert cues
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alerts: Option<AlertsConfig>,
+    /// optional dashboard proportions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<LayoutConfig>,
+    /// optional startup focus and weather view
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup: Option<StartupConfig>,
+    /// optional weather refresh interval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather: Option<WeatherConfig>,
+    /// optional exchange rate refresh interval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exchange: Option<ExchangeConfig>,
+    /// optional cache size and age limits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheConfig>,
+    /// optional custom colours
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ThemeConfig>,
+    /// optional right-hand tabs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tabs: Option<TabsConfig>,
+    /// travel checklist items
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub todos: Vec<TodoItem>,
+    /// freeform notes keyed by city code, shown when that city is the destination
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub notes: BTreeMap<String, String>,
+    /// main-screen actions moved to other keys, e.g. quit = "Q"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            // wellington is home - NZ anchor city
+            current_city: City::wellington(),
+            // boston as primary world city (for "around the world")
+            home_city: City::boston(),
+            // track other world cities for world clock
+            tracked_cities: vec![
+                City::london(),
+                City::los_angeles(),
+                City::austin(),
+                City::paris(),
+                City::berlin(),
+                City::sydney(),
+                City::tokyo(),
+                City::singapore(),
+                City::kuala_lumpur(),
+                City::rio(),
+                City::addis_ababa(),
+                City::dhaka(),
+                City::beijing(),
+            ],
+            display: DisplayConfig::default(),
+            time: None,
+            currency: None,
+            map: None,
+            markers: Vec::new(),
+            planner: None,
+            call: None,
+            panel: None,
+            events: Vec::new(),
+            alarms: Vec::new(),
+            alerts: None,
+            layout: None,
+            startup: None,
+            weather: None,
+            exchange: None,
+            cache: None,
+            theme: None,
+            tabs: None,
+            todos: Vec::new(),
+            notes: BTreeMap::new(),
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+/// earlier versions of the config file kept beside it as `config.toml.bak.1` (newest) on up
+const CONFIG_BACKUPS: usize = 5;
+
+/// a config file named on the command line, read and saved instead of config.toml
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+/// command-line overrides for this session
+static SESSION_OVERRIDES: OnceLock<SessionOverrides> = OnceLock::new();
+/// the config file's text as this process last read or wrote it, so the file watcher
+/// can tell an outside edit from our own save
+static SYNCED_CONTENT: Mutex<Option<String>> = Mutex::new(None);
+/// the problems found by the last load; while there are any, saves leave the file alone
+/// rather than write the defaults used in their place over what the user wrote
+static LOADED_PROBLEMS: Mutex<Vec<ConfigProblem>> = Mutex::new(Vec::new());
+
+/// settings given on the command line: applied over every load and kept out of every
+/// save, so the file keeps its own values
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionOverrides {
+    /// code of the NZ city to use as the current city
+    pub city: Option<String>,
+    /// code of the city to use as home
+    pub home_city: Option<String>,
+    pub no_animations: bool,
+    pub theme: Option<ThemeName>,
+}
+
+impl SessionOverrides {
+    pub fn apply(&self, config: &mut Config) -> Result<()> {
+        if let Some(code) = &self.city {
+            let Some(city) = config.city_by_code(code) else {
+                bail!("unknown city code for --city: {}", code);
+            };
+            if !matches!(
+                city.timezone.as_str(),
+                "Pacific/Auckland" | "Pacific/Chatham"
+            ) {
+                bail!("--city must be a New Zealand city, not {}", city.name);
+            }
+            config.current_city = city;
+        }
+        if let Some(code) = &self.home_city {
+            let Some(city) = config.city_by_code(code) else {
+                bail!("unknown city code for --home-city: {}", code);
+            };
+            config.home_city = city;
+        }
+        if self.no_animations {
+            config.display.show_animations = false;
+        }
+        if let Some(theme) = self.theme {
+            // a theme named on the command line is used as it is
+            config.display.theme = theme;
+            config.display.background = None;
+        }
+        Ok(())
+    }
+
+    /// put the file's own values back into each overridden field before a save
+    fn restore(&self, config: &mut Config, on_disk: &Config) {
+        if self.city.is_some() {
+            config.current_city = on_disk.current_city.clone();
+        }
+        if self.home_city.is_some() {
+            config.home_city = on_disk.home_city.clone();
+        }
+        if self.no_animations {
+            config.display.show_animations = on_disk.display.show_animations;
+        }
+        if self.theme.is_some() {
+            config.display.theme = on_disk.display.theme;
+            config.display.background = on_disk.display.background;
+        }
+    }
+}
+
+impl Config {
+    /// use `config_file` instead of config.toml and `overrides` over it for the rest of
+    /// the session; called once, before the first load
+    pub fn start_session(config_file: Option<PathBuf>, overrides: SessionOverrides) {
+        if let Some(path) = config_file {
+            let _ = CONFIG_FILE.set(path);
+        }
+        let _ = SESSION_OVERRIDES.set(overrides);
+    }
+
+    /// path to config directory: $XDG_CONFIG_HOME/nzi-cli, else ~/.config/nzi-cli - margo style
+    pub fn config_dir() -> PathBuf {
+        if let Some(path) = std::env::var_os("NZI_CONFIG_DIR") {
+            return PathBuf::from(path);
+        }
+
+        xdg_dir(std::env::var_os("XDG_CONFIG_HOME"), ".config")
+    }
+
+    /// where recorded data (the ledger, climate records) lives: $XDG_DATA_HOME/nzi-cli,
+    /// else ~/.local/share/nzi-cli; NZI_CONFIG_DIR keeps everything in one directory
+    pub fn data_dir() -> PathBuf {
+        if let Some(path) = std::env::var_os("NZI_CONFIG_DIR") {
+            return PathBuf::from(path);
+        }
+
+        xdg_dir(std::env::var_os("XDG_DATA_HOME"), ".local/share")
+    }
+
+    /// where copies of fetched data that can be rebuilt live: $XDG_CACHE_HOME/nzi-cli,
+    /// else ~/.cache/nzi-cli
+    pub fn cache_dir() -> PathBuf {
+        if let Some(path) = std::env::var_os("NZI_CONFIG_DIR") {
+            return PathBuf::from(path).join("cache");
+        }
+
+        xdg_dir(std::env::var_os("XDG_CACHE_HOME"), ".cache")
+    }
+
+    /// move files out of the single ~/.config/nzi-cli directory earlier versions kept
+    /// everything in; returns the names of whatever moved
+    pub fn migrate_legacy_files() -> Result<Vec<String>> {
+        if std::env::var_os("NZI_CONFIG_DIR").is_some() {
+            return Ok(Vec::new());
+        }
+        let Some(home) = dirs::home_dir() else {
+            return Ok(Vec::new());
+        };
+        migrate_files(
+            &home.join(".config").join("nzi-cli"),
+            &Self::config_dir(),
+            &Self::data_dir(),
+        )
+    }
+
+    /// get the config file path: `--config` when given, else config.toml
+    pub fn config_path() -> PathBuf {
+        if let Some(path) = CONFIG_FILE.get() {
+            return path.clone();
+        }
+        Self::config_dir().join("config.toml")
+    }
+
+    pub fn snapshot_dir() -> PathBuf {
+        Self::config_dir().join("snapshots")
+    }
+
+    pub fn latest_snapshot_path() -> PathBuf {
+        Self::snapshot_dir().join("latest.toml")
+    }
+
+    /// load configuration from file, or create default if it doesn't exist
+    pub fn load() -> Result<Self> {
+        Self::load_checked().map(|(config, _)| config)
+    }
+
+    /// load configuration, falling back to the default for each field that doesn't parse
+    /// or validate; the problems come back alongside, and the file is left as written
+    /// until they're fixed
+    pub fn load_checked() -> Result<(Self, Vec<ConfigProblem>)> {
+        let (mut config, problems) = Self::read_checked()?;
+        if let Some(overrides) = SESSION_OVERRIDES.get() {
+            overrides.apply(&mut config)?;
+        }
+        Ok((config, problems))
+    }
+
+    fn read_checked() -> Result<(Self, Vec<ConfigProblem>)> {
+        let config_path = Self::config_path();
+
+        if config_path.exists() {
+            let content = fs::read_to_string(&config_path).context("failed to read config file")?;
+            Self::mark_synced(&content);
+            let (mut config, mut problems) = config_check::parse(&content);
+            let mut updated = false;
+            updated |= config.normalize_legacy_cities();
+            updated |= config.normalize();
+            updated |= config.ensure_tracked_city(City::rio());
+            updated |= config.ensure_tracked_city(City::addis_ababa());
+            updated |= config.ensure_tracked_city(City::kuala_lumpur());
+            updated |= config.ensure_tracked_city(City::berlin());
+            updated |= config.ensure_tracked_city(City::dhaka());
+            updated |= config.ensure_tracked_city(City::beijing());
+            problems.extend(config_check::repair(&mut config, &content));
+            config.validate()?;
+            Self::mark_problems(&problems);
+            if updated && problems.is_empty() {
+                config.save()?;
+            }
+            Ok((config, problems))
+        } else {
+            // create default config
+            Self::mark_problems(&[]);
+            let config = Config::default();
+            config.validate()?;
+            config.save()?;
+            Ok((config, Vec::new()))
+        }
+    }
+
+    /// save configuration to file
+    pub fn save(&self) -> Result<()> {
+        self.write(false)
+    }
+
+    /// save an edit the user made to the config itself (an applied draft or the
+    /// settings), keeping the file it replaces as `config.toml.bak.1`; the app's own
+    /// frequent saves of todos, alarms and the like use `save` and leave the backups alone
+    pub fn save_backed_up(&self) -> Result<()> {
+        self.write(true)
+    }
+
+    fn write(&self, back_up: bool) -> Result<()> {
+        let held = LOADED_PROBLEMS.lock().map_or(0, |problems| problems.len());
+        if held > 0 {
+            bail!(
+                "config.toml has {} problem{}; fix the file (/problems) and /reload before nzi writes to it",
+                held,
+                if held == 1 { "" } else { "s" }
+            );
+        }
+        let config_path = Self::config_path();
+
+        // ensure the config directory exists
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent).context("failed to create config directory")?;
         }
 
         let mut config = self.clone();
+        if let Some(overrides) = SESSION_OVERRIDES.get()
+            && *overrides != SessionOverrides::default()
+        {
+            let on_disk = fs::read_to_string(&config_path)
+                .map(|content| config_check::parse(&content).0)
+                .unwrap_or_default();
+            overrides.restore(&mut config, &on_disk);
+        }
         config.normalize();
         config.validate()?;
 
         let content = toml::to_string_pretty(&config).context("failed to serialise config")?;
 
-        fs::write(&config_path, content).context("failed to write config file")?;
+        if fs::read_to_string(&config_path).ok().as_deref() != Some(content.as_str()) {
+            if back_up {
+                rotate_backups(&config_path, CONFIG_BACKUPS)?;
+            }
+            write_atomically(&config_path, &content).context("failed to write config file")?;
+        }
+        Self::mark_synced(&content);
 
         Ok(())
     }
 
+    /// keep the config file as `config.toml.bak.1`, shifting the older backups up
+    pub fn back_up() -> Result<()> {
+        rotate_backups(&Self::config_path(), CONFIG_BACKUPS)
+    }
+
+    fn mark_problems(problems: &[ConfigProblem]) {
+        if let Ok(mut loaded) = LOADED_PROBLEMS.lock() {
+            *loaded = problems.to_vec();
+        }
+    }
+
+    fn mark_synced(content: &str) {
+        if let Ok(mut synced) = SYNCED_CONTENT.lock() {
+            *synced = Some(content.to_string());
+        }
+    }
+
+    /// whether `content` is what this process last read from or wrote to the config file
+    pub fn is_synced(content: &str) -> bool {
+        SYNCED_CONTENT
+            .lock()
+            .is_ok_and(|synced| synced.as_deref() == Some(content))
+    }
+
     pub fn save_snapshot(&self) -> Result<PathBuf> {
         let snapshot_dir = Self::snapshot_dir();
         fs::create_dir_all(&snapshot_dir).context("failed to create snapshot directory")?;
@@ -538,6 +1683,43 @@ impl Config {
         self.representative_city_for_country_code(country_code)
     }
 
+    /// a city by code: a configured one, one of the NZ map cities, or a bundled
+    /// representative city
+    pub fn city_by_code(&self, code: &str) -> Option<City> {
+        let code = code.trim().to_uppercase();
+        if let Some(city) = self
+            .all_cities()
+            .into_iter()
+            .find(|city| city.code.eq_ignore_ascii_case(&code))
+        {
+            return Some(city.clone());
+        }
+        if let Some(city) = NZ_CITIES.iter().find(|city| city.code == code) {
+            return Some(City {
+                name: city.name.to_string(),
+                code: city.code.to_string(),
+                country: "New Zealand".to_string(),
+                timezone: "Pacific/Auckland".to_string(),
+                currency: "NZD".to_string(),
+            });
+        }
+        representative_city_by_city_code(&code).map(|city| City {
+            name: city.city_name.to_string(),
+            code: city.city_code.to_string(),
+            country: city.country_name.to_string(),
+            timezone: city.timezone.to_string(),
+            currency: city.currency_code.to_string(),
+        })
+    }
+
+    /// a configured city that already uses `city`'s code for a different place
+    pub fn code_conflict(&self, city: &City) -> Option<&City> {
+        self.all_cities().into_iter().find(|existing| {
+            existing.code.trim().eq_ignore_ascii_case(city.code.trim())
+                && !existing.same_place(city)
+        })
+    }
+
     /// get all city codes for time conversion cycling
     pub fn all_city_codes(&self) -> Vec<String> {
         self.all_cities().iter().map(|c| c.code.clone()).collect()
@@ -607,6 +1789,104 @@ impl Config {
         self.currency.clone().unwrap_or_default()
     }
 
+    pub fn shows_weather_panel(&self) -> bool {
+        self.panel.as_ref().is_none_or(|panel| panel.weather) && self.tab_shows(TabPanel::Weather)
+    }
+
+    pub fn shows_time_panel(&self) -> bool {
+        self.panel.as_ref().is_none_or(|panel| panel.time) && self.tab_shows(TabPanel::Time)
+    }
+
+    pub fn shows_currency_panel(&self) -> bool {
+        self.panel.as_ref().is_none_or(|panel| panel.currency) && self.tab_shows(TabPanel::Currency)
+    }
+
+    /// the panels on the `[tabs]` page shown, when there are tabs
+    pub fn selected_tab(&self) -> Option<&[TabPanel]> {
+        let tabs = self.tabs.as_ref()?;
+        let last = tabs.pages.len().checked_sub(1)?;
+        Some(&tabs.pages[tabs.selected.min(last)])
+    }
+
+    /// whether the shown `[tabs]` page has `panel`; everything is shown without tabs
+    fn tab_shows(&self, panel: TabPanel) -> bool {
+        self.selected_tab().is_none_or(|page| page.contains(&panel))
+    }
+
+    pub fn effective_custom_panels(&self) -> Vec<CustomPanelConfig> {
+        self.panel
+            .as_ref()
+            .map(|panel| panel.custom.clone())
+            .unwrap_or_default()
+    }
+
+    /// the note for a city code, matched case-insensitively
+    pub fn note_for(&self, code: &str) -> Option<&str> {
+        self.notes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(code))
+            .map(|(_, note)| note.as_str())
+    }
+
+    pub fn effective_alerts(&self) -> AlertsConfig {
+        self.alerts.clone().unwrap_or_default()
+    }
+
+    pub fn effective_layout(&self) -> LayoutConfig {
+        self.layout.unwrap_or_default()
+    }
+
+    pub fn effective_startup(&self) -> StartupConfig {
+        self.startup.clone().unwrap_or_default()
+    }
+
+    /// `[theme.colors]` as colours; entries that don't parse are left out
+    pub fn custom_colours(&self) -> CustomColours {
+        let mut custom = CustomColours::default();
+        let Some(theme) = &self.theme else {
+            return custom;
+        };
+        for (name, value) in &theme.colors.slots {
+            if let Some(index) = SLOT_NAMES.iter().position(|slot| slot == name) {
+                custom.slots[index] = parse_hex(value);
+            }
+        }
+        let rainbow: Vec<_> = theme
+            .colors
+            .rainbow
+            .iter()
+            .filter_map(|value| parse_hex(value))
+            .collect();
+        custom.rainbow = rainbow.try_into().ok();
+        custom
+    }
+
+    pub fn effective_cache(&self) -> CacheConfig {
+        self.cache.unwrap_or_default()
+    }
+
+    pub fn effective_weather(&self) -> WeatherConfig {
+        self.weather.unwrap_or_default()
+    }
+
+    pub fn effective_exchange(&self) -> ExchangeConfig {
+        self.exchange.unwrap_or_default()
+    }
+
+    pub fn effective_planner_settings(&self) -> PlannerConfig {
+        self.planner.clone().unwrap_or_default()
+    }
+
+    /// (mine, theirs) hours for call suggestions
+    pub fn effective_call_hours(&self) -> (PlannerConfig, PlannerConfig) {
+        let planner = self.effective_planner_settings();
+        let call = self.call.clone().unwrap_or_default();
+        (
+            call.mine.unwrap_or_else(|| planner.clone()),
+            call.theirs.unwrap_or(planner),
+        )
+    }
+
     pub fn effective_map_settings(&self) -> MapConfig {
         let mut map = self.map.clone().unwrap_or_default();
         map.mode = MapMode::Countries;
@@ -701,12 +1981,12 @@ impl Config {
     }
 
     fn normalize_city_name_and_code_to_boston(city: &mut City) -> bool {
-        if city.code.eq_ignore_ascii_case("NYC") || city.name.eq_ignore_ascii_case("New York") {
-            if city.code != "BOS" || city.name != "Boston" {
-                city.code = "BOS".to_string();
-                city.name = "Boston".to_string();
-                return true;
-            }
+        if (city.code.eq_ignore_ascii_case("NYC") || city.name.eq_ignore_ascii_case("New York"))
+            && (city.code != "BOS" || city.name != "Boston")
+        {
+            city.code = "BOS".to_string();
+            city.name = "Boston".to_string();
+            return true;
         }
         false
     }
@@ -893,101 +2173,456 @@ impl Config {
         }
     }
 
-    fn validate(&self) -> Result<()> {
-        let mut seen = HashSet::new();
+    pub(crate) fn validate(&self) -> Result<()> {
+        let mut seen: BTreeMap<String, &City> = BTreeMap::new();
 
         for city in self.all_cities() {
             let code = city.code.trim().to_uppercase();
             if code.is_empty() {
                 bail!("city code cannot be empty");
             }
-            if !seen.insert(code.clone()) {
-                bail!("duplicate city code: {}", code);
+            // the same city may appear twice (e.g. home is also the current city),
+            // but two different places sharing a code would make lookups ambiguous
+            if let Some(existing) = seen.get(&code)
+                && !existing.same_place(city)
+            {
+                bail!(
+                    "duplicate city code {}: used by both {} and {}; give one of them a different code",
+                    code,
+                    existing.name,
+                    city.name
+                );
+            }
+            seen.insert(code, city);
+
+            parse_city_timezone(&city.timezone).with_context(|| {
+                format!("invalid timezone for {}: {}", city.name, city.timezone)
+            })?;
+
+            if !is_valid_currency_code(&city.currency) {
+                bail!("invalid currency code for {}: {}", city.name, city.currency);
+            }
+        }
+
+        if let Some(time) = &self.time {
+            if let Some(anchor_city_code) = &time.anchor_city_code
+                && !self
+                    .all_city_codes()
+                    .iter()
+                    .any(|code| code.eq_ignore_ascii_case(anchor_city_code))
+            {
+                bail!("unknown time.anchor_city_code entry: {}", anchor_city_code);
+            }
+
+            for city_code in &time.target_city_codes {
+                if !self
+                    .all_city_codes()
+                    .iter()
+                    .any(|code| code.eq_ignore_ascii_case(city_code))
+                {
+                    bail!("unknown time.target_city_codes entry: {}", city_code);
+                }
+            }
+
+            for city_code in &time.city_codes {
+                if !self
+                    .all_city_codes()
+                    .iter()
+                    .any(|code| code.eq_ignore_ascii_case(city_code))
+                {
+                    bail!("unknown time.city_codes entry: {}", city_code);
+                }
+            }
+        }
+
+        if let Some(currency) = &self.currency {
+            for country_code in &currency.country_codes {
+                if !is_valid_country_code(country_code) || country_by_code(country_code).is_none() {
+                    bail!("unknown currency.country_codes entry: {}", country_code);
+                }
+            }
+            if let Some(default_from) = &currency.default_from
+                && !is_valid_currency_code(default_from)
+            {
+                bail!("invalid currency.default_from: {}", default_from);
+            }
+            if let Some(default_to) = &currency.default_to
+                && !is_valid_currency_code(default_to)
+            {
+                bail!("invalid currency.default_to: {}", default_to);
+            }
+            for code in &currency.pinned_codes {
+                if !is_valid_currency_code(code) {
+                    bail!("invalid currency.pinned_codes entry: {}", code);
+                }
+            }
+        }
+
+        if let Some(map) = &self.map {
+            if let Some(city_code) = &map.focus_city_code
+                && !self
+                    .all_city_codes()
+                    .iter()
+                    .any(|code| code.eq_ignore_ascii_case(city_code))
+            {
+                bail!("unknown map.focus_city_code: {}", city_code);
+            }
+
+            if let Some(country_code) = &map.focal_country_code
+                && (!is_valid_country_code(country_code) || country_by_code(country_code).is_none())
+            {
+                bail!("invalid map.focal_country_code: {}", country_code);
+            }
+
+            for country_code in &map.focus_country_codes {
+                if !is_valid_country_code(country_code) || country_by_code(country_code).is_none() {
+                    bail!("invalid map.focus_country_codes entry: {}", country_code);
+                }
+            }
+
+            if map.markers.len() > MAX_MAP_MARKERS {
+                bail!(
+                    "map.markers has {} entries; the NZ map shows at most {}",
+                    map.markers.len(),
+                    MAX_MAP_MARKERS
+                );
+            }
+            let mut marker_codes: Vec<&str> = NZ_CITIES.iter().map(|city| city.code).collect();
+            for marker in &map.markers {
+                if marker.name.trim().is_empty() || marker.code.trim().is_empty() {
+                    bail!("map.markers entries need a name and a code");
+                }
+                if !(NZ_LAT_MIN..=NZ_LAT_MAX).contains(&marker.lat)
+                    || !(NZ_LON_MIN..=NZ_LON_MAX).contains(&marker.lon)
+                {
+                    bail!(
+                        "map marker {} at {}, {} is outside the NZ map",
+                        marker.name,
+                        marker.lat,
+                        marker.lon
+                    );
+                }
+                if marker_codes
+                    .iter()
+                    .any(|code| code.eq_ignore_ascii_case(marker.code.trim()))
+                {
+                    bail!("duplicate map marker code: {}", marker.code);
+                }
+                marker_codes.push(marker.code.trim());
+            }
+
+            if let Some(colour) = &map.coastline_colour
+                && parse_colour(colour).is_none()
+            {
+                bail!(
+                    "map.coastline_colour {} is unknown (use a name like lavender or #rrggbb)",
+                    colour
+                );
+            }
+
+            for bookmark in &map.bookmarks {
+                if bookmark.name.trim().is_empty() {
+                    bail!("map.bookmarks entries need a name");
+                }
+                if bookmark.zoom > MAX_MAP_ZOOM {
+                    bail!(
+                        "map bookmark {} zoom {} is past the deepest level, {}",
+                        bookmark.name,
+                        bookmark.zoom,
+                        MAX_MAP_ZOOM
+                    );
+                }
+                let on_map = if bookmark.world {
+                    (-90.0..=90.0).contains(&bookmark.lat)
+                        && (-180.0..=180.0).contains(&bookmark.lon)
+                } else {
+                    (NZ_LAT_MIN..=NZ_LAT_MAX).contains(&bookmark.lat)
+                        && (NZ_LON_MIN..=NZ_LON_MAX).contains(&bookmark.lon)
+                };
+                if !on_map {
+                    bail!(
+                        "map bookmark {} at {}, {} is outside the {} map",
+                        bookmark.name,
+                        bookmark.lat,
+                        bookmark.lon,
+                        if bookmark.world { "world" } else { "NZ" }
+                    );
+                }
+            }
+        }
+
+        if self.markers.len() > MAX_WORLD_MARKERS {
+            bail!(
+                "markers has {} entries; the world map shows at most {}",
+                self.markers.len(),
+                MAX_WORLD_MARKERS
+            );
+        }
+        for marker in &self.markers {
+            if marker.label.trim().is_empty() {
+                bail!("markers entries need a label");
+            }
+            if !(-90.0..=90.0).contains(&marker.lat) || !(-180.0..=180.0).contains(&marker.lon) {
+                bail!(
+                    "marker {} at {}, {} is not a valid position",
+                    marker.label,
+                    marker.lat,
+                    marker.lon
+                );
+            }
+            if let Some(colour) = &marker.colour
+                && parse_colour(colour).is_none()
+            {
+                bail!(
+                    "marker {} has unknown colour {} (use a name like peach or #rrggbb)",
+                    marker.label,
+                    colour
+                );
+            }
+        }
+
+        if let Some(format) = &self.display.footer
+            && let Some(name) = footer::unknown_placeholder(format)
+        {
+            bail!(
+                "display.footer has unknown placeholder {{{}}} (use {})",
+                name,
+                footer::PLACEHOLDERS
+                    .map(|name| format!("{{{}}}", name))
+                    .join(", ")
+            );
+        }
+
+        if let Some(layout) = &self.layout {
+            for (name, percent) in [
+                ("layout.map_percent", layout.map_percent),
+                ("layout.compact_map_percent", layout.compact_map_percent),
+            ] {
+                if !LAYOUT_PERCENT_RANGE.contains(&percent) {
+                    bail!(
+                        "{} must be between {} and {}: {}",
+                        name,
+                        LAYOUT_PERCENT_RANGE.start(),
+                        LAYOUT_PERCENT_RANGE.end(),
+                        percent
+                    );
+                }
+            }
+            for (name, height) in [
+                ("layout.bottom_height", layout.bottom_height),
+                ("layout.compact_bottom_height", layout.compact_bottom_height),
+                ("layout.weather_min_height", layout.weather_min_height),
+                (
+                    "layout.compact_weather_min_height",
+                    layout.compact_weather_min_height,
+                ),
+            ] {
+                if !LAYOUT_HEIGHT_RANGE.contains(&height) {
+                    bail!(
+                        "{} must be between {} and {}: {}",
+                        name,
+                        LAYOUT_HEIGHT_RANGE.start(),
+                        LAYOUT_HEIGHT_RANGE.end(),
+                        height
+                    );
+                }
+            }
+        }
+
+        if let Some(code) = self
+            .startup
+            .as_ref()
+            .and_then(|startup| startup.weather_city_code.as_deref())
+            && !NZ_CITIES.iter().any(|city| city.code == code)
+        {
+            bail!(
+                "startup.weather_city_code {} isn't an NZ weather city (e.g. AKL, WLG, CHC)",
+                code
+            );
+        }
+
+        if self.effective_weather().refresh_secs == 0 {
+            bail!("weather.refresh_secs must be at least 1");
+        }
+        if self.effective_exchange().refresh_secs == 0 {
+            bail!("exchange.refresh_secs must be at least 1");
+        }
+        if self.effective_cache().max_mb == 0 {
+            bail!("cache.max_mb must be at least 1");
+        }
+        if let Some(theme) = &self.theme {
+            for (name, value) in &theme.colors.slots {
+                if !SLOT_NAMES.contains(&name.as_str()) {
+                    bail!(
+                        "theme.colors.{} isn't a palette colour (use one of {})",
+                        name,
+                        SLOT_NAMES.join(", ")
+                    );
+                }
+                if parse_hex(value).is_none() {
+                    bail!("theme.colors.{} must be #rrggbb: {}", name, value);
+                }
+            }
+            let rainbow = &theme.colors.rainbow;
+            if !rainbow.is_empty() && rainbow.len() != 7 {
+                bail!(
+                    "theme.colors.rainbow needs 7 colours, not {}",
+                    rainbow.len()
+                );
             }
-
-            parse_city_timezone(&city.timezone).with_context(|| {
-                format!("invalid timezone for {}: {}", city.name, city.timezone)
-            })?;
-
-            if !is_valid_currency_code(&city.currency) {
-                bail!("invalid currency code for {}: {}", city.name, city.currency);
+            if let Some(value) = rainbow.iter().find(|value| parse_hex(value).is_none()) {
+                bail!("theme.colors.rainbow entries must be #rrggbb: {}", value);
             }
         }
 
-        if let Some(time) = &self.time {
-            if let Some(anchor_city_code) = &time.anchor_city_code
-                && !self
-                    .all_city_codes()
-                    .iter()
-                    .any(|code| code.eq_ignore_ascii_case(anchor_city_code))
-            {
-                bail!("unknown time.anchor_city_code entry: {}", anchor_city_code);
+        if let Some(tabs) = &self.tabs {
+            if tabs.pages.is_empty() {
+                bail!("tabs.pages needs at least one page");
             }
-
-            for city_code in &time.target_city_codes {
-                if !self
-                    .all_city_codes()
+            for (index, page) in tabs.pages.iter().enumerate() {
+                if page.is_empty() || page.len() > 3 {
+                    bail!("tabs.pages[{}] must list one to three panels", index);
+                }
+                if page
                     .iter()
-                    .any(|code| code.eq_ignore_ascii_case(city_code))
+                    .enumerate()
+                    .any(|(at, panel)| page[..at].contains(panel))
                 {
-                    bail!("unknown time.target_city_codes entry: {}", city_code);
+                    bail!("tabs.pages[{}] lists a panel twice", index);
                 }
             }
+            if tabs.selected >= tabs.pages.len() {
+                bail!(
+                    "tabs.selected is {} but there are only {} pages",
+                    tabs.selected,
+                    tabs.pages.len()
+                );
+            }
+        }
 
-            for city_code in &time.city_codes {
-                if !self
-                    .all_city_codes()
-                    .iter()
-                    .any(|code| code.eq_ignore_ascii_case(city_code))
-                {
-                    bail!("unknown time.city_codes entry: {}", city_code);
+        if let Some(planner) = &self.planner {
+            planner.validate("planner")?;
+        }
+        if let Some(call) = &self.call {
+            for (label, hours) in [("call.mine", &call.mine), ("call.theirs", &call.theirs)] {
+                if let Some(hours) = hours {
+                    hours.validate(label)?;
                 }
             }
         }
 
-        if let Some(currency) = &self.currency {
-            for country_code in &currency.country_codes {
-                if !is_valid_country_code(country_code) || country_by_code(country_code).is_none() {
-                    bail!("unknown currency.country_codes entry: {}", country_code);
-                }
+        if !self.effective_map_settings().enabled
+            && !self.shows_weather_panel()
+            && !self.shows_time_panel()
+            && !self.shows_currency_panel()
+        {
+            bail!(
+                "panel: at least one of the map, weather, time and currency panels must be shown"
+            );
+        }
+
+        for panel in self.effective_custom_panels() {
+            if panel.command.trim().is_empty() {
+                bail!("custom panel {} has an empty command", panel.title);
             }
-            if let Some(default_from) = &currency.default_from
-                && !is_valid_currency_code(default_from)
-            {
-                bail!("invalid currency.default_from: {}", default_from);
+            if panel.refresh_secs == 0 {
+                bail!(
+                    "custom panel {} refresh_secs must be at least 1",
+                    panel.title
+                );
             }
-            if let Some(default_to) = &currency.default_to
-                && !is_valid_currency_code(default_to)
-            {
-                bail!("invalid currency.default_to: {}", default_to);
+        }
+
+        if let Some(alerts) = &self.alerts {
+            if alerts.severe_wind_kmph <= 0 {
+                bail!(
+                    "alerts.severe_wind_kmph must be positive: {}",
+                    alerts.severe_wind_kmph
+                );
             }
-            for code in &currency.pinned_codes {
-                if !is_valid_currency_code(code) {
-                    bail!("invalid currency.pinned_codes entry: {}", code);
+            for (name, spec) in [
+                ("rain", &alerts.rain),
+                ("wind", &alerts.wind),
+                ("reminder", &alerts.reminder),
+                ("fx", &alerts.fx),
+            ] {
+                if spec
+                    .as_deref()
+                    .is_some_and(|spec| Notifier::parse(spec).is_none())
+                {
+                    bail!(
+                        "alerts.{} is not a notifier (use \"bell\", \"desktop\", a webhook URL or a command)",
+                        name
+                    );
+                }
+            }
+            for threshold in &alerts.fx_thresholds {
+                if threshold.currencies().is_none() {
+                    bail!(
+                        "invalid alerts.fx_thresholds pair: {} (use FROM/TO, e.g. NZD/USD)",
+                        threshold.pair
+                    );
+                }
+                if threshold.above.is_none() && threshold.below.is_none() {
+                    bail!(
+                        "alerts.fx_thresholds entry {} needs above or below",
+                        threshold.pair
+                    );
                 }
             }
         }
 
-        if let Some(map) = &self.map {
-            if let Some(city_code) = &map.focus_city_code
-                && !self
-                    .all_city_codes()
-                    .iter()
-                    .any(|code| code.eq_ignore_ascii_case(city_code))
+        if self.todos.iter().any(|todo| todo.text.trim().is_empty()) {
+            bail!("checklist items cannot be empty");
+        }
+
+        for code in self.notes.keys() {
+            if !self
+                .all_city_codes()
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(code))
             {
-                bail!("unknown map.focus_city_code: {}", city_code);
+                bail!("note for unknown city code: {}", code);
             }
+        }
 
-            if let Some(country_code) = &map.focal_country_code
-                && (!is_valid_country_code(country_code) || country_by_code(country_code).is_none())
+        Keymap::from_config(&self.keys)?;
+
+        for event in &self.events {
+            if !self
+                .all_city_codes()
+                .iter()
+                .any(|code| code.eq_ignore_ascii_case(&event.city_code))
             {
-                bail!("invalid map.focal_country_code: {}", country_code);
+                bail!(
+                    "unknown city for event {}: {}",
+                    event.title,
+                    event.city_code
+                );
             }
+            if parse_event_time(&event.at).is_none() {
+                bail!(
+                    "invalid time for event {}: {} (expected YYYY-MM-DD HH:MM)",
+                    event.title,
+                    event.at
+                );
+            }
+        }
 
-            for country_code in &map.focus_country_codes {
-                if !is_valid_country_code(country_code) || country_by_code(country_code).is_none() {
-                    bail!("invalid map.focus_country_codes entry: {}", country_code);
-                }
+        for alarm in &self.alarms {
+            if !self
+                .all_city_codes()
+                .iter()
+                .any(|code| code.eq_ignore_ascii_case(&alarm.city_code))
+            {
+                bail!("unknown city for alarm: {}", alarm.city_code);
+            }
+            if parse_event_time(&alarm.at).is_none() {
+                bail!(
+                    "invalid time for alarm in {}: {} (expected YYYY-MM-DD HH:MM)",
+                    alarm.city_code,
+                    alarm.at
+                );
             }
         }
 
@@ -1006,6 +2641,99 @@ impl Config {
     }
 }
 
+/// `<value>/nzi-cli` when the variable holds an absolute path (relative ones are ignored,
+/// as the XDG base directory spec asks), else `~/<fallback>/nzi-cli`
+fn xdg_dir(value: Option<std::ffi::OsString>, fallback: &str) -> PathBuf {
+    value
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| {
+            dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(fallback)
+        })
+        .join("nzi-cli")
+}
+
+/// `config.toml.bak.<index>` beside `path`
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".bak.{}", index));
+    PathBuf::from(name)
+}
+
+/// shift the existing backups of `path` up one, dropping the oldest past `keep`, and copy
+/// the current file in as `.bak.1`
+fn rotate_backups(path: &Path, keep: usize) -> Result<()> {
+    if keep == 0 || !path.exists() {
+        return Ok(());
+    }
+    for index in (1..keep).rev() {
+        let from = backup_path(path, index);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, index + 1))
+                .context("failed to rotate config backups")?;
+        }
+    }
+    fs::copy(path, backup_path(path, 1)).context("failed to back up config file")?;
+    Ok(())
+}
+
+/// write `content` to a temporary file beside `path` and rename it over `path`, so a
+/// crash mid-save leaves either the old file or the new one
+pub fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    let mut file =
+        fs::File::create(&temp).with_context(|| format!("failed to create {}", temp.display()))?;
+    file.write_all(content.as_bytes())
+        .and_then(|()| file.sync_all())
+        .with_context(|| format!("failed to write {}", temp.display()))?;
+    fs::rename(&temp, path).with_context(|| format!("failed to replace {}", path.display()))
+}
+
+/// move config.toml and snapshots into `config_dir` and the ledger and climate records into
+/// `data_dir`, leaving anything already at the destination untouched
+fn migrate_files(legacy: &Path, config_dir: &Path, data_dir: &Path) -> Result<Vec<String>> {
+    let moves = [
+        ("config.toml", config_dir),
+        ("snapshots", config_dir),
+        ("ledger.csv", data_dir),
+        ("climate.csv", data_dir),
+    ];
+    let mut moved = Vec::new();
+    for (name, dir) in moves {
+        let (from, to) = (legacy.join(name), dir.join(name));
+        if from == to || !from.exists() || to.exists() {
+            continue;
+        }
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        move_path(&from, &to)
+            .with_context(|| format!("failed to move {} to {}", from.display(), to.display()))?;
+        moved.push(name.to_string());
+    }
+    Ok(moved)
+}
+
+/// rename, or copy then remove when the two paths are on different filesystems
+fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            move_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        fs::remove_dir(from)
+    } else {
+        fs::copy(from, to)?;
+        fs::remove_file(from)
+    }
+}
+
 #[cfg(test)]
 pub(crate) fn with_temp_config_dir_for_test<T>(test: impl FnOnce() -> T) -> T {
     use std::sync::{Mutex, OnceLock};
@@ -1016,6 +2744,7 @@ pub(crate) fn with_temp_config_dir_for_test<T>(test: impl FnOnce() -> T) -> T {
     }
 
     let _guard = test_lock().lock().expect("test lock should be available");
+    Config::mark_problems(&[]);
     let temp_dir = std::env::temp_dir().join(format!(
         "nzi-cli-test-{}",
         std::time::SystemTime::now()
@@ -1044,6 +2773,7 @@ pub(crate) fn with_temp_config_dir_for_test<T>(test: impl FnOnce() -> T) -> T {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ratatui::style::Color;
 
     fn legacy_new_york_city() -> City {
         let mut city = City::boston();
@@ -1054,8 +2784,10 @@ mod tests {
 
     #[test]
     fn normalises_legacy_home_city_to_boston() {
-        let mut config = Config::default();
-        config.home_city = legacy_new_york_city();
+        let mut config = Config {
+            home_city: legacy_new_york_city(),
+            ..Config::default()
+        };
 
         let updated = config.normalize_legacy_cities();
         assert!(updated);
@@ -1097,14 +2829,16 @@ mod tests {
 
     #[test]
     fn derives_currency_pairs_from_places_before_legacy_currency_overrides() {
-        let mut config = Config::default();
-        config.currency = Some(CurrencyConfig {
-            sync_with_cities: true,
-            country_codes: Vec::new(),
-            pinned_codes: vec!["cad".to_string()],
-            default_from: Some("nzd".to_string()),
-            default_to: Some("sgd".to_string()),
-        });
+        let mut config = Config {
+            currency: Some(CurrencyConfig {
+                sync_with_cities: true,
+                country_codes: Vec::new(),
+                pinned_codes: vec!["cad".to_string()],
+                default_from: Some("nzd".to_string()),
+                default_to: Some("sgd".to_string()),
+            }),
+            ..Config::default()
+        };
         config.normalize();
 
         let pairs = config.effective_currency_pairs();
@@ -1122,12 +2856,14 @@ mod tests {
 
     #[test]
     fn derives_anchor_and_target_city_codes_from_explicit_list() {
-        let mut config = Config::default();
-        config.time = Some(TimeConfig {
-            anchor_city_code: Some("bos".to_string()),
-            target_city_codes: vec!["tyo".to_string()],
-            city_codes: vec!["bos".to_string(), "tyo".to_string()],
-        });
+        let mut config = Config {
+            time: Some(TimeConfig {
+                anchor_city_code: Some("bos".to_string()),
+                target_city_codes: vec!["tyo".to_string()],
+                city_codes: vec!["bos".to_string(), "tyo".to_string()],
+            }),
+            ..Config::default()
+        };
         config.normalize();
 
         assert_eq!(config.effective_anchor_city_code(), "BOS");
@@ -1162,6 +2898,57 @@ mod tests {
         assert!(representatives.iter().any(|city| city.code == "DEN"));
     }
 
+    #[test]
+    fn call_hours_fall_back_to_planner_per_side() {
+        let mut config: Config = toml::from_str(&format!(
+            "{}\n[planner]\nwork_start_hour = 8\n\n[call.theirs]\nwork_start_hour = 10\n",
+            toml::to_string(&Config::default()).unwrap()
+        ))
+        .unwrap();
+        config.validate().unwrap();
+        let (mine, theirs) = config.effective_call_hours();
+        assert_eq!(mine.work_start_hour, 8);
+        assert_eq!(theirs.work_start_hour, 10);
+        assert_eq!(theirs.work_end_hour, 17);
+
+        config.call.as_mut().unwrap().mine = Some(PlannerConfig {
+            work_start_hour: 18,
+            ..PlannerConfig::default()
+        });
+        let err = config.validate().unwrap_err().to_string();
+        assert!(
+            err.starts_with("invalid call.mine working hours"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn duplicate_codes_only_fail_for_different_places() {
+        let mut config = Config {
+            home_city: City::wellington(),
+            ..Config::default()
+        };
+        config.validate().expect("home may repeat the current city");
+
+        config.tracked_cities.push(City {
+            name: "Sydney".to_string(),
+            code: "aus".to_string(),
+            country: "Australia".to_string(),
+            timezone: "Australia/Sydney".to_string(),
+            currency: "AUD".to_string(),
+        });
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("duplicate city code AUS"), "{}", err);
+        assert!(err.contains("Austin") && err.contains("Sydney"), "{}", err);
+        assert_eq!(
+            config
+                .code_conflict(&City::austin())
+                .map(|city| city.name.as_str()),
+            Some("Sydney")
+        );
+    }
+
     #[test]
     fn validates_fixed_utc_offset_timezones() {
         let mut config = Config::default();
@@ -1178,14 +2965,16 @@ mod tests {
 
     #[test]
     fn derives_currency_pairs_from_country_codes() {
-        let mut config = Config::default();
-        config.currency = Some(CurrencyConfig {
-            sync_with_cities: false,
-            country_codes: vec!["JPN".to_string(), "GBR".to_string()],
-            pinned_codes: Vec::new(),
-            default_from: Some("NZD".to_string()),
-            default_to: None,
-        });
+        let config = Config {
+            currency: Some(CurrencyConfig {
+                sync_with_cities: false,
+                country_codes: vec!["JPN".to_string(), "GBR".to_string()],
+                pinned_codes: Vec::new(),
+                default_from: Some("NZD".to_string()),
+                default_to: None,
+            }),
+            ..Config::default()
+        };
 
         let pairs = config.effective_currency_pairs();
 
@@ -1193,21 +2982,302 @@ mod tests {
         assert!(pairs.contains(&(String::from("NZD"), String::from("GBP"))));
     }
 
+    #[test]
+    fn date_format_and_week_start_follow_display_settings() {
+        let display: DisplayConfig = toml::from_str(
+            r#"
+            show_seconds = true
+            use_24_hour = true
+            show_animations = true
+            animation_speed_ms = 100
+            date_format = "month-day"
+            week_starts = "sunday"
+            "#,
+        )
+        .expect("display should parse");
+        let date = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+
+        assert_eq!(display.date_format.short(date), "Mar 14");
+        assert_eq!(display.week_starts, WeekStart::Sunday);
+        assert_eq!(DateFormat::default().with_weekday(date), "Sat 14 Mar");
+        assert_eq!(DateFormat::Iso.short(date), "03-14");
+    }
+
+    #[test]
+    fn parses_custom_panel_blocks_with_default_refresh() {
+        let config: Config = toml::from_str(
+            r#"
+            tracked_cities = []
+
+            [current_city]
+            name = "Wellington"
+            code = "WLG"
+            country = "New Zealand"
+            timezone = "Pacific/Auckland"
+            currency = "NZD"
+
+            [home_city]
+            name = "Boston"
+            code = "BOS"
+            country = "USA"
+            timezone = "America/New_York"
+            currency = "USD"
+
+            [display]
+            show_seconds = true
+            use_24_hour = true
+            show_animations = true
+            animation_speed_ms = 100
+
+            [[panel.custom]]
+            title = "Uptime"
+            command = "uptime"
+            "#,
+        )
+        .expect("config should parse");
+
+        let panels = config.effective_custom_panels();
+        assert_eq!(panels.len(), 1);
+        assert_eq!(panels[0].refresh_secs, 60);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn validates_fx_threshold_pairs_and_levels() {
+        let threshold = |pair: &str, below: Option<f64>| FxThreshold {
+            pair: pair.to_string(),
+            above: None,
+            below,
+        };
+        let with_threshold = |threshold: FxThreshold| Config {
+            alerts: Some(AlertsConfig {
+                fx: Some("desktop".to_string()),
+                fx_thresholds: vec![threshold],
+                ..AlertsConfig::default()
+            }),
+            ..Config::default()
+        };
+
+        assert!(
+            with_threshold(threshold("nzd/usd", Some(0.58)))
+                .validate()
+                .is_ok()
+        );
+        let err = with_threshold(threshold("NZDUSD", Some(0.58)))
+            .validate()
+            .expect_err("a pair needs a slash");
+        assert!(
+            err.to_string()
+                .contains("invalid alerts.fx_thresholds pair")
+        );
+        let err = with_threshold(threshold("NZD/USD", None))
+            .validate()
+            .expect_err("a threshold needs a level");
+        assert!(err.to_string().contains("needs above or below"));
+    }
+
     #[test]
     fn validates_map_focus_city_against_known_cities() {
-        let mut config = Config::default();
-        config.map = Some(MapConfig {
-            enabled: true,
-            mode: MapMode::Cities,
-            focus_city_code: Some("XXX".to_string()),
-            focus_country_codes: Vec::new(),
-            focal_country_code: None,
-        });
+        let config = Config {
+            map: Some(MapConfig {
+                enabled: true,
+                mode: MapMode::Cities,
+                focus_city_code: Some("XXX".to_string()),
+                focus_country_codes: Vec::new(),
+                focal_country_code: None,
+                markers: Vec::new(),
+                quakes: true,
+                aurora: true,
+                iss: false,
+                heat: false,
+                detailed_coastline: false,
+                highways: false,
+                marker_style: None,
+                coastline_colour: None,
+                projection: WorldProjection::Greenwich,
+                bookmarks: Vec::new(),
+            }),
+            ..Config::default()
+        };
 
         let err = config.validate().expect_err("expected validation failure");
         assert!(err.to_string().contains("unknown map.focus_city_code"));
     }
 
+    #[test]
+    fn validates_map_markers() {
+        let napier = MapMarkerConfig {
+            name: "Napier".to_string(),
+            code: "NPE".to_string(),
+            lat: -39.49,
+            lon: 176.91,
+        };
+        let with_markers = |markers: Vec<MapMarkerConfig>| Config {
+            map: Some(MapConfig {
+                enabled: true,
+                markers,
+                ..MapConfig::default()
+            }),
+            ..Config::default()
+        };
+
+        let config: Config = toml::from_str(&format!(
+            "{}\n[[map.markers]]\nname = \"Napier\"\ncode = \"NPE\"\nlat = -39.49\nlon = 176.91\n",
+            toml::to_string(&Config::default()).unwrap()
+        ))
+        .expect("markers should parse");
+        assert_eq!(
+            config.effective_map_settings().markers,
+            vec![napier.clone()]
+        );
+        assert!(config.validate().is_ok());
+
+        let clash = MapMarkerConfig {
+            code: "wlg".to_string(),
+            ..napier.clone()
+        };
+        let err = with_markers(vec![clash]).validate().unwrap_err();
+        assert!(err.to_string().contains("duplicate map marker code"));
+
+        let sydney = MapMarkerConfig {
+            lat: -33.87,
+            lon: 151.21,
+            ..napier.clone()
+        };
+        let err = with_markers(vec![sydney]).validate().unwrap_err();
+        assert!(err.to_string().contains("outside the NZ map"));
+
+        let too_many = (0..=MAX_MAP_MARKERS)
+            .map(|i| MapMarkerConfig {
+                code: format!("T{}", i),
+                ..napier.clone()
+            })
+            .collect();
+        let err = with_markers(too_many).validate().unwrap_err();
+        assert!(err.to_string().contains("at most"));
+    }
+
+    #[test]
+    fn marker_style_falls_back_to_half_blocks_where_braille_breaks() {
+        use MapMarkerStyle::*;
+        assert_eq!(MapMarkerStyle::detect(None, None), Braille);
+        assert_eq!(
+            MapMarkerStyle::detect(Some("xterm-256color"), Some("en_NZ.UTF-8")),
+            Braille
+        );
+        assert_eq!(MapMarkerStyle::detect(Some("tmux"), Some("C")), Braille);
+        assert_eq!(MapMarkerStyle::detect(Some("linux"), None), HalfBlock);
+        assert_eq!(MapMarkerStyle::detect(Some("vt220"), None), HalfBlock);
+        assert_eq!(
+            MapMarkerStyle::detect(Some("xterm"), Some("en_NZ.ISO-8859-1")),
+            HalfBlock
+        );
+
+        assert_eq!(MapMarkerStyle::from_name("Half-Block"), Some(HalfBlock));
+        assert_eq!(MapMarkerStyle::from_name("halfblock"), Some(HalfBlock));
+        assert_eq!(MapMarkerStyle::from_name("bars"), None);
+        assert_eq!(Dot.next(), Braille);
+
+        let map: MapConfig = toml::from_str("marker_style = \"half-block\"").unwrap();
+        assert_eq!(map.marker_style(), HalfBlock);
+    }
+
+    #[test]
+    fn pacific_projection_wraps_longitudes_past_the_date_line() {
+        let map: MapConfig = toml::from_str("projection = \"pacific\"").unwrap();
+        assert_eq!(map.projection, WorldProjection::Pacific);
+        assert_eq!(MapConfig::default().projection, WorldProjection::Greenwich);
+
+        let pacific = WorldProjection::Pacific;
+        assert_eq!(pacific.lon_bounds(), [-30.0, 330.0]);
+        assert_eq!(pacific.wrap(174.78), 174.78);
+        assert!((pacific.wrap(-157.86) - 202.14).abs() < 1e-9);
+        assert_eq!(pacific.wrap_span([-170.0, -30.0]), [190.0, 330.0]);
+        assert!((WorldProjection::Greenwich.wrap(202.14) + 157.86).abs() < 1e-9);
+        assert_eq!(WorldProjection::Greenwich.wrap(-0.13), -0.13);
+    }
+
+    #[test]
+    fn validates_coastline_colour() {
+        let with_coastline = |colour: &str| Config {
+            map: Some(MapConfig {
+                coastline_colour: Some(colour.to_string()),
+                ..MapConfig::default()
+            }),
+            ..Config::default()
+        };
+        assert!(with_coastline("lavender").validate().is_ok());
+        assert!(with_coastline("#94e2d5").validate().is_ok());
+        assert!(with_coastline("sea green").validate().is_err());
+    }
+
+    #[test]
+    fn validates_footer_placeholders() {
+        let with_footer = |format: &str| {
+            let mut config = Config::default();
+            config.display.footer = Some(format.to_string());
+            config
+        };
+        assert!(
+            with_footer("NZ {nz_time} · {temp} · {rate}")
+                .validate()
+                .is_ok()
+        );
+        let err = with_footer("{nz_time} {wind}").validate().unwrap_err();
+        assert!(err.to_string().contains("unknown placeholder {wind}"));
+    }
+
+    #[test]
+    fn validates_world_markers() {
+        let config: Config = toml::from_str(&format!(
+            "{}\n[[markers]]\nlabel = \"Ōhope\"\nlat = -37.97\nlon = 177.07\ncolour = \"#FAB387\"\n",
+            toml::to_string(&Config::default()).unwrap()
+        ))
+        .expect("markers should parse");
+        assert_eq!(config.markers.len(), 1);
+        assert_eq!(config.markers[0].label, "Ōhope");
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            parse_colour("#FAB388"),
+            Some(ratatui::style::Color::Rgb(250, 179, 136))
+        );
+        assert_eq!(
+            parse_colour(" Peach "),
+            Some(crate::theme::catppuccin::PEACH)
+        );
+
+        let village = WorldMarkerConfig {
+            label: "Cong".to_string(),
+            lat: 53.54,
+            lon: -9.29,
+            colour: None,
+        };
+        let with_markers = |markers: Vec<WorldMarkerConfig>| Config {
+            markers,
+            ..Config::default()
+        };
+        assert!(with_markers(vec![village.clone()]).validate().is_ok());
+
+        let off_the_map = WorldMarkerConfig {
+            lon: 190.0,
+            ..village.clone()
+        };
+        let err = with_markers(vec![off_the_map]).validate().unwrap_err();
+        assert!(err.to_string().contains("not a valid position"));
+
+        let odd_colour = WorldMarkerConfig {
+            colour: Some("#12345".to_string()),
+            ..village.clone()
+        };
+        let err = with_markers(vec![odd_colour]).validate().unwrap_err();
+        assert!(err.to_string().contains("unknown colour"));
+
+        let err = with_markers(vec![village; MAX_WORLD_MARKERS + 1])
+            .validate()
+            .unwrap_err();
+        assert!(err.to_string().contains("at most"));
+    }
+
     #[test]
     fn derives_default_focal_country_from_current_city() {
         let config = Config::default();
@@ -1235,16 +3305,83 @@ mod tests {
     }
 
     #[test]
-    fn saves_and_restores_latest_snapshot() {
+    fn backed_up_saves_keep_rotating_backups_of_earlier_versions() {
         with_temp_config_dir_for_test(|| {
+            let path = Config::config_path();
             let mut config = Config::default();
-            config.map = Some(MapConfig {
-                enabled: true,
-                mode: MapMode::Countries,
-                focus_city_code: None,
-                focus_country_codes: vec!["GBR".to_string()],
-                focal_country_code: Some("JPN".to_string()),
-            });
+            for minutes in 1..=CONFIG_BACKUPS as u64 + 2 {
+                config.weather = Some(WeatherConfig {
+                    refresh_secs: minutes * 60,
+                });
+                config.save_backed_up().expect("config should save");
+                config.save_backed_up().expect("an unchanged save is fine");
+            }
+
+            let newest = fs::read_to_string(backup_path(&path, 1)).unwrap();
+            let newest: Config = toml::from_str(&newest).unwrap();
+            assert_eq!(newest.effective_weather().refresh_secs, 360);
+            let oldest = fs::read_to_string(backup_path(&path, CONFIG_BACKUPS)).unwrap();
+            let oldest: Config = toml::from_str(&oldest).unwrap();
+            assert_eq!(oldest.effective_weather().refresh_secs, 120);
+            assert!(!backup_path(&path, CONFIG_BACKUPS + 1).exists());
+            assert!(!path.with_extension("toml.tmp").exists());
+
+            // everyday saves leave the backups as they are
+            config.weather = None;
+            config.save().expect("config should save");
+            let newest = fs::read_to_string(backup_path(&path, 1)).unwrap();
+            let newest: Config = toml::from_str(&newest).unwrap();
+            assert_eq!(newest.effective_weather().refresh_secs, 360);
+        });
+    }
+
+    #[test]
+    fn saves_leave_a_file_with_problems_alone_until_a_clean_reload() {
+        with_temp_config_dir_for_test(|| {
+            let path = Config::config_path();
+            let good = toml::to_string_pretty(&Config::default()).unwrap();
+            let broken = good.clone()
+                + "\n[[tracked_cities]]\nname = \"Lisbon\"\ncode = \"LIS\"\ncountry = \"Portugal\"\n\
+                   timezone = \"Europe/Lisbn\"\ncurrency = \"EUR\"\n";
+            fs::write(&path, &broken).unwrap();
+
+            let (mut config, problems) = Config::load_checked().expect("config should load");
+            assert_eq!(problems.len(), 1);
+            config.display.use_24_hour = !config.display.use_24_hour;
+            assert!(config.save().is_err());
+            assert_eq!(fs::read_to_string(&path).unwrap(), broken);
+
+            fs::write(&path, &good).unwrap();
+            let (config, problems) = Config::load_checked().expect("config should load");
+            assert!(problems.is_empty());
+            config.save().expect("a clean config should save");
+        });
+    }
+
+    #[test]
+    fn saves_and_restores_latest_snapshot() {
+        with_temp_config_dir_for_test(|| {
+            let config = Config {
+                map: Some(MapConfig {
+                    enabled: true,
+                    mode: MapMode::Countries,
+                    focus_city_code: None,
+                    focus_country_codes: vec!["GBR".to_string()],
+                    focal_country_code: Some("JPN".to_string()),
+                    markers: Vec::new(),
+                    quakes: true,
+                    aurora: true,
+                    iss: false,
+                    heat: false,
+                    detailed_coastline: false,
+                    highways: false,
+                    marker_style: None,
+                    coastline_colour: None,
+                    projection: WorldProjection::Greenwich,
+                    bookmarks: Vec::new(),
+                }),
+                ..Config::default()
+            };
 
             config.save_snapshot().expect("snapshot should save");
 
@@ -1255,4 +3392,112 @@ mod tests {
             assert_eq!(restored_map.focal_country_code.as_deref(), Some("JPN"));
         });
     }
+
+    #[test]
+    fn theme_colors_override_palette_slots_and_the_rainbow() {
+        let mut raw = toml::to_string(&Config::default()).expect("config should serialise");
+        raw.push_str(
+            r##"
+[theme.colors]
+base = "#101418"
+text = "#E0E0E0"
+rainbow = ["#ff0000", "#ff8800", "#ffff00", "#00ff00", "#00ffff", "#0000ff", "#8800ff"]
+"##,
+        );
+        let mut config: Config = toml::from_str(&raw).expect("config should parse");
+        assert!(config.validate().is_ok());
+
+        let custom = config.custom_colours();
+        assert_eq!(custom.slots[15], Some(Color::Rgb(16, 20, 24)));
+        assert_eq!(custom.slots[8], Some(Color::Rgb(224, 224, 224)));
+        assert_eq!(custom.slots[0], None);
+        assert_eq!(
+            custom.rainbow.map(|rainbow| rainbow[6]),
+            Some(Color::Rgb(136, 0, 255))
+        );
+
+        let colors = &mut config.theme.as_mut().unwrap().colors;
+        colors.rainbow.pop();
+        assert!(config.validate().is_err());
+        let colors = &mut config.theme.as_mut().unwrap().colors;
+        colors.rainbow.clear();
+        colors
+            .slots
+            .insert("background".to_string(), "#000000".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn session_overrides_apply_over_the_file_and_stay_out_of_saves() {
+        let on_disk = Config::default();
+        let mut config = on_disk.clone();
+        let overrides = SessionOverrides {
+            city: Some("akl".to_string()),
+            home_city: Some("TYO".to_string()),
+            no_animations: true,
+            theme: Some(ThemeName::Latte),
+        };
+        overrides.apply(&mut config).unwrap();
+        assert_eq!(config.current_city.name, "Auckland");
+        assert_eq!(config.home_city, City::tokyo());
+        assert!(!config.display.show_animations);
+        assert_eq!(config.display.theme, ThemeName::Latte);
+
+        config.display.use_24_hour = false;
+        overrides.restore(&mut config, &on_disk);
+        assert_eq!(config.current_city, City::wellington());
+        assert_eq!(config.home_city, City::boston());
+        assert!(config.display.show_animations);
+        assert_eq!(config.display.theme, ThemeName::Mocha);
+        // fields the flags don't touch keep their session changes
+        assert!(!config.display.use_24_hour);
+
+        let outside_nz = SessionOverrides {
+            city: Some("LDN".to_string()),
+            ..SessionOverrides::default()
+        };
+        assert!(outside_nz.apply(&mut config).is_err());
+    }
+
+    #[test]
+    fn xdg_dirs_move_old_files_into_place() {
+        let absolute = std::env::temp_dir().join("xdg-config");
+        assert_eq!(
+            xdg_dir(Some(absolute.clone().into()), ".config"),
+            absolute.join("nzi-cli")
+        );
+        assert!(xdg_dir(Some("relative/dir".into()), ".cache").ends_with(".cache/nzi-cli"));
+        assert!(xdg_dir(None, ".local/share").ends_with(".local/share/nzi-cli"));
+
+        let root = std::env::temp_dir().join(format!("nzi-xdg-{}", std::process::id()));
+        let (legacy, config_dir, data_dir) =
+            (root.join("old"), root.join("config"), root.join("data"));
+        fs::create_dir_all(legacy.join("snapshots")).unwrap();
+        fs::write(legacy.join("config.toml"), "home_city = 'WLG'").unwrap();
+        fs::write(legacy.join("snapshots").join("latest.toml"), "").unwrap();
+        fs::write(legacy.join("ledger.csv"), "old").unwrap();
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("ledger.csv"), "new").unwrap();
+
+        let moved = migrate_files(&legacy, &config_dir, &data_dir).unwrap();
+        assert_eq!(moved, vec!["config.toml", "snapshots"]);
+        assert!(config_dir.join("snapshots").join("latest.toml").exists());
+        assert!(!legacy.join("config.toml").exists());
+        assert_eq!(
+            fs::read_to_string(data_dir.join("ledger.csv")).unwrap(),
+            "new"
+        );
+        assert!(legacy.join("ledger.csv").exists());
+        assert!(
+            migrate_files(&legacy, &config_dir, &data_dir)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            migrate_files(&config_dir, &config_dir, &data_dir)
+                .unwrap()
+                .is_empty()
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
 }
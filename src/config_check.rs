@@ -0,0 +1,769 @@
+//! a forgiving pass over config.toml: anything that doesn't parse or validate falls back to
+//! its default on its own, and each problem is reported with its field, line and a suggestion
+
+use std::fmt;
+
+use chrono_tz::TZ_VARIANTS;
+use toml::{Table, Value};
+
+use crate::config::{City, Config};
+use crate::timezone::parse_city_timezone;
+
+/// sections replaced whole rather than field by field; half of one city and half of
+/// another would show the wrong time without looking wrong
+const WHOLE_SECTIONS: &[&str] = &["current_city", "home_city"];
+
+/// one thing wrong with the config file, and what was used instead
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// path to the field, e.g. `tracked_cities[2].timezone`
+    pub field: String,
+    /// 1-based line in config.toml, when it can be found
+    pub line: Option<usize>,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        write!(f, "{}: {}", self.field, self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({})", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// `tracked_cities[2].timezone` from its path segments
+fn field_name(path: &[String]) -> String {
+    let mut name = String::new();
+    for segment in path {
+        if segment.parse::<usize>().is_ok() {
+            name.push_str(&format!("[{}]", segment));
+        } else {
+            if !name.is_empty() {
+                name.push('.');
+            }
+            name.push_str(segment);
+        }
+    }
+    name
+}
+
+fn problem(content: &str, path: &[String], message: String) -> ConfigProblem {
+    ConfigProblem {
+        field: field_name(path),
+        line: field_line(content, path),
+        message,
+        suggestion: None,
+    }
+}
+
+/// 1-based line of a byte offset
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+/// the line holding a field: section headers narrow the search, an index picks the nth
+/// `[[array]]` table, and the nearest enclosing section answers when the key isn't written
+fn field_line(content: &str, path: &[String]) -> Option<usize> {
+    let lines: Vec<&str> = content.lines().collect();
+    let is_header = |line: &str| line.trim_start().starts_with('[');
+    let section_end = |from: usize| {
+        (from..lines.len())
+            .find(|&i| is_header(lines[i]))
+            .unwrap_or(lines.len())
+    };
+    let (mut from, mut to) = (0, section_end(0));
+    let mut table = String::new();
+    let mut found = None;
+    for segment in path {
+        if let Ok(index) = segment.parse::<usize>() {
+            let header = format!("[[{}]]", table);
+            let Some(at) = (0..lines.len())
+                .filter(|&i| lines[i].trim() == header)
+                .nth(index)
+            else {
+                break;
+            };
+            (from, to) = (at + 1, section_end(at + 1));
+            found = Some(at);
+            continue;
+        }
+        let key = lines[from..to].iter().position(|line| {
+            line.trim_start()
+                .strip_prefix(segment.as_str())
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        });
+        if let Some(offset) = key {
+            found = Some(from + offset);
+            break;
+        }
+        table = if table.is_empty() {
+            segment.clone()
+        } else {
+            format!("{}.{}", table, segment)
+        };
+        let (single, array) = (format!("[{}]", table), format!("[[{}]]", table));
+        let Some(at) =
+            (0..lines.len()).find(|&i| lines[i].trim() == single || lines[i].trim() == array)
+        else {
+            break;
+        };
+        (from, to) = (at + 1, section_end(at + 1));
+        found = Some(at);
+    }
+    found.map(|index| index + 1)
+}
+
+/// the first line quoting a word from `message`, which is usually the offending value
+fn value_line(content: &str, message: &str) -> Option<usize> {
+    message
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| c == ':' || c == ',' || c == ';' || c == '"'))
+        .filter(|word| word.len() > 1)
+        .find_map(|word| content.find(&format!("\"{}\"", word)))
+        .map(|offset| line_at(content, offset))
+}
+
+fn parent_mut<'a>(root: &'a mut Table, path: &[String]) -> Option<&'a mut Table> {
+    let mut table = root;
+    for key in &path[..path.len() - 1] {
+        table = table.get_mut(key)?.as_table_mut()?;
+    }
+    Some(table)
+}
+
+fn get_at<'a>(root: &'a Table, path: &[String]) -> Option<&'a Value> {
+    let (last, parents) = path.split_last()?;
+    let mut table = root;
+    for key in parents {
+        table = table.get(key)?.as_table()?;
+    }
+    table.get(last)
+}
+
+/// a copy of `root` with the value at `path` replaced, or removed when `value` is None
+fn with_value(root: &Table, path: &[String], value: Option<Value>) -> Table {
+    let mut candidate = root.clone();
+    if let Some(parent) = parent_mut(&mut candidate, path) {
+        let key = path[path.len() - 1].clone();
+        match value {
+            Some(value) => parent.insert(key, value),
+            None => parent.remove(&key),
+        };
+    }
+    candidate
+}
+
+fn deserialise(root: &Table) -> Result<Config, toml::de::Error> {
+    Value::Table(root.clone()).try_into()
+}
+
+/// parse config.toml, keeping every field that deserialises and the default for the rest
+pub fn parse(content: &str) -> (Config, Vec<ConfigProblem>) {
+    if let Ok(config) = toml::from_str::<Config>(content) {
+        return (config, Vec::new());
+    }
+    let user: Table = match toml::from_str(content) {
+        Ok(table) => table,
+        Err(e) => {
+            let problem = ConfigProblem {
+                field: "config.toml".to_string(),
+                line: e.span().map(|span| line_at(content, span.start)),
+                message: format!("{}; using the defaults", e.message().trim()),
+                suggestion: None,
+            };
+            return (Config::default(), vec![problem]);
+        }
+    };
+    let mut root = match Value::try_from(Config::default()) {
+        Ok(Value::Table(table)) => table,
+        _ => Table::new(),
+    };
+    let mut problems = Vec::new();
+    merge_table(&mut root, &mut Vec::new(), user, content, &mut problems);
+    match deserialise(&root) {
+        Ok(config) => (config, problems),
+        Err(_) => (Config::default(), problems),
+    }
+}
+
+/// take the user's fields into `root` one at a time, keeping the default where one won't
+/// deserialise; tables are merged key by key and arrays entry by entry
+fn merge_table(
+    root: &mut Table,
+    path: &mut Vec<String>,
+    user: Table,
+    content: &str,
+    problems: &mut Vec<ConfigProblem>,
+) {
+    let user_keys: Vec<String> = user.keys().cloned().collect();
+    for (key, value) in user {
+        path.push(key);
+        let candidate = with_value(root, path, Some(value.clone()));
+        match deserialise(&candidate) {
+            Ok(_) => *root = candidate,
+            Err(e) => {
+                let whole = path.len() == 1 && WHOLE_SECTIONS.contains(&path[0].as_str());
+                match value {
+                    Value::Table(fields) if !whole => {
+                        merge_section(root, path, fields, e, content, problems)
+                    }
+                    Value::Array(items) => merge_array(root, path, items, e, content, problems),
+                    _ => problems.push(problem(
+                        content,
+                        path,
+                        format!("{}; {}", e.message().trim(), fallback_note(path)),
+                    )),
+                }
+            }
+        }
+        path.pop();
+    }
+
+    // fields the user left out that have no default of their own
+    let defaults: Vec<String> = if path.is_empty() {
+        root.keys().cloned().collect()
+    } else {
+        get_at(root, path)
+            .and_then(Value::as_table)
+            .map(|table| table.keys().cloned().collect())
+            .unwrap_or_default()
+    };
+    for key in defaults.into_iter().filter(|key| !user_keys.contains(key)) {
+        path.push(key);
+        if deserialise(&with_value(root, path, None)).is_err() {
+            problems.push(problem(
+                content,
+                path,
+                "is missing; using the default".to_string(),
+            ));
+        }
+        path.pop();
+    }
+}
+
+fn merge_section(
+    root: &mut Table,
+    path: &mut Vec<String>,
+    fields: Table,
+    error: toml::de::Error,
+    content: &str,
+    problems: &mut Vec<ConfigProblem>,
+) {
+    if !get_at(root, path).is_some_and(Value::is_table) {
+        let empty = with_value(root, path, Some(Value::Table(Table::new())));
+        if deserialise(&empty).is_err() {
+            problems.push(problem(
+                content,
+                path,
+                format!("{}; left out", error.message().trim()),
+            ));
+            return;
+        }
+        *root = empty;
+    }
+    merge_table(root, path, fields, content, problems);
+}
+
+fn merge_array(
+    root: &mut Table,
+    path: &mut Vec<String>,
+    items: Vec<Value>,
+    error: toml::de::Error,
+    content: &str,
+    problems: &mut Vec<ConfigProblem>,
+) {
+    let empty = with_value(root, path, Some(Value::Array(Vec::new())));
+    if deserialise(&empty).is_err() {
+        problems.push(problem(
+            content,
+            path,
+            format!("{}; using the default", error.message().trim()),
+        ));
+        return;
+    }
+    *root = empty;
+    let mut kept = Vec::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let mut grown = kept.clone();
+        grown.push(item);
+        let candidate = with_value(root, path, Some(Value::Array(grown.clone())));
+        match deserialise(&candidate) {
+            Ok(_) => {
+                *root = candidate;
+                kept = grown;
+            }
+            Err(e) => {
+                path.push(index.to_string());
+                problems.push(problem(
+                    content,
+                    path,
+                    format!("{}; left out", e.message().trim()),
+                ));
+                path.pop();
+            }
+        }
+    }
+}
+
+/// the IANA name a mistyped timezone most likely meant: one whose city part matches,
+/// else the nearest by edit distance
+pub fn closest_timezone(value: &str) -> Option<&'static str> {
+    let wanted = value.trim().to_lowercase().replace(' ', "_");
+    if wanted.is_empty() {
+        return None;
+    }
+    let names = || TZ_VARIANTS.iter().map(|tz| tz.name());
+    if let Some(name) = names().find(|name| {
+        name.rsplit('/')
+            .next()
+            .is_some_and(|city| city.to_lowercase() == wanted)
+    }) {
+        return Some(name);
+    }
+    names()
+        .map(|name| (edit_distance(&wanted, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= 3.max(wanted.len() / 4))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(a != *b));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// drop every reference to a city code that no configured city uses any more
+fn forget_code(config: &mut Config, code: &str) {
+    let matches = |other: &String| other.eq_ignore_ascii_case(code);
+    if config.all_city_codes().iter().any(matches) {
+        return;
+    }
+    if let Some(time) = &mut config.time {
+        if time.anchor_city_code.as_ref().is_some_and(matches) {
+            time.anchor_city_code = None;
+        }
+        time.target_city_codes.retain(|other| !matches(other));
+        time.city_codes.retain(|other| !matches(other));
+    }
+    if let Some(map) = &mut config.map
+        && map.focus_city_code.as_ref().is_some_and(matches)
+    {
+        map.focus_city_code = None;
+    }
+    config.notes.retain(|other, _| !matches(other));
+}
+
+/// a copy with one city gone: tracked cities are left out, the current and home cities
+/// go back to Wellington and Boston
+fn without_city(config: &Config, path: &[String]) -> Config {
+    let mut candidate = config.clone();
+    let old = match path[0].as_str() {
+        "current_city" => std::mem::replace(&mut candidate.current_city, City::wellington()),
+        "home_city" => std::mem::replace(&mut candidate.home_city, City::boston()),
+        _ => {
+            let index = path[1].parse().unwrap_or(0);
+            candidate.tracked_cities.remove(index)
+        }
+    };
+    forget_code(&mut candidate, &old.code);
+    candidate
+}
+
+fn city_paths(config: &Config) -> Vec<Vec<String>> {
+    let mut paths: Vec<Vec<String>> = (0..config.tracked_cities.len())
+        .rev()
+        .map(|index| vec!["tracked_cities".to_string(), index.to_string()])
+        .collect();
+    paths.push(vec!["current_city".to_string()]);
+    paths.push(vec!["home_city".to_string()]);
+    paths
+}
+
+fn fallback_note(path: &[String]) -> &'static str {
+    match path[0].as_str() {
+        "current_city" => "using Wellington",
+        "home_city" => "using Boston",
+        _ if path.len() > 1
+            && (path[0] == "notes" || path[path.len() - 1].parse::<usize>().is_ok()) =>
+        {
+            "left out"
+        }
+        _ => "using the default",
+    }
+}
+
+/// every single-step fallback, finest first: one city, entry or note at a time, then
+/// whole optional sections
+fn fallbacks(config: &Config) -> Vec<(Vec<String>, Config)> {
+    let path = |parts: &[&str]| {
+        parts
+            .iter()
+            .map(|part| part.to_string())
+            .collect::<Vec<_>>()
+    };
+    // an alarm or event naming a missing city goes before any city does, since dropping
+    // a city would only move the error on to the next alarm
+    let mut fallbacks: Vec<(Vec<String>, Config)> = Vec::new();
+    for index in (0..config.alarms.len()).rev() {
+        let mut candidate = config.clone();
+        candidate.alarms.remove(index);
+        fallbacks.push((path(&["alarms", &index.to_string()]), candidate));
+    }
+    for index in (0..config.events.len()).rev() {
+        let mut candidate = config.clone();
+        candidate.events.remove(index);
+        fallbacks.push((path(&["events", &index.to_string()]), candidate));
+    }
+    fallbacks.extend(city_paths(config).into_iter().map(|path| {
+        let candidate = without_city(config, &path);
+        (path, candidate)
+    }));
+
+    for index in (0..config.markers.len()).rev() {
+        let mut candidate = config.clone();
+        candidate.markers.remove(index);
+        fallbacks.push((path(&["markers", &index.to_string()]), candidate));
+    }
+    if let Some(map) = &config.map {
+        for index in (0..map.markers.len()).rev() {
+            let mut candidate = config.clone();
+            if let Some(map) = &mut candidate.map {
+                map.markers.remove(index);
+            }
+            fallbacks.push((path(&["map", "markers", &index.to_string()]), candidate));
+        }
+    }
+    if let Some(panel) = &config.panel {
+        for index in (0..panel.custom.len()).rev() {
+            let mut candidate = config.clone();
+            if let Some(panel) = &mut candidate.panel {
+                panel.custom.remove(index);
+            }
+            fallbacks.push((path(&["panel", "custom", &index.to_string()]), candidate));
+        }
+    }
+    for index in (0..config.todos.len()).rev() {
+        let mut candidate = config.clone();
+        candidate.todos.remove(index);
+        fallbacks.push((path(&["todos", &index.to_string()]), candidate));
+    }
+    for code in config.notes.keys() {
+        let mut candidate = config.clone();
+        candidate.notes.remove(code);
+        fallbacks.push((path(&["notes", code]), candidate));
+    }
+    for action in config.keys.keys() {
+        let mut candidate = config.clone();
+        candidate.keys.remove(action);
+        fallbacks.push((path(&["keys", action]), candidate));
+    }
+
+    for name in [
+        "time", "currency", "map", "planner", "call", "panel", "alerts", "layout", "startup",
+        "weather", "exchange", "cache", "theme", "tabs",
+    ] {
+        let mut candidate = config.clone();
+        match name {
+            "time" => candidate.time = None,
+            "currency" => candidate.currency = None,
+            "map" => candidate.map = None,
+            "planner" => candidate.planner = None,
+            "call" => candidate.call = None,
+            "panel" => candidate.panel = None,
+            "alerts" => candidate.alerts = None,
+            "layout" => candidate.layout = None,
+            "startup" => candidate.startup = None,
+            "weather" => candidate.weather = None,
+            "exchange" => candidate.exchange = None,
+            "cache" => candidate.cache = None,
+            "theme" => candidate.theme = None,
+            _ => candidate.tabs = None,
+        }
+        fallbacks.push((path(&[name]), candidate));
+    }
+    fallbacks
+}
+
+/// fix what deserialised but doesn't make sense, one city, entry or section at a time,
+/// so `config` passes validation afterwards
+pub fn repair(config: &mut Config, content: &str) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+
+    // timezones first, since they get a suggestion
+    for path in city_paths(config) {
+        let city = match path[0].as_str() {
+            "current_city" => &config.current_city,
+            "home_city" => &config.home_city,
+            _ => &config.tracked_cities[path[1].parse::<usize>().unwrap_or(0)],
+        };
+        if parse_city_timezone(&city.timezone).is_some() {
+            continue;
+        }
+        let mut field = path.clone();
+        field.push("timezone".to_string());
+        problems.push(ConfigProblem {
+            field: field_name(&field),
+            line: value_line(content, &city.timezone).or_else(|| field_line(content, &field)),
+            message: format!(
+                "unknown timezone \"{}\" for {}; {}",
+                city.timezone,
+                city.name,
+                fallback_note(&path)
+            ),
+            suggestion: closest_timezone(&city.timezone)
+                .map(|name| format!("did you mean {}?", name)),
+        });
+        *config = without_city(config, &path);
+    }
+
+    // then whatever validation still rejects, each fallback tried until the error moves on
+    while let Err(error) = config.validate() {
+        let error = format!("{:#}", error);
+        let fix = fallbacks(config).into_iter().find(|(_, candidate)| {
+            candidate.validate().err().map(|e| format!("{:#}", e)) != Some(error.clone())
+        });
+        let Some((path, candidate)) = fix else {
+            problems.push(ConfigProblem {
+                field: "config.toml".to_string(),
+                line: value_line(content, &error),
+                message: format!("{}; using the defaults", error),
+                suggestion: None,
+            });
+            *config = Config::default();
+            break;
+        };
+        problems.push(ConfigProblem {
+            field: field_name(&path),
+            line: value_line(content, &error).or_else(|| field_line(content, &path)),
+            message: format!("{}; {}", error, fallback_note(&path)),
+            suggestion: None,
+        });
+        *config = candidate;
+    }
+
+    problems
+}
+
+/// take out alarms whose city is no longer configured, one problem each; they could
+/// never ring, and would stop the config from saving
+pub fn drop_stranded_alarms(config: &mut Config) -> Vec<ConfigProblem> {
+    let codes = config.all_city_codes();
+    let mut problems = Vec::new();
+    let mut index = 0;
+    config.alarms.retain(|alarm| {
+        let path = ["alarms".to_string(), index.to_string()];
+        index += 1;
+        let known = codes
+            .iter()
+            .any(|code| code.eq_ignore_ascii_case(&alarm.city_code));
+        if !known {
+            problems.push(problem(
+                "",
+                &path,
+                format!(
+                    "unknown city for alarm: {} ({}); {}",
+                    alarm.city_code,
+                    alarm.at,
+                    fallback_note(&path)
+                ),
+            ));
+        }
+        known
+    });
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOOD_CITIES: &str = r#"
+[current_city]
+name = "Auckland"
+code = "AKL"
+country = "New Zealand"
+timezone = "Pacific/Auckland"
+currency = "NZD"
+
+[home_city]
+name = "Boston"
+code = "BOS"
+country = "USA"
+timezone = "America/New_York"
+currency = "USD"
+"#;
+
+    const DISPLAY: &str = r#"
+[display]
+show_seconds = false
+use_24_hour = true
+show_animations = false
+animation_speed_ms = 100
+"#;
+
+    #[test]
+    fn bad_fields_fall_back_one_at_a_time() {
+        let content = format!(
+            r#"{}
+[[tracked_cities]]
+name = "Lisbon"
+code = "LIS"
+country = "Portugal"
+timezone = "Europe/Lisbn"
+currency = "EUR"
+
+[[tracked_cities]]
+name = "Tokyo"
+code = "TYO"
+country = "Japan"
+currency = "JPY"
+
+[[tracked_cities]]
+name = "Sydney"
+code = "SYD"
+country = "Australia"
+timezone = "Australia/Sydney"
+currency = "AUD"
+
+[display]
+use_24_hour = true
+show_animations = false
+animation_speed_ms = 100
+show_seconds = "yes"
+
+[time]
+target_city_codes = ["LIS", "SYD"]
+"#,
+            GOOD_CITIES
+        );
+        let (mut config, mut problems) = parse(&content);
+        problems.extend(repair(&mut config, &content));
+        let shown: Vec<String> = problems.iter().map(|problem| problem.to_string()).collect();
+
+        assert_eq!(config.current_city.code, "AKL");
+        let codes: Vec<&str> = config
+            .tracked_cities
+            .iter()
+            .map(|city| city.code.as_str())
+            .collect();
+        assert_eq!(codes, vec!["SYD"]);
+        assert_eq!(
+            config.time.as_ref().unwrap().target_city_codes,
+            vec!["SYD".to_string()]
+        );
+        assert!(config.validate().is_ok());
+
+        assert_eq!(problems.len(), 3, "{:#?}", shown);
+        assert_eq!(
+            shown[0],
+            "line 40: display.show_seconds: invalid type: string \"yes\", expected a boolean; \
+             using the default"
+        );
+        assert_eq!(
+            shown[1],
+            "line 23: tracked_cities[1]: missing field `timezone`; left out"
+        );
+        assert_eq!(
+            shown[2],
+            "line 20: tracked_cities[0].timezone: unknown timezone \"Europe/Lisbn\" for Lisbon; \
+             left out (did you mean Europe/Lisbon?)"
+        );
+    }
+
+    #[test]
+    fn broken_files_and_cities_fall_back_to_defaults() {
+        let (config, problems) = parse("[current_city\nname = 1");
+        assert_eq!(config.current_city.code, "WLG");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(1));
+
+        let content = "tracked_cities = []\n".to_string()
+            + &GOOD_CITIES.replace("code = \"BOS\"\n", "")
+            + DISPLAY;
+        let (mut config, problems) = parse(&content);
+        assert_eq!(config.home_city.code, "BOS");
+        assert_eq!(config.current_city.code, "AKL");
+        assert_eq!(problems[0].field, "home_city");
+        assert_eq!(problems[0].line, Some(10));
+        let problems = repair(&mut config, &content);
+        assert!(problems.is_empty(), "{:?}", problems);
+
+        let content = "tracked_cities = []\n".to_string()
+            + &GOOD_CITIES.replace("America/New_York", "new york")
+            + DISPLAY;
+        let (mut config, problems) = parse(&content);
+        assert!(problems.is_empty(), "{:?}", problems);
+        let problems = repair(&mut config, &content);
+        assert_eq!(config.home_city, City::boston());
+        assert_eq!(
+            problems[0].suggestion.as_deref(),
+            Some("did you mean America/New_York?")
+        );
+        assert_eq!(problems[0].line, Some(14));
+
+        // a bad key binding drops just that binding
+        let content = "tracked_cities = []\n".to_string()
+            + GOOD_CITIES
+            + DISPLAY
+            + "\n[keys]\nquit = \"Q\"\nswap = \"ctrl-s\"\n";
+        let (mut config, problems) = parse(&content);
+        assert!(problems.is_empty(), "{:?}", problems);
+        let problems = repair(&mut config, &content);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].field, "keys.swap");
+        assert_eq!(config.keys.get("quit").map(String::as_str), Some("Q"));
+        assert!(!config.keys.contains_key("swap"));
+    }
+
+    #[test]
+    fn alarms_for_a_missing_city_are_reported_and_left_out() {
+        let content = "tracked_cities = []\n".to_string()
+            + GOOD_CITIES
+            + DISPLAY
+            + "\n[[alarms]]\ncity_code = \"AKL\"\nat = \"2026-03-01 07:00\"\n"
+            + "\n[[alarms]]\ncity_code = \"TYO\"\nat = \"2026-03-01 09:00\"\n";
+        let (mut config, problems) = parse(&content);
+        assert!(problems.is_empty(), "{:?}", problems);
+        let problems = repair(&mut config, &content);
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert_eq!(problems[0].field, "alarms[1]");
+        assert_eq!(problems[0].message, "unknown city for alarm: TYO; left out");
+        assert_eq!(config.current_city.code, "AKL");
+        assert_eq!(config.alarms.len(), 1);
+
+        // a city removed while nzi runs takes its alarms with it
+        config.current_city = Config::default().current_city;
+        let problems = drop_stranded_alarms(&mut config);
+        assert_eq!(problems[0].field, "alarms[0]");
+        assert!(config.alarms.is_empty());
+    }
+
+    #[test]
+    fn suggests_timezones_by_city_or_spelling() {
+        assert_eq!(closest_timezone("Auckland"), Some("Pacific/Auckland"));
+        assert_eq!(
+            closest_timezone("pacific/aukland"),
+            Some("Pacific/Auckland")
+        );
+        assert_eq!(closest_timezone("Mars/Olympus"), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
@@ -0,0 +1,250 @@
+//! countdown events in overseas cities
+//! resolves stored local times to instants and parses the /event command
+
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDateTime, NaiveTime, Utc, Weekday};
+
+use crate::config::{City, Config, EventConfig};
+use crate::timezone::parse_city_timezone;
+
+/// storage format for event times, local to the event city
+pub const EVENT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// how far ahead an event triggers its reminder
+pub const REMINDER_LEAD: Duration = Duration::minutes(10);
+
+pub fn parse_event_time(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value.trim(), EVENT_TIME_FORMAT).ok()
+}
+
+/// the absolute instant an event happens, honouring the city's DST rules
+pub fn event_instant(event: &EventConfig, city: &City) -> Option<DateTime<Utc>> {
+    city_local_instant(parse_event_time(&event.at)?, city)
+}
+
+/// the instant a city's wall clock shows `local`; a time skipped by a DST change
+/// resolves to the moment the clocks jump, the first one after it
+pub fn city_local_instant(local: NaiveDateTime, city: &City) -> Option<DateTime<Utc>> {
+    let timezone = parse_city_timezone(&city.timezone)?;
+    // gaps are at most a few hours, and start on a whole minute
+    (0..=MAX_DST_GAP_MINUTES).find_map(|minutes| {
+        match timezone.resolve_local_datetime(&(local + Duration::minutes(minutes))) {
+            LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => {
+                Some(datetime.with_timezone(&Utc))
+            }
+            LocalResult::None => None,
+        }
+    })
+}
+
+/// longest clock jump `city_local_instant` steps over
+const MAX_DST_GAP_MINUTES: i64 = 180;
+
+/// the soonest event that has not started yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpcomingEvent {
+    pub title: String,
+    pub city_code: String,
+    pub local: NaiveDateTime,
+    pub remaining: Duration,
+}
+
+impl UpcomingEvent {
+    /// stable identity so a reminder fires once per event
+    pub fn key(&self) -> String {
+        format!("{}@{}", self.title, self.local.format(EVENT_TIME_FORMAT))
+    }
+
+    pub fn is_due_for_reminder(&self) -> bool {
+        self.remaining <= REMINDER_LEAD
+    }
+}
+
+pub fn next_event(config: &Config, now: DateTime<Utc>) -> Option<UpcomingEvent> {
+    config
+        .events
+        .iter()
+        .filter_map(|event| {
+            let city = config
+                .all_cities()
+                .into_iter()
+                .find(|city| city.code.eq_ignore_ascii_case(&event.city_code))?;
+            let instant = event_instant(event, city)?;
+            let local = parse_event_time(&event.at)?;
+            (instant > now).then(|| UpcomingEvent {
+                title: event.title.clone(),
+                city_code: city.code.clone(),
+                local,
+                remaining: instant - now,
+            })
+        })
+        .min_by_key(|event| event.remaining)
+}
+
+/// whether an event's time is behind `now`; events whose city or time can't be
+/// resolved are left for the config problems overlay rather than treated as past
+pub fn event_has_passed(event: &EventConfig, config: &Config, now: DateTime<Utc>) -> bool {
+    config
+        .all_cities()
+        .into_iter()
+        .find(|city| city.code.eq_ignore_ascii_case(&event.city_code))
+        .and_then(|city| event_instant(event, city))
+        .is_some_and(|instant| instant <= now)
+}
+
+/// "2d 03h", "3h 05m", or "4m 09s" depending on how far away the event is
+pub fn format_countdown(remaining: Duration) -> String {
+    let total = remaining.num_seconds().max(0);
+    let (days, hours, minutes, seconds) = (
+        total / 86_400,
+        (total % 86_400) / 3600,
+        (total % 3600) / 60,
+        total % 60,
+    );
+    if days > 0 {
+        format!("{}d {:02}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m {:02}s", minutes, seconds)
+    }
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value.to_lowercase().get(..3)? {
+        "mon" => Some(Weekday::Mon),
+        "tue" => Some(Weekday::Tue),
+        "wed" => Some(Weekday::Wed),
+        "thu" => Some(Weekday::Thu),
+        "fri" => Some(Weekday::Fri),
+        "sat" => Some(Weekday::Sat),
+        "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// parse "<day> <HH:MM> <city code> <title>" where day is a date, weekday, today, or tomorrow;
+/// weekdays resolve to the next occurrence that is still ahead in the event city
+pub fn parse_event_command(
+    input: &str,
+    config: &Config,
+    now: DateTime<Utc>,
+) -> std::result::Result<EventConfig, String> {
+    const USAGE: &str = "usage: /event <date|weekday|today|tomorrow> <HH:MM> <city code> <title>";
+
+    let mut parts = input.split_whitespace();
+    let (Some(day), Some(time), Some(code)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(USAGE.to_string());
+    };
+    let title = parts.collect::<Vec<_>>().join(" ");
+    if title.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let city = config
+        .all_cities()
+        .into_iter()
+        .find(|city| city.code.eq_ignore_ascii_case(code))
+        .ok_or_else(|| format!("unknown city code: {}", code))?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| format!("invalid time: {} (expected HH:MM)", time))?;
+    let timezone = parse_city_timezone(&city.timezone)
+        .ok_or_else(|| format!("invalid timezone for {}", city.name))?;
+    let city_now = timezone.convert_datetime(&now.fixed_offset()).naive_local();
+    let today = city_now.date();
+
+    let date = match day.to_lowercase().as_str() {
+        "today" => today,
+        "tomorrow" => today + Duration::days(1),
+        other => match chrono::NaiveDate::parse_from_str(other, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => {
+                let weekday = parse_weekday(other)
+                    .ok_or_else(|| format!("invalid day: {} (use a date or weekday)", day))?;
+                let ahead = (weekday.num_days_from_monday() + 7
+                    - today.weekday().num_days_from_monday())
+                    % 7;
+                let date = today + Duration::days(ahead.into());
+                if date.and_time(time) <= city_now {
+                    date + Duration::days(7)
+                } else {
+                    date
+                }
+            }
+        },
+    };
+
+    let at = date.and_time(time);
+    if at <= city_now {
+        return Err(format!(
+            "{} {} has already passed in {}",
+            day, time, city.name
+        ));
+    }
+
+    Ok(EventConfig {
+        title,
+        city_code: city.code.clone(),
+        at: at.format(EVENT_TIME_FORMAT).to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn config_with_event(at: &str) -> Config {
+        Config {
+            events: vec![EventConfig {
+                title: "Call with Mum".to_string(),
+                city_code: "BOS".to_string(),
+                at: at.to_string(),
+            }],
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn next_event_resolves_city_local_time() {
+        let config = config_with_event("2025-06-07 19:00");
+        // 19:00 EDT is 23:00 UTC
+        let now = Utc.with_ymd_and_hms(2025, 6, 7, 22, 0, 0).unwrap();
+        let event = next_event(&config, now).expect("event should be upcoming");
+
+        assert_eq!(event.remaining, Duration::hours(1));
+        assert!(!event.is_due_for_reminder());
+        assert_eq!(format_countdown(event.remaining), "1h 00m");
+
+        let later = Utc.with_ymd_and_hms(2025, 6, 7, 23, 0, 1).unwrap();
+        assert!(next_event(&config, later).is_none());
+        assert!(!event_has_passed(&config.events[0], &config, now));
+        assert!(event_has_passed(&config.events[0], &config, later));
+    }
+
+    #[test]
+    fn weekday_resolves_to_next_occurrence_in_event_city() {
+        let config = Config::default();
+        // friday 2025-06-06 12:00 UTC is friday 08:00 in boston
+        let now = Utc.with_ymd_and_hms(2025, 6, 6, 12, 0, 0).unwrap();
+
+        let event = parse_event_command("sat 19:00 bos Call with Mum", &config, now).unwrap();
+        assert_eq!(event.at, "2025-06-07 19:00");
+        assert_eq!(event.city_code, "BOS");
+        assert_eq!(event.title, "Call with Mum");
+
+        let event = parse_event_command("fri 07:00 BOS Standup", &config, now).unwrap();
+        assert_eq!(event.at, "2025-06-13 07:00");
+
+        assert!(parse_event_command("sat 19:00 XXX Call", &config, now).is_err());
+        assert!(parse_event_command("2025-06-01 19:00 BOS Past", &config, now).is_err());
+    }
+
+    #[test]
+    fn countdown_switches_units_with_distance() {
+        assert_eq!(
+            format_countdown(Duration::days(2) + Duration::hours(3)),
+            "2d 03h"
+        );
+        assert_eq!(format_countdown(Duration::seconds(249)), "4m 09s");
+    }
+}
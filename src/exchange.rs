@@ -2,9 +2,63 @@
 //! supports any currency pair with caching
 
 use anyhow::{Context, Result, bail};
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, Instant};
 
+use crate::cache::SnapshotStore;
+use crate::config::FxThreshold;
+use crate::reference::bundled_rate;
+use crate::schema;
+use crate::stats::ServiceMetrics;
+
+/// top-level fields the exchangerate-api v4 and v6 payloads are known to carry
+const KNOWN_RATE_FIELDS: &[&str] = &[
+    "provider",
+    "WARNING_UPGRADE_TO_V6",
+    "terms",
+    "terms_of_use",
+    "documentation",
+    "result",
+    "date",
+    "time_last_updated",
+    "time_last_update_unix",
+    "time_last_update_utc",
+    "time_next_update_unix",
+    "time_next_update_utc",
+];
+
+/// exchangerate-api latest-rates payload (v4 `rates`/`base`, v6 `conversion_rates`/`base_code`)
+#[derive(Debug, Deserialize)]
+struct RatesPayload {
+    #[serde(default, alias = "base_code")]
+    base: Option<String>,
+    #[serde(alias = "conversion_rates")]
+    rates: HashMap<String, Value>, // values kept loose so one odd entry can't sink the rest
+    #[serde(flatten)]
+    extra: BTreeMap<String, Value>,
+}
+
+impl RatesPayload {
+    fn parse(body: &str) -> Result<Self> {
+        serde_json::from_str(body).context("failed to parse exchange rate response")
+    }
+
+    fn rate(&self, to: &str) -> Option<f64> {
+        match self.rates.get(&to.to_uppercase())? {
+            Value::Number(number) => number.as_f64(),
+            Value::String(text) => text.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn unknown_fields(&self) -> Vec<String> {
+        schema::unknown_fields(&self.extra, KNOWN_RATE_FIELDS)
+    }
+}
+
 /// cached exchange rate data
 #[derive(Debug, Clone)]
 struct CachedRate {
@@ -13,8 +67,8 @@ struct CachedRate {
 }
 
 impl CachedRate {
-    fn is_stale(&self) -> bool {
-        self.last_updated.elapsed() > Duration::from_secs(600)
+    fn is_older_than(&self, max_age: Duration) -> bool {
+        self.last_updated.elapsed() >= max_age
     }
 }
 
@@ -22,39 +76,57 @@ impl CachedRate {
 pub struct ExchangeService {
     cache: HashMap<String, CachedRate>,
     client: reqwest::Client,
+    last_fetch_ok: Option<bool>, // None when the last lookup was served from cache
+    served_bundled: bool,        // the last rate came from the bundled monthly averages
+    metrics: ServiceMetrics,
+    unknown_fields: Vec<String>,
+    snapshots: Option<SnapshotStore>, // raw responses kept in the cache directory
 }
 
 impl ExchangeService {
-    pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .unwrap_or_default();
-
+    pub fn new(client: reqwest::Client) -> Self {
         Self {
             cache: HashMap::new(),
             client,
+            last_fetch_ok: None,
+            served_bundled: false,
+            metrics: ServiceMetrics::default(),
+            unknown_fields: Vec::new(),
+            snapshots: None,
         }
     }
 
+    /// keep each fetched response in `store`
+    pub fn keep_snapshots_in(&mut self, store: SnapshotStore) {
+        self.snapshots = Some(store);
+    }
+
+    pub fn snapshots_mut(&mut self) -> Option<&mut SnapshotStore> {
+        self.snapshots.as_mut()
+    }
+
     /// get the cache key for a currency pair
     fn cache_key(from: &str, to: &str) -> String {
         format!("{}_{}", from.to_uppercase(), to.to_uppercase())
     }
 
-    /// get exchange rate, using cache if available and not stale
-    pub async fn get_rate(&mut self, from: &str, to: &str) -> Result<f64> {
+    /// get exchange rate, using the cached one if it is younger than `max_age`
+    pub async fn get_rate(&mut self, from: &str, to: &str, max_age: Duration) -> Result<f64> {
         let key = Self::cache_key(from, to);
+        self.last_fetch_ok = None;
+        self.served_bundled = false;
 
         // check cache first
-        if let Some(cached) = self.cache.get(&key)
-            && !cached.is_stale()
-        {
-            return Ok(cached.rate);
+        if let Some(rate) = self.fresh_cached_rate(&key, max_age) {
+            self.metrics.record_cache_hit();
+            return Ok(rate);
         }
 
         // try to fetch fresh rate
-        match self.fetch_rate(from, to).await {
+        self.metrics.record_api_call();
+        let fetched = self.fetch_rate(from, to).await;
+        self.last_fetch_ok = Some(fetched.is_ok());
+        match fetched {
             Ok(rate) => {
                 self.cache.insert(
                     key,
@@ -66,43 +138,109 @@ impl ExchangeService {
                 Ok(rate)
             }
             Err(_) => {
-                // use cached/identity fallback if API fails
+                // use cached, bundled or identity fallback if API fails
                 self.get_fallback_rate(from, to)
             }
         }
     }
 
-    /// fetch rate from the API
-    async fn fetch_rate(&self, from: &str, to: &str) -> Result<f64> {
-        // using the free exchangerate-api
-        let url = format!(
-            "https://api.exchangerate-api.com/v4/latest/{}",
-            from.to_uppercase()
+    fn fresh_cached_rate(&self, key: &str, max_age: Duration) -> Option<f64> {
+        self.cache
+            .get(key)
+            .filter(|cached| !cached.is_older_than(max_age))
+            .map(|cached| cached.rate)
+    }
+
+    /// the pair's cached rate when it is younger than `max_age`, without fetching
+    pub fn fresh_rate(&self, from: &str, to: &str, max_age: Duration) -> Option<f64> {
+        self.fresh_cached_rate(&Self::cache_key(from, to), max_age)
+    }
+
+    /// the request for `from`'s rates, to run off the UI loop; hand its result to
+    /// `finish_rate_fetch`
+    pub fn start_rate_fetch(
+        &mut self,
+        from: &str,
+    ) -> impl Future<Output = Result<String>> + Send + 'static {
+        self.metrics.record_api_call();
+        request_rates(self.client.clone(), from.to_string())
+    }
+
+    /// the `from` to `to` rate in a `start_rate_fetch` response, cached as `get_rate` would
+    pub fn finish_rate_fetch(&mut self, from: &str, to: &str, body: Result<String>) -> Result<f64> {
+        let rate = self.read_rate(from, to, &body?)?;
+        self.cache.insert(
+            Self::cache_key(from, to),
+            CachedRate {
+                rate,
+                last_updated: Instant::now(),
+            },
         );
+        Ok(rate)
+    }
 
-        let response: serde_json::Value = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("failed to fetch exchange rate")?
-            .json()
-            .await
-            .context("failed to parse exchange rate response")?;
+    /// whether the last get_rate call reached the API (None if served from cache)
+    pub fn last_fetch_ok(&self) -> Option<bool> {
+        self.last_fetch_ok
+    }
 
-        let rates = response["rates"]
-            .as_object()
-            .context("invalid response format")?;
+    /// whether the last get_rate call fell back to the bundled monthly averages
+    pub fn served_bundled_rate(&self) -> bool {
+        self.served_bundled
+    }
 
-        let to_upper = to.to_uppercase();
-        rates
-            .get(&to_upper)
-            .and_then(|v| v.as_f64())
-            .context("currency not found in response")
+    pub fn metrics(&self) -> ServiceMetrics {
+        self.metrics
+    }
+
+    /// fields the last API response carried that the adapter does not recognise
+    pub fn unknown_fields(&self) -> &[String] {
+        &self.unknown_fields
+    }
+
+    /// the pair's rate in each kept response, either direction, oldest first
+    pub fn rate_history(&self, from: &str, to: &str) -> Vec<f64> {
+        self.snapshots
+            .as_ref()
+            .map_or_else(Vec::new, |store| kept_rate_history(store, from, to))
+    }
+
+    /// whether a response giving this pair (either direction) is kept on disk, from this
+    /// session or an earlier one
+    pub fn has_kept_rate(&self, from: &str, to: &str) -> bool {
+        !self.rate_history(from, to).is_empty()
+    }
+
+    /// fetch rate from the API
+    async fn fetch_rate(&mut self, from: &str, to: &str) -> Result<f64> {
+        let body = request_rates(self.client.clone(), from.to_string()).await?;
+        self.read_rate(from, to, &body)
+    }
+
+    /// the `to` rate in a response for `from`, keeping the response as a snapshot
+    fn read_rate(&mut self, from: &str, to: &str, body: &str) -> Result<f64> {
+        let payload = RatesPayload::parse(body)?;
+        self.unknown_fields = payload.unknown_fields();
+        if let Some(store) = &self.snapshots {
+            // losing a snapshot only costs the cache's history
+            let _ = store.save(from, body, Utc::now());
+        }
+        if let Some(base) = &payload.base
+            && !base.eq_ignore_ascii_case(from)
+        {
+            bail!(
+                "rates returned for {} instead of {}",
+                base,
+                from.to_uppercase()
+            );
+        }
+
+        payload.rate(to).context("currency not found in response")
     }
 
-    /// get fallback rate when offline
-    fn get_fallback_rate(&self, from: &str, to: &str) -> Result<f64> {
+    /// get fallback rate when offline: identity, then any cached rate, then the latest kept
+    /// response (from an earlier session or an imported bundle), then the bundled averages
+    fn get_fallback_rate(&mut self, from: &str, to: &str) -> Result<f64> {
         let from_upper = from.to_uppercase();
         let to_upper = to.to_uppercase();
 
@@ -127,13 +265,94 @@ impl ExchangeService {
             return Ok(1.0 / cached.rate);
         }
 
+        if let Some(&rate) = self.rate_history(&from_upper, &to_upper).last() {
+            return Ok(rate);
+        }
+
+        if let Some(rate) = bundled_rate(&from_upper, &to_upper) {
+            self.served_bundled = true;
+            return Ok(rate);
+        }
+
         bail!("rate unavailable (offline, no cache)")
     }
 }
 
+/// `from`'s latest rates from the free exchangerate-api, as the raw response
+async fn request_rates(client: reqwest::Client, from: String) -> Result<String> {
+    let url = format!(
+        "https://api.exchangerate-api.com/v4/latest/{}",
+        from.to_uppercase()
+    );
+    client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context("failed to fetch exchange rate")?
+        .text()
+        .await
+        .context("failed to read exchange rate response")
+}
+
+/// the alert a threshold raises at `rate`, e.g. "NZD/USD below 0.5800"; `None` while the
+/// rate is inside its levels
+pub fn threshold_breach(threshold: &FxThreshold, rate: f64) -> Option<String> {
+    let (from, to) = threshold.currencies()?;
+    if let Some(level) = threshold.above
+        && rate > level
+    {
+        return Some(format!("{}/{} above {:.4}", from, to, level));
+    }
+    if let Some(level) = threshold.below
+        && rate < level
+    {
+        return Some(format!("{}/{} below {:.4}", from, to, level));
+    }
+    None
+}
+
+/// the pair's rate in each response kept in `store`, either direction, oldest first
+pub fn kept_rate_history(store: &SnapshotStore, from: &str, to: &str) -> Vec<f64> {
+    let rate_in = |body: &str, currency: &str| RatesPayload::parse(body).ok()?.rate(currency);
+    let mut rates: Vec<(String, f64)> = store
+        .saved(from)
+        .into_iter()
+        .filter_map(|(time, body)| Some((time, rate_in(&body, to)?)))
+        .chain(store.saved(to).into_iter().filter_map(|(time, body)| {
+            let rate = rate_in(&body, from)?;
+            (rate > 0.0).then(|| (time, 1.0 / rate))
+        }))
+        .collect();
+    rates.sort_by(|a, b| a.0.cmp(&b.0));
+    rates.into_iter().map(|(_, rate)| rate).collect()
+}
+
+/// keep `FROM_TO` rates in `store` as one response per base currency, stamped `at`
+pub fn keep_rates(
+    store: &SnapshotStore,
+    rates: &BTreeMap<String, f64>,
+    at: DateTime<Utc>,
+) -> Result<()> {
+    let mut by_base: BTreeMap<&str, BTreeMap<&str, f64>> = BTreeMap::new();
+    for (pair, &rate) in rates {
+        if let Some((from, to)) = pair.split_once('_')
+            && rate.is_finite()
+            && rate > 0.0
+        {
+            by_base.entry(from).or_default().insert(to, rate);
+        }
+    }
+    for (base, rates) in by_base {
+        let body = serde_json::json!({ "base": base, "rates": rates });
+        store.save(base, &body.to_string(), at)?;
+    }
+    Ok(())
+}
+
 impl Default for ExchangeService {
     fn default() -> Self {
-        Self::new()
+        Self::new(crate::http::client())
     }
 }
 
@@ -306,3 +525,173 @@ impl CurrencyConverter {
         pairs
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_v4_payload_with_provider_extras() {
+        let body = r#"{
+            "provider": "https://www.exchangerate-api.com",
+            "WARNING_UPGRADE_TO_V6": "https://www.exchangerate-api.com/docs/free",
+            "terms": "https://www.exchangerate-api.com/terms",
+            "base": "NZD",
+            "date": "2025-06-02",
+            "time_last_updated": 1748822401,
+            "rates": {"NZD": 1, "USD": 0.6012, "GBP": 0.4451}
+        }"#;
+        let payload = RatesPayload::parse(body).unwrap();
+
+        assert_eq!(payload.base.as_deref(), Some("NZD"));
+        assert_eq!(payload.rate("usd"), Some(0.6012));
+        assert!(payload.unknown_fields().is_empty());
+    }
+
+    #[test]
+    fn thresholds_report_which_level_the_rate_crossed() {
+        let threshold = FxThreshold {
+            pair: "nzd/usd".to_string(),
+            above: Some(0.62),
+            below: Some(0.58),
+        };
+        assert_eq!(threshold_breach(&threshold, 0.60), None);
+        assert_eq!(
+            threshold_breach(&threshold, 0.5712).as_deref(),
+            Some("NZD/USD below 0.5800")
+        );
+        assert_eq!(
+            threshold_breach(&threshold, 0.63).as_deref(),
+            Some("NZD/USD above 0.6200")
+        );
+    }
+
+    #[test]
+    fn parses_v6_payload_field_names() {
+        let body = r#"{
+            "result": "success",
+            "base_code": "NZD",
+            "conversion_rates": {"USD": 0.6012}
+        }"#;
+        let payload = RatesPayload::parse(body).unwrap();
+
+        assert_eq!(payload.base.as_deref(), Some("NZD"));
+        assert_eq!(payload.rate("USD"), Some(0.6012));
+    }
+
+    #[test]
+    fn tolerates_new_fields_and_odd_rate_values() {
+        let body = r#"{
+            "base": "NZD",
+            "rates": {"USD": "0.6012", "XDR": null, "GBP": 0.4451},
+            "source_tier": "free"
+        }"#;
+        let payload = RatesPayload::parse(body).unwrap();
+
+        assert_eq!(payload.rate("USD"), Some(0.6012));
+        assert_eq!(payload.rate("XDR"), None);
+        assert_eq!(payload.rate("GBP"), Some(0.4451));
+        assert_eq!(payload.unknown_fields(), vec!["source_tier".to_string()]);
+    }
+
+    #[test]
+    fn offline_fallback_uses_bundled_averages_after_cache() {
+        let mut service = ExchangeService::new(reqwest::Client::new());
+        assert_eq!(service.get_fallback_rate("nzd", "NZD").unwrap(), 1.0);
+
+        let rate = service.get_fallback_rate("NZD", "MYR").unwrap();
+        assert!(service.served_bundled_rate());
+        assert!((2.0..3.0).contains(&rate), "{}", rate);
+        let reverse = service.get_fallback_rate("MYR", "NZD").unwrap();
+        assert!((rate * reverse - 1.0).abs() < 1e-9);
+
+        service.served_bundled = false;
+        service.cache.insert(
+            ExchangeService::cache_key("NZD", "MYR"),
+            CachedRate {
+                rate: 2.5,
+                last_updated: Instant::now(),
+            },
+        );
+        assert_eq!(service.get_fallback_rate("NZD", "MYR").unwrap(), 2.5);
+        assert!(!service.served_bundled_rate());
+        assert!(service.get_fallback_rate("NZD", "XXX").is_err());
+    }
+
+    #[test]
+    fn a_one_minute_interval_fetches_again_after_a_minute() {
+        let every_minute = Duration::from_secs(60);
+        let key = ExchangeService::cache_key("NZD", "AUD");
+        let mut service = ExchangeService::new(reqwest::Client::new());
+        service.cache.insert(
+            key.clone(),
+            CachedRate {
+                rate: 0.91,
+                last_updated: Instant::now(),
+            },
+        );
+        assert_eq!(service.fresh_cached_rate(&key, every_minute), Some(0.91));
+
+        service.cache.insert(
+            key.clone(),
+            CachedRate {
+                rate: 0.91,
+                last_updated: Instant::now() - Duration::from_secs(61),
+            },
+        );
+        assert_eq!(service.fresh_cached_rate(&key, every_minute), None);
+        assert_eq!(
+            service.fresh_cached_rate(&key, Duration::from_secs(300)),
+            Some(0.91)
+        );
+    }
+
+    #[test]
+    fn rate_history_reads_kept_responses_in_both_directions() {
+        use crate::cache::{CacheLimits, RATE_SNAPSHOTS};
+
+        let root = std::env::temp_dir().join(format!("nzi-rate-history-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let store = SnapshotStore::new(
+            root.clone(),
+            RATE_SNAPSHOTS,
+            CacheLimits {
+                max_bytes: 1_000_000,
+                max_age: None,
+            },
+        );
+        let start = Utc::now();
+        let at = |minutes| start + chrono::Duration::minutes(minutes);
+        store
+            .save("NZD", r#"{"base": "NZD", "rates": {"AUD": 0.90}}"#, at(0))
+            .unwrap();
+        store
+            .save("AUD", r#"{"base": "AUD", "rates": {"NZD": 1.0}}"#, at(1))
+            .unwrap();
+        store
+            .save("NZD", r#"{"base": "NZD", "rates": {"GBP": 0.47}}"#, at(2))
+            .unwrap();
+        store
+            .save("NZD", r#"{"base": "NZD", "rates": {"AUD": 0.93}}"#, at(3))
+            .unwrap();
+
+        let mut service = ExchangeService::new(reqwest::Client::new());
+        assert!(service.rate_history("NZD", "AUD").is_empty());
+        service.keep_snapshots_in(store);
+        assert_eq!(service.rate_history("NZD", "AUD"), vec![0.90, 1.0, 0.93]);
+
+        let bundled =
+            BTreeMap::from([("NZD_AUD".to_string(), 0.95), ("NZD_XXX".to_string(), -1.0)]);
+        keep_rates(service.snapshots_mut().unwrap(), &bundled, at(4)).unwrap();
+        assert_eq!(
+            service.rate_history("NZD", "AUD"),
+            vec![0.90, 1.0, 0.93, 0.95]
+        );
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn missing_rates_table_is_an_error() {
+        assert!(RatesPayload::parse(r#"{"base": "NZD"}"#).is_err());
+    }
+}
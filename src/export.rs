@@ -0,0 +1,145 @@
+//! map snapshots as plain UTF-8 or ANSI-coloured text, for pasting into READMEs and chats
+//! a rendered buffer is read back cell by cell; only the foreground colour and bold survive
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr;
+
+/// default snapshot target: a timestamped text file in the home directory
+pub fn default_map_path(now: DateTime<Local>) -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(format!("nzi-map-{}.txt", now.format("%Y%m%d-%H%M%S")))
+}
+
+/// `.ans` and `.ansi` files keep the colours; anything else is plain text
+pub fn wants_ansi(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| matches!(extension.to_lowercase().as_str(), "ans" | "ansi"))
+}
+
+/// write a rendered map to `path`, coloured or plain by its extension
+pub fn write_map(path: &Path, buf: &Buffer) -> Result<()> {
+    let text = if wants_ansi(path) {
+        buffer_ansi(buf)
+    } else {
+        buffer_text(buf)
+    };
+    fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// each row's cells, skipping the blanks that follow a wide glyph such as an emoji
+fn row_cells(buf: &Buffer, y: u16) -> Vec<&ratatui::buffer::Cell> {
+    let area = buf.area;
+    let mut cells = Vec::new();
+    let mut x = area.left();
+    while x < area.right() {
+        let cell = &buf[(x, y)];
+        cells.push(cell);
+        x += cell.symbol().width().max(1) as u16;
+    }
+    cells
+}
+
+/// the buffer's text, one line per row with trailing spaces trimmed
+pub fn buffer_text(buf: &Buffer) -> String {
+    let area = buf.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let line: String = row_cells(buf, y).iter().map(|cell| cell.symbol()).collect();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// SGR parameters for a foreground colour, or None for the terminal's default
+fn foreground_sgr(color: Color) -> Option<String> {
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Indexed(index) => return Some(format!("38;5;{}", index)),
+        Color::Rgb(r, g, b) => return Some(format!("38;2;{};{};{}", r, g, b)),
+    };
+    Some(code.to_string())
+}
+
+/// the buffer's text with escape codes for each run of foreground colour and bold,
+/// reset at the end of every line so pasted rows don't bleed into each other
+pub fn buffer_ansi(buf: &Buffer) -> String {
+    let area = buf.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let cells = row_cells(buf, y);
+        let used = cells
+            .iter()
+            .rposition(|cell| !cell.symbol().trim().is_empty())
+            .map_or(0, |last| last + 1);
+        let mut current = None;
+        for cell in &cells[..used] {
+            let bold = cell.modifier.contains(Modifier::BOLD);
+            let style = (cell.fg, bold);
+            if current != Some(style) {
+                let mut params = vec!["0".to_string()];
+                params.extend(foreground_sgr(cell.fg));
+                if bold {
+                    params.push("1".to_string());
+                }
+                text.push_str(&format!("\x1b[{}m", params.join(";")));
+                current = Some(style);
+            }
+            text.push_str(cell.symbol());
+        }
+        if current.is_some() {
+            text.push_str("\x1b[0m");
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn snapshots_read_back_as_plain_or_coloured_text() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 8, 2));
+        buf.set_string(0, 0, "⣿⣿", Style::default().fg(Color::Rgb(166, 227, 161)));
+        buf.set_string(2, 0, "🥝a", Style::default().fg(Color::Red).bold());
+
+        assert_eq!(buffer_text(&buf), "⣿⣿🥝a\n\n");
+        assert_eq!(
+            buffer_ansi(&buf),
+            "\x1b[0;38;2;166;227;161m⣿⣿\x1b[0;31;1m🥝a\x1b[0m\n\n"
+        );
+
+        assert!(wants_ansi(Path::new("aotearoa.ANS")));
+        assert!(wants_ansi(Path::new("map.ansi")));
+        assert!(!wants_ansi(Path::new("map.txt")));
+        assert!(!wants_ansi(Path::new("map")));
+    }
+}
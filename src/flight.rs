@@ -0,0 +1,70 @@
+//! rough flight estimates between the converter cities
+//! great-circle distance at a typical airliner cruise, plus a fixed allowance for taxi, climb and descent
+
+use chrono::Duration;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+/// average block speed once airborne, km/h
+const CRUISE_KMPH: f64 = 850.0;
+/// taxi, climb and approach
+const GROUND_ALLOWANCE_MINUTES: i64 = 30;
+/// closer than this the cities share an airport and no estimate is shown
+pub const MIN_FLIGHT_KM: f64 = 150.0;
+
+/// great-circle distance in km between two (lat, lon) points in degrees
+pub fn great_circle_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (to.1 - from.1).to_radians();
+    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// approximate door-to-door flying time (non-stop), rounded to 5 minutes
+pub fn flight_duration(distance_km: f64) -> Duration {
+    let airborne = (distance_km / CRUISE_KMPH * 60.0).round() as i64;
+    let minutes = airborne + GROUND_ALLOWANCE_MINUTES;
+    Duration::minutes((minutes + 2) / 5 * 5)
+}
+
+/// "18,790 km", rounded to the nearest 10 km
+pub fn format_distance_km(distance_km: f64) -> String {
+    let tens = (distance_km / 10.0).round() as u64 * 10;
+    let digits = tens.to_string();
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{} km", grouped)
+}
+
+/// "14h 05m" or "55m"
+pub fn format_flight_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_long_haul_from_wellington() {
+        // wellington to london is roughly 18,800 km
+        let distance = great_circle_km((-41.2865, 174.7762), (51.5074, -0.1278));
+        assert!((distance - 18_800.0).abs() < 100.0, "{}", distance);
+
+        let duration = flight_duration(distance);
+        assert_eq!(format_flight_duration(duration), "22h 40m");
+        assert_eq!(format_flight_duration(Duration::minutes(55)), "55m");
+        assert_eq!(format_distance_km(18_794.6), "18,790 km");
+        assert_eq!(format_distance_km(484.0), "480 km");
+    }
+}
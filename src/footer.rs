@@ -0,0 +1,68 @@
+//! `display.footer`: text with `{placeholder}`s drawn at the right of the footer whatever
+//! is focused, like tmux's status-right
+
+/// the placeholders a footer format can use
+pub const PLACEHOLDERS: [&str; 5] = ["nz_time", "home_time", "rate", "weather_city", "temp"];
+
+/// a footer format split into literal text and placeholder names
+fn pieces(format: &str) -> Vec<(bool, &str)> {
+    let mut pieces = Vec::new();
+    let mut rest = format;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        if open > 0 {
+            pieces.push((false, &rest[..open]));
+        }
+        pieces.push((true, &rest[open + 1..open + close]));
+        rest = &rest[open + close + 1..];
+    }
+    if !rest.is_empty() {
+        pieces.push((false, rest));
+    }
+    pieces
+}
+
+/// the first `{name}` in `format` that isn't one of `PLACEHOLDERS`
+pub fn unknown_placeholder(format: &str) -> Option<&str> {
+    pieces(format)
+        .into_iter()
+        .find(|(placeholder, name)| *placeholder && !PLACEHOLDERS.contains(name))
+        .map(|(_, name)| name)
+}
+
+/// `format` with each placeholder replaced by `value(name)`
+pub fn render(format: &str, value: impl Fn(&str) -> String) -> String {
+    pieces(format)
+        .into_iter()
+        .map(|(placeholder, text)| {
+            if placeholder {
+                value(text)
+            } else {
+                text.to_string()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_placeholders_and_keeps_the_rest() {
+        let value = |name: &str| match name {
+            "nz_time" => "09:30".to_string(),
+            "temp" => "14°C".to_string(),
+            _ => "?".to_string(),
+        };
+        assert_eq!(
+            render("NZ {nz_time} · {temp} {", value),
+            "NZ 09:30 · 14°C {"
+        );
+        assert_eq!(render("no fields", value), "no fields");
+        assert_eq!(unknown_placeholder("{nz_time} {rate}"), None);
+        assert_eq!(unknown_placeholder("{nz_time} {wind}"), Some("wind"));
+    }
+}
@@ -0,0 +1,138 @@
+//! data source health tracking
+//! feeds the startup splash with cache and reachability state
+
+use chrono::{DateTime, Local};
+
+/// an external data source the dashboard depends on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataSource {
+    Weather,
+    Exchange,
+}
+
+impl DataSource {
+    pub const ALL: [DataSource; 2] = [DataSource::Weather, DataSource::Exchange];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Weather => "Weather",
+            Self::Exchange => "Exchange rates",
+        }
+    }
+}
+
+/// last known state of a data source
+#[derive(Debug, Clone)]
+pub struct SourceHealth {
+    pub source: DataSource,
+    pub cache_warm: bool,
+    pub last_refresh: Option<DateTime<Local>>,
+    pub reachable: Option<bool>, // None until the network is actually contacted
+    checked: bool,
+}
+
+impl SourceHealth {
+    pub fn new(source: DataSource) -> Self {
+        Self {
+            source,
+            cache_warm: false,
+            last_refresh: None,
+            reachable: None,
+            checked: false,
+        }
+    }
+
+    /// record the outcome of a refresh attempt; `reachable` is None when it was served
+    /// from cache, which says nothing about the network
+    pub fn record(&mut self, reachable: Option<bool>, cache_warm: bool) {
+        self.cache_warm = cache_warm;
+        self.checked = true;
+        if let Some(reachable) = reachable {
+            self.reachable = Some(reachable);
+            if reachable {
+                self.last_refresh = Some(Local::now());
+            }
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn status_label(&self) -> &'static str {
+        match self.reachable {
+            None if self.checked => "unchecked",
+            None => "checking…",
+            Some(true) => "reachable",
+            Some(false) => "unreachable",
+        }
+    }
+
+    pub fn last_refresh_label(&self) -> String {
+        self.last_refresh
+            .map(|at| at.format("%H:%M:%S").to_string())
+            .unwrap_or_else(|| "never".to_string())
+    }
+}
+
+/// overall network reachability across the sources that contacted it; None while one is
+/// still checking, or when every source was served from cache
+pub fn network_reachable(sources: &[SourceHealth]) -> Option<bool> {
+    if sources.iter().any(|source| source.reachable == Some(true)) {
+        return Some(true);
+    }
+    if sources.iter().all(SourceHealth::is_checked)
+        && sources.iter().any(|source| source.reachable == Some(false))
+    {
+        return Some(false);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn successful_refresh_marks_source_reachable() {
+        let mut health = SourceHealth::new(DataSource::Weather);
+        assert_eq!(health.status_label(), "checking…");
+
+        health.record(Some(true), true);
+        assert!(health.is_checked());
+        assert!(health.cache_warm);
+        assert!(health.last_refresh.is_some());
+    }
+
+    #[test]
+    fn network_is_unreachable_only_once_every_source_failed() {
+        let mut sources: Vec<SourceHealth> =
+            DataSource::ALL.into_iter().map(SourceHealth::new).collect();
+        assert_eq!(network_reachable(&sources), None);
+
+        sources[0].record(Some(false), false);
+        assert_eq!(network_reachable(&sources), None);
+
+        sources[1].record(Some(false), false);
+        assert_eq!(network_reachable(&sources), Some(false));
+
+        sources[1].record(Some(true), true);
+        assert_eq!(network_reachable(&sources), Some(true));
+    }
+
+    #[test]
+    fn cache_hit_leaves_the_network_unchecked() {
+        let mut sources: Vec<SourceHealth> =
+            DataSource::ALL.into_iter().map(SourceHealth::new).collect();
+        for source in &mut sources {
+            source.record(None, true);
+        }
+        assert!(sources.iter().all(SourceHealth::is_checked));
+        assert_eq!(sources[0].reachable, None);
+        assert_eq!(sources[0].status_label(), "unchecked");
+        assert_eq!(network_reachable(&sources), None);
+
+        sources[1].record(Some(false), true);
+        assert_eq!(network_reachable(&sources), Some(false));
+    }
+}
@@ -0,0 +1,201 @@
+//! a coarse national temperature picture from open-meteo, for shading the NZ map
+//! one request covers the whole grid; the map colours each stretch of coast by its nearest point
+
+use std::future::Future;
+use std::time::Instant;
+
+use anyhow::{Context, Result, bail};
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::stats::ServiceMetrics;
+
+/// how often the grid is re-fetched; open-meteo's current values move every 15 minutes
+const REFRESH_EVERY: std::time::Duration = std::time::Duration::from_secs(1800);
+
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+/// (lat, lon) of towns spread over both islands, from Kaitaia to Stewart Island
+pub const HEAT_GRID: &[(f64, f64)] = &[
+    (-35.11, 173.26),
+    (-35.73, 174.32),
+    (-36.85, 174.76),
+    (-37.79, 175.28),
+    (-37.69, 176.17),
+    (-38.14, 176.25),
+    (-38.66, 178.02),
+    (-38.69, 176.07),
+    (-39.06, 174.08),
+    (-39.49, 176.91),
+    (-39.93, 175.05),
+    (-40.36, 175.61),
+    (-40.95, 175.66),
+    (-41.29, 174.78),
+    (-41.27, 173.28),
+    (-41.51, 173.96),
+    (-41.75, 171.60),
+    (-42.40, 173.68),
+    (-42.45, 171.21),
+    (-43.53, 172.64),
+    (-43.73, 170.10),
+    (-43.88, 169.04),
+    (-44.40, 171.25),
+    (-45.03, 168.66),
+    (-45.41, 167.72),
+    (-45.88, 170.50),
+    (-46.41, 168.35),
+    (-46.90, 168.13),
+];
+
+#[derive(Deserialize)]
+struct GridPoint {
+    current: GridCurrent,
+}
+
+#[derive(Deserialize)]
+struct GridCurrent {
+    temperature_2m: f64,
+}
+
+/// temperatures in grid order from a multi-location response; open-meteo sends
+/// an array for several locations and a bare object for one
+pub fn parse_grid_temperatures(body: &str, expected: usize) -> Result<Vec<f64>> {
+    let points: Vec<GridPoint> = if body.trim_start().starts_with('[') {
+        serde_json::from_str(body).context("failed to parse temperature grid")?
+    } else {
+        vec![serde_json::from_str(body).context("failed to parse temperature grid")?]
+    };
+    if points.len() != expected {
+        bail!(
+            "temperature grid had {} points, expected {}",
+            points.len(),
+            expected
+        );
+    }
+    Ok(points
+        .into_iter()
+        .map(|point| point.current.temperature_2m)
+        .collect())
+}
+
+/// blue at freezing through yellow at 15° to red at 28° and above
+pub fn heat_colour(temp_c: f64) -> Color {
+    const STOPS: [(f64, (f64, f64, f64)); 3] = [
+        (0.0, (137.0, 180.0, 250.0)),
+        (15.0, (249.0, 226.0, 175.0)),
+        (28.0, (243.0, 139.0, 168.0)),
+    ];
+    let temp = temp_c.clamp(STOPS[0].0, STOPS[2].0);
+    let (low, high) = if temp <= STOPS[1].0 {
+        (STOPS[0], STOPS[1])
+    } else {
+        (STOPS[1], STOPS[2])
+    };
+    let t = (temp - low.0) / (high.0 - low.0);
+    let mix = |a: f64, b: f64| (a + (b - a) * t).round() as u8;
+    Color::Rgb(
+        mix(low.1.0, high.1.0),
+        mix(low.1.1, high.1.1),
+        mix(low.1.2, high.1.2),
+    )
+}
+
+/// current temperatures across `HEAT_GRID` as (lat, lon, °C), fetched every half hour while shown
+pub struct HeatService {
+    client: reqwest::Client,
+    points: Vec<(f64, f64, f64)>,
+    last_attempt: Option<Instant>,
+    metrics: ServiceMetrics,
+}
+
+impl HeatService {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            points: Vec::new(),
+            last_attempt: None,
+            metrics: ServiceMetrics::default(),
+        }
+    }
+
+    pub fn needs_fetch(&self) -> bool {
+        self.last_attempt
+            .is_none_or(|attempt| attempt.elapsed() >= REFRESH_EVERY)
+    }
+
+    /// the request for the grid's temperatures, to run off the UI loop; hand its result to
+    /// `finish_fetch`
+    pub fn start_fetch(&mut self) -> impl Future<Output = Result<Vec<f64>>> + Send + 'static {
+        self.last_attempt = Some(Instant::now());
+        self.metrics.record_api_call();
+
+        let join = |coordinate: fn(&(f64, f64)) -> f64| {
+            HEAT_GRID
+                .iter()
+                .map(|point| coordinate(point).to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let url = format!(
+            "{}?latitude={}&longitude={}&current=temperature_2m",
+            OPEN_METEO_URL,
+            join(|point| point.0),
+            join(|point| point.1)
+        );
+        let client = self.client.clone();
+        async move {
+            let body = client
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .context("failed to fetch temperature grid")?
+                .text()
+                .await
+                .context("failed to read temperature grid")?;
+            parse_grid_temperatures(&body, HEAT_GRID.len())
+        }
+    }
+
+    /// replace the grid; on failure the previous one is kept until the next attempt
+    pub fn finish_fetch(&mut self, fetched: Result<Vec<f64>>) -> Result<()> {
+        let temperatures = fetched?;
+        self.points = HEAT_GRID
+            .iter()
+            .zip(temperatures)
+            .map(|(&(lat, lon), temp)| (lat, lon, temp))
+            .collect();
+        Ok(())
+    }
+
+    pub fn points(&self) -> &[(f64, f64, f64)] {
+        &self.points
+    }
+
+    pub fn metrics(&self) -> ServiceMetrics {
+        self.metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_grids_and_grades_from_blue_to_red() {
+        let body = r#"[{"latitude":-35.1,"longitude":173.25,"current":{"time":"2025-01-10T03:00","temperature_2m":23.4}},
+            {"latitude":-46.9,"longitude":168.125,"current":{"time":"2025-01-10T03:00","temperature_2m":11.0}}]"#;
+        assert_eq!(parse_grid_temperatures(body, 2).unwrap(), vec![23.4, 11.0]);
+        assert!(parse_grid_temperatures(body, 3).is_err());
+        let single = r#"{"current":{"temperature_2m":-2.5}}"#;
+        assert_eq!(parse_grid_temperatures(single, 1).unwrap(), vec![-2.5]);
+
+        assert_eq!(heat_colour(-5.0), Color::Rgb(137, 180, 250));
+        assert_eq!(heat_colour(15.0), Color::Rgb(249, 226, 175));
+        assert_eq!(heat_colour(35.0), Color::Rgb(243, 139, 168));
+        let Color::Rgb(mild, _, _) = heat_colour(7.5) else {
+            unreachable!()
+        };
+        assert!((137..249).contains(&mild));
+    }
+}
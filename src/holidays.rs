@@ -0,0 +1,488 @@
+//! public holidays: bundled NZ rules plus Nager.Date lookups for other countries
+//! NZ covers Mondayisation, Matariki, and regional anniversary days
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+
+use crate::stats::ServiceMetrics;
+
+/// a public holiday on the date it is observed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+impl Holiday {
+    fn new(date: NaiveDate, name: &str) -> Self {
+        Self {
+            date,
+            name: name.to_string(),
+        }
+    }
+}
+
+/// days ahead scanned for an upcoming holiday
+pub const HOLIDAY_LOOKAHEAD_DAYS: i64 = 7;
+
+/// Matariki dates set in the Te Kāhui o Matariki Public Holiday Act 2022
+const MATARIKI: &[(i32, u32, u32)] = &[
+    (2022, 6, 24),
+    (2023, 7, 14),
+    (2024, 6, 28),
+    (2025, 6, 20),
+    (2026, 7, 10),
+    (2027, 6, 25),
+    (2028, 7, 14),
+    (2029, 7, 6),
+    (2030, 6, 21),
+    (2031, 7, 11),
+    (2032, 7, 2),
+    (2033, 6, 24),
+    (2034, 7, 7),
+    (2035, 6, 29),
+    (2036, 7, 18),
+    (2037, 7, 10),
+    (2038, 6, 25),
+    (2039, 7, 15),
+    (2040, 7, 6),
+    (2041, 7, 19),
+    (2042, 7, 11),
+    (2043, 7, 3),
+    (2044, 6, 24),
+    (2045, 7, 7),
+    (2046, 6, 29),
+    (2047, 7, 19),
+    (2048, 7, 3),
+    (2049, 6, 25),
+    (2050, 7, 15),
+    (2051, 6, 30),
+    (2052, 6, 21),
+];
+
+/// NZ regions that observe their own anniversary day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NzRegion {
+    Auckland,
+    Wellington,
+    Nelson,
+    Taranaki,
+    Otago,
+    Southland,
+    SouthCanterbury,
+    HawkesBay,
+    Marlborough,
+    Canterbury,
+    ChathamIslands,
+    Westland,
+}
+
+impl NzRegion {
+    /// region for a city name; Waikato, Bay of Plenty, and Northland keep Auckland's day,
+    /// Manawatū keeps Wellington's
+    pub fn for_city(name: &str) -> Option<Self> {
+        let region = match name.trim().to_lowercase().as_str() {
+            "auckland" | "hamilton" | "tauranga" | "whangarei" | "whangārei" | "rotorua" => {
+                Self::Auckland
+            }
+            "wellington" | "lower hutt" | "porirua" | "palmerston north" | "masterton" => {
+                Self::Wellington
+            }
+            "nelson" => Self::Nelson,
+            "new plymouth" => Self::Taranaki,
+            "dunedin" | "queenstown" | "oamaru" => Self::Otago,
+            "invercargill" => Self::Southland,
+            "timaru" => Self::SouthCanterbury,
+            "napier" | "hastings" => Self::HawkesBay,
+            "blenheim" => Self::Marlborough,
+            "christchurch" => Self::Canterbury,
+            "greymouth" | "hokitika" => Self::Westland,
+            "waitangi" | "chatham islands" => Self::ChathamIslands,
+            _ => return None,
+        };
+        Some(region)
+    }
+
+    fn anniversary(self, year: i32) -> Option<Holiday> {
+        let date = match self {
+            Self::Auckland => nearest_monday(ymd(year, 1, 29)?),
+            Self::Wellington => nearest_monday(ymd(year, 1, 22)?),
+            Self::Nelson => nearest_monday(ymd(year, 2, 1)?),
+            Self::Taranaki => nth_weekday(year, 3, Weekday::Mon, 2)?,
+            Self::Otago => nearest_monday(ymd(year, 3, 23)?),
+            Self::Southland => easter_sunday(year)? + Duration::days(2),
+            Self::SouthCanterbury => nth_weekday(year, 9, Weekday::Mon, 4)?,
+            Self::HawkesBay => labour_day(year)? - Duration::days(3),
+            Self::Marlborough => labour_day(year)? + Duration::days(7),
+            Self::Canterbury => {
+                // show day: the second Friday after the first Tuesday in November
+                nth_weekday(year, 11, Weekday::Tue, 1)? + Duration::days(10)
+            }
+            Self::ChathamIslands => nearest_monday(ymd(year, 11, 30)?),
+            Self::Westland => nearest_monday(ymd(year, 12, 1)?),
+        };
+        let name = match self {
+            Self::Auckland => "Auckland Anniversary",
+            Self::Wellington => "Wellington Anniversary",
+            Self::Nelson => "Nelson Anniversary",
+            Self::Taranaki => "Taranaki Anniversary",
+            Self::Otago => "Otago Anniversary",
+            Self::Southland => "Southland Anniversary",
+            Self::SouthCanterbury => "South Canterbury Anniversary",
+            Self::HawkesBay => "Hawke's Bay Anniversary",
+            Self::Marlborough => "Marlborough Anniversary",
+            Self::Canterbury => "Canterbury Show Day",
+            Self::ChathamIslands => "Chatham Islands Anniversary",
+            Self::Westland => "Westland Anniversary",
+        };
+        Some(Holiday::new(date, name))
+    }
+}
+
+fn ymd(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n)
+}
+
+/// Monday closest to the date; Friday through Sunday roll forward
+fn nearest_monday(date: NaiveDate) -> NaiveDate {
+    match date.weekday().num_days_from_monday() {
+        offset @ 0..=3 => date - Duration::days(offset.into()),
+        offset => date + Duration::days((7 - offset).into()),
+    }
+}
+
+/// weekend holidays move to the following Monday
+fn mondayise(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date + Duration::days(2),
+        Weekday::Sun => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+/// back-to-back holidays (Christmas and Boxing Day, 1 and 2 January) keep two weekdays off
+fn mondayise_pair(first: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let second = first + Duration::days(1);
+    match first.weekday() {
+        Weekday::Fri => (first, first + Duration::days(3)),
+        Weekday::Sat => (first + Duration::days(2), first + Duration::days(3)),
+        Weekday::Sun => (first + Duration::days(2), second),
+        _ => (first, second),
+    }
+}
+
+/// Easter Sunday by the anonymous Gregorian algorithm
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    ymd(year, month as u32, day as u32)
+}
+
+fn labour_day(year: i32) -> Option<NaiveDate> {
+    nth_weekday(year, 10, Weekday::Mon, 4)
+}
+
+/// observed public holidays for a year, including the region's anniversary day
+pub fn nz_holidays(year: i32, region: Option<NzRegion>) -> Vec<Holiday> {
+    let mut holidays = Vec::new();
+
+    if let Some(new_year) = ymd(year, 1, 1) {
+        let (first, second) = mondayise_pair(new_year);
+        holidays.push(Holiday::new(first, "New Year's Day"));
+        holidays.push(Holiday::new(second, "Day after New Year's"));
+    }
+    if let Some(waitangi) = ymd(year, 2, 6) {
+        holidays.push(Holiday::new(mondayise(waitangi), "Waitangi Day"));
+    }
+    if let Some(easter) = easter_sunday(year) {
+        holidays.push(Holiday::new(easter - Duration::days(2), "Good Friday"));
+        holidays.push(Holiday::new(easter + Duration::days(1), "Easter Monday"));
+    }
+    if let Some(anzac) = ymd(year, 4, 25) {
+        holidays.push(Holiday::new(mondayise(anzac), "ANZAC Day"));
+    }
+    if let Some(birthday) = nth_weekday(year, 6, Weekday::Mon, 1) {
+        holidays.push(Holiday::new(birthday, "King's Birthday"));
+    }
+    if let Some(&(_, month, day)) = MATARIKI.iter().find(|(y, ..)| *y == year)
+        && let Some(matariki) = ymd(year, month, day)
+    {
+        holidays.push(Holiday::new(matariki, "Matariki"));
+    }
+    if let Some(labour) = labour_day(year) {
+        holidays.push(Holiday::new(labour, "Labour Day"));
+    }
+    if let Some(christmas) = ymd(year, 12, 25) {
+        let (first, second) = mondayise_pair(christmas);
+        holidays.push(Holiday::new(first, "Christmas Day"));
+        holidays.push(Holiday::new(second, "Boxing Day"));
+    }
+    if let Some(anniversary) = region.and_then(|region| region.anniversary(year)) {
+        holidays.push(anniversary);
+    }
+
+    holidays.sort_by_key(|holiday| holiday.date);
+    holidays
+}
+
+/// the first holiday from today through the lookahead window
+pub fn upcoming_nz_holiday(today: NaiveDate, region: Option<NzRegion>) -> Option<Holiday> {
+    let last = today + Duration::days(HOLIDAY_LOOKAHEAD_DAYS);
+    let mut holidays = nz_holidays(today.year(), region);
+    if last.year() != today.year() {
+        holidays.extend(nz_holidays(last.year(), region));
+    }
+    holidays
+        .into_iter()
+        .find(|holiday| holiday.date >= today && holiday.date <= last)
+}
+
+/// one entry from Nager.Date's PublicHolidays endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NagerHoliday {
+    date: String,
+    name: String,
+    #[serde(default = "default_true")]
+    global: bool,
+    #[serde(default)]
+    types: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// keep nationwide public holidays; regional and observance-only days would over-warn
+fn parse_nager_holidays(body: &str) -> Result<Vec<Holiday>> {
+    let entries: Vec<NagerHoliday> =
+        serde_json::from_str(body).context("failed to parse holiday response")?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            entry.global && (entry.types.is_empty() || entry.types.iter().any(|t| t == "Public"))
+        })
+        .filter_map(|entry| {
+            let date = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").ok()?;
+            Some(Holiday {
+                date,
+                name: entry.name,
+            })
+        })
+        .collect())
+}
+
+/// public holidays by country, fetched once per country and year
+pub struct HolidayService {
+    client: reqwest::Client,
+    cache: HashMap<(String, i32), Vec<Holiday>>,
+    attempted: HashSet<(String, i32)>,
+    metrics: ServiceMetrics,
+}
+
+impl HolidayService {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            cache: HashMap::new(),
+            attempted: HashSet::new(),
+            metrics: ServiceMetrics::default(),
+        }
+    }
+
+    /// whether a country/year still needs a lookup; NZ uses the bundled rules
+    pub fn needs_fetch(&self, alpha2: &str, year: i32) -> bool {
+        alpha2 != "NZ" && !self.attempted.contains(&(alpha2.to_string(), year))
+    }
+
+    /// the request for a country's holidays in a year, to run off the UI loop; hand its result
+    /// to `finish_fetch`. Failures are not retried this session
+    pub fn start_fetch(
+        &mut self,
+        alpha2: &str,
+        year: i32,
+    ) -> impl Future<Output = Result<Vec<Holiday>>> + Send + 'static {
+        self.attempted.insert((alpha2.to_string(), year));
+        self.metrics.record_api_call();
+
+        let url = format!(
+            "https://date.nager.at/api/v3/PublicHolidays/{}/{}",
+            year, alpha2
+        );
+        let client = self.client.clone();
+        async move {
+            let response = client
+                .get(&url)
+                .send()
+                .await
+                .context("failed to fetch holidays")?;
+            // unsupported countries answer 404 or 204; remember them as having no data
+            if matches!(
+                response.status(),
+                reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::NO_CONTENT
+            ) {
+                return Ok(Vec::new());
+            }
+            let body = response
+                .error_for_status()
+                .context("failed to fetch holidays")?
+                .text()
+                .await
+                .context("failed to read holiday response")?;
+            parse_nager_holidays(&body)
+        }
+    }
+
+    /// cache a country's fetched holidays for a year
+    pub fn finish_fetch(
+        &mut self,
+        alpha2: &str,
+        year: i32,
+        fetched: Result<Vec<Holiday>>,
+    ) -> Result<()> {
+        self.store(alpha2, year, fetched?);
+        Ok(())
+    }
+
+    pub fn store(&mut self, alpha2: &str, year: i32, holidays: Vec<Holiday>) {
+        let key = (alpha2.to_string(), year);
+        self.attempted.insert(key.clone());
+        self.cache.insert(key, holidays);
+    }
+
+    /// the holiday observed on a date in a country, if known
+    pub fn holiday_on(&self, alpha2: &str, date: NaiveDate) -> Option<Holiday> {
+        if alpha2 == "NZ" {
+            return nz_holidays(date.year(), None)
+                .into_iter()
+                .find(|holiday| holiday.date == date);
+        }
+        self.cache
+            .get(&(alpha2.to_string(), date.year()))?
+            .iter()
+            .find(|holiday| holiday.date == date)
+            .cloned()
+    }
+
+    pub fn metrics(&self) -> ServiceMetrics {
+        self.metrics
+    }
+}
+
+impl Default for HolidayService {
+    fn default() -> Self {
+        Self::new(crate::http::client())
+    }
+}
+
+/// "Today", "Tomorrow", or the short weekday name
+pub fn holiday_day_label(date: NaiveDate, today: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => "Today".to_string(),
+        1 => "Tomorrow".to_string(),
+        _ => date.format("%a").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn find(holidays: &[Holiday], name: &str) -> NaiveDate {
+        holidays
+            .iter()
+            .find(|holiday| holiday.name == name)
+            .unwrap_or_else(|| panic!("missing {}", name))
+            .date
+    }
+
+    #[test]
+    fn national_holidays_follow_mondayisation() {
+        let holidays = nz_holidays(2022, None);
+        // christmas 2022 fell on a sunday, boxing day on the monday
+        assert_eq!(find(&holidays, "Christmas Day"), date(2022, 12, 27));
+        assert_eq!(find(&holidays, "Boxing Day"), date(2022, 12, 26));
+        // new year's day 2022 was a saturday
+        assert_eq!(find(&holidays, "New Year's Day"), date(2022, 1, 3));
+        assert_eq!(find(&holidays, "Day after New Year's"), date(2022, 1, 4));
+        assert_eq!(find(&holidays, "Good Friday"), date(2022, 4, 15));
+        assert_eq!(find(&holidays, "Matariki"), date(2022, 6, 24));
+
+        let holidays = nz_holidays(2025, None);
+        assert_eq!(find(&holidays, "Labour Day"), date(2025, 10, 27));
+        assert_eq!(find(&holidays, "King's Birthday"), date(2025, 6, 2));
+        assert_eq!(find(&holidays, "Easter Monday"), date(2025, 4, 21));
+    }
+
+    #[test]
+    fn regional_anniversaries_use_their_own_rules() {
+        let anniversary = |region: NzRegion| region.anniversary(2025).unwrap().date;
+        assert_eq!(anniversary(NzRegion::Wellington), date(2025, 1, 20));
+        assert_eq!(anniversary(NzRegion::Auckland), date(2025, 1, 27));
+        assert_eq!(anniversary(NzRegion::Canterbury), date(2025, 11, 14));
+        assert_eq!(anniversary(NzRegion::HawkesBay), date(2025, 10, 24));
+        assert_eq!(NzRegion::for_city("Dunedin"), Some(NzRegion::Otago));
+        assert!(nz_holidays(2025, None).len() < nz_holidays(2025, Some(NzRegion::Otago)).len());
+    }
+
+    #[test]
+    fn finds_upcoming_holiday_within_a_week() {
+        let today = date(2025, 10, 23);
+        let holiday = upcoming_nz_holiday(today, Some(NzRegion::Wellington)).unwrap();
+        assert_eq!(holiday.name, "Labour Day");
+        assert_eq!(holiday_day_label(holiday.date, today), "Mon");
+
+        // crosses into the next year
+        let holiday = upcoming_nz_holiday(date(2025, 12, 30), None).unwrap();
+        assert_eq!(holiday.name, "New Year's Day");
+        assert!(upcoming_nz_holiday(date(2025, 8, 1), None).is_none());
+    }
+
+    #[test]
+    fn parses_nationwide_nager_holidays_only() {
+        let body = r#"[
+            {"date": "2025-07-04", "localName": "Independence Day", "name": "Independence Day",
+             "countryCode": "US", "fixed": false, "global": true, "counties": null,
+             "launchYear": null, "types": ["Public"]},
+            {"date": "2025-04-21", "localName": "Patriots' Day", "name": "Patriots' Day",
+             "countryCode": "US", "global": false, "counties": ["US-MA"], "types": ["Public"]}
+        ]"#;
+        let holidays = parse_nager_holidays(body).unwrap();
+        assert_eq!(holidays.len(), 1);
+        assert_eq!(holidays[0].name, "Independence Day");
+
+        let mut service = HolidayService::new(reqwest::Client::new());
+        service.store("US", 2025, holidays);
+        assert!(!service.needs_fetch("US", 2025));
+        assert!(service.needs_fetch("US", 2026));
+        assert!(!service.needs_fetch("NZ", 2026));
+        assert!(service.holiday_on("US", date(2025, 7, 4)).is_some());
+        assert_eq!(
+            service.holiday_on("NZ", date(2025, 10, 27)).map(|h| h.name),
+            Some("Labour Day".to_string())
+        );
+    }
+}
@@ -0,0 +1,19 @@
+//! the HTTP client shared by every network call: the data services, the clock check and
+//! webhook notifications; built once per process, so they all share one connection pool
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// the shared client; cloning it is cheap and keeps the same pool
+pub fn client() -> reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            reqwest::Client::builder()
+                .user_agent(format!("nzi-cli/{}", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default()
+        })
+        .clone()
+}
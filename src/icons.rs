@@ -0,0 +1,200 @@
+//! icon sets for weather, day and night, and the clock and call glyphs
+//! emoji by default, plain ascii for fonts with neither, nerd font glyphs for patched fonts
+
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::weather::WeatherIcon;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    #[default]
+    Emoji,
+    Ascii,
+    Nerd,
+}
+
+/// the glyphs drawn beside text, apart from the weather conditions themselves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyph {
+    Day,
+    Night,
+    StrongWind,
+    Breeze,
+    Calm,
+    Humidity,
+    Alarm,
+    Alert,
+    Countdown,
+    Timestamp,
+    Call,
+    Holiday,
+}
+
+impl IconSet {
+    pub fn glyph(self, glyph: Glyph) -> &'static str {
+        match self {
+            Self::Emoji => match glyph {
+                Glyph::Day => "☀",
+                Glyph::Night => "☾",
+                Glyph::StrongWind => "💨",
+                Glyph::Breeze => "🌬️",
+                Glyph::Calm => "🍃",
+                Glyph::Humidity => "💧",
+                Glyph::Alarm => "⏰",
+                Glyph::Alert => "🔔",
+                Glyph::Countdown => "⏳",
+                Glyph::Timestamp => "⏱",
+                Glyph::Call => "📞",
+                Glyph::Holiday => "🎉",
+            },
+            Self::Ascii => match glyph {
+                Glyph::Day => "*",
+                Glyph::Night => ")",
+                Glyph::StrongWind => ">>",
+                Glyph::Breeze => ">",
+                Glyph::Calm => "-",
+                Glyph::Humidity => "RH",
+                Glyph::Alarm => "(!)",
+                Glyph::Alert => "(!)",
+                Glyph::Countdown => "T-",
+                Glyph::Timestamp => "@",
+                Glyph::Call => "tel",
+                Glyph::Holiday => "*",
+            },
+            Self::Nerd => match glyph {
+                Glyph::Day => "\u{e30d}",
+                Glyph::Night => "\u{e32b}",
+                Glyph::StrongWind => "\u{e34b}",
+                Glyph::Breeze => "\u{e31e}",
+                Glyph::Calm => "\u{f06c}",
+                Glyph::Humidity => "\u{e373}",
+                Glyph::Alarm => "\u{f0020}",
+                Glyph::Alert => "\u{f0f3}",
+                Glyph::Countdown => "\u{f252}",
+                Glyph::Timestamp => "\u{f017}",
+                Glyph::Call => "\u{f095}",
+                Glyph::Holiday => "\u{f06b}",
+            },
+        }
+    }
+
+    /// the icon for a weather condition
+    pub fn weather(self, icon: WeatherIcon, is_day: bool) -> &'static str {
+        match self {
+            Self::Emoji => match (icon, is_day) {
+                (WeatherIcon::Sunny, true) => "☀",
+                (WeatherIcon::Sunny, false) => "☾",
+                (WeatherIcon::PartlyCloudy, true) => "⛅",
+                (WeatherIcon::PartlyCloudy, false) => "☁️",
+                (WeatherIcon::Cloudy, _) => "☁️",
+                (WeatherIcon::Fog, _) => "🌫",
+                (WeatherIcon::Drizzle, _) => "🌦",
+                (WeatherIcon::Rain, _) => "🌧",
+                (WeatherIcon::HeavyRain, _) => "🌧",
+                (WeatherIcon::Snow, _) => "❄",
+                (WeatherIcon::Thunderstorm, _) => "⛈",
+                (WeatherIcon::Unknown, _) => "?",
+            },
+            Self::Ascii => match (icon, is_day) {
+                (WeatherIcon::Sunny, true) => "*",
+                (WeatherIcon::Sunny, false) => ")",
+                (WeatherIcon::PartlyCloudy, true) => "*~",
+                (WeatherIcon::PartlyCloudy, false) => ")~",
+                (WeatherIcon::Cloudy, _) => "~~",
+                (WeatherIcon::Fog, _) => "==",
+                (WeatherIcon::Drizzle, _) => ".,",
+                (WeatherIcon::Rain, _) => "/,",
+                (WeatherIcon::HeavyRain, _) => "//",
+                (WeatherIcon::Snow, _) => "**",
+                (WeatherIcon::Thunderstorm, _) => "!/",
+                (WeatherIcon::Unknown, _) => "?",
+            },
+            Self::Nerd => match (icon, is_day) {
+                (WeatherIcon::Sunny, true) => "\u{e30d}",
+                (WeatherIcon::Sunny, false) => "\u{e32b}",
+                (WeatherIcon::PartlyCloudy, true) => "\u{e302}",
+                (WeatherIcon::PartlyCloudy, false) => "\u{e37e}",
+                (WeatherIcon::Cloudy, _) => "\u{e312}",
+                (WeatherIcon::Fog, _) => "\u{e313}",
+                (WeatherIcon::Drizzle, _) => "\u{e31b}",
+                (WeatherIcon::Rain, _) => "\u{e318}",
+                (WeatherIcon::HeavyRain, _) => "\u{e319}",
+                (WeatherIcon::Snow, _) => "\u{e31a}",
+                (WeatherIcon::Thunderstorm, _) => "\u{e31d}",
+                (WeatherIcon::Unknown, _) => "\u{e374}",
+            },
+        }
+    }
+
+    /// the larger, colour-presentation emoji beside a condition's description; the same
+    /// as `weather` in the other sets
+    pub fn weather_label(self, icon: WeatherIcon, is_day: bool) -> &'static str {
+        if self != Self::Emoji {
+            return self.weather(icon, is_day);
+        }
+        match (icon, is_day) {
+            (WeatherIcon::Sunny, true) => "☀️",
+            (WeatherIcon::Sunny, false) => "🌙",
+            (WeatherIcon::PartlyCloudy, _) => "⛅",
+            (WeatherIcon::Cloudy, _) => "☁️",
+            (WeatherIcon::Rain | WeatherIcon::HeavyRain, _) => "🌧️",
+            (WeatherIcon::Drizzle, _) => "🌦️",
+            (WeatherIcon::Snow, _) => "❄️",
+            (WeatherIcon::Thunderstorm, _) => "⛈️",
+            (WeatherIcon::Fog, _) => "🌫️",
+            (WeatherIcon::Unknown, _) => "❓",
+        }
+    }
+}
+
+/// the icon set in use, set from the config at startup and on reload
+static ICON_SET: RwLock<IconSet> = RwLock::new(IconSet::Emoji);
+
+pub fn set_icon_set(set: IconSet) {
+    if let Ok(mut current) = ICON_SET.write() {
+        *current = set;
+    }
+}
+
+pub fn icon_set() -> IconSet {
+    ICON_SET.read().map_or(IconSet::Emoji, |set| *set)
+}
+
+/// `glyph` in the icon set in use
+pub fn glyph(glyph: Glyph) -> &'static str {
+    icon_set().glyph(glyph)
+}
+
+/// ☀ or ☾, in the icon set in use
+pub fn day_night(is_day: bool) -> &'static str {
+    glyph(if is_day { Glyph::Day } else { Glyph::Night })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_set_has_its_own_glyphs() {
+        assert_eq!(IconSet::Emoji.weather(WeatherIcon::Rain, true), "🌧");
+        assert_eq!(IconSet::Ascii.weather(WeatherIcon::Rain, true), "/,");
+        assert_eq!(IconSet::Nerd.weather(WeatherIcon::Rain, true), "\u{e318}");
+        assert_eq!(
+            IconSet::Emoji.weather_label(WeatherIcon::Sunny, false),
+            "🌙"
+        );
+        assert_eq!(IconSet::Ascii.weather_label(WeatherIcon::Sunny, false), ")");
+        assert!(IconSet::Ascii.glyph(Glyph::Alarm).is_ascii());
+
+        let config: crate::config::DisplayConfig = toml::from_str(
+            &toml::to_string(&crate::config::DisplayConfig::default())
+                .unwrap()
+                .replace("icons = \"emoji\"", "icons = \"nerd\""),
+        )
+        .unwrap();
+        assert_eq!(config.icons, IconSet::Nerd);
+    }
+}
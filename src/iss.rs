@@ -0,0 +1,368 @@
+//! live ISS position from open-notify, drawn drifting across the world map
+//! each fetch adds to a short trail so the track shows between refreshes; the station's
+//! orbital elements from CelesTrak give the next pass that can be seen from the weather city
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::orbit::{Orbit, earth_fixed, elevation_of, parse_tle};
+use crate::stats::ServiceMetrics;
+use crate::sun::sun_direction;
+
+/// how often the position is re-fetched; the station covers about 115 km in that time
+const REFRESH_EVERY: Duration = Duration::from_secs(15);
+
+/// the longest wait after failures in a row, each of which doubles the last
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// positions kept for the trail, about ten minutes of orbit
+pub const TRAIL_LENGTH: usize = 40;
+
+/// the elements are re-fetched twice a day; CelesTrak asks for no more than that
+const ORBIT_REFRESH_EVERY: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// the wait before trying again when no elements have arrived yet
+const ORBIT_RETRY_EVERY: Duration = Duration::from_secs(30 * 60);
+
+/// how far ahead passes are looked for, and the step the search takes; seen passes come in
+/// runs of a few days with a week or more of daylight passes between
+const PASS_SEARCH: chrono::Duration = chrono::Duration::days(10);
+const PASS_STEP: chrono::Duration = chrono::Duration::seconds(20);
+
+/// the station is only picked out once it clears the rooftops
+const MIN_PASS_ELEVATION: f64 = 10.0;
+
+/// the sky is dark enough to see it once the sun is this far below the horizon
+const MAX_SUN_ELEVATION: f64 = -6.0;
+
+/// the ISS's current element set
+const ISS_TLE_URL: &str = "https://celestrak.org/NORAD/elements/gp.php?CATNR=25544&FORMAT=TLE";
+
+/// open-notify only serves plain HTTP; the position is public and range-checked in
+/// `parse_iss_now`, so a tampered reply can at worst misplace the marker
+const ISS_NOW_URL: &str = "http://api.open-notify.org/iss-now.json";
+
+#[derive(Deserialize)]
+struct IssNow {
+    message: String,
+    iss_position: IssPosition,
+}
+
+/// open-notify sends the coordinates as strings
+#[derive(Deserialize)]
+struct IssPosition {
+    latitude: String,
+    longitude: String,
+}
+
+/// parse an iss-now response into (lat, lon)
+pub fn parse_iss_now(body: &str) -> Result<(f64, f64)> {
+    let now: IssNow = serde_json::from_str(body).context("failed to parse ISS position")?;
+    if now.message != "success" {
+        bail!("ISS position request returned {}", now.message);
+    }
+    let lat: f64 = now
+        .iss_position
+        .latitude
+        .parse()
+        .context("invalid ISS latitude")?;
+    let lon: f64 = now
+        .iss_position
+        .longitude
+        .parse()
+        .context("invalid ISS longitude")?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        bail!("ISS position {}, {} is off the globe", lat, lon);
+    }
+    Ok((lat, lon))
+}
+
+/// a stretch of time the station is up, sunlit, against a dark sky
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pass {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// the highest it gets during the visible stretch, in degrees
+    pub peak_elevation: f64,
+}
+
+/// whether the station can be seen from a (lat, lon) place at a moment
+fn is_visible(orbit: &Orbit, place: (f64, f64), at: DateTime<Utc>) -> bool {
+    if orbit.elevation_from(place, at) < MIN_PASS_ELEVATION {
+        return false;
+    }
+    let sun = sun_direction(at);
+    elevation_of(place, earth_fixed(sun, at)) <= MAX_SUN_ELEVATION && orbit.is_sunlit(at, sun)
+}
+
+/// the first visible pass over a (lat, lon) place from `from` on, including one under way
+pub fn next_visible_pass(orbit: &Orbit, place: (f64, f64), from: DateTime<Utc>) -> Option<Pass> {
+    let mut at = from;
+    while !is_visible(orbit, place, at) {
+        at += PASS_STEP;
+        if at - from > PASS_SEARCH {
+            return None;
+        }
+    }
+    let start = at;
+    let mut peak_elevation = orbit.elevation_from(place, at);
+    while is_visible(orbit, place, at + PASS_STEP) {
+        at += PASS_STEP;
+        peak_elevation = peak_elevation.max(orbit.elevation_from(place, at));
+    }
+    Some(Pass {
+        start,
+        end: at,
+        peak_elevation,
+    })
+}
+
+/// the pass last worked out, the place it was for, and when it needs working out again
+struct PassPlan {
+    place: (f64, f64),
+    valid_until: DateTime<Utc>,
+    pass: Option<Pass>,
+}
+
+/// the station's recent positions as (lat, lon), oldest first, and its orbit
+pub struct IssService {
+    client: reqwest::Client,
+    trail: Vec<(f64, f64)>,
+    last_attempt: Option<Instant>,
+    failures: u32,
+    orbit: Option<Orbit>,
+    last_orbit_attempt: Option<Instant>,
+    pass_plan: Option<PassPlan>,
+    metrics: ServiceMetrics,
+}
+
+impl IssService {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            trail: Vec::new(),
+            last_attempt: None,
+            failures: 0,
+            orbit: None,
+            last_orbit_attempt: None,
+            pass_plan: None,
+            metrics: ServiceMetrics::default(),
+        }
+    }
+
+    pub fn needs_fetch(&self) -> bool {
+        self.last_attempt
+            .is_none_or(|attempt| attempt.elapsed() >= self.retry_after())
+    }
+
+    /// the wait before the next fetch: doubled for each failure in a row, up to `MAX_BACKOFF`
+    fn retry_after(&self) -> Duration {
+        REFRESH_EVERY
+            .saturating_mul(1 << self.failures.min(8))
+            .min(MAX_BACKOFF)
+    }
+
+    /// the request for the current position, to run off the UI loop; hand its result to
+    /// `finish_fetch`
+    pub fn start_fetch(&mut self) -> impl Future<Output = Result<(f64, f64)>> + Send + 'static {
+        self.last_attempt = Some(Instant::now());
+        self.metrics.record_api_call();
+        let client = self.client.clone();
+        async move {
+            let body = client
+                .get(ISS_NOW_URL)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .context("failed to fetch ISS position")?
+                .text()
+                .await
+                .context("failed to read ISS response")?;
+            parse_iss_now(&body)
+        }
+    }
+
+    /// add a fetched position to the trail; on failure the trail is left as it was and
+    /// the next try backs off
+    pub fn finish_fetch(&mut self, fetched: Result<(f64, f64)>) -> Result<()> {
+        match fetched {
+            Ok(position) => {
+                self.failures = 0;
+                self.push(position);
+                Ok(())
+            }
+            Err(err) => {
+                self.failures = self.failures.saturating_add(1);
+                Err(err)
+            }
+        }
+    }
+
+    pub fn needs_orbit_fetch(&self) -> bool {
+        let wait = if self.orbit.is_some() {
+            ORBIT_REFRESH_EVERY
+        } else {
+            ORBIT_RETRY_EVERY
+        };
+        self.last_orbit_attempt
+            .is_none_or(|attempt| attempt.elapsed() >= wait)
+    }
+
+    /// the request for the station's orbital elements, to run off the UI loop; hand its
+    /// result to `finish_orbit_fetch`
+    pub fn start_orbit_fetch(&mut self) -> impl Future<Output = Result<Orbit>> + Send + 'static {
+        self.last_orbit_attempt = Some(Instant::now());
+        self.metrics.record_api_call();
+        let client = self.client.clone();
+        async move {
+            let body = client
+                .get(ISS_TLE_URL)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .context("failed to fetch ISS orbit")?
+                .text()
+                .await
+                .context("failed to read ISS orbit")?;
+            parse_tle(&body)
+        }
+    }
+
+    /// take fresh elements, dropping the pass worked out from the old ones; on failure the
+    /// old elements are kept
+    pub fn finish_orbit_fetch(&mut self, fetched: Result<Orbit>) -> Result<()> {
+        self.orbit = Some(fetched?);
+        self.pass_plan = None;
+        Ok(())
+    }
+
+    /// work out the next visible pass over a (lat, lon) place, unless the last one still holds
+    pub fn plan_pass(&mut self, place: (f64, f64), now: DateTime<Utc>) {
+        let Some(orbit) = &self.orbit else {
+            return;
+        };
+        if self
+            .pass_plan
+            .as_ref()
+            .is_some_and(|plan| plan.place == place && now < plan.valid_until)
+        {
+            return;
+        }
+        let pass = next_visible_pass(orbit, place, now);
+        // with no pass in sight, look again in an hour rather than every frame
+        let valid_until = pass.map_or(now + chrono::Duration::hours(1), |pass| pass.end);
+        self.pass_plan = Some(PassPlan {
+            place,
+            valid_until,
+            pass,
+        });
+    }
+
+    /// the next visible pass over the place last planned for
+    pub fn next_pass(&self) -> Option<&Pass> {
+        self.pass_plan.as_ref()?.pass.as_ref()
+    }
+
+    fn push(&mut self, position: (f64, f64)) {
+        self.trail.push(position);
+        if self.trail.len() > TRAIL_LENGTH {
+            self.trail.remove(0);
+        }
+    }
+
+    pub fn trail(&self) -> &[(f64, f64)] {
+        &self.trail
+    }
+
+    pub fn metrics(&self) -> ServiceMetrics {
+        self.metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_positions_and_keeps_a_short_trail() {
+        let body = r#"{"message": "success", "timestamp": 1749207600,
+            "iss_position": {"latitude": "-41.2010", "longitude": "174.9921"}}"#;
+        assert_eq!(parse_iss_now(body).unwrap(), (-41.201, 174.9921));
+
+        let failed =
+            r#"{"message": "failure", "iss_position": {"latitude": "0", "longitude": "0"}}"#;
+        assert!(parse_iss_now(failed).is_err());
+        let off_globe =
+            r#"{"message": "success", "iss_position": {"latitude": "95", "longitude": "0"}}"#;
+        assert!(parse_iss_now(off_globe).is_err());
+
+        let mut service = IssService::new(reqwest::Client::new());
+        assert!(service.trail().is_empty());
+        for step in 0..=TRAIL_LENGTH {
+            service.push((0.0, step as f64));
+        }
+        assert_eq!(service.trail().len(), TRAIL_LENGTH);
+        assert_eq!(service.trail()[0], (0.0, 1.0));
+        assert_eq!(service.trail().last(), Some(&(0.0, TRAIL_LENGTH as f64)));
+    }
+
+    #[test]
+    fn failures_back_off_until_a_position_arrives() {
+        let mut service = IssService::new(reqwest::Client::new());
+        assert_eq!(service.retry_after(), REFRESH_EVERY);
+        for _ in 0..3 {
+            assert!(
+                service
+                    .finish_fetch(Err(anyhow::anyhow!("offline")))
+                    .is_err()
+            );
+        }
+        assert_eq!(service.retry_after(), REFRESH_EVERY * 8);
+        for _ in 0..10 {
+            let _ = service.finish_fetch(Err(anyhow::anyhow!("offline")));
+        }
+        assert_eq!(service.retry_after(), MAX_BACKOFF);
+
+        service.finish_fetch(Ok((-41.2, 174.8))).unwrap();
+        assert_eq!(service.retry_after(), REFRESH_EVERY);
+        assert_eq!(service.trail(), &[(-41.2, 174.8)]);
+    }
+
+    #[test]
+    fn finds_the_next_pass_seen_against_a_dark_sky() {
+        let orbit = parse_tle(
+            "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537",
+        )
+        .unwrap();
+        let from = DateTime::parse_from_rfc3339("2008-09-20T12:00:00Z")
+            .unwrap()
+            .to_utc();
+        let wellington = (-41.2865, 174.7762);
+        let pass = next_visible_pass(&orbit, wellington, from).expect("a pass within ten days");
+        assert!(pass.start > from && pass.end >= pass.start);
+        assert!(pass.peak_elevation >= MIN_PASS_ELEVATION);
+        assert!(is_visible(&orbit, wellington, pass.start));
+        assert!(!is_visible(&orbit, wellington, pass.start - PASS_STEP));
+        let sun = sun_direction(pass.start);
+        assert!(elevation_of(wellington, earth_fixed(sun, pass.start)) <= MAX_SUN_ELEVATION);
+
+        let mut service = IssService::new(reqwest::Client::new());
+        service.plan_pass(wellington, from);
+        assert_eq!(service.next_pass(), None);
+        service.finish_orbit_fetch(Ok(orbit)).unwrap();
+        service.plan_pass(wellington, from);
+        assert_eq!(service.next_pass(), Some(&pass));
+        // once it has gone by, the one after is found
+        service.plan_pass(wellington, pass.end + PASS_STEP);
+        assert!(
+            service
+                .next_pass()
+                .is_some_and(|next| next.start > pass.end)
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! remappable keys for the main screen's everyday actions, read from `[keys]`
+//! a remapped key is translated back to the action's default key before dispatch,
+//! and the default key stops doing anything globally once its action has moved; panels
+//! that give a default key their own meaning keep it
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+use crossterm::event::KeyCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Quit,
+    Swap,
+    CycleCity,
+    ToggleHelp,
+    ToggleClock,
+    Refresh,
+    ReloadConfig,
+    EditConfig,
+    Edit,
+    Command,
+    FocusLeft,
+    FocusDown,
+    FocusUp,
+    FocusRight,
+    Zoom,
+}
+
+impl KeyAction {
+    pub const ALL: [Self; 15] = [
+        Self::Quit,
+        Self::Swap,
+        Self::CycleCity,
+        Self::ToggleHelp,
+        Self::ToggleClock,
+        Self::Refresh,
+        Self::ReloadConfig,
+        Self::EditConfig,
+        Self::Edit,
+        Self::Command,
+        Self::FocusLeft,
+        Self::FocusDown,
+        Self::FocusUp,
+        Self::FocusRight,
+        Self::Zoom,
+    ];
+
+    /// the action's name in `[keys]`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Swap => "swap",
+            Self::CycleCity => "cycle_city",
+            Self::ToggleHelp => "toggle_help",
+            Self::ToggleClock => "toggle_clock",
+            Self::Refresh => "refresh",
+            Self::ReloadConfig => "reload_config",
+            Self::EditConfig => "edit_config",
+            Self::Edit => "edit",
+            Self::Command => "command",
+            Self::FocusLeft => "focus_left",
+            Self::FocusDown => "focus_down",
+            Self::FocusUp => "focus_up",
+            Self::FocusRight => "focus_right",
+            Self::Zoom => "zoom",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.name() == name.trim())
+    }
+
+    /// the key the main screen handles this action on
+    pub fn default_key(self) -> KeyCode {
+        KeyCode::Char(match self {
+            Self::Quit => 'q',
+            Self::Swap => 's',
+            Self::CycleCity => ' ',
+            Self::ToggleHelp => '?',
+            Self::ToggleClock => 't',
+            Self::Refresh => 'r',
+            Self::ReloadConfig => 'R',
+            Self::EditConfig => 'E',
+            Self::Edit => 'e',
+            Self::Command => '/',
+            Self::FocusLeft => 'h',
+            Self::FocusDown => 'j',
+            Self::FocusUp => 'k',
+            Self::FocusRight => 'l',
+            Self::Zoom => 'z',
+        })
+    }
+}
+
+/// keys panels, chords and tabs already use, and what for; an action can only be moved onto
+/// one of these if it is that action's own default
+const SCREEN_KEYS: &[(KeyCode, &str)] = &[
+    (KeyCode::Char('x'), "the map crosshair"),
+    (KeyCode::Char('h'), "the map crosshair"),
+    (KeyCode::Char('j'), "the map crosshair"),
+    (KeyCode::Char('k'), "the map crosshair"),
+    (KeyCode::Char('l'), "the map crosshair"),
+    (KeyCode::Char('m'), "measuring on the map"),
+    (
+        KeyCode::Char('f'),
+        "the ferry layer and the converter's from city",
+    ),
+    (
+        KeyCode::Char('b'),
+        "the map marker style and the converter's broadcast",
+    ),
+    (KeyCode::Char('a'), "the airport layer"),
+    (KeyCode::Char('i'), "the map legend"),
+    (KeyCode::Char('H'), "the heat layer"),
+    (KeyCode::Char('r'), "the highway layer"),
+    (KeyCode::Char('\''), "map bookmarks"),
+    (KeyCode::Char('v'), "switching maps"),
+    (KeyCode::Char('+'), "map zoom"),
+    (KeyCode::Char('='), "map zoom"),
+    (KeyCode::Char('-'), "map zoom"),
+    (KeyCode::Char('n'), "setting the converter to now"),
+    (KeyCode::Char('S'), "scrubbing the converter"),
+    (KeyCode::Char('g'), "g chords"),
+    (KeyCode::Char('c'), "c chords and cycling the currency pair"),
+    (KeyCode::Char('['), "switching tabs"),
+    (KeyCode::Char(']'), "switching tabs"),
+    (KeyCode::Enter, "editing and the crosshair readout"),
+    (KeyCode::Tab, "cycling focus"),
+    (KeyCode::Backspace, "the converter's input"),
+];
+
+/// a key name from `[keys]`: a single character, or space, enter, tab, backspace,
+/// f1 to f12; "none" leaves the action without a key
+pub fn parse_key(name: &str) -> Result<Option<KeyCode>> {
+    let lower = name.trim().to_lowercase();
+    let key = match lower.as_str() {
+        "none" => return Ok(None),
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_digit() => {
+                    bail!("key {:?} is kept for counts and typed numbers", name)
+                }
+                (Some(c), None) => KeyCode::Char(c),
+                _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => bail!(
+                        "unknown key {:?} (use one character, space, enter, tab, backspace, f1-f12 or none)",
+                        name
+                    ),
+                },
+            }
+        }
+    };
+    Ok(Some(key))
+}
+
+/// how a key is written in `[keys]` and in messages
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::F(n) => format!("f{}", n),
+        other => format!("{:?}", other).to_lowercase(),
+    }
+}
+
+/// each action's key after `[keys]` has been applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(KeyAction, Option<KeyCode>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: KeyAction::ALL
+                .into_iter()
+                .map(|action| (action, Some(action.default_key())))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// apply `[keys]` over the defaults; unknown actions, bad key names, keys a panel
+    /// already uses and two actions sharing a key are errors
+    pub fn from_config(keys: &BTreeMap<String, String>) -> Result<Self> {
+        let mut keymap = Self::default();
+        for (name, key) in keys {
+            let Some(action) = KeyAction::from_name(name) else {
+                bail!("keys.{} is not an action", name);
+            };
+            let key = parse_key(key)?;
+            if let Some(key) = key
+                && key != action.default_key()
+                && let Some((_, used_for)) = SCREEN_KEYS.iter().find(|(taken, _)| *taken == key)
+            {
+                bail!(
+                    "key {} is already used for {}, so {} can't move onto it",
+                    key_name(key),
+                    used_for,
+                    action.name()
+                );
+            }
+            for binding in &mut keymap.bindings {
+                if binding.0 == action {
+                    binding.1 = key;
+                }
+            }
+        }
+        for (index, (action, key)) in keymap.bindings.iter().enumerate() {
+            if let Some(key) = key
+                && let Some((other, _)) = keymap.bindings[index + 1..]
+                    .iter()
+                    .find(|(_, other_key)| *other_key == Some(*key))
+            {
+                bail!(
+                    "key {} is bound to both {} and {}",
+                    key_name(*key),
+                    action.name(),
+                    other.name()
+                );
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn key(&self, action: KeyAction) -> Option<KeyCode> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .and_then(|(_, key)| *key)
+    }
+
+    /// the key the main screen's global keys should act on: a remapped key becomes its
+    /// action's default, and a default whose action has moved away becomes a no-op.
+    /// Panel keys are matched before translation, so a panel's own use of a default survives
+    pub fn translate(&self, key: KeyCode) -> KeyCode {
+        if let Some((action, _)) = self.bindings.iter().find(|(_, bound)| *bound == Some(key)) {
+            return action.default_key();
+        }
+        if KeyAction::ALL
+            .iter()
+            .any(|action| action.default_key() == key)
+        {
+            return KeyCode::Null;
+        }
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(action, key)| (action.to_string(), key.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn remapped_keys_translate_and_free_their_defaults() {
+        let keymap = Keymap::from_config(&keys(&[
+            ("quit", "Q"),
+            ("focus_left", "d"),
+            ("toggle_help", "f1"),
+            ("cycle_city", "none"),
+        ]))
+        .unwrap();
+
+        assert_eq!(keymap.translate(KeyCode::Char('Q')), KeyCode::Char('q'));
+        assert_eq!(keymap.translate(KeyCode::Char('q')), KeyCode::Null);
+        assert_eq!(keymap.translate(KeyCode::Char('d')), KeyCode::Char('h'));
+        assert_eq!(keymap.translate(KeyCode::F(1)), KeyCode::Char('?'));
+        assert_eq!(keymap.translate(KeyCode::Char(' ')), KeyCode::Null);
+        // untouched actions and unrelated keys pass straight through
+        assert_eq!(keymap.translate(KeyCode::Char('s')), KeyCode::Char('s'));
+        assert_eq!(keymap.translate(KeyCode::Char('x')), KeyCode::Char('x'));
+        assert_eq!(keymap.key(KeyAction::CycleCity), None);
+        assert_eq!(
+            Keymap::default().translate(KeyCode::Char('q')),
+            KeyCode::Char('q')
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_actions_bad_keys_and_clashes() {
+        let error =
+            |pairs: &[(&str, &str)]| Keymap::from_config(&keys(pairs)).unwrap_err().to_string();
+        assert_eq!(error(&[("launch", "x")]), "keys.launch is not an action");
+        assert!(error(&[("quit", "ctrl-q")]).contains("unknown key"));
+        assert!(error(&[("quit", "5")]).contains("counts"));
+        assert_eq!(
+            error(&[("swap", "t")]),
+            "key t is bound to both swap and toggle_clock"
+        );
+        // moving the other action out of the way resolves the clash
+        assert!(Keymap::from_config(&keys(&[("swap", "t"), ("toggle_clock", "T")])).is_ok());
+    }
+
+    #[test]
+    fn rejects_keys_panels_chords_and_tabs_use() {
+        let error =
+            |pairs: &[(&str, &str)]| Keymap::from_config(&keys(pairs)).unwrap_err().to_string();
+        assert_eq!(
+            error(&[("quit", "x")]),
+            "key x is already used for the map crosshair, so quit can't move onto it"
+        );
+        for key in [
+            "m", "f", "b", "a", "i", "H", "'", "v", "n", "S", "g", "c", "[", "]", "tab",
+        ] {
+            assert!(
+                Keymap::from_config(&keys(&[("zoom", key)])).is_err(),
+                "{key}"
+            );
+        }
+        // a freed default that a panel also uses can't be taken by another action either
+        assert!(error(&[("refresh", "f5"), ("zoom", "r")]).contains("highway layer"));
+        assert!(error(&[("focus_left", "d"), ("zoom", "h")]).contains("crosshair"));
+        // an action bound to its own default is fine
+        assert!(Keymap::from_config(&keys(&[("refresh", "r"), ("focus_left", "h")])).is_ok());
+    }
+}
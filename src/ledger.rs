@@ -0,0 +1,161 @@
+//! currency conversion ledger
+//! appends each completed conversion to a CSV file for later reconciliation
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+pub const LEDGER_HEADER: &str = "timestamp,from_currency,from_amount,to_currency,to_amount,rate";
+
+/// one completed conversion
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    pub timestamp: DateTime<Utc>,
+    pub from_currency: String,
+    pub from_amount: f64,
+    pub to_currency: String,
+    pub to_amount: f64,
+    pub rate: f64,
+}
+
+impl LedgerEntry {
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},{},{:.2},{},{:.2},{}",
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.from_currency,
+            self.from_amount,
+            self.to_currency,
+            self.to_amount,
+            self.rate
+        )
+    }
+
+    pub fn from_csv(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.trim().split(',').collect();
+        let [
+            timestamp,
+            from_currency,
+            from_amount,
+            to_currency,
+            to_amount,
+            rate,
+        ] = fields[..]
+        else {
+            return None;
+        };
+        Some(Self {
+            timestamp: DateTime::parse_from_rfc3339(timestamp)
+                .ok()?
+                .with_timezone(&Utc),
+            from_currency: from_currency.to_string(),
+            from_amount: from_amount.parse().ok()?,
+            to_currency: to_currency.to_string(),
+            to_amount: to_amount.parse().ok()?,
+            rate: rate.parse().ok()?,
+        })
+    }
+}
+
+/// the ledger lives in the data directory, alongside the climate records
+pub fn ledger_path() -> PathBuf {
+    Config::data_dir().join("ledger.csv")
+}
+
+/// append an entry, writing the header when the file is new
+pub fn append(entry: &LedgerEntry) -> Result<()> {
+    let path = ledger_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("failed to create data directory")?;
+    }
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context("failed to open conversion ledger")?;
+    if is_new {
+        writeln!(file, "{}", LEDGER_HEADER).context("failed to write ledger header")?;
+    }
+    writeln!(file, "{}", entry.to_csv()).context("failed to write ledger entry")?;
+    Ok(())
+}
+
+/// every readable entry, oldest first; a missing ledger is empty
+pub fn load() -> Result<Vec<LedgerEntry>> {
+    let path = ledger_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).context("failed to read conversion ledger")?;
+    Ok(content.lines().filter_map(LedgerEntry::from_csv).collect())
+}
+
+/// default export target: a dated file in the home directory
+pub fn default_export_path(now: DateTime<Utc>) -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(format!("nzi-ledger-{}.csv", now.format("%Y%m%d")))
+}
+
+/// expand a leading `~/` so typed export paths behave like the shell
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// write the whole ledger to `path` as CSV
+pub fn export(path: &Path) -> Result<usize> {
+    let entries = load()?;
+    if entries.is_empty() {
+        bail!("no conversions recorded yet");
+    }
+    let mut content = String::from(LEDGER_HEADER);
+    content.push('\n');
+    for entry in &entries {
+        content.push_str(&entry.to_csv());
+        content.push('\n');
+    }
+    fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::with_temp_config_dir_for_test;
+    use chrono::TimeZone;
+
+    #[test]
+    fn appends_loads_and_exports_entries() {
+        with_temp_config_dir_for_test(|| {
+            assert!(load().unwrap().is_empty());
+
+            let entry = LedgerEntry {
+                timestamp: Utc.with_ymd_and_hms(2026, 3, 31, 9, 30, 0).unwrap(),
+                from_currency: "NZD".to_string(),
+                from_amount: 250.0,
+                to_currency: "USD".to_string(),
+                to_amount: 147.5,
+                rate: 0.59,
+            };
+            append(&entry).unwrap();
+            append(&entry).unwrap();
+
+            let content = fs::read_to_string(ledger_path()).unwrap();
+            assert!(content.starts_with(LEDGER_HEADER));
+            assert!(content.contains("2026-03-31T09:30:00Z,NZD,250.00,USD,147.50,0.59"));
+            assert_eq!(load().unwrap(), vec![entry.clone(), entry]);
+
+            let target = Config::config_dir().join("export.csv");
+            assert_eq!(export(&target).unwrap(), 2);
+            assert_eq!(fs::read_to_string(target).unwrap(), content);
+        });
+    }
+}
@@ -11,47 +11,120 @@
 //!
 //! configuration is stored in ~/.config/nzi-cli/config.toml
 
+mod alarms;
 mod app;
+mod aurora;
+mod bigdigits;
+mod bundle;
+mod cache;
+mod calendar;
+mod cli;
+mod climate;
+mod clock;
 mod config;
+mod config_check;
+mod events;
 mod exchange;
+mod export;
+mod flight;
+mod footer;
+mod health;
+mod heat;
+mod holidays;
+mod http;
+mod icons;
+mod iss;
+mod keymap;
+mod ledger;
 mod map;
+mod notify;
+mod orbit;
+mod panels;
+mod places;
+mod planner;
+mod providers;
+mod quakes;
 mod reference;
+mod schema;
+mod settings;
+mod sparkline;
+mod stats;
+mod sun;
 mod theme;
 mod timezone;
+mod tour;
 mod ui;
+mod watch;
 mod weather;
 
-use std::io;
+use std::io::{self, IsTerminal, Write};
 use std::process::Command;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event, KeyEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 
 use app::App;
+use clap::Parser;
+use cli::Cli;
 use config::Config;
+use watch::ConfigWatcher;
 
 /// main entry point
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    Config::start_session(cli.config.clone(), cli.overrides());
+
+    // `nzi --export-map [path]` snapshots the map without starting the tui
+    if let Some(path) = &cli.export_map {
+        return export_map(path.as_deref());
+    }
+
+    // `nzi --export-config [path]` / `--import-config <path>` move the config between machines
+    if let Some(path) = &cli.export_config {
+        return export_config(path.as_deref());
+    }
+    if let Some(path) = &cli.import_config {
+        return import_config(path, cli.yes);
+    }
+
+    // loaded before the terminal is taken over, so a bad flag prints plainly
+    let mut app = App::load()?;
+
     // set up terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // create app and run
-    let mut app = App::load()?;
-
-    // initial data fetch
+    // initial data fetch, redrawing the splash as each source reports in
+    terminal.draw(|f| {
+        ui::draw(f, &app);
+    })?;
     app.refresh_exchange_rate().await;
+    terminal.draw(|f| {
+        ui::draw(f, &app);
+    })?;
     app.refresh_weather().await;
+    terminal.draw(|f| {
+        ui::draw(f, &app);
+    })?;
+    app.check_clock_skew();
 
     let result = run_app(&mut terminal, &mut app).await;
 
@@ -60,7 +133,8 @@ async fn main() -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
     terminal.show_cursor()?;
 
@@ -71,25 +145,117 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// render the map at the export size and write it to `path`, or to stdout when none is given
+/// (coloured when stdout is a terminal, plain text when piped); it's the NZ map, drawn
+/// whether or not the map is switched on in the tui
+fn export_map(path: Option<&str>) -> Result<()> {
+    let mut config = Config::load()?;
+    config.map.get_or_insert_with(Default::default).enabled = true;
+    let mut app = App::new(config);
+    // the weather panel's map is the NZ one
+    app.focus = app::Focus::Weather;
+    let (width, height) = app::EXPORT_MAP_SIZE;
+    let Some(buf) = ui::render_map(&app, width, height) else {
+        bail!("the map could not be drawn");
+    };
+    match path {
+        Some(path) => export::write_map(&ledger::expand_home(path), &buf),
+        None => {
+            let text = if io::stdout().is_terminal() {
+                export::buffer_ansi(&buf)
+            } else {
+                export::buffer_text(&buf)
+            };
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+/// write a config bundle to `path`, or to stdout when none is given
+fn export_config(path: Option<&str>) -> Result<()> {
+    // creates config.toml on a first run, so there's something to export
+    Config::load()?;
+    match path {
+        Some(path) => {
+            let path = ledger::expand_home(path);
+            let summary = bundle::export(&path)?;
+            println!(
+                "Exported {} cities and {} exchange rates to {}",
+                summary.cities,
+                summary.rates,
+                path.display()
+            );
+        }
+        None => print!("{}", bundle::export_text()?.0),
+    }
+    Ok(())
+}
+
+/// replace the config with the bundle at `path`, asking before keeping any shell commands
+/// it runs unless `yes`
+fn import_config(path: &str, yes: bool) -> Result<()> {
+    let path = ledger::expand_home(path);
+    let commands = bundle::read(&path)?.commands();
+    let allow_commands = yes || (!commands.is_empty() && confirm_commands(&commands)?);
+    let summary = bundle::import(&path, allow_commands)?;
+    let (_, problems) = Config::load_checked()?;
+    println!(
+        "Imported {} cities and {} exchange rates into {}",
+        summary.cities,
+        summary.rates,
+        Config::config_path().display()
+    );
+    for problem in problems {
+        eprintln!("config problem: {}", problem);
+    }
+    Ok(())
+}
+
+/// list the commands a bundle runs and ask whether to keep them; only a terminal can say yes
+fn confirm_commands(commands: &[String]) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    println!("This bundle runs shell commands:");
+    for command in commands {
+        println!("  {}", command);
+    }
+    print!("Import them too? [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
 /// main event loop
 async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
 ) -> Result<()> {
-    let tick_rate = Duration::from_millis(100);
-    let mut last_data_refresh = std::time::Instant::now();
-    let data_refresh_interval = Duration::from_secs(300); // 5 minutes
+    let mut last_weather_refresh = std::time::Instant::now();
+    let mut last_exchange_refresh = std::time::Instant::now();
+    let mut config_watcher = ConfigWatcher::start(Config::config_path());
 
     loop {
-        // draw ui
-        terminal.draw(|f| ui::draw(f, app))?;
+        // clock times change once a second at most; cheap to check every pass
+        app.refresh_times_if_due();
+        app.receive_fetched();
 
-        // handle events with timeout for animation
-        if crossterm::event::poll(tick_rate)?
-            && let Event::Key(key) = event::read()?
-            && key.kind == KeyEventKind::Press
-        {
-            app.handle_key(key.code);
+        // draw ui, remembering where panels, hints and rows went for mouse clicks
+        let mut click_areas = ui::ClickAreas::default();
+        terminal.draw(|f| click_areas = ui::draw(f, app))?;
+        app.click_areas = click_areas;
+
+        // handle events with timeout for animation (slower while unfocused)
+        if crossterm::event::poll(app.next_redraw_in())? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key_event(key),
+                Event::FocusGained => app.set_terminal_focus(true),
+                Event::FocusLost => app.set_terminal_focus(false),
+                Event::Mouse(mouse) => app.handle_mouse(mouse),
+                _ => {}
+            }
         }
 
         // tick for animations and time updates
@@ -103,6 +269,37 @@ async fn run_app(
             app.refresh_weather().await;
         }
 
+        // look up destination-country holidays the first time a country/year is shown
+        if app.needs_holiday_refresh() {
+            app.refresh_holidays();
+        }
+
+        // recent quakes for the NZ map
+        if app.needs_quake_refresh() {
+            app.refresh_quakes();
+        }
+
+        // geomagnetic activity for the aurora banner
+        if app.needs_aurora_refresh() {
+            app.refresh_aurora();
+        }
+
+        // yesterday's highs, lows and rain for the personal climate record
+        if app.needs_climate_refresh() {
+            app.refresh_climate();
+        }
+
+        // current temperatures for the NZ heat layer
+        if app.needs_heat_refresh() {
+            app.refresh_heat();
+        }
+
+        // the ISS's drift across the world map
+        if app.needs_iss_refresh() {
+            app.refresh_iss();
+        }
+        app.plan_iss_pass();
+
         // check for pending currency refresh (e.g., pair changed)
         if app.needs_currency_refresh() {
             app.currency_converter.clear_refresh_flag();
@@ -118,11 +315,23 @@ async fn run_app(
             }
         }
 
-        // periodic data refresh (exchange rate + weather)
-        if last_data_refresh.elapsed() > data_refresh_interval {
-            app.refresh_exchange_rate().await;
+        // pick up edits made to the config file from outside the app
+        if config_watcher.as_mut().is_some_and(ConfigWatcher::changed)
+            && let Err(e) = app.reload_config()
+        {
+            app.set_status(format!("Config reload failed: {}", e));
+        }
+
+        // periodic refreshes, each on its own interval from [weather] and [exchange]
+        let weather_interval = Duration::from_secs(app.config.effective_weather().refresh_secs);
+        if last_weather_refresh.elapsed() > weather_interval {
             app.refresh_weather().await;
-            last_data_refresh = std::time::Instant::now();
+            last_weather_refresh = std::time::Instant::now();
+        }
+        let exchange_interval = Duration::from_secs(app.config.effective_exchange().refresh_secs);
+        if last_exchange_refresh.elapsed() > exchange_interval {
+            app.refresh_exchange_rate().await;
+            last_exchange_refresh = std::time::Instant::now();
         }
 
         // check if we should quit
@@ -147,10 +356,16 @@ async fn open_editor_for_config(
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
     terminal.show_cursor()?;
 
+    // the editor writes the file itself, so keep the version it starts from
+    if let Err(e) = Config::back_up() {
+        app.set_status(format!("Config backup failed: {}", e));
+    }
+
     // spawn editor and wait for it to finish
     let status = Command::new(&editor).arg(&config_path).status();
 
@@ -159,7 +374,8 @@ async fn open_editor_for_config(
     execute!(
         terminal.backend_mut(),
         EnterAlternateScreen,
-        EnableMouseCapture
+        EnableMouseCapture,
+        EnableFocusChange
     )?;
     terminal.hide_cursor()?;
     terminal.clear()?;
@@ -3,16 +3,20 @@
 
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
-    style::{Modifier, Style},
+    layout::{Margin, Position, Rect},
+    style::{Color, Modifier, Style},
     symbols::Marker,
-    text::Span,
+    text::{Line, Span},
     widgets::{
-        Block, BorderType, Borders, Widget,
-        canvas::{Canvas, Map, MapResolution, Points},
+        Block, BorderType, Borders, Clear, Padding, Paragraph, Widget,
+        canvas::{Canvas, Line as CanvasLine, Map, MapResolution, Points},
     },
 };
 
+use crate::aurora::aurora_outlook;
+use crate::config::{AnimationLayers, MapMarkerStyle, WorldProjection};
+use crate::heat::heat_colour;
+use crate::quakes::Quake;
 use crate::theme::{Theme, catppuccin};
 
 // nz bounding box for canvas map (from nzme-cli)
@@ -53,11 +57,402 @@ pub const NZ_CITIES: &[CityMarker] = &[
     CityMarker::new("DUD", "Dunedin", -45.8788, 170.5028),
 ];
 
+// NZ_COASTLINE: the main islands' outlines as closed (lon, lat) rings,
+// generated by build.rs from data/nz_coastline.geojson
+include!(concat!(env!("OUT_DIR"), "/coastline_data.rs"));
+
+// NZ_HIGHWAYS: SH1 and the main state highways as (ref, (lon, lat) polyline),
+// generated by build.rs from data/nz_highways.geojson
+include!(concat!(env!("OUT_DIR"), "/highways_data.rs"));
+
+/// state highway colour, quiet enough to sit behind the coast and city markers
+const HIGHWAY: Color = catppuccin::SURFACE2;
+
+/// the main domestic and international airports, by IATA code
+pub const NZ_AIRPORTS: &[CityMarker] = &[
+    CityMarker::new("AKL", "Auckland Airport", -37.0082, 174.7850),
+    CityMarker::new("WLG", "Wellington Airport", -41.3272, 174.8053),
+    CityMarker::new("CHC", "Christchurch Airport", -43.4894, 172.5322),
+    CityMarker::new("ZQN", "Queenstown Airport", -45.0211, 168.7392),
+    CityMarker::new("DUD", "Dunedin Airport", -45.9281, 170.1983),
+];
+
+/// long-haul hubs on the world map, roughly the connections out of NZ
+pub const HUB_AIRPORTS: &[CityMarker] = &[
+    CityMarker::new("SYD", "Sydney", -33.9399, 151.1753),
+    CityMarker::new("SIN", "Singapore Changi", 1.3644, 103.9915),
+    CityMarker::new("HKG", "Hong Kong", 22.3080, 113.9185),
+    CityMarker::new("NRT", "Tokyo Narita", 35.7720, 140.3929),
+    CityMarker::new("DXB", "Dubai", 25.2532, 55.3657),
+    CityMarker::new("DOH", "Doha", 25.2731, 51.6081),
+    CityMarker::new("LHR", "London Heathrow", 51.4700, -0.4543),
+    CityMarker::new("FRA", "Frankfurt", 50.0379, 8.5622),
+    CityMarker::new("LAX", "Los Angeles", 33.9416, -118.4085),
+    CityMarker::new("SFO", "San Francisco", 37.6213, -122.3790),
+    CityMarker::new("JFK", "New York JFK", 40.6413, -73.7781),
+    CityMarker::new("YVR", "Vancouver", 49.1967, -123.1815),
+    CityMarker::new("SCL", "Santiago", -33.3930, -70.7858),
+    CityMarker::new("NAN", "Nadi", -17.7554, 177.4434),
+];
+
+/// an extra town from `[[map.markers]]`, drawn smaller than the built-in cities
+#[derive(Debug, Clone, PartialEq)]
+pub struct TownMarker {
+    pub code: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// the terminal cell a canvas with these bounds draws (lon, lat) in, matching ratatui's
+/// own label placement; None when the point is out of view
+fn canvas_cell(
+    inner: Rect,
+    lon: f64,
+    lat: f64,
+    x_bounds: [f64; 2],
+    y_bounds: [f64; 2],
+) -> Option<Position> {
+    if inner.width == 0
+        || inner.height == 0
+        || !(x_bounds[0]..=x_bounds[1]).contains(&lon)
+        || !(y_bounds[0]..=y_bounds[1]).contains(&lat)
+    {
+        return None;
+    }
+    let x = (lon - x_bounds[0]) * f64::from(inner.width - 1) / (x_bounds[1] - x_bounds[0]);
+    let y = (y_bounds[1] - lat) * f64::from(inner.height - 1) / (y_bounds[1] - y_bounds[0]);
+    Some(Position::new(inner.x + x as u16, inner.y + y as u16))
+}
+
+/// below this many columns or rows inside its border the NZ map shows dots without labels
+const MIN_LABELLED_MAP: (u16, u16) = (24, 10);
+
+/// where to start each label so it sits beside its point without covering another
+/// label or point, trying right, left, then the rows above and below; a spot clear of
+/// the drawing (`drawn` says whether one covers any) beats the first free one, earlier
+/// labels win, and a label with no free spot is dropped
+pub fn place_labels(
+    area: Rect,
+    labels: &[(Position, usize)],
+    drawn: impl Fn(Rect) -> bool,
+) -> Vec<Option<Position>> {
+    let mut taken: Vec<Rect> = labels
+        .iter()
+        .map(|(point, _)| Rect::new(point.x, point.y, 1, 1))
+        .collect();
+
+    labels
+        .iter()
+        .map(|&(point, width)| {
+            let width = width as u16;
+            let right = point.x.saturating_add(1);
+            let left = point.x.checked_sub(width);
+            let candidates = [
+                Some((right, Some(point.y))),
+                left.map(|x| (x, Some(point.y))),
+                Some((right, point.y.checked_sub(1))),
+                Some((right, point.y.checked_add(1))),
+                left.map(|x| (x, point.y.checked_sub(1))),
+                left.map(|x| (x, point.y.checked_add(1))),
+            ];
+            let free: Vec<Rect> = candidates
+                .into_iter()
+                .flatten()
+                .filter_map(|(x, y)| Some(Rect::new(x, y?, width, 1)))
+                .filter(|spot| {
+                    area.contains(spot.as_position())
+                        && spot.right() <= area.right()
+                        && !taken.iter().any(|other| other.intersects(*spot))
+                })
+                .collect();
+            let spot = free
+                .iter()
+                .find(|spot| !drawn(**spot))
+                .or(free.first())
+                .copied()?;
+            taken.push(spot);
+            Some(spot.as_position())
+        })
+        .collect()
+}
+
+/// Wellington to Picton through Tory Channel, as (lon, lat) waypoints
+const FERRY_ROUTE: &[(f64, f64)] = &[
+    (174.784, -41.277),
+    (174.818, -41.300),
+    (174.838, -41.345),
+    (174.800, -41.400),
+    (174.560, -41.330),
+    (174.330, -41.210),
+    (174.220, -41.228),
+    (174.120, -41.190),
+    (174.050, -41.220),
+    (174.007, -41.290),
+];
+
+/// the ferry route filled in with evenly spaced points, terminal to terminal
+fn ferry_points() -> Vec<(f64, f64)> {
+    const STEP_DEG: f64 = 0.01;
+    let mut points = vec![FERRY_ROUTE[0]];
+    for pair in FERRY_ROUTE.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        let steps = ((x1 - x0).hypot(y1 - y0) / STEP_DEG).ceil().max(1.0) as usize;
+        for step in 1..=steps {
+            let t = step as f64 / steps as f64;
+            points.push((x0 + (x1 - x0) * t, y0 + (y1 - y0) * t));
+        }
+    }
+    points
+}
+
+/// dot radius and colour for a quake of this magnitude
+fn quake_style(magnitude: f64) -> (i32, Color) {
+    if magnitude >= 5.0 {
+        (3, catppuccin::RED)
+    } else if magnitude >= 4.0 {
+        (2, catppuccin::PEACH)
+    } else if magnitude >= 3.0 {
+        (1, catppuccin::YELLOW)
+    } else {
+        (0, catppuccin::GREEN)
+    }
+}
+
+/// deepest zoom level; each level halves the visible span
+pub const MAX_MAP_ZOOM: u32 = 7;
+
+/// a named part of a map, for the focused map's 1/2/3 presets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapRegion {
+    pub name: &'static str,
+    pub x_bounds: [f64; 2],
+    pub y_bounds: [f64; 2],
+}
+
+impl MapRegion {
+    const fn new(name: &'static str, x_bounds: [f64; 2], y_bounds: [f64; 2]) -> Self {
+        Self {
+            name,
+            x_bounds,
+            y_bounds,
+        }
+    }
+}
+
+/// NZ map presets; the key after the last shows the whole country
+pub const NZ_REGIONS: &[MapRegion] = &[
+    MapRegion::new("North Island", [172.4, 178.8], [-41.8, -34.2]),
+    MapRegion::new("South Island", [166.2, 174.6], [-47.4, -40.3]),
+];
+
+/// world map presets, in greenwich longitudes; the Pacific stops at the date line, where the
+/// greenwich canvas ends
+pub const WORLD_REGIONS: &[MapRegion] = &[
+    MapRegion::new("Pacific", [100.0, 180.0], [-50.0, 30.0]),
+    MapRegion::new("Europe", [-12.0, 42.0], [34.0, 72.0]),
+    MapRegion::new("Americas", [-170.0, -30.0], [-56.0, 72.0]),
+];
+
+/// zoom level and centre of a map canvas, with the centre stored as a fraction of the
+/// canvas's full bounds so the same view applies whatever those bounds are
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapViewport {
+    pub zoom: u32,
+    pub center_x: f64,
+    pub center_y: f64,
+    /// a preset region shown exactly, as fractions of the full bounds; zooming or
+    /// panning carries on from the nearest zoom level that still shows all of it
+    region: Option<(&'static str, [f64; 2], [f64; 2])>,
+}
+
+impl Default for MapViewport {
+    fn default() -> Self {
+        Self {
+            zoom: 0,
+            center_x: 0.5,
+            center_y: 0.5,
+            region: None,
+        }
+    }
+}
+
+impl MapViewport {
+    /// a view of exactly `region` within a canvas whose full bounds are `x` and `y`
+    pub fn region(region: &MapRegion, x: [f64; 2], y: [f64; 2]) -> Self {
+        let fraction = |[min, max]: [f64; 2], [lo, hi]: [f64; 2]| {
+            [(lo - min) / (max - min), (hi - min) / (max - min)].map(|f| f.clamp(0.0, 1.0))
+        };
+        Self {
+            region: Some((
+                region.name,
+                fraction(x, region.x_bounds),
+                fraction(y, region.y_bounds),
+            )),
+            ..Self::default()
+        }
+    }
+
+    /// a view `zoom` levels in, centred on (`lon`, `lat`) as far as the full bounds allow
+    pub fn centred(zoom: u32, lon: f64, lat: f64, x: [f64; 2], y: [f64; 2]) -> Self {
+        let mut view = Self {
+            zoom: zoom.min(MAX_MAP_ZOOM),
+            center_x: (lon - x[0]) / (x[1] - x[0]),
+            center_y: (lat - y[0]) / (y[1] - y[0]),
+            region: None,
+        };
+        view.clamp();
+        view
+    }
+
+    /// the zoom level and centre (lon, lat) of this view, as `centred` takes them
+    pub fn centre(&self, x: [f64; 2], y: [f64; 2]) -> (u32, f64, f64) {
+        let mut view = *self;
+        view.settle();
+        (
+            view.zoom,
+            x[0] + (x[1] - x[0]) * view.center_x,
+            y[0] + (y[1] - y[0]) * view.center_y,
+        )
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > 0 || self.region.is_some()
+    }
+
+    /// the title note for this view: the preset's name or the magnification
+    pub fn label(&self) -> Option<String> {
+        match self.region {
+            Some((name, _, _)) => Some(name.to_string()),
+            None if self.zoom > 0 => Some(format!("×{}", 1u32 << self.zoom)),
+            None => None,
+        }
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.settle();
+        self.zoom = (self.zoom + 1).min(MAX_MAP_ZOOM);
+        self.clamp();
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.settle();
+        self.zoom = self.zoom.saturating_sub(1);
+        self.clamp();
+    }
+
+    /// move by a fraction of the visible span (positive x is east, positive y north)
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.settle();
+        let span = self.span();
+        self.center_x += dx * span;
+        self.center_y += dy * span;
+        self.clamp();
+    }
+
+    /// fraction of the full bounds that is visible
+    fn span(&self) -> f64 {
+        1.0 / f64::from(1u32 << self.zoom)
+    }
+
+    /// swap a preset region for the deepest zoom level that still shows all of it
+    fn settle(&mut self) {
+        let Some((_, x, y)) = self.region.take() else {
+            return;
+        };
+        let widest = (x[1] - x[0]).max(y[1] - y[0]).max(f64::EPSILON);
+        self.zoom = ((1.0 / widest).log2().floor().max(0.0) as u32).min(MAX_MAP_ZOOM);
+        self.center_x = (x[0] + x[1]) / 2.0;
+        self.center_y = (y[0] + y[1]) / 2.0;
+    }
+
+    /// keep the view inside the full bounds
+    fn clamp(&mut self) {
+        let half = self.span() / 2.0;
+        self.center_x = self.center_x.clamp(half, 1.0 - half);
+        self.center_y = self.center_y.clamp(half, 1.0 - half);
+    }
+
+    /// the x and y canvas bounds to draw within the full bounds
+    pub fn bounds(&self, x: [f64; 2], y: [f64; 2]) -> ([f64; 2], [f64; 2]) {
+        if let Some((_, fx, fy)) = self.region {
+            let scale =
+                |[min, max]: [f64; 2], fraction: [f64; 2]| fraction.map(|f| min + (max - min) * f);
+            return (scale(x, fx), scale(y, fy));
+        }
+        let half = self.span() / 2.0;
+        let scale = |[min, max]: [f64; 2], center: f64| {
+            let width = max - min;
+            [min + width * (center - half), min + width * (center + half)]
+        };
+        (scale(x, self.center_x), scale(y, self.center_y))
+    }
+}
+
+/// the (lon, lat) under a terminal cell of a bordered map canvas drawn in `area`
+pub fn canvas_point(
+    area: Rect,
+    column: u16,
+    row: u16,
+    x_bounds: [f64; 2],
+    y_bounds: [f64; 2],
+) -> Option<(f64, f64)> {
+    let inner = area.inner(Margin::new(1, 1));
+    if !inner.contains(Position::new(column, row)) {
+        return None;
+    }
+    // cell centres, with rows counting down from the northern edge
+    let x = (f64::from(column - inner.x) + 0.5) / f64::from(inner.width);
+    let y = (f64::from(row - inner.y) + 0.5) / f64::from(inner.height);
+    Some((
+        x_bounds[0] + x * (x_bounds[1] - x_bounds[0]),
+        y_bounds[1] - y * (y_bounds[1] - y_bounds[0]),
+    ))
+}
+
+/// index of the (lat, lon) closest to a point, treating degrees as flat
+/// with longitude narrowed towards the poles
+pub fn nearest_point(
+    points: impl IntoIterator<Item = (f64, f64)>,
+    lat: f64,
+    lon: f64,
+) -> Option<usize> {
+    let squeeze = lat.to_radians().cos();
+    points
+        .into_iter()
+        .map(|(point_lat, point_lon)| {
+            let dlon = (point_lon - lon + 540.0).rem_euclid(360.0) - 180.0;
+            (point_lat - lat).powi(2) + (dlon * squeeze).powi(2)
+        })
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
 #[derive(Debug, Clone)]
 pub struct WorldMarker {
     pub label: String,
     pub lat: f64,
     pub lon: f64,
+    /// current temperature, when weather for the marker's city is cached
+    pub temp_c: Option<i32>,
+}
+
+impl WorldMarker {
+    fn label_line(&self) -> Line<'static> {
+        marker_label(self.label.clone(), Style::default(), self.temp_c)
+    }
+}
+
+/// a marker's label, followed by its temperature when known
+fn marker_label(label: String, style: Style, temp_c: Option<i32>) -> Line<'static> {
+    let mut spans = vec![Span::styled(label, style)];
+    if let Some(temp) = temp_c {
+        spans.push(Span::styled(
+            format!(" {}°", temp),
+            Theme::temperature(temp),
+        ));
+    }
+    Line::from(spans)
 }
 
 /// canvas-based nz map widget with braille rendering
@@ -66,6 +461,22 @@ pub struct NzMapCanvas {
     tick: u64,
     highlight_city: Option<String>,
     focused: bool,
+    viewport: MapViewport,
+    towns: Vec<TownMarker>,
+    temperatures: Vec<(&'static str, i32)>,
+    quakes: Vec<Quake>,
+    ferry: bool,
+    airports: bool,
+    aurora_kp: Option<f64>,
+    animations: AnimationLayers,
+    note: Option<String>,
+    crosshair: Option<(f64, f64)>,
+    marker_style: MapMarkerStyle,
+    coastline: Option<Color>,
+    heat: Vec<(f64, f64, f64)>,
+    detailed_coastline: bool,
+    trip: Option<[(String, (f64, f64)); 2]>,
+    highways: bool,
 }
 
 impl NzMapCanvas {
@@ -87,6 +498,100 @@ impl NzMapCanvas {
         self.focused = focused;
         self
     }
+
+    pub fn viewport(mut self, viewport: MapViewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn towns(mut self, towns: Vec<TownMarker>) -> Self {
+        self.towns = towns;
+        self
+    }
+
+    /// cached current temperatures by city code, shown beside the city labels
+    pub fn temperatures(mut self, temperatures: Vec<(&'static str, i32)>) -> Self {
+        self.temperatures = temperatures;
+        self
+    }
+
+    pub fn quakes(mut self, quakes: Vec<Quake>) -> Self {
+        self.quakes = quakes;
+        self
+    }
+
+    /// draw the Wellington–Picton ferry route with a sailing moving along it
+    pub fn ferry(mut self, ferry: bool) -> Self {
+        self.ferry = ferry;
+        self
+    }
+
+    /// mark the main airports with their IATA codes
+    pub fn airports(mut self, airports: bool) -> Self {
+        self.airports = airports;
+        self
+    }
+
+    pub fn animations(mut self, animations: AnimationLayers) -> Self {
+        self.animations = animations;
+        self
+    }
+
+    /// the time converter's from and to cities as (code, (lat, lon)), marked in their
+    /// own colours and joined by a line
+    pub fn trip(mut self, trip: Option<[(String, (f64, f64)); 2]>) -> Self {
+        self.trip = trip;
+        self
+    }
+
+    /// draw the bundled NZ outline, with Banks Peninsula and Stewart Island, instead of
+    /// ratatui's world coastline
+    pub fn detailed_coastline(mut self, detailed: bool) -> Self {
+        self.detailed_coastline = detailed;
+        self
+    }
+
+    /// trace SH1 and the other main state highways; their numbers show once zoomed in
+    pub fn highways(mut self, highways: bool) -> Self {
+        self.highways = highways;
+        self
+    }
+
+    /// current temperatures as (lat, lon, °C); the coastline is shaded by the nearest one
+    pub fn heat(mut self, points: Vec<(f64, f64, f64)>) -> Self {
+        self.heat = points;
+        self
+    }
+
+    /// the latest Kp index; when the aurora may be visible the south is tinted and a banner shown
+    pub fn aurora(mut self, kp: Option<f64>) -> Self {
+        self.aurora_kp = kp;
+        self
+    }
+
+    /// extra text after the title, e.g. the largest recent quake
+    pub fn note(mut self, note: Option<String>) -> Self {
+        self.note = note;
+        self
+    }
+
+    /// a (lat, lon) to mark with a cross, for reading coordinates off the map
+    pub fn crosshair(mut self, crosshair: Option<(f64, f64)>) -> Self {
+        self.crosshair = crosshair;
+        self
+    }
+
+    /// braille, half blocks or dots, for terminals whose fonts lack braille
+    pub fn marker_style(mut self, style: MapMarkerStyle) -> Self {
+        self.marker_style = style;
+        self
+    }
+
+    /// draw the coastline in one colour, however the rainbow animation is set
+    pub fn coastline(mut self, color: Option<Color>) -> Self {
+        self.coastline = color;
+        self
+    }
 }
 
 impl Widget for NzMapCanvas {
@@ -106,10 +611,36 @@ impl Widget for NzMapCanvas {
         }
 
         // rainbow colour cycling for the map coastline (like nzme-cli)
-        let map_color = rainbow[(tick / 3) % rainbow.len()];
+        // the heat layer recolours the coastline, so it starts from the plain one
+        let map_color = match self.coastline {
+            _ if !self.heat.is_empty() => STATIC_COASTLINE,
+            Some(color) => color,
+            None if self.animations.rainbow => rainbow[(tick / 3) % rainbow.len()],
+            None => STATIC_COASTLINE,
+        };
+
+        let detailed_coastline = self.detailed_coastline;
+        let highways = if self.highways { NZ_HIGHWAYS } else { &[] };
+        let trip = self.trip.clone();
+        let trip_color = |code: &str| {
+            let [from, to] = self.trip.as_ref()?;
+            if from.0.eq_ignore_ascii_case(code) {
+                Some(TRIP_FROM)
+            } else if to.0.eq_ignore_ascii_case(code) {
+                Some(TRIP_TO)
+            } else {
+                None
+            }
+        };
+        let trip_style = |code: &str| {
+            trip_color(code).map_or(Style::default(), |color| {
+                Style::default().fg(color).add_modifier(Modifier::BOLD)
+            })
+        };
 
         // wave animation along the bottom of the map
-        let wave_points: Vec<(f64, f64)> = (0..70)
+        let wave_count = if self.animations.waves { 70 } else { 0 };
+        let wave_points: Vec<(f64, f64)> = (0..wave_count)
             .map(|i| {
                 let t = self.tick as f64 / 6.0;
                 let x = NZ_LON_MIN + (NZ_LON_MAX - NZ_LON_MIN) * (i as f64 / 70.0);
@@ -137,7 +668,7 @@ impl Widget for NzMapCanvas {
         // kiwi birds (slower, ground level) - these don't fly but waddle!
         let kiwi_offset = (tick_f / 25.0) % (bird_span * 0.3);
 
-        let birds = vec![
+        let mut birds = vec![
             // north island flock
             (NZ_LON_MIN + north_offset, north_y),
             (NZ_LON_MIN + north_offset - 0.8, north_y + 0.15),
@@ -151,8 +682,18 @@ impl Widget for NzMapCanvas {
             // kiwi near wellington (ground level, slower)
             (174.5 + kiwi_offset, -41.3),
         ];
+        if !self.animations.birds {
+            birds.clear();
+        }
 
         let highlight_city = self.highlight_city.clone();
+        let is_highlighted = |code: &str| {
+            highlight_city
+                .as_ref()
+                .is_some_and(|c| c.eq_ignore_ascii_case(code))
+        };
+        let towns = self.towns.clone();
+        let airports = if self.airports { NZ_AIRPORTS } else { &[] };
 
         let (border_type, border_color) = if self.focused {
             (BorderType::Double, catppuccin::YELLOW)
@@ -168,6 +709,52 @@ impl Widget for NzMapCanvas {
             Theme::block_title()
         };
 
+        let (x_bounds, y_bounds) = self
+            .viewport
+            .bounds([NZ_LON_MIN, NZ_LON_MAX], [NZ_LAT_MIN, NZ_LAT_MAX]);
+        let mut title = " Aotearoa New Zealand ".to_string();
+        if let Some(label) = self.viewport.label() {
+            title.push_str(&format!("{} ", label));
+        }
+        if let Some(note) = &self.note {
+            title.push_str(&format!("· {} ", note));
+        }
+
+        // quakes as discs of braille dots, wider and hotter with magnitude
+        let inner = area.inner(Margin::new(1, 1));
+        let dot = (
+            (x_bounds[1] - x_bounds[0]) / (f64::from(inner.width.max(1)) * 2.0),
+            (y_bounds[1] - y_bounds[0]) / (f64::from(inner.height.max(1)) * 4.0),
+        );
+        let quake_dots: Vec<(Vec<(f64, f64)>, Color)> = self
+            .quakes
+            .iter()
+            .map(|quake| {
+                let (radius, color) = quake_style(quake.magnitude);
+                let mut coords = Vec::new();
+                for dx in -radius..=radius {
+                    for dy in -radius..=radius {
+                        if dx * dx + dy * dy <= radius * radius {
+                            coords.push((
+                                quake.lon + f64::from(dx) * dot.0,
+                                quake.lat + f64::from(dy) * dot.1,
+                            ));
+                        }
+                    }
+                }
+                (coords, color)
+            })
+            .collect();
+
+        let ferry_route = if self.ferry {
+            ferry_points()
+        } else {
+            Vec::new()
+        };
+        let ferry_at = ferry_route
+            .get((tick / 3) % ferry_route.len().max(1))
+            .copied();
+
         let canvas = Canvas::default()
             .block(
                 Block::default()
@@ -175,18 +762,45 @@ impl Widget for NzMapCanvas {
                     .borders(Borders::ALL)
                     .border_type(border_type)
                     .border_style(Style::default().fg(border_color))
-                    .title(Span::styled(" Aotearoa New Zealand ", title_style)),
+                    .title(Span::styled(title, title_style)),
             )
             .background_color(catppuccin::BASE)
-            .marker(Marker::Braille)
-            .x_bounds([NZ_LON_MIN, NZ_LON_MAX])
-            .y_bounds([NZ_LAT_MIN, NZ_LAT_MAX])
+            .marker(canvas_marker(self.marker_style))
+            .x_bounds(x_bounds)
+            .y_bounds(y_bounds)
             .paint(move |ctx| {
-                // draw NZ using the built-in high-resolution world map
-                ctx.draw(&Map {
-                    color: map_color,
-                    resolution: MapResolution::High,
-                });
+                // highways first, so the coast keeps its colour where a road hugs it
+                for (_, line) in highways {
+                    for edge in line.windows(2) {
+                        ctx.draw(&CanvasLine {
+                            x1: edge[0].0,
+                            y1: edge[0].1,
+                            x2: edge[1].0,
+                            y2: edge[1].1,
+                            color: HIGHWAY,
+                        });
+                    }
+                }
+
+                // draw NZ from the bundled outline, or the built-in high-resolution world map
+                if detailed_coastline {
+                    for ring in NZ_COASTLINE {
+                        for edge in ring.windows(2) {
+                            ctx.draw(&CanvasLine {
+                                x1: edge[0].0,
+                                y1: edge[0].1,
+                                x2: edge[1].0,
+                                y2: edge[1].1,
+                                color: map_color,
+                            });
+                        }
+                    }
+                } else {
+                    ctx.draw(&Map {
+                        color: map_color,
+                        resolution: MapResolution::High,
+                    });
+                }
 
                 // draw wave animation
                 ctx.draw(&Points {
@@ -200,38 +814,268 @@ impl Widget for NzMapCanvas {
                     color: catppuccin::YELLOW,
                 });
 
-                // draw city markers
-                for city in NZ_CITIES {
-                    let is_highlighted = highlight_city
-                        .as_ref()
-                        .is_some_and(|c| c.eq_ignore_ascii_case(city.code));
+                ctx.draw(&Points {
+                    coords: &ferry_route,
+                    color: catppuccin::LAVENDER,
+                });
+                if let Some(ferry) = ferry_at {
+                    ctx.draw(&Points {
+                        coords: &[ferry],
+                        color: catppuccin::TEXT,
+                    });
+                }
 
-                    let dot_color = if is_highlighted {
+                for (coords, color) in &quake_dots {
+                    ctx.draw(&Points {
+                        coords,
+                        color: *color,
+                    });
+                }
+
+                for airport in airports {
+                    ctx.draw(&Points {
+                        coords: &[(airport.lon, airport.lat)],
+                        color: catppuccin::PEACH,
+                    });
+                }
+
+                // configured towns, then the main centres over them
+                for town in &towns {
+                    ctx.draw(&Points {
+                        coords: &[(town.lon, town.lat)],
+                        color: catppuccin::GREEN,
+                    });
+                }
+                for city in NZ_CITIES {
+                    let dot_color = if is_highlighted(city.code) {
                         catppuccin::YELLOW
                     } else {
                         catppuccin::SAPPHIRE
                     };
-
-                    // city dot
                     ctx.draw(&Points {
                         coords: &[(city.lon, city.lat)],
                         color: dot_color,
                     });
+                }
 
-                    // city label
-                    let label = if is_highlighted {
-                        format!("★★{}★★", city.name)
-                    } else {
-                        city.code.to_string()
-                    };
-                    ctx.print(city.lon + 0.25, city.lat + 0.15, label);
+                if let Some([(_, from), (_, to)]) = &trip {
+                    ctx.draw(&CanvasLine {
+                        x1: from.1,
+                        y1: from.0,
+                        x2: to.1,
+                        y2: to.0,
+                        color: catppuccin::OVERLAY0,
+                    });
+                    ctx.draw(&Points {
+                        coords: &[(from.1, from.0)],
+                        color: TRIP_FROM,
+                    });
+                    ctx.draw(&Points {
+                        coords: &[(to.1, to.0)],
+                        color: TRIP_TO,
+                    });
                 }
             });
 
         canvas.render(area, buf);
+
+        // heat: each stretch of coast takes the colour of its nearest grid temperature
+        if !self.heat.is_empty() {
+            for row in inner.top()..inner.bottom() {
+                for column in inner.left()..inner.right() {
+                    let cell = &mut buf[(column, row)];
+                    if cell.fg != map_color || cell.symbol() == " " {
+                        continue;
+                    }
+                    let Some((lon, lat)) = canvas_point(area, column, row, x_bounds, y_bounds)
+                    else {
+                        continue;
+                    };
+                    let grid = self.heat.iter().map(|&(lat, lon, _)| (lat, lon));
+                    if let Some(index) = nearest_point(grid, lat, lon) {
+                        cell.set_fg(heat_colour(self.heat[index].2));
+                    }
+                }
+            }
+        }
+
+        // labels go on after the canvas so they can be moved out of each other's way
+        // and off the coastline; the highlighted city is placed first, then the main
+        // centres, towns and airports. A panel too small for them shows only the dots
+        let labelled = inner.width >= MIN_LABELLED_MAP.0 && inner.height >= MIN_LABELLED_MAP.1;
+        let mut labels: Vec<(Position, Line)> = Vec::new();
+        let mut cities: Vec<&CityMarker> = NZ_CITIES.iter().collect();
+        cities.sort_by_key(|city| !is_highlighted(city.code));
+        for city in cities {
+            let Some(cell) = canvas_cell(inner, city.lon, city.lat, x_bounds, y_bounds) else {
+                continue;
+            };
+            let label = if is_highlighted(city.code) {
+                format!("★★{}★★", city.name)
+            } else {
+                city.code.to_string()
+            };
+            let temp_c = self
+                .temperatures
+                .iter()
+                .find(|(code, _)| *code == city.code)
+                .map(|(_, temp)| *temp);
+            labels.push((cell, marker_label(label, trip_style(city.code), temp_c)));
+        }
+        // trip ends that aren't main centres or towns get labels of their own
+        if let Some([from, to]) = &self.trip {
+            let ends = if from.0 == to.0 {
+                vec![from]
+            } else {
+                vec![from, to]
+            };
+            for (code, (lat, lon)) in ends {
+                let labelled = NZ_CITIES
+                    .iter()
+                    .map(|city| city.code)
+                    .chain(self.towns.iter().map(|town| town.code.as_str()))
+                    .any(|other| other.eq_ignore_ascii_case(code));
+                if !labelled && let Some(cell) = canvas_cell(inner, *lon, *lat, x_bounds, y_bounds)
+                {
+                    labels.push((cell, marker_label(code.clone(), trip_style(code), None)));
+                }
+            }
+        }
+        for town in &self.towns {
+            if let Some(cell) = canvas_cell(inner, town.lon, town.lat, x_bounds, y_bounds) {
+                let style = trip_color(&town.code)
+                    .map_or(Style::default().fg(catppuccin::SUBTEXT0), |_| {
+                        trip_style(&town.code)
+                    });
+                labels.push((cell, marker_label(town.code.clone(), style, None)));
+            }
+        }
+        for airport in airports {
+            if let Some(cell) = canvas_cell(inner, airport.lon, airport.lat, x_bounds, y_bounds) {
+                let style = Style::default().fg(catppuccin::PEACH);
+                labels.push((
+                    cell,
+                    marker_label(format!("✈{}", airport.code), style, None),
+                ));
+            }
+        }
+
+        // highway numbers once zoomed in, at the visible point nearest each line's middle
+        if self.viewport.is_zoomed() {
+            for (reference, line) in highways {
+                let middle = line.len() / 2;
+                let cell = (0..line.len())
+                    .map(|offset| {
+                        if offset % 2 == 0 {
+                            middle + offset / 2
+                        } else {
+                            middle.saturating_sub(offset / 2 + 1)
+                        }
+                    })
+                    .filter_map(|index| line.get(index))
+                    .find_map(|&(lon, lat)| canvas_cell(inner, lon, lat, x_bounds, y_bounds));
+                if let Some(cell) = cell {
+                    // a shade lighter than the line, so the number stays readable
+                    let style = Style::default().fg(catppuccin::OVERLAY0);
+                    labels.push((cell, marker_label(reference.to_string(), style, None)));
+                }
+            }
+        }
+
+        let spots: Vec<(Position, usize)> = labels
+            .iter()
+            .map(|(cell, label)| (*cell, label.width()))
+            .collect();
+        let drawn = |spot: Rect| spot.positions().any(|cell| buf[cell].symbol() != " ");
+        let placed = if labelled {
+            place_labels(inner, &spots, drawn)
+        } else {
+            vec![None; spots.len()]
+        };
+        for ((_, label), spot) in labels.iter().zip(placed) {
+            if let Some(spot) = spot {
+                buf.set_line(spot.x, spot.y, label, label.width() as u16);
+            }
+        }
+
+        // aurora: tint every row south of where it may be seen, and say so along the bottom
+        if let Some(kp) = self.aurora_kp
+            && let Some((reach, from_lat)) = aurora_outlook(kp)
+            && inner.height > 0
+        {
+            for row in inner.top()..inner.bottom() {
+                let lat = y_bounds[1]
+                    - (f64::from(row - inner.y) + 0.5) / f64::from(inner.height)
+                        * (y_bounds[1] - y_bounds[0]);
+                if lat > from_lat {
+                    continue;
+                }
+                for column in inner.left()..inner.right() {
+                    buf[(column, row)].set_bg(AURORA_TINT);
+                }
+            }
+            let banner = Line::from(Span::styled(
+                format!(" ✦ Aurora possible tonight {} · Kp {:.1} ", reach, kp),
+                Style::default()
+                    .fg(catppuccin::GREEN)
+                    .bg(AURORA_TINT)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            buf.set_line(inner.x, inner.bottom() - 1, &banner, inner.width);
+        }
+
+        draw_crosshair(buf, inner, self.crosshair, x_bounds, y_bounds);
+    }
+}
+
+/// the ratatui marker that draws a style's points
+fn canvas_marker(style: MapMarkerStyle) -> Marker {
+    match style {
+        MapMarkerStyle::Braille => Marker::Braille,
+        MapMarkerStyle::HalfBlock => Marker::HalfBlock,
+        MapMarkerStyle::Dot => Marker::Dot,
+    }
+}
+
+/// mark a (lat, lon) with a cross in the cell it falls in, over whatever is drawn there
+fn draw_crosshair(
+    buf: &mut Buffer,
+    inner: Rect,
+    crosshair: Option<(f64, f64)>,
+    x_bounds: [f64; 2],
+    y_bounds: [f64; 2],
+) {
+    let Some((lat, lon)) = crosshair else {
+        return;
+    };
+    if let Some(cell) = canvas_cell(inner, lon, lat, x_bounds, y_bounds) {
+        buf[cell].set_symbol("╋").set_style(
+            Style::default()
+                .fg(catppuccin::RED)
+                .add_modifier(Modifier::BOLD),
+        );
     }
 }
 
+/// the time converter's from and to cities when both are in NZ
+const TRIP_FROM: Color = catppuccin::BLUE;
+const TRIP_TO: Color = catppuccin::MAUVE;
+
+/// coastline colour when the rainbow cycling is off and no colour is configured
+const STATIC_COASTLINE: Color = catppuccin::OVERLAY1;
+
+/// background behind the part of the NZ map where the aurora may be visible
+const AURORA_TINT: Color = Color::Rgb(30, 56, 48);
+
+/// a `[[markers]]` pin on the world map
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldPin {
+    pub label: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub color: Color,
+}
+
 #[derive(Default)]
 pub struct WorldMapCanvas {
     tick: u64,
@@ -239,6 +1083,16 @@ pub struct WorldMapCanvas {
     secondary: Option<WorldMarker>,
     focused: bool,
     title: Option<String>,
+    viewport: MapViewport,
+    airports: bool,
+    pins: Vec<WorldPin>,
+    iss: Vec<(f64, f64)>,
+    rainbow: bool,
+    crosshair: Option<(f64, f64)>,
+    marker_style: MapMarkerStyle,
+    coastline: Option<Color>,
+    homeward: Option<[(f64, f64); 2]>,
+    projection: WorldProjection,
 }
 
 impl WorldMapCanvas {
@@ -270,18 +1124,102 @@ impl WorldMapCanvas {
         self.title = Some(title.into());
         self
     }
+
+    pub fn viewport(mut self, viewport: MapViewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn pins(mut self, pins: Vec<WorldPin>) -> Self {
+        self.pins = pins;
+        self
+    }
+
+    /// cycle the coastline through the rainbow rather than drawing it in one colour
+    pub fn rainbow(mut self, rainbow: bool) -> Self {
+        self.rainbow = rainbow;
+        self
+    }
+
+    /// the ISS's recent (lat, lon) positions, oldest first; the last is drawn as the station
+    pub fn iss(mut self, trail: Vec<(f64, f64)>) -> Self {
+        self.iss = trail;
+        self
+    }
+
+    /// mark the long-haul hubs; their codes only fit once zoomed in
+    pub fn airports(mut self, airports: bool) -> Self {
+        self.airports = airports;
+        self
+    }
+
+    /// a (lat, lon) to mark with a cross, for reading coordinates off the map
+    pub fn crosshair(mut self, crosshair: Option<(f64, f64)>) -> Self {
+        self.crosshair = crosshair;
+        self
+    }
+
+    /// braille, half blocks or dots, for terminals whose fonts lack braille
+    pub fn marker_style(mut self, style: MapMarkerStyle) -> Self {
+        self.marker_style = style;
+        self
+    }
+
+    /// draw the coastline in one colour, however the rainbow animation is set
+    pub fn coastline(mut self, color: Option<Color>) -> Self {
+        self.coastline = color;
+        self
+    }
+
+    /// (lat, lon) of NZ and home, for a plane looping along the arc between them
+    pub fn homeward(mut self, ends: Option<[(f64, f64); 2]>) -> Self {
+        self.homeward = ends;
+        self
+    }
+
+    /// centre the map on Greenwich or the Pacific
+    pub fn projection(mut self, projection: WorldProjection) -> Self {
+        self.projection = projection;
+        self
+    }
 }
 
+/// frames for one flight home; at the usual frame rate a leisurely quarter minute or so
+const HOMEWARD_FRAMES: usize = 240;
+
+/// where the flight home is after `tick` frames: easing out of NZ and into home,
+/// then starting again
+fn homeward_position(route: &[(f64, f64)], tick: usize) -> Option<(f64, f64)> {
+    let last = route.len().checked_sub(1)?;
+    let t = (tick % HOMEWARD_FRAMES) as f64 / (HOMEWARD_FRAMES - 1) as f64;
+    let eased = t * t * (3.0 - 2.0 * t);
+    Some(route[(eased * last as f64).round() as usize])
+}
+
+/// (lon, lat) points along the great circle between two markers, the shortest way round
+/// (so a Pacific crossing wraps at the date line rather than cutting across the map)
 fn route_points(from: &WorldMarker, to: &WorldMarker, steps: usize) -> Vec<(f64, f64)> {
-    let steps = steps.max(2);
-    let mut points = Vec::with_capacity(steps);
-    for i in 0..steps {
-        let t = i as f64 / (steps - 1) as f64;
-        let lon = from.lon + (to.lon - from.lon) * t;
-        let lat = from.lat + (to.lat - from.lat) * t;
-        points.push((lon, lat));
+    let to_vector = |marker: &WorldMarker| {
+        let (lat, lon) = (marker.lat.to_radians(), marker.lon.to_radians());
+        [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+    };
+    let (a, b) = (to_vector(from), to_vector(to));
+    let dot = (a[0] * b[0] + a[1] * b[1] + a[2] * b[2]).clamp(-1.0, 1.0);
+    let angle = dot.acos();
+    if angle.sin().abs() < 1e-9 {
+        return vec![(from.lon, from.lat)];
     }
-    points
+
+    let steps = steps.max(2);
+    (0..steps)
+        .map(|i| {
+            let t = i as f64 / (steps - 1) as f64;
+            let wa = ((1.0 - t) * angle).sin() / angle.sin();
+            let wb = (t * angle).sin() / angle.sin();
+            let [x, y, z] = [0, 1, 2].map(|k| wa * a[k] + wb * b[k]);
+            (y.atan2(x).to_degrees(), z.atan2(x.hypot(y)).to_degrees())
+        })
+        .collect()
 }
 
 impl Widget for WorldMapCanvas {
@@ -311,17 +1249,87 @@ impl Widget for WorldMapCanvas {
             Theme::block_title()
         };
 
-        let title = self.title.unwrap_or_else(|| "🌍 World map".to_string());
+        let mut title = self.title.unwrap_or_else(|| "🌍 World map".to_string());
+        if let Some(label) = self.viewport.label() {
+            title.push_str(&format!(" {}", label));
+        }
+        let projection = self.projection;
+        let (x_bounds, y_bounds) = self
+            .viewport
+            .bounds(projection.lon_bounds(), [WORLD_LAT_MIN, WORLD_LAT_MAX]);
+        // everything below is placed in the projection's longitudes
+        let wrap_points = |points: Vec<(f64, f64)>| -> Vec<(f64, f64)> {
+            points
+                .into_iter()
+                .map(|(lon, lat)| (projection.wrap(lon), lat))
+                .collect()
+        };
 
         let primary = self.primary.clone();
         let secondary = self.secondary.clone();
         let route = match (&primary, &secondary) {
-            (Some(from), Some(to)) => route_points(from, to, 40),
+            (Some(from), Some(to)) => wrap_points(route_points(from, to, 80)),
             _ => Vec::new(),
         };
+        // a dot travelling the route, start to finish
+        let traveller = (!route.is_empty()).then(|| route[(tick / 2) % route.len()]);
+
+        // the flight home, unless the route above already covers the same two places
+        let marker_at = |(lat, lon): (f64, f64)| WorldMarker {
+            label: String::new(),
+            lat,
+            lon,
+            temp_c: None,
+        };
+        let same_place = |marker: &Option<WorldMarker>, (lat, lon): (f64, f64)| {
+            marker
+                .as_ref()
+                .is_some_and(|m| (m.lat - lat).abs() < 0.5 && (m.lon - lon).abs() < 0.5)
+        };
+        let homeward = self
+            .homeward
+            .filter(|&[nz, home]| {
+                !(same_place(&primary, nz) && same_place(&secondary, home)
+                    || same_place(&primary, home) && same_place(&secondary, nz))
+            })
+            .and_then(|[nz, home]| {
+                homeward_position(&route_points(&marker_at(nz), &marker_at(home), 100), tick)
+            })
+            .map(|(lon, lat)| (projection.wrap(lon), lat));
 
         let rainbow = Theme::rainbow_colors();
-        let map_color = rainbow[(tick / 4) % rainbow.len()];
+        let map_color = match self.coastline {
+            Some(color) => color,
+            None if self.rainbow => rainbow[(tick / 4) % rainbow.len()],
+            None => STATIC_COASTLINE,
+        };
+        let zoomed = self.viewport.is_zoomed();
+        let hubs = if self.airports { HUB_AIRPORTS } else { &[] };
+        let pins = self.pins.clone();
+        let iss_trail = wrap_points(self.iss.iter().map(|&(lat, lon)| (lon, lat)).collect());
+        let inner = area.inner(Margin::new(1, 1));
+        let resolution = if zoomed {
+            MapResolution::High
+        } else {
+            MapResolution::Low
+        };
+
+        // ratatui's coastline only runs from -180° to 180°, so past the date line it's
+        // drawn again a turn to the west, underneath everything else
+        if x_bounds[1] > WORLD_LON_MAX {
+            Canvas::default()
+                .background_color(catppuccin::BASE)
+                .marker(canvas_marker(self.marker_style))
+                .x_bounds([x_bounds[0] - 360.0, x_bounds[1] - 360.0])
+                .y_bounds(y_bounds)
+                .paint(|ctx| {
+                    ctx.draw(&Map {
+                        color: map_color,
+                        resolution,
+                    })
+                })
+                .render(inner, buf);
+        }
 
         let canvas = Canvas::default()
             .block(
@@ -333,40 +1341,257 @@ impl Widget for WorldMapCanvas {
                     .title(Span::styled(format!(" {} ", title), title_style)),
             )
             .background_color(catppuccin::BASE)
-            .marker(Marker::Braille)
-            .x_bounds([WORLD_LON_MIN, WORLD_LON_MAX])
-            .y_bounds([WORLD_LAT_MIN, WORLD_LAT_MAX])
+            .marker(canvas_marker(self.marker_style))
+            .x_bounds(x_bounds)
+            .y_bounds(y_bounds)
             .paint(move |ctx| {
+                // the low-resolution coastline turns blocky once zoomed in
                 ctx.draw(&Map {
                     color: map_color,
-                    resolution: MapResolution::Low,
+                    resolution,
                 });
 
+                for hub in hubs {
+                    let lon = projection.wrap(hub.lon);
+                    ctx.draw(&Points {
+                        coords: &[(lon, hub.lat)],
+                        color: catppuccin::PEACH,
+                    });
+                    if zoomed {
+                        ctx.print(
+                            lon + 0.5,
+                            hub.lat,
+                            Span::styled(hub.code, Style::default().fg(catppuccin::PEACH)),
+                        );
+                    }
+                }
+
+                for pin in &pins {
+                    let lon = projection.wrap(pin.lon);
+                    ctx.draw(&Points {
+                        coords: &[(lon, pin.lat)],
+                        color: pin.color,
+                    });
+                    ctx.print(
+                        lon + 1.5,
+                        pin.lat + 1.0,
+                        Span::styled(pin.label.clone(), Style::default().fg(pin.color)),
+                    );
+                }
+
                 if !route.is_empty() {
                     ctx.draw(&Points {
                         coords: &route,
                         color: catppuccin::OVERLAY0,
                     });
                 }
+                if let Some(point) = traveller {
+                    ctx.draw(&Points {
+                        coords: &[point],
+                        color: catppuccin::YELLOW,
+                    });
+                }
+
+                if let Some((lon, lat)) = homeward {
+                    ctx.print(
+                        lon,
+                        lat,
+                        Span::styled("✈", Style::default().fg(catppuccin::LAVENDER)),
+                    );
+                }
+
+                if let Some((&(lon, lat), track)) = iss_trail.split_last() {
+                    ctx.draw(&Points {
+                        coords: track,
+                        color: catppuccin::OVERLAY1,
+                    });
+                    ctx.draw(&Points {
+                        coords: &[(lon, lat)],
+                        color: catppuccin::TEXT,
+                    });
+                    ctx.print(
+                        lon + 1.5,
+                        lat + 1.0,
+                        Span::styled("🛰 ISS", Style::default().fg(catppuccin::TEXT)),
+                    );
+                }
 
                 if let Some(marker) = &primary {
+                    let lon = projection.wrap(marker.lon);
                     ctx.draw(&Points {
-                        coords: &[(marker.lon, marker.lat)],
+                        coords: &[(lon, marker.lat)],
                         color: catppuccin::SAPPHIRE,
                     });
-                    ctx.print(marker.lon + 1.5, marker.lat + 1.0, marker.label.clone());
+                    ctx.print(lon + 1.5, marker.lat + 1.0, marker.label_line());
                 }
 
                 if let Some(marker) = &secondary {
+                    let lon = projection.wrap(marker.lon);
                     ctx.draw(&Points {
-                        coords: &[(marker.lon, marker.lat)],
+                        coords: &[(lon, marker.lat)],
                         color: catppuccin::MAUVE,
                     });
-                    ctx.print(marker.lon + 1.5, marker.lat + 1.0, marker.label.clone());
+                    ctx.print(lon + 1.5, marker.lat + 1.0, marker.label_line());
                 }
             });
 
         canvas.render(area, buf);
+        draw_crosshair(buf, inner, self.crosshair, x_bounds, y_bounds);
+    }
+}
+
+/// a key to a map's markers and layers, drawn in the bottom-right corner of the panel
+pub struct MapLegend {
+    lines: Vec<Line<'static>>,
+}
+
+/// one legend row: a coloured symbol and what it stands for
+fn legend_line(symbol: &'static str, color: Color, label: &'static str) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(symbol, Style::default().fg(color)),
+        Span::styled(
+            format!(" {}", label),
+            Style::default().fg(catppuccin::SUBTEXT1),
+        ),
+    ])
+}
+
+/// which of the NZ map's optional layers are on show, for its legend
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NzLegendLayers {
+    pub towns: bool,
+    pub quakes: bool,
+    pub ferry: bool,
+    pub airports: bool,
+    pub heat: bool,
+    pub trip: bool,
+    pub highways: bool,
+}
+
+impl MapLegend {
+    /// the NZ map, listing only the layers that are switched on
+    pub fn nz(layers: NzLegendLayers, animations: AnimationLayers) -> Self {
+        let NzLegendLayers {
+            towns,
+            quakes,
+            ferry,
+            airports,
+            heat,
+            trip,
+            highways,
+        } = layers;
+        let mut lines = vec![
+            legend_line("●", catppuccin::SAPPHIRE, "city"),
+            legend_line("★", catppuccin::YELLOW, "weather city"),
+            Line::from(vec![
+                Span::styled("18°", Theme::temperature(18)),
+                Span::styled(
+                    " cached temperature",
+                    Style::default().fg(catppuccin::SUBTEXT1),
+                ),
+            ]),
+        ];
+        if towns {
+            lines.push(legend_line("·", catppuccin::GREEN, "town"));
+        }
+        if trip {
+            lines.push(legend_line("●", TRIP_FROM, "converting from"));
+            lines.push(legend_line("●", TRIP_TO, "converting to"));
+        }
+        if quakes {
+            let mut quake = vec![2.5, 3.5, 4.5, 5.5]
+                .into_iter()
+                .map(|magnitude| Span::styled("●", Style::default().fg(quake_style(magnitude).1)))
+                .collect::<Vec<_>>();
+            quake.push(Span::styled(
+                " quake, M<3 to M5+",
+                Style::default().fg(catppuccin::SUBTEXT1),
+            ));
+            lines.push(Line::from(quake));
+        }
+        if highways {
+            lines.push(legend_line("─", HIGHWAY, "state highway"));
+        }
+        if ferry {
+            lines.push(legend_line("·", catppuccin::LAVENDER, "Cook Strait ferry"));
+        }
+        if airports {
+            lines.push(legend_line("✈", catppuccin::PEACH, "airport"));
+        }
+        if heat {
+            let mut scale = [0.0, 10.0, 20.0, 28.0]
+                .into_iter()
+                .map(|temp| Span::styled("━", Style::default().fg(heat_colour(temp))))
+                .collect::<Vec<_>>();
+            scale.push(Span::styled(
+                " coast by temperature, 0° to 28°",
+                Style::default().fg(catppuccin::SUBTEXT1),
+            ));
+            lines.push(Line::from(scale));
+        }
+        if animations.waves {
+            lines.push(legend_line("∿", catppuccin::GREEN, "waves"));
+        }
+        if animations.birds {
+            lines.push(legend_line("·", catppuccin::YELLOW, "birds and kiwi"));
+        }
+        Self { lines }
+    }
+
+    /// the world map; `route` when two places are marked
+    pub fn world(route: bool, airports: bool, pins: bool, iss: bool, homeward: bool) -> Self {
+        let mut lines = vec![
+            legend_line("●", catppuccin::SAPPHIRE, "from"),
+            legend_line("●", catppuccin::MAUVE, "to"),
+        ];
+        if route {
+            lines.push(legend_line("·", catppuccin::OVERLAY0, "great-circle route"));
+            lines.push(legend_line("●", catppuccin::YELLOW, "in flight"));
+        }
+        if airports {
+            lines.push(legend_line("●", catppuccin::PEACH, "airport hub"));
+        }
+        if pins {
+            lines.push(legend_line("●", catppuccin::GREEN, "your markers"));
+        }
+        if iss {
+            lines.push(legend_line("🛰", catppuccin::TEXT, "ISS and its track"));
+        }
+        if homeward {
+            lines.push(legend_line("✈", catppuccin::LAVENDER, "flying home"));
+        }
+        Self { lines }
+    }
+}
+
+impl Widget for MapLegend {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // inside the map's own border, and only when it fits whole
+        let inner = area.inner(Margin::new(1, 1));
+        let width = self.lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+        let height = self.lines.len() as u16 + 2;
+        if width > inner.width || height > inner.height {
+            return;
+        }
+        let corner = Rect::new(
+            inner.right() - width,
+            inner.bottom() - height,
+            width,
+            height,
+        );
+
+        Clear.render(corner, buf);
+        Paragraph::new(self.lines)
+            .style(Style::default().bg(catppuccin::BASE))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(catppuccin::SURFACE2))
+                    .title(Span::styled(" Legend ", Theme::block_title()))
+                    .padding(Padding::horizontal(1)),
+            )
+            .render(corner, buf);
     }
 }
 
@@ -423,3 +1648,645 @@ impl Widget for Sparkles {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn viewport_zooms_about_its_centre_and_stays_in_bounds() {
+        let full_x = [NZ_LON_MIN, NZ_LON_MAX];
+        let full_y = [NZ_LAT_MIN, NZ_LAT_MAX];
+        let mut view = MapViewport::default();
+        assert_eq!(view.bounds(full_x, full_y), (full_x, full_y));
+
+        // panning the full view has nowhere to go
+        view.pan(0.25, 0.0);
+        assert_eq!(view.bounds(full_x, full_y), (full_x, full_y));
+
+        view.zoom_in();
+        let (x, y) = view.bounds(full_x, full_y);
+        assert!((x[1] - x[0] - 6.5).abs() < 1e-9);
+        assert!((y[0] - -44.125).abs() < 1e-9);
+
+        // panning east stops at the edge of the full bounds
+        for _ in 0..10 {
+            view.pan(0.25, 0.0);
+        }
+        assert!((view.bounds(full_x, full_y).0[1] - NZ_LON_MAX).abs() < 1e-9);
+
+        for _ in 0..20 {
+            view.zoom_in();
+        }
+        assert_eq!(view.zoom, MAX_MAP_ZOOM);
+        for _ in 0..20 {
+            view.zoom_out();
+        }
+        assert_eq!(view.bounds(full_x, full_y), (full_x, full_y));
+    }
+
+    #[test]
+    fn centred_views_round_trip_through_their_centre() {
+        let (x, y) = ([NZ_LON_MIN, NZ_LON_MAX], [NZ_LAT_MIN, NZ_LAT_MAX]);
+        let view = MapViewport::centred(5, 174.8, -41.3, x, y);
+        let (zoom, lon, lat) = view.centre(x, y);
+        assert_eq!(zoom, 5);
+        assert!((lon - 174.8).abs() < 1e-9 && (lat + 41.3).abs() < 1e-9);
+
+        // a centre near the edge is pulled in so the view stays on the map
+        let edge = MapViewport::centred(1, NZ_LON_MIN, NZ_LAT_MIN, x, y);
+        assert_eq!((edge.center_x, edge.center_y), (0.25, 0.25));
+    }
+
+    #[test]
+    fn region_presets_show_their_bounds_then_zoom_from_there() {
+        let full_x = [NZ_LON_MIN, NZ_LON_MAX];
+        let full_y = [NZ_LAT_MIN, NZ_LAT_MAX];
+        let mut view = MapViewport::region(&NZ_REGIONS[1], full_x, full_y);
+        let (x, y) = view.bounds(full_x, full_y);
+        assert!((x[0] - 166.2).abs() < 1e-9 && (x[1] - 174.6).abs() < 1e-9);
+        assert!((y[0] - -47.4).abs() < 1e-9 && (y[1] - -40.3).abs() < 1e-9);
+        assert!(view.is_zoomed());
+        assert_eq!(view.label().as_deref(), Some("South Island"));
+
+        // the south island is over half the country wide, so zooming in carries on
+        // from the first zoom step, centred on the island
+        view.zoom_in();
+        assert_eq!(view.zoom, 1);
+        assert_eq!(view.label().as_deref(), Some("×2"));
+        let (x, _) = view.bounds(full_x, full_y);
+        assert!(((x[0] + x[1]) / 2.0 - 170.4).abs() < 1e-9);
+
+        // regions reaching past the canvas are cut at its edge
+        let world_x = [WORLD_LON_MIN, WORLD_LON_MAX];
+        let world_y = [WORLD_LAT_MIN, WORLD_LAT_MAX];
+        let americas = MapViewport::region(&WORLD_REGIONS[2], world_x, world_y);
+        assert_eq!(americas.bounds(world_x, world_y).1, [-56.0, 72.0]);
+    }
+
+    #[test]
+    fn clicks_map_to_the_nearest_nz_city() {
+        // 26 x 27 canvas cells inside the border, half a degree each
+        let area = Rect::new(10, 5, 28, 29);
+        let bounds = ([NZ_LON_MIN, NZ_LON_MAX], [NZ_LAT_MIN, NZ_LAT_MAX]);
+        assert_eq!(canvas_point(area, 10, 5, bounds.0, bounds.1), None);
+
+        let (lon, lat) = canvas_point(area, 11, 6, bounds.0, bounds.1).unwrap();
+        assert!((lon - 166.25).abs() < 1e-9 && (lat - -34.25).abs() < 1e-9);
+
+        let nearest = |column, row| {
+            let (lon, lat) = canvas_point(area, column, row, bounds.0, bounds.1).unwrap();
+            let index = nearest_point(NZ_CITIES.iter().map(|c| (c.lat, c.lon)), lat, lon);
+            NZ_CITIES[index.unwrap()].code
+        };
+        // cell columns sit at 166 + (column - 11) / 2 degrees, rows at -34 - (row - 6) / 2
+        assert_eq!(nearest(28, 20), "WLG");
+        assert_eq!(nearest(28, 11), "AKL");
+        assert_eq!(nearest(20, 29), "DUD");
+    }
+
+    #[test]
+    fn labels_step_around_each_other() {
+        let area = Rect::new(0, 0, 20, 5);
+        let placed = place_labels(
+            area,
+            &[
+                (Position::new(2, 1), 3),
+                (Position::new(4, 1), 3),
+                (Position::new(18, 3), 4),
+            ],
+            |_| false,
+        );
+        // the first label would cover the second point and has no room on the left,
+        // so it moves up a row; the second keeps its spot on the right
+        assert_eq!(placed[0], Some(Position::new(3, 0)));
+        assert_eq!(placed[1], Some(Position::new(5, 1)));
+        // near the right edge a label flips to the left of its point
+        assert_eq!(placed[2], Some(Position::new(14, 3)));
+
+        // with nowhere to go a label is dropped
+        let tiny = Rect::new(0, 0, 3, 1);
+        assert_eq!(
+            place_labels(tiny, &[(Position::new(1, 0), 4)], |_| false),
+            vec![None]
+        );
+
+        // a spot over the drawing gives way to a clear one, but is used if it's all there is
+        let coast = Rect::new(3, 1, 4, 1);
+        let over_coast = |spot: Rect| spot.intersects(coast);
+        let placed = place_labels(area, &[(Position::new(2, 1), 3)], over_coast);
+        assert_eq!(placed, vec![Some(Position::new(3, 0))]);
+        let narrow = Rect::new(2, 1, 6, 1);
+        let placed = place_labels(narrow, &[(Position::new(2, 1), 3)], over_coast);
+        assert_eq!(placed, vec![Some(Position::new(3, 1))]);
+    }
+
+    #[test]
+    fn trip_between_nz_cities_is_joined_and_coloured() {
+        let area = Rect::new(0, 0, 60, 30);
+        let render = |trip| {
+            let mut buf = Buffer::empty(area);
+            NzMapCanvas::new()
+                .animations(AnimationLayers::NONE)
+                .trip(trip)
+                .render(area, &mut buf);
+            buf
+        };
+        let colours = |buf: &Buffer| area.positions().map(|pos| buf[pos].fg).collect::<Vec<_>>();
+
+        let plain = render(None);
+        assert!(!colours(&plain).contains(&TRIP_FROM));
+        let trip = render(Some([
+            ("WLG".to_string(), (-41.2865, 174.7762)),
+            ("ZQN".to_string(), (-45.0312, 168.6626)),
+        ]));
+        let trip_colours = colours(&trip);
+        assert!(trip_colours.contains(&TRIP_FROM) && trip_colours.contains(&TRIP_TO));
+        assert!(trip_colours.contains(&catppuccin::OVERLAY0));
+        // Queenstown isn't a main centre, so it gets a label of its own
+        let text: String = area.positions().map(|pos| trip[pos].symbol()).collect();
+        assert!(text.contains("ZQN"));
+    }
+
+    #[test]
+    fn small_panels_show_dots_without_labels() {
+        let text = |area: Rect| {
+            let mut buf = Buffer::empty(area);
+            NzMapCanvas::new()
+                .animations(AnimationLayers::NONE)
+                .render(area, &mut buf);
+            area.positions()
+                .map(|pos| buf[pos].symbol().to_string())
+                .collect::<String>()
+        };
+        assert!(text(Rect::new(0, 0, 40, 20)).contains("AKL"));
+        let small = text(Rect::new(0, 0, 20, 10));
+        assert!(!small.contains("AKL") && !small.contains("WLG"));
+    }
+
+    #[test]
+    fn routes_follow_the_great_circle() {
+        let marker = |lat, lon| WorldMarker {
+            label: String::new(),
+            lat,
+            lon,
+            temp_c: None,
+        };
+
+        let quarter = route_points(&marker(0.0, 0.0), &marker(0.0, 90.0), 3);
+        assert!((quarter[1].0 - 45.0).abs() < 1e-9 && quarter[1].1.abs() < 1e-9);
+
+        // wellington to santiago bows south of both ends
+        let south = route_points(&marker(-41.29, 174.78), &marker(-33.45, -70.67), 41);
+        assert!(south[20].1 < -50.0);
+
+        // auckland to los angeles crosses the date line instead of the whole map
+        let pacific = route_points(&marker(-36.85, 174.76), &marker(34.05, -118.24), 41);
+        assert!(
+            pacific
+                .iter()
+                .all(|(lon, _)| *lon >= 174.0 || *lon <= -118.0)
+        );
+        assert_eq!(pacific.len(), 41);
+        assert!((pacific[40].0 - -118.24).abs() < 1e-6);
+    }
+
+    #[test]
+    fn city_labels_carry_cached_temperatures() {
+        let area = Rect::new(0, 0, 60, 30);
+        let mut buf = Buffer::empty(area);
+        NzMapCanvas::new()
+            .temperatures(vec![("WLG", 14), ("AKL", 26)])
+            .render(area, &mut buf);
+
+        let rows: Vec<String> = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        let find = |text: &str| {
+            rows.iter().enumerate().find_map(|(y, row)| {
+                row.find(text)
+                    .map(|x| (row[..x].chars().count() as u16, y as u16))
+            })
+        };
+
+        let (x, y) = find("WLG 14°").expect("wellington label with temperature");
+        assert_eq!(buf[(x + 4, y)].fg, catppuccin::GREEN);
+        let (x, y) = find("AKL 26°").expect("auckland label with temperature");
+        assert_eq!(buf[(x + 4, y)].fg, catppuccin::RED);
+        // cities without cached weather keep a bare code
+        assert!(find("CHC").is_some());
+        assert_eq!(rows.concat().matches('°').count(), 2);
+    }
+
+    #[test]
+    fn quakes_grow_with_magnitude_and_lead_the_title() {
+        let quake = |magnitude, lat, lon| Quake {
+            time: chrono::Utc::now(),
+            magnitude,
+            depth_km: 10.0,
+            lat,
+            lon,
+            locality: String::new(),
+        };
+        let area = Rect::new(0, 0, 60, 30);
+        let render = |quakes| {
+            let mut buf = Buffer::empty(area);
+            NzMapCanvas::new()
+                .quakes(quakes)
+                .note(Some("M5.6 40 km east of Te Araroa, 3h ago".to_string()))
+                .render(area, &mut buf);
+            buf
+        };
+        let plain = render(Vec::new());
+        let buf = render(vec![quake(5.6, -37.5, 179.0), quake(2.4, -42.0, 168.0)]);
+
+        let title: String = (0..area.width).map(|x| buf[(x, 0)].symbol()).collect();
+        assert!(title.contains("Aotearoa New Zealand · M5.6 40 km east of Te Araroa"));
+
+        // cells the quakes turned to each colour, over the same map without them
+        let added = |color| {
+            (0..area.height)
+                .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+                .filter(|&(x, y)| buf[(x, y)].fg == color && plain[(x, y)] != buf[(x, y)])
+                .count()
+        };
+        // a radius-3 disc spans several braille cells; a small quake is a single dot
+        assert!(added(catppuccin::RED) >= 4);
+        assert_eq!(added(catppuccin::GREEN), 1);
+    }
+
+    #[test]
+    fn ferry_runs_between_the_terminals_through_cook_strait() {
+        let route = ferry_points();
+        let wellington = NZ_CITIES.iter().find(|c| c.code == "WLG").unwrap();
+        let (lon, lat) = route[0];
+        assert!((lon - wellington.lon).abs() < 0.05 && (lat - wellington.lat).abs() < 0.05);
+        assert_eq!(route.last(), FERRY_ROUTE.last());
+        // evenly filled, and never far from the strait
+        assert!(
+            route
+                .windows(2)
+                .all(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1) <= 0.011)
+        );
+        assert!(
+            route
+                .iter()
+                .all(|(lon, lat)| (173.9..174.9).contains(lon) && (-41.45..-41.15).contains(lat))
+        );
+    }
+
+    #[test]
+    fn configured_coastline_colour_stops_the_rainbow() {
+        let area = Rect::new(0, 0, 60, 30);
+        let fixed = Color::Rgb(1, 2, 3);
+        // inside the border, away from the title
+        let colours = |buf: &Buffer| {
+            (1..area.height - 1)
+                .flat_map(|y| (1..area.width - 1).map(move |x| (x, y)))
+                .filter(|&(x, y)| buf[(x, y)].symbol() != " ")
+                .map(|(x, y)| buf[(x, y)].fg)
+                .collect::<std::collections::HashSet<_>>()
+        };
+
+        for tick in [0, 9] {
+            let mut buf = Buffer::empty(area);
+            NzMapCanvas::new()
+                .tick(tick)
+                .animations(AnimationLayers::default())
+                .coastline(Some(fixed))
+                .render(area, &mut buf);
+            assert!(colours(&buf).contains(&fixed));
+
+            let mut buf = Buffer::empty(area);
+            WorldMapCanvas::new()
+                .tick(tick)
+                .rainbow(true)
+                .coastline(Some(fixed))
+                .render(area, &mut buf);
+            let world = colours(&buf);
+            assert!(world.contains(&fixed));
+            assert!(!Theme::rainbow_colors().iter().any(|c| world.contains(c)));
+        }
+    }
+
+    #[test]
+    fn heat_layer_shades_the_coast_by_the_nearest_temperature() {
+        let area = Rect::new(0, 0, 60, 30);
+        let colours = |heat: Vec<(f64, f64, f64)>| {
+            let mut buf = Buffer::empty(area);
+            NzMapCanvas::new()
+                .animations(AnimationLayers::NONE)
+                .heat(heat)
+                .render(area, &mut buf);
+            (1..area.height - 1)
+                .flat_map(|y| (1..area.width - 1).map(move |x| (x, y)))
+                .map(|pos| buf[pos].fg)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(colours(Vec::new()).contains(&STATIC_COASTLINE));
+        // a cold south and a warm north split the coast between them
+        let shaded = colours(vec![(-36.0, 174.0, 28.0), (-46.0, 169.0, 0.0)]);
+        assert!(!shaded.contains(&STATIC_COASTLINE));
+        assert!(shaded.contains(&heat_colour(28.0)) && shaded.contains(&heat_colour(0.0)));
+    }
+
+    #[test]
+    fn detailed_coastline_draws_the_bundled_outline() {
+        assert_eq!(NZ_COASTLINE.len(), 3);
+        assert!(NZ_COASTLINE.iter().all(|ring| ring.first() == ring.last()));
+
+        let area = Rect::new(0, 0, 60, 30);
+        let coast = |detailed| {
+            let mut buf = Buffer::empty(area);
+            NzMapCanvas::new()
+                .animations(AnimationLayers::NONE)
+                .detailed_coastline(detailed)
+                .render(area, &mut buf);
+            (1..area.height - 1)
+                .flat_map(|y| (1..area.width - 1).map(move |x| (x, y)))
+                .filter(|&pos| buf[pos].fg == STATIC_COASTLINE && buf[pos].symbol() != " ")
+                .filter_map(|(x, y)| {
+                    canvas_point(
+                        area,
+                        x,
+                        y,
+                        [NZ_LON_MIN, NZ_LON_MAX],
+                        [NZ_LAT_MIN, NZ_LAT_MAX],
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let detailed = coast(true);
+        assert_ne!(detailed, coast(false));
+        // Stewart Island is there, south of Foveaux Strait
+        assert!(detailed.iter().any(|&(_, lat)| lat < -47.0));
+    }
+
+    #[test]
+    fn crosshair_marks_its_cell_on_either_map() {
+        let area = Rect::new(0, 0, 60, 30);
+        let crosses = |buf: &Buffer| {
+            (0..area.height)
+                .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+                .filter(|&(x, y)| buf[(x, y)].symbol() == "╋")
+                .collect::<Vec<_>>()
+        };
+
+        let mut buf = Buffer::empty(area);
+        NzMapCanvas::new().render(area, &mut buf);
+        assert!(crosses(&buf).is_empty());
+
+        // the north-west corner of the NZ map is the first inner cell
+        let mut buf = Buffer::empty(area);
+        NzMapCanvas::new()
+            .crosshair(Some((NZ_LAT_MAX, NZ_LON_MIN)))
+            .render(area, &mut buf);
+        assert_eq!(crosses(&buf), vec![(1, 1)]);
+
+        let mut buf = Buffer::empty(area);
+        WorldMapCanvas::new()
+            .crosshair(Some((WORLD_LAT_MIN, WORLD_LON_MAX)))
+            .render(area, &mut buf);
+        assert_eq!(crosses(&buf), vec![(58, 28)]);
+    }
+
+    #[test]
+    fn flight_home_loops_along_the_arc_and_gives_way_to_the_route() {
+        let route: Vec<(f64, f64)> = (0..=10).map(|i| (i as f64, 0.0)).collect();
+        assert_eq!(homeward_position(&route, 0), Some((0.0, 0.0)));
+        assert_eq!(
+            homeward_position(&route, HOMEWARD_FRAMES - 1),
+            Some((10.0, 0.0))
+        );
+        assert_eq!(homeward_position(&route, HOMEWARD_FRAMES), Some((0.0, 0.0)));
+        // eased, so it lingers near either end and hurries through the middle
+        let (early, _) = homeward_position(&route, HOMEWARD_FRAMES / 10).unwrap();
+        assert!(early < 1.0);
+        assert_eq!(homeward_position(&[], 5), None);
+
+        let area = Rect::new(0, 0, 60, 30);
+        let wellington = (-41.29, 174.78);
+        let london = (51.51, -0.13);
+        let planes = |canvas: WorldMapCanvas| {
+            let mut buf = Buffer::empty(area);
+            canvas
+                .tick(HOMEWARD_FRAMES as u64 / 2)
+                .homeward(Some([wellington, london]))
+                .render(area, &mut buf);
+            buf.content()
+                .iter()
+                .filter(|cell| cell.symbol() == "✈")
+                .count()
+        };
+        assert_eq!(planes(WorldMapCanvas::new()), 1);
+
+        let marker = |(lat, lon): (f64, f64)| {
+            Some(WorldMarker {
+                label: String::new(),
+                lat,
+                lon,
+                temp_c: None,
+            })
+        };
+        let same_trip = WorldMapCanvas::new()
+            .primary(marker(london))
+            .secondary(marker(wellington));
+        assert_eq!(planes(same_trip), 0);
+    }
+
+    #[test]
+    fn pacific_projection_brings_nz_in_from_the_edge() {
+        let area = Rect::new(0, 0, 80, 30);
+        let wellington = Some(WorldMarker {
+            label: "WLG".to_string(),
+            lat: -41.29,
+            lon: 174.78,
+            temp_c: None,
+        });
+        let render = |projection| {
+            let mut buf = Buffer::empty(area);
+            WorldMapCanvas::new()
+                .primary(wellington.clone())
+                .projection(projection)
+                .render(area, &mut buf);
+            buf
+        };
+        let label_column = |buf: &Buffer| {
+            (0..area.height).find_map(|y| {
+                let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+                row.find("WLG").map(|byte| row[..byte].chars().count())
+            })
+        };
+        // against the right-hand edge, where the label may not even fit
+        let greenwich = label_column(&render(WorldProjection::Greenwich));
+        assert!(greenwich.is_none_or(|column| column > 70));
+        let pacific = render(WorldProjection::Pacific);
+        assert!((40..56).contains(&label_column(&pacific).unwrap()));
+
+        // the Americas, west of the date line, are drawn on the right-hand side
+        let coast_east = (1..area.height - 1)
+            .flat_map(|y| (62..area.width - 1).map(move |x| (x, y)))
+            .filter(|&pos| pacific[pos].symbol() != " ")
+            .count();
+        assert!(coast_east > 20);
+    }
+
+    #[test]
+    fn highways_trace_the_bundled_routes_and_label_once_zoomed() {
+        let sh1: Vec<_> = NZ_HIGHWAYS
+            .iter()
+            .filter(|(reference, _)| *reference == "SH1")
+            .collect();
+        assert_eq!(sh1.len(), 2, "SH1 runs in both islands");
+        assert!(NZ_HIGHWAYS.iter().all(|(_, line)| line.len() >= 2));
+
+        let area = Rect::new(0, 0, 60, 30);
+        let render = |canvas: NzMapCanvas| {
+            let mut buf = Buffer::empty(area);
+            canvas
+                .animations(AnimationLayers::NONE)
+                .render(area, &mut buf);
+            buf
+        };
+        let road_cells = |buf: &Buffer| {
+            buf.content()
+                .iter()
+                .filter(|cell| cell.fg == HIGHWAY)
+                .count()
+        };
+        assert_eq!(road_cells(&render(NzMapCanvas::new())), 0);
+        let roads = render(NzMapCanvas::new().highways(true));
+        assert!(road_cells(&roads) > 30);
+        let text =
+            |buf: &Buffer| -> String { buf.content().iter().map(|cell| cell.symbol()).collect() };
+        assert!(!text(&roads).contains("SH1"));
+
+        let south = MapViewport::region(
+            &NZ_REGIONS[1],
+            [NZ_LON_MIN, NZ_LON_MAX],
+            [NZ_LAT_MIN, NZ_LAT_MAX],
+        );
+        let zoomed = render(NzMapCanvas::new().highways(true).viewport(south));
+        assert!(text(&zoomed).contains("SH6"));
+    }
+
+    #[test]
+    fn airports_are_labelled_only_when_switched_on() {
+        let area = Rect::new(0, 0, 60, 30);
+        let text = |airports| {
+            let mut buf = Buffer::empty(area);
+            NzMapCanvas::new().airports(airports).render(area, &mut buf);
+            (0..area.height)
+                .map(|y| {
+                    (0..area.width)
+                        .map(|x| buf[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        assert!(!text(false).contains('✈'));
+        // queenstown has the south-west to itself
+        assert!(text(true).contains("✈ZQN"));
+
+        let zoomed = MapViewport {
+            zoom: 2,
+            center_x: (180.0 - 0.4543) / 360.0,
+            center_y: (51.47 + 60.0) / 145.0,
+            ..MapViewport::default()
+        };
+        let mut buf = Buffer::empty(area);
+        WorldMapCanvas::new()
+            .airports(true)
+            .viewport(zoomed)
+            .render(area, &mut buf);
+        let rows: String = (0..area.height)
+            .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+            .map(|pos| buf[pos].symbol().to_string())
+            .collect();
+        assert!(rows.contains("LHR"));
+    }
+
+    #[test]
+    fn legend_lists_the_layers_in_view_and_sits_in_the_corner() {
+        let area = Rect::new(0, 0, 50, 20);
+        let text = |legend: MapLegend| {
+            let mut buf = Buffer::empty(area);
+            legend.render(area, &mut buf);
+            (0..area.height)
+                .map(|y| {
+                    (0..area.width)
+                        .map(|x| buf[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let plain = text(MapLegend::nz(
+            NzLegendLayers::default(),
+            AnimationLayers::NONE,
+        ));
+        assert!(plain.iter().any(|row| row.contains("Legend")));
+        assert!(!plain.concat().contains("ferry"));
+        // bottom-right, inside the map border
+        assert!(plain[18].trim_end().ends_with('╯'));
+        assert_eq!(plain[19].trim(), "");
+
+        let layered = text(MapLegend::nz(
+            NzLegendLayers {
+                towns: true,
+                quakes: true,
+                ferry: true,
+                airports: true,
+                heat: true,
+                trip: true,
+                highways: true,
+            },
+            AnimationLayers::default(),
+        ))
+        .concat();
+        assert!(layered.contains("converting from") && layered.contains("converting to"));
+        assert!(layered.contains("Cook Strait ferry") && layered.contains("✈ airport"));
+        assert!(layered.contains("coast by temperature"));
+        assert!(layered.contains("quake, M<3 to M5+"));
+        assert!(layered.contains("─ state highway"));
+
+        // a panel too small for it gets no legend
+        let tiny = Rect::new(0, 0, 12, 5);
+        let mut buf = Buffer::empty(tiny);
+        MapLegend::world(true, true, true, true, true).render(tiny, &mut buf);
+        assert_eq!(buf, Buffer::empty(tiny));
+    }
+
+    #[test]
+    fn strong_geomagnetic_activity_tints_the_south() {
+        let area = Rect::new(0, 0, 80, 30);
+        let render = |kp| {
+            let mut buf = Buffer::empty(area);
+            NzMapCanvas::new().aurora(kp).render(area, &mut buf);
+            buf
+        };
+
+        let quiet = render(Some(3.0));
+        assert!((0..area.height).all(|y| quiet[(30, y)].bg != AURORA_TINT));
+
+        let storm = render(Some(6.3));
+        let bottom: String = (0..area.width).map(|x| storm[(x, 28)].symbol()).collect();
+        assert!(bottom.contains("Aurora possible tonight from Christchurch southwards · Kp 6.3"));
+        // Christchurch sits about two thirds of the way down
+        let tinted: Vec<u16> = (1..29)
+            .filter(|&y| storm[(30, y)].bg == AURORA_TINT)
+            .collect();
+        assert_eq!(tinted.last(), Some(&28));
+        assert!((18..22).contains(&tinted[0]));
+    }
+
+    #[test]
+    fn nearest_point_wraps_the_date_line() {
+        // Fiji's eastern islands sit just past 180°, nearer Auckland than Hawaii
+        let points = [(-36.8, 174.8), (21.3, -157.8)];
+        assert_eq!(nearest_point(points, -16.8, -179.9), Some(0));
+    }
+}
@@ -0,0 +1,136 @@
+//! notification backends for alerts and reminders
+//! each alert type names its backend in config: "bell", "desktop", a webhook URL, or a shell command
+
+use std::process::Stdio;
+
+/// where a notification goes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notifier {
+    /// the terminal bell
+    Bell,
+    /// a desktop notification via notify-send (or osascript on macOS); shelling out keeps
+    /// notify-rust and its D-Bus stack out of the build, and a missing tool just means no popup
+    Desktop,
+    /// POST a small JSON payload to this URL
+    Webhook(String),
+    /// run through `sh -c`, with NZI_ALERT_TITLE and NZI_ALERT_BODY set
+    Command(String),
+}
+
+impl Notifier {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return None;
+        }
+        let lowered = spec.to_lowercase();
+        if lowered == "bell" {
+            return Some(Self::Bell);
+        }
+        if lowered == "desktop" {
+            return Some(Self::Desktop);
+        }
+        if let Some(url) = spec.strip_prefix("webhook:") {
+            let url = url.trim();
+            return (!url.is_empty()).then(|| Self::Webhook(url.to_string()));
+        }
+        if lowered.starts_with("https://") || lowered.starts_with("http://") {
+            return Some(Self::Webhook(spec.to_string()));
+        }
+        Some(Self::Command(spec.to_string()))
+    }
+
+    /// deliver without blocking the UI; failures are ignored so a flaky backend never interrupts
+    pub fn send(&self, title: &str, body: &str) {
+        match self {
+            Self::Bell => {
+                use std::io::Write;
+                let mut stdout = std::io::stdout();
+                let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+            }
+            Self::Desktop => {
+                let mut command = if cfg!(target_os = "macos") {
+                    let mut command = tokio::process::Command::new("osascript");
+                    command.arg("-e").arg(format!(
+                        "display notification {:?} with title {:?}",
+                        body, title
+                    ));
+                    command
+                } else {
+                    let mut command = tokio::process::Command::new("notify-send");
+                    command.arg("--app-name=nzi").arg(title).arg(body);
+                    command
+                };
+                spawn_quietly(&mut command);
+            }
+            Self::Webhook(url) => {
+                let url = url.clone();
+                let payload = serde_json::json!({
+                    "title": title,
+                    "body": body,
+                    "text": format!("{}: {}", title, body),
+                });
+                tokio::spawn(async move {
+                    let _ = crate::http::client().post(&url).json(&payload).send().await;
+                });
+            }
+            Self::Command(command_line) => {
+                let mut command = tokio::process::Command::new("sh");
+                command
+                    .arg("-c")
+                    .arg(command_line)
+                    .env("NZI_ALERT_TITLE", title)
+                    .env("NZI_ALERT_BODY", body);
+                spawn_quietly(&mut command);
+            }
+        }
+    }
+}
+
+fn spawn_quietly(command: &mut tokio::process::Command) {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Ok(mut child) = command.spawn() {
+        tokio::spawn(async move {
+            let _ = child.wait().await;
+        });
+    }
+}
+
+/// send to the backend named by `spec`, if it is set and valid
+pub fn notify(spec: Option<&str>, title: &str, body: &str) -> bool {
+    match spec.and_then(Notifier::parse) {
+        Some(notifier) => {
+            notifier.send(title, body);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_backend_specs() {
+        assert_eq!(Notifier::parse(" Bell "), Some(Notifier::Bell));
+        assert_eq!(Notifier::parse("desktop"), Some(Notifier::Desktop));
+        assert_eq!(
+            Notifier::parse("webhook: https://hooks.example.com/x"),
+            Some(Notifier::Webhook("https://hooks.example.com/x".to_string()))
+        );
+        assert_eq!(
+            Notifier::parse("https://ntfy.sh/nzi"),
+            Some(Notifier::Webhook("https://ntfy.sh/nzi".to_string()))
+        );
+        assert_eq!(
+            Notifier::parse("say rain"),
+            Some(Notifier::Command("say rain".to_string()))
+        );
+        assert_eq!(Notifier::parse("webhook:"), None);
+        assert_eq!(Notifier::parse("  "), None);
+    }
+}
@@ -0,0 +1,274 @@
+//! satellite positions from two-line element sets (TLEs)
+//! the mean elements are advanced with the earth's J2 oblateness and the TLE's decay term rather
+//! than full SGP4; over a day or two that is good to a few tens of km, enough to time a pass
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// WGS84 equatorial radius, km
+const EARTH_RADIUS_KM: f64 = 6378.137;
+const EARTH_FLATTENING: f64 = 1.0 / 298.257_223_563;
+/// earth's gravitational parameter, km³/s²
+const MU: f64 = 398_600.441_8;
+/// the oblateness term that swings the orbit plane round
+const J2: f64 = 0.001_082_63;
+const J2000: f64 = 2_451_545.0;
+const UNIX_EPOCH_JULIAN: f64 = 2_440_587.5;
+
+/// a satellite's mean orbit at its element-set epoch; angles in radians, rates per second
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Orbit {
+    epoch: DateTime<Utc>,
+    inclination: f64,
+    ascending_node: f64,
+    eccentricity: f64,
+    perigee: f64,
+    mean_anomaly: f64,
+    /// mean motion with the oblateness share taken out, as SGP4 does
+    mean_motion: f64,
+    /// half the rate of change of mean motion, from drag
+    half_decay: f64,
+}
+
+/// the TLE checksum: the digits summed, with each minus sign counting one, mod 10
+fn checksum_matches(line: &str) -> bool {
+    let Some((body, check)) = line.split_at_checked(68) else {
+        return false;
+    };
+    let sum: u32 = body
+        .chars()
+        .map(|c| match c {
+            '-' => 1,
+            c => c.to_digit(10).unwrap_or(0),
+        })
+        .sum();
+    check.trim().parse::<u32>().ok() == Some(sum % 10)
+}
+
+fn field(line: &str, columns: std::ops::Range<usize>, name: &str) -> Result<f64> {
+    line.get(columns)
+        .and_then(|text| text.trim().parse().ok())
+        .with_context(|| format!("invalid TLE {}", name))
+}
+
+/// parse a two-line element set; a leading name line is skipped
+pub fn parse_tle(text: &str) -> Result<Orbit> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let (Some(line1), Some(line2)) = (
+        lines.iter().find(|line| line.starts_with("1 ")),
+        lines.iter().find(|line| line.starts_with("2 ")),
+    ) else {
+        bail!("no two-line element set in the response");
+    };
+    if !checksum_matches(line1) || !checksum_matches(line2) {
+        bail!("TLE checksum mismatch");
+    }
+
+    let year = field(line1, 18..20, "epoch year")? as i32;
+    let year = if year < 57 { 2000 + year } else { 1900 + year };
+    let day = field(line1, 20..32, "epoch day")?;
+    let epoch = NaiveDate::from_ymd_opt(year, 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .context("invalid TLE epoch")?
+        .and_utc()
+        + chrono::Duration::milliseconds(((day - 1.0) * 86_400_000.0).round() as i64);
+
+    let revs_per_day = std::f64::consts::TAU / 86_400.0;
+    let half_decay = field(line1, 33..43, "mean motion derivative")? * revs_per_day / 86_400.0;
+    let inclination = field(line2, 8..16, "inclination")?.to_radians();
+    let ascending_node = field(line2, 17..25, "ascending node")?.to_radians();
+    let eccentricity = field(line2, 26..33, "eccentricity")? * 1e-7;
+    let perigee = field(line2, 34..42, "argument of perigee")?.to_radians();
+    let mean_anomaly = field(line2, 43..51, "mean anomaly")?.to_radians();
+    let kozai_motion = field(line2, 52..63, "mean motion")? * revs_per_day;
+    if kozai_motion <= 0.0 || !(0.0..1.0).contains(&eccentricity) {
+        bail!("TLE does not describe a closed orbit");
+    }
+
+    // the TLE's mean motion has a J2 share folded in; take it out as SGP4's initialisation does
+    let shape = (3.0 * inclination.cos().powi(2) - 1.0) / (1.0 - eccentricity.powi(2)).powf(1.5);
+    let delta = |axis: f64| 0.75 * J2 * (EARTH_RADIUS_KM / axis).powi(2) * shape;
+    let axis1 = (MU / kozai_motion.powi(2)).cbrt();
+    let delta1 = delta(axis1);
+    let axis0 = axis1 * (1.0 - delta1 / 3.0 - delta1.powi(2) - 134.0 / 81.0 * delta1.powi(3));
+    let mean_motion = kozai_motion / (1.0 + delta(axis0));
+
+    Ok(Orbit {
+        epoch,
+        inclination,
+        ascending_node,
+        eccentricity,
+        perigee,
+        mean_anomaly,
+        mean_motion,
+        half_decay,
+    })
+}
+
+/// greenwich mean sidereal time, radians
+fn sidereal_angle(at: DateTime<Utc>) -> f64 {
+    let days = at.timestamp_millis() as f64 / 86_400_000.0 + UNIX_EPOCH_JULIAN - J2000;
+    (280.460_618_37 + 360.985_647_366_29 * days)
+        .rem_euclid(360.0)
+        .to_radians()
+}
+
+/// turn an earth-centred inertial vector into earth-fixed coordinates
+pub fn earth_fixed(inertial: [f64; 3], at: DateTime<Utc>) -> [f64; 3] {
+    let (sin, cos) = sidereal_angle(at).sin_cos();
+    [
+        cos * inertial[0] + sin * inertial[1],
+        -sin * inertial[0] + cos * inertial[1],
+        inertial[2],
+    ]
+}
+
+/// the earth-fixed position, km, of a sea-level place, and its local up direction
+fn ground_station(place: (f64, f64)) -> ([f64; 3], [f64; 3]) {
+    let (lat, lon) = (place.0.to_radians(), place.1.to_radians());
+    let squared_eccentricity = EARTH_FLATTENING * (2.0 - EARTH_FLATTENING);
+    let radius = EARTH_RADIUS_KM / (1.0 - squared_eccentricity * lat.sin().powi(2)).sqrt();
+    let up = [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()];
+    let position = [
+        radius * up[0],
+        radius * up[1],
+        radius * (1.0 - squared_eccentricity) * up[2],
+    ];
+    (position, up)
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// degrees above the horizon at a (lat, lon) place of an earth-fixed direction
+pub fn elevation_of(place: (f64, f64), direction: [f64; 3]) -> f64 {
+    let (_, up) = ground_station(place);
+    (dot(direction, up) / dot(direction, direction).sqrt())
+        .asin()
+        .to_degrees()
+}
+
+impl Orbit {
+    /// earth-centred inertial position in km
+    pub fn position(&self, at: DateTime<Utc>) -> [f64; 3] {
+        let elapsed = (at - self.epoch).num_milliseconds() as f64 / 1000.0;
+        let (e, incl) = (self.eccentricity, self.inclination);
+        let axis = (MU / self.mean_motion.powi(2)).cbrt();
+        let oblateness = 0.75 * J2 * (EARTH_RADIUS_KM / (axis * (1.0 - e * e))).powi(2);
+
+        let node = self.ascending_node - 2.0 * oblateness * self.mean_motion * incl.cos() * elapsed;
+        let perigee = self.perigee
+            + oblateness * self.mean_motion * (4.0 - 5.0 * incl.sin().powi(2)) * elapsed;
+        let anomaly_rate = self.mean_motion
+            * (1.0 + oblateness * (1.0 - e * e).sqrt() * (3.0 * incl.cos().powi(2) - 1.0));
+        let mean_anomaly =
+            self.mean_anomaly + anomaly_rate * elapsed + self.half_decay * elapsed.powi(2);
+
+        // kepler's equation by newton's method; a near-circular orbit settles in a step or two
+        let mut eccentric = mean_anomaly;
+        for _ in 0..10 {
+            let step =
+                (eccentric - e * eccentric.sin() - mean_anomaly) / (1.0 - e * eccentric.cos());
+            eccentric -= step;
+            if step.abs() < 1e-12 {
+                break;
+            }
+        }
+        let true_anomaly = 2.0
+            * ((1.0 + e).sqrt() * (eccentric / 2.0).sin())
+                .atan2((1.0 - e).sqrt() * (eccentric / 2.0).cos());
+        let radius = axis * (1.0 - e * eccentric.cos());
+
+        let (sin_u, cos_u) = (perigee + true_anomaly).sin_cos();
+        let (sin_node, cos_node) = node.sin_cos();
+        let (sin_i, cos_i) = incl.sin_cos();
+        [
+            radius * (cos_node * cos_u - sin_node * sin_u * cos_i),
+            radius * (sin_node * cos_u + cos_node * sin_u * cos_i),
+            radius * sin_u * sin_i,
+        ]
+    }
+
+    /// degrees above the horizon as seen from a (lat, lon) place
+    pub fn elevation_from(&self, place: (f64, f64), at: DateTime<Utc>) -> f64 {
+        let (station, _) = ground_station(place);
+        let [x, y, z] = earth_fixed(self.position(at), at);
+        elevation_of(place, [x - station[0], y - station[1], z - station[2]])
+    }
+
+    /// whether the satellite is out of the earth's shadow, taken as a cylinder
+    pub fn is_sunlit(&self, at: DateTime<Utc>, sun: [f64; 3]) -> bool {
+        let position = self.position(at);
+        let along = dot(position, sun);
+        along > 0.0 || dot(position, position) - along * along > EARTH_RADIUS_KM.powi(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the point beneath the satellite as geocentric (lat, lon) in degrees
+    fn subpoint(orbit: &Orbit, at: DateTime<Utc>) -> (f64, f64) {
+        let [x, y, z] = earth_fixed(orbit.position(at), at);
+        (z.atan2(x.hypot(y)).to_degrees(), y.atan2(x).to_degrees())
+    }
+
+    /// the ISS element set used as the worked example in most TLE references
+    const ISS_2008: &str = "ISS (ZARYA)
+1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+    #[test]
+    fn parses_an_element_set_and_checks_it() {
+        let orbit = parse_tle(ISS_2008).unwrap();
+        assert_eq!(
+            orbit.epoch.format("%Y-%m-%d %H:%M").to_string(),
+            "2008-09-20 12:25"
+        );
+        assert!((orbit.inclination.to_degrees() - 51.6416).abs() < 1e-9);
+        assert!((orbit.eccentricity - 0.000_670_3).abs() < 1e-12);
+        assert!(orbit.half_decay < 0.0);
+
+        let corrupted = ISS_2008.replace("51.6416", "51.6417");
+        assert!(parse_tle(&corrupted).is_err());
+        assert!(parse_tle("no elements here").is_err());
+    }
+
+    #[test]
+    fn keeps_the_station_in_low_orbit_within_its_inclination() {
+        let orbit = parse_tle(ISS_2008).unwrap();
+        for minutes in (0..2 * 24 * 60).step_by(7) {
+            let at = orbit.epoch + chrono::Duration::minutes(minutes);
+            let position = orbit.position(at);
+            let altitude = dot(position, position).sqrt() - EARTH_RADIUS_KM;
+            assert!((300.0..400.0).contains(&altitude), "{} km", altitude);
+            let (lat, lon) = subpoint(&orbit, at);
+            assert!(lat.abs() <= 51.7, "{}", lat);
+            assert!((-180.0..=180.0).contains(&lon));
+        }
+
+        // a place right under the station sees it overhead, and the far side of the earth can't
+        let at = orbit.epoch + chrono::Duration::hours(5);
+        let below = subpoint(&orbit, at);
+        assert!(orbit.elevation_from(below, at) > 85.0);
+        let antipode = (-below.0, below.1 - 180.0);
+        assert!(orbit.elevation_from(antipode, at) < -60.0);
+    }
+
+    #[test]
+    fn the_plane_drifts_west_about_five_degrees_a_day() {
+        // J2 turns a 51.6° orbit's ascending node back roughly 5° a day
+        let orbit = parse_tle(ISS_2008).unwrap();
+        let node_at = |at: DateTime<Utc>| {
+            let p = orbit.position(at);
+            let v = orbit.position(at + chrono::Duration::seconds(1));
+            let normal = [p[1] * v[2] - p[2] * v[1], p[2] * v[0] - p[0] * v[2]];
+            normal[0].atan2(-normal[1]).to_degrees()
+        };
+        let drift = node_at(orbit.epoch + chrono::Duration::days(1)) - node_at(orbit.epoch);
+        let drift = (drift + 540.0).rem_euclid(360.0) - 180.0;
+        assert!((-5.5..-4.5).contains(&drift), "{}", drift);
+    }
+}
@@ -0,0 +1,284 @@
+//! user-defined panels fed by shell commands
+//! commands run on the tokio runtime and report back over a channel drained each tick
+
+use std::time::{Duration, Instant};
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use tokio::process::Command;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+use crate::config::CustomPanelConfig;
+
+/// a command that hangs is abandoned after this long
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// latest state of one custom panel
+#[derive(Debug, Clone)]
+pub struct CustomPanel {
+    pub config: CustomPanelConfig,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    last_run: Option<Instant>,
+    running: bool,
+}
+
+impl CustomPanel {
+    fn is_due(&self) -> bool {
+        !self.running
+            && self
+                .last_run
+                .is_none_or(|at| at.elapsed() >= Duration::from_secs(self.config.refresh_secs))
+    }
+}
+
+type PanelResult = (usize, Result<String, String>);
+
+/// all custom panels plus the channel their commands report on
+pub struct CustomPanels {
+    pub panels: Vec<CustomPanel>,
+    sender: UnboundedSender<PanelResult>,
+    receiver: UnboundedReceiver<PanelResult>,
+}
+
+impl CustomPanels {
+    pub fn new(configs: &[CustomPanelConfig]) -> Self {
+        let (sender, receiver) = unbounded_channel();
+        Self {
+            panels: configs
+                .iter()
+                .map(|config| CustomPanel {
+                    config: config.clone(),
+                    output: None,
+                    error: None,
+                    last_run: None,
+                    running: false,
+                })
+                .collect(),
+            sender,
+            receiver,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.panels.is_empty()
+    }
+
+    /// collect finished commands and start any that are due
+    pub fn poll(&mut self) {
+        while let Ok((index, result)) = self.receiver.try_recv() {
+            if let Some(panel) = self.panels.get_mut(index) {
+                panel.running = false;
+                match result {
+                    Ok(output) => {
+                        panel.output = Some(output);
+                        panel.error = None;
+                    }
+                    Err(error) => panel.error = Some(error),
+                }
+            }
+        }
+
+        for (index, panel) in self.panels.iter_mut().enumerate() {
+            if panel.is_due() {
+                panel.running = true;
+                panel.last_run = Some(Instant::now());
+                let command = panel.config.command.clone();
+                let sender = self.sender.clone();
+                tokio::spawn(async move {
+                    let _ = sender.send((index, run_command(&command).await));
+                });
+            }
+        }
+    }
+}
+
+async fn run_command(command: &str) -> Result<String, String> {
+    let output = tokio::time::timeout(
+        COMMAND_TIMEOUT,
+        Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| format!("timed out after {}s", COMMAND_TIMEOUT.as_secs()))?
+    .map_err(|err| err.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(stderr
+            .lines()
+            .next()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("exited with {}", output.status)))
+    }
+}
+
+/// the 8 basic ANSI colours, normal then bright
+fn ansi_colour(code: u8) -> Color {
+    match code {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Gray,
+        8 => Color::DarkGray,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+/// apply one SGR sequence ("1;31", "38;5;208", ...) to a style
+fn apply_sgr(mut style: Style, params: &str, base: Style) -> Style {
+    let codes: Vec<u16> = params
+        .split(';')
+        .map(|code| code.parse().unwrap_or(0))
+        .collect();
+    let mut index = 0;
+    while index < codes.len() {
+        match codes[index] {
+            0 => style = base,
+            1 => style = style.add_modifier(Modifier::BOLD),
+            2 => style = style.add_modifier(Modifier::DIM),
+            3 => style = style.add_modifier(Modifier::ITALIC),
+            4 => style = style.add_modifier(Modifier::UNDERLINED),
+            7 => style = style.add_modifier(Modifier::REVERSED),
+            22 => style = style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style = style.remove_modifier(Modifier::ITALIC),
+            24 => style = style.remove_modifier(Modifier::UNDERLINED),
+            27 => style = style.remove_modifier(Modifier::REVERSED),
+            code @ 30..=37 => style = style.fg(ansi_colour((code - 30) as u8)),
+            code @ 90..=97 => style = style.fg(ansi_colour((code - 90 + 8) as u8)),
+            code @ 40..=47 => style = style.bg(ansi_colour((code - 40) as u8)),
+            code @ 100..=107 => style = style.bg(ansi_colour((code - 100 + 8) as u8)),
+            39 => style.fg = base.fg,
+            49 => style.bg = base.bg,
+            code @ (38 | 48) => {
+                let (colour, used) = match codes.get(index + 1) {
+                    Some(5) => (codes.get(index + 2).map(|&n| Color::Indexed(n as u8)), 2),
+                    Some(2) => (
+                        codes
+                            .get(index + 2..index + 5)
+                            .map(|rgb| Color::Rgb(rgb[0] as u8, rgb[1] as u8, rgb[2] as u8)),
+                        4,
+                    ),
+                    _ => (None, 0),
+                };
+                index += used;
+                if let Some(colour) = colour {
+                    style = if code == 38 {
+                        style.fg(colour)
+                    } else {
+                        style.bg(colour)
+                    };
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    style
+}
+
+/// convert command output with ANSI SGR colour codes into styled lines;
+/// other escape sequences are dropped
+pub fn ansi_lines(text: &str, base: Style) -> Vec<Line<'static>> {
+    text.lines()
+        .map(|raw| {
+            let mut spans = Vec::new();
+            let mut style = base;
+            let mut current = String::new();
+            let mut chars = raw.chars().peekable();
+
+            while let Some(c) = chars.next() {
+                if c != '\u{1b}' {
+                    if c != '\r' {
+                        current.push(c);
+                    }
+                    continue;
+                }
+                if chars.peek() != Some(&'[') {
+                    continue;
+                }
+                chars.next();
+                let mut params = String::new();
+                let mut terminator = None;
+                for next in chars.by_ref() {
+                    if next.is_ascii_alphabetic() {
+                        terminator = Some(next);
+                        break;
+                    }
+                    params.push(next);
+                }
+                if terminator == Some('m') {
+                    if !current.is_empty() {
+                        spans.push(Span::styled(std::mem::take(&mut current), style));
+                    }
+                    style = apply_sgr(style, &params, base);
+                }
+            }
+            if !current.is_empty() {
+                spans.push(Span::styled(current, style));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_basic_and_extended_sgr_colours() {
+        let base = Style::default().fg(Color::White);
+        let lines = ansi_lines(
+            "plain \u{1b}[1;31mred\u{1b}[0m \u{1b}[38;5;208morange\u{1b}[K",
+            base,
+        );
+
+        let spans = &lines[0].spans;
+        assert_eq!(spans[0].content, "plain ");
+        assert_eq!(spans[0].style, base);
+        assert_eq!(spans[1].content, "red");
+        assert_eq!(spans[1].style.fg, Some(Color::Red));
+        assert!(spans[1].style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(spans[2].style, base);
+        assert_eq!(spans[3].content, "orange");
+        assert_eq!(spans[3].style.fg, Some(Color::Indexed(208)));
+    }
+
+    #[tokio::test]
+    async fn polls_command_output_into_panel() {
+        let mut panels = CustomPanels::new(&[CustomPanelConfig {
+            title: "Echo".to_string(),
+            command: "printf 'hello'".to_string(),
+            refresh_secs: 60,
+        }]);
+
+        panels.poll();
+        assert!(panels.panels[0].running);
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            panels.poll();
+            if panels.panels[0].output.is_some() {
+                break;
+            }
+        }
+
+        assert_eq!(panels.panels[0].output.as_deref(), Some("hello"));
+        assert!(!panels.panels[0].is_due());
+    }
+}
@@ -0,0 +1,303 @@
+//! city search for `/city add` and `/addcity`
+//! fuzzy-matches the bundled world cities, the representative cities and the IANA zone list
+//! into new City entries
+
+use chrono_tz::TZ_VARIANTS;
+
+use crate::config::City;
+use crate::reference::{
+    REPRESENTATIVE_CITY_REFERENCES, RepresentativeCityReference, WORLD_CITY_REFERENCES,
+    WorldCityReference, country_by_code, world_city_by_name,
+};
+
+/// rank of `query` within `text`: prefix, then substring, then in-order subsequence
+fn fuzzy_rank(query: &str, text: &str) -> Option<usize> {
+    let text = text.to_lowercase();
+    if text.starts_with(query) {
+        return Some(0);
+    }
+    if text.contains(query) {
+        return Some(1);
+    }
+
+    // subsequence match, penalised by how spread out the matched characters are
+    let mut chars = text.chars().enumerate();
+    let mut first = None;
+    let mut last = 0;
+    for wanted in query.chars() {
+        let (index, _) = chars.find(|(_, c)| *c == wanted)?;
+        first.get_or_insert(index);
+        last = index;
+    }
+    Some(2 + last - first.unwrap_or(0))
+}
+
+/// city name from an IANA zone, e.g. "America/Argentina/Buenos_Aires" -> "Buenos Aires"
+fn zone_city_name(zone: &str) -> Option<String> {
+    let (region, rest) = zone.split_once('/')?;
+    if region == "Etc" || region == "SystemV" {
+        return None;
+    }
+    Some(rest.rsplit('/').next()?.replace('_', " "))
+}
+
+/// first unused three-letter code built from the city name's letters
+pub fn unique_city_code(name: &str, taken: &[String]) -> String {
+    let letters: Vec<char> = name
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let is_free = |code: &str| !taken.iter().any(|used| used.eq_ignore_ascii_case(code));
+
+    if let Some(&head) = letters.first() {
+        for i in 1..letters.len() {
+            for j in i + 1..letters.len() {
+                let code: String = [head, letters[i], letters[j]].iter().collect();
+                if is_free(&code) {
+                    return code;
+                }
+            }
+        }
+    }
+
+    let stem: String = letters.iter().take(2).collect();
+    let stem = format!("{:X<2}", stem);
+    (1..)
+        .map(|n| format!("{}{}", stem, n))
+        .find(|code| is_free(code))
+        .unwrap_or(stem)
+}
+
+fn database_city_by_name(name: &str) -> Option<&'static RepresentativeCityReference> {
+    REPRESENTATIVE_CITY_REFERENCES
+        .iter()
+        .find(|city| city.city_name.eq_ignore_ascii_case(name))
+}
+
+/// the bundled world city for `/addcity`: an exact name or code, else the closest name
+pub fn find_world_city(query: &str) -> Option<&'static WorldCityReference> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    world_city_by_name(&query)
+        .or_else(|| {
+            WORLD_CITY_REFERENCES
+                .iter()
+                .find(|city| city.code.eq_ignore_ascii_case(&query))
+        })
+        .or_else(|| {
+            WORLD_CITY_REFERENCES
+                .iter()
+                .filter_map(|city| Some((fuzzy_rank(&query, city.name)?, city)))
+                .min_by_key(|(rank, city)| (*rank, city.name.len()))
+                .map(|(_, city)| city)
+        })
+}
+
+/// a tracked-city entry for a bundled world city, renaming its code if another city holds it
+pub fn world_city_entry(reference: &WorldCityReference, taken: &[String]) -> City {
+    let code = if taken
+        .iter()
+        .any(|used| used.eq_ignore_ascii_case(reference.code))
+    {
+        unique_city_code(reference.name, taken)
+    } else {
+        reference.code.to_string()
+    };
+    City {
+        name: reference.name.to_string(),
+        code,
+        country: country_by_code(reference.country_code)
+            .map(|country| country.name)
+            .unwrap_or(reference.country_code)
+            .to_string(),
+        timezone: reference.timezone.to_string(),
+        currency: reference.currency_code.to_string(),
+    }
+}
+
+/// new-city candidates for a query, best matches first; cities already tracked are skipped.
+/// Cities known only from an IANA zone have an empty country and currency, for the
+/// caller to ask about
+pub fn search_new_cities(query: &str, existing: &[&City]) -> Vec<City> {
+    let query = query.trim().to_lowercase();
+    let taken: Vec<String> = existing.iter().map(|city| city.code.clone()).collect();
+    let is_tracked = |name: &str| {
+        existing
+            .iter()
+            .any(|city| city.name.eq_ignore_ascii_case(name))
+    };
+    let rank = |fields: &[&str]| -> Option<usize> {
+        if query.is_empty() {
+            return Some(0);
+        }
+        fields
+            .iter()
+            .filter_map(|field| fuzzy_rank(&query, field))
+            .min()
+    };
+
+    let mut ranked: Vec<(usize, City)> = Vec::new();
+
+    // bundled world cities, which also carry weather coordinates
+    for reference in WORLD_CITY_REFERENCES {
+        if is_tracked(reference.name) {
+            continue;
+        }
+        let Some(score) = rank(&[reference.name, reference.code, reference.timezone]) else {
+            continue;
+        };
+        ranked.push((score, world_city_entry(reference, &taken)));
+    }
+
+    // representative cities, upgraded to the IANA zone when one is named after the city
+    for reference in REPRESENTATIVE_CITY_REFERENCES {
+        if is_tracked(reference.city_name) || world_city_by_name(reference.city_name).is_some() {
+            continue;
+        }
+        let timezone = TZ_VARIANTS
+            .iter()
+            .map(|tz| tz.name())
+            .find(|zone| zone_city_name(zone).as_deref() == Some(reference.city_name))
+            .unwrap_or(reference.timezone);
+        let Some(score) = rank(&[
+            reference.city_name,
+            reference.country_name,
+            reference.city_code,
+            timezone,
+        ]) else {
+            continue;
+        };
+        let code = if taken
+            .iter()
+            .any(|used| used.eq_ignore_ascii_case(reference.city_code))
+        {
+            unique_city_code(reference.city_name, &taken)
+        } else {
+            reference.city_code.to_string()
+        };
+        ranked.push((
+            score,
+            City {
+                name: reference.city_name.to_string(),
+                code,
+                country: reference.country_name.to_string(),
+                timezone: timezone.to_string(),
+                currency: reference.currency_code.to_string(),
+            },
+        ));
+    }
+
+    // remaining IANA zones; the zone's region ("America") is no country, so both stay unknown
+    for tz in TZ_VARIANTS {
+        let zone = tz.name();
+        let Some(name) = zone_city_name(zone) else {
+            continue;
+        };
+        if is_tracked(&name)
+            || database_city_by_name(&name).is_some()
+            || world_city_by_name(&name).is_some()
+        {
+            continue;
+        }
+        let Some(score) = rank(&[name.as_str(), zone]) else {
+            continue;
+        };
+        ranked.push((
+            score,
+            City {
+                code: unique_city_code(&name, &taken),
+                name,
+                country: String::new(),
+                timezone: zone.to_string(),
+                currency: String::new(),
+            },
+        ));
+    }
+
+    ranked.sort_by(|(left_score, left), (right_score, right)| {
+        left_score
+            .cmp(right_score)
+            .then_with(|| left.name.cmp(&right.name))
+    });
+    ranked.dedup_by(|right, left| right.1.name == left.1.name);
+    ranked.into_iter().map(|(_, city)| city).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_rank_prefers_prefix_then_substring_then_subsequence() {
+        assert_eq!(fuzzy_rank("lis", "Lisbon"), Some(0));
+        assert_eq!(fuzzy_rank("bon", "Lisbon"), Some(1));
+        assert_eq!(fuzzy_rank("lsb", "Lisbon"), Some(5));
+        assert_eq!(fuzzy_rank("xyz", "Lisbon"), None);
+    }
+
+    #[test]
+    fn generates_codes_that_avoid_existing_ones() {
+        assert_eq!(unique_city_code("Chicago", &[]), "CHI");
+        assert_eq!(unique_city_code("Chicago", &["CHI".to_string()]), "CHC");
+        assert_eq!(unique_city_code("Yu", &["YUX".to_string()]), "YU1");
+    }
+
+    #[test]
+    fn iana_zones_are_searchable_by_city_name() {
+        let wellington = City::wellington();
+        let results = search_new_cities("chicago", &[&wellington]);
+        let chicago = results.first().expect("chicago should match");
+
+        assert_eq!(chicago.name, "Chicago");
+        assert_eq!(chicago.timezone, "America/Chicago");
+        assert_eq!(chicago.code, "CHI");
+
+        // a zone-only city gets no made-up country or currency
+        let thule = search_new_cities("thule", &[&wellington])
+            .into_iter()
+            .find(|city| city.timezone == "America/Thule")
+            .expect("thule should match");
+        assert_eq!((thule.country.as_str(), thule.currency.as_str()), ("", ""));
+    }
+
+    #[test]
+    fn finds_world_cities_by_name_code_or_nearest_spelling() {
+        assert_eq!(find_world_city("Tokyo").map(|city| city.code), Some("TYO"));
+        assert_eq!(
+            find_world_city("zqn").map(|city| city.name),
+            Some("Queenstown")
+        );
+        assert_eq!(
+            find_world_city("reykjav").map(|city| city.name),
+            Some("Reykjavik")
+        );
+        assert!(find_world_city("").is_none());
+        assert!(find_world_city("qqqqzz").is_none());
+    }
+
+    #[test]
+    fn world_city_entry_renames_a_taken_code() {
+        let osaka = find_world_city("osaka").expect("osaka should be bundled");
+        let city = world_city_entry(osaka, &["OSA".to_string()]);
+
+        assert_eq!(city.country, "Japan");
+        assert_eq!(city.timezone, "Asia/Tokyo");
+        assert_eq!(city.currency, "JPY");
+        assert_ne!(city.code, "OSA");
+    }
+
+    #[test]
+    fn database_cities_use_named_iana_zone_and_skip_tracked_ones() {
+        let tokyo = City::tokyo();
+        let results = search_new_cities("lisbon", &[&tokyo]);
+        let lisbon = results.first().expect("lisbon should match");
+        assert_eq!(lisbon.timezone, "Europe/Lisbon");
+        assert_eq!(lisbon.currency, "EUR");
+
+        let results = search_new_cities("tokyo", &[&tokyo]);
+        assert!(results.iter().all(|city| city.name != "Tokyo"));
+    }
+}
@@ -0,0 +1,351 @@
+//! meeting planner across tracked cities
+//! classifies local hours and searches for the best overlapping slot
+
+use chrono::{
+    DateTime, Duration, DurationRound, FixedOffset, LocalResult, NaiveDate, NaiveTime, Timelike,
+    Utc,
+};
+
+use crate::config::{City, PlannerConfig};
+use crate::timezone::parse_city_timezone;
+
+/// how a local hour sits against the planner windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HourStatus {
+    Working,
+    Awake,
+    Asleep,
+}
+
+impl HourStatus {
+    pub fn classify(hour: u32, settings: &PlannerConfig) -> Self {
+        if (settings.work_start_hour..settings.work_end_hour).contains(&hour) {
+            Self::Working
+        } else if (settings.awake_start_hour..settings.awake_end_hour).contains(&hour) {
+            Self::Awake
+        } else {
+            Self::Asleep
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Working => "working",
+            Self::Awake => "awake",
+            Self::Asleep => "asleep",
+        }
+    }
+
+    fn score(self) -> u32 {
+        match self {
+            Self::Working => 2,
+            Self::Awake => 1,
+            Self::Asleep => 0,
+        }
+    }
+}
+
+/// one city's local time for the candidate slot
+#[derive(Debug, Clone)]
+pub struct PlannerRow {
+    pub city_code: String,
+    pub city_name: String,
+    pub local: DateTime<FixedOffset>,
+    pub status: HourStatus,
+}
+
+/// the best slot found on the anchor city's day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BestSlot {
+    pub anchor_time: NaiveTime,
+    pub working: usize,
+    pub awake: usize,
+}
+
+/// resolve an anchor-city local time into an absolute instant
+fn anchor_instant(
+    anchor: &City,
+    date: NaiveDate,
+    time: NaiveTime,
+) -> Option<DateTime<FixedOffset>> {
+    let timezone = parse_city_timezone(&anchor.timezone)?;
+    match timezone.resolve_local_datetime(&date.and_time(time)) {
+        LocalResult::Single(datetime) => Some(datetime),
+        LocalResult::Ambiguous(first, _) => Some(first),
+        LocalResult::None => None,
+    }
+}
+
+/// local time in every city for one anchor-city slot
+pub fn plan_rows(
+    anchor: &City,
+    cities: &[&City],
+    date: NaiveDate,
+    time: NaiveTime,
+    settings: &PlannerConfig,
+) -> Vec<PlannerRow> {
+    let Some(instant) = anchor_instant(anchor, date, time) else {
+        return Vec::new();
+    };
+
+    cities
+        .iter()
+        .filter_map(|city| {
+            let local = parse_city_timezone(&city.timezone)?.convert_datetime(&instant);
+            Some(PlannerRow {
+                city_code: city.code.clone(),
+                city_name: city.name.clone(),
+                local,
+                status: HourStatus::classify(local.hour(), settings),
+            })
+        })
+        .collect()
+}
+
+/// search the anchor day in 30-minute steps for the slot with most overlap
+pub fn best_slot(
+    anchor: &City,
+    cities: &[&City],
+    date: NaiveDate,
+    settings: &PlannerConfig,
+) -> Option<BestSlot> {
+    let mut best: Option<(u32, BestSlot)> = None;
+    let mut time = NaiveTime::MIN;
+
+    for _ in 0..48 {
+        let rows = plan_rows(anchor, cities, date, time, settings);
+        if !rows.is_empty() {
+            let score: u32 = rows.iter().map(|row| row.status.score()).sum();
+            let slot = BestSlot {
+                anchor_time: time,
+                working: rows
+                    .iter()
+                    .filter(|row| row.status == HourStatus::Working)
+                    .count(),
+                awake: rows
+                    .iter()
+                    .filter(|row| row.status != HourStatus::Asleep)
+                    .count(),
+            };
+            if best.is_none_or(|(best_score, _)| score > best_score) {
+                best = Some((score, slot));
+            }
+        }
+        time += Duration::minutes(30);
+    }
+
+    best.map(|(_, slot)| slot)
+}
+
+/// how comfortable a call slot is for both sides; orders best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CallQuality {
+    BothWorking,
+    OneWorking,
+    BothAwake,
+}
+
+impl CallQuality {
+    /// `None` when either side is asleep
+    fn of(mine: HourStatus, theirs: HourStatus) -> Option<Self> {
+        match (mine, theirs) {
+            (HourStatus::Asleep, _) | (_, HourStatus::Asleep) => None,
+            (HourStatus::Working, HourStatus::Working) => Some(Self::BothWorking),
+            (HourStatus::Working, _) | (_, HourStatus::Working) => Some(Self::OneWorking),
+            _ => Some(Self::BothAwake),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::BothWorking => "both working",
+            Self::OneWorking => "one side working",
+            Self::BothAwake => "both awake",
+        }
+    }
+}
+
+/// a stretch when both sides are within their hours, in each side's local time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallWindow {
+    pub quality: CallQuality,
+    pub mine_start: DateTime<FixedOffset>,
+    pub mine_end: DateTime<FixedOffset>,
+    pub their_start: DateTime<FixedOffset>,
+    pub their_end: DateTime<FixedOffset>,
+}
+
+/// how far ahead call windows are searched
+const CALL_HORIZON_SLOTS: usize = 96;
+
+/// upcoming windows over the next two days in 30-minute slots, split where the quality
+/// changes; returns the best `limit`, ranked by quality and then by how soon they start
+pub fn call_windows(
+    mine: &City,
+    theirs: &City,
+    now: DateTime<Utc>,
+    mine_hours: &PlannerConfig,
+    their_hours: &PlannerConfig,
+    limit: usize,
+) -> Vec<CallWindow> {
+    let (Some(mine_zone), Some(their_zone)) = (
+        parse_city_timezone(&mine.timezone),
+        parse_city_timezone(&theirs.timezone),
+    ) else {
+        return Vec::new();
+    };
+    let slot = Duration::minutes(30);
+    let first_slot = now.duration_trunc(slot).unwrap_or(now);
+
+    let mut windows: Vec<(CallQuality, DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+    let mut open: Option<(CallQuality, DateTime<Utc>)> = None;
+    for index in 0..=CALL_HORIZON_SLOTS {
+        let start = first_slot + slot * index as i32;
+        // one slot past the horizon closes any window still open
+        let quality = if index < CALL_HORIZON_SLOTS {
+            let instant = start.fixed_offset();
+            let mine_hour = mine_zone.convert_datetime(&instant).hour();
+            let their_hour = their_zone.convert_datetime(&instant).hour();
+            CallQuality::of(
+                HourStatus::classify(mine_hour, mine_hours),
+                HourStatus::classify(their_hour, their_hours),
+            )
+        } else {
+            None
+        };
+        if let Some((open_quality, open_start)) = open
+            && quality != Some(open_quality)
+        {
+            windows.push((open_quality, open_start.max(now), start));
+            open = None;
+        }
+        if open.is_none()
+            && let Some(quality) = quality
+        {
+            open = Some((quality, start));
+        }
+    }
+
+    windows.sort_by_key(|(quality, start, _)| (*quality, *start));
+    windows
+        .into_iter()
+        .take(limit)
+        .map(|(quality, start, end)| CallWindow {
+            quality,
+            mine_start: mine_zone.convert_datetime(&start.fixed_offset()),
+            mine_end: mine_zone.convert_datetime(&end.fixed_offset()),
+            their_start: their_zone.convert_datetime(&start.fixed_offset()),
+            their_end: their_zone.convert_datetime(&end.fixed_offset()),
+        })
+        .collect()
+}
+
+/// status of each hour of a reference day for a city `offset_delta_minutes` ahead of it
+pub fn day_bar(offset_delta_minutes: i32, settings: &PlannerConfig) -> [HourStatus; 24] {
+    std::array::from_fn(|hour| {
+        let local_minutes = (hour as i32 * 60 + offset_delta_minutes).rem_euclid(24 * 60);
+        HourStatus::classify((local_minutes / 60) as u32, settings)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_hours_against_windows() {
+        let settings = PlannerConfig::default();
+        assert_eq!(HourStatus::classify(10, &settings), HourStatus::Working);
+        assert_eq!(HourStatus::classify(17, &settings), HourStatus::Awake);
+        assert_eq!(HourStatus::classify(3, &settings), HourStatus::Asleep);
+    }
+
+    #[test]
+    fn call_windows_rank_by_quality_then_start() {
+        use chrono::TimeZone;
+
+        let wellington = City::wellington();
+        let boston = City::boston();
+        let hours = PlannerConfig::default();
+        // 00:00 UTC is noon friday in wellington and 20:00 thursday in boston
+        let now = Utc.with_ymd_and_hms(2025, 6, 6, 0, 0, 0).unwrap();
+
+        let windows = call_windows(&wellington, &boston, now, &hours, &hours, 3);
+        assert_eq!(windows.len(), 3);
+        assert!(
+            windows
+                .iter()
+                .all(|window| window.quality == CallQuality::OneWorking)
+        );
+        assert_eq!(windows[0].mine_start.hour(), 12);
+        assert_eq!(windows[0].mine_end.hour(), 14);
+        assert_eq!(windows[0].their_start.hour(), 20);
+        assert_eq!(windows[1].mine_start.hour(), 7);
+        assert_eq!(windows[1].their_end.hour(), 22);
+
+        // a late-working contact overlaps with the wellington working day
+        let late = PlannerConfig {
+            work_start_hour: 19,
+            work_end_hour: 22,
+            awake_start_hour: 7,
+            awake_end_hour: 23,
+        };
+        let windows = call_windows(&wellington, &boston, now, &hours, &late, 2);
+        assert!(
+            windows
+                .iter()
+                .all(|window| window.quality == CallQuality::BothWorking)
+        );
+        assert_eq!(windows[0].their_start.hour(), 20);
+        assert_eq!(windows[1].their_start.hour(), 19);
+        assert!(windows[0].mine_start < windows[1].mine_start);
+    }
+
+    #[test]
+    fn day_bar_shifts_by_offset_including_fractional_zones() {
+        let settings = PlannerConfig::default();
+        let same = day_bar(0, &settings);
+        assert_eq!(same[9], HourStatus::Working);
+        assert_eq!(same[3], HourStatus::Asleep);
+
+        // london is 11h behind wellington in june: 20:00 there is 09:00 here
+        let london = day_bar(-11 * 60, &settings);
+        assert_eq!(london[20], HourStatus::Working);
+        assert_eq!(london[9], HourStatus::Asleep);
+
+        // kathmandu is 5h45m ahead of utc: 03:00 utc is 08:45 local
+        let kathmandu = day_bar(5 * 60 + 45, &settings);
+        assert_eq!(kathmandu[3], HourStatus::Awake);
+        assert_eq!(kathmandu[4], HourStatus::Working);
+    }
+
+    #[test]
+    fn best_slot_for_a_single_city_is_within_working_hours() {
+        let wellington = City::wellington();
+        let date = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let slot = best_slot(&wellington, &[&wellington], date, &PlannerConfig::default())
+            .expect("slot should exist");
+
+        assert_eq!(slot.anchor_time, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(slot.working, 1);
+    }
+
+    #[test]
+    fn best_slot_prefers_overlap_between_wellington_and_london() {
+        let wellington = City::wellington();
+        let london = City::london();
+        let date = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let settings = PlannerConfig::default();
+        let slot = best_slot(&wellington, &[&wellington, &london], date, &settings)
+            .expect("slot should exist");
+
+        let rows = plan_rows(
+            &wellington,
+            &[&wellington, &london],
+            date,
+            slot.anchor_time,
+            &settings,
+        );
+        assert!(rows.iter().all(|row| row.status != HourStatus::Asleep));
+    }
+}
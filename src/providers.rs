@@ -0,0 +1,117 @@
+//! registry of external data providers
+//! drives the /about overlay; new integrations register themselves here
+
+/// an upstream data provider and its attribution terms
+#[derive(Debug, Clone, Copy)]
+pub struct Provider {
+    pub name: &'static str,
+    pub url: &'static str,
+    pub used_for: &'static str,
+    pub licence: &'static str,
+    pub attribution: &'static str,
+}
+
+pub const PROVIDERS: &[Provider] = &[
+    Provider {
+        name: "Open-Meteo",
+        url: "https://open-meteo.com",
+        used_for: "Weather, forecasts and the NZ heat map",
+        licence: "CC BY 4.0",
+        attribution: "Weather data by Open-Meteo.com",
+    },
+    Provider {
+        name: "ExchangeRate-API",
+        url: "https://www.exchangerate-api.com",
+        used_for: "Exchange rates",
+        licence: "Open access terms",
+        attribution: "Rates By Exchange Rate API",
+    },
+    Provider {
+        name: "Nager.Date",
+        url: "https://date.nager.at",
+        used_for: "Public holidays outside NZ",
+        licence: "MIT",
+        attribution: "Holiday data by Nager.Date",
+    },
+    Provider {
+        name: "GeoNet",
+        url: "https://www.geonet.org.nz",
+        used_for: "Recent earthquakes on the NZ map",
+        licence: "CC BY 3.0 NZ",
+        attribution: "Earthquake data from GeoNet, funded by EQC, GNS Science and LINZ",
+    },
+    Provider {
+        name: "NOAA Space Weather Prediction Center",
+        url: "https://www.swpc.noaa.gov",
+        used_for: "Planetary Kp index for the aurora outlook",
+        licence: "Public domain (US government work)",
+        attribution: "Kp index from NOAA SWPC",
+    },
+    Provider {
+        name: "Open Notify",
+        url: "https://github.com/open-notify/Open-Notify-API",
+        used_for: "ISS position on the world map",
+        licence: "Free public API (no licence stated)",
+        attribution: "ISS position from Open Notify",
+    },
+    Provider {
+        name: "CelesTrak",
+        url: "https://celestrak.org",
+        used_for: "ISS orbital elements for visible pass times",
+        licence: "Free public data (no licence stated)",
+        attribution: "Orbital elements from CelesTrak",
+    },
+    Provider {
+        name: "Natural Earth",
+        url: "https://www.naturalearthdata.com",
+        used_for: "Map coastlines and the bundled NZ outline",
+        licence: "Public domain",
+        attribution: "Made with Natural Earth",
+    },
+    Provider {
+        name: "LINZ Data Service",
+        url: "https://data.linz.govt.nz",
+        used_for: "State highway routes on the NZ map",
+        licence: "CC BY 4.0",
+        attribution: "Sourced from the LINZ Data Service and licensed for reuse under CC BY 4.0",
+    },
+    Provider {
+        name: "GeoNames",
+        url: "https://www.geonames.org",
+        used_for: "Positions of the bundled world cities for /addcity",
+        licence: "CC BY 4.0",
+        attribution: "City data from GeoNames (geonames.org)",
+    },
+    Provider {
+        name: "IANA tz database",
+        url: "https://www.iana.org/time-zones",
+        used_for: "Time zone rules",
+        licence: "Public domain",
+        attribution: "Time zone data from the IANA tz database",
+    },
+];
+
+/// the app's own licence, as declared in Cargo.toml
+pub const APP_LICENCE: &str = env!("CARGO_PKG_LICENSE");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_provider_declares_licence_and_attribution() {
+        for provider in PROVIDERS {
+            assert!(!provider.licence.is_empty(), "{} licence", provider.name);
+            assert!(
+                !provider.attribution.is_empty(),
+                "{} attribution",
+                provider.name
+            );
+            assert!(
+                provider.url.starts_with("https://"),
+                "{} url",
+                provider.name
+            );
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! recent felt earthquakes from GeoNet, plotted on the NZ map
+//! the quake endpoint returns the latest MMI 3+ events; only the last week is kept
+
+use std::future::Future;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+use crate::stats::ServiceMetrics;
+
+/// quakes older than this are left off the map
+pub const RECENT: Duration = Duration::days(7);
+
+/// how often the quake list is re-fetched
+const REFRESH_EVERY: std::time::Duration = std::time::Duration::from_secs(600);
+
+const GEONET_QUAKE_URL: &str = "https://api.geonet.org.nz/quake?MMI=3";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quake {
+    pub time: DateTime<Utc>,
+    pub magnitude: f64,
+    pub depth_km: f64,
+    pub lat: f64,
+    pub lon: f64,
+    pub locality: String,
+}
+
+impl Quake {
+    /// e.g. "M4.3 10 km north of Seddon, 2h ago"
+    pub fn summary(&self, now: DateTime<Utc>) -> String {
+        let age = now - self.time;
+        let ago = if age < Duration::hours(1) {
+            format!("{}m ago", age.num_minutes().max(0))
+        } else if age < Duration::days(1) {
+            format!("{}h ago", age.num_hours())
+        } else {
+            format!("{}d ago", age.num_days())
+        };
+        format!("M{:.1} {}, {}", self.magnitude, self.locality, ago)
+    }
+}
+
+#[derive(Deserialize)]
+struct FeatureCollection {
+    features: Vec<Feature>,
+}
+
+#[derive(Deserialize)]
+struct Feature {
+    geometry: Geometry,
+    properties: Properties,
+}
+
+#[derive(Deserialize)]
+struct Geometry {
+    /// [lon, lat]
+    coordinates: Vec<f64>,
+}
+
+#[derive(Deserialize)]
+struct Properties {
+    time: String,
+    depth: f64,
+    magnitude: f64,
+    locality: String,
+}
+
+/// parse GeoNet's GeoJSON quake list, skipping entries without a usable time or position
+pub fn parse_geonet_quakes(body: &str) -> Result<Vec<Quake>> {
+    let collection: FeatureCollection =
+        serde_json::from_str(body).context("failed to parse GeoNet quakes")?;
+    Ok(collection
+        .features
+        .into_iter()
+        .filter_map(|feature| {
+            let [lon, lat] = feature.geometry.coordinates[..] else {
+                return None;
+            };
+            let time = DateTime::parse_from_rfc3339(&feature.properties.time).ok()?;
+            Some(Quake {
+                time: time.with_timezone(&Utc),
+                magnitude: feature.properties.magnitude,
+                depth_km: feature.properties.depth,
+                lat,
+                lon,
+                locality: feature.properties.locality,
+            })
+        })
+        .collect())
+}
+
+/// the quakes within `RECENT` of now, largest first
+pub fn recent_quakes(quakes: &[Quake], now: DateTime<Utc>) -> Vec<&Quake> {
+    let mut recent: Vec<&Quake> = quakes
+        .iter()
+        .filter(|quake| now - quake.time <= RECENT)
+        .collect();
+    recent.sort_by(|a, b| b.magnitude.total_cmp(&a.magnitude));
+    recent
+}
+
+/// the latest felt quakes, fetched every ten minutes while the map is on
+pub struct QuakeService {
+    client: reqwest::Client,
+    quakes: Vec<Quake>,
+    last_attempt: Option<Instant>,
+    metrics: ServiceMetrics,
+}
+
+impl QuakeService {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            quakes: Vec::new(),
+            last_attempt: None,
+            metrics: ServiceMetrics::default(),
+        }
+    }
+
+    pub fn needs_fetch(&self) -> bool {
+        self.last_attempt
+            .is_none_or(|attempt| attempt.elapsed() >= REFRESH_EVERY)
+    }
+
+    /// the request for the quake list, to run off the UI loop; hand its result to `finish_fetch`
+    pub fn start_fetch(&mut self) -> impl Future<Output = Result<Vec<Quake>>> + Send + 'static {
+        self.last_attempt = Some(Instant::now());
+        self.metrics.record_api_call();
+        let client = self.client.clone();
+        async move {
+            let body = client
+                .get(GEONET_QUAKE_URL)
+                .header("Accept", "application/vnd.geo+json;version=2")
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .context("failed to fetch quakes")?
+                .text()
+                .await
+                .context("failed to read quake response")?;
+            parse_geonet_quakes(&body)
+        }
+    }
+
+    /// replace the quake list; on failure the previous list is kept until the next attempt
+    pub fn finish_fetch(&mut self, fetched: Result<Vec<Quake>>) -> Result<()> {
+        self.quakes = fetched?;
+        Ok(())
+    }
+
+    pub fn quakes(&self) -> &[Quake] {
+        &self.quakes
+    }
+
+    pub fn metrics(&self) -> ServiceMetrics {
+        self.metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const SAMPLE: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [174.1, -41.7]},
+                "properties": {
+                    "publicID": "2025p100001",
+                    "time": "2025-06-06T10:15:00.000Z",
+                    "depth": 12.4,
+                    "magnitude": 4.3,
+                    "mmi": 4,
+                    "locality": "10 km north of Seddon",
+                    "quality": "best"
+                }
+            },
+            {
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [176.2, -38.1]},
+                "properties": {
+                    "publicID": "2025p100002",
+                    "time": "2025-06-06T11:40:00.000Z",
+                    "depth": 5.0,
+                    "magnitude": 3.1,
+                    "mmi": 3,
+                    "locality": "5 km west of Rotorua",
+                    "quality": "preliminary"
+                }
+            },
+            {
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [178.0, -37.5]},
+                "properties": {
+                    "publicID": "2025p000003",
+                    "time": "2025-05-01T00:00:00.000Z",
+                    "depth": 30.0,
+                    "magnitude": 5.6,
+                    "mmi": 5,
+                    "locality": "40 km east of Te Araroa",
+                    "quality": "best"
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn parses_geonet_quakes_and_keeps_the_last_week() {
+        let quakes = parse_geonet_quakes(SAMPLE).expect("sample should parse");
+        assert_eq!(quakes.len(), 3);
+        assert_eq!((quakes[0].lon, quakes[0].lat), (174.1, -41.7));
+        assert_eq!(quakes[0].depth_km, 12.4);
+
+        let now = Utc.with_ymd_and_hms(2025, 6, 6, 12, 15, 0).unwrap();
+        let recent = recent_quakes(&quakes, now);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].magnitude, 4.3);
+        assert_eq!(recent[0].summary(now), "M4.3 10 km north of Seddon, 2h ago");
+        assert_eq!(recent[1].summary(now), "M3.1 5 km west of Rotorua, 35m ago");
+
+        assert!(parse_geonet_quakes("{}").is_err());
+    }
+
+    #[test]
+    fn a_failed_fetch_keeps_the_last_list() {
+        let mut service = QuakeService::new(reqwest::Client::new());
+        assert!(service.finish_fetch(parse_geonet_quakes(SAMPLE)).is_ok());
+        assert_eq!(service.quakes().len(), 3);
+        assert!(
+            service
+                .finish_fetch(Err(anyhow::anyhow!("offline")))
+                .is_err()
+        );
+        assert_eq!(service.quakes().len(), 3);
+    }
+}
@@ -28,6 +28,18 @@ pub struct RepresentativeCityReference {
     pub currency_code: &'static str,
 }
 
+/// an entry in the bundled world city database (`data/world_cities.csv`)
+#[derive(Debug, Clone, Copy)]
+pub struct WorldCityReference {
+    pub name: &'static str,
+    pub code: &'static str,
+    pub country_code: &'static str,
+    pub timezone: &'static str,
+    pub currency_code: &'static str,
+    pub lat: f64,
+    pub lon: f64,
+}
+
 include!(concat!(env!("OUT_DIR"), "/reference_data.rs"));
 
 pub fn normalise_country_code(value: &str) -> String {
@@ -55,6 +67,19 @@ pub fn country_by_code(code: &str) -> Option<&'static CountryReference> {
         .find(|country| country.code == code.as_str())
 }
 
+/// ISO 3166 alpha-2 code, taken from the country's two-letter alias
+pub fn country_alpha2(code: &str) -> Option<String> {
+    let aliases = country_by_code(code)?.aliases;
+    let two_letter =
+        |alias: &&&str| alias.len() == 2 && alias.chars().all(|ch| ch.is_ascii_alphabetic());
+    aliases
+        .iter()
+        .filter(two_letter)
+        .find(|alias| alias.chars().all(|ch| ch.is_ascii_uppercase()))
+        .or_else(|| aliases.iter().find(two_letter))
+        .map(|alias| alias.to_ascii_uppercase())
+}
+
 pub fn lookup_country(query: &str) -> Option<&'static CountryReference> {
     let query = query.trim().to_lowercase();
     COUNTRY_REFERENCES.iter().find(|country| {
@@ -74,6 +99,18 @@ pub fn currency_by_code(code: &str) -> Option<&'static CurrencyReference> {
         .find(|currency| currency.code == code.as_str())
 }
 
+/// approximate cross rate from the bundled monthly averages, for when the API is unreachable
+pub fn bundled_rate(from: &str, to: &str) -> Option<f64> {
+    let per_usd = |code: &str| {
+        let code = normalise_currency_code(code);
+        FALLBACK_RATES_PER_USD
+            .iter()
+            .find(|(bundled, _)| *bundled == code.as_str())
+            .map(|(_, rate)| *rate)
+    };
+    Some(per_usd(to)? / per_usd(from)?)
+}
+
 pub fn lookup_currency(query: &str) -> Option<&'static CurrencyReference> {
     let query = query.trim().to_lowercase();
     CURRENCY_REFERENCES.iter().find(|currency| {
@@ -123,6 +160,13 @@ pub fn representative_city_by_currency_code(
     representative_city_by_country_code(country_code)
 }
 
+pub fn world_city_by_name(name: &str) -> Option<&'static WorldCityReference> {
+    let name = name.trim();
+    WORLD_CITY_REFERENCES
+        .iter()
+        .find(|city| city.name.eq_ignore_ascii_case(name))
+}
+
 pub fn search_representative_cities(query: &str) -> Vec<&'static RepresentativeCityReference> {
     let query = query.trim().to_lowercase();
     let mut matches: Vec<_> = REPRESENTATIVE_CITY_REFERENCES
@@ -289,6 +333,14 @@ mod tests {
         assert_eq!(country.code, "GBR");
     }
 
+    #[test]
+    fn derives_alpha2_from_two_letter_alias() {
+        assert_eq!(country_alpha2("USA").as_deref(), Some("US"));
+        assert_eq!(country_alpha2("GBR").as_deref(), Some("GB"));
+        assert_eq!(country_alpha2("NZL").as_deref(), Some("NZ"));
+        assert_eq!(country_alpha2("BES"), None);
+    }
+
     #[test]
     fn looks_up_currency_aliases() {
         let currency = lookup_currency("yen").expect("currency alias should resolve");
@@ -346,6 +398,28 @@ mod tests {
         assert_eq!(currency.code, "ILS");
     }
 
+    #[test]
+    fn world_cities_resolve_country_currency_and_timezone() {
+        assert!(WORLD_CITY_REFERENCES.len() >= 500);
+        for city in WORLD_CITY_REFERENCES {
+            assert!(
+                country_by_code(city.country_code).is_some(),
+                "unknown country for {}",
+                city.name
+            );
+            assert!(
+                city.timezone.parse::<chrono_tz::Tz>().is_ok(),
+                "unknown timezone {} for {}",
+                city.timezone,
+                city.name
+            );
+        }
+
+        let tokyo = world_city_by_name("tokyo").expect("tokyo should be bundled");
+        assert_eq!(tokyo.timezone, "Asia/Tokyo");
+        assert_eq!(tokyo.currency_code, "JPY");
+    }
+
     #[test]
     fn every_country_has_a_representative_city() {
         for country in COUNTRY_REFERENCES {
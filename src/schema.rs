@@ -0,0 +1,47 @@
+//! provider response schema helpers
+//! adapters capture unrecognised fields so upstream additions are noted, not fatal
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// names of captured fields that are not in the adapter's known list, sorted
+pub fn unknown_fields(extra: &BTreeMap<String, Value>, known: &[&str]) -> Vec<String> {
+    extra
+        .keys()
+        .filter(|key| !known.contains(&key.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// short summary for the stats overlay, e.g. "2 new fields: foo, bar"
+pub fn drift_label(fields: &[String]) -> Option<String> {
+    match fields.len() {
+        0 => None,
+        1 => Some(format!("1 new field: {}", fields[0])),
+        count => Some(format!("{} new fields: {}", count, fields.join(", "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_known_fields_and_summarises_the_rest() {
+        let extra: BTreeMap<String, Value> = [
+            ("provider".to_string(), Value::Null),
+            ("zeta".to_string(), Value::Null),
+            ("alpha".to_string(), Value::Null),
+        ]
+        .into_iter()
+        .collect();
+
+        let fields = unknown_fields(&extra, &["provider"]);
+        assert_eq!(fields, vec!["alpha".to_string(), "zeta".to_string()]);
+        assert_eq!(
+            drift_label(&fields).as_deref(),
+            Some("2 new fields: alpha, zeta")
+        );
+        assert_eq!(drift_label(&[]), None);
+    }
+}
@@ -0,0 +1,239 @@
+//! rows of the config editor's Settings tab: display options and refresh intervals,
+//! each stepped forward or back from the keyboard
+
+use crate::config::{Config, DateFormat, WeekStart};
+
+/// frame intervals offered for the animations, fastest first
+const ANIMATION_SPEEDS_MS: &[u64] = &[50, 75, 100, 150, 200, 300];
+/// how often weather and exchange rates can be re-fetched
+const DATA_REFRESH_SECS: &[u64] = &[60, 120, 300, 600, 900, 1800, 3600];
+/// how often a custom panel's command can be re-run
+const PANEL_REFRESH_SECS: &[u64] = &[5, 10, 30, 60, 120, 300, 600, 1800, 3600];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingRow {
+    Seconds,
+    Clock,
+    Animations,
+    AnimationSpeed,
+    WorldClock,
+    DateDetails,
+    BigClock,
+    DateFormat,
+    WeekStart,
+    Checklist,
+    WeatherRefresh,
+    RatesRefresh,
+    /// a custom panel, by its index in `[[panel.custom]]`
+    PanelRefresh(usize),
+}
+
+impl SettingRow {
+    /// every row for this config: the display options, then one per custom panel
+    pub fn rows(config: &Config) -> Vec<Self> {
+        let mut rows = vec![
+            Self::Seconds,
+            Self::Clock,
+            Self::Animations,
+            Self::AnimationSpeed,
+            Self::WorldClock,
+            Self::DateDetails,
+            Self::BigClock,
+            Self::DateFormat,
+            Self::WeekStart,
+            Self::Checklist,
+            Self::WeatherRefresh,
+            Self::RatesRefresh,
+        ];
+        let panels = config.panel.as_ref().map_or(0, |panel| panel.custom.len());
+        rows.extend((0..panels).map(Self::PanelRefresh));
+        rows
+    }
+
+    pub fn label(self, config: &Config) -> String {
+        match self {
+            Self::Seconds => "Show seconds".to_string(),
+            Self::Clock => "Clock".to_string(),
+            Self::Animations => "Animations".to_string(),
+            Self::AnimationSpeed => "Animation frame".to_string(),
+            Self::WorldClock => "World clock strip".to_string(),
+            Self::DateDetails => "Date details".to_string(),
+            Self::BigClock => "Big clock".to_string(),
+            Self::DateFormat => "Date format".to_string(),
+            Self::WeekStart => "Week starts".to_string(),
+            Self::Checklist => "Checklist panel".to_string(),
+            Self::WeatherRefresh => "Weather refresh".to_string(),
+            Self::RatesRefresh => "Rates refresh".to_string(),
+            Self::PanelRefresh(index) => config
+                .panel
+                .as_ref()
+                .and_then(|panel| panel.custom.get(index))
+                .map_or_else(
+                    || "Panel refresh".to_string(),
+                    |panel| format!("Panel: {}", panel.title),
+                ),
+        }
+    }
+
+    pub fn value(self, config: &Config) -> String {
+        let display = &config.display;
+        let on_off = |on: bool| if on { "On" } else { "Off" }.to_string();
+        match self {
+            Self::Seconds => on_off(display.show_seconds),
+            Self::Clock => if display.use_24_hour {
+                "24-hour"
+            } else {
+                "12-hour"
+            }
+            .to_string(),
+            Self::Animations => on_off(display.show_animations),
+            Self::AnimationSpeed => format!("{} ms", display.animation_speed_ms),
+            Self::WorldClock => on_off(display.show_world_clock),
+            Self::DateDetails => on_off(display.show_date_details),
+            Self::BigClock => on_off(display.big_clock),
+            Self::DateFormat => match display.date_format {
+                DateFormat::DayMonth => "day-month (14 Mar)",
+                DateFormat::MonthDay => "month-day (Mar 14)",
+                DateFormat::Iso => "iso (03-14)",
+            }
+            .to_string(),
+            Self::WeekStart => match display.week_starts {
+                WeekStart::Monday => "Monday",
+                WeekStart::Sunday => "Sunday",
+            }
+            .to_string(),
+            Self::Checklist => on_off(display.show_checklist),
+            Self::WeatherRefresh => every(config.effective_weather().refresh_secs),
+            Self::RatesRefresh => every(config.effective_exchange().refresh_secs),
+            Self::PanelRefresh(index) => config
+                .panel
+                .as_ref()
+                .and_then(|panel| panel.custom.get(index))
+                .map_or_else(String::new, |panel| every(panel.refresh_secs)),
+        }
+    }
+
+    /// step the setting forward (Enter, +) or back (-): toggles flip either way, choices
+    /// cycle and numbers move to the next preset, stopping at either end
+    pub fn step(self, config: &mut Config, forward: bool) {
+        let display = &mut config.display;
+        match self {
+            Self::Seconds => display.show_seconds = !display.show_seconds,
+            Self::Clock => display.use_24_hour = !display.use_24_hour,
+            Self::Animations => display.show_animations = !display.show_animations,
+            Self::AnimationSpeed => {
+                display.animation_speed_ms =
+                    step_preset(ANIMATION_SPEEDS_MS, display.animation_speed_ms, forward);
+            }
+            Self::WorldClock => display.show_world_clock = !display.show_world_clock,
+            Self::DateDetails => display.show_date_details = !display.show_date_details,
+            Self::BigClock => display.big_clock = !display.big_clock,
+            Self::DateFormat => {
+                let order = [DateFormat::DayMonth, DateFormat::MonthDay, DateFormat::Iso];
+                let at = order
+                    .iter()
+                    .position(|format| *format == display.date_format)
+                    .unwrap_or(0);
+                let next = if forward {
+                    at + 1
+                } else {
+                    at + order.len() - 1
+                };
+                display.date_format = order[next % order.len()];
+            }
+            Self::WeekStart => {
+                display.week_starts = match display.week_starts {
+                    WeekStart::Monday => WeekStart::Sunday,
+                    WeekStart::Sunday => WeekStart::Monday,
+                };
+            }
+            Self::Checklist => display.show_checklist = !display.show_checklist,
+            Self::WeatherRefresh => {
+                let weather = config.weather.get_or_insert_default();
+                weather.refresh_secs =
+                    step_preset(DATA_REFRESH_SECS, weather.refresh_secs, forward);
+            }
+            Self::RatesRefresh => {
+                let exchange = config.exchange.get_or_insert_default();
+                exchange.refresh_secs =
+                    step_preset(DATA_REFRESH_SECS, exchange.refresh_secs, forward);
+            }
+            Self::PanelRefresh(index) => {
+                if let Some(panel) = config
+                    .panel
+                    .as_mut()
+                    .and_then(|panel| panel.custom.get_mut(index))
+                {
+                    panel.refresh_secs =
+                        step_preset(PANEL_REFRESH_SECS, panel.refresh_secs, forward);
+                }
+            }
+        }
+    }
+}
+
+/// "every 5 min" for whole minutes, else "every 45 s"
+fn every(secs: u64) -> String {
+    if secs >= 60 && secs.is_multiple_of(60) {
+        format!("every {} min", secs / 60)
+    } else {
+        format!("every {} s", secs)
+    }
+}
+
+/// the nearest preset above (or below) `current`, or `current` at either end
+fn step_preset(presets: &[u64], current: u64, forward: bool) -> u64 {
+    if forward {
+        presets.iter().copied().find(|&preset| preset > current)
+    } else {
+        presets
+            .iter()
+            .rev()
+            .copied()
+            .find(|&preset| preset < current)
+    }
+    .unwrap_or(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CustomPanelConfig, PanelConfig};
+
+    #[test]
+    fn rows_step_through_toggles_choices_and_presets() {
+        let mut config = Config {
+            panel: Some(PanelConfig {
+                custom: vec![CustomPanelConfig {
+                    title: "Uptime".to_string(),
+                    command: "uptime".to_string(),
+                    refresh_secs: 45,
+                }],
+                ..PanelConfig::default()
+            }),
+            ..Config::default()
+        };
+        let rows = SettingRow::rows(&config);
+        assert_eq!(rows.len(), 13);
+        assert_eq!(rows[12].label(&config), "Panel: Uptime");
+
+        SettingRow::Clock.step(&mut config, true);
+        assert_eq!(SettingRow::Clock.value(&config), "12-hour");
+        SettingRow::DateFormat.step(&mut config, false);
+        assert_eq!(config.display.date_format, DateFormat::Iso);
+        SettingRow::DateFormat.step(&mut config, true);
+        assert_eq!(config.display.date_format, DateFormat::DayMonth);
+
+        SettingRow::WeatherRefresh.step(&mut config, true);
+        assert_eq!(SettingRow::WeatherRefresh.value(&config), "every 10 min");
+        SettingRow::RatesRefresh.step(&mut config, false);
+        assert_eq!(SettingRow::RatesRefresh.value(&config), "every 2 min");
+        SettingRow::PanelRefresh(0).step(&mut config, false);
+        assert_eq!(SettingRow::PanelRefresh(0).value(&config), "every 30 s");
+        for _ in 0..10 {
+            SettingRow::AnimationSpeed.step(&mut config, true);
+        }
+        assert_eq!(config.display.animation_speed_ms, 300);
+        assert!(config.validate().is_ok());
+    }
+}
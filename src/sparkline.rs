@@ -0,0 +1,62 @@
+//! one-row trend charts drawn with block characters, for spans beside text
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// `values` as a row of bars at most `width` wide, scaled from the lowest to the highest;
+/// longer series are averaged down to fit, and fewer than two values draw nothing
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    let values: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if values.len() < 2 || width == 0 {
+        return String::new();
+    }
+    let values: Vec<f64> = if values.len() > width {
+        (0..width)
+            .map(|bucket| {
+                let start = bucket * values.len() / width;
+                let end = ((bucket + 1) * values.len() / width).max(start + 1);
+                values[start..end].iter().sum::<f64>() / (end - start) as f64
+            })
+            .collect()
+    } else {
+        values
+    };
+
+    let (low, high) = values.iter().fold((f64::MAX, f64::MIN), |(low, high), &v| {
+        (low.min(v), high.max(v))
+    });
+    let span = high - low;
+    values
+        .iter()
+        .map(|&v| {
+            // a flat series sits in the middle rather than on the floor
+            let level = if span > 0.0 {
+                ((v - low) / span * 7.0).round() as usize
+            } else {
+                3
+            };
+            BARS[level.min(7)]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_between_the_extremes_and_fits_the_width() {
+        assert_eq!(
+            sparkline(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], 8),
+            "▁▂▃▄▅▆▇█"
+        );
+        assert_eq!(sparkline(&[4.0, 4.0, 4.0], 8), "▄▄▄");
+        assert_eq!(sparkline(&[3.0], 8), "");
+        assert_eq!(sparkline(&[1.0, f64::NAN, 9.0], 8), "▁█");
+
+        // 24 hours in six bars, each the average of four
+        let day: Vec<f64> = (0..24).map(|hour| hour as f64).collect();
+        let bars = sparkline(&day, 6);
+        assert_eq!(bars.chars().count(), 6);
+        assert!(bars.starts_with('▁') && bars.ends_with('█'));
+    }
+}
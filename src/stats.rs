@@ -0,0 +1,79 @@
+//! lightweight session metrics
+//! counters for the /stats overlay, threaded through the data services
+
+use std::time::{Duration, Instant};
+
+/// per-service request counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServiceMetrics {
+    pub api_calls: u64,
+    pub cache_hits: u64,
+}
+
+impl ServiceMetrics {
+    pub fn record_api_call(&mut self) {
+        self.api_calls += 1;
+    }
+
+    pub fn record_cache_hit(&mut self) {
+        self.cache_hits += 1;
+    }
+}
+
+/// user-facing counters for the current session
+#[derive(Debug, Clone)]
+pub struct SessionStats {
+    started: Instant,
+    pub conversions: u64,
+    pub cities_cycled: u64,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            conversions: 0,
+            cities_cycled: 0,
+        }
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn record_conversion(&mut self) {
+        self.conversions += 1;
+    }
+
+    pub fn record_city_cycle(&mut self) {
+        self.cities_cycled += 1;
+    }
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// format a duration as "1h 02m 05s" (hours omitted when zero)
+pub fn format_uptime(duration: Duration) -> String {
+    let total = duration.as_secs();
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else {
+        format!("{}m {:02}s", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_uptime_with_and_without_hours() {
+        assert_eq!(format_uptime(Duration::from_secs(65)), "1m 05s");
+        assert_eq!(format_uptime(Duration::from_secs(3725)), "1h 02m 05s");
+    }
+}
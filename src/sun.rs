@@ -0,0 +1,135 @@
+//! sunrise and sunset from latitude and longitude
+//! the NOAA sunrise equation, accurate to a minute or two; no API involved
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::config::City;
+use crate::reference::lookup_country;
+use crate::weather::city_coords;
+
+/// solar altitude at sunrise and sunset, allowing for refraction and the sun's radius
+const HORIZON_DEGREES: f64 = -0.833;
+/// earth's axial tilt
+const OBLIQUITY_DEGREES: f64 = 23.4397;
+const J2000: f64 = 2_451_545.0;
+const UNIX_EPOCH_JULIAN: f64 = 2_440_587.5;
+
+/// daylight for one local date
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Daylight {
+    Normal {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+    },
+    PolarDay,
+    PolarNight,
+}
+
+impl Daylight {
+    pub fn is_day_at(&self, instant: DateTime<Utc>) -> bool {
+        match self {
+            Self::Normal { sunrise, sunset } => *sunrise <= instant && instant < *sunset,
+            Self::PolarDay => true,
+            Self::PolarNight => false,
+        }
+    }
+}
+
+fn julian_to_utc(julian: f64) -> Option<DateTime<Utc>> {
+    let millis = ((julian - UNIX_EPOCH_JULIAN) * 86_400_000.0).round() as i64;
+    DateTime::from_timestamp_millis(millis)
+}
+
+/// sunrise and sunset on a calendar date at a place (longitude east-positive)
+pub fn daylight(date: NaiveDate, lat: f64, lon: f64) -> Option<Daylight> {
+    let j2000 = NaiveDate::from_ymd_opt(2000, 1, 1)?;
+    // mean solar noon at this longitude, in days since J2000
+    let mean_noon = (date - j2000).num_days() as f64 - lon / 360.0;
+
+    let anomaly = (357.5291 + 0.985_600_28 * mean_noon)
+        .rem_euclid(360.0)
+        .to_radians();
+    let centre =
+        1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_longitude = (anomaly.to_degrees() + centre + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit =
+        J2000 + mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+
+    let declination = (ecliptic_longitude.sin() * OBLIQUITY_DEGREES.to_radians().sin()).asin();
+    let latitude = lat.to_radians();
+    let cos_hour_angle = (HORIZON_DEGREES.to_radians().sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+
+    if cos_hour_angle < -1.0 {
+        return Some(Daylight::PolarDay);
+    }
+    if cos_hour_angle > 1.0 {
+        return Some(Daylight::PolarNight);
+    }
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    Some(Daylight::Normal {
+        sunrise: julian_to_utc(transit - half_day)?,
+        sunset: julian_to_utc(transit + half_day)?,
+    })
+}
+
+/// unit vector towards the sun in earth-centred equatorial (inertial) coordinates, good to
+/// about a hundredth of a degree, which is plenty for telling night from twilight
+pub fn sun_direction(instant: DateTime<Utc>) -> [f64; 3] {
+    let days = instant.timestamp_millis() as f64 / 86_400_000.0 + UNIX_EPOCH_JULIAN - J2000;
+    let mean_longitude = 280.460 + 0.985_647_4 * days;
+    let anomaly = (357.528 + 0.985_600_3 * days).to_radians();
+    let ecliptic_longitude =
+        (mean_longitude + 1.915 * anomaly.sin() + 0.020 * (2.0 * anomaly).sin()).to_radians();
+    let obliquity = (OBLIQUITY_DEGREES - 0.000_000_4 * days).to_radians();
+    [
+        ecliptic_longitude.cos(),
+        obliquity.cos() * ecliptic_longitude.sin(),
+        obliquity.sin() * ecliptic_longitude.sin(),
+    ]
+}
+
+/// best-known coordinates for a city: the bundled city tables, then the country centre
+pub fn city_coordinates(city: &City) -> Option<(f64, f64)> {
+    city_coords(&city.name, &city.code)
+        .or_else(|| lookup_country(&city.country).map(|country| (country.lat, country.lon)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    fn minutes_of_day(instant: DateTime<Utc>, offset_hours: i64) -> i64 {
+        let local = instant + chrono::Duration::hours(offset_hours);
+        i64::from(local.hour()) * 60 + i64::from(local.minute())
+    }
+
+    #[test]
+    fn matches_published_times_within_a_few_minutes() {
+        // wellington, 21 june 2025 (NZST): sunrise 07:47, sunset 16:58
+        let date = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+        let Some(Daylight::Normal { sunrise, sunset }) = daylight(date, -41.2865, 174.7762) else {
+            panic!("wellington should have a sunrise");
+        };
+        assert!((minutes_of_day(sunrise, 12) - (7 * 60 + 47)).abs() <= 3);
+        assert!((minutes_of_day(sunset, 12) - (16 * 60 + 58)).abs() <= 3);
+        assert!(!Daylight::Normal { sunrise, sunset }.is_day_at(sunset));
+
+        // london, same date (BST): sunrise 04:43, sunset 21:21
+        let Some(Daylight::Normal { sunrise, sunset }) = daylight(date, 51.5074, -0.1278) else {
+            panic!("london should have a sunrise");
+        };
+        assert!((minutes_of_day(sunrise, 1) - (4 * 60 + 43)).abs() <= 3);
+        assert!((minutes_of_day(sunset, 1) - (21 * 60 + 21)).abs() <= 3);
+    }
+
+    #[test]
+    fn reports_polar_day_and_night() {
+        let date = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+        assert_eq!(daylight(date, 78.22, 15.65), Some(Daylight::PolarDay));
+        assert_eq!(daylight(date, -77.85, 166.67), Some(Daylight::PolarNight));
+    }
+}
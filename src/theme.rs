@@ -1,7 +1,12 @@
-//! catppuccin mocha theme implementation for ratatui
-//! provides a cohesive colour palette for the entire application
+//! colour themes for ratatui: the screen is drawn in catppuccin mocha and recoloured
+//! into the chosen theme, custom slot colours and colour depth on the way out
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, RwLock};
+
+use ratatui::buffer::Buffer;
 use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
 
 /// catppuccin mocha colour palette
 /// see: https://github.com/catppuccin/catppuccin
@@ -29,6 +34,516 @@ pub mod catppuccin {
     pub const BASE: Color = Color::Rgb(30, 30, 46);
 }
 
+/// the palette slots the screen is drawn with, in the order every flavour lists them
+const MOCHA: [Color; 16] = [
+    catppuccin::MAUVE,
+    catppuccin::RED,
+    catppuccin::PEACH,
+    catppuccin::YELLOW,
+    catppuccin::GREEN,
+    catppuccin::SAPPHIRE,
+    catppuccin::BLUE,
+    catppuccin::LAVENDER,
+    catppuccin::TEXT,
+    catppuccin::SUBTEXT1,
+    catppuccin::SUBTEXT0,
+    catppuccin::OVERLAY1,
+    catppuccin::OVERLAY0,
+    catppuccin::SURFACE2,
+    catppuccin::SURFACE1,
+    catppuccin::BASE,
+];
+
+/// names for the palette slots in `[theme.colors]`, in palette order
+pub const SLOT_NAMES: [&str; 16] = [
+    "mauve", "red", "peach", "yellow", "green", "sapphire", "blue", "lavender", "text", "subtext1",
+    "subtext0", "overlay1", "overlay0", "surface2", "surface1", "base",
+];
+
+/// catppuccin latte, the light flavour
+const LATTE: [Color; 16] = [
+    Color::Rgb(136, 57, 239),
+    Color::Rgb(210, 15, 57),
+    Color::Rgb(254, 100, 11),
+    Color::Rgb(223, 142, 29),
+    Color::Rgb(64, 160, 43),
+    Color::Rgb(32, 159, 181),
+    Color::Rgb(30, 102, 245),
+    Color::Rgb(114, 135, 253),
+    Color::Rgb(76, 79, 105),
+    Color::Rgb(92, 95, 119),
+    Color::Rgb(108, 111, 133),
+    Color::Rgb(140, 143, 161),
+    Color::Rgb(156, 160, 176),
+    Color::Rgb(172, 176, 190),
+    Color::Rgb(188, 192, 204),
+    Color::Rgb(239, 241, 245),
+];
+
+/// gruvbox dark
+const GRUVBOX: [Color; 16] = [
+    Color::Rgb(211, 134, 155),
+    Color::Rgb(251, 73, 52),
+    Color::Rgb(254, 128, 25),
+    Color::Rgb(250, 189, 47),
+    Color::Rgb(184, 187, 38),
+    Color::Rgb(142, 192, 124),
+    Color::Rgb(131, 165, 152),
+    Color::Rgb(177, 98, 134),
+    Color::Rgb(235, 219, 178),
+    Color::Rgb(213, 196, 161),
+    Color::Rgb(189, 174, 147),
+    Color::Rgb(168, 153, 132),
+    Color::Rgb(146, 131, 116),
+    Color::Rgb(102, 92, 84),
+    Color::Rgb(80, 73, 69),
+    Color::Rgb(40, 40, 40),
+];
+
+/// nord: polar night surfaces, snow storm text and aurora accents
+const NORD: [Color; 16] = [
+    Color::Rgb(180, 142, 173),
+    Color::Rgb(191, 97, 106),
+    Color::Rgb(208, 135, 112),
+    Color::Rgb(235, 203, 139),
+    Color::Rgb(163, 190, 140),
+    Color::Rgb(136, 192, 208),
+    Color::Rgb(129, 161, 193),
+    Color::Rgb(143, 188, 187),
+    Color::Rgb(236, 239, 244),
+    Color::Rgb(229, 233, 240),
+    Color::Rgb(216, 222, 233),
+    Color::Rgb(123, 136, 161),
+    Color::Rgb(97, 110, 136),
+    Color::Rgb(76, 86, 106),
+    Color::Rgb(67, 76, 94),
+    Color::Rgb(46, 52, 64),
+];
+
+/// dracula, which has no blue: purple and pink stand in
+const DRACULA: [Color; 16] = [
+    Color::Rgb(255, 121, 198),
+    Color::Rgb(255, 85, 85),
+    Color::Rgb(255, 184, 108),
+    Color::Rgb(241, 250, 140),
+    Color::Rgb(80, 250, 123),
+    Color::Rgb(139, 233, 253),
+    Color::Rgb(189, 147, 249),
+    Color::Rgb(214, 172, 255),
+    Color::Rgb(248, 248, 242),
+    Color::Rgb(230, 230, 224),
+    Color::Rgb(200, 200, 196),
+    Color::Rgb(128, 141, 190),
+    Color::Rgb(98, 114, 164),
+    Color::Rgb(68, 71, 90),
+    Color::Rgb(56, 58, 76),
+    Color::Rgb(40, 42, 54),
+];
+
+/// tokyo night, the "night" style
+const TOKYO_NIGHT: [Color; 16] = [
+    Color::Rgb(187, 154, 247),
+    Color::Rgb(247, 118, 142),
+    Color::Rgb(255, 158, 100),
+    Color::Rgb(224, 175, 104),
+    Color::Rgb(158, 206, 106),
+    Color::Rgb(125, 207, 255),
+    Color::Rgb(122, 162, 247),
+    Color::Rgb(180, 249, 248),
+    Color::Rgb(192, 202, 245),
+    Color::Rgb(169, 177, 214),
+    Color::Rgb(154, 165, 206),
+    Color::Rgb(115, 122, 162),
+    Color::Rgb(86, 95, 137),
+    Color::Rgb(65, 72, 104),
+    Color::Rgb(41, 46, 66),
+    Color::Rgb(26, 27, 38),
+];
+
+/// gruvbox light
+const GRUVBOX_LIGHT: [Color; 16] = [
+    Color::Rgb(143, 63, 113),
+    Color::Rgb(157, 0, 6),
+    Color::Rgb(175, 58, 3),
+    Color::Rgb(181, 118, 20),
+    Color::Rgb(121, 116, 14),
+    Color::Rgb(66, 123, 88),
+    Color::Rgb(7, 102, 120),
+    Color::Rgb(177, 98, 134),
+    Color::Rgb(60, 56, 54),
+    Color::Rgb(80, 73, 69),
+    Color::Rgb(102, 92, 84),
+    Color::Rgb(124, 111, 100),
+    Color::Rgb(146, 131, 116),
+    Color::Rgb(189, 174, 147),
+    Color::Rgb(213, 196, 161),
+    Color::Rgb(251, 241, 199),
+];
+
+/// the colour scheme the screen is shown in; everything is drawn in mocha and
+/// recoloured slot for slot on the way out
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Mocha,
+    Latte,
+    Gruvbox,
+    GruvboxLight,
+    Nord,
+    Dracula,
+    TokyoNight,
+}
+
+/// the terminal background a theme should suit; "auto" asks the terminal through
+/// COLORFGBG and keeps the theme as it is when that isn't set
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Background {
+    Light,
+    Dark,
+    Auto,
+}
+
+/// whether COLORFGBG ("15;0", "0;15", "default;default;0") names a light background
+fn colorfgbg_is_light(value: &str) -> Option<bool> {
+    let background: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    Some(matches!(background, 7 | 9..=15))
+}
+
+/// the terminal's background as COLORFGBG describes it, read once
+fn terminal_is_light() -> Option<bool> {
+    static LIGHT: OnceLock<Option<bool>> = OnceLock::new();
+    *LIGHT.get_or_init(|| {
+        std::env::var("COLORFGBG")
+            .ok()
+            .and_then(|value| colorfgbg_is_light(&value))
+    })
+}
+
+impl ThemeName {
+    pub const ALL: [Self; 7] = [
+        Self::Mocha,
+        Self::Latte,
+        Self::Gruvbox,
+        Self::GruvboxLight,
+        Self::Nord,
+        Self::Dracula,
+        Self::TokyoNight,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mocha => "mocha",
+            Self::Latte => "latte",
+            Self::Gruvbox => "gruvbox",
+            Self::GruvboxLight => "gruvbox-light",
+            Self::Nord => "nord",
+            Self::Dracula => "dracula",
+            Self::TokyoNight => "tokyo-night",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+
+    pub fn is_light(self) -> bool {
+        matches!(self, Self::Latte | Self::GruvboxLight)
+    }
+
+    /// this theme, or its counterpart when it doesn't suit `background`: gruvbox has a
+    /// light and a dark flavour, every other dark theme pairs with latte
+    pub fn for_background(self, background: Option<Background>) -> Self {
+        let light = match background {
+            None => return self,
+            Some(Background::Light) => true,
+            Some(Background::Dark) => false,
+            Some(Background::Auto) => match terminal_is_light() {
+                Some(light) => light,
+                None => return self,
+            },
+        };
+        match (self, light) {
+            (theme, light) if theme.is_light() == light => theme,
+            (Self::Gruvbox, _) => Self::GruvboxLight,
+            (Self::GruvboxLight, _) => Self::Gruvbox,
+            (_, true) => Self::Latte,
+            (_, false) => Self::Mocha,
+        }
+    }
+
+    fn palette(self) -> &'static [Color; 16] {
+        match self {
+            Self::Mocha => &MOCHA,
+            Self::Latte => &LATTE,
+            Self::Gruvbox => &GRUVBOX,
+            Self::GruvboxLight => &GRUVBOX_LIGHT,
+            Self::Nord => &NORD,
+            Self::Dracula => &DRACULA,
+            Self::TokyoNight => &TOKYO_NIGHT,
+        }
+    }
+}
+
+/// colours from `[theme.colors]`, laid over whichever theme is chosen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CustomColours {
+    /// one per palette slot, in `SLOT_NAMES` order
+    pub slots: [Option<Color>; 16],
+    pub rainbow: Option<[Color; 7]>,
+}
+
+impl CustomColours {
+    const NONE: Self = Self {
+        slots: [None; 16],
+        rainbow: None,
+    };
+
+    fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+/// the custom colours in use, set from the config at startup and on reload
+static CUSTOM_COLOURS: RwLock<CustomColours> = RwLock::new(CustomColours::NONE);
+
+pub fn set_custom_colours(colours: CustomColours) {
+    if let Ok(mut custom) = CUSTOM_COLOURS.write() {
+        *custom = colours;
+    }
+}
+
+fn custom_colours() -> CustomColours {
+    CUSTOM_COLOURS
+        .read()
+        .map_or(CustomColours::NONE, |custom| *custom)
+}
+
+/// high-contrast mode, set from the config at startup, on reload and by /contrast
+static HIGH_CONTRAST: AtomicBool = AtomicBool::new(false);
+
+pub fn set_high_contrast(on: bool) {
+    HIGH_CONTRAST.store(on, Ordering::Relaxed);
+}
+
+pub fn high_contrast() -> bool {
+    HIGH_CONTRAST.load(Ordering::Relaxed)
+}
+
+/// the slot each foreground slot is drawn with in high contrast: subtext and overlay
+/// become text, and the surface shades (unfocused borders, separators) become subtext0
+const HIGH_CONTRAST_FG: [usize; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 8, 8, 8, 8, 10, 10, 15];
+
+/// redraw a buffer drawn in mocha in `theme`, with any custom colours over it
+pub fn recolour(buf: &mut Buffer, theme: ThemeName) {
+    let custom = custom_colours();
+    let high_contrast = high_contrast();
+    if theme == ThemeName::Mocha && custom.is_empty() && !high_contrast {
+        return;
+    }
+    let mut palette = *theme.palette();
+    for (slot, colour) in palette.iter_mut().zip(custom.slots) {
+        if let Some(colour) = colour {
+            *slot = colour;
+        }
+    }
+    // anything off the palette is left as it is, which is what keeps a user's own
+    // #rrggbb (see `pin`) in the colour they asked for
+    let colour = |color: Color, foreground: bool| {
+        MOCHA
+            .iter()
+            .position(|slot| *slot == color)
+            .map_or(color, |index| {
+                if foreground && high_contrast {
+                    palette[HIGH_CONTRAST_FG[index]]
+                } else {
+                    palette[index]
+                }
+            })
+    };
+    for cell in buf.content.iter_mut() {
+        cell.fg = colour(cell.fg, true);
+        cell.bg = colour(cell.bg, false);
+    }
+}
+
+/// how many colours the terminal can show
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ColourDepth {
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Indexed256,
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+impl ColourDepth {
+    /// what COLORTERM and TERM say the terminal supports; terminals that set neither
+    /// (windows consoles) are taken to do truecolor
+    fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if colorterm.is_some_and(|value| matches!(value, "truecolor" | "24bit")) {
+            return Self::TrueColor;
+        }
+        let Some(term) = term.filter(|term| !term.is_empty()) else {
+            return Self::TrueColor;
+        };
+        if term.ends_with("-direct")
+            || ["kitty", "alacritty", "wezterm", "foot", "ghostty"]
+                .iter()
+                .any(|name| term.contains(name))
+        {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Indexed256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// the terminal's colour depth, read once
+    pub fn detect() -> Self {
+        static DEPTH: OnceLock<ColourDepth> = OnceLock::new();
+        *DEPTH.get_or_init(|| {
+            if std::env::var_os("WT_SESSION").is_some() {
+                return Self::TrueColor;
+            }
+            let colorterm = std::env::var("COLORTERM").ok();
+            let term = std::env::var("TERM").ok();
+            Self::from_env(colorterm.as_deref(), term.as_deref())
+        })
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// the nearest xterm 256-colour index: a 6×6×6 cube from 16, a grey ramp from 232
+fn nearest_indexed(rgb: (u8, u8, u8)) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |value: u8| {
+        (0..LEVELS.len())
+            .min_by_key(|&i| (LEVELS[i] as i32 - value as i32).abs())
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = (16 + 36 * r + 6 * g + b) as u8;
+    let cube_rgb = (LEVELS[r], LEVELS[g], LEVELS[b]);
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let grey = 8 + 10 * step;
+    if distance(rgb, (grey, grey, grey)) < distance(rgb, cube_rgb) {
+        232 + step
+    } else {
+        cube
+    }
+}
+
+/// the closest of the sixteen ANSI colours by hue, so pastels keep their colour rather
+/// than all landing on grey; near-greys go by lightness
+fn nearest_ansi((r, g, b): (u8, u8, u8)) -> Color {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = (max - min) as f64;
+    if chroma < 40.0 {
+        return match max {
+            0..60 => Color::Black,
+            60..140 => Color::DarkGray,
+            140..210 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+    let hue = if max as f64 == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max as f64 == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    let bright = max > 200;
+    match (((hue + 30.0) / 60.0) as usize % 6, bright) {
+        (0, false) => Color::Red,
+        (0, true) => Color::LightRed,
+        (1, false) => Color::Yellow,
+        (1, true) => Color::LightYellow,
+        (2, false) => Color::Green,
+        (2, true) => Color::LightGreen,
+        (3, false) => Color::Cyan,
+        (3, true) => Color::LightCyan,
+        (4, false) => Color::Blue,
+        (4, true) => Color::LightBlue,
+        (_, false) => Color::Magenta,
+        (_, true) => Color::LightMagenta,
+    }
+}
+
+/// bring every rgb colour in `buf` down to what a 256 or 16 colour terminal can show;
+/// run after `recolour`
+pub fn reduce_colours(buf: &mut Buffer, depth: ColourDepth) {
+    if depth == ColourDepth::TrueColor {
+        return;
+    }
+    let colour = |color: Color| match (color, depth) {
+        (Color::Rgb(r, g, b), ColourDepth::Indexed256) => {
+            Color::Indexed(nearest_indexed((r, g, b)))
+        }
+        (Color::Rgb(r, g, b), ColourDepth::Ansi16) => nearest_ansi((r, g, b)),
+        _ => color,
+    };
+    for cell in buf.content.iter_mut() {
+        cell.fg = colour(cell.fg);
+        cell.bg = colour(cell.bg);
+    }
+}
+
+/// a "#rrggbb" hex colour
+pub fn parse_hex(hex: &str) -> Option<Color> {
+    let hex = hex.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// a colour the user gave as #rrggbb, moved a step off any mocha slot it lands on so
+/// `recolour` doesn't take it for a theme colour and swap it out
+fn pin(colour: Color) -> Color {
+    match colour {
+        Color::Rgb(r, g, b) if MOCHA.contains(&colour) => {
+            Color::Rgb(r, g, if b == u8::MAX { b - 1 } else { b + 1 })
+        }
+        colour => colour,
+    }
+}
+
+/// a palette colour by name ("peach", "sapphire", ...), which follows the theme, or a
+/// "#rrggbb" hex colour, which is shown as given whatever the theme
+pub fn parse_colour(name: &str) -> Option<Color> {
+    let name = name.trim().to_ascii_lowercase();
+    if name.starts_with('#') {
+        return parse_hex(&name).map(pin);
+    }
+    Some(match name.as_str() {
+        "mauve" => catppuccin::MAUVE,
+        "red" => catppuccin::RED,
+        "peach" => catppuccin::PEACH,
+        "yellow" => catppuccin::YELLOW,
+        "green" => catppuccin::GREEN,
+        "sapphire" => catppuccin::SAPPHIRE,
+        "blue" => catppuccin::BLUE,
+        "lavender" => catppuccin::LAVENDER,
+        "text" => catppuccin::TEXT,
+        _ => return None,
+    })
+}
+
 /// themed styles for the application
 pub struct Theme;
 
@@ -62,8 +577,25 @@ impl Theme {
             .add_modifier(Modifier::BOLD)
     }
 
-    /// rainbow colour array for animations
+    /// temperature style, on the same bands as the forecast grid
+    pub fn temperature(temp_c: i32) -> Style {
+        let color = if temp_c >= 25 {
+            catppuccin::RED
+        } else if temp_c >= 18 {
+            catppuccin::YELLOW
+        } else if temp_c >= 10 {
+            catppuccin::GREEN
+        } else {
+            catppuccin::SAPPHIRE
+        };
+        Style::default().fg(color)
+    }
+
+    /// rainbow colour array for animations; `[theme.colors] rainbow` when set
     pub fn rainbow_colors() -> [Color; 7] {
+        if let Some(rainbow) = custom_colours().rainbow {
+            return rainbow.map(pin);
+        }
         [
             catppuccin::RED,
             catppuccin::PEACH,
@@ -80,3 +612,82 @@ impl Theme {
         Self::rainbow_colors()[index % 7]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn background_picks_a_light_or_dark_counterpart() {
+        assert_eq!(colorfgbg_is_light("0;15"), Some(true));
+        assert_eq!(colorfgbg_is_light("default;default;0"), Some(false));
+        assert_eq!(colorfgbg_is_light("15;8"), Some(false));
+        assert_eq!(colorfgbg_is_light("light"), None);
+
+        let light = Some(Background::Light);
+        let dark = Some(Background::Dark);
+        assert_eq!(ThemeName::Nord.for_background(None), ThemeName::Nord);
+        assert_eq!(ThemeName::Nord.for_background(light), ThemeName::Latte);
+        assert_eq!(ThemeName::Latte.for_background(light), ThemeName::Latte);
+        assert_eq!(ThemeName::Latte.for_background(dark), ThemeName::Mocha);
+        assert_eq!(
+            ThemeName::Gruvbox.for_background(light),
+            ThemeName::GruvboxLight
+        );
+        assert_eq!(
+            ThemeName::GruvboxLight.for_background(dark),
+            ThemeName::Gruvbox
+        );
+    }
+
+    #[test]
+    fn recolouring_leaves_hex_colours_alone() {
+        for slot in MOCHA {
+            assert!(!MOCHA.contains(&pin(slot)));
+        }
+        let peach = parse_colour("peach").unwrap();
+        let hex = parse_colour("#fab387").unwrap();
+        assert_ne!(peach, hex);
+
+        let mut buf = Buffer::empty(ratatui::layout::Rect::new(0, 0, 2, 1));
+        buf.content[0].fg = peach;
+        buf.content[1].fg = hex;
+        recolour(&mut buf, ThemeName::Nord);
+        assert_eq!(buf.content[0].fg, NORD[2]);
+        assert_eq!(buf.content[1].fg, hex);
+    }
+
+    #[test]
+    fn reduces_rgb_to_what_the_terminal_can_show() {
+        assert_eq!(
+            ColourDepth::from_env(Some("truecolor"), Some("xterm-256color")),
+            ColourDepth::TrueColor
+        );
+        assert_eq!(
+            ColourDepth::from_env(None, Some("screen-256color")),
+            ColourDepth::Indexed256
+        );
+        assert_eq!(
+            ColourDepth::from_env(None, Some("xterm")),
+            ColourDepth::Ansi16
+        );
+        assert_eq!(
+            ColourDepth::from_env(None, Some("xterm-kitty")),
+            ColourDepth::TrueColor
+        );
+
+        assert_eq!(nearest_indexed((255, 0, 0)), 196);
+        assert_eq!(nearest_indexed((30, 30, 46)), 235);
+        assert_eq!(nearest_ansi((243, 139, 168)), Color::LightRed);
+        assert_eq!(nearest_ansi((166, 227, 161)), Color::LightGreen);
+        assert_eq!(nearest_ansi((30, 30, 46)), Color::Black);
+        assert_eq!(nearest_ansi((205, 214, 244)), Color::White);
+
+        let mut buf = Buffer::empty(ratatui::layout::Rect::new(0, 0, 2, 1));
+        buf.content[0].fg = catppuccin::GREEN;
+        buf.content[1].fg = Color::Reset;
+        reduce_colours(&mut buf, ColourDepth::Indexed256);
+        assert!(matches!(buf.content[0].fg, Color::Indexed(_)));
+        assert_eq!(buf.content[1].fg, Color::Reset);
+    }
+}
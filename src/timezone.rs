@@ -2,11 +2,14 @@
 //! supports iana timezones and fixed utc offsets
 
 use chrono::{
-    DateTime, FixedOffset, Local, LocalResult, NaiveDateTime, Offset, TimeZone, Timelike, Utc,
+    DateTime, Datelike, FixedOffset, Local, LocalResult, NaiveDateTime, Offset, TimeZone, Timelike,
+    Utc,
 };
 use chrono_tz::Tz;
+use std::collections::HashMap;
 
-use crate::config::City;
+use crate::config::{City, DateFormat, WeekStart};
+use crate::sun::{Daylight, city_coordinates, daylight};
 
 #[derive(Debug, Clone)]
 pub(crate) enum ParsedTimezone {
@@ -23,7 +26,7 @@ impl ParsedTimezone {
         }
     }
 
-    fn from_local_datetime(
+    pub(crate) fn resolve_local_datetime(
         &self,
         naive_local: &NaiveDateTime,
     ) -> LocalResult<DateTime<FixedOffset>> {
@@ -39,7 +42,10 @@ impl ParsedTimezone {
         }
     }
 
-    fn convert_datetime(&self, datetime: &DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+    pub(crate) fn convert_datetime(
+        &self,
+        datetime: &DateTime<FixedOffset>,
+    ) -> DateTime<FixedOffset> {
         match self {
             Self::Iana(timezone) => datetime.with_timezone(timezone).fixed_offset(),
             Self::Fixed(offset) => datetime.with_timezone(offset),
@@ -79,6 +85,89 @@ fn parse_fixed_utc_offset(value: &str) -> Option<FixedOffset> {
     FixedOffset::east_opt(total_seconds)
 }
 
+/// parse a unix epoch in seconds, or milliseconds when it has 13 or more digits
+pub fn parse_epoch(input: &str) -> Option<DateTime<Utc>> {
+    let input = input.trim();
+    let value: i64 = input.parse().ok()?;
+    if input.trim_start_matches('-').len() >= 13 {
+        DateTime::from_timestamp_millis(value)
+    } else {
+        DateTime::from_timestamp(value, 0)
+    }
+}
+
+/// an instant as local time in a city, like "Tue 14 Nov 17:13:20 (UTC-05:00)"
+pub fn format_instant_in(
+    instant: DateTime<Utc>,
+    timezone: &str,
+    use_24_hour: bool,
+) -> Option<String> {
+    let local = parse_city_timezone(timezone)?.convert_datetime(&instant.fixed_offset());
+    Some(format!(
+        "{} (UTC{})",
+        local.format(&format!("%a %d %b {}", clock_format(use_24_hour, true))),
+        local.format("%:z")
+    ))
+}
+
+/// chrono format string for a clock time in the user's preferred style
+/// step size of the time converter's scrub mode
+pub const SCRUB_STEP_MINUTES: i32 = 30;
+
+/// a signed span as "+7h30m", "-45m" or "+2h"
+pub fn format_offset_minutes(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let (hours, mins) = (minutes.abs() / 60, minutes.abs() % 60);
+    match (hours, mins) {
+        (0, mins) => format!("{}{}m", sign, mins),
+        (hours, 0) => format!("{}{}h", sign, hours),
+        (hours, mins) => format!("{}{}h{:02}m", sign, hours, mins),
+    }
+}
+
+pub fn clock_format(use_24_hour: bool, show_seconds: bool) -> &'static str {
+    match (use_24_hour, show_seconds) {
+        (true, true) => "%H:%M:%S",
+        (true, false) => "%H:%M",
+        (false, true) => "%I:%M:%S %p",
+        (false, false) => "%I:%M %p",
+    }
+}
+
+/// format an hour and minute as "14:05" or "02:05 PM"
+pub fn format_clock(hour: u32, minute: u32, use_24_hour: bool) -> String {
+    if use_24_hour {
+        return format!("{:02}:{:02}", hour, minute);
+    }
+    let suffix = if hour < 12 { "AM" } else { "PM" };
+    let hour = match hour % 12 {
+        0 => 12,
+        other => other,
+    };
+    format!("{:02}:{:02} {}", hour, minute, suffix)
+}
+
+/// phrase like "their tomorrow morning" or "late tonight for them"
+pub fn relative_phrase(hour: u32, day_offset: i32) -> String {
+    let part = match hour {
+        0..=4 => "early hours",
+        5..=11 => "morning",
+        12..=16 => "afternoon",
+        17..=20 => "evening",
+        _ => "night",
+    };
+    match day_offset {
+        0 => match part {
+            "early hours" => "early hours for them".to_string(),
+            "night" => "late tonight for them".to_string(),
+            part => format!("this {} for them", part),
+        },
+        1 => format!("their tomorrow {}", part),
+        -1 => format!("their yesterday {}", part),
+        days => format!("their {} ({:+} days)", part, days),
+    }
+}
+
 /// time information for a city
 #[derive(Debug, Clone)]
 pub struct CityTime {
@@ -86,38 +175,45 @@ pub struct CityTime {
     pub city_code: String,
     timezone: ParsedTimezone,
     pub datetime: DateTime<FixedOffset>,
-    pub offset_hours: f32,
+    /// UTC offset in whole minutes, so +05:45 and +12:45 zones stay exact
+    pub offset_minutes: i32,
+    /// sunrise and sunset for the city's local date, when its location is known
+    pub daylight: Option<Daylight>,
 }
 
 impl CityTime {
     /// create a new city time from a city configuration
     pub fn from_city(city: &City) -> Option<Self> {
-        let timezone = parse_city_timezone(&city.timezone)?;
+        Some(Self::with_timezone(
+            city,
+            parse_city_timezone(&city.timezone)?,
+        ))
+    }
+
+    /// current time for a city whose timezone has already been parsed
+    fn with_timezone(city: &City, timezone: ParsedTimezone) -> Self {
         let datetime = timezone.current_datetime();
 
-        // calculate offset in hours using the fixed offset
         let fixed: FixedOffset = datetime.offset().fix();
-        let offset_secs = fixed.local_minus_utc();
-        let offset_hours = offset_secs as f32 / 3600.0;
+        let offset_minutes = fixed.local_minus_utc() / 60;
+        let daylight =
+            city_coordinates(city).and_then(|(lat, lon)| daylight(datetime.date_naive(), lat, lon));
 
-        Some(Self {
+        Self {
             city_name: city.name.clone(),
             city_code: city.code.clone(),
             timezone,
             datetime,
-            offset_hours,
-        })
+            offset_minutes,
+            daylight,
+        }
     }
 
     /// get the time formatted for display
     pub fn time_string(&self, use_24_hour: bool, show_seconds: bool) -> String {
-        let format = match (use_24_hour, show_seconds) {
-            (true, true) => "%H:%M:%S",
-            (true, false) => "%H:%M",
-            (false, true) => "%I:%M:%S %p",
-            (false, false) => "%I:%M %p",
-        };
-        self.datetime.format(format).to_string()
+        self.datetime
+            .format(clock_format(use_24_hour, show_seconds))
+            .to_string()
     }
 
     /// get the hour for clock display (0-23)
@@ -125,29 +221,74 @@ impl CityTime {
         self.datetime.hour()
     }
 
-    /// check if it's daytime (between 6am and 6pm)
+    /// whether the sun is up, falling back to 6am-6pm when the location is unknown
     pub fn is_daytime(&self) -> bool {
-        let hour = self.hour();
-        (6..18).contains(&hour)
+        match self.daylight {
+            Some(daylight) => daylight.is_day_at(self.datetime.with_timezone(&Utc)),
+            None => (6..18).contains(&self.hour()),
+        }
+    }
+
+    /// full local date with week number and day-of-year, like "Fri 17 Oct 2026 W42 d290"
+    pub fn date_details(&self, date_format: DateFormat, week_starts: WeekStart) -> String {
+        let date = self.datetime.date_naive();
+        format!(
+            "{} W{:02} d{:03}",
+            date_format.full(date),
+            week_starts.week_number(date),
+            date.ordinal()
+        )
+    }
+
+    /// local sunrise and sunset, like "↑07:47 ↓16:58"
+    pub fn sun_summary(&self, use_24_hour: bool) -> Option<String> {
+        let clock = clock_format(use_24_hour, false);
+        match self.daylight? {
+            Daylight::Normal { sunrise, sunset } => {
+                let offset = self.datetime.offset();
+                Some(format!(
+                    "↑{} ↓{}",
+                    sunrise.with_timezone(offset).format(clock),
+                    sunset.with_timezone(offset).format(clock)
+                ))
+            }
+            Daylight::PolarDay => Some("midnight sun".to_string()),
+            Daylight::PolarNight => Some("polar night".to_string()),
+        }
     }
 }
 
 /// time zone service for managing multiple city times
 pub struct TimezoneService {
     cities: Vec<CityTime>,
+    parsed: HashMap<String, Option<ParsedTimezone>>, // keyed by the config timezone string
 }
 
 impl TimezoneService {
     pub fn new() -> Self {
-        Self { cities: Vec::new() }
+        Self {
+            cities: Vec::new(),
+            parsed: HashMap::new(),
+        }
+    }
+
+    /// current time for a city, parsing its timezone string only once
+    pub fn city_time(&mut self, city: &City) -> Option<CityTime> {
+        let timezone = self
+            .parsed
+            .entry(city.timezone.clone())
+            .or_insert_with(|| parse_city_timezone(&city.timezone))
+            .clone()?;
+        Some(CityTime::with_timezone(city, timezone))
     }
 
     /// update all city times
     pub fn update(&mut self, cities: &[&City]) {
-        self.cities = cities
+        let times = cities
             .iter()
-            .filter_map(|city| CityTime::from_city(city))
+            .filter_map(|city| self.city_time(city))
             .collect();
+        self.cities = times;
     }
 
     /// get time for a specific city by code
@@ -169,7 +310,7 @@ impl TimezoneService {
         let from_date = from_city.datetime.date_naive();
         let naive_local = from_date.and_hms_opt(hour, minute, 0)?;
 
-        let from_datetime = match from_city.timezone.from_local_datetime(&naive_local) {
+        let from_datetime = match from_city.timezone.resolve_local_datetime(&naive_local) {
             LocalResult::Single(dt) => dt,
             LocalResult::Ambiguous(first, second) => {
                 // prefer the earlier (usually standard) offset when ambiguous
@@ -276,15 +417,31 @@ impl TimeConverter {
         };
     }
 
-    pub fn format_input_time(&self) -> String {
-        format!("{:02}:{:02}", self.input_hour, self.input_minute)
+    /// move the input time to the next half-hour mark either way, wrapping at midnight;
+    /// returns the minutes moved
+    pub fn scrub(&mut self, forward: bool) -> i32 {
+        let minutes = (self.input_hour * 60 + self.input_minute) as i32;
+        let next = if forward {
+            (minutes / SCRUB_STEP_MINUTES + 1) * SCRUB_STEP_MINUTES
+        } else {
+            (minutes - 1).div_euclid(SCRUB_STEP_MINUTES) * SCRUB_STEP_MINUTES
+        };
+        let wrapped = next.rem_euclid(24 * 60) as u32;
+        self.input_hour = wrapped / 60;
+        self.input_minute = wrapped % 60;
+        self.clear_input_buffer();
+        next - minutes
     }
 
-    pub fn format_result_time(&self) -> String {
+    pub fn format_input_time(&self, use_24_hour: bool) -> String {
+        format_clock(self.input_hour, self.input_minute, use_24_hour)
+    }
+
+    pub fn format_result_time(&self, use_24_hour: bool) -> String {
         if self.invalid_input {
             return "invalid local time".to_string();
         }
-        let time = format!("{:02}:{:02}", self.result_hour, self.result_minute);
+        let time = format_clock(self.result_hour, self.result_minute, use_24_hour);
         match self.day_offset {
             -1 => format!("{} (yesterday)", time),
             1 => format!("{} (tomorrow)", time),
@@ -292,6 +449,11 @@ impl TimeConverter {
         }
     }
 
+    /// human phrasing of the result from the destination's point of view
+    pub fn result_phrase(&self) -> Option<String> {
+        (!self.invalid_input).then(|| relative_phrase(self.result_hour, self.day_offset))
+    }
+
     pub fn set_to_now(&mut self) {
         let now = Local::now();
         self.input_hour = now.hour();
@@ -373,10 +535,10 @@ impl TimeConverter {
         !self.input_buffer.is_empty()
     }
 
-    /// format input display (shows buffer if typing)
-    pub fn format_input_display(&self) -> String {
+    /// format input display (shows buffer if typing; typed digits are always 24-hour)
+    pub fn format_input_display(&self, use_24_hour: bool) -> String {
         if self.input_buffer.is_empty() {
-            self.format_input_time()
+            self.format_input_time(use_24_hour)
         } else {
             match self.input_buffer.len() {
                 1 => format!("{}█:__", self.input_buffer),
@@ -387,7 +549,7 @@ impl TimeConverter {
                     &self.input_buffer[2..3]
                 ),
                 4 => format!("{}:{}", &self.input_buffer[0..2], &self.input_buffer[2..4]),
-                _ => self.format_input_time(),
+                _ => self.format_input_time(use_24_hour),
             }
         }
     }
@@ -414,7 +576,25 @@ mod tests {
         let city_time = CityTime::from_city(&city).expect("fixed offset should parse");
 
         assert_eq!(city_time.datetime.offset().local_minus_utc(), 9 * 3600);
-        assert_eq!(city_time.offset_hours, 9.0);
+        assert_eq!(city_time.offset_minutes, 9 * 60);
+    }
+
+    #[test]
+    fn date_details_show_iso_week_and_day_of_year() {
+        let city = test_city("KOR", "Seoul", "UTC+09:00");
+        let mut city_time = CityTime::from_city(&city).expect("fixed offset should parse");
+
+        // 1 jan 2027 is a friday, so it still belongs to ISO week 53 of 2026
+        city_time.datetime = DateTime::parse_from_rfc3339("2027-01-01T08:00:00+09:00").unwrap();
+        assert_eq!(
+            city_time.date_details(DateFormat::Iso, WeekStart::Monday),
+            "Fri 2027-01-01 W53 d001"
+        );
+        // sunday-start weeks number from the year's first sunday (3 jan 2027)
+        assert_eq!(
+            city_time.date_details(DateFormat::MonthDay, WeekStart::Sunday),
+            "Fri Jan 1 2027 W00 d001"
+        );
     }
 
     #[test]
@@ -428,4 +608,76 @@ mod tests {
 
         assert_eq!(converted, Some((0, 30, 0)));
     }
+
+    #[test]
+    fn service_parses_each_timezone_string_once() {
+        let mut service = TimezoneService::new();
+        let wellington = test_city("WLG", "Wellington", "Pacific/Auckland");
+        let auckland = test_city("AKL", "Auckland", "Pacific/Auckland");
+        let broken = test_city("BAD", "Nowhere", "Mars/Olympus");
+
+        service.update(&[&wellington, &auckland, &broken]);
+
+        assert_eq!(service.parsed.len(), 2);
+        assert_eq!(service.cities.len(), 2);
+        assert!(service.city_time(&broken).is_none());
+    }
+
+    #[test]
+    fn formats_clock_in_both_styles() {
+        assert_eq!(format_clock(14, 5, true), "14:05");
+        assert_eq!(format_clock(14, 5, false), "02:05 PM");
+        assert_eq!(format_clock(0, 30, false), "12:30 AM");
+        assert_eq!(format_clock(12, 0, false), "12:00 PM");
+    }
+
+    #[test]
+    fn scrubs_to_half_hour_marks_across_midnight() {
+        let mut converter = TimeConverter::new("WLG", "LDN");
+        converter.input_hour = 23;
+        converter.input_minute = 10;
+
+        assert_eq!(converter.scrub(true), 20);
+        assert_eq!(converter.format_input_time(true), "23:30");
+        assert_eq!(converter.scrub(true), 30);
+        assert_eq!(converter.format_input_time(true), "00:00");
+        assert_eq!(converter.scrub(false), -30);
+        assert_eq!(converter.format_input_time(true), "23:30");
+
+        converter.input_minute = 45;
+        assert_eq!(converter.scrub(false), -15);
+        assert_eq!(converter.format_input_time(true), "23:30");
+    }
+
+    #[test]
+    fn formats_signed_offsets_in_hours_and_minutes() {
+        assert_eq!(format_offset_minutes(450), "+7h30m");
+        assert_eq!(format_offset_minutes(-45), "-45m");
+        assert_eq!(format_offset_minutes(120), "+2h");
+        assert_eq!(format_offset_minutes(0), "+0m");
+    }
+
+    #[test]
+    fn phrases_result_relative_to_destination_day() {
+        assert_eq!(relative_phrase(9, 1), "their tomorrow morning");
+        assert_eq!(relative_phrase(22, 0), "late tonight for them");
+        assert_eq!(relative_phrase(14, 0), "this afternoon for them");
+        assert_eq!(relative_phrase(3, -1), "their yesterday early hours");
+    }
+
+    #[test]
+    fn renders_epoch_seconds_and_millis_in_city_time() {
+        let instant = parse_epoch("1700000000").expect("seconds should parse");
+        assert_eq!(parse_epoch("1700000000000"), Some(instant));
+        assert!(parse_epoch("17e8").is_none());
+
+        assert_eq!(
+            format_instant_in(instant, "America/New_York", true).as_deref(),
+            Some("Tue 14 Nov 17:13:20 (UTC-05:00)")
+        );
+        assert_eq!(
+            format_instant_in(instant, "Pacific/Auckland", false).as_deref(),
+            Some("Wed 15 Nov 11:13:20 AM (UTC+13:00)")
+        );
+    }
 }
@@ -0,0 +1,150 @@
+//! guided /tour: one card per panel, with the rest of the screen dimmed
+//! keys other than the tour's own pass through, so each panel can be tried while it is highlighted
+
+use crate::app::Focus;
+use crate::keymap::{KeyAction, Keymap, key_name};
+
+/// what a tour step highlights
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourTarget {
+    /// nothing dimmed; used for the welcome and closing cards
+    Screen,
+    Panel(Focus),
+    Footer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TourStep {
+    pub target: TourTarget,
+    pub title: &'static str,
+    /// `{action}` stands for the key `[keys]` gives that action
+    pub lines: &'static [&'static str],
+}
+
+pub const TOUR_STEPS: &[TourStep] = &[
+    TourStep {
+        target: TourTarget::Screen,
+        title: "Welcome to nzi",
+        lines: &[
+            "A quick walk through each panel.",
+            "Keys you press are passed through, so try them as you go.",
+        ],
+    },
+    TourStep {
+        target: TourTarget::Panel(Focus::Map),
+        title: "Map",
+        lines: &[
+            "Shows the countries behind the time or currency you're working with.",
+            "Arrows or {focus_left}/{focus_down}/{focus_up}/{focus_right} move between panels; Tab cycles them.",
+            "+/- zoom in and out, arrows pan once zoomed; 1/2/3 jump to regions; v switches to NZ.",
+            "f draws the Cook Strait ferry route on the NZ map; a marks airports; i shows a legend.",
+            "m measures: click two places for the distance and flight time.",
+            "x puts a crosshair on the map; move it with the arrows to read its latitude and longitude.",
+            "/map on or /map off shows or hides it.",
+        ],
+    },
+    TourStep {
+        target: TourTarget::Panel(Focus::Weather),
+        title: "Weather",
+        lines: &[
+            "Current conditions and forecast for your NZ city.",
+            "{swap} toggles the expanded grid; {cycle_city} cycles NZ cities; {refresh} refreshes.",
+        ],
+    },
+    TourStep {
+        target: TourTarget::Panel(Focus::TimeConvert),
+        title: "Time",
+        lines: &[
+            "Clocks for your anchor and target cities, with working-hours bars.",
+            "Type digits to convert a time; {swap} swaps the cities; {cycle_city} cycles the destination.",
+            "n resets to now; b lists the converted time in every city.",
+        ],
+    },
+    TourStep {
+        target: TourTarget::Panel(Focus::Currency),
+        title: "Currency",
+        lines: &[
+            "Converts between the currencies of the same places.",
+            "Type an amount and Enter to record it in /history; {swap} swaps the pair.",
+        ],
+    },
+    TourStep {
+        target: TourTarget::Footer,
+        title: "Commands",
+        lines: &[
+            "Press {command} to type a command, e.g. /event, /alarm, /cal or /config.",
+            "{toggle_help} or /help lists every key and command.",
+        ],
+    },
+    TourStep {
+        target: TourTarget::Screen,
+        title: "That's it",
+        lines: &[
+            "Run /tour any time to see this again.",
+            "[Esc] or [>] to finish",
+        ],
+    },
+];
+
+/// the tour's steps, skipping the map when it is hidden
+pub fn tour_steps(map_enabled: bool) -> Vec<&'static TourStep> {
+    TOUR_STEPS
+        .iter()
+        .filter(|step| map_enabled || step.target != TourTarget::Panel(Focus::Map))
+        .collect()
+}
+
+/// a step's lines with each `{action}` replaced by its key, or "-" when it has none
+pub fn step_lines(step: &TourStep, keymap: &Keymap) -> Vec<String> {
+    step.lines
+        .iter()
+        .map(|line| {
+            KeyAction::ALL
+                .iter()
+                .fold(line.to_string(), |line, &action| {
+                    let key = keymap.key(action).map_or_else(|| "-".to_string(), key_name);
+                    line.replace(&format!("{{{}}}", action.name()), &key)
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_step_only_when_map_is_shown() {
+        assert_eq!(tour_steps(true).len(), TOUR_STEPS.len());
+        let without_map = tour_steps(false);
+        assert_eq!(without_map.len(), TOUR_STEPS.len() - 1);
+        assert!(
+            without_map
+                .iter()
+                .all(|step| step.target != TourTarget::Panel(Focus::Map))
+        );
+    }
+
+    #[test]
+    fn key_hints_follow_the_keymap() {
+        let keys = [("swap", "w"), ("cycle_city", "none")]
+            .iter()
+            .map(|(action, key)| (action.to_string(), key.to_string()))
+            .collect();
+        let keymap = Keymap::from_config(&keys).unwrap();
+        let currency = TOUR_STEPS
+            .iter()
+            .find(|step| step.title == "Currency")
+            .unwrap();
+        assert!(step_lines(currency, &keymap)[1].ends_with("w swaps the pair."));
+
+        let time = TOUR_STEPS.iter().find(|step| step.title == "Time").unwrap();
+        assert!(step_lines(time, &keymap)[1].contains("- cycles the destination"));
+        assert!(
+            TOUR_STEPS
+                .iter()
+                .flat_map(|step| step_lines(step, &Keymap::default()))
+                .all(|line| !line.contains('{'))
+        );
+    }
+}
@@ -2,61 +2,638 @@
 //! handles layout and drawing all widgets
 //! inspired by nzme-cli's high-density, information-rich design
 
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate, Timelike, Utc};
 use ratatui::{
-    Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    Frame, Terminal,
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    widgets::{Block, BorderType, Borders, Clear, Gauge, Paragraph, Wrap},
 };
 
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::app::{App, ConfigTab, Focus, InputMode};
-use crate::config::City;
-use crate::map::{NZ_CITIES, NzMapCanvas, Sparkles, WorldMapCanvas, WorldMarker};
+use crate::app::{App, ConfigTab, Focus, InputMode, Overlay, chord_hints};
+use crate::bigdigits;
+use crate::calendar::weekday_headings;
+use crate::climate::month_name as climate_month_name;
+use crate::config::{City, Config, LayoutConfig, TabPanel};
+use crate::events::format_countdown;
+use crate::flight::{format_distance_km, format_flight_duration};
+use crate::footer;
+use crate::health::{SourceHealth, network_reachable};
+use crate::icons::{Glyph, day_night, glyph, icon_set};
+use crate::keymap::{KeyAction, Keymap, key_name};
+use crate::map::{
+    MapLegend, NZ_CITIES, NzLegendLayers, NzMapCanvas, Sparkles, TownMarker, WorldMapCanvas,
+    WorldMarker, WorldPin,
+};
+use crate::panels::{CustomPanel, ansi_lines};
+use crate::planner::{HourStatus, day_bar};
+use crate::providers::{APP_LICENCE, PROVIDERS};
 use crate::reference::{country_by_code, focal_country_code_for_currency, lookup_country};
-use crate::theme::{Theme, catppuccin};
-use crate::timezone::CityTime;
-use crate::weather::{city_coords_by_code, city_coords_by_name};
+use crate::schema::drift_label;
+use crate::settings::SettingRow;
+use crate::sparkline::sparkline;
+use crate::stats::format_uptime;
+use crate::theme::{Theme, catppuccin, high_contrast, parse_colour, recolour, reduce_colours};
+use crate::timezone::{CityTime, clock_format, format_clock, format_offset_minutes};
+use crate::tour::{self, TourStep, TourTarget};
+use crate::weather::{TimeOfDay, city_coords};
 
 const WEATHER_GRID_CELL_WIDTH: usize = 18;
+const WEATHER_GRID_MAX_CELL_WIDTH: usize = 28;
 const WEATHER_GRID_COLUMNS: usize = 4;
 const WEATHER_GRID_WIDTH: u16 =
     (WEATHER_GRID_CELL_WIDTH * WEATHER_GRID_COLUMNS + WEATHER_GRID_COLUMNS + 1) as u16;
+// columns the expanded weather art takes, and the widest its gauges grow beside it
+const WEATHER_ART_WIDTH: u16 = 12;
+const WEATHER_GAUGE_WIDTH: u16 = 26;
+
+// sparklines narrower than this say too little to be worth the room
+const MIN_SPARKLINE_WIDTH: usize = 4;
+
+// rows for the compact weather panel, and the fewest the map keeps, in stacked layouts
+const NARROW_WEATHER_HEIGHT: u16 = 10;
+const NARROW_MAP_MIN_HEIGHT: u16 = 8;
+
+// rows the ambient view gives its two stacked clocks, above the map
+const AMBIENT_CLOCK_HEIGHT: u16 = 9;
+
+// the big time panel clocks want this much room beside the digits for the city labels
+const BIG_CLOCK_LABEL_WIDTH: usize = 20;
+
+// narrow panes fall back to a day/night grid
+const WEATHER_GRID_NARROW_CELL_WIDTH: usize = 13;
+const WEATHER_GRID_NARROW_WIDTH: u16 = (WEATHER_GRID_NARROW_CELL_WIDTH * 2 + 3) as u16;
+/// rows the expanded grid needs to draw; `[layout] weather_min_height` starts from the same
 const WEATHER_EXPANDED_MIN_HEIGHT: u16 = 14;
-/// main ui rendering function
-pub fn draw(frame: &mut Frame, app: &App) {
+/// height of the strip holding user-defined command panels and the checklist
+const CUSTOM_PANEL_HEIGHT: u16 = 8;
+/// panel titles, with the `[key:label]` hints a click on the title triggers
+const WEATHER_TITLE: &str = "Weather [s:view] [space:city]";
+const TIME_TITLE: &str = "Time [space:to] [f:from] [s:swap] [e:edit] [b:all]";
+const CURRENCY_TITLE: &str = "Currency [space:cycle] [s:swap] [e:edit/Esc]";
+
+/// where the clickable parts of the screen were last drawn, for the mouse
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClickAreas {
+    pub map: Option<Rect>,
+    /// every content panel shown, the map included
+    pub panels: Vec<(Rect, Focus)>,
+    /// title hints and the key each one presses on its panel
+    pub hints: Vec<(Rect, (Focus, char))>,
+    /// `[tabs]` labels and their page
+    pub tabs: Vec<(Rect, usize)>,
+    /// checklist rows and their item
+    pub checklist: Vec<(Rect, usize)>,
+    /// picker rows and their option
+    pub picker: Vec<(Rect, usize)>,
+}
+
+impl ClickAreas {
+    pub fn panel_at(&self, column: u16, row: u16) -> Option<Focus> {
+        hit(&self.panels, column, row).map(|(_, focus)| focus)
+    }
+}
+
+/// the value beside the first area holding (column, row)
+pub fn hit<T: Copy>(areas: &[(Rect, T)], column: u16, row: u16) -> Option<(Rect, T)> {
+    areas
+        .iter()
+        .copied()
+        .find(|(area, _)| area.contains(ratatui::layout::Position::new(column, row)))
+}
+
+/// main ui rendering function; returns where things went so clicks can find them
+pub fn draw(frame: &mut Frame, app: &App) -> ClickAreas {
+    let areas = draw_screen(frame, app);
+    recolour(frame.buffer_mut(), app.config.display.effective_theme());
+    reduce_colours(
+        frame.buffer_mut(),
+        app.config.display.effective_colour_depth(),
+    );
+    areas
+}
+
+/// everything on screen, drawn in the mocha palette
+fn draw_screen(frame: &mut Frame, app: &App) -> ClickAreas {
     let area = frame.area();
 
     // fill background with base colour
     let bg_block = Block::default().style(Style::default().bg(catppuccin::BASE));
     frame.render_widget(bg_block, area);
 
-    // main layout: header (3), content (flexible), footer (3)
+    if app.show_splash {
+        draw_splash(frame, area, app);
+        return ClickAreas::default();
+    }
+
+    if app.ambient {
+        draw_ambient(frame, area, app);
+        return ClickAreas::default();
+    }
+
+    // main layout: header (3), optional world clock (1), content (flexible),
+    // optional custom panels, footer (3)
+    let clock_height = if app.config.display.show_world_clock {
+        1
+    } else {
+        0
+    };
+    // panels given a `[tabs]` page leave the strip above the footer
+    let strip_custom = !app.custom_panels.is_empty() && !on_a_tab(app, TabPanel::Custom);
+    let strip_checklist = app.checklist_visible() && !on_a_tab(app, TabPanel::Checklist);
+    let custom_height = if strip_custom || strip_checklist {
+        CUSTOM_PANEL_HEIGHT
+    } else {
+        0
+    };
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // header with rainbow animation
-            Constraint::Min(12),   // content
-            Constraint::Length(3), // footer with city codes + help hint
+            Constraint::Length(3),             // header with rainbow animation
+            Constraint::Length(clock_height),  // tracked-cities world clock strip
+            Constraint::Min(12),               // content
+            Constraint::Length(custom_height), // command panels and checklist
+            Constraint::Length(3),             // footer with city codes + help hint
         ])
         .split(area);
 
     draw_header(frame, main_chunks[0], app);
-    draw_content(frame, main_chunks[1], app);
-    draw_footer(frame, main_chunks[2], app);
+    if clock_height > 0 {
+        draw_world_clock_strip(frame, main_chunks[1], app);
+    }
+    let mut panels = draw_content(frame, main_chunks[2], app);
+    if custom_height > 0 {
+        let checklist =
+            draw_custom_panels(frame, main_chunks[3], app, strip_custom, strip_checklist);
+        panels.checklist = panels.checklist.or(checklist);
+    }
+    draw_footer(frame, main_chunks[4], app);
 
     if app.config_editor_state().is_some() {
         draw_config_editor_overlay(frame, area, app);
     }
 
+    let mut picker_rows = Vec::new();
     if app.picker.is_some() {
-        draw_picker_overlay(frame, area, app);
+        picker_rows = draw_picker_overlay(frame, area, app);
+    } else if app.planner.is_some() {
+        draw_planner_overlay(frame, area, app);
+    } else if let Some(overlay) = app.overlay {
+        match overlay {
+            Overlay::Stats => draw_stats_overlay(frame, area, app),
+            Overlay::About => draw_about_overlay(frame, area),
+            Overlay::History => draw_history_overlay(frame, area, app),
+            Overlay::Climate => draw_climate_overlay(frame, area, app),
+            Overlay::Calendar => draw_calendar_overlay(frame, area, app),
+            Overlay::ConfigProblems => draw_config_problems_overlay(frame, area, app),
+        }
     } else if app.show_help && app.config_editor_state().is_none() {
-        draw_help_overlay(frame, area);
+        draw_help_overlay(frame, area, app.keymap());
+    }
+
+    if let Some(prefix) = app.pending_chord {
+        draw_chord_hint(frame, main_chunks[2], prefix);
+    }
+
+    if let Some((index, total, step)) = app.current_tour_step() {
+        let spotlight = match step.target {
+            TourTarget::Screen => None,
+            TourTarget::Panel(focus) => panels.get(focus),
+            TourTarget::Footer => Some(main_chunks[4]),
+        };
+        draw_tour_step(frame, area, spotlight, index, total, step, app.keymap());
+    }
+
+    click_areas(app, &panels, picker_rows)
+}
+
+/// the clickable parts of the main screen, from where its panels went
+fn click_areas(app: &App, panels: &PanelAreas, picker: Vec<(Rect, usize)>) -> ClickAreas {
+    let mut areas = ClickAreas {
+        map: panels.map,
+        picker,
+        ..ClickAreas::default()
+    };
+    for focus in [
+        Focus::Map,
+        Focus::Weather,
+        Focus::TimeConvert,
+        Focus::Currency,
+    ] {
+        let Some(area) = panels.get(focus) else {
+            continue;
+        };
+        areas.panels.push((area, focus));
+        let title = match focus {
+            Focus::Map => continue,
+            Focus::Weather => WEATHER_TITLE,
+            Focus::TimeConvert => TIME_TITLE,
+            Focus::Currency => CURRENCY_TITLE,
+        };
+        for (hint, key) in title_hints(area, title, app.focus == focus) {
+            areas.hints.push((hint, (focus, key)));
+        }
+    }
+    if let Some(strip) = panels.tabs {
+        let mut x = strip.x;
+        for (index, label) in tab_labels(app).iter().enumerate() {
+            let width = UnicodeWidthStr::width(label.as_str()) as u16;
+            let tab = Rect::new(x, strip.y, width, 1).intersection(strip);
+            areas.tabs.push((tab, index));
+            x = x.saturating_add(width + 1);
+        }
+    }
+    if let Some(checklist) = panels.checklist {
+        let inner = Block::default().borders(Borders::ALL).inner(checklist);
+        let rows = (inner.height as usize).min(app.config.todos.len());
+        for index in 0..rows {
+            let row = Rect::new(inner.x, inner.y + index as u16, inner.width, 1);
+            areas.checklist.push((row, index));
+        }
+    }
+    areas
+}
+
+/// the `[key:label]` hints in a panel title as drawn on the top border of `area`, with the
+/// key each stands for ("space" is the space bar)
+fn title_hints(area: Rect, title: &str, focused: bool) -> Vec<(Rect, char)> {
+    // the title starts after the corner and a space, and the focus marker in high contrast
+    let mut x = area.x + 2 + if focused && high_contrast() { 2 } else { 0 };
+    let mut hints = Vec::new();
+    let mut start = None;
+    for (index, c) in title.char_indices() {
+        match c {
+            '[' => start = Some((index, x)),
+            ']' => {
+                if let Some((from, left)) = start.take() {
+                    let name = title[from + 1..index].split(':').next().unwrap_or("");
+                    let key = match name {
+                        "space" => Some(' '),
+                        _ if name.chars().count() == 1 => name.chars().next(),
+                        _ => None,
+                    };
+                    if let Some(key) = key {
+                        let hint = Rect::new(left, area.y, x + 1 - left, 1).intersection(area);
+                        hints.push((hint, key));
+                    }
+                }
+            }
+            _ => {}
+        }
+        x += UnicodeWidthChar::width(c).unwrap_or(0) as u16;
+    }
+    hints
+}
+
+/// dim everything but the spotlighted area and show the step's card beside it
+fn draw_tour_step(
+    frame: &mut Frame,
+    area: Rect,
+    spotlight: Option<Rect>,
+    index: usize,
+    total: usize,
+    step: &TourStep,
+    keymap: &Keymap,
+) {
+    if let Some(spotlight) = spotlight {
+        let buffer = frame.buffer_mut();
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if !spotlight.contains(Position::new(x, y)) {
+                    let cell = &mut buffer[(x, y)];
+                    cell.set_fg(catppuccin::SURFACE2);
+                    cell.modifier.insert(Modifier::DIM);
+                }
+            }
+        }
+    }
+
+    let step_lines = tour::step_lines(step, keymap);
+    let card_width = step_lines
+        .iter()
+        .map(|line| line.width() as u16 + 4)
+        .max()
+        .unwrap_or(0)
+        .clamp(36, area.width.saturating_sub(4).max(1));
+    let card_height = (step_lines.len() as u16 + 2).min(area.height);
+    // keep the card off the spotlight: below it when there is room, otherwise above
+    let y = match spotlight {
+        Some(spotlight) if spotlight.bottom() + card_height <= area.bottom() => spotlight.bottom(),
+        Some(spotlight) if spotlight.top() >= area.top() + card_height => {
+            spotlight.top() - card_height
+        }
+        _ => area.top() + area.height.saturating_sub(card_height) / 2,
+    };
+    let x = match spotlight {
+        Some(spotlight) => spotlight
+            .left()
+            .min(area.right().saturating_sub(card_width)),
+        None => area.left() + area.width.saturating_sub(card_width) / 2,
+    };
+    let card = Rect::new(x, y, card_width, card_height).intersection(area);
+
+    frame.render_widget(Clear, card);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(catppuccin::MAUVE))
+        .style(Style::default().bg(catppuccin::BASE))
+        .title(Span::styled(
+            format!(" {} · {}/{} ", step.title, index + 1, total),
+            Style::default()
+                .fg(catppuccin::MAUVE)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " [>] next [<] back [Esc] ",
+            Style::default().fg(catppuccin::OVERLAY1),
+        ));
+    let lines: Vec<Line> = step_lines
+        .iter()
+        .map(|line| {
+            Line::from(Span::styled(
+                format!(" {}", line),
+                Style::default().fg(catppuccin::TEXT),
+            ))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), card);
+}
+
+/// user-defined panels side by side, each showing its command's latest output,
+/// with the checklist last
+/// the command panels and checklist in the strip above the footer; returns where the
+/// checklist went
+fn draw_custom_panels(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    custom: bool,
+    checklist: bool,
+) -> Option<Rect> {
+    let panels: &[CustomPanel] = if custom {
+        &app.custom_panels.panels
+    } else {
+        &[]
+    };
+    let count = panels.len() + usize::from(checklist);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, count as u32); count])
+        .split(area);
+
+    for (panel, column) in panels.iter().zip(columns.iter()) {
+        draw_custom_panel(frame, *column, panel);
+    }
+
+    let column = columns.last().filter(|_| checklist)?;
+    draw_checklist(frame, *column, app);
+    Some(*column)
+}
+
+fn draw_custom_panel(frame: &mut Frame, area: Rect, panel: &CustomPanel) {
+    let block = styled_block(&panel.config.title, false);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut lines = match &panel.output {
+        Some(output) => ansi_lines(output, Style::default().fg(catppuccin::TEXT)),
+        None => vec![Line::from(Span::styled(
+            "running…",
+            Style::default().fg(catppuccin::OVERLAY0),
+        ))],
+    };
+    if let Some(error) = &panel.error {
+        lines.truncate(inner.height.saturating_sub(1) as usize);
+        lines.push(Line::from(Span::styled(
+            format!("! {}", error),
+            Style::default().fg(catppuccin::RED),
+        )));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// travel checklist with numbered items for /todo done and /todo rm
+fn draw_checklist(frame: &mut Frame, area: Rect, app: &App) {
+    let todos = &app.config.todos;
+    let open = todos.iter().filter(|todo| !todo.done).count();
+    let block = styled_block(&format!("Checklist {}/{}", open, todos.len()), false);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines: Vec<Line> = todos
+        .iter()
+        .enumerate()
+        .map(|(index, todo)| {
+            let (mark, style) = if todo.done {
+                (
+                    "☑",
+                    Style::default()
+                        .fg(catppuccin::OVERLAY0)
+                        .add_modifier(Modifier::CROSSED_OUT),
+                )
+            } else {
+                ("☐", Style::default().fg(catppuccin::TEXT))
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>2} {} ", index + 1, mark),
+                    Style::default().fg(catppuccin::OVERLAY1),
+                ),
+                Span::styled(
+                    truncate_display(&todo.text, (inner.width as usize).saturating_sub(5)),
+                    style,
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// the idle screen: big anchor and home clocks over the map, with sparkles behind
+fn draw_ambient(frame: &mut Frame, area: Rect, app: &App) {
+    if app.config.display.active_animations().sparkles {
+        frame.render_widget(Sparkles::new(app.animation_frame).density(12), area);
+    }
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(AMBIENT_CLOCK_HEIGHT),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+    // anchor then home clock, stacked in block digits unless the screen is too narrow
+    let display = &app.config.display;
+    let mut lines = Vec::new();
+    for time in [app.current_city_time.as_ref(), app.home_city_time.as_ref()]
+        .into_iter()
+        .flatten()
+    {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        let time_str = time.time_string(display.use_24_hour, display.show_seconds);
+        let time_style = Style::default()
+            .fg(catppuccin::TEXT)
+            .add_modifier(Modifier::BOLD);
+        if big_clock_width(&time_str) <= usize::from(rows[0].width) {
+            lines.extend(big_clock_lines(&time_str, time_style, Default::default()));
+        } else {
+            lines.push(Line::from(Span::styled(time_str, time_style)));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(
+                time.city_name.clone(),
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+            Span::styled(
+                format!(
+                    " · {}",
+                    display.date_format.with_weekday(time.datetime.date_naive())
+                ),
+                Style::default().fg(catppuccin::OVERLAY1),
+            ),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), rows[0]);
+
+    if rows[1].height >= NARROW_MAP_MIN_HEIGHT {
+        draw_map_panel(frame, rows[1], app);
+    }
+}
+
+fn draw_splash(frame: &mut Frame, area: Rect, app: &App) {
+    let splash_width = 64.min(area.width.saturating_sub(4));
+    let splash_height = (8 + app.data_sources.len() as u16).min(area.height.saturating_sub(2));
+    let x = (area.width.saturating_sub(splash_width)) / 2;
+    let y = (area.height.saturating_sub(splash_height)) / 2;
+    let splash_area = Rect::new(x, y, splash_width, splash_height);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(catppuccin::MAUVE))
+        .title(Span::styled(
+            format!(" nzi v{} ", env!("CARGO_PKG_VERSION")),
+            Style::default()
+                .fg(catppuccin::MAUVE)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(splash_area);
+    frame.render_widget(block, splash_area);
+
+    let (network_label, network_colour) = match network_reachable(&app.data_sources) {
+        None if app.data_sources.iter().all(SourceHealth::is_checked) => {
+            ("unchecked", catppuccin::SUBTEXT0)
+        }
+        None => ("checking…", catppuccin::YELLOW),
+        Some(true) => ("reachable", catppuccin::GREEN),
+        Some(false) => ("unreachable", catppuccin::RED),
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Checking data sources",
+            Style::default()
+                .fg(catppuccin::PEACH)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    for health in &app.data_sources {
+        let status_colour = match health.reachable {
+            None if health.is_checked() => catppuccin::SUBTEXT0,
+            None => catppuccin::YELLOW,
+            Some(true) => catppuccin::GREEN,
+            Some(false) => catppuccin::RED,
+        };
+        let cache_label = if health.cache_warm { "warm" } else { "cold" };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<15}", health.source.label()),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+            Span::styled(
+                format!("{:<12}", health.status_label()),
+                Style::default().fg(status_colour),
+            ),
+            Span::styled(
+                format!("cache {:<5} ", cache_label),
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+            Span::styled(
+                format!("last {}", health.last_refresh_label()),
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+        ]));
     }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(
+            "  Network        ",
+            Style::default().fg(catppuccin::SAPPHIRE),
+        ),
+        Span::styled(network_label, Style::default().fg(network_colour)),
+    ]));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Press any key to continue",
+        Style::default().fg(catppuccin::OVERLAY1),
+    )));
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// which-key style popup listing the keys that complete a chord
+fn draw_chord_hint(frame: &mut Frame, area: Rect, prefix: char) {
+    let hints = chord_hints(prefix);
+    let popup_width = 26.min(area.width);
+    let popup_height = (hints.len() as u16 + 2).min(area.height);
+    let popup_area = Rect::new(
+        area.x + area.width.saturating_sub(popup_width + 1),
+        area.y + area.height.saturating_sub(popup_height),
+        popup_width,
+        popup_height,
+    );
+
+    frame.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(catppuccin::PEACH))
+        .style(Style::default().bg(catppuccin::BASE))
+        .title(Span::styled(
+            format!(" {} … ", prefix),
+            Style::default()
+                .fg(catppuccin::PEACH)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let lines: Vec<Line> = hints
+        .into_iter()
+        .map(|(key, hint)| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {}  ", key),
+                    Style::default().fg(catppuccin::SAPPHIRE),
+                ),
+                Span::styled(hint, Style::default().fg(catppuccin::TEXT)),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
 }
 
 fn draw_config_editor_overlay(frame: &mut Frame, area: Rect, app: &App) {
@@ -93,13 +670,17 @@ fn draw_config_editor_overlay(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(block, popup_area);
 
     let tab_line = Line::from(
-        [ConfigTab::Places, ConfigTab::Actions]
+        ConfigTab::ALL
             .into_iter()
             .flat_map(|tab| {
                 let is_active = tab == editor.tab;
                 [
                     Span::styled(
-                        format!(" {} ", tab.label()),
+                        if is_active && high_contrast() {
+                            format!("[{}]", tab.label())
+                        } else {
+                            format!(" {} ", tab.label())
+                        },
                         Style::default()
                             .fg(if is_active {
                                 catppuccin::BASE
@@ -136,6 +717,7 @@ fn draw_config_editor_overlay(frame: &mut Frame, area: Rect, app: &App) {
 
     let lines = match editor.tab {
         ConfigTab::Places => config_editor_places_lines(app, config, editor.selected),
+        ConfigTab::Settings => config_editor_settings_lines(config, editor.selected),
         ConfigTab::Actions => {
             config_editor_action_lines(editor.selected, config.effective_map_settings().enabled)
         }
@@ -167,6 +749,18 @@ fn draw_config_editor_overlay(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled("[Esc]", Style::default().fg(catppuccin::OVERLAY1)),
             Span::styled(" close", Theme::text_muted()),
         ]),
+        ConfigTab::Settings => Line::from(vec![
+            Span::styled("[Tab]", Style::default().fg(catppuccin::OVERLAY1)),
+            Span::styled(" tabs ", Theme::text_muted()),
+            Span::styled("[j/k]", Style::default().fg(catppuccin::OVERLAY1)),
+            Span::styled(" move ", Theme::text_muted()),
+            Span::styled("[Enter/+]", Style::default().fg(catppuccin::OVERLAY1)),
+            Span::styled(" change ", Theme::text_muted()),
+            Span::styled("[-]", Style::default().fg(catppuccin::OVERLAY1)),
+            Span::styled(" back ", Theme::text_muted()),
+            Span::styled("[Esc]", Style::default().fg(catppuccin::OVERLAY1)),
+            Span::styled(" close", Theme::text_muted()),
+        ]),
         ConfigTab::Actions => Line::from(vec![
             Span::styled("[Tab]", Style::default().fg(catppuccin::OVERLAY1)),
             Span::styled(" tabs ", Theme::text_muted()),
@@ -242,6 +836,31 @@ fn config_editor_places_lines(
     lines
 }
 
+fn config_editor_settings_lines(
+    config: &crate::config::Config,
+    selected: usize,
+) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(vec![Span::styled(
+            "Settings",
+            Style::default()
+                .fg(catppuccin::PEACH)
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from("Display options and refresh intervals, staged in the draft until applied."),
+        Line::from("Press Enter or + to change the highlighted setting, - to step back."),
+        Line::from(""),
+    ];
+    for (index, row) in SettingRow::rows(config).into_iter().enumerate() {
+        lines.push(config_editor_row(
+            selected == index,
+            &row.label(config),
+            &row.value(config),
+        ));
+    }
+    lines
+}
+
 fn config_editor_action_lines(selected: usize, map_enabled: bool) -> Vec<Line<'static>> {
     vec![
         Line::from(vec![Span::styled(
@@ -295,6 +914,12 @@ fn config_editor_selected_line_index(
             }
             line_index
         }
+        ConfigTab::Settings => {
+            let base_line = 4;
+            let row_count = SettingRow::rows(config).len();
+            let selected = editor.selected.min(row_count.saturating_sub(1));
+            base_line + selected
+        }
         ConfigTab::Actions => {
             let base_line = 4;
             let row_count = 6usize;
@@ -351,11 +976,12 @@ fn config_editor_row(selected: bool, label: &str, detail: &str) -> Line<'static>
     ])
 }
 
-fn draw_picker_overlay(frame: &mut Frame, area: Rect, app: &App) {
+/// draw the picker; returns where each shown option went so clicks can pick it
+fn draw_picker_overlay(frame: &mut Frame, area: Rect, app: &App) -> Vec<(Rect, usize)> {
     let title = app.picker_title().unwrap_or_else(|| "Picker".to_string());
     let prompt = app.picker_prompt().unwrap_or("");
     let Some(picker) = app.picker.as_ref() else {
-        return;
+        return Vec::new();
     };
     let options = app.picker_options();
 
@@ -398,6 +1024,11 @@ fn draw_picker_overlay(frame: &mut Frame, area: Rect, app: &App) {
         )]),
         Line::from(""),
     ];
+    // the prompt wraps, pushing the options down
+    let prompt_rows = (UnicodeWidthStr::width(prompt) as u16)
+        .div_ceil(inner.width.max(1))
+        .max(1);
+    let mut rows = Vec::new();
 
     if options.is_empty() {
         lines.push(Line::from(vec![Span::styled(
@@ -432,6 +1063,13 @@ fn draw_picker_overlay(frame: &mut Frame, area: Rect, app: &App) {
         for (index, option) in options[start..end].iter().enumerate() {
             let absolute_index = start + index;
             let is_selected = absolute_index == selected;
+            let row = Rect::new(
+                inner.x,
+                inner.y + 3 + prompt_rows + index as u16,
+                inner.width,
+                1,
+            );
+            rows.push((row.intersection(inner), absolute_index));
             lines.push(Line::from(vec![
                 Span::styled(
                     if is_selected { "▸ " } else { "  " },
@@ -472,19 +1110,635 @@ fn draw_picker_overlay(frame: &mut Frame, area: Rect, app: &App) {
     ]));
 
     frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    rows.retain(|(row, _)| !row.is_empty());
+    rows
 }
 
-/// draw help overlay popup
-fn draw_help_overlay(frame: &mut Frame, area: Rect) {
-    // centre the help box
-    let help_width = 50.min(area.width.saturating_sub(4));
-    let help_height = 28.min(area.height.saturating_sub(4));
-    let x = (area.width.saturating_sub(help_width)) / 2;
-    let y = (area.height.saturating_sub(help_height)) / 2;
-    let help_area = Rect::new(x, y, help_width, help_height);
+/// draw the /plan meeting planner popup
+fn draw_planner_overlay(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(planner) = &app.planner else {
+        return;
+    };
+    let rows = app.planner_rows();
+    let clock = clock_format(app.config.display.use_24_hour, false);
 
-    // clear the area behind
-    frame.render_widget(Clear, help_area);
+    let popup_width = 60.min(area.width.saturating_sub(4));
+    let popup_height = (rows.len() as u16 + 9).min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Block::default().style(Style::default().bg(catppuccin::BASE)),
+        popup_area,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(catppuccin::SAPPHIRE))
+        .title(Span::styled(
+            " Meeting Planner [Esc] ",
+            Style::default()
+                .fg(catppuccin::SAPPHIRE)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let anchor_name = rows
+        .first()
+        .map(|row| row.city_name.as_str())
+        .unwrap_or("anchor");
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Candidate ", Style::default().fg(catppuccin::SUBTEXT0)),
+            Span::styled(
+                planner.time.format(clock).to_string(),
+                Style::default()
+                    .fg(catppuccin::PEACH)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    " in {} on {}",
+                    anchor_name,
+                    app.config.display.date_format.with_weekday(planner.date)
+                ),
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    for row in &rows {
+        let colour = match row.status {
+            HourStatus::Working => catppuccin::GREEN,
+            HourStatus::Awake => catppuccin::YELLOW,
+            HourStatus::Asleep => catppuccin::RED,
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<4}", row.city_code),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+            Span::styled(
+                format!("{:<14}", truncate_display(&row.city_name, 13)),
+                Style::default().fg(catppuccin::TEXT),
+            ),
+            Span::styled(
+                format!("{} {} ", row.local.format(clock), row.local.format("%a")),
+                Style::default().fg(catppuccin::TEXT),
+            ),
+            Span::styled("● ", Style::default().fg(colour)),
+            Span::styled(row.status.label(), Style::default().fg(colour)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    match planner.best {
+        Some(best) => lines.push(Line::from(vec![
+            Span::styled("Best slot ", Style::default().fg(catppuccin::SUBTEXT0)),
+            Span::styled(
+                best.anchor_time.format(clock).to_string(),
+                Style::default()
+                    .fg(catppuccin::GREEN)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "  {} working, {} awake of {}",
+                    best.working,
+                    best.awake,
+                    rows.len()
+                ),
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+        ])),
+        None => lines.push(Line::from(Span::styled(
+            "No overlapping slot found",
+            Style::default().fg(catppuccin::RED),
+        ))),
+    }
+    lines.push(Line::from(Span::styled(
+        "[←→]:30m [↑↓]:1h [b]:best [n]:now",
+        Style::default().fg(catppuccin::OVERLAY0),
+    )));
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_stats_overlay(frame: &mut Frame, area: Rect, app: &App) {
+    let popup_width = 44.min(area.width.saturating_sub(4));
+    let popup_height = 18.min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Block::default().style(Style::default().bg(catppuccin::BASE)),
+        popup_area,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(catppuccin::LAVENDER))
+        .title(Span::styled(
+            " Session Stats [Esc] ",
+            Style::default()
+                .fg(catppuccin::LAVENDER)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let stats = &app.session_stats;
+    let weather = app.weather_service.metrics();
+    let exchange = app.exchange_service.metrics();
+    let stat_line = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(
+                format!("  {:<24}", label),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+            Span::styled(value, Style::default().fg(catppuccin::TEXT)),
+        ])
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "This session",
+            Style::default()
+                .fg(catppuccin::PEACH)
+                .add_modifier(Modifier::BOLD),
+        )),
+        stat_line("Uptime", format_uptime(stats.uptime())),
+        stat_line("Conversions", stats.conversions.to_string()),
+        stat_line("Cities cycled", stats.cities_cycled.to_string()),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Data services",
+            Style::default()
+                .fg(catppuccin::PEACH)
+                .add_modifier(Modifier::BOLD),
+        )),
+        stat_line("Weather API calls", weather.api_calls.to_string()),
+        stat_line("Exchange API calls", exchange.api_calls.to_string()),
+        stat_line(
+            "Holiday API calls",
+            app.holiday_service.metrics().api_calls.to_string(),
+        ),
+        stat_line(
+            "Quake API calls",
+            app.quake_service.metrics().api_calls.to_string(),
+        ),
+        stat_line(
+            "Kp index API calls",
+            app.aurora_service.metrics().api_calls.to_string(),
+        ),
+        stat_line(
+            "Heat map API calls",
+            app.heat_service.metrics().api_calls.to_string(),
+        ),
+        stat_line(
+            "ISS API calls",
+            app.iss_service.metrics().api_calls.to_string(),
+        ),
+        stat_line(
+            "API calls saved by cache",
+            (weather.cache_hits + exchange.cache_hits).to_string(),
+        ),
+    ];
+
+    // upstream schema drift: fields the adapters ignored on the last response
+    let drift = [
+        ("Weather schema", app.weather_service.unknown_fields()),
+        ("Exchange schema", app.exchange_service.unknown_fields()),
+    ];
+    for (label, fields) in drift {
+        if let Some(summary) = drift_label(fields) {
+            lines.push(stat_line(label, summary));
+        }
+    }
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_climate_overlay(frame: &mut Frame, area: Rect, app: &App) {
+    let popup_width = 64.min(area.width.saturating_sub(4));
+    let popup_height = 18.min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Block::default().style(Style::default().bg(catppuccin::BASE)),
+        popup_area,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(catppuccin::LAVENDER))
+        .title(Span::styled(
+            format!(" Climate · {} [Esc] ", app.get_weather_city_name()),
+            Style::default()
+                .fg(catppuccin::LAVENDER)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    if app.climate.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                "  No days recorded yet - each day adds the one before",
+                Style::default().fg(catppuccin::OVERLAY1),
+            ))),
+            inner,
+        );
+        return;
+    }
+
+    let mut lines = Vec::new();
+    if let Some(headline) = &app.climate_headline {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", headline),
+            Style::default()
+                .fg(catppuccin::MAUVE)
+                .add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  {:<15} {:>4} {:>7} {:>7} {:>5} {:>5} {:>8}",
+            "Month", "Days", "Avg hi", "Avg lo", "Max", "Min", "Rain"
+        ),
+        Style::default()
+            .fg(catppuccin::PEACH)
+            .add_modifier(Modifier::BOLD),
+    )));
+    for summary in &app.climate {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "  {:<15} {:>4} ",
+                    format!("{} {}", climate_month_name(summary.month), summary.year),
+                    summary.days
+                ),
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+            Span::styled(
+                format!(
+                    "{:>6.1}° {:>6.1}° {:>4}° {:>4}°",
+                    summary.mean_high_c, summary.mean_low_c, summary.warmest_c, summary.coldest_c
+                ),
+                Style::default().fg(catppuccin::TEXT),
+            ),
+            Span::styled(
+                format!(" {:>6.1}mm", summary.rain_mm),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+        ]));
+    }
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// month grid with each day as "anchor/home" day numbers
+fn draw_calendar_overlay(frame: &mut Frame, area: Rect, app: &App) {
+    let popup_width = 52.min(area.width.saturating_sub(4));
+    let popup_height = 22.min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Block::default().style(Style::default().bg(catppuccin::BASE)),
+        popup_area,
+    );
+
+    let month = app.calendar_month;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(catppuccin::LAVENDER))
+        .title(Span::styled(
+            format!(
+                " {} {} · {}/{} [h/l] [Esc] ",
+                climate_month_name(month.month()),
+                month.year(),
+                app.config.current_city.code,
+                app.config.home_city.code
+            ),
+            Style::default()
+                .fg(catppuccin::LAVENDER)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut lines = vec![Line::from(Span::styled(
+        weekday_headings(app.config.display.week_starts)
+            .iter()
+            .map(|heading| format!("{:^6}", heading))
+            .collect::<String>(),
+        Style::default()
+            .fg(catppuccin::PEACH)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    for week in app.calendar_weeks() {
+        let cells = week.iter().map(|cell| {
+            let label = match cell.home {
+                Some(home) if home != cell.anchor => {
+                    format!("{}/{}", cell.anchor.day(), home.day())
+                }
+                _ => cell.anchor.day().to_string(),
+            };
+            let colour = if !cell.in_month {
+                catppuccin::OVERLAY0
+            } else if cell.event {
+                catppuccin::MAUVE
+            } else if cell.holiday {
+                catppuccin::PEACH
+            } else {
+                catppuccin::TEXT
+            };
+            let mut style = Style::default().fg(colour);
+            if cell.today {
+                style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+            }
+            Span::styled(format!("{:^6}", label), style)
+        });
+        lines.push(Line::from(cells.collect::<Vec<_>>()));
+    }
+
+    let notes = app.calendar_notes();
+    if !notes.is_empty() {
+        lines.push(Line::from(""));
+        for (date, note) in notes {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "  {:<10} ",
+                        app.config.display.date_format.with_weekday(date)
+                    ),
+                    Style::default().fg(catppuccin::SUBTEXT0),
+                ),
+                Span::styled(note, Style::default().fg(catppuccin::TEXT)),
+            ]));
+        }
+    }
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_history_overlay(frame: &mut Frame, area: Rect, app: &App) {
+    let popup_width = 72.min(area.width.saturating_sub(4));
+    let popup_height = 20.min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Block::default().style(Style::default().bg(catppuccin::BASE)),
+        popup_area,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(catppuccin::LAVENDER))
+        .title(Span::styled(
+            format!(" Conversion History ({}) [j/k] [Esc] ", app.history.len()),
+            Style::default()
+                .fg(catppuccin::LAVENDER)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    if app.history.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                "  No conversions yet - press Enter after editing an amount",
+                Style::default().fg(catppuccin::OVERLAY1),
+            ))),
+            inner,
+        );
+        return;
+    }
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "  {:<17} {:>14}     {:>14}  {:>10}",
+            "When (local)", "Amount", "Converted", "Rate"
+        ),
+        Style::default()
+            .fg(catppuccin::PEACH)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    let visible = inner.height.saturating_sub(1) as usize;
+    for entry in app.history.iter().skip(app.history_scroll).take(visible) {
+        let when = entry
+            .timestamp
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M");
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<17} ", when),
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+            Span::styled(
+                format!("{:>10.2} {}", entry.from_amount, entry.from_currency),
+                Style::default().fg(catppuccin::TEXT),
+            ),
+            Span::styled(" → ", Style::default().fg(catppuccin::OVERLAY1)),
+            Span::styled(
+                format!("{:>10.2} {}", entry.to_amount, entry.to_currency),
+                Style::default().fg(catppuccin::GREEN),
+            ),
+            Span::styled(
+                format!("  {:>10.4}", entry.rate),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+        ]));
+    }
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// each config.toml problem with its line and suggestion, worst-first as found
+fn draw_config_problems_overlay(frame: &mut Frame, area: Rect, app: &App) {
+    let popup_width = 76.min(area.width.saturating_sub(4));
+    let popup_height =
+        (app.config_problems.len() as u16 * 3 + 4).min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Block::default().style(Style::default().bg(catppuccin::BASE)),
+        popup_area,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(catppuccin::PEACH))
+        .title(Span::styled(
+            " Config problems [Esc] ",
+            Style::default()
+                .fg(catppuccin::PEACH)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "  {} - defaults are used until these are fixed",
+            Config::config_path().display()
+        ),
+        Style::default().fg(catppuccin::SUBTEXT0),
+    ))];
+    for problem in &app.config_problems {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "  {:<9}",
+                    problem
+                        .line
+                        .map_or_else(|| "-".to_string(), |line| format!("line {}", line))
+                ),
+                Style::default().fg(catppuccin::OVERLAY1),
+            ),
+            Span::styled(
+                problem.field.clone(),
+                Style::default()
+                    .fg(catppuccin::YELLOW)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!("           {}", problem.message),
+            Style::default().fg(catppuccin::TEXT),
+        )));
+        if let Some(suggestion) = &problem.suggestion {
+            lines.push(Line::from(Span::styled(
+                format!("           {}", suggestion),
+                Style::default().fg(catppuccin::GREEN),
+            )));
+        }
+    }
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
+fn draw_about_overlay(frame: &mut Frame, area: Rect) {
+    let popup_width = 64.min(area.width.saturating_sub(4));
+    let popup_height = (PROVIDERS.len() as u16 * 3 + 7).min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Block::default().style(Style::default().bg(catppuccin::BASE)),
+        popup_area,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(catppuccin::BLUE))
+        .title(Span::styled(
+            " About [Esc] ",
+            Style::default()
+                .fg(catppuccin::BLUE)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                format!("nzi v{}", env!("CARGO_PKG_VERSION")),
+                Style::default()
+                    .fg(catppuccin::PEACH)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  {} licence", APP_LICENCE),
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Data providers",
+            Style::default()
+                .fg(catppuccin::PEACH)
+                .add_modifier(Modifier::BOLD),
+        )),
+    ];
+
+    for provider in PROVIDERS {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<18}", provider.name),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+            Span::styled(
+                format!("{} · {}", provider.used_for, provider.licence),
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!("    \"{}\"", provider.attribution),
+            Style::default().fg(catppuccin::SUBTEXT0),
+        )));
+        lines.push(Line::from(Span::styled(
+            format!("    {}", provider.url),
+            Style::default().fg(catppuccin::OVERLAY1),
+        )));
+    }
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// a help line's key column for a remappable action, padded like the fixed ones
+fn help_key(keymap: &Keymap, action: KeyAction) -> String {
+    let key = keymap.key(action).map_or_else(|| "-".to_string(), key_name);
+    format!("  {:<10}", key)
+}
+
+/// draw help overlay popup
+fn draw_help_overlay(frame: &mut Frame, area: Rect, keymap: &Keymap) {
+    // centre the help box
+    let help_width = 50.min(area.width.saturating_sub(4));
+    let help_height = 68.min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(help_width)) / 2;
+    let y = (area.height.saturating_sub(help_height)) / 2;
+    let help_area = Rect::new(x, y, help_width, help_height);
+
+    // clear the area behind
+    frame.render_widget(Clear, help_area);
     let clear = Block::default().style(Style::default().bg(catppuccin::BASE));
     frame.render_widget(clear, help_area);
 
@@ -531,9 +1785,39 @@ fn draw_help_overlay(frame: &mut Frame, area: Rect) {
             ),
         ]),
         Line::from(vec![
-            Span::styled("  q         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                help_key(keymap, KeyAction::Quit),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
             Span::styled("Quit application", Style::default().fg(catppuccin::TEXT)),
         ]),
+        Line::from(vec![
+            Span::styled(
+                help_key(keymap, KeyAction::ToggleClock),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+            Span::styled(
+                "Toggle 12/24-hour clock",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                help_key(keymap, KeyAction::Zoom),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+            Span::styled(
+                "Zoom the focused panel to the whole screen and back",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  C-arrows  ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Resize the map and time/currency splits (saved)",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
         Line::from(""),
         Line::from(vec![
             Span::styled(
@@ -547,64 +1831,333 @@ fn draw_help_overlay(frame: &mut Frame, area: Rect) {
         Line::from(vec![
             Span::styled("  Space     ", Style::default().fg(catppuccin::SAPPHIRE)),
             Span::styled(
-                "Cycle weather city / current target",
+                "Cycle weather city / current target",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  [ / ]     ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Previous / next right-hand tab ([tabs])",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  g w/m/t/c ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Go to weather / map / time / currency",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  c p/f/w   ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Cycle pair / from city / weather city",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  c         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Cycle pair (on the currency panel)",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  f         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Cycle time converter from city",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  '         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Focused map: next bookmark (/bookmark <name>)",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  b         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Show the input time in every city",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  +/-  v    ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Zoom the focused map (arrows pan, 0 resets); v world/NZ",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  1 2 3     ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Focused map: North/South Island/all NZ; Pacific/Europe/Americas",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  f a i m   ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Focused map: ferry / airports / legend; m measure",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  x         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Focused map: crosshair (arrows move, Enter shows lat/lon)",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  b         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Focused map: braille / half blocks / dots",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  H         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Focused map: shade the NZ coast by temperature",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  r         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Focused map: trace SH1 and the main state highways",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  click     ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "On the map: nearest city to weather / destination",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  S         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Scrub the input time in 30-minute steps (←/→)",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                help_key(keymap, KeyAction::Swap),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+            Span::styled(
+                "Swap current comparison / toggle weather view",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                help_key(keymap, KeyAction::Edit),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+            Span::styled(
+                "Edit time panel input or FX amount",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  0-9       ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Direct entry (time in normal mode, amount in currency)",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  Esc       ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled("Leave edit", Style::default().fg(catppuccin::TEXT)),
+        ]),
+        Line::from(vec![
+            Span::styled("  Hint      ", Style::default().fg(catppuccin::OVERLAY0)),
+            Span::styled(
+                "Title bars show keys (space, s, e)",
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Slash Commands",
+            Style::default()
+                .fg(catppuccin::PEACH)
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![
+            Span::styled("  /help     ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled("Show this help", Style::default().fg(catppuccin::TEXT)),
+        ]),
+        Line::from(vec![
+            Span::styled("  /edit     ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Edit config in $EDITOR",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /config   ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Open the staged Places editor",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /settings ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Display options and refresh intervals",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /stats    ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled("Show session stats", Style::default().fg(catppuccin::TEXT)),
+        ]),
+        Line::from(vec![
+            Span::styled("  /cache    ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Cache size; clear empties it",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /contrast ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Toggle high-contrast mode",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /climate  ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Monthly weather records you've kept",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /cal      ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Month calendar with anchor and home dates",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /tour     ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Guided walkthrough of each panel",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /anim     ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Flip waves, birds, sparkles, rainbow or flight",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /history  ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Conversion ledger; export [path]",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /export   ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "map [path]: save the map as text (.ans for colour)",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /export   ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "config [path]: bundle config and rates",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /import   ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "config <path>: restore a bundle",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /event    ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Countdown, e.g. sat 19:00 BOS Call",
+                Style::default().fg(catppuccin::TEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  /alarm    ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Ring at a city's local time, e.g. 09:00 LDN",
                 Style::default().fg(catppuccin::TEXT),
             ),
         ]),
         Line::from(vec![
-            Span::styled("  s         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled("  /todo     ", Style::default().fg(catppuccin::SAPPHIRE)),
             Span::styled(
-                "Swap current comparison / toggle weather view",
+                "Checklist: add <text>, done/rm <n>",
                 Style::default().fg(catppuccin::TEXT),
             ),
         ]),
         Line::from(vec![
-            Span::styled("  e         ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled("  /note     ", Style::default().fg(catppuccin::SAPPHIRE)),
             Span::styled(
-                "Edit time panel input or FX amount",
+                "Note on the destination city",
                 Style::default().fg(catppuccin::TEXT),
             ),
         ]),
         Line::from(vec![
-            Span::styled("  0-9       ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled("  /ts       ", Style::default().fg(catppuccin::SAPPHIRE)),
             Span::styled(
-                "Direct entry (time in normal mode, amount in currency)",
+                "Show a unix timestamp in both cities",
                 Style::default().fg(catppuccin::TEXT),
             ),
         ]),
         Line::from(vec![
-            Span::styled("  Esc       ", Style::default().fg(catppuccin::SAPPHIRE)),
-            Span::styled("Leave edit", Style::default().fg(catppuccin::TEXT)),
+            Span::styled("  /city add ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Search and track a new city",
+                Style::default().fg(catppuccin::TEXT),
+            ),
         ]),
         Line::from(vec![
-            Span::styled("  Hint      ", Style::default().fg(catppuccin::OVERLAY0)),
+            Span::styled("  /addcity  ", Style::default().fg(catppuccin::SAPPHIRE)),
             Span::styled(
-                "Title bars show keys (space, s, e)",
-                Style::default().fg(catppuccin::SUBTEXT0),
+                "Track a city from the world database",
+                Style::default().fg(catppuccin::TEXT),
             ),
         ]),
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            "Slash Commands",
-            Style::default()
-                .fg(catppuccin::PEACH)
-                .add_modifier(Modifier::BOLD),
-        )]),
         Line::from(vec![
-            Span::styled("  /help     ", Style::default().fg(catppuccin::SAPPHIRE)),
-            Span::styled("Show this help", Style::default().fg(catppuccin::TEXT)),
+            Span::styled("  /plan     ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled(
+                "Meeting planner across cities",
+                Style::default().fg(catppuccin::TEXT),
+            ),
         ]),
         Line::from(vec![
-            Span::styled("  /edit     ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled("  /about    ", Style::default().fg(catppuccin::SAPPHIRE)),
             Span::styled(
-                "Edit config in $EDITOR",
+                "Data providers and licence",
                 Style::default().fg(catppuccin::TEXT),
             ),
         ]),
         Line::from(vec![
-            Span::styled("  /config   ", Style::default().fg(catppuccin::SAPPHIRE)),
+            Span::styled("  /problems ", Style::default().fg(catppuccin::SAPPHIRE)),
             Span::styled(
-                "Open the staged Places editor",
+                "Config fields that fell back to defaults",
                 Style::default().fg(catppuccin::TEXT),
             ),
         ]),
@@ -706,7 +2259,7 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(block, area);
 
     // render sparkle background
-    if app.config.display.show_animations {
+    if app.config.display.active_animations().sparkles {
         frame.render_widget(Sparkles::new(app.animation_frame).density(12), inner);
     }
 
@@ -737,6 +2290,16 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
         ));
     }
 
+    // a skewed system clock undermines every time shown, so say so up top
+    if let Some(warning) = app.clock_skew_warning() {
+        title_spans.push(Span::styled(
+            format!("  {}", warning),
+            Style::default()
+                .fg(catppuccin::RED)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
     // version on the right
     let version = format!("v{} ", env!("CARGO_PKG_VERSION"));
     let version_span = Span::styled(version, Style::default().fg(catppuccin::OVERLAY0));
@@ -756,16 +2319,584 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
     }
 }
 
+/// one-line strip of abbreviated times for every tracked city
+fn draw_world_clock_strip(frame: &mut Frame, area: Rect, app: &App) {
+    let entries = world_clock_entries(app);
+    let mut spans: Vec<Span> = Vec::new();
+    let mut used = 0;
+
+    for (i, (code, time)) in entries.iter().enumerate() {
+        let separator = if i == 0 { " " } else { " · " };
+        let entry_width = separator.len() + code.len() + 1 + time.len();
+        if used + entry_width > area.width as usize {
+            break;
+        }
+        used += entry_width;
+
+        spans.push(Span::styled(
+            separator,
+            Style::default().fg(catppuccin::SURFACE2),
+        ));
+        spans.push(Span::styled(
+            format!("{} ", code),
+            Style::default().fg(catppuccin::SAPPHIRE),
+        ));
+        spans.push(Span::styled(
+            time.clone(),
+            Style::default().fg(catppuccin::TEXT),
+        ));
+    }
+
+    frame.render_widget(
+        Paragraph::new(Line::from(spans)).alignment(Alignment::Center),
+        area,
+    );
+}
+
+/// (code, time) pairs for the anchor and every target city, in display order;
+/// while scrubbing they show the converter's hypothetical time instead of now
+fn world_clock_entries(app: &App) -> Vec<(String, String)> {
+    let use_24_hour = app.config.display.use_24_hour;
+    let converter = &app.time_converter;
+    app.current_city_time
+        .iter()
+        .chain(app.world_city_times.iter())
+        .map(|ct| {
+            let scrubbed = app
+                .is_scrubbing()
+                .then(|| {
+                    app.timezone_service.convert_time(
+                        &converter.from_city_code,
+                        &ct.city_code,
+                        converter.input_hour,
+                        converter.input_minute,
+                    )
+                })
+                .flatten();
+            let time = match scrubbed {
+                Some((hour, minute, 0)) => format_clock(hour, minute, use_24_hour),
+                Some((hour, minute, day_offset)) => format!(
+                    "{}{:+}d",
+                    format_clock(hour, minute, use_24_hour),
+                    day_offset
+                ),
+                None => ct.time_string(use_24_hour, false),
+            };
+            (ct.city_code.clone(), time)
+        })
+        .collect()
+}
+
+/// where each content panel was drawn, so the tour can spotlight one; None for a panel
+/// turned off
+#[derive(Debug, Clone, Copy, Default)]
+struct PanelAreas {
+    map: Option<Rect>,
+    weather: Option<Rect>,
+    time: Option<Rect>,
+    currency: Option<Rect>,
+    /// the `[tabs]` strip
+    tabs: Option<Rect>,
+    checklist: Option<Rect>,
+}
+
+impl PanelAreas {
+    fn get(&self, focus: Focus) -> Option<Rect> {
+        match focus {
+            Focus::Map => self.map,
+            Focus::Weather => self.weather,
+            Focus::TimeConvert => self.time,
+            Focus::Currency => self.currency,
+        }
+    }
+}
+
+/// the time and currency panels side by side in `area`, either one taking all of it when
+/// the other is turned off
+fn draw_time_and_currency(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    time_percent: u16,
+    panels: &mut PanelAreas,
+) {
+    let (time, currency) = match (
+        app.panel_shown(Focus::TimeConvert),
+        app.panel_shown(Focus::Currency),
+    ) {
+        (true, true) => {
+            let halves = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(time_percent),
+                    Constraint::Percentage(100 - time_percent),
+                ])
+                .split(area);
+            (Some(halves[0]), Some(halves[1]))
+        }
+        (true, false) => (Some(area), None),
+        (false, true) => (None, Some(area)),
+        (false, false) => (None, None),
+    };
+    if let Some(time) = time {
+        draw_time_panel(frame, time, app);
+    }
+    if let Some(currency) = currency {
+        draw_currency_panel(frame, currency, app);
+    }
+    panels.time = time;
+    panels.currency = currency;
+}
+
+/// whether the content area is below `layout.narrow_width`, where panels stack
+fn is_narrow(app: &App, area: Rect) -> bool {
+    area.width < app.config.effective_layout().narrow_width
+}
+
+/// narrow terminals: map, compact weather, time and currency one above the other, the
+/// map dropped when it would get too few rows to read
+fn draw_stacked_content(frame: &mut Frame, area: Rect, app: &App) -> PanelAreas {
+    let layout = app.config.effective_layout();
+    let mut rows = Vec::new();
+    if app.panel_shown(Focus::Weather) {
+        rows.push((Focus::Weather, NARROW_WEATHER_HEIGHT));
+    }
+    if app.panel_shown(Focus::TimeConvert) {
+        rows.push((Focus::TimeConvert, layout.bottom_height));
+    }
+    if app.panel_shown(Focus::Currency) {
+        rows.push((Focus::Currency, layout.bottom_height));
+    }
+    let below: u16 = rows.iter().map(|(_, height)| height).sum();
+    if app.map_enabled()
+        && (rows.is_empty() || area.height.saturating_sub(below) >= NARROW_MAP_MIN_HEIGHT)
+    {
+        rows.insert(0, (Focus::Map, 0));
+    }
+
+    // the map takes what's left; without it the last panel does
+    let fill = rows.first().is_some_and(|(focus, _)| *focus == Focus::Map);
+    let count = rows.len();
+    let constraints: Vec<Constraint> = rows
+        .iter()
+        .enumerate()
+        .map(|(index, (_, height))| {
+            if (fill && index == 0) || (!fill && index + 1 == count) {
+                Constraint::Fill(1)
+            } else {
+                Constraint::Length(*height)
+            }
+        })
+        .collect();
+    let areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    let mut panels = PanelAreas::default();
+    for ((focus, _), &area) in rows.iter().zip(areas.iter()) {
+        match focus {
+            Focus::Map => {
+                draw_map_panel(frame, area, app);
+                panels.map = Some(area);
+            }
+            Focus::Weather => {
+                draw_weather_panel(frame, area, app);
+                panels.weather = Some(area);
+            }
+            Focus::TimeConvert => {
+                draw_time_panel(frame, area, app);
+                panels.time = Some(area);
+            }
+            Focus::Currency => {
+                draw_currency_panel(frame, area, app);
+                panels.currency = Some(area);
+            }
+        }
+    }
+    panels
+}
+
+/// layouts with the weather panel, or both the time and currency panels, turned off: the
+/// map keeps its side and what's left fills the other
+fn draw_partial_content(frame: &mut Frame, area: Rect, app: &App) -> PanelAreas {
+    let layout = app.config.effective_layout();
+    let show_weather = app.panel_shown(Focus::Weather);
+    let show_bottom = app.panel_shown(Focus::TimeConvert) || app.panel_shown(Focus::Currency);
+    let mut panels = PanelAreas::default();
+
+    let info = if app.map_enabled() {
+        if !show_weather && !show_bottom {
+            draw_map_panel(frame, area, app);
+            panels.map = Some(area);
+            return panels;
+        }
+        let percent = if show_weather && app.weather_expanded {
+            layout.map_percent
+        } else {
+            layout.compact_map_percent
+        };
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(percent),
+                Constraint::Percentage(100 - percent),
+            ])
+            .split(area);
+        draw_map_panel(frame, body[0], app);
+        panels.map = Some(body[0]);
+        body[1]
+    } else {
+        area
+    };
+
+    if show_weather {
+        if app.weather_expanded && weather_grid_can_fit(info) {
+            draw_weather_panel_expanded(frame, info, app);
+        } else {
+            draw_weather_panel(frame, info, app);
+        }
+        panels.weather = Some(info);
+    } else {
+        draw_time_and_currency(frame, info, app, 55, &mut panels);
+    }
+    panels
+}
+
+/// whether `panel` is on any `[tabs]` page
+fn on_a_tab(app: &App, panel: TabPanel) -> bool {
+    app.config
+        .tabs
+        .as_ref()
+        .is_some_and(|tabs| tabs.pages.iter().flatten().any(|&listed| listed == panel))
+}
+
+/// the `[tabs]` strip's labels, as drawn
+fn tab_labels(app: &App) -> Vec<String> {
+    let Some(tabs) = &app.config.tabs else {
+        return Vec::new();
+    };
+    let selected = tabs.selected.min(tabs.pages.len().saturating_sub(1));
+    tabs.pages
+        .iter()
+        .enumerate()
+        .map(|(index, page)| {
+            let label = page
+                .iter()
+                .map(|panel| panel.label())
+                .collect::<Vec<_>>()
+                .join("·");
+            if index == selected && high_contrast() {
+                format!("[{} {}]", index + 1, label)
+            } else {
+                format!(" {} {} ", index + 1, label)
+            }
+        })
+        .collect()
+}
+
+/// one row naming the `[tabs]` pages, the shown one highlighted
+fn draw_tab_strip(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(tabs) = &app.config.tabs else {
+        return;
+    };
+    let selected = tabs.selected.min(tabs.pages.len().saturating_sub(1));
+    let mut spans = Vec::new();
+    for (index, label) in tab_labels(app).into_iter().enumerate() {
+        let is_active = index == selected;
+        spans.push(Span::styled(
+            label,
+            if is_active {
+                Style::default()
+                    .fg(catppuccin::BASE)
+                    .bg(catppuccin::MAUVE)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+                    .fg(catppuccin::OVERLAY1)
+                    .bg(catppuccin::SURFACE1)
+            },
+        ));
+        spans.push(Span::raw(" "));
+    }
+    spans.push(Span::styled(
+        "[ ]",
+        Style::default().fg(catppuccin::OVERLAY0),
+    ));
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// recent quakes, newest first, from the map's quake layer
+fn draw_quakes_panel(frame: &mut Frame, area: Rect, app: &App) {
+    let block = styled_block("Quakes", false);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let quakes = app.recent_quakes();
+    let lines: Vec<Line> = if !app.config.effective_map_settings().quakes {
+        vec![Line::from(Span::styled(
+            "Turn on map.quakes to list recent quakes",
+            Style::default().fg(catppuccin::OVERLAY0),
+        ))]
+    } else if quakes.is_empty() {
+        vec![Line::from(Span::styled(
+            "No recent quakes",
+            Style::default().fg(catppuccin::OVERLAY0),
+        ))]
+    } else {
+        let now = Utc::now();
+        quakes
+            .iter()
+            .map(|quake| {
+                let colour = if quake.magnitude >= 5.0 {
+                    catppuccin::RED
+                } else if quake.magnitude >= 4.0 {
+                    catppuccin::PEACH
+                } else {
+                    catppuccin::TEXT
+                };
+                Line::from(Span::styled(
+                    truncate_display(&quake.summary(now), inner.width as usize),
+                    Style::default().fg(colour),
+                ))
+            })
+            .collect()
+    };
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// the map beside the `[tabs]` page shown: the tab strip, then the page's panels stacked
+/// beneath it, with time and currency sharing a row when they're next to each other
+fn draw_tabbed_content(frame: &mut Frame, area: Rect, app: &App, page: &[TabPanel]) -> PanelAreas {
+    let layout = app.config.effective_layout();
+    let mut panels = PanelAreas::default();
+    let info = if app.map_enabled() {
+        let percent = if page.contains(&TabPanel::Weather) && app.weather_expanded {
+            layout.map_percent
+        } else {
+            layout.compact_map_percent
+        };
+        // narrow terminals put the map above the page instead of beside it
+        let body = Layout::default()
+            .direction(if is_narrow(app, area) {
+                Direction::Vertical
+            } else {
+                Direction::Horizontal
+            })
+            .constraints([
+                Constraint::Percentage(percent),
+                Constraint::Percentage(100 - percent),
+            ])
+            .split(area);
+        draw_map_panel(frame, body[0], app);
+        panels.map = Some(body[0]);
+        body[1]
+    } else {
+        area
+    };
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(info);
+    draw_tab_strip(frame, parts[0], app);
+    panels.tabs = Some(parts[0]);
+
+    let mut rows: Vec<Vec<TabPanel>> = Vec::new();
+    for &panel in page {
+        let shown = match panel {
+            TabPanel::Weather => app.panel_shown(Focus::Weather),
+            TabPanel::Time => app.panel_shown(Focus::TimeConvert),
+            TabPanel::Currency => app.panel_shown(Focus::Currency),
+            TabPanel::Quakes | TabPanel::Checklist | TabPanel::Custom => true,
+        };
+        if !shown {
+            continue;
+        }
+        match rows.last_mut() {
+            Some(row)
+                if matches!(
+                    (row.as_slice(), panel),
+                    ([TabPanel::Time], TabPanel::Currency) | ([TabPanel::Currency], TabPanel::Time)
+                ) =>
+            {
+                row.push(panel);
+            }
+            _ => rows.push(vec![panel]),
+        }
+    }
+    let has_weather = rows.iter().any(|row| row[0] == TabPanel::Weather);
+    let constraints: Vec<Constraint> = rows
+        .iter()
+        .map(|row| match row[0] {
+            TabPanel::Time | TabPanel::Currency if has_weather => {
+                Constraint::Length(layout.compact_bottom_height)
+            }
+            _ => Constraint::Fill(1),
+        })
+        .collect();
+    let areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(parts[1]);
+
+    for (row, &area) in rows.iter().zip(areas.iter()) {
+        match row.as_slice() {
+            [TabPanel::Weather] => {
+                if app.weather_expanded && weather_grid_can_fit(area) {
+                    draw_weather_panel_expanded(frame, area, app);
+                } else {
+                    draw_weather_panel(frame, area, app);
+                }
+                panels.weather = Some(area);
+            }
+            [TabPanel::Time] | [TabPanel::Currency] | [_, _] => {
+                draw_time_and_currency(frame, area, app, 55, &mut panels);
+            }
+            [TabPanel::Quakes] => draw_quakes_panel(frame, area, app),
+            [TabPanel::Checklist] => {
+                draw_checklist(frame, area, app);
+                panels.checklist = Some(area);
+            }
+            _ => {
+                let custom = &app.custom_panels.panels;
+                if custom.is_empty() {
+                    let block = styled_block("Custom", false);
+                    let inner = block.inner(area);
+                    frame.render_widget(block, area);
+                    frame.render_widget(
+                        Paragraph::new(Span::styled(
+                            "Add [[panel.custom]] commands to fill this panel",
+                            Style::default().fg(catppuccin::OVERLAY0),
+                        )),
+                        inner,
+                    );
+                }
+                let slots = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(vec![
+                        Constraint::Ratio(1, custom.len().max(1) as u32);
+                        custom.len()
+                    ])
+                    .split(area);
+                for (panel, &slot) in custom.iter().zip(slots.iter()) {
+                    draw_custom_panel(frame, slot, panel);
+                }
+            }
+        }
+    }
+    panels
+}
+
+/// the focused panel alone, filling the content area
+fn draw_zoomed_content(frame: &mut Frame, area: Rect, app: &App) -> PanelAreas {
+    let mut panels = PanelAreas::default();
+    draw_panel(frame, area, app, app.focus);
+    match app.focus {
+        Focus::Map => panels.map = Some(area),
+        Focus::Weather => panels.weather = Some(area),
+        Focus::TimeConvert => panels.time = Some(area),
+        Focus::Currency => panels.currency = Some(area),
+    }
+    panels
+}
+
+/// one panel on its own in `area`, as zoom and layout transitions draw them
+fn draw_panel(frame: &mut Frame, area: Rect, app: &App, focus: Focus) {
+    match focus {
+        Focus::Map => draw_map_panel(frame, area, app),
+        Focus::Weather => {
+            if app.weather_expanded && weather_grid_can_fit(area) {
+                draw_weather_panel_expanded(frame, area, app);
+            } else {
+                draw_weather_panel(frame, area, app);
+            }
+        }
+        Focus::TimeConvert => draw_time_panel(frame, area, app),
+        Focus::Currency => draw_currency_panel(frame, area, app),
+    }
+}
+
+/// draw the content area, part way between the old and new layouts while a zoom or
+/// weather view change is settling
+fn draw_content(frame: &mut Frame, area: Rect, app: &App) -> PanelAreas {
+    let panels = draw_layout(frame, area, app);
+    let Some((from, progress)) = app.transition_progress() else {
+        return panels;
+    };
+
+    let to: Vec<(Rect, Focus)> = [
+        Focus::Map,
+        Focus::Weather,
+        Focus::TimeConvert,
+        Focus::Currency,
+    ]
+    .into_iter()
+    .filter_map(|focus| Some((panels.get(focus)?, focus)))
+    .collect();
+    for (rect, _) in from.iter().chain(&to) {
+        frame.render_widget(Clear, *rect);
+        frame.render_widget(
+            Block::default().style(Style::default().bg(catppuccin::BASE)),
+            *rect,
+        );
+    }
+
+    // panels only in one layout stay put underneath; those in both slide and grow on top
+    let moving = |focus: Focus| from.iter().chain(&to).filter(|(_, f)| *f == focus).count() == 2;
+    for (rect, focus) in from.iter().chain(&to) {
+        if !moving(*focus) {
+            draw_panel(frame, *rect, app, *focus);
+        }
+    }
+    for (rect, focus) in &to {
+        if let Some((start, _)) = from.iter().find(|(_, f)| f == focus) {
+            draw_panel(frame, lerp_rect(*start, *rect, progress), app, *focus);
+        }
+    }
+    panels
+}
+
+/// the rect `progress` of the way from `from` to `to`, moving each edge so panels that
+/// share an edge keep sharing it
+fn lerp_rect(from: Rect, to: Rect, progress: f64) -> Rect {
+    let lerp =
+        |a: u16, b: u16| (f64::from(a) + (f64::from(b) - f64::from(a)) * progress).round() as u16;
+    let left = lerp(from.left(), to.left());
+    let top = lerp(from.top(), to.top());
+    let right = lerp(from.right(), to.right());
+    let bottom = lerp(from.bottom(), to.bottom());
+    Rect::new(
+        left,
+        top,
+        right.saturating_sub(left),
+        bottom.saturating_sub(top),
+    )
+}
+
 /// draw the main content area with dynamic layout based on weather expansion
-fn draw_content(frame: &mut Frame, area: Rect, app: &App) {
+fn draw_layout(frame: &mut Frame, area: Rect, app: &App) -> PanelAreas {
+    if app.zoomed {
+        return draw_zoomed_content(frame, area, app);
+    }
+    if let Some(page) = app.config.selected_tab() {
+        return draw_tabbed_content(frame, area, app, page);
+    }
+    if is_narrow(app, area) {
+        return draw_stacked_content(frame, area, app);
+    }
+    if !app.panel_shown(Focus::Weather)
+        || !(app.panel_shown(Focus::TimeConvert) || app.panel_shown(Focus::Currency))
+    {
+        return draw_partial_content(frame, area, app);
+    }
     if !app.map_enabled() {
-        draw_content_without_map(frame, area, app);
-        return;
+        return draw_content_without_map(frame, area, app);
     }
 
     // decide whether expanded grid can fit; otherwise fall back to compact
+    let layout = app.config.effective_layout();
     let mut use_expanded = app.weather_expanded;
-    if use_expanded && !weather_grid_can_fit(expanded_weather_panel_area(area, true)) {
+    if use_expanded && !weather_grid_can_fit(expanded_weather_panel_area(area, true, &layout)) {
         use_expanded = false;
     }
 
@@ -774,15 +2905,15 @@ fn draw_content(frame: &mut Frame, area: Rect, app: &App) {
         let body = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
-                Constraint::Percentage(33), // map
-                Constraint::Percentage(67), // info panels
+                Constraint::Percentage(layout.map_percent),       // map
+                Constraint::Percentage(100 - layout.map_percent), // info panels
             ])
             .split(area);
 
         // scale weather height with terminal height; reserve a small strip for time/currency
         let rhs_height = body[1].height;
-        let min_bottom = 7;
-        let min_weather = 14;
+        let min_bottom = layout.bottom_height;
+        let min_weather = layout.weather_min_height;
 
         let mut weather_height = rhs_height.saturating_sub(min_bottom);
         if weather_height < min_weather {
@@ -805,82 +2936,92 @@ fn draw_content(frame: &mut Frame, area: Rect, app: &App) {
             ])
             .split(body[1]);
 
-        let bottom_right = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(right_side[1]);
-
         draw_map_panel(frame, body[0], app);
         draw_weather_panel_expanded(frame, right_side[0], app);
-        draw_time_panel(frame, bottom_right[0], app);
-        draw_currency_panel(frame, bottom_right[1], app);
+        let mut panels = PanelAreas {
+            map: Some(body[0]),
+            weather: Some(right_side[0]),
+            ..PanelAreas::default()
+        };
+        draw_time_and_currency(frame, right_side[1], app, 50, &mut panels);
+        panels
     } else {
         // compact view: map on left, weather + utilities on right
         let body = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
-                Constraint::Percentage(40), // map
-                Constraint::Percentage(60), // info panels
+                Constraint::Percentage(layout.compact_map_percent), // map
+                Constraint::Percentage(100 - layout.compact_map_percent), // info panels
             ])
             .split(area);
 
         let right_side = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Min(10),    // compact weather panel
-                Constraint::Length(11), // world clocks + fx
+                Constraint::Min(layout.compact_weather_min_height), // compact weather panel
+                Constraint::Length(layout.compact_bottom_height),   // world clocks + fx
             ])
             .split(body[1]);
 
-        let bottom_right = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
-            .split(right_side[1]);
-
         draw_map_panel(frame, body[0], app);
         draw_weather_panel(frame, right_side[0], app);
-        draw_time_panel(frame, bottom_right[0], app);
-        draw_currency_panel(frame, bottom_right[1], app);
+        let mut panels = PanelAreas {
+            map: Some(body[0]),
+            weather: Some(right_side[0]),
+            ..PanelAreas::default()
+        };
+        draw_time_and_currency(frame, right_side[1], app, 55, &mut panels);
+        panels
     }
 }
 
-fn draw_content_without_map(frame: &mut Frame, area: Rect, app: &App) {
+fn draw_content_without_map(frame: &mut Frame, area: Rect, app: &App) -> PanelAreas {
+    let layout = app.config.effective_layout();
     let mut use_expanded = app.weather_expanded;
-    if use_expanded && !weather_grid_can_fit(expanded_weather_panel_area(area, false)) {
+    if use_expanded && !weather_grid_can_fit(expanded_weather_panel_area(area, false, &layout)) {
         use_expanded = false;
     }
 
     if use_expanded {
         let body = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(14), Constraint::Length(7)])
+            .constraints([
+                Constraint::Min(layout.weather_min_height),
+                Constraint::Length(layout.bottom_height),
+            ])
             .split(area);
 
-        let bottom = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
-            .split(body[1]);
-
         draw_weather_panel_expanded(frame, body[0], app);
-        draw_time_panel(frame, bottom[0], app);
-        draw_currency_panel(frame, bottom[1], app);
+        let mut panels = PanelAreas {
+            weather: Some(body[0]),
+            ..PanelAreas::default()
+        };
+        draw_time_and_currency(frame, body[1], app, 55, &mut panels);
+        panels
     } else {
         let body = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(10), Constraint::Length(11)])
+            .constraints([
+                Constraint::Min(layout.compact_weather_min_height),
+                Constraint::Length(layout.compact_bottom_height),
+            ])
             .split(area);
 
-        let bottom = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
-            .split(body[1]);
-
         draw_weather_panel(frame, body[0], app);
-        draw_time_panel(frame, bottom[0], app);
-        draw_currency_panel(frame, bottom[1], app);
+        let mut panels = PanelAreas {
+            weather: Some(body[0]),
+            ..PanelAreas::default()
+        };
+        draw_time_and_currency(frame, body[1], app, 55, &mut panels);
+        panels
     }
 }
 
+/// a forecast reading, or a dash when the provider left it out
+fn reading(value: Option<i32>) -> String {
+    value.map_or_else(|| "–".to_string(), |value| value.to_string())
+}
+
 /// create a styled block with focus indication
 fn styled_block(title: &str, focused: bool) -> Block<'static> {
     let (border_type, border_color) = if focused {
@@ -894,7 +3035,12 @@ fn styled_block(title: &str, focused: bool) -> Block<'static> {
         .border_type(border_type)
         .border_style(Style::default().fg(border_color))
         .title(Span::styled(
-            format!(" {} ", title),
+            // high contrast marks focus in text as well as colour
+            if focused && high_contrast() {
+                format!(" ▶ {} ", title)
+            } else {
+                format!(" {} ", title)
+            },
             if focused {
                 Style::default()
                     .fg(catppuccin::YELLOW)
@@ -905,16 +3051,19 @@ fn styled_block(title: &str, focused: bool) -> Block<'static> {
         ))
 }
 
-fn expanded_weather_panel_area(area: Rect, map_enabled: bool) -> Rect {
+fn expanded_weather_panel_area(area: Rect, map_enabled: bool, layout: &LayoutConfig) -> Rect {
     if map_enabled {
         let body = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(33), Constraint::Percentage(67)])
+            .constraints([
+                Constraint::Percentage(layout.map_percent),
+                Constraint::Percentage(100 - layout.map_percent),
+            ])
             .split(area);
 
         let rhs_height = body[1].height;
-        let min_bottom = 7;
-        let min_weather = WEATHER_EXPANDED_MIN_HEIGHT;
+        let min_bottom = layout.bottom_height;
+        let min_weather = layout.weather_min_height;
 
         let mut weather_height = rhs_height.saturating_sub(min_bottom);
         if weather_height < min_weather {
@@ -935,19 +3084,89 @@ fn expanded_weather_panel_area(area: Rect, map_enabled: bool) -> Rect {
             ])
             .split(body[1])[0]
     } else {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(WEATHER_EXPANDED_MIN_HEIGHT),
-                Constraint::Length(7),
-            ])
-            .split(area)[0]
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(layout.weather_min_height),
+                Constraint::Length(layout.bottom_height),
+            ])
+            .split(area)[0]
+    }
+}
+
+fn weather_grid_can_fit(panel_area: Rect) -> bool {
+    weather_grid_layout(panel_area.width.saturating_sub(2)).is_some()
+        && panel_area.height.saturating_sub(2) >= WEATHER_EXPANDED_MIN_HEIGHT
+}
+
+/// column set and cell width for the forecast grid at a given inner width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WeatherGridLayout {
+    periods: &'static [TimeOfDay],
+    cell_width: usize,
+}
+
+impl WeatherGridLayout {
+    fn width(&self) -> usize {
+        self.cell_width * self.periods.len() + self.periods.len() + 1
+    }
+
+    fn is_narrow(&self) -> bool {
+        self.periods.len() < WEATHER_GRID_COLUMNS
+    }
+}
+
+fn weather_grid_layout(inner_width: u16) -> Option<WeatherGridLayout> {
+    const FULL: &[TimeOfDay] = &[
+        TimeOfDay::Morning,
+        TimeOfDay::Noon,
+        TimeOfDay::Evening,
+        TimeOfDay::Night,
+    ];
+    const NARROW: &[TimeOfDay] = &[TimeOfDay::Noon, TimeOfDay::Night];
+
+    let (periods, min_cell) = if inner_width >= WEATHER_GRID_WIDTH {
+        (FULL, WEATHER_GRID_CELL_WIDTH)
+    } else if inner_width >= WEATHER_GRID_NARROW_WIDTH {
+        (NARROW, WEATHER_GRID_NARROW_CELL_WIDTH)
+    } else {
+        return None;
+    };
+
+    // widen cells to use the space, leaving room for one border per column plus one
+    let borders = periods.len() + 1;
+    let cell_width = ((inner_width as usize - borders) / periods.len())
+        .clamp(min_cell, WEATHER_GRID_MAX_CELL_WIDTH);
+    Some(WeatherGridLayout {
+        periods,
+        cell_width,
+    })
+}
+
+fn period_header(period: TimeOfDay, narrow: bool) -> (&'static str, ratatui::style::Color) {
+    match period {
+        TimeOfDay::Morning => ("Morning", catppuccin::PEACH),
+        TimeOfDay::Noon if narrow => ("Day", catppuccin::YELLOW),
+        TimeOfDay::Noon => ("Noon", catppuccin::YELLOW),
+        TimeOfDay::Evening => ("Evening", catppuccin::MAUVE),
+        TimeOfDay::Night => ("Night", catppuccin::LAVENDER),
     }
 }
 
-fn weather_grid_can_fit(panel_area: Rect) -> bool {
-    panel_area.width.saturating_sub(2) >= WEATHER_GRID_WIDTH
-        && panel_area.height.saturating_sub(2) >= WEATHER_EXPANDED_MIN_HEIGHT
+/// the map panel as it's currently shown, drawn off screen at `width`×`height` for export;
+/// None while the map is switched off
+pub fn render_map(app: &App, width: u16, height: u16) -> Option<Buffer> {
+    if !app.config.effective_map_settings().enabled {
+        return None;
+    }
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).ok()?;
+    terminal
+        .draw(|frame| {
+            draw_map_panel(frame, frame.area(), app);
+            recolour(frame.buffer_mut(), app.config.display.effective_theme());
+        })
+        .ok()?;
+    Some(terminal.backend().buffer().clone())
 }
 
 /// draw the new zealand map panel with canvas/braille rendering
@@ -962,48 +3181,177 @@ fn draw_map_panel(frame: &mut Frame, area: Rect, app: &App) {
     match context {
         Focus::Weather => {
             let highlight = Some(app.get_weather_city_code().to_string());
+            let quakes = app.recent_quakes();
+            let trip = (app.focus == Focus::TimeConvert)
+                .then(|| app.converter_nz_trip())
+                .flatten();
+            let note = app
+                .map_measurement()
+                .or_else(|| app.map_crosshair_readout())
+                .or_else(|| {
+                    trip.as_ref()
+                        .map(|[from, to]| format!("Time {}→{}", from.0, to.0))
+                })
+                .or_else(|| quakes.first().map(|quake| quake.summary(Utc::now())));
+            let legend = MapLegend::nz(
+                NzLegendLayers {
+                    towns: !map_settings.markers.is_empty(),
+                    quakes: !quakes.is_empty(),
+                    ferry: app.map_shows_ferry,
+                    airports: app.map_shows_airports,
+                    heat: !app.heat_points().is_empty(),
+                    trip: trip.is_some(),
+                    highways: app.map_shows_highways,
+                },
+                app.config.display.active_animations(),
+            );
             frame.render_widget(
                 NzMapCanvas::new()
                     .highlight_city(highlight)
+                    .quakes(quakes.into_iter().cloned().collect())
+                    .ferry(app.map_shows_ferry)
+                    .airports(app.map_shows_airports)
+                    .aurora(app.aurora_kp())
+                    .heat(app.heat_points().to_vec())
+                    .detailed_coastline(map_settings.detailed_coastline)
+                    .highways(app.map_shows_highways)
+                    .trip(trip)
+                    .animations(app.config.display.active_animations())
+                    .note(note)
+                    .crosshair(app.map_crosshair)
+                    .marker_style(map_settings.marker_style())
+                    .coastline(
+                        map_settings
+                            .coastline_colour
+                            .as_deref()
+                            .and_then(parse_colour),
+                    )
+                    .temperatures(
+                        NZ_CITIES
+                            .iter()
+                            .filter_map(|city| {
+                                Some((city.code, cached_temperature(app, city.name)?))
+                            })
+                            .collect(),
+                    )
+                    .towns(
+                        map_settings
+                            .markers
+                            .iter()
+                            .map(|marker| TownMarker {
+                                code: marker.code.clone(),
+                                lat: marker.lat,
+                                lon: marker.lon,
+                            })
+                            .collect(),
+                    )
                     .tick(app.animation_frame as u64)
+                    .viewport(app.nz_map_view)
                     .focused(app.focus == Focus::Map),
                 area,
             );
+            if app.map_shows_legend {
+                frame.render_widget(legend, area);
+            }
         }
         Focus::TimeConvert | Focus::Currency | Focus::Map => {
             let (primary, secondary, label) = world_map_markers(app, context);
-            let title = if context == Focus::Map {
+            let legend = MapLegend::world(
+                primary.is_some() && secondary.is_some(),
+                app.map_shows_airports,
+                !app.config.markers.is_empty(),
+                !app.iss_trail().is_empty(),
+                app.homeward_flight().is_some(),
+            );
+            let mut title = if context == Focus::Map {
                 format!("World map ({})", configured_map_summary(app))
             } else {
                 format!("World map ({})", label)
             };
+            if let Some(measurement) = app.map_measurement() {
+                title.push_str(&format!(" · {}", measurement));
+            }
+            if let Some(readout) = app.map_crosshair_readout() {
+                title.push_str(&format!(" · {}", readout));
+            }
+            if let Some(pass) = app.iss_pass_readout() {
+                title.push_str(&format!(" · {}", pass));
+            }
             frame.render_widget(
                 WorldMapCanvas::new()
                     .primary(primary)
                     .secondary(secondary)
                     .title(title)
                     .tick(app.animation_frame as u64)
+                    .viewport(app.world_map_view)
+                    .airports(app.map_shows_airports)
+                    .iss(app.iss_trail().to_vec())
+                    .rainbow(app.config.display.active_animations().rainbow)
+                    .homeward(app.homeward_flight())
+                    .projection(map_settings.projection)
+                    .crosshair(app.map_crosshair)
+                    .marker_style(map_settings.marker_style())
+                    .coastline(
+                        map_settings
+                            .coastline_colour
+                            .as_deref()
+                            .and_then(parse_colour),
+                    )
+                    .pins(
+                        app.config
+                            .markers
+                            .iter()
+                            .map(|marker| WorldPin {
+                                label: marker.label.clone(),
+                                lat: marker.lat,
+                                lon: marker.lon,
+                                color: marker
+                                    .colour
+                                    .as_deref()
+                                    .and_then(parse_colour)
+                                    .unwrap_or(catppuccin::GREEN),
+                            })
+                            .collect(),
+                    )
                     .focused(app.focus == Focus::Map),
                 area,
             );
+            if app.map_shows_legend {
+                frame.render_widget(legend, area);
+            }
         }
     }
 }
 
-fn world_marker_for_city(city: &City) -> Option<WorldMarker> {
-    let (lat, lon) = city_coords_by_code(&city.code).or_else(|| city_coords_by_name(&city.name))?;
+fn world_marker_for_city(app: &App, city: &City) -> Option<WorldMarker> {
+    let (lat, lon) = city_coords(&city.name, &city.code)?;
+    // a city code that is also a country code (Austin's AUS) would read as the country
+    let label = if country_by_code(&city.code).is_some() {
+        city.name.clone()
+    } else {
+        city.code.clone()
+    };
     Some(WorldMarker {
-        label: city.code.clone(),
+        label,
         lat,
         lon,
+        temp_c: cached_temperature(app, &city.name),
     })
 }
 
+/// the last fetched temperature for a city, if the weather panel has loaded it
+fn cached_temperature(app: &App, city_name: &str) -> Option<i32> {
+    app.weather_service
+        .cached_weather(city_name)
+        .map(|weather| weather.temp_c)
+}
+
 fn world_marker_for_country_code(code: &str) -> Option<WorldMarker> {
     country_by_code(code).map(|country| WorldMarker {
         label: country.code.to_string(),
         lat: country.lat,
         lon: country.lon,
+        temp_c: None,
     })
 }
 
@@ -1026,7 +3374,7 @@ fn configured_world_map_markers(
         .effective_target_city_codes()
         .first()
         .and_then(|code| app.city_by_code(code))
-        .and_then(world_marker_for_city);
+        .and_then(|city| world_marker_for_city(app, city));
     let focal_country = map
         .focal_country_code
         .as_deref()
@@ -1084,18 +3432,18 @@ fn world_map_markers(
         Focus::Map => configured_world_map_markers(app),
         Focus::Weather => (
             app.city_by_code(&app.time_converter.from_city_code)
-                .and_then(world_marker_for_city),
+                .and_then(|city| world_marker_for_city(app, city)),
             app.city_by_code(&app.time_converter.to_city_code)
-                .and_then(world_marker_for_city),
+                .and_then(|city| world_marker_for_city(app, city)),
             "Time",
         ),
     };
 
     if primary.is_none() {
-        primary = world_marker_for_city(&app.config.current_city);
+        primary = world_marker_for_city(app, &app.config.current_city);
     }
     if secondary.is_none() && app.config.effective_map_settings().enabled {
-        secondary = world_marker_for_city(&app.config.home_city);
+        secondary = world_marker_for_city(app, &app.config.home_city);
     }
 
     (primary, secondary, label)
@@ -1104,7 +3452,7 @@ fn world_map_markers(
 /// draw weather panel with current conditions and forecast-style layout (compact view)
 fn draw_weather_panel(frame: &mut Frame, area: Rect, app: &App) {
     let focused = app.focus == Focus::Weather;
-    let block = styled_block("Weather [s:view] [space:city]", focused);
+    let block = styled_block(WEATHER_TITLE, focused);
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
@@ -1128,7 +3476,7 @@ fn draw_weather_detail(frame: &mut Frame, area: Rect, app: &App) {
             let mut lines = vec![];
 
             // row 1: city selector with navigation hint
-            let day_night = if w.is_day { "☀" } else { "☾" };
+            let day_night = day_night(w.is_day);
             lines.push(Line::from(vec![
                 Span::styled(
                     format!(" {} ", city_code),
@@ -1169,7 +3517,7 @@ fn draw_weather_detail(frame: &mut Frame, area: Rect, app: &App) {
                 crate::weather::WeatherIcon::Fog => catppuccin::OVERLAY0,
                 crate::weather::WeatherIcon::Unknown => catppuccin::SUBTEXT0,
             };
-            lines.push(Line::from(vec![
+            let mut temperature = Line::from(vec![
                 Span::styled(
                     format!(" {} ", icon),
                     Style::default().fg(icon_color).add_modifier(Modifier::BOLD),
@@ -1184,26 +3532,18 @@ fn draw_weather_detail(frame: &mut Frame, area: Rect, app: &App) {
                     format!("feels {}", w.feels_like_string()),
                     Theme::text_dim(),
                 ),
-            ]));
+            ]);
+            // the coming day's temperatures, hour by hour, in what's left of the row
+            let room = (area.width as usize).saturating_sub(temperature.width() + 2);
+            let trend = sparkline(&w.hourly_temps, room);
+            if trend.chars().count() >= MIN_SPARKLINE_WIDTH {
+                temperature.push_span(Span::raw("  "));
+                temperature.push_span(Span::styled(trend, Style::default().fg(catppuccin::PEACH)));
+            }
+            lines.push(temperature);
 
             // row 3: condition description with emoji
-            let condition_emoji = match w.icon {
-                crate::weather::WeatherIcon::Sunny => {
-                    if w.is_day {
-                        "☀️"
-                    } else {
-                        "🌙"
-                    }
-                }
-                crate::weather::WeatherIcon::PartlyCloudy => "⛅",
-                crate::weather::WeatherIcon::Cloudy => "☁️",
-                crate::weather::WeatherIcon::Rain | crate::weather::WeatherIcon::HeavyRain => "🌧️",
-                crate::weather::WeatherIcon::Drizzle => "🌦️",
-                crate::weather::WeatherIcon::Snow => "❄️",
-                crate::weather::WeatherIcon::Thunderstorm => "⛈️",
-                crate::weather::WeatherIcon::Fog => "🌫️",
-                crate::weather::WeatherIcon::Unknown => "❓",
-            };
+            let condition_emoji = icon_set().weather_label(w.icon, w.is_day);
             lines.push(Line::from(vec![
                 Span::styled(format!("    {}", condition_emoji), Style::default()),
                 Span::styled(&w.description, Style::default().fg(catppuccin::SUBTEXT1)),
@@ -1222,13 +3562,13 @@ fn draw_weather_detail(frame: &mut Frame, area: Rect, app: &App) {
                 _ => "○",
             };
             let wind_strength = if w.wind_kmph >= 50 {
-                ("💨", catppuccin::RED, " STRONG")
+                (glyph(Glyph::StrongWind), catppuccin::RED, " STRONG")
             } else if w.wind_kmph >= 30 {
-                ("💨", catppuccin::PEACH, " gusty")
+                (glyph(Glyph::StrongWind), catppuccin::PEACH, " gusty")
             } else if w.wind_kmph >= 15 {
-                ("🌬️", catppuccin::SAPPHIRE, "")
+                (glyph(Glyph::Breeze), catppuccin::SAPPHIRE, "")
             } else {
-                ("🍃", catppuccin::GREEN, " calm")
+                (glyph(Glyph::Calm), catppuccin::GREEN, " calm")
             };
             lines.push(Line::from(vec![
                 Span::styled(
@@ -1250,7 +3590,10 @@ fn draw_weather_detail(frame: &mut Frame, area: Rect, app: &App) {
 
             // row 5: humidity
             lines.push(Line::from(vec![
-                Span::styled("  💧 ", Style::default().fg(catppuccin::SAPPHIRE)),
+                Span::styled(
+                    format!("  {} ", glyph(Glyph::Humidity)),
+                    Style::default().fg(catppuccin::SAPPHIRE),
+                ),
                 Span::styled(format!("{}% humidity", w.humidity), Theme::text()),
             ]));
 
@@ -1262,19 +3605,19 @@ fn draw_weather_detail(frame: &mut Frame, area: Rect, app: &App) {
                 )]));
                 for day in w.forecast.iter().take(3) {
                     let day_icon = day.icon.icon(true);
-                    let wind_indicator = if day.wind_max >= 40 {
-                        "💨"
-                    } else if day.wind_max >= 20 {
-                        "🌬️"
-                    } else {
-                        "🍃"
-                    };
-                    // format date as short (e.g., "Dec 10")
-                    let short_date = if day.date.len() >= 10 {
-                        format!("{}-{}", &day.date[5..7], &day.date[8..10])
-                    } else {
-                        day.date.clone()
-                    };
+                    let wind_indicator = day.wind_max.map_or("", |wind| {
+                        glyph(if wind >= 40 {
+                            Glyph::StrongWind
+                        } else if wind >= 20 {
+                            Glyph::Breeze
+                        } else {
+                            Glyph::Calm
+                        })
+                    });
+                    // format date as short (e.g., "10 Dec")
+                    let short_date = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
+                        .map(|date| app.config.display.date_format.short(date))
+                        .unwrap_or_else(|_| day.date.clone());
                     lines.push(Line::from(vec![
                         Span::styled(
                             format!("  {} ", day_icon),
@@ -1285,11 +3628,15 @@ fn draw_weather_detail(frame: &mut Frame, area: Rect, app: &App) {
                             Style::default().fg(catppuccin::SUBTEXT0),
                         ),
                         Span::styled(
-                            format!("{:>2}/{:<2}°C ", day.temp_max, day.temp_min),
+                            format!(
+                                "{:>2}/{:<2}°C ",
+                                reading(day.temp_max),
+                                reading(day.temp_min)
+                            ),
                             Style::default().fg(catppuccin::GREEN),
                         ),
                         Span::styled(
-                            format!("{}{:>2}km/h", wind_indicator, day.wind_max),
+                            format!("{}{:>2}km/h", wind_indicator, reading(day.wind_max)),
                             Style::default().fg(catppuccin::SAPPHIRE),
                         ),
                     ]));
@@ -1377,6 +3724,15 @@ fn draw_weather_detail(frame: &mut Frame, area: Rect, app: &App) {
     }
 }
 
+/// one row of gauge beside the expanded weather art, its label over the bar
+fn draw_weather_gauge(frame: &mut Frame, area: Rect, ratio: f64, label: String, colour: Color) {
+    let gauge = Gauge::default()
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(Span::styled(label, Style::default().fg(catppuccin::TEXT)))
+        .gauge_style(Style::default().fg(colour).bg(catppuccin::SURFACE1));
+    frame.render_widget(gauge, area);
+}
+
 /// get ASCII art for weather condition (wttr-style, 5 lines)
 #[allow(dead_code)]
 fn weather_ascii_art(icon: crate::weather::WeatherIcon, is_day: bool) -> [&'static str; 5] {
@@ -1538,17 +3894,17 @@ fn pad_display_right(content: &str, width: usize) -> String {
     format!("{}{}", rendered, " ".repeat(width.saturating_sub(used)))
 }
 
-fn text_cell(content: &str) -> String {
-    pad_display_right(content, WEATHER_GRID_CELL_WIDTH)
+fn text_cell(content: &str, width: usize) -> String {
+    pad_display_right(content, width)
 }
 
-fn weather_desc_cell(label: &str) -> String {
-    text_cell(label)
+fn weather_desc_cell(label: &str, width: usize) -> String {
+    text_cell(label, width)
 }
 
-fn grid_rule(left: &str, mid: &str, right: &str, fill: char) -> String {
-    let cell = fill.to_string().repeat(WEATHER_GRID_CELL_WIDTH);
-    let middle = std::iter::repeat_n(cell.as_str(), WEATHER_GRID_COLUMNS).collect::<Vec<_>>();
+fn grid_rule(grid: &WeatherGridLayout, left: &str, mid: &str, right: &str, fill: char) -> String {
+    let cell = fill.to_string().repeat(grid.cell_width);
+    let middle = std::iter::repeat_n(cell.as_str(), grid.periods.len()).collect::<Vec<_>>();
     format!("{left}{}{right}", middle.join(mid))
 }
 
@@ -1564,13 +3920,16 @@ fn push_grid_line(lines: &mut Vec<Line<'static>>, padding: usize, spans: Vec<Spa
 /// draw weather panel with wttr-style 3-day grid
 fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
     let focused = app.focus == Focus::Weather;
-    let block = styled_block("Weather [s:view] [space:city]", focused);
+    let block = styled_block(WEATHER_TITLE, focused);
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     if !weather_grid_can_fit(area) {
         return;
     }
+    let Some(grid) = weather_grid_layout(inner.width) else {
+        return;
+    };
 
     let city_name = app.get_weather_city_name();
     let city_code = app.get_weather_city_code();
@@ -1581,7 +3940,8 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
         Some(w) => {
             let mut lines: Vec<Line> = vec![];
             let border = Style::default().fg(catppuccin::SURFACE2);
-            let grid_width = WEATHER_GRID_WIDTH;
+            let grid_width = grid.width() as u16;
+            let cell_width = grid.cell_width;
             let is_stale_or_offline = w.is_stale() || app.weather_error.is_some();
             let grid_padding = 0;
 
@@ -1617,7 +3977,13 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
                 ),
             ]));
 
-            // row 2: art + wind
+            // rows 2-4: art beside wind, UV and humidity gauges, drawn over it below
+            for art in &current_art[2..] {
+                lines.push(Line::from(Span::styled(
+                    *art,
+                    Style::default().fg(catppuccin::YELLOW),
+                )));
+            }
             let wind_color = if w.wind_kmph >= 40 {
                 catppuccin::RED
             } else if w.wind_kmph >= 25 {
@@ -1625,53 +3991,51 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
             } else {
                 catppuccin::GREEN
             };
-            lines.push(Line::from(vec![
-                Span::styled(current_art[2], Style::default().fg(catppuccin::YELLOW)),
-                Span::styled(
-                    format!("  {} {} km/h", arrow, w.wind_kmph),
-                    Style::default().fg(wind_color),
+            let uv_color = match w.uv_band() {
+                Some("low") => catppuccin::GREEN,
+                Some("moderate") => catppuccin::YELLOW,
+                Some("high") => catppuccin::PEACH,
+                Some("very high") => catppuccin::RED,
+                Some(_) => catppuccin::MAUVE,
+                None => catppuccin::OVERLAY0,
+            };
+            let humidity_color = if w.humidity < 30 {
+                catppuccin::PEACH
+            } else if w.humidity >= 85 {
+                catppuccin::BLUE
+            } else {
+                catppuccin::SAPPHIRE
+            };
+            let gauges = [
+                (
+                    w.wind_kmph as f64 / 100.0,
+                    format!("{} {} km/h", arrow, w.wind_kmph),
+                    wind_color,
                 ),
-            ]));
-
-            // row 3: art + visibility
-            lines.push(Line::from(vec![
-                Span::styled(current_art[3], Style::default().fg(catppuccin::YELLOW)),
-                Span::styled("  10 km", Style::default().fg(catppuccin::SUBTEXT0)),
-            ]));
-
-            // row 4: art + humidity
-            lines.push(Line::from(vec![
-                Span::styled(current_art[4], Style::default().fg(catppuccin::YELLOW)),
-                Span::styled(
-                    format!("  {}% humidity", w.humidity),
-                    Style::default().fg(catppuccin::SUBTEXT0),
+                (
+                    w.uv_index.unwrap_or(0.0) / 11.0,
+                    match (w.uv_index, w.uv_band()) {
+                        (Some(uv), Some(band)) => format!("UV {:.0} {}", uv, band),
+                        _ => "UV --".to_string(),
+                    },
+                    uv_color,
                 ),
-            ]));
+                (
+                    w.humidity as f64 / 100.0,
+                    format!("{}% humidity", w.humidity),
+                    humidity_color,
+                ),
+            ];
 
             // blank line before grid
             lines.push(Line::from(""));
 
             // wttr-style grid with day headers
-            use crate::weather::TimeOfDay;
-            let period_order = [
-                TimeOfDay::Morning,
-                TimeOfDay::Noon,
-                TimeOfDay::Evening,
-                TimeOfDay::Night,
-            ];
-
             for day in w.forecast.iter().take(3) {
                 // format day header (centred)
-                let day_header = if day.date.len() >= 10 {
-                    let month = &day.date[5..7];
-                    let dom = &day.date[8..10];
-                    let day_name = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
-                        .map(|date| date.format("%a").to_string())
-                        .unwrap_or_else(|_| "???".to_string());
-                    format!("{} {} {}", day_name, dom, month_name(month))
-                } else {
-                    day.date.clone()
-                };
+                let day_header = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
+                    .map(|date| app.config.display.date_format.with_weekday(date))
+                    .unwrap_or_else(|_| day.date.clone());
 
                 // day header row (centred above columns)
                 push_grid_line(
@@ -1688,62 +4052,40 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
                 );
 
                 // column headers
-                push_grid_line(
-                    &mut lines,
-                    grid_padding,
-                    vec![
-                        Span::styled("│", border),
-                        Span::styled(
-                            center_pad("Morning", WEATHER_GRID_CELL_WIDTH),
-                            Style::default()
-                                .fg(catppuccin::PEACH)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::styled("│", border),
-                        Span::styled(
-                            center_pad("Noon", WEATHER_GRID_CELL_WIDTH),
-                            Style::default()
-                                .fg(catppuccin::YELLOW)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::styled("│", border),
-                        Span::styled(
-                            center_pad("Evening", WEATHER_GRID_CELL_WIDTH),
-                            Style::default()
-                                .fg(catppuccin::MAUVE)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::styled("│", border),
-                        Span::styled(
-                            center_pad("Night", WEATHER_GRID_CELL_WIDTH),
-                            Style::default()
-                                .fg(catppuccin::LAVENDER)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::styled("│", border),
-                    ],
-                );
+                let mut header_spans = vec![Span::styled("│", border)];
+                for period in grid.periods {
+                    let (label, colour) = period_header(*period, grid.is_narrow());
+                    header_spans.push(Span::styled(
+                        center_pad(label, cell_width),
+                        Style::default().fg(colour).add_modifier(Modifier::BOLD),
+                    ));
+                    header_spans.push(Span::styled("│", border));
+                }
+                push_grid_line(&mut lines, grid_padding, header_spans);
 
                 // separator
                 push_grid_line(
                     &mut lines,
                     grid_padding,
-                    vec![Span::styled(grid_rule("├", "┼", "┤", '─'), border)],
+                    vec![Span::styled(grid_rule(&grid, "├", "┼", "┤", '─'), border)],
                 );
 
                 // content row: description
                 let mut desc_spans = vec![Span::styled("│", border)];
-                for target in &period_order {
+                for target in grid.periods {
                     if let Some(p) = day.periods.iter().find(|p| {
                         std::mem::discriminant(&p.period) == std::mem::discriminant(target)
                     }) {
                         let desc = wttr_desc(p.icon);
                         desc_spans.push(Span::styled(
-                            weather_desc_cell(desc),
+                            weather_desc_cell(desc, cell_width),
                             Style::default().fg(catppuccin::TEXT),
                         ));
                     } else {
-                        desc_spans.push(Span::styled(text_cell("--"), Theme::text_muted()));
+                        desc_spans.push(Span::styled(
+                            text_cell("--", cell_width),
+                            Theme::text_muted(),
+                        ));
                     }
                     desc_spans.push(Span::styled("│", border));
                 }
@@ -1751,25 +4093,19 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
 
                 // content row: temp
                 let mut temp_spans = vec![Span::styled("│", border)];
-                for target in &period_order {
+                for target in grid.periods {
                     if let Some(p) = day.periods.iter().find(|p| {
                         std::mem::discriminant(&p.period) == std::mem::discriminant(target)
                     }) {
-                        let temp_color = if p.temp >= 25 {
-                            catppuccin::RED
-                        } else if p.temp >= 18 {
-                            catppuccin::YELLOW
-                        } else if p.temp >= 10 {
-                            catppuccin::GREEN
-                        } else {
-                            catppuccin::SAPPHIRE
-                        };
                         temp_spans.push(Span::styled(
-                            text_cell(&format!("{} °C", p.temp)),
-                            Style::default().fg(temp_color),
+                            text_cell(&format!("{} °C", p.temp), cell_width),
+                            Theme::temperature(p.temp),
                         ));
                     } else {
-                        temp_spans.push(Span::styled(text_cell("--"), Theme::text_muted()));
+                        temp_spans.push(Span::styled(
+                            text_cell("--", cell_width),
+                            Theme::text_muted(),
+                        ));
                     }
                     temp_spans.push(Span::styled("│", border));
                 }
@@ -1777,7 +4113,7 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
 
                 // content row: wind
                 let mut wind_spans = vec![Span::styled("│", border)];
-                for target in &period_order {
+                for target in grid.periods {
                     if let Some(p) = day.periods.iter().find(|p| {
                         std::mem::discriminant(&p.period) == std::mem::discriminant(target)
                     }) {
@@ -1790,18 +4126,21 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
                         };
                         let wind_arrow = wind_arrow(&p.wind_dir);
                         wind_spans.push(Span::styled(
-                            text_cell(&format!("{} {} km/h", wind_arrow, p.wind)),
+                            text_cell(&format!("{} {} km/h", wind_arrow, p.wind), cell_width),
                             Style::default().fg(wind_color),
                         ));
                     } else {
-                        wind_spans.push(Span::styled(text_cell("--"), Theme::text_muted()));
+                        wind_spans.push(Span::styled(
+                            text_cell("--", cell_width),
+                            Theme::text_muted(),
+                        ));
                     }
                     wind_spans.push(Span::styled("│", border));
                 }
                 push_grid_line(&mut lines, grid_padding, wind_spans);
 
                 // bottom of day section
-                let bottom = Span::styled(grid_rule("└", "┴", "┘", '─'), border);
+                let bottom = Span::styled(grid_rule(&grid, "└", "┴", "┘", '─'), border);
                 push_grid_line(&mut lines, grid_padding, vec![bottom]);
             }
 
@@ -1825,6 +4164,22 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
             let content_area =
                 Rect::new(inner.x, inner.y, grid_width.min(inner.width), inner.height);
             frame.render_widget(para, content_area);
+
+            let width = content_area
+                .width
+                .saturating_sub(WEATHER_ART_WIDTH)
+                .min(WEATHER_GAUGE_WIDTH);
+            for (row, (ratio, label, colour)) in (2..).zip(gauges) {
+                if row < content_area.height {
+                    let area = Rect::new(
+                        content_area.x + WEATHER_ART_WIDTH,
+                        content_area.y + row,
+                        width,
+                        1,
+                    );
+                    draw_weather_gauge(frame, area, ratio, label, colour);
+                }
+            }
         }
         None => {
             // show loading or error state
@@ -1860,7 +4215,7 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
             let content_area = Rect::new(
                 inner.x,
                 inner.y,
-                WEATHER_GRID_WIDTH.min(inner.width),
+                (grid.width() as u16).min(inner.width),
                 inner.height,
             );
             frame.render_widget(para, content_area);
@@ -1868,100 +4223,60 @@ fn draw_weather_panel_expanded(frame: &mut Frame, area: Rect, app: &App) {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::app::App;
-    use crate::config::Config;
-
-    #[test]
-    fn weather_desc_cell_respects_display_width() {
-        let cell = weather_desc_cell("Pt cld");
-
-        assert_eq!(
-            UnicodeWidthStr::width(cell.as_str()),
-            WEATHER_GRID_CELL_WIDTH
-        );
-        assert!(cell.starts_with("Pt cld"));
-    }
-
-    #[test]
-    fn weather_grid_fit_uses_inner_panel_width() {
-        assert!(weather_grid_can_fit(Rect::new(
-            0,
-            0,
-            WEATHER_GRID_WIDTH + 2,
-            WEATHER_EXPANDED_MIN_HEIGHT + 2,
-        )));
-        assert!(!weather_grid_can_fit(Rect::new(
-            0,
-            0,
-            WEATHER_GRID_WIDTH + 1,
-            WEATHER_EXPANDED_MIN_HEIGHT + 2,
-        )));
-    }
-
-    #[test]
-    fn time_focus_world_map_uses_country_markers() {
-        let mut app = App::new(Config::default());
-        app.time_converter.from_city_code = "LAX".to_string();
-        app.time_converter.to_city_code = "BOS".to_string();
-
-        let (primary, secondary, label) = world_map_markers(&app, Focus::TimeConvert);
-
-        assert_eq!(label, "Time");
-        assert_eq!(
-            primary.as_ref().map(|marker| marker.label.as_str()),
-            Some("USA")
-        );
-        assert_eq!(
-            secondary.as_ref().map(|marker| marker.label.as_str()),
-            Some("USA")
-        );
-    }
-
-    #[test]
-    fn time_focus_world_map_distinguishes_countries() {
-        let mut app = App::new(Config::default());
-        app.time_converter.from_city_code = "WLG".to_string();
-        app.time_converter.to_city_code = "TYO".to_string();
-
-        let (primary, secondary, _) = world_map_markers(&app, Focus::TimeConvert);
+/// sunrise and sunset after a city row, shown while the time panel is focused
+/// columns a big clock takes: the digits, then a column for any AM/PM
+fn big_clock_width(time: &str) -> usize {
+    let digits = time.trim_end_matches(|c: char| !c.is_ascii_digit());
+    bigdigits::width(digits) + if digits.len() < time.len() { 4 } else { 1 }
+}
 
-        assert_eq!(
-            primary.as_ref().map(|marker| marker.label.as_str()),
-            Some("NZL")
-        );
-        assert_eq!(
-            secondary.as_ref().map(|marker| marker.label.as_str()),
-            Some("JPN")
-        );
-    }
+/// `time` in block digits with each row continued by its labels; AM/PM sits after the
+/// digits on the bottom row
+fn big_clock_lines(
+    time: &str,
+    style: Style,
+    labels: [Vec<Span<'static>>; bigdigits::HEIGHT],
+) -> Vec<Line<'static>> {
+    let digits = time.trim_end_matches(|c: char| !c.is_ascii_digit());
+    let suffix = time[digits.len()..].trim();
+    let suffix_width = big_clock_width(time) - bigdigits::width(digits);
+    bigdigits::render(digits)
+        .into_iter()
+        .zip(labels)
+        .enumerate()
+        .map(|(row, (big, labels))| {
+            let suffix = if row + 1 == bigdigits::HEIGHT {
+                suffix
+            } else {
+                ""
+            };
+            let mut spans = vec![
+                Span::styled(big, style),
+                Span::styled(format!(" {:<1$}", suffix, suffix_width - 1), style),
+            ];
+            spans.extend(labels);
+            Line::from(spans)
+        })
+        .collect()
 }
 
-/// convert month number to short name
-fn month_name(month: &str) -> &'static str {
-    match month {
-        "01" => "Jan",
-        "02" => "Feb",
-        "03" => "Mar",
-        "04" => "Apr",
-        "05" => "May",
-        "06" => "Jun",
-        "07" => "Jul",
-        "08" => "Aug",
-        "09" => "Sep",
-        "10" => "Oct",
-        "11" => "Nov",
-        "12" => "Dec",
-        _ => "???",
+fn sun_times_span(ct: &CityTime, app: &App, focused: bool) -> Option<Span<'static>> {
+    if !focused {
+        return None;
     }
+    ct.sun_summary(app.config.display.use_24_hour)
+        .map(|summary| {
+            Span::styled(
+                format!("  {}", summary),
+                Style::default().fg(catppuccin::OVERLAY0),
+            )
+        })
 }
 
 /// draw time panel - simplified NZ → overseas city
 fn draw_time_panel(frame: &mut Frame, area: Rect, app: &App) {
     let focused = app.focus == Focus::TimeConvert;
-    let block = styled_block("Time [space:city] [s:swap] [e:edit/Esc]", focused);
+    let block = styled_block(TIME_TITLE, focused);
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
@@ -1971,37 +4286,78 @@ fn draw_time_panel(frame: &mut Frame, area: Rect, app: &App) {
 
     let mut lines = vec![];
 
-    // NZ city (anchor) - always Wellington
-    if let Some(ct) = &app.current_city_time {
-        let time_str = ct.time_string(true, false);
-        let day = if ct.is_daytime() { "☀" } else { "☾" };
+    // from city - the anchor unless cycled with 'f'
+    let from_time = app
+        .from_city_time
+        .as_ref()
+        .or(app.current_city_time.as_ref());
+    // block-digit clocks need six rows and room for the converter below; seconds are
+    // left off when the panel is too narrow for them
+    let big_fits = |seconds: bool| {
+        from_time.is_none_or(|ct| {
+            let time_str = ct.time_string(app.config.display.use_24_hour, seconds);
+            usize::from(inner.width) >= big_clock_width(&time_str) + BIG_CLOCK_LABEL_WIDTH
+        })
+    };
+    let big_seconds =
+        if !app.config.display.big_clock || usize::from(inner.height) < 2 * bigdigits::HEIGHT + 3 {
+            None
+        } else if app.config.display.show_seconds && big_fits(true) {
+            Some(true)
+        } else {
+            big_fits(false).then_some(false)
+        };
+    let big = big_seconds.is_some();
+    if let Some(ct) = from_time {
+        let time_str = ct.time_string(
+            app.config.display.use_24_hour,
+            app.config.display.show_seconds,
+        );
+        let day = day_night(ct.is_daytime());
         let day_color = if ct.is_daytime() {
             catppuccin::YELLOW
         } else {
             catppuccin::LAVENDER
         };
 
-        lines.push(Line::from(vec![
-            Span::styled("▸ ", Style::default().fg(catppuccin::GREEN)),
-            Span::styled(
-                format!("{:<3}", ct.city_code),
-                Style::default().fg(catppuccin::SAPPHIRE),
-            ),
-            Span::styled(" ", Style::default()),
-            Span::styled(
-                format!("{:<12}", ct.city_name),
-                Style::default()
-                    .fg(catppuccin::PEACH)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                format!(" {} ", time_str),
-                Style::default()
-                    .fg(catppuccin::GREEN)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(day, Style::default().fg(day_color)),
-        ]));
+        let marker = Span::styled("▸ ", Style::default().fg(catppuccin::GREEN));
+        let code = Span::styled(
+            format!("{:<3}", ct.city_code),
+            Style::default().fg(catppuccin::SAPPHIRE),
+        );
+        let name_style = Style::default()
+            .fg(catppuccin::PEACH)
+            .add_modifier(Modifier::BOLD);
+        let time_style = Style::default()
+            .fg(catppuccin::GREEN)
+            .add_modifier(Modifier::BOLD);
+        if let Some(seconds) = big_seconds {
+            lines.extend(big_clock_lines(
+                &ct.time_string(app.config.display.use_24_hour, seconds),
+                time_style,
+                [
+                    vec![
+                        marker,
+                        code,
+                        Span::raw(" "),
+                        Span::styled(ct.city_name.clone(), name_style),
+                    ],
+                    vec![Span::styled(day, Style::default().fg(day_color))],
+                    sun_times_span(ct, app, focused).into_iter().collect(),
+                ],
+            ));
+        } else {
+            let mut row = vec![
+                marker,
+                code,
+                Span::styled(" ", Style::default()),
+                Span::styled(format!("{:<12}", ct.city_name), name_style),
+                Span::styled(format!(" {} ", time_str), time_style),
+                Span::styled(day, Style::default().fg(day_color)),
+            ];
+            row.extend(sun_times_span(ct, app, focused));
+            lines.push(Line::from(row));
+        }
     }
 
     // overseas city (cycles with spacebar - uses converter's to_city)
@@ -2013,43 +4369,225 @@ fn draw_time_panel(frame: &mut Frame, area: Rect, app: &App) {
         .or(app.home_city_time.as_ref());
 
     if let Some(ht) = overseas_time {
-        let time_str = ht.time_string(true, false);
-        let day = if ht.is_daytime() { "☀" } else { "☾" };
+        let time_str = ht.time_string(
+            app.config.display.use_24_hour,
+            app.config.display.show_seconds,
+        );
+        let day = day_night(ht.is_daytime());
         let day_color = if ht.is_daytime() {
             catppuccin::YELLOW
         } else {
             catppuccin::LAVENDER
         };
 
-        let delta = if let Some(ct) = &app.current_city_time {
+        let delta = if let Some(ct) = from_time {
             format_time_delta(ct, ht)
         } else {
             String::new()
         };
 
+        let code = Span::styled(
+            format!("{:<3}", ht.city_code),
+            Style::default().fg(catppuccin::OVERLAY1),
+        );
+        let name_style = Style::default().fg(catppuccin::SUBTEXT0);
+        let time_style = Style::default().fg(catppuccin::TEXT);
+        let day = Span::styled(day, Style::default().fg(day_color));
+        let delta = Span::styled(
+            format!(" {}", delta),
+            Style::default().fg(catppuccin::OVERLAY1),
+        );
+        if let Some(seconds) = big_seconds {
+            lines.extend(big_clock_lines(
+                &ht.time_string(app.config.display.use_24_hour, seconds),
+                time_style,
+                [
+                    vec![
+                        Span::raw("  "),
+                        code,
+                        Span::raw(" "),
+                        Span::styled(ht.city_name.clone(), name_style),
+                    ],
+                    vec![day, delta],
+                    sun_times_span(ht, app, focused).into_iter().collect(),
+                ],
+            ));
+        } else {
+            let mut row = vec![
+                Span::styled("  ", Style::default()),
+                code,
+                Span::styled(" ", Style::default()),
+                Span::styled(format!("{:<12}", ht.city_name), name_style),
+                Span::styled(format!(" {} ", time_str), time_style),
+                day,
+                delta,
+            ];
+            row.extend(sun_times_span(ht, app, focused));
+            lines.push(Line::from(row));
+        }
+    }
+
+    // date, ISO week and day-of-year; highlighted when the cities straddle midnight
+    let date_details =
+        app.config.display.show_date_details && from_time.is_some() && overseas_time.is_some();
+    if date_details && let (Some(ct), Some(ht)) = (from_time, overseas_time) {
+        let display = &app.config.display;
+        let date_color = if ct.datetime.date_naive() == ht.datetime.date_naive() {
+            catppuccin::OVERLAY1
+        } else {
+            catppuccin::PEACH
+        };
         lines.push(Line::from(vec![
-            Span::styled("  ", Style::default()),
             Span::styled(
-                format!("{:<3}", ht.city_code),
+                format!("  {} ", ct.city_code),
+                Style::default().fg(catppuccin::SAPPHIRE),
+            ),
+            Span::styled(
+                ct.date_details(display.date_format, display.week_starts),
+                Style::default().fg(date_color),
+            ),
+            Span::styled(
+                format!("  {} ", ht.city_code),
                 Style::default().fg(catppuccin::OVERLAY1),
             ),
-            Span::styled(" ", Style::default()),
             Span::styled(
-                format!("{:<12}", ht.city_name),
-                Style::default().fg(catppuccin::SUBTEXT0),
+                ht.date_details(display.date_format, display.week_starts),
+                Style::default().fg(date_color),
             ),
+        ]));
+    }
+
+    // countdown to the next scheduled event
+    let next_event = app.next_event();
+    if let Some(event) = &next_event {
+        let clock = clock_format(app.config.display.use_24_hour, false);
+        let countdown_style = if event.is_due_for_reminder() {
+            Style::default()
+                .fg(catppuccin::RED)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(catppuccin::PEACH)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", glyph(Glyph::Countdown)), countdown_style),
             Span::styled(
-                format!(" {} ", time_str),
+                truncate_display(&event.title, 16),
                 Style::default().fg(catppuccin::TEXT),
             ),
-            Span::styled(day, Style::default().fg(day_color)),
             Span::styled(
-                format!(" {}", delta),
+                format!(
+                    " {} {} {} ",
+                    app.config
+                        .display
+                        .date_format
+                        .with_weekday(event.local.date()),
+                    event.local.format(clock),
+                    event.city_code
+                ),
+                Style::default().fg(catppuccin::OVERLAY1),
+            ),
+            Span::styled(
+                format!("in {}", format_countdown(event.remaining)),
+                countdown_style,
+            ),
+        ]));
+    }
+
+    // pending alarms, soonest first
+    let alarms = app.pending_alarms();
+    if !alarms.is_empty() {
+        let clock = clock_format(app.config.display.use_24_hour, false);
+        let mut spans = vec![Span::styled(
+            glyph(Glyph::Alarm),
+            Style::default().fg(catppuccin::YELLOW),
+        )];
+        for alarm in alarms.iter().take(3) {
+            spans.push(Span::styled(
+                format!(" {} {}", alarm.local.format(clock), alarm.alarm.city_code),
+                Style::default().fg(catppuccin::TEXT),
+            ));
+            spans.push(Span::styled(
+                format!(" in {}", format_countdown(alarm.remaining)),
+                Style::default().fg(catppuccin::OVERLAY1),
+            ));
+        }
+        if alarms.len() > 3 {
+            spans.push(Span::styled(
+                format!(" +{}", alarms.len() - 3),
+                Style::default().fg(catppuccin::OVERLAY1),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    // NZ public holiday today or in the coming week
+    let holiday = app.upcoming_nz_holiday();
+    if let Some((day, holiday)) = &holiday {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{}: ", day),
                 Style::default().fg(catppuccin::OVERLAY1),
             ),
+            Span::styled(
+                format!("{} {}", holiday.name, glyph(Glyph::Holiday)),
+                Style::default().fg(catppuccin::MAUVE),
+            ),
         ]));
     }
 
+    // working-hours bars, aligned on the from city's day so overlap lines up
+    let broadcast_rows = if app.broadcast_expanded || app.is_scrubbing() {
+        app.broadcast_rows()
+    } else {
+        Vec::new()
+    };
+    let call_windows = if focused {
+        app.call_windows()
+    } else {
+        Vec::new()
+    };
+    let timestamp_rows = app.timestamp_rows();
+    let timestamp_height = if app.timestamp.is_some() {
+        1 + timestamp_rows.len()
+    } else {
+        0
+    };
+    if inner.height as usize
+        >= 9 + usize::from(date_details)
+            + if big { 2 * (bigdigits::HEIGHT - 1) } else { 0 }
+            + usize::from(next_event.is_some())
+            + usize::from(!alarms.is_empty())
+            + usize::from(holiday.is_some())
+            + usize::from(
+                app.config
+                    .note_for(&app.time_converter.to_city_code)
+                    .is_some(),
+            )
+            + timestamp_height
+            + broadcast_rows.len()
+            + usize::from(focused && app.flight_estimate().is_some())
+            + call_windows.len()
+        && inner.width >= 30
+        && let Some(ct) = from_time
+    {
+        let settings = app.config.effective_planner_settings();
+        let from_offset = ct.datetime.offset().local_minus_utc() / 60;
+        // the scrub slider moves the highlighted hour with it
+        let now_hour = if app.is_scrubbing() {
+            app.time_converter.input_hour as usize
+        } else {
+            ct.datetime.hour() as usize
+        };
+        for city in std::iter::once(ct).chain(overseas_time) {
+            let delta = city.datetime.offset().local_minus_utc() / 60 - from_offset;
+            lines.push(hour_bar_line(
+                &city.city_code,
+                &day_bar(delta, &settings),
+                now_hour,
+            ));
+        }
+    }
+
     // blank line
     lines.push(Line::from(""));
 
@@ -2058,10 +4596,11 @@ fn draw_time_panel(frame: &mut Frame, area: Rect, app: &App) {
     let from_name = app.get_time_convert_from_name();
     let to_name = app.get_time_convert_to_name();
 
+    let use_24_hour = app.config.display.use_24_hour;
     let input_display = if converter.is_typing() {
-        converter.format_input_display()
+        converter.format_input_display(use_24_hour)
     } else {
-        converter.format_input_time()
+        converter.format_input_time(use_24_hour)
     };
     let result_style = if converter.invalid_input {
         Style::default()
@@ -2089,17 +4628,153 @@ fn draw_time_panel(frame: &mut Frame, area: Rect, app: &App) {
             format!("{} → ", from_name.chars().take(6).collect::<String>()),
             Style::default().fg(catppuccin::SUBTEXT1),
         ),
-        Span::styled(format!("{} ", converter.format_result_time()), result_style),
+        Span::styled(
+            format!("{} ", converter.format_result_time(use_24_hour)),
+            result_style,
+        ),
         Span::styled(
             to_name.chars().take(6).collect::<String>(),
             Style::default().fg(catppuccin::SUBTEXT1),
         ),
     ]));
 
+    if let Some(phrase) = converter.result_phrase() {
+        lines.push(Line::from(Span::styled(
+            format!("   ↳ {}", phrase),
+            Style::default().fg(catppuccin::OVERLAY1),
+        )));
+    }
+
+    // the same input time in every city ('b')
+    for (code, name, hour, minute, day_offset) in &broadcast_rows {
+        let highlight = code.eq_ignore_ascii_case(&converter.to_city_code);
+        let day = match day_offset {
+            0 => String::new(),
+            offset => format!(" {:+}d", offset),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("   {:<3} ", code),
+                Style::default().fg(if highlight {
+                    catppuccin::SAPPHIRE
+                } else {
+                    catppuccin::OVERLAY1
+                }),
+            ),
+            Span::styled(
+                format!("{:<12} ", truncate_display(name, 12)),
+                Style::default().fg(catppuccin::SUBTEXT0),
+            ),
+            Span::styled(
+                format_clock(*hour, *minute, use_24_hour),
+                Style::default().fg(if highlight {
+                    catppuccin::GREEN
+                } else {
+                    catppuccin::TEXT
+                }),
+            ),
+            Span::styled(day, Style::default().fg(catppuccin::PEACH)),
+        ]));
+    }
+
+    // rough non-stop flight for trip planning, departing at the entered time
+    if focused && let Some((distance, duration, arrival)) = app.flight_estimate() {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "   ✈ {} · ~{} · lands {} {}",
+                format_distance_km(distance),
+                format_flight_duration(duration),
+                arrival.format(&format!("%a {}", clock_format(use_24_hour, false))),
+                converter.to_city_code
+            ),
+            Style::default().fg(catppuccin::SAPPHIRE),
+        )));
+    }
+
+    if let Some((date, holiday)) = app.destination_holiday() {
+        let country = app
+            .city_by_code(&converter.to_city_code)
+            .map(|city| city.country.as_str())
+            .unwrap_or(&converter.to_city_code);
+        lines.push(Line::from(Span::styled(
+            format!(
+                "   ⚠ {} — public holiday in {} ({})",
+                app.config.display.date_format.short(date),
+                country,
+                holiday.name
+            ),
+            Style::default().fg(catppuccin::PEACH),
+        )));
+    }
+
+    if let Some(note) = app.config.note_for(&converter.to_city_code) {
+        lines.push(Line::from(vec![
+            Span::styled("   ✎ ", Style::default().fg(catppuccin::YELLOW)),
+            Span::styled(
+                truncate_display(note, (inner.width as usize).saturating_sub(6)),
+                Style::default().fg(catppuccin::SUBTEXT1),
+            ),
+        ]));
+    }
+
+    if let Some(instant) = app.timestamp {
+        lines.push(Line::from(Span::styled(
+            format!(" {} {}", glyph(Glyph::Timestamp), instant.timestamp()),
+            Style::default().fg(catppuccin::SUBTEXT1),
+        )));
+        for (code, local) in &timestamp_rows {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("   {:<4}", code),
+                    Style::default().fg(catppuccin::OVERLAY1),
+                ),
+                Span::styled(local.clone(), Style::default().fg(catppuccin::SAPPHIRE)),
+            ]));
+        }
+    }
+
+    // best upcoming times to call the destination, ranked
+    for window in &call_windows {
+        let clock = clock_format(use_24_hour, false);
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("   {} ", glyph(Glyph::Call)),
+                Style::default().fg(catppuccin::GREEN),
+            ),
+            Span::styled(
+                format!(
+                    "{} {}–{}",
+                    window.mine_start.format("%a"),
+                    window.mine_start.format(clock),
+                    window.mine_end.format(clock)
+                ),
+                Style::default().fg(catppuccin::TEXT),
+            ),
+            Span::styled(
+                format!(
+                    " · {} {}–{}",
+                    converter.to_city_code,
+                    window.their_start.format(clock),
+                    window.their_end.format(clock)
+                ),
+                Style::default().fg(catppuccin::SUBTEXT1),
+            ),
+            Span::styled(
+                format!(" {}", window.quality.label()),
+                Style::default().fg(catppuccin::OVERLAY1),
+            ),
+        ]));
+    }
+
     // hint for controls
     if focused {
+        let hint = if app.is_scrubbing() {
+            " [←→]:±30m [Enter]:keep [Esc]:now"
+        } else {
+            " [0-9]:time [S]:scrub [Esc]:exit"
+        };
         lines.push(Line::from(vec![Span::styled(
-            " [0-9]:time [Esc]:exit",
+            hint,
             Style::default().fg(catppuccin::OVERLAY0),
         )]));
     }
@@ -2108,8 +4783,38 @@ fn draw_time_panel(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(para, inner);
 
     if app.input_mode == InputMode::EditingTime {
-        draw_editing_indicator(frame, area);
+        draw_mode_indicator(frame, area, "editing");
+    } else if app.is_scrubbing() {
+        draw_mode_indicator(
+            frame,
+            area,
+            &format!(
+                "scrubbing {}",
+                format_offset_minutes(app.scrub_offset_minutes)
+            ),
+        );
+    }
+}
+
+/// one city's 24-hour working/awake/asleep bar with the current hour highlighted
+fn hour_bar_line(code: &str, bar: &[HourStatus; 24], now_hour: usize) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        format!("  {:<4}", code),
+        Style::default().fg(catppuccin::OVERLAY1),
+    )];
+    for (hour, status) in bar.iter().enumerate() {
+        let (glyph, colour) = match status {
+            HourStatus::Working => ("█", catppuccin::GREEN),
+            HourStatus::Awake => ("▓", catppuccin::YELLOW),
+            HourStatus::Asleep => ("░", catppuccin::SURFACE2),
+        };
+        let mut style = Style::default().fg(colour);
+        if hour == now_hour {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        spans.push(Span::styled(glyph, style));
     }
+    Line::from(spans)
 }
 
 /// format a city time line with optional marker
@@ -2121,7 +4826,7 @@ fn format_city_time_line(
     _tick: usize,
 ) -> Line<'static> {
     let time_str = ct.time_string(true, false);
-    let day_indicator = if ct.is_daytime() { "☀" } else { "☾" };
+    let day_indicator = day_night(ct.is_daytime());
     let day_color = if ct.is_daytime() {
         catppuccin::YELLOW
     } else {
@@ -2169,7 +4874,7 @@ fn format_city_time_line_with_delta(
     delta: &str,
 ) -> Line<'static> {
     let time_str = ct.time_string(true, false);
-    let day_indicator = if ct.is_daytime() { "☀" } else { "☾" };
+    let day_indicator = day_night(ct.is_daytime());
     let day_color = if ct.is_daytime() {
         catppuccin::YELLOW
     } else {
@@ -2202,24 +4907,15 @@ fn format_city_time_line_with_delta(
     ])
 }
 
-/// format time delta between two cities
+/// format time delta between two cities, to the minute (e.g. "+7h30m ahead")
 fn format_time_delta(from: &CityTime, to: &CityTime) -> String {
-    let diff_hours = to.offset_hours - from.offset_hours;
-    let hours = diff_hours.abs() as i32;
-    let mins = ((diff_hours.abs() - hours as f32) * 60.0) as i32;
-
-    let direction = if diff_hours > 0.0 { "ahead" } else { "behind" };
-
-    if mins == 0 {
-        format!("{:+}h {}", diff_hours as i32, direction)
-    } else {
-        format!(
-            "{:+}h {:02}m {}",
-            hours * diff_hours.signum() as i32,
-            mins,
-            direction
-        )
-    }
+    let diff = to.offset_minutes - from.offset_minutes;
+    let direction = match diff.cmp(&0) {
+        std::cmp::Ordering::Greater => "ahead",
+        std::cmp::Ordering::Less => "behind",
+        std::cmp::Ordering::Equal => return "same time".to_string(),
+    };
+    format!("{} {}", format_offset_minutes(diff), direction)
 }
 
 /// draw compact time converter
@@ -2234,10 +4930,11 @@ fn draw_time_converter_compact(frame: &mut Frame, area: Rect, app: &App) {
     let to_name = app.get_time_convert_to_name();
 
     // show typing indicator if actively entering time
+    let use_24_hour = app.config.display.use_24_hour;
     let input_display = if converter.is_typing() {
-        converter.format_input_display()
+        converter.format_input_display(use_24_hour)
     } else {
-        converter.format_input_time()
+        converter.format_input_time(use_24_hour)
     };
     let result_style = if converter.invalid_input {
         Style::default()
@@ -2270,7 +4967,10 @@ fn draw_time_converter_compact(frame: &mut Frame, area: Rect, app: &App) {
             Style::default().fg(catppuccin::SUBTEXT1),
         ),
         Span::styled(" → ", Style::default().fg(catppuccin::OVERLAY1)),
-        Span::styled(format!("{} ", converter.format_result_time()), result_style),
+        Span::styled(
+            format!("{} ", converter.format_result_time(use_24_hour)),
+            result_style,
+        ),
         Span::styled(
             to_name.chars().take(8).collect::<String>(),
             Style::default().fg(catppuccin::SUBTEXT1),
@@ -2297,14 +4997,14 @@ fn draw_time_converter_compact(frame: &mut Frame, area: Rect, app: &App) {
 
     // editing indicator
     if app.input_mode == InputMode::EditingTime {
-        draw_editing_indicator(frame, area);
+        draw_mode_indicator(frame, area, "editing");
     }
 }
 
 /// draw currency panel with bidirectional conversion
 fn draw_currency_panel(frame: &mut Frame, area: Rect, app: &App) {
     let focused = app.focus == Focus::Currency;
-    let block = styled_block("Currency [space:cycle] [s:swap] [e:edit/Esc]", focused);
+    let block = styled_block(CURRENCY_TITLE, focused);
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
@@ -2347,10 +5047,26 @@ fn draw_currency_detail(frame: &mut Frame, area: Rect, app: &App) {
         "rate unavailable (offline, no cache)".to_string()
     };
 
-    lines.push(Line::from(vec![
+    let mut rate_line = Line::from(vec![
         Span::styled("    ↓ ", Style::default().fg(catppuccin::OVERLAY1)),
         Span::styled(rate_display, Style::default().fg(catppuccin::OVERLAY0)),
-    ]));
+    ]);
+    // the rate across recent fetches, rising or falling to the one shown
+    let room = (area.width as usize).saturating_sub(rate_line.width() + 1);
+    let trend = sparkline(&app.rate_history, room);
+    if trend.chars().count() >= MIN_SPARKLINE_WIDTH {
+        let rising = app.rate_history.last() >= app.rate_history.first();
+        rate_line.push_span(Span::raw(" "));
+        rate_line.push_span(Span::styled(
+            trend,
+            Style::default().fg(if rising {
+                catppuccin::GREEN
+            } else {
+                catppuccin::RED
+            }),
+        ));
+    }
+    lines.push(rate_line);
 
     // to amount and currency
     lines.push(Line::from(vec![
@@ -2421,7 +5137,7 @@ fn draw_currency_detail(frame: &mut Frame, area: Rect, app: &App) {
 
     // editing indicator
     if app.input_mode == InputMode::EditingCurrency {
-        draw_editing_indicator(frame, area);
+        draw_mode_indicator(frame, area, "editing");
     }
 }
 
@@ -2469,8 +5185,9 @@ fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
             let converter = &app.time_converter;
             let from = &converter.from_city_code;
             let to = &converter.to_city_code;
-            let input = converter.format_input_time();
-            let result = converter.format_result_time();
+            let use_24_hour = app.config.display.use_24_hour;
+            let input = converter.format_input_time(use_24_hour);
+            let result = converter.format_result_time(use_24_hour);
             Line::from(vec![
                 Span::styled(" Time: ", Style::default().fg(catppuccin::GREEN)),
                 Span::styled(
@@ -2515,16 +5232,35 @@ fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
         }
     };
 
-    // help hint for right side (margo style)
-    let help_hint = Line::from(vec![Span::styled(
+    // help hint for right side (margo style), after the user's own footer
+    let mut help_spans = Vec::new();
+    if let Some(format) = &app.config.display.footer {
+        help_spans.push(Span::styled(
+            format!("{} ", footer::render(format, |name| app.footer_value(name))),
+            Style::default().fg(catppuccin::SUBTEXT1),
+        ));
+    }
+    if let Some(count) = app.pending_count {
+        help_spans.push(Span::styled(
+            format!("{} ", count),
+            Style::default()
+                .fg(catppuccin::YELLOW)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    help_spans.push(Span::styled(
         "/help ",
         Style::default().fg(catppuccin::OVERLAY0),
-    )]);
+    ));
+    let help_hint = Line::from(help_spans);
 
     // split horizontally
     let cols = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(20), Constraint::Length(10)])
+        .constraints([
+            Constraint::Min(20),
+            Constraint::Length((help_hint.width() as u16).max(10)),
+        ])
         .split(inner);
 
     frame.render_widget(Paragraph::new(left_content), cols[0]);
@@ -2534,8 +5270,8 @@ fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
     );
 }
 
-/// draw editing indicator overlay
-fn draw_editing_indicator(frame: &mut Frame, area: Rect) {
+/// draw a mode indicator (e.g. "editing") in the panel's bottom-right corner
+fn draw_mode_indicator(frame: &mut Frame, area: Rect, label: &str) {
     if area.height < 1 || area.width < 10 {
         return;
     }
@@ -2543,7 +5279,7 @@ fn draw_editing_indicator(frame: &mut Frame, area: Rect) {
     let indicator = Paragraph::new(Line::from(vec![
         Span::styled("▸ ", Style::default().fg(catppuccin::GREEN)),
         Span::styled(
-            "editing",
+            label,
             Style::default()
                 .fg(catppuccin::GREEN)
                 .add_modifier(Modifier::BOLD),
@@ -2560,3 +5296,563 @@ fn draw_editing_indicator(frame: &mut Frame, area: Rect) {
 
     frame.render_widget(indicator, indicator_area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::App;
+    use crate::config::Config;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn weather_desc_cell_respects_display_width() {
+        let cell = weather_desc_cell("Pt cld", WEATHER_GRID_CELL_WIDTH);
+
+        assert_eq!(
+            UnicodeWidthStr::width(cell.as_str()),
+            WEATHER_GRID_CELL_WIDTH
+        );
+        assert!(cell.starts_with("Pt cld"));
+    }
+
+    #[test]
+    fn weather_grid_fit_uses_inner_panel_width() {
+        assert!(weather_grid_can_fit(Rect::new(
+            0,
+            0,
+            WEATHER_GRID_NARROW_WIDTH + 2,
+            WEATHER_EXPANDED_MIN_HEIGHT + 2,
+        )));
+        assert!(!weather_grid_can_fit(Rect::new(
+            0,
+            0,
+            WEATHER_GRID_NARROW_WIDTH + 1,
+            WEATHER_EXPANDED_MIN_HEIGHT + 2,
+        )));
+    }
+
+    #[test]
+    fn turned_off_panels_give_their_space_to_the_rest() {
+        let mut config = Config::default();
+        config.panel = Some(crate::config::PanelConfig {
+            weather: false,
+            time: false,
+            currency: false,
+            ..crate::config::PanelConfig::default()
+        });
+        config.map.get_or_insert_default().enabled = true;
+        let mut app = App::new(config);
+        app.show_splash = false;
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        let mut map = None;
+        terminal.draw(|frame| map = draw(frame, &app).map).unwrap();
+        assert_eq!(map.map(|area| area.width), Some(120));
+
+        let panel = app.config.panel.get_or_insert_default();
+        panel.weather = true;
+        let mut map = None;
+        terminal.draw(|frame| map = draw(frame, &app).map).unwrap();
+        let map = map.expect("map shown");
+        assert!(map.width < 60);
+    }
+
+    #[test]
+    fn tabs_switch_the_right_hand_page_and_remember_it() {
+        crate::config::with_temp_config_dir_for_test(|| {
+            let mut config = Config::default();
+            config.map.get_or_insert_default().enabled = true;
+            config.tabs = Some(crate::config::TabsConfig::default());
+            let mut app = App::new(config);
+            app.show_splash = false;
+            app.focus = Focus::Weather;
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            terminal
+                .draw(|frame| {
+                    draw(frame, &app);
+                })
+                .unwrap();
+            let text = |terminal: &Terminal<TestBackend>| {
+                terminal
+                    .backend()
+                    .buffer()
+                    .content
+                    .iter()
+                    .map(|cell| cell.symbol())
+                    .collect::<String>()
+            };
+            assert!(text(&terminal).contains("1 Weather·Time·Currency"));
+            assert!(!text(&terminal).contains("No recent quakes"));
+
+            app.handle_key(crossterm::event::KeyCode::Char(']'));
+            assert_eq!(
+                app.config.selected_tab(),
+                Some(&app.config.tabs.as_ref().unwrap().pages[1][..])
+            );
+            assert!(!app.panel_shown(Focus::Weather));
+            assert_eq!(app.focus, Focus::Map);
+            terminal
+                .draw(|frame| {
+                    draw(frame, &app);
+                })
+                .unwrap();
+            assert!(text(&terminal).contains("No recent quakes"));
+            let saved = Config::load().expect("config should reload");
+            assert_eq!(saved.tabs.map(|tabs| tabs.selected), Some(1));
+
+            app.handle_key(crossterm::event::KeyCode::Char('['));
+            assert!(app.panel_shown(Focus::Weather));
+        });
+    }
+
+    #[test]
+    fn clicks_and_the_wheel_reach_panels_hints_tabs_and_rows() {
+        use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+        crate::config::with_temp_config_dir_for_test(|| {
+            let mut config = Config::default();
+            config.map.get_or_insert_default().enabled = true;
+            config.tabs = Some(crate::config::TabsConfig::default());
+            config.todos = vec![crate::config::TodoItem {
+                text: "pack a jacket".to_string(),
+                done: false,
+            }];
+            let mut app = App::new(config);
+            app.show_splash = false;
+            app.focus = Focus::Weather;
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            let mut redraw = |app: &mut App| {
+                let mut areas = ClickAreas::default();
+                terminal.draw(|frame| areas = draw(frame, app)).unwrap();
+                app.click_areas = areas;
+            };
+            let mouse = |kind, (column, row): (u16, u16)| MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            };
+            let click = |at| mouse(MouseEventKind::Down(MouseButton::Left), at);
+            let middle = |area: Rect| (area.x + area.width / 2, area.y + area.height / 2);
+            redraw(&mut app);
+
+            let time = app
+                .click_areas
+                .panels
+                .iter()
+                .find(|(_, f)| *f == Focus::TimeConvert);
+            app.handle_mouse(click(middle(time.expect("time panel shown").0)));
+            assert_eq!(app.focus, Focus::TimeConvert);
+
+            // [space:city] on the weather title focuses weather and moves to the next city
+            let first_city = app.get_weather_city_code().to_string();
+            let (hint, _) = app
+                .click_areas
+                .hints
+                .iter()
+                .copied()
+                .find(|(_, hint)| *hint == (Focus::Weather, ' '))
+                .expect("weather title has a space hint");
+            app.handle_mouse(click((hint.x, hint.y)));
+            assert_eq!(app.focus, Focus::Weather);
+            assert_ne!(app.get_weather_city_code(), first_city);
+
+            let weather = app.click_areas.panel_at(hint.x, hint.y + 2);
+            assert_eq!(weather, Some(Focus::Weather));
+            app.handle_mouse(mouse(MouseEventKind::ScrollUp, (hint.x, hint.y + 2)));
+            assert_eq!(app.get_weather_city_code(), first_city);
+
+            let (tab, _) = app.click_areas.tabs[1];
+            app.handle_mouse(click((tab.x, tab.y)));
+            assert_eq!(app.config.tabs.as_ref().map(|tabs| tabs.selected), Some(1));
+            redraw(&mut app);
+
+            let (row, _) = app.click_areas.checklist[0];
+            app.handle_mouse(click((row.x, row.y)));
+            assert!(app.config.todos[0].done);
+        });
+    }
+
+    #[test]
+    fn narrow_terminals_stack_the_panels_and_drop_a_cramped_map() {
+        let mut config = Config::default();
+        config.map.get_or_insert_default().enabled = true;
+        let mut app = App::new(config);
+        app.show_splash = false;
+        app.focus = Focus::Map;
+        let mut terminal = Terminal::new(TestBackend::new(60, 50)).unwrap();
+        let mut areas = ClickAreas::default();
+        terminal.draw(|frame| areas = draw(frame, &app)).unwrap();
+        let order: Vec<Focus> = areas.panels.iter().map(|(_, focus)| *focus).collect();
+        assert_eq!(
+            order,
+            [
+                Focus::Map,
+                Focus::Weather,
+                Focus::TimeConvert,
+                Focus::Currency
+            ]
+        );
+        assert!(areas.panels.iter().all(|(area, _)| area.width == 60));
+        assert!(areas.map.expect("map shown").height >= NARROW_MAP_MIN_HEIGHT);
+
+        // up and down follow the column
+        app.click_areas = areas.clone();
+        app.handle_key(crossterm::event::KeyCode::Down);
+        assert_eq!(app.focus, Focus::Weather);
+        app.handle_key(crossterm::event::KeyCode::Down);
+        assert_eq!(app.focus, Focus::TimeConvert);
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 30)).unwrap();
+        terminal.draw(|frame| areas = draw(frame, &app)).unwrap();
+        assert_eq!(areas.map, None);
+        assert_eq!(areas.panels.len(), 3);
+
+        // wide enough, the map goes back beside the others
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| areas = draw(frame, &app)).unwrap();
+        assert!(areas.map.expect("map shown").width < 100);
+    }
+
+    #[test]
+    fn expanded_weather_shows_wind_uv_and_humidity_gauges() {
+        let mut app = App::new(Config::default());
+        app.show_splash = false;
+        app.weather_expanded = true;
+        app.current_weather = Some(crate::weather::CurrentWeather {
+            temp_c: 12,
+            feels_like_c: 9,
+            humidity: 81,
+            wind_kmph: 32,
+            wind_dir: "NW".to_string(),
+            description: "Overcast".to_string(),
+            icon: crate::weather::WeatherIcon::Cloudy,
+            is_day: true,
+            last_updated: std::time::Instant::now(),
+            forecast: Vec::new(),
+            next_hours: Vec::new(),
+            hourly_temps: Vec::new(),
+            uv_index: Some(6.4),
+        });
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal
+            .draw(|frame| {
+                draw(frame, &app);
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content.iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("32 km/h"));
+        assert!(text.contains("UV 6 high"));
+        assert!(text.contains("81% humidity"));
+        assert!(!text.contains("10 km"));
+    }
+
+    #[test]
+    fn big_clock_stacks_block_digits_when_the_time_panel_has_room() {
+        let mut config = Config::default();
+        config.display.big_clock = true;
+        let mut app = App::new(config);
+        app.show_splash = false;
+        app.tick();
+        let screen = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            terminal
+                .draw(|frame| {
+                    draw(frame, app);
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..buffer.area.height)
+                .map(|y| {
+                    (0..buffer.area.width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let anchor = app.current_city_time.as_ref().expect("anchor time");
+        let digits = bigdigits::render(&anchor.time_string(true, true));
+
+        // the compact weather view leaves the time panel its eleven rows
+        app.weather_expanded = false;
+        let text = screen(&app);
+        assert!(digits.iter().all(|row| text.contains(row.as_str())));
+        assert!(text.contains("▸ WLG Wellington"));
+
+        // seven rows are too few, so the text rows come back
+        app.weather_expanded = true;
+        let text = screen(&app);
+        assert!(!text.contains(digits[0].as_str()));
+        assert!(text.contains(&anchor.time_string(true, true)));
+    }
+
+    #[test]
+    fn idling_switches_to_the_ambient_view_until_a_key() {
+        let mut config = Config::default();
+        config.display.ambient_after_mins = 5;
+        let mut app = App::new(config);
+        app.show_splash = false;
+        app.tick();
+        assert!(!app.ambient);
+
+        app.last_input = Instant::now() - Duration::from_secs(5 * 60);
+        app.tick();
+        assert!(app.ambient);
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        let mut areas = ClickAreas::default();
+        terminal.draw(|frame| areas = draw(frame, &app)).unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("Wellington"));
+        assert!(!text.contains("/help"));
+        assert!(areas.panels.is_empty());
+
+        // the waking key is swallowed rather than acted on
+        let focus = app.focus;
+        app.handle_key(crossterm::event::KeyCode::Tab);
+        assert!(!app.ambient);
+        assert_eq!(app.focus, focus);
+    }
+
+    #[test]
+    fn zoom_gives_the_focused_panel_the_whole_content_area() {
+        let mut config = Config::default();
+        config.map.get_or_insert_default().enabled = true;
+        let mut app = App::new(config);
+        app.show_splash = false;
+        app.focus = Focus::Map;
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        let mut map = None;
+        terminal.draw(|frame| map = draw(frame, &app).map).unwrap();
+        assert!(map.expect("map shown").width < 60);
+
+        app.handle_key(crossterm::event::KeyCode::Char('z'));
+        terminal.draw(|frame| map = draw(frame, &app).map).unwrap();
+        assert_eq!(map.map(|area| area.width), Some(120));
+
+        app.focus = Focus::Weather;
+        terminal.draw(|frame| map = draw(frame, &app).map).unwrap();
+        assert_eq!(map, None);
+
+        app.handle_key(crossterm::event::KeyCode::Esc);
+        assert!(!app.zoomed);
+    }
+
+    #[test]
+    fn zoom_grows_the_panel_out_of_its_old_place_over_a_few_frames() {
+        use crate::app::TRANSITION_FRAMES;
+        use crossterm::event::KeyCode;
+
+        let mut config = Config::default();
+        config.map.get_or_insert_default().enabled = true;
+        let mut app = App::new(config);
+        app.show_splash = false;
+        app.focus = Focus::Map;
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        let mut areas = ClickAreas::default();
+        terminal.draw(|frame| areas = draw(frame, &app)).unwrap();
+        let before = areas.map.expect("map shown");
+        app.click_areas = areas.clone();
+
+        // the focused map's top-right corner shows where it is drawn
+        let corner = |terminal: &Terminal<TestBackend>, rect: Rect| {
+            terminal.backend().buffer()[(rect.right() - 1, rect.y)]
+                .symbol()
+                .to_string()
+        };
+        app.handle_key(KeyCode::Char('z'));
+        terminal.draw(|frame| areas = draw(frame, &app)).unwrap();
+        let zoomed = areas.map.expect("map shown");
+        assert_eq!(zoomed.width, 120);
+        assert_eq!(corner(&terminal, before), "╗");
+        assert_ne!(corner(&terminal, zoomed), "╗");
+
+        app.animation_frame += TRANSITION_FRAMES / 2;
+        terminal.draw(|frame| areas = draw(frame, &app)).unwrap();
+        let (_, progress) = app.transition_progress().expect("still moving");
+        assert!(progress > 0.5 && progress < 1.0);
+        let between = lerp_rect(before, zoomed, progress);
+        assert!(between.width > before.width && between.width < zoomed.width);
+        assert_eq!(corner(&terminal, between), "╗");
+
+        for _ in 0..TRANSITION_FRAMES {
+            app.tick();
+        }
+        assert_eq!(app.transition, None);
+        terminal.draw(|frame| areas = draw(frame, &app)).unwrap();
+        assert_eq!(corner(&terminal, zoomed), "╗");
+
+        // with animations off the layout snaps straight over
+        app.config.display.show_animations = false;
+        app.click_areas = areas;
+        app.handle_key(KeyCode::Char('z'));
+        assert!(!app.zoomed);
+        assert_eq!(app.transition, None);
+    }
+
+    #[test]
+    fn layout_section_rebalances_the_map_and_bottom_strip() {
+        let area = Rect::new(0, 0, 200, 40);
+        let default = expanded_weather_panel_area(area, true, &LayoutConfig::default());
+        assert_eq!((default.x, default.height), (66, 33));
+
+        let layout = LayoutConfig {
+            map_percent: 50,
+            bottom_height: 12,
+            ..LayoutConfig::default()
+        };
+        let rebalanced = expanded_weather_panel_area(area, true, &layout);
+        assert_eq!((rebalanced.x, rebalanced.width), (100, 100));
+        assert_eq!(rebalanced.height, 28);
+
+        // on a short terminal the weather minimum decides who gives way
+        let short = Rect::new(0, 0, 200, 18);
+        let layout = LayoutConfig {
+            weather_min_height: 16,
+            ..LayoutConfig::default()
+        };
+        let default_min = expanded_weather_panel_area(short, false, &LayoutConfig::default());
+        assert_eq!(default_min.height, 14);
+        assert_eq!(
+            expanded_weather_panel_area(short, false, &layout).height,
+            16
+        );
+    }
+
+    #[test]
+    fn weather_grid_drops_to_day_night_columns_on_narrow_panes() {
+        let full = weather_grid_layout(WEATHER_GRID_WIDTH).expect("full grid");
+        assert_eq!(full.periods.len(), 4);
+        assert_eq!(full.cell_width, WEATHER_GRID_CELL_WIDTH);
+        assert_eq!(full.width(), WEATHER_GRID_WIDTH as usize);
+
+        let narrow = weather_grid_layout(WEATHER_GRID_WIDTH - 1).expect("narrow grid");
+        assert!(narrow.is_narrow());
+        assert!(narrow.width() <= (WEATHER_GRID_WIDTH - 1) as usize);
+
+        assert!(weather_grid_layout(WEATHER_GRID_NARROW_WIDTH - 1).is_none());
+    }
+
+    fn city_time(code: &str, timezone: &str) -> CityTime {
+        let city = crate::config::City {
+            name: code.to_string(),
+            code: code.to_string(),
+            country: String::new(),
+            timezone: timezone.to_string(),
+            currency: String::new(),
+        };
+        CityTime::from_city(&city).expect("timezone should parse")
+    }
+
+    #[test]
+    fn time_delta_is_minute_accurate_for_fractional_zones() {
+        // each pair shares its DST rules (or has none), so the gap holds all year
+        let auckland = city_time("AKL", "Pacific/Auckland");
+        let chatham = city_time("CHT", "Pacific/Chatham");
+        assert_eq!(format_time_delta(&auckland, &chatham), "+45m ahead");
+        assert_eq!(format_time_delta(&chatham, &auckland), "-45m behind");
+
+        let kolkata = city_time("DEL", "Asia/Kolkata");
+        let kathmandu = city_time("KTM", "Asia/Kathmandu");
+        assert_eq!(format_time_delta(&kolkata, &kathmandu), "+15m ahead");
+
+        let utc = city_time("UTC", "UTC");
+        assert_eq!(format_time_delta(&utc, &kathmandu), "+5h45m ahead");
+        assert_eq!(format_time_delta(&kolkata, &utc), "-5h30m behind");
+        let bangkok = city_time("BKK", "UTC+07:00");
+        assert_eq!(format_time_delta(&utc, &bangkok), "+7h ahead");
+        let fixed = city_time("XXX", "UTC+07:30");
+        assert_eq!(format_time_delta(&utc, &fixed), "+7h30m ahead");
+        assert_eq!(format_time_delta(&fixed, &fixed), "same time");
+
+        let sydney = city_time("SYD", "Australia/Sydney");
+        let adelaide = city_time("ADL", "Australia/Adelaide");
+        assert_eq!(format_time_delta(&sydney, &adelaide), "-30m behind");
+    }
+
+    #[test]
+    fn world_clock_lists_current_city_then_targets() {
+        let mut app = App::new(Config::default());
+        app.tick();
+
+        let entries = world_clock_entries(&app);
+        assert_eq!(entries[0].0, "WLG");
+        assert_eq!(entries.len(), 1 + app.world_city_times.len());
+        assert!(entries.iter().all(|(_, time)| time.len() == 5));
+    }
+
+    #[test]
+    fn hour_bar_line_marks_the_current_hour() {
+        let bar = day_bar(0, &crate::config::PlannerConfig::default());
+        let line = hour_bar_line("WLG", &bar, 10);
+
+        assert_eq!(line.spans.len(), 25);
+        assert_eq!(line.spans[11].content, "█");
+        assert!(
+            line.spans[11]
+                .style
+                .add_modifier
+                .contains(Modifier::REVERSED)
+        );
+        assert!(
+            !line.spans[12]
+                .style
+                .add_modifier
+                .contains(Modifier::REVERSED)
+        );
+    }
+
+    #[test]
+    fn weather_grid_widens_cells_on_wide_panes() {
+        let wide = weather_grid_layout(101).expect("wide grid");
+        assert_eq!(wide.periods.len(), 4);
+        assert_eq!(wide.cell_width, 24);
+        assert_eq!(wide.width(), 101);
+
+        let capped = weather_grid_layout(400).expect("capped grid");
+        assert_eq!(capped.cell_width, WEATHER_GRID_MAX_CELL_WIDTH);
+    }
+
+    #[test]
+    fn time_focus_world_map_uses_country_markers() {
+        let mut app = App::new(Config::default());
+        app.time_converter.from_city_code = "LAX".to_string();
+        app.time_converter.to_city_code = "BOS".to_string();
+
+        let (primary, secondary, label) = world_map_markers(&app, Focus::TimeConvert);
+
+        assert_eq!(label, "Time");
+        assert_eq!(
+            primary.as_ref().map(|marker| marker.label.as_str()),
+            Some("USA")
+        );
+        assert_eq!(
+            secondary.as_ref().map(|marker| marker.label.as_str()),
+            Some("USA")
+        );
+    }
+
+    #[test]
+    fn time_focus_world_map_distinguishes_countries() {
+        let mut app = App::new(Config::default());
+        app.time_converter.from_city_code = "WLG".to_string();
+        app.time_converter.to_city_code = "TYO".to_string();
+
+        let (primary, secondary, _) = world_map_markers(&app, Focus::TimeConvert);
+
+        assert_eq!(
+            primary.as_ref().map(|marker| marker.label.as_str()),
+            Some("NZL")
+        );
+        assert_eq!(
+            secondary.as_ref().map(|marker| marker.label.as_str()),
+            Some("JPN")
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! config.toml watcher for hot reload
+//! watches the config file's directory, since editors often save by renaming a new file
+//! over the old one, and reports an edit once the file has settled
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::config::Config;
+
+/// quiet time after the last write before the file is read, so a half-written save
+/// isn't picked up
+const SETTLE: Duration = Duration::from_millis(300);
+
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    path: PathBuf,
+    touched_at: Option<Instant>,
+}
+
+impl ConfigWatcher {
+    /// watch `path`, or None when the platform watcher can't be started
+    pub fn start(path: PathBuf) -> Option<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).ok()?;
+        watcher
+            .watch(path.parent()?, RecursiveMode::NonRecursive)
+            .ok()?;
+        Some(Self {
+            _watcher: watcher,
+            events,
+            path,
+            touched_at: None,
+        })
+    }
+
+    /// whether the file was changed by something other than this process since the last
+    /// call; our own saves and reloads are recognised by their content and skipped
+    pub fn changed(&mut self) -> bool {
+        let name = self.path.file_name();
+        for event in self.events.try_iter().flatten() {
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            if event.paths.iter().any(|path| path.file_name() == name) {
+                self.touched_at = Some(Instant::now());
+            }
+        }
+
+        match self.touched_at {
+            Some(touched_at) if touched_at.elapsed() >= SETTLE => self.touched_at = None,
+            _ => return false,
+        }
+        fs::read_to_string(&self.path).is_ok_and(|content| !Config::is_synced(&content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_an_outside_edit_once_it_settles() {
+        let dir = std::env::temp_dir().join(format!("nzi-watch-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "tracked_cities = []\n").unwrap();
+
+        let mut watcher = ConfigWatcher::start(path.clone()).expect("watcher should start");
+        assert!(!watcher.changed());
+
+        fs::write(dir.join("other.toml"), "x = 1\n").unwrap();
+        std::thread::sleep(SETTLE * 2);
+        assert!(!watcher.changed());
+
+        fs::write(&path, "tracked_cities = []\n# edited\n").unwrap();
+        assert!(!watcher.changed());
+        let deadline = Instant::now() + SETTLE * 10;
+        while !watcher.changed() {
+            assert!(Instant::now() < deadline, "the edit should be reported");
+            std::thread::sleep(SETTLE / 4);
+        }
+        assert!(!watcher.changed());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,10 +2,18 @@
 //! faster than wttr.in with better caching
 
 use anyhow::{Context, Result};
+use chrono::Utc;
 use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
+use crate::cache::SnapshotStore;
+use crate::reference::world_city_by_name;
+use crate::schema;
+use crate::stats::ServiceMetrics;
+
 /// weather condition icons
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WeatherIcon {
@@ -22,22 +30,9 @@ pub enum WeatherIcon {
 }
 
 impl WeatherIcon {
-    /// get an icon character for display
+    /// get an icon character for display, in the configured icon set
     pub fn icon(&self, is_day: bool) -> &'static str {
-        match (self, is_day) {
-            (Self::Sunny, true) => "☀",
-            (Self::Sunny, false) => "☾",
-            (Self::PartlyCloudy, true) => "⛅",
-            (Self::PartlyCloudy, false) => "☁️",
-            (Self::Cloudy, _) => "☁️",
-            (Self::Fog, _) => "🌫",
-            (Self::Drizzle, _) => "🌦",
-            (Self::Rain, _) => "🌧",
-            (Self::HeavyRain, _) => "🌧",
-            (Self::Snow, _) => "❄",
-            (Self::Thunderstorm, _) => "⛈",
-            (Self::Unknown, _) => "?",
-        }
+        crate::icons::icon_set().weather(*self, is_day)
     }
 
     /// parse from wmo weather code (open-meteo uses wmo codes)
@@ -55,10 +50,40 @@ impl WeatherIcon {
             _ => Self::Unknown,
         }
     }
+
+    /// rain, drizzle, snow, or storms
+    pub fn is_wet(&self) -> bool {
+        matches!(
+            self,
+            Self::Drizzle | Self::Rain | Self::HeavyRain | Self::Snow | Self::Thunderstorm
+        )
+    }
+}
+
+/// hours ahead checked for rain about to start
+const RAIN_LOOKAHEAD_HOURS: usize = 2;
+
+/// hours of temperatures kept for the weather panel's sparkline
+const TEMPERATURE_TREND_HOURS: usize = 24;
+
+/// weather conditions that can trigger an opt-in cue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeatherAlert {
+    RainSoon,
+    SevereWind,
+}
+
+impl WeatherAlert {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::RainSoon => "Rain expected soon",
+            Self::SevereWind => "Severe wind",
+        }
+    }
 }
 
 /// time of day period
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimeOfDay {
     Morning, // 6-12
     Noon,    // 12-18
@@ -91,9 +116,10 @@ pub struct PeriodForecast {
 #[derive(Debug, Clone)]
 pub struct DayForecast {
     pub date: String,
-    pub temp_max: i32,
-    pub temp_min: i32,
-    pub wind_max: i32,
+    /// None when the provider left the reading out, rather than a made-up zero
+    pub temp_max: Option<i32>,
+    pub temp_min: Option<i32>,
+    pub wind_max: Option<i32>,
     pub icon: WeatherIcon,
     pub periods: Vec<PeriodForecast>,
 }
@@ -111,6 +137,12 @@ pub struct CurrentWeather {
     pub is_day: bool,
     pub last_updated: Instant,
     pub forecast: Vec<DayForecast>,
+    /// conditions for the next few hours, starting after the current one
+    pub next_hours: Vec<WeatherIcon>,
+    /// hourly temperatures for the next day, starting with the current hour
+    pub hourly_temps: Vec<f64>,
+    /// UV index now, when the response carries it
+    pub uv_index: Option<f64>,
 }
 
 impl CurrentWeather {
@@ -119,6 +151,11 @@ impl CurrentWeather {
         self.last_updated.elapsed() > Duration::from_secs(600)
     }
 
+    /// whether the data has outlived `max_age`, the configured refresh interval
+    pub fn is_older_than(&self, max_age: Duration) -> bool {
+        self.last_updated.elapsed() >= max_age
+    }
+
     /// format temperature
     pub fn temp_string(&self) -> String {
         format!("{}°C", self.temp_c)
@@ -128,28 +165,80 @@ impl CurrentWeather {
     pub fn feels_like_string(&self) -> String {
         format!("{}°C", self.feels_like_c)
     }
+
+    /// the WHO band for the UV index: low, moderate, high, very high or extreme
+    pub fn uv_band(&self) -> Option<&'static str> {
+        let uv = self.uv_index?.round();
+        Some(if uv < 3.0 {
+            "low"
+        } else if uv < 6.0 {
+            "moderate"
+        } else if uv < 8.0 {
+            "high"
+        } else if uv < 11.0 {
+            "very high"
+        } else {
+            "extreme"
+        })
+    }
+
+    /// alert conditions currently met: rain about to start (dry now, wet within the
+    /// lookahead) and wind at or above the severe threshold
+    pub fn active_alerts(&self, severe_wind_kmph: i32) -> Vec<WeatherAlert> {
+        let mut alerts = Vec::new();
+        if !self.icon.is_wet() && self.next_hours.iter().any(WeatherIcon::is_wet) {
+            alerts.push(WeatherAlert::RainSoon);
+        }
+        if self.wind_kmph >= severe_wind_kmph {
+            alerts.push(WeatherAlert::SevereWind);
+        }
+        alerts
+    }
 }
 
-/// open-meteo api response
+/// top-level fields open-meteo sends alongside the requested sections
+const KNOWN_OPEN_METEO_FIELDS: &[&str] = &[
+    "latitude",
+    "longitude",
+    "generationtime_ms",
+    "utc_offset_seconds",
+    "timezone",
+    "timezone_abbreviation",
+    "elevation",
+    "current_units",
+    "daily_units",
+    "hourly_units",
+];
+
+/// open-meteo v1 forecast response; every section is optional except the current temperature
 #[derive(Debug, Deserialize)]
 struct OpenMeteoResponse {
+    #[serde(default)]
     current: OpenMeteoCurrent,
+    #[serde(default)]
     daily: Option<OpenMeteoDaily>,
+    #[serde(default)]
     hourly: Option<OpenMeteoHourly>,
+    #[serde(flatten)]
+    extra: BTreeMap<String, Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 struct OpenMeteoCurrent {
-    temperature_2m: f64,
-    apparent_temperature: f64,
-    relative_humidity_2m: i32,
+    time: String,
+    temperature_2m: Option<f64>,
+    apparent_temperature: Option<f64>,
+    relative_humidity_2m: f64,
     wind_speed_10m: f64,
     wind_direction_10m: f64,
     weather_code: i32,
-    is_day: i32,
+    is_day: Option<i32>,
+    uv_index: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 struct OpenMeteoDaily {
     time: Vec<String>,
     temperature_2m_max: Vec<f64>,
@@ -158,9 +247,9 @@ struct OpenMeteoDaily {
     weather_code: Vec<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 struct OpenMeteoHourly {
-    #[allow(dead_code)]
     time: Vec<String>,
     temperature_2m: Vec<f64>,
     wind_speed_10m: Vec<f64>,
@@ -168,6 +257,110 @@ struct OpenMeteoHourly {
     weather_code: Vec<i32>,
 }
 
+impl OpenMeteoResponse {
+    fn parse(body: &str) -> Result<Self> {
+        serde_json::from_str(body).context("failed to parse weather response")
+    }
+
+    fn unknown_fields(&self) -> Vec<String> {
+        schema::unknown_fields(&self.extra, KNOWN_OPEN_METEO_FIELDS)
+    }
+
+    /// adapt the response into the panel model; missing forecast sections degrade to empty
+    fn to_weather(&self) -> Result<CurrentWeather> {
+        let current = &self.current;
+        let temperature = current
+            .temperature_2m
+            .context("weather response missing current temperature")?;
+
+        // parse hourly data into periods for each day
+        let hourly_periods = if let Some(hourly) = &self.hourly {
+            parse_hourly_to_periods(hourly)
+        } else {
+            Vec::new()
+        };
+
+        // parse 3-day forecast with period breakdowns
+        let forecast = if let Some(daily) = &self.daily {
+            daily
+                .time
+                .iter()
+                .enumerate()
+                .take(3)
+                .map(|(i, date)| {
+                    // get periods for this day
+                    let day_periods = if i < hourly_periods.len() {
+                        hourly_periods[i].clone()
+                    } else {
+                        Vec::new()
+                    };
+
+                    DayForecast {
+                        date: date.clone(),
+                        temp_max: daily.temperature_2m_max.get(i).map(|t| t.round() as i32),
+                        temp_min: daily.temperature_2m_min.get(i).map(|t| t.round() as i32),
+                        wind_max: daily.wind_speed_10m_max.get(i).map(|w| w.round() as i32),
+                        icon: daily
+                            .weather_code
+                            .get(i)
+                            .map_or(WeatherIcon::Unknown, |code| {
+                                WeatherIcon::from_wmo_code(*code)
+                            }),
+                        periods: day_periods,
+                    }
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // the hourly entry containing the current observation
+        let now = self.hourly.as_ref().and_then(|hourly| {
+            let hour = format!("{}:00", current.time.get(..13)?);
+            let index = hourly.time.iter().position(|time| *time == hour)?;
+            Some((hourly, index))
+        });
+        let next_hours = now
+            .map(|(hourly, index)| {
+                hourly
+                    .weather_code
+                    .iter()
+                    .skip(index + 1)
+                    .take(RAIN_LOOKAHEAD_HOURS)
+                    .map(|&code| WeatherIcon::from_wmo_code(code))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let hourly_temps = now
+            .map(|(hourly, index)| {
+                hourly
+                    .temperature_2m
+                    .iter()
+                    .skip(index)
+                    .take(TEMPERATURE_TREND_HOURS)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(CurrentWeather {
+            temp_c: temperature.round() as i32,
+            feels_like_c: current.apparent_temperature.unwrap_or(temperature).round() as i32,
+            humidity: current.relative_humidity_2m.round() as i32,
+            wind_kmph: current.wind_speed_10m.round() as i32,
+            wind_dir: wind_direction(current.wind_direction_10m).to_string(),
+            description: weather_description(current.weather_code).to_string(),
+            icon: WeatherIcon::from_wmo_code(current.weather_code),
+            is_day: current.is_day.unwrap_or(1) == 1,
+            last_updated: Instant::now(),
+            forecast,
+            next_hours,
+            hourly_temps,
+            uv_index: current.uv_index,
+        })
+    }
+}
+
 /// city coordinates for weather lookup
 pub struct CityCoords {
     pub name: &'static str,
@@ -398,17 +591,14 @@ pub const CITY_CODE_COORDS: &[CityCodeCoords] = &[
     },
 ];
 
-/// get coordinates for a city name
+/// get coordinates for a city name, falling back to the bundled world city database
 fn get_city_coords(city_name: &str) -> Option<(f64, f64)> {
     let name_lower = city_name.to_lowercase();
     CITY_COORDS
         .iter()
         .find(|c| name_lower.contains(c.name))
         .map(|c| (c.lat, c.lon))
-}
-
-pub fn city_coords_by_name(city_name: &str) -> Option<(f64, f64)> {
-    get_city_coords(city_name)
+        .or_else(|| world_city_by_name(city_name).map(|city| (city.lat, city.lon)))
 }
 
 fn get_city_coords_by_code(code: &str) -> Option<(f64, f64)> {
@@ -419,8 +609,10 @@ fn get_city_coords_by_code(code: &str) -> Option<(f64, f64)> {
         .map(|c| (c.lat, c.lon))
 }
 
-pub fn city_coords_by_code(code: &str) -> Option<(f64, f64)> {
-    get_city_coords_by_code(code)
+/// coordinates for a configured city, matching by name before code
+/// since short codes can be ambiguous (AUS is Austin here but Australia's country code)
+pub fn city_coords(name: &str, code: &str) -> Option<(f64, f64)> {
+    get_city_coords(name).or_else(|| get_city_coords_by_code(code))
 }
 
 /// wind direction from degrees
@@ -478,37 +670,54 @@ fn weather_description(code: i32) -> &'static str {
 pub struct WeatherService {
     client: reqwest::Client,
     cache: std::collections::HashMap<String, CurrentWeather>,
+    last_fetch_ok: Option<bool>, // None when the last lookup was served from cache
+    metrics: ServiceMetrics,
+    unknown_fields: Vec<String>,
+    snapshots: Option<SnapshotStore>, // raw responses kept in the cache directory
 }
 
 impl WeatherService {
-    pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .user_agent(format!("nzi-cli/{}", env!("CARGO_PKG_VERSION")))
-            .timeout(Duration::from_secs(10))
-            .build()
-            .unwrap_or_default();
-
+    pub fn new(client: reqwest::Client) -> Self {
         Self {
             client,
             cache: std::collections::HashMap::new(),
+            last_fetch_ok: None,
+            metrics: ServiceMetrics::default(),
+            unknown_fields: Vec::new(),
+            snapshots: None,
         }
     }
 
-    /// get weather for a location (city name)
-    pub async fn get_weather(&mut self, location: &str) -> Result<CurrentWeather> {
+    /// keep each fetched response in `store`
+    pub fn keep_snapshots_in(&mut self, store: SnapshotStore) {
+        self.snapshots = Some(store);
+    }
+
+    pub fn snapshots_mut(&mut self) -> Option<&mut SnapshotStore> {
+        self.snapshots.as_mut()
+    }
+
+    /// get weather for a location (city name); a cached reading younger than `max_age` is
+    /// served without a request
+    pub async fn get_weather(
+        &mut self,
+        location: &str,
+        max_age: Duration,
+    ) -> Result<CurrentWeather> {
         let cache_key = location.to_lowercase();
+        self.last_fetch_ok = None;
 
         // check cache
-        if let Some(cached) = self.cache.get(&cache_key)
-            && !cached.is_stale()
-        {
-            return Ok(cached.clone());
+        if let Some(cached) = self.fresh_cached(&cache_key, max_age) {
+            self.metrics.record_cache_hit();
+            return Ok(cached);
         }
 
         let mut last_err = None;
         let mut backoff = Duration::from_millis(500);
         let mut weather = None;
         for attempt in 0..3 {
+            self.metrics.record_api_call();
             match self.fetch_weather(location).await {
                 Ok(fresh) => {
                     weather = Some(fresh);
@@ -524,6 +733,7 @@ impl WeatherService {
             }
         }
 
+        self.last_fetch_ok = Some(weather.is_some());
         let weather = match weather {
             Some(fresh) => fresh,
             None => return Err(last_err.unwrap()),
@@ -533,94 +743,66 @@ impl WeatherService {
         Ok(weather)
     }
 
+    /// whether the last get_weather call reached the API (None if served from cache)
+    pub fn last_fetch_ok(&self) -> Option<bool> {
+        self.last_fetch_ok
+    }
+
+    pub fn metrics(&self) -> ServiceMetrics {
+        self.metrics
+    }
+
+    /// fields the last API response carried that the adapter does not recognise
+    pub fn unknown_fields(&self) -> &[String] {
+        &self.unknown_fields
+    }
+
+    fn fresh_cached(&self, cache_key: &str, max_age: Duration) -> Option<CurrentWeather> {
+        self.cache
+            .get(cache_key)
+            .filter(|cached| !cached.is_older_than(max_age))
+            .cloned()
+    }
+
+    /// whether a response for `location` is kept on disk, from this session or an earlier one
+    pub fn has_kept_weather(&self, location: &str) -> bool {
+        self.snapshots
+            .as_ref()
+            .is_some_and(|store| !store.saved(location).is_empty())
+    }
+
     pub fn cached_weather(&self, location: &str) -> Option<CurrentWeather> {
         self.cache.get(&location.to_lowercase()).cloned()
     }
 
-    async fn fetch_weather(&self, location: &str) -> Result<CurrentWeather> {
+    async fn fetch_weather(&mut self, location: &str) -> Result<CurrentWeather> {
         let (lat, lon) =
             get_city_coords(location).context("unknown city - add coordinates to CITY_COORDS")?;
 
         // open-meteo api - fast and free, with 3-day forecast + hourly for period breakdown
         let url = format!(
-            "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m,apparent_temperature,relative_humidity_2m,wind_speed_10m,wind_direction_10m,weather_code,is_day&daily=temperature_2m_max,temperature_2m_min,wind_speed_10m_max,weather_code&hourly=temperature_2m,wind_speed_10m,wind_direction_10m,weather_code&timezone=auto&forecast_days=3",
+            "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m,apparent_temperature,relative_humidity_2m,wind_speed_10m,wind_direction_10m,weather_code,is_day,uv_index&daily=temperature_2m_max,temperature_2m_min,wind_speed_10m_max,weather_code&hourly=temperature_2m,wind_speed_10m,wind_direction_10m,weather_code&timezone=auto&forecast_days=3",
             lat, lon
         );
 
-        let response: OpenMeteoResponse = self
+        let body = self
             .client
             .get(&url)
             .send()
             .await
+            .and_then(|response| response.error_for_status())
             .context("failed to fetch weather")?
-            .json()
+            .text()
             .await
-            .context("failed to parse weather response")?;
-
-        let current = &response.current;
-
-        // parse hourly data into periods for each day
-        let hourly_periods = if let Some(hourly) = &response.hourly {
-            parse_hourly_to_periods(hourly)
-        } else {
-            Vec::new()
-        };
-
-        // parse 3-day forecast with period breakdowns
-        let forecast = if let Some(daily) = &response.daily {
-            daily
-                .time
-                .iter()
-                .enumerate()
-                .take(3)
-                .map(|(i, date)| {
-                    // get periods for this day
-                    let day_periods = if i < hourly_periods.len() {
-                        hourly_periods[i].clone()
-                    } else {
-                        Vec::new()
-                    };
-
-                    DayForecast {
-                        date: date.clone(),
-                        temp_max: daily
-                            .temperature_2m_max
-                            .get(i)
-                            .map(|t| t.round() as i32)
-                            .unwrap_or(0),
-                        temp_min: daily
-                            .temperature_2m_min
-                            .get(i)
-                            .map(|t| t.round() as i32)
-                            .unwrap_or(0),
-                        wind_max: daily
-                            .wind_speed_10m_max
-                            .get(i)
-                            .map(|w| w.round() as i32)
-                            .unwrap_or(0),
-                        icon: WeatherIcon::from_wmo_code(
-                            daily.weather_code.get(i).copied().unwrap_or(0),
-                        ),
-                        periods: day_periods,
-                    }
-                })
-                .collect()
-        } else {
-            Vec::new()
-        };
+            .context("failed to read weather response")?;
 
-        Ok(CurrentWeather {
-            temp_c: current.temperature_2m.round() as i32,
-            feels_like_c: current.apparent_temperature.round() as i32,
-            humidity: current.relative_humidity_2m,
-            wind_kmph: current.wind_speed_10m.round() as i32,
-            wind_dir: wind_direction(current.wind_direction_10m).to_string(),
-            description: weather_description(current.weather_code).to_string(),
-            icon: WeatherIcon::from_wmo_code(current.weather_code),
-            is_day: current.is_day == 1,
-            last_updated: Instant::now(),
-            forecast,
-        })
+        let response = OpenMeteoResponse::parse(&body)?;
+        self.unknown_fields = response.unknown_fields();
+        if let Some(store) = &self.snapshots {
+            // losing a snapshot only costs the cache's history
+            let _ = store.save(location, &body, Utc::now());
+        }
+        response.to_weather()
     }
 }
 
@@ -689,6 +871,170 @@ fn parse_hourly_to_periods(hourly: &OpenMeteoHourly) -> Vec<Vec<PeriodForecast>>
 
 impl Default for WeatherService {
     fn default() -> Self {
-        Self::new()
+        Self::new(crate::http::client())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adapts_full_forecast_response() {
+        let hourly_temps = vec![12.0; 72];
+        let body = serde_json::json!({
+            "latitude": -41.3,
+            "longitude": 174.8,
+            "timezone": "Pacific/Auckland",
+            "current_units": {"temperature_2m": "°C"},
+            "current": {
+                "time": "2025-06-02T09:00",
+                "temperature_2m": 11.6,
+                "apparent_temperature": 8.2,
+                "relative_humidity_2m": 81,
+                "wind_speed_10m": 24.1,
+                "wind_direction_10m": 350.0,
+                "weather_code": 3,
+                "is_day": 1,
+                "uv_index": 5.2
+            },
+            "daily": {
+                "time": ["2025-06-02", "2025-06-03", "2025-06-04"],
+                "temperature_2m_max": [13.0, 14.0, 12.0],
+                "temperature_2m_min": [7.0, 8.0, 6.0],
+                "wind_speed_10m_max": [30.0, 20.0, 40.0],
+                "weather_code": [3, 61, 2]
+            },
+            "hourly": {
+                "time": vec!["t"; 72],
+                "temperature_2m": hourly_temps,
+                "wind_speed_10m": vec![10.0; 72],
+                "wind_direction_10m": vec![180.0; 72],
+                "weather_code": vec![1; 72]
+            }
+        })
+        .to_string();
+
+        let response = OpenMeteoResponse::parse(&body).unwrap();
+        let weather = response.to_weather().unwrap();
+
+        assert_eq!(weather.temp_c, 12);
+        assert_eq!(weather.feels_like_c, 8);
+        assert_eq!(weather.humidity, 81);
+        assert_eq!(weather.uv_band(), Some("moderate"));
+        assert_eq!(weather.forecast.len(), 3);
+        assert_eq!(weather.forecast[1].periods.len(), 4);
+        assert!(response.unknown_fields().is_empty());
+    }
+
+    #[test]
+    fn missing_forecast_sections_degrade_to_current_only() {
+        let body = r#"{"current": {"temperature_2m": 15.4}}"#;
+        let weather = OpenMeteoResponse::parse(body)
+            .unwrap()
+            .to_weather()
+            .unwrap();
+
+        assert_eq!(weather.temp_c, 15);
+        assert_eq!(weather.feels_like_c, 15);
+        assert!(weather.is_day);
+        assert!(weather.forecast.is_empty());
+    }
+
+    #[test]
+    fn new_upstream_fields_are_reported_not_fatal() {
+        let body = r#"{
+            "current": {"temperature_2m": 9.0, "relative_humidity_2m": 70.4, "uv_index": 2},
+            "daily": {"time": ["2025-06-02"], "sunshine_duration": [3600]},
+            "air_quality": {"pm2_5": 4}
+        }"#;
+        let response = OpenMeteoResponse::parse(body).unwrap();
+        let weather = response.to_weather().unwrap();
+
+        assert_eq!(weather.humidity, 70);
+        assert_eq!(weather.forecast.len(), 1);
+        // daily readings the response left out stay unknown rather than reading as zero
+        assert_eq!(weather.forecast[0].temp_max, None);
+        assert_eq!(weather.forecast[0].wind_max, None);
+        assert_eq!(weather.forecast[0].icon, WeatherIcon::Unknown);
+        assert_eq!(response.unknown_fields(), vec!["air_quality".to_string()]);
+    }
+
+    #[test]
+    fn missing_current_temperature_is_an_error() {
+        let response = OpenMeteoResponse::parse(r#"{"current": {"weather_code": 2}}"#).unwrap();
+        assert!(response.to_weather().is_err());
+    }
+
+    #[test]
+    fn flags_rain_about_to_start_and_severe_wind() {
+        let body = r#"{
+            "current": {"time": "2025-06-02T09:15", "temperature_2m": 11.0,
+                        "wind_speed_10m": 72.0, "weather_code": 3},
+            "hourly": {"time": ["2025-06-02T09:00", "2025-06-02T10:00", "2025-06-02T11:00"],
+                       "temperature_2m": [10.0, 11.0, 12.5], "weather_code": [3, 3, 63]}
+        }"#;
+        let weather = OpenMeteoResponse::parse(body)
+            .unwrap()
+            .to_weather()
+            .unwrap();
+
+        assert_eq!(weather.next_hours.len(), 2);
+        assert_eq!(weather.hourly_temps, vec![10.0, 11.0, 12.5]);
+        assert_eq!(
+            weather.active_alerts(60),
+            vec![WeatherAlert::RainSoon, WeatherAlert::SevereWind]
+        );
+        assert!(weather.active_alerts(80).len() == 1);
+
+        let raining = CurrentWeather {
+            icon: WeatherIcon::Rain,
+            ..weather
+        };
+        assert!(raining.active_alerts(80).is_empty());
+        assert_eq!(raining.uv_band(), None);
+        let sunburn = CurrentWeather {
+            uv_index: Some(7.6),
+            ..raining
+        };
+        assert_eq!(sunburn.uv_band(), Some("very high"));
+    }
+
+    #[test]
+    fn a_one_minute_interval_fetches_again_after_a_minute() {
+        let weather = OpenMeteoResponse::parse(r#"{"current": {"temperature_2m": 15.4}}"#)
+            .unwrap()
+            .to_weather()
+            .unwrap();
+        let every_minute = Duration::from_secs(60);
+        let mut service = WeatherService::new(reqwest::Client::new());
+        service
+            .cache
+            .insert("wellington".to_string(), weather.clone());
+        assert!(service.fresh_cached("wellington", every_minute).is_some());
+
+        let last_updated = Instant::now() - Duration::from_secs(61);
+        service.cache.insert(
+            "wellington".to_string(),
+            CurrentWeather {
+                last_updated,
+                ..weather
+            },
+        );
+        // well inside the old ten-minute window, but due again at one minute
+        assert!(service.fresh_cached("wellington", every_minute).is_none());
+        assert!(
+            service
+                .fresh_cached("wellington", Duration::from_secs(300))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn world_database_supplies_coordinates_for_added_cities() {
+        let (lat, lon) = city_coords("Reykjavik", "REK").expect("reykjavik should resolve");
+        assert!((lat - 64.15).abs() < 0.1 && (lon + 21.95).abs() < 0.1);
+
+        assert!(city_coords("Nowhere", "XXQ").is_none());
     }
 }